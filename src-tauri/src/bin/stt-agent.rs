@@ -0,0 +1,451 @@
+// src-tauri/src/bin/stt-agent.rs
+//
+// 命令列模式：不開啟視窗，直接使用 service 層處理專案，
+// 供排程 (cron / 工作排程器) 夜間批次處理使用。
+//
+//   stt-agent convert --project <dir> a.mp4 b.mkv
+//   stt-agent split   --project <dir> --input a.mp3 --segment "開場=00:00:00-00:05:00"
+//   stt-agent silence --project <dir> --input 開場.mp3 --range 00:01:00-00:01:05
+//   stt-agent report  --project <dir> --api-key <key>
+//
+// 設定中的外掛 (plugins) 與程式內相同，在轉檔後、報告上傳前與報告產生後執行。
+
+use clap::{Args, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use stt_agent_rust_lib::services::file_manager::{renamed_note, ProjectPaths};
+use stt_agent_rust_lib::services::i18n::{self, Locale};
+use stt_agent_rust_lib::services::jobs::CancelToken;
+use stt_agent_rust_lib::services::manifest::ProjectManifest;
+use stt_agent_rust_lib::services::report::{self, ReportAgent};
+use stt_agent_rust_lib::services::settings::PluginHook;
+use stt_agent_rust_lib::services::sidecar::{self, Ffmpeg};
+use stt_agent_rust_lib::services::workflows::parse_time;
+use stt_agent_rust_lib::services::{
+    backup, dependencies, dictaphone, encryption, ingest, plugins, settings, storage,
+};
+use stt_agent_rust_lib::services::{Converter, Silence, Splitter};
+
+#[derive(Parser)]
+#[command(name = "stt-agent", version, about = "STT Agent 命令列批次處理")]
+struct Cli {
+    /// FFmpeg 執行檔路徑 (預設使用安裝包附帶或已下載的版本，最後使用 PATH 中的 ffmpeg)
+    #[arg(long, global = true, env = "STT_AGENT_FFMPEG")]
+    ffmpeg: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 轉換影音檔為 MP3 (輸出到 01_converted)
+    Convert {
+        #[command(flatten)]
+        project: ProjectArg,
+        /// 要轉換的檔案
+        #[arg(required = true)]
+        files: Vec<String>,
+    },
+    /// 依段落切割音檔 (輸出到 02_split)
+    Split {
+        #[command(flatten)]
+        project: ProjectArg,
+        #[arg(long)]
+        input: String,
+        /// 段落，格式為 "名稱=開始-結束"，例如 "開場=00:00:00-00:05:00"
+        #[arg(long = "segment", required = true)]
+        segments: Vec<String>,
+    },
+    /// 對指定時段消音 (輸出到 03_silence)
+    Silence {
+        #[command(flatten)]
+        project: ProjectArg,
+        #[arg(long)]
+        input: String,
+        /// 消音時段，格式為 "開始-結束"，例如 "00:01:00-00:01:05"
+        #[arg(long = "range", required = true)]
+        ranges: Vec<String>,
+    },
+    /// 產生報告 (輸出到 04_report/report.md 並轉換為 DOCX)
+    Report {
+        #[command(flatten)]
+        project: ProjectArg,
+        /// 音檔資料夾 (預設為專案的 03_silence)
+        #[arg(long)]
+        folder: Option<PathBuf>,
+        #[arg(long, env = "GEMINI_API_KEY", hide_env_values = true)]
+        api_key: String,
+        /// Gemini 模型 (預設使用設定中的模型)
+        #[arg(long)]
+        model: Option<String>,
+        /// 自訂 Prompt 檔案
+        #[arg(long)]
+        prompt: Option<PathBuf>,
+        /// 雙語報告 (逐字稿後附英文翻譯；預設使用設定)
+        #[arg(long)]
+        bilingual: bool,
+    },
+}
+
+#[derive(Args)]
+struct ProjectArg {
+    /// 專案資料夾 (不存在時會建立)
+    #[arg(long)]
+    project: Option<PathBuf>,
+}
+
+impl ProjectArg {
+    /// 未指定專案時，依輸入檔案推算 (與 GUI 轉檔相同的規則)
+    fn resolve(&self, input: &str) -> Result<ProjectPaths, String> {
+        let paths = match &self.project {
+            Some(root) => ProjectPaths::from_root(root.clone())?,
+            None => ProjectPaths::new(input)?,
+        };
+        paths.create_all_dirs()?;
+        Ok(paths)
+    }
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    tracing_subscriber::fmt()
+        .with_writer(std::io::stderr)
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    let cli = Cli::parse();
+    i18n::set_locale(Locale::from_language(&settings::load().language));
+    let ffmpeg = Ffmpeg::Binary(resolve_ffmpeg(cli.ffmpeg));
+
+    // Ctrl+C 時中止 FFmpeg 與報告處理
+    let cancel = CancelToken::new();
+    let signal_token = cancel.clone();
+    tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            signal_token.cancel();
+        }
+    });
+
+    let result = match cli.command {
+        Command::Convert { project, files } => convert(&ffmpeg, &cancel, &project, &files).await,
+        Command::Split {
+            project,
+            input,
+            segments,
+        } => split(&ffmpeg, &cancel, &project, &input, &segments).await,
+        Command::Silence {
+            project,
+            input,
+            ranges,
+        } => silence(&ffmpeg, &cancel, &project, &input, &ranges).await,
+        Command::Report {
+            project,
+            folder,
+            api_key,
+            model,
+            prompt,
+            bilingual,
+        } => {
+            report(
+                &ffmpeg, &cancel, &project, folder, api_key, model, prompt, bilingual,
+            )
+            .await
+        }
+    };
+
+    match result {
+        Ok(message) => {
+            println!("{}", message);
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("錯誤: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// 依序尋找 FFmpeg：參數 > 安裝包附帶 > 已下載安裝 > PATH
+fn resolve_ffmpeg(explicit: Option<PathBuf>) -> PathBuf {
+    explicit
+        .or_else(sidecar::bundled_ffmpeg_path)
+        .or_else(dependencies::installed_ffmpeg_path_headless)
+        .unwrap_or_else(|| PathBuf::from(sidecar::executable_name("ffmpeg")))
+}
+
+async fn convert(
+    ffmpeg: &Ffmpeg,
+    cancel: &CancelToken,
+    project: &ProjectArg,
+    files: &[String],
+) -> Result<String, String> {
+    let config = settings::load();
+    let converter = Converter::new()
+        .with_cancel(cancel.clone())
+        .with_encoding(config.converted_encoding())
+        .with_collision(config.output_collision);
+    let mut failed = 0;
+
+    for file in files {
+        let paths = project.resolve(file)?;
+        storage::ensure_space(&paths.converted, storage::estimate_mp3_size(file))
+            .map_err(|e| e.to_string())?;
+
+        let output_dir = paths.converted.to_string_lossy().to_string();
+        let decoded = match dictaphone::detect(Path::new(file)) {
+            Some(format) => {
+                dictaphone::prepare(ffmpeg, format, Path::new(file), Some(&paths.root), cancel)
+                    .await
+                    .map_err(|e| e.to_string())?
+            }
+            None => None,
+        };
+        let input = decoded
+            .as_ref()
+            .map(|d| d.path.to_string_lossy().to_string())
+            .unwrap_or_else(|| file.clone());
+        let converted = converter.convert_to_mp3(ffmpeg, &input, &output_dir).await;
+        drop(decoded);
+        match converted {
+            Ok(output) => {
+                println!("✓ {}", output);
+                if let Some(note) = Converter::output_path(file, &output_dir)
+                    .and_then(|requested| renamed_note(&requested, &output))
+                {
+                    println!("{}", note);
+                }
+                let record = ProjectManifest::update(&paths.root, |m| {
+                    m.record_conversion(file, Path::new(&output))
+                });
+                if let Err(e) = record {
+                    tracing::warn!("無法更新專案描述檔: {}", e);
+                }
+                plugins::run_hook(
+                    PluginHook::PostConvert,
+                    Some(&paths.root),
+                    &[PathBuf::from(&output)],
+                    cancel,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+            }
+            Err(e) => {
+                if cancel.is_cancelled() {
+                    return Err(e);
+                }
+                failed += 1;
+                eprintln!("✗ {} - {}", file, e);
+            }
+        }
+    }
+
+    if failed > 0 {
+        Err(format!("{} 個檔案轉檔失敗", failed))
+    } else {
+        Ok(format!("轉檔完成，共 {} 個檔案", files.len()))
+    }
+}
+
+async fn split(
+    ffmpeg: &Ffmpeg,
+    cancel: &CancelToken,
+    project: &ProjectArg,
+    input: &str,
+    segments: &[String],
+) -> Result<String, String> {
+    let segments = segments
+        .iter()
+        .map(|s| parse_segment(s))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let paths = project.resolve(input)?;
+    storage::ensure_space(&paths.split, storage::file_size(input)).map_err(|e| e.to_string())?;
+
+    let output_dir = paths.split.to_string_lossy().to_string();
+    let config = settings::load();
+    let outputs = Splitter::new()
+        .with_cancel(cancel.clone())
+        .with_collision(config.output_collision)
+        .with_encoding(config.encoding.split)
+        .split_segments(ffmpeg, input, &output_dir, segments.clone())
+        .await?;
+
+    let mut lines = outputs.clone();
+    lines.extend(
+        segments
+            .iter()
+            .zip(&outputs)
+            .filter_map(|((name, _, _), actual)| {
+                renamed_note(&Splitter::output_path(input, &output_dir, name), actual)
+            }),
+    );
+    Ok(format!(
+        "切割完成，共 {} 個檔案\n{}",
+        outputs.len(),
+        lines.join("\n")
+    ))
+}
+
+async fn silence(
+    ffmpeg: &Ffmpeg,
+    cancel: &CancelToken,
+    project: &ProjectArg,
+    input: &str,
+    ranges: &[String],
+) -> Result<String, String> {
+    let ranges = ranges
+        .iter()
+        .map(|r| parse_range(r))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let paths = project.resolve(input)?;
+    ensure_not_encrypted(&paths.root)?;
+    storage::ensure_space(&paths.silence, storage::file_size(input)).map_err(|e| e.to_string())?;
+
+    let output_dir = paths.silence.to_string_lossy().to_string();
+    backup::snapshot(
+        &paths.root,
+        &[PathBuf::from(Silence::silenced_output_path(
+            input,
+            &output_dir,
+        ))],
+    )?;
+
+    let output = Silence::new()
+        .with_cancel(cancel.clone())
+        .with_collision(settings::load().output_collision)
+        .apply_silence_to_segments(ffmpeg, input, &output_dir, ranges)
+        .await?;
+
+    match renamed_note(&Silence::silenced_output_path(input, &output_dir), &output) {
+        Some(note) => Ok(format!("消音處理完成: {}\n{}", output, note)),
+        None => Ok(format!("消音處理完成: {}", output)),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn report(
+    ffmpeg: &Ffmpeg,
+    cancel: &CancelToken,
+    project: &ProjectArg,
+    folder: Option<PathBuf>,
+    api_key: String,
+    model: Option<String>,
+    prompt: Option<PathBuf>,
+    bilingual: bool,
+) -> Result<String, String> {
+    let root = project
+        .project
+        .clone()
+        .ok_or("產生報告需要指定 --project")?;
+    let paths = ProjectPaths::from_root(root)?;
+    ensure_not_encrypted(&paths.root)?;
+    paths.create_all_dirs()?;
+
+    let folder = folder.unwrap_or_else(|| paths.silence.clone());
+    let custom_prompt = match prompt {
+        Some(path) => Some(
+            std::fs::read_to_string(&path).map_err(|e| format!("無法讀取 Prompt 檔案: {}", e))?,
+        ),
+        None => None,
+    };
+    let config = settings::load();
+    let model = model.or(config.default_model);
+    let bilingual = bilingual || config.bilingual_report;
+
+    let output_path = paths.report.join("report.md");
+    let output_str = output_path.to_string_lossy().to_string();
+    backup::snapshot(
+        &paths.root,
+        &[
+            output_path.clone(),
+            output_path.with_extension("docx"),
+            report::summary_path(&output_path),
+        ],
+    )?;
+
+    let mut uploads: Vec<PathBuf> = std::fs::read_dir(&folder)
+        .map_err(|e| format!("無法讀取資料夾 {}: {}", folder.display(), e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && ingest::is_media_file(p))
+        .collect();
+    uploads.sort();
+    plugins::run_hook(PluginHook::PreUpload, Some(&paths.root), &uploads, cancel)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    let result = ReportAgent::new(api_key)
+        .with_cancel(cancel.clone())
+        .with_ffmpeg(ffmpeg.clone())
+        .with_bilingual(bilingual)
+        .with_template_vars(
+            ProjectManifest::load(&paths.root)
+                .unwrap_or_default()
+                .template_vars(&paths.root),
+        )
+        .process_folder(&folder.to_string_lossy(), &output_str, model, custom_prompt)
+        .await?;
+
+    let mut produced = vec![output_path.clone()];
+    let summary = report::summary_path(&output_path);
+    if summary.exists() {
+        produced.push(summary);
+    }
+    let message = match report::convert_md_to_docx(&output_str).await {
+        Ok(docx) => {
+            produced.push(PathBuf::from(&docx));
+            format!("{}\nDOCX: {}", result, docx)
+        }
+        Err(e) => format!("{}\n⚠️ Word 轉換失敗: {}", result, e),
+    };
+    plugins::run_hook(PluginHook::PostReport, Some(&paths.root), &produced, cancel)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(message)
+}
+
+/// 命令列沒有專案金鑰，加密專案的 03_silence、04_report 不能由命令列寫入 (會留下明文)
+fn ensure_not_encrypted(root: &Path) -> Result<(), String> {
+    if encryption::is_enabled(root) {
+        return Err(format!(
+            "專案已啟用加密，請在程式中解鎖後處理: {}",
+            root.display()
+        ));
+    }
+    Ok(())
+}
+
+/// "名稱=開始-結束" → (名稱, 開始, 結束)
+fn parse_segment(value: &str) -> Result<(String, String, String), String> {
+    let (name, range) = value
+        .split_once('=')
+        .ok_or_else(|| format!("段落格式錯誤 (應為 名稱=開始-結束): {}", value))?;
+    let (start, end) = range
+        .split_once('-')
+        .ok_or_else(|| format!("段落時間格式錯誤 (應為 開始-結束): {}", value))?;
+    let (name, start, end) = (name.trim(), start.trim(), end.trim());
+    if name.is_empty() {
+        return Err(format!("段落名稱不能為空: {}", value));
+    }
+    parse_time(start)?;
+    parse_time(end)?;
+    Ok((name.to_string(), start.to_string(), end.to_string()))
+}
+
+/// "開始-結束" → (開始秒數, 結束秒數)
+fn parse_range(value: &str) -> Result<(f64, f64), String> {
+    let (start, end) = value
+        .split_once('-')
+        .ok_or_else(|| format!("時段格式錯誤 (應為 開始-結束): {}", value))?;
+    let start = parse_time(start.trim())?;
+    let end = parse_time(end.trim())?;
+    if start >= end {
+        return Err(format!("開始時間必須小於結束時間: {}", value));
+    }
+    Ok((start, end))
+}
@@ -0,0 +1,182 @@
+// src-tauri/src/commands/annotation_cmd.rs
+//
+// Tauri commands for project annotations (shared by split, silence and report)
+
+use crate::commands::job_cmd::job_result_string;
+use crate::models::{AppError, ErrorKind};
+use crate::services::access::AccessPolicy;
+use crate::services::annotations::{self, Annotation, AnnotationCategory, AnnotationDraft};
+use crate::services::encryption;
+use crate::services::file_manager::{current_project, CurrentProjectState};
+use crate::services::jobs::{JobManager, JobSpec};
+use crate::services::timeline_export::{self, TimelineFormat};
+use crate::services::{transcript, viewer};
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, State, Window};
+
+fn project_root(state: &CurrentProjectState, window: &Window) -> Result<PathBuf, AppError> {
+    current_project(state, window.label()).ok_or_else(|| {
+        AppError::localized(ErrorKind::InvalidInput, "error.annotations_no_project", &[])
+    })
+}
+
+fn writable_root(state: &CurrentProjectState, window: &Window) -> Result<PathBuf, AppError> {
+    let root = project_root(state, window)?;
+    viewer::ensure_writable(&root)?;
+    Ok(root)
+}
+
+/// 列出專案標註；指定 file 時只列出該音檔
+#[command]
+pub fn list_annotations(
+    window: Window,
+    state: State<'_, CurrentProjectState>,
+    file: Option<String>,
+) -> Result<Vec<Annotation>, AppError> {
+    let root = project_root(&state, &window)?;
+    Ok(annotations::list(&root, file.as_deref().map(Path::new)))
+}
+
+#[command]
+pub fn add_annotation(
+    window: Window,
+    state: State<'_, CurrentProjectState>,
+    annotation: AnnotationDraft,
+) -> Result<Annotation, AppError> {
+    let root = writable_root(&state, &window)?;
+    annotations::add(&root, annotation).map_err(AppError::invalid_input)
+}
+
+#[command]
+pub fn update_annotation(
+    window: Window,
+    state: State<'_, CurrentProjectState>,
+    id: u64,
+    annotation: AnnotationDraft,
+) -> Result<Annotation, AppError> {
+    let root = writable_root(&state, &window)?;
+    annotations::update(&root, id, annotation).map_err(AppError::invalid_input)
+}
+
+/// 刪除標註，回傳刪除的數量
+#[command]
+pub fn delete_annotations(
+    window: Window,
+    state: State<'_, CurrentProjectState>,
+    ids: Vec<u64>,
+) -> Result<usize, AppError> {
+    let root = writable_root(&state, &window)?;
+    annotations::remove(&root, &ids).map_err(AppError::io)
+}
+
+/// 依音檔的標註切割 (categories 未指定時使用所有類別)
+#[command]
+pub async fn split_by_annotations(
+    window: Window,
+    state: State<'_, CurrentProjectState>,
+    jobs: State<'_, JobManager>,
+    audio_path: String,
+    categories: Option<Vec<AnnotationCategory>>,
+) -> Result<String, AppError> {
+    let root = project_root(&state, &window)?;
+    let selected: Vec<Annotation> = annotations::list(&root, Some(Path::new(&audio_path)))
+        .into_iter()
+        .filter(|a| match &categories {
+            Some(categories) => categories.contains(&a.category),
+            None => true,
+        })
+        .collect();
+    if selected.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_annotations",
+            &[],
+        ));
+    }
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Split {
+                audio_path,
+                project_root: Some(root.to_string_lossy().to_string()),
+                segments: annotations::split_segments(&selected),
+                transcribe: None,
+            },
+            0,
+        )
+        .await,
+    )
+}
+
+/// 將音檔中遮蔽類標註的時段消音
+#[command]
+pub async fn silence_by_annotations(
+    window: Window,
+    state: State<'_, CurrentProjectState>,
+    jobs: State<'_, JobManager>,
+    audio_path: String,
+) -> Result<String, AppError> {
+    let root = project_root(&state, &window)?;
+    let ranges =
+        annotations::redact_ranges(&annotations::list(&root, Some(Path::new(&audio_path))));
+    if ranges.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_annotations",
+            &[],
+        ));
+    }
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Silence {
+                audio_path,
+                project_root: Some(root.to_string_lossy().to_string()),
+                segments: ranges,
+            },
+            0,
+        )
+        .await,
+    )
+}
+
+/// 將音檔的標註輸出為 Audacity 標籤軌或 Reaper 區段 CSV (輸出到專案的 04_report)
+/// transcript_json: 一併輸出逐字稿段落的時間軸
+#[command]
+pub fn export_timeline(
+    app: AppHandle,
+    window: Window,
+    state: State<'_, CurrentProjectState>,
+    policy: State<'_, AccessPolicy>,
+    audio_path: String,
+    format: Option<TimelineFormat>,
+    transcript_json: Option<String>,
+) -> Result<String, AppError> {
+    let root = writable_root(&state, &window)?;
+    let transcript = match transcript_json {
+        Some(path) => {
+            let path = policy
+                .check(&app, Path::new(&path))
+                .map_err(AppError::permission_denied)?;
+            Some(transcript::load(&path)?)
+        }
+        None => None,
+    };
+    let audio = Path::new(&audio_path);
+    let entries =
+        timeline_export::entries(&annotations::list(&root, Some(audio)), transcript.as_ref());
+    if entries.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_annotations",
+            &[],
+        ));
+    }
+    let key = encryption::project_key(&app, &root)?;
+    let output = timeline_export::export(
+        &root,
+        audio,
+        &entries,
+        format.unwrap_or_default(),
+        key.as_ref(),
+    )?;
+    Ok(output.to_string_lossy().to_string())
+}
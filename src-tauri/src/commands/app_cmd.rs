@@ -1,30 +1,73 @@
+use crate::models::{AppError, ErrorKind};
+use crate::services::uninstall::{self, PurgePlan, UninstallMethod};
+use tauri::{AppHandle, Manager};
+
+/// 結束程式；執行中的工作會先被取消並等待結束 (見 main.rs 的 ExitRequested)
 #[tauri::command]
-pub fn exit_app() {
-    std::process::exit(0);
+pub fn exit_app(app: AppHandle) {
+    app.exit(0);
 }
 
+/// 解除安裝；purge_data 為 true 時一併刪除設定、app data 與日誌 (不含專案)
 #[tauri::command]
-pub fn uninstall_app() -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        let current_exe = std::env::current_exe().map_err(|e| e.to_string())?;
-        let parent_dir = current_exe.parent().ok_or("Cannot find parent directory")?;
-        let uninstall_path = parent_dir.join("uninstall.exe");
-
-        if !uninstall_path.exists() {
-            return Err("找不到解除安裝程式 (uninstall.exe)".to_string());
-        }
+pub fn uninstall_app(app: AppHandle, purge_data: Option<bool>) -> Result<(), AppError> {
+    let method = uninstall::detect_method().ok_or_else(|| {
+        AppError::localized(ErrorKind::NotFound, "error.uninstaller_not_found", &[])
+    })?;
 
-        std::process::Command::new(uninstall_path)
-            .spawn()
-            .map_err(|e| e.to_string())?;
+    if let UninstallMethod::PackageManager(command) = &method {
+        return Err(AppError::localized(
+            ErrorKind::Unsupported,
+            "error.uninstall_package_manager",
+            &[("command", command.clone())],
+        ));
+    }
 
-        // Optional: Exit app so uninstaller can remove files
-        std::process::exit(0);
+    if purge_data.unwrap_or(false) {
+        let plan = uninstall::plan_purge(app.path().app_data_dir().ok(), false);
+        // 程式即將結束，部分檔案 (例如開啟中的日誌) 刪除失敗時不中斷解除安裝
+        if let Err(e) = uninstall::purge(plan) {
+            tracing::warn!("部分資料無法刪除:\n{}", e);
+        }
     }
 
-    #[cfg(not(target_os = "windows"))]
-    {
-        return Err("PLATFORM_NOT_SUPPORTED".to_string());
+    uninstall::start(&method).map_err(AppError::tool)?;
+
+    // Exit app so uninstaller can remove files
+    std::process::exit(0);
+}
+
+/// 列出清除資料時會刪除的項目 (dry run)
+#[tauri::command]
+pub fn preview_purge(app: AppHandle, include_projects: Option<bool>) -> PurgePlan {
+    uninstall::plan_purge(
+        app.path().app_data_dir().ok(),
+        include_projects.unwrap_or(false),
+    )
+}
+
+/// 刪除設定、app data 與日誌；包含專案時 confirm_project_root 必須與專案根目錄相同
+#[tauri::command]
+pub fn purge_app_data(
+    app: AppHandle,
+    include_projects: Option<bool>,
+    confirm_project_root: Option<String>,
+) -> Result<PurgePlan, AppError> {
+    let plan = uninstall::plan_purge(
+        app.path().app_data_dir().ok(),
+        include_projects.unwrap_or(false),
+    );
+
+    if let Some(root) = plan.projects_root() {
+        if confirm_project_root.as_deref() != Some(root) {
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.purge_projects_unconfirmed",
+                &[("path", root.to_string())],
+            ));
+        }
     }
+
+    uninstall::purge(plan)
+        .map_err(|e| AppError::localized(ErrorKind::Io, "error.purge_failed", &[]).with_detail(e))
 }
@@ -1,6 +1,21 @@
 // src-tauri/src/commands/audio_cmd.rs
-use crate::services::file_manager::{CurrentProjectState, ProjectPaths};
-use crate::services::{Converter, Silence, Splitter};
+use crate::commands::job_cmd::job_result_string;
+use crate::models::{AppError, ErrorKind};
+use crate::services::access::AccessPolicy;
+use crate::services::batch_guard;
+use crate::services::chapters::{self, Chapter};
+use crate::services::downmix::{self, ChannelAnalysis};
+use crate::services::file_manager::{current_project, CurrentProjectState};
+use crate::services::jobs::{JobManager, JobSpec, TranscribeOnSplit};
+use crate::services::probe::{self, AudioInfo};
+use crate::services::quality::{self, QualityReport};
+use crate::services::sidecar::Ffmpeg;
+use crate::services::splitter::format_timestamp;
+use crate::services::storage::{self, SpaceCheck};
+use crate::services::timeline_import::{self, TimelineSource};
+use crate::services::waveform::{self, Waveform};
+use crate::services::workflows::parse_time;
+use crate::services::{Silence, Splitter};
 use tauri::command;
 
 /// 取得系統下載資料夾路徑 (跨平台)
@@ -19,99 +34,44 @@ fn get_download_dir() -> String {
 
 #[command]
 pub fn run_convert_cmd() -> String {
-    format!("Converter 已就緒，輸出目錄: {}", get_download_dir())
+    crate::tr!("result.converter_ready", dir = get_download_dir())
 }
 
 /// 轉換多個檔案為 MP3
 #[command]
 pub async fn convert_files_to_mp3(
-    app: tauri::AppHandle,
+    window: tauri::Window,
     state: tauri::State<'_, CurrentProjectState>,
+    jobs: tauri::State<'_, JobManager>,
     file_paths: Vec<String>,
-) -> Result<String, String> {
+    confirm_token: Option<String>,
+) -> Result<String, AppError> {
     if file_paths.is_empty() {
-        return Err("未選擇任何檔案".to_string());
-    }
-
-    let converter = Converter::new();
-    let mut success_count = 0;
-    let mut fail_count = 0;
-    let mut messages = Vec::new();
-
-    // 用於最後顯示路徑
-    let first_file_path = file_paths.first().cloned();
-
-    let current_project_root = state.lock().unwrap().clone();
-
-    // 針對每一個檔案，都必須建立其專屬的 Project Folder
-    for path in file_paths {
-        // 1. 初始化專案路徑
-        let project_paths_result = if let Some(root) = &current_project_root {
-            ProjectPaths::from_root(root.clone())
-        } else {
-            ProjectPaths::new(&path)
-        };
-
-        let project_paths = match project_paths_result {
-            Ok(p) => p,
-            Err(e) => {
-                fail_count += 1;
-                messages.push(format!("✗ {} - 路徑錯誤: {}", path, e));
-                continue;
-            }
-        };
-
-        // 2. 建立資料夾
-        if let Err(e) = project_paths.create_all_dirs() {
-            fail_count += 1;
-            messages.push(format!("✗ {} - 無法建立資料夾: {}", path, e));
-            continue;
-        }
-
-        let output_dir = project_paths.converted.to_string_lossy().to_string();
-
-        // 3. 執行單一轉檔
-        match converter.convert_to_mp3(&app, &path, &output_dir).await {
-            Ok(output_path) => {
-                success_count += 1;
-                messages.push(format!("✓ {}", output_path));
-            }
-            Err(e) => {
-                fail_count += 1;
-                messages.push(format!("✗ {} - {}", path, e));
-            }
-        }
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_files_selected",
+            &[],
+        ));
     }
-
-    // 4. 計算最後顯示的根目錄路徑
-    let root_path_display = if let Some(path) = first_file_path {
-        if let Ok(p) = crate::services::ProjectPaths::new(&path) {
-            p.root
-                .parent()
-                .map(|p| p.to_string_lossy().to_string())
-                .unwrap_or_else(|| "Unknown".to_string())
-        } else {
-            "Unknown".to_string()
-        }
-    } else {
-        "Unknown".to_string()
-    };
-
-    let result_msg = format!(
-        "轉檔完成！成功: {} 個，失敗: {} 個\n檔案位置: {}\n(已依照檔名自動分類專案資料夾)\n\n{}",
-        success_count,
-        fail_count,
-        root_path_display,
-        messages.join("\n")
-    );
-
-    Ok(result_msg)
-}
-
-#[command]
-pub fn set_project_root_dir(path: String) -> Result<String, String> {
-    crate::services::ProjectPaths::set_custom_root(path.clone())
-        .map(|_| format!("成功設定預設專案路徑為: {}", path))
+    let file_paths = tauri::async_runtime::spawn_blocking(move || {
+        batch_guard::check(&file_paths, confirm_token.as_deref()).map(|_| file_paths)
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))??;
+
+    let project_root =
+        current_project(&state, window.label()).map(|p| p.to_string_lossy().to_string());
+
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Convert {
+                file_paths,
+                project_root,
+            },
+            0,
+        )
+        .await,
+    )
 }
 
 #[command]
@@ -131,7 +91,7 @@ pub fn run_silence_cmd() -> String {
 }
 
 /// 段落資訊（從前端傳入）
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct SegmentInfo {
     pub name: String,
     #[serde(rename = "startTime")]
@@ -142,42 +102,53 @@ pub struct SegmentInfo {
 
 /// 切割音訊檔案
 /// 根據傳入的段落列表，將音檔切割成多個片段
+/// transcribe_server: 指定 STT 伺服器時，每切出一段就送去轉錄
 #[command]
 pub async fn split_audio_segments(
-    app: tauri::AppHandle,
+    window: tauri::Window,
     state: tauri::State<'_, CurrentProjectState>,
+    jobs: tauri::State<'_, JobManager>,
     audio_path: String,
     segments: Vec<SegmentInfo>,
-) -> Result<String, String> {
+    transcribe_server: Option<String>,
+    diarize: Option<bool>,
+) -> Result<String, AppError> {
     if audio_path.is_empty() {
-        return Err("未載入音訊檔案".to_string());
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_track_loaded",
+            &[],
+        ));
     }
 
     if segments.is_empty() {
-        return Err("未設定任何段落".to_string());
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_segments",
+            &[],
+        ));
     }
 
     // 驗證段落資料
     for (i, seg) in segments.iter().enumerate() {
         if seg.name.trim().is_empty() {
-            return Err(format!("第 {} 個段落名稱不能為空", i + 1));
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.segment_name_empty",
+                &[("index", (i + 1).to_string())],
+            ));
         }
         if seg.start_time.is_empty() || seg.end_time.is_empty() {
-            return Err(format!("第 {} 個段落 '{}' 的時間不完整", i + 1, seg.name));
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.segment_time_incomplete",
+                &[("index", (i + 1).to_string()), ("name", seg.name.clone())],
+            ));
         }
     }
 
-    let current_project_root = state.lock().unwrap().clone();
-
-    // 使用 ProjectPaths 建立輸出目錄 (02_split)
-    let project_paths = if let Some(root) = &current_project_root {
-        ProjectPaths::from_root(root.clone())?
-    } else {
-        crate::services::ProjectPaths::new(&audio_path)?
-    };
-
-    project_paths.create_all_dirs()?;
-    let output_dir_str = project_paths.split.to_string_lossy().to_string();
+    let project_root =
+        current_project(&state, window.label()).map(|p| p.to_string_lossy().to_string());
 
     // 轉換段落資料格式
     let segment_tuples: Vec<(String, String, String)> = segments
@@ -185,32 +156,154 @@ pub async fn split_audio_segments(
         .map(|s| (s.name, s.start_time, s.end_time))
         .collect();
 
-    // 執行切割
-    let splitter = Splitter::new();
-    let output_files = splitter
-        .split_segments(&app, &audio_path, &output_dir_str, segment_tuples)
-        .await?;
-
-    Ok(format!(
-        "切割完成！共產生 {} 個檔案\n輸出目錄: {}\n\n{}",
-        output_files.len(),
-        output_dir_str,
-        output_files.join("\n")
-    ))
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Split {
+                audio_path,
+                project_root,
+                segments: segment_tuples,
+                transcribe: transcribe_server
+                    .filter(|server| !server.trim().is_empty())
+                    .map(|server| TranscribeOnSplit {
+                        server,
+                        diarize: diarize.unwrap_or(false),
+                    }),
+            },
+            0,
+        )
+        .await,
+    )
+}
+
+/// 讀取音檔內含的章節 (m4b、mkv 等)，沒有章節時回傳空清單
+#[command]
+pub async fn get_audio_chapters(
+    app: tauri::AppHandle,
+    audio_path: String,
+) -> Result<Vec<Chapter>, AppError> {
+    chapters::read_chapters(&Ffmpeg::from(&app), &audio_path)
+        .await
+        .map_err(AppError::tool)
+}
+
+/// 依音檔內含的章節切割到 02_split，每個章節一個檔案，以章節標題命名
+#[command]
+pub async fn split_by_chapters(
+    app: tauri::AppHandle,
+    window: tauri::Window,
+    state: tauri::State<'_, CurrentProjectState>,
+    jobs: tauri::State<'_, JobManager>,
+    audio_path: String,
+) -> Result<String, AppError> {
+    if audio_path.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_track_loaded",
+            &[],
+        ));
+    }
+    let found = chapters::read_chapters(&Ffmpeg::from(&app), &audio_path)
+        .await
+        .map_err(AppError::tool)?;
+    if found.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_chapters",
+            &[],
+        ));
+    }
+
+    let project_root =
+        current_project(&state, window.label()).map(|p| p.to_string_lossy().to_string());
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Split {
+                audio_path,
+                project_root,
+                segments: chapters::to_segments(&found),
+                transcribe: None,
+            },
+            0,
+        )
+        .await,
+    )
+}
+
+/// 降噪單一檔案，輸出到專案的 01b_cleaned (方式與強度依設定)
+#[command]
+pub async fn denoise_file(
+    window: tauri::Window,
+    state: tauri::State<'_, CurrentProjectState>,
+    jobs: tauri::State<'_, JobManager>,
+    audio_path: String,
+) -> Result<String, AppError> {
+    if audio_path.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_track_loaded",
+            &[],
+        ));
+    }
+    let project_root =
+        current_project(&state, window.label()).map(|p| p.to_string_lossy().to_string());
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Denoise {
+                audio_path,
+                project_root,
+            },
+            0,
+        )
+        .await,
+    )
 }
 
+/// 人聲強化 (高通、壓縮、去齒音)，輸出到專案的 01b_cleaned 並回傳輸出檔路徑
+/// 前端可再以 start_compare 比較處理前後
 #[command]
-pub fn list_audio_files(dir_path: String) -> Result<Vec<String>, String> {
+pub async fn enhance_speech(
+    window: tauri::Window,
+    state: tauri::State<'_, CurrentProjectState>,
+    jobs: tauri::State<'_, JobManager>,
+    path: String,
+) -> Result<String, AppError> {
+    if path.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_track_loaded",
+            &[],
+        ));
+    }
+    let project_root =
+        current_project(&state, window.label()).map(|p| p.to_string_lossy().to_string());
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Enhance {
+                audio_path: path,
+                project_root,
+            },
+            0,
+        )
+        .await,
+    )
+}
+
+#[command]
+pub fn list_audio_files(dir_path: String) -> Result<Vec<String>, AppError> {
     use std::fs;
     use std::path::Path;
 
     let path = Path::new(&dir_path);
     if !path.exists() || !path.is_dir() {
-        return Err("目錄不存在或無效".to_string());
+        return Err(AppError::localized(
+            ErrorKind::NotFound,
+            "error.dir_not_found",
+            &[],
+        ));
     }
 
     let mut files = Vec::new();
-    let entries = fs::read_dir(path).map_err(|e| e.to_string())?;
+    let entries = fs::read_dir(path)?;
 
     for entry in entries {
         if let Ok(entry) = entry {
@@ -232,7 +325,7 @@ pub fn list_audio_files(dir_path: String) -> Result<Vec<String>, String> {
     Ok(files)
 }
 
-#[derive(serde::Deserialize)]
+#[derive(serde::Deserialize, serde::Serialize)]
 pub struct SilenceSegment {
     pub note: Option<String>,
     #[serde(rename = "startTime")]
@@ -244,111 +337,196 @@ pub struct SilenceSegment {
 /// 執行手動消音處理
 #[command]
 pub async fn apply_silence_command(
-    app: tauri::AppHandle,
+    window: tauri::Window,
     state: tauri::State<'_, CurrentProjectState>,
+    jobs: tauri::State<'_, JobManager>,
     audio_path: String,
     segments: Vec<SilenceSegment>,
-) -> Result<String, String> {
-    use crate::services::Silence;
-
+) -> Result<String, AppError> {
     if audio_path.is_empty() {
-        return Err("未載入音訊檔案".to_string());
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_track_loaded",
+            &[],
+        ));
     }
     if segments.is_empty() {
-        return Err("未設定任何消音時段".to_string());
-    }
-
-    // Helper to parse "HH:MM:SS.mmm" or "SS.mmm" to seconds
-    fn parse_time(t: &str) -> Result<f64, String> {
-        let parts: Vec<&str> = t.split(':').collect();
-        match parts.len() {
-            3 => {
-                let h: f64 = parts[0].parse().map_err(|_| "Invalid hour")?;
-                let m: f64 = parts[1].parse().map_err(|_| "Invalid minute")?;
-                let s: f64 = parts[2].parse().map_err(|_| "Invalid second")?;
-                Ok(h * 3600.0 + m * 60.0 + s)
-            }
-            2 => {
-                let m: f64 = parts[0].parse().map_err(|_| "Invalid minute")?;
-                let s: f64 = parts[1].parse().map_err(|_| "Invalid second")?;
-                Ok(m * 60.0 + s)
-            }
-            1 => parts[0]
-                .parse()
-                .map_err(|_| format!("Invalid time format: {}", t)),
-            _ => Err(format!("Invalid time format: {}", t)),
-        }
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_silence_ranges",
+            &[],
+        ));
     }
 
     let mut parsed_segments = Vec::new();
     for seg in segments {
-        let start = parse_time(&seg.start_time).map_err(|e| format!("開始時間格式錯誤: {}", e))?;
-        let end = parse_time(&seg.end_time).map_err(|e| format!("結束時間格式錯誤: {}", e))?;
+        let start = parse_time(&seg.start_time).map_err(|e| {
+            AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.invalid_start_time",
+                &[("detail", e)],
+            )
+        })?;
+        let end = parse_time(&seg.end_time).map_err(|e| {
+            AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.invalid_end_time",
+                &[("detail", e)],
+            )
+        })?;
 
         if start >= end {
-            return Err(format!(
-                "開始時間必須小於結束時間 ({}-{})",
-                seg.start_time, seg.end_time
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.start_after_end",
+                &[
+                    ("start", seg.start_time.clone()),
+                    ("end", seg.end_time.clone()),
+                ],
             ));
         }
         parsed_segments.push((start, end));
     }
 
-    let current_project_root = state.lock().unwrap().clone();
-
-    // 建立輸出目錄 (03_silence)
-    let project_paths = if let Some(root) = &current_project_root {
-        ProjectPaths::from_root(root.clone())?
-    } else {
-        crate::services::ProjectPaths::new(&audio_path)?
-    };
-
-    project_paths.create_all_dirs()?;
-    let output_dir_str = project_paths.silence.to_string_lossy().to_string();
-
-    // 檢查 03_silence 是否為空
-    // 規則：若是第一次執行 (03 為空)，將 02_split 下的所有檔案 複製 (Copy) 過來
-    // 這樣 02_split 保留所有原始檔，03_silence 則作為報告用的工作目錄
-    let silence_dir = &project_paths.silence;
-    if let Ok(entries) = std::fs::read_dir(silence_dir) {
-        if entries.count() == 0 {
-            // 03_silence 為空，執行複製
-            if let Ok(split_entries) = std::fs::read_dir(&project_paths.split) {
-                for entry in split_entries {
-                    if let Ok(entry) = entry {
-                        let path = entry.path();
-                        if path.is_file() {
-                            if let Some(file_name) = path.file_name() {
-                                let dest_path = silence_dir.join(file_name);
-                                if let Err(e) = std::fs::copy(&path, &dest_path) {
-                                    println!("Failed to copy file {:?} to 03_silence: {}", path, e);
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let project_root =
+        current_project(&state, window.label()).map(|p| p.to_string_lossy().to_string());
+
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Silence {
+                audio_path,
+                project_root,
+                segments: parsed_segments,
+            },
+            0,
+        )
+        .await,
+    )
+}
 
-    let silence_service = Silence::new();
-    let output_path = silence_service
-        .apply_silence_to_segments(&app, &audio_path, &output_dir_str, parsed_segments)
-        .await?;
-
-    // 處理完成後，將該檔案的"原始檔"從 03_silence 中移除 (如果存在)
-    // 根據需求：03_silence 應該只保留"已處理的檔案"以及"尚未處理的其他檔案"
-    // 當某個檔案被處理成 xxx_silenced.mp3 後，原本在 03_silence 的 xxx.mp3 就應該移除，避免重複
-    if let Some(file_name) = std::path::Path::new(&audio_path).file_name() {
-        let original_in_silence = project_paths.silence.join(file_name);
-        if original_in_silence.exists() && original_in_silence.is_file() {
-            // 確認一下不是刪除剛產生的 output_path (雖然檔名應該不同，output 有 suffix)
-            // 這裡簡單檢查一下路徑是否完全相同
-            if original_in_silence.to_string_lossy() != output_path {
-                let _ = std::fs::remove_file(original_in_silence);
-            }
-        }
+/// 預先檢查轉檔所需的磁碟空間，讓前端在開始前提示使用者
+#[command]
+pub fn check_conversion_space(
+    target_dir: String,
+    file_paths: Vec<String>,
+) -> Result<SpaceCheck, AppError> {
+    let estimated = file_paths
+        .iter()
+        .map(|p| storage::estimate_mp3_size(p))
+        .sum();
+    storage::check_space(std::path::Path::new(&target_dir), estimated)
+}
+
+/// 檢查錄音品質 (削波、數位靜音、斷訊、直流偏移、損毀)，在送出轉錄前找出無法使用的檔案
+#[command]
+pub async fn analyze_audio_quality(path: String) -> Result<QualityReport, AppError> {
+    tauri::async_runtime::spawn_blocking(move || quality::analyze(&path))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(|detail| {
+            AppError::localized(
+                ErrorKind::Tool,
+                "error.quality_analysis",
+                &[("detail", detail)],
+            )
+        })
+}
+
+/// 分析立體聲錄音的聲道配置 (雙單聲道 / 立體聲 / 單邊錄音) 與建議的轉單聲道方式
+#[command]
+pub async fn analyze_channels(path: String) -> Result<ChannelAnalysis, AppError> {
+    tauri::async_runtime::spawn_blocking(move || downmix::analyze(&path))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(|detail| {
+            AppError::localized(
+                ErrorKind::Tool,
+                "error.channel_analysis",
+                &[("detail", detail)],
+            )
+        })
+}
+
+/// 取得音檔波形峰值 (有快取時直接讀取)；buckets 為前端需要的區間數
+#[command]
+pub async fn get_waveform(path: String, buckets: Option<usize>) -> Result<Waveform, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        waveform::load_or_compute(std::path::Path::new(&path))
+            .map(|w| w.reduce(buckets.unwrap_or(0)))
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?
+    .map_err(|detail| AppError::localized(ErrorKind::Tool, "error.waveform", &[("detail", detail)]))
+}
+
+/// 匯入的時間軸：同時提供切割與消音兩種段落，前端依頁面取用
+#[derive(serde::Serialize)]
+pub struct ImportedTimeline {
+    pub source: TimelineSource,
+    /// 切割用 (重疊的段落已調整)
+    pub segments: Vec<SegmentInfo>,
+    /// 消音用 (重疊的段落已合併)
+    pub silence_segments: Vec<SilenceSegment>,
+    /// 切割段落中被調整或略過的數量
+    pub adjusted: usize,
+    /// 消音段落中被合併的數量
+    pub merged: usize,
+}
+
+/// 匯入 Audacity 標籤 (.txt)、SRT 或 VTT，轉成切割與消音段落
+#[command]
+pub fn import_timeline(
+    app: tauri::AppHandle,
+    policy: tauri::State<'_, AccessPolicy>,
+    path: String,
+) -> Result<ImportedTimeline, AppError> {
+    let path = policy
+        .check(&app, std::path::Path::new(&path))
+        .map_err(AppError::permission_denied)?;
+    let (source, entries) = timeline_import::parse(&path).map_err(AppError::invalid_input)?;
+    if entries.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.timeline_empty",
+            &[],
+        ));
     }
 
-    Ok(format!("消音處理完成！\n輸出檔案: {}", output_path))
+    let (split, adjusted) = timeline_import::trim_overlaps(&entries);
+    let (silence, merged) = timeline_import::merge_overlaps(&entries);
+    Ok(ImportedTimeline {
+        source,
+        segments: split
+            .into_iter()
+            .enumerate()
+            .map(|(i, e)| SegmentInfo {
+                name: if e.label.is_empty() {
+                    format!("segment_{}", i + 1)
+                } else {
+                    e.label
+                },
+                start_time: format_timestamp(e.start),
+                end_time: format_timestamp(e.end),
+            })
+            .collect(),
+        silence_segments: silence
+            .into_iter()
+            .map(|e| SilenceSegment {
+                note: Some(e.label).filter(|l| !l.is_empty()),
+                start_time: format_timestamp(e.start),
+                end_time: format_timestamp(e.end),
+            })
+            .collect(),
+        adjusted,
+        merged,
+    })
+}
+
+/// 探測資料夾內所有音檔的時長、編碼與大小 (專案內的結果會快取，檔案未變更時不重新探測)
+#[command]
+pub async fn probe_folder(dir: String) -> Result<Vec<AudioInfo>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || probe::probe_folder(std::path::Path::new(&dir)))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::not_found)
 }
@@ -0,0 +1,27 @@
+// src-tauri/src/commands/dependency_cmd.rs
+//
+// Tauri commands for external tool checks (FFmpeg / Pandoc) and service diagnostics
+
+use crate::models::AppError;
+use crate::services::dependencies::{self, DependencyReport};
+use crate::services::health::{self, ServiceReport};
+use crate::services::stt_models::ModelDownloads;
+use tauri::{command, AppHandle, State};
+
+/// 檢查 FFmpeg 與 Pandoc 是否可用，首次啟動時由前端呼叫
+#[command]
+pub async fn check_dependencies(app: AppHandle) -> DependencyReport {
+    dependencies::check_dependencies(&app).await
+}
+
+/// 啟動診斷：一次檢查 FFmpeg、Pandoc、STT 伺服器、Gemini (API Key) 與本機模型
+/// stt_server 未指定時使用設定中的位址；api_key 未指定時 Gemini 顯示為未設定
+#[command]
+pub async fn check_all_services(
+    app: AppHandle,
+    downloads: State<'_, ModelDownloads>,
+    stt_server: Option<String>,
+    api_key: Option<String>,
+) -> Result<ServiceReport, AppError> {
+    Ok(health::check_all(&app, &downloads, stt_server, api_key).await)
+}
@@ -1,31 +1,488 @@
+use crate::models::{AppError, ErrorKind};
+use crate::services::access::AccessPolicy;
+use crate::services::file_manager::write_atomic;
+use crate::services::trash::{self, DeleteResult, TrashedFile};
+use crate::services::viewer;
+use crate::services::volume::{self, LocationStatus};
+use crate::services::{history, storage};
+use serde_json::{json, Value};
 use std::fs;
-use std::path::Path;
-use tauri::command;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{command, AppHandle, Manager, State};
+use tauri_plugin_dialog::DialogExt;
+
+/// 檢查路徑是否在允許存取的範圍內 (專案、app data、使用者授權的路徑)
+fn checked_path(app: &AppHandle, policy: &AccessPolicy, path: &str) -> Result<PathBuf, AppError> {
+    policy
+        .check(app, Path::new(path))
+        .map_err(AppError::permission_denied)
+}
 
 /// Create directory if it doesn't exist
 #[command]
-pub fn ensure_dir_exists(path: String) -> Result<(), String> {
-    if !Path::new(&path).exists() {
-        fs::create_dir_all(&path).map_err(|e| format!("Failed to create directory: {}", e))
+pub fn ensure_dir_exists(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+) -> Result<(), AppError> {
+    let dir = checked_path(&app, &policy, &path)?;
+    viewer::ensure_writable(&dir)?;
+    if !dir.exists() {
+        fs::create_dir_all(&dir).map_err(|e| {
+            AppError::from(e).with_detail(format!("Failed to create directory: {}", path))
+        })
     } else {
         Ok(())
     }
 }
 
-/// Save content to a JSON file
+/// 文字/JSON 檔案讀寫上限 (50 MB)
+const MAX_TEXT_FILE_BYTES: u64 = 50 * 1024 * 1024;
+
+fn ensure_size_limit(path: &Path, size: u64) -> Result<(), AppError> {
+    if size > MAX_TEXT_FILE_BYTES {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.file_too_large",
+            &[
+                ("path", path.display().to_string()),
+                ("size", storage::format_bytes(size)),
+                ("limit", storage::format_bytes(MAX_TEXT_FILE_BYTES)),
+            ],
+        ));
+    }
+    Ok(())
+}
+
+/// 寫入檔案：先寫暫存檔再改名，斷電時不會留下寫到一半的檔案；自動建立上層資料夾
+/// append 為 true 且檔案已存在時直接接在檔尾並 fsync，不重寫整個檔案
+fn write_checked(file: &Path, content: &[u8], append: bool) -> Result<(), AppError> {
+    if append && file.exists() {
+        let existing_size = fs::metadata(file).map(|m| m.len()).unwrap_or(0);
+        ensure_size_limit(file, existing_size.saturating_add(content.len() as u64))?;
+        return append_synced(file, content).map_err(|e| {
+            AppError::from(e).with_detail(format!("Failed to write file: {}", file.display()))
+        });
+    }
+    ensure_size_limit(file, content.len() as u64)?;
+    write_atomic(file, content).map_err(|e| {
+        AppError::from(e).with_detail(format!("Failed to write file: {}", file.display()))
+    })
+}
+
+fn append_synced(file: &Path, content: &[u8]) -> std::io::Result<()> {
+    let mut handle = fs::OpenOptions::new().append(true).open(file)?;
+    handle.write_all(content)?;
+    handle.sync_all()
+}
+
+fn read_checked(file: &Path) -> Result<String, AppError> {
+    let size = fs::metadata(file)
+        .map_err(|e| {
+            AppError::from(e).with_detail(format!("Failed to read file: {}", file.display()))
+        })?
+        .len();
+    ensure_size_limit(file, size)?;
+    fs::read_to_string(file).map_err(|e| {
+        AppError::from(e).with_detail(format!("Failed to read file: {}", file.display()))
+    })
+}
+
+/// Save content to a text file (atomic, optional append)
 #[command]
-pub fn save_text_file(path: String, content: String) -> Result<(), String> {
-    fs::write(&path, content).map_err(|e| format!("Failed to write file: {}", e))
+pub fn save_text_file(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+    content: String,
+    append: Option<bool>,
+) -> Result<(), AppError> {
+    let file = checked_path(&app, &policy, &path)?;
+    viewer::ensure_writable(&file)?;
+    let started = Instant::now();
+    let append = append.unwrap_or(false);
+    let result = write_checked(&file, content.as_bytes(), append);
+    record_write(
+        &file,
+        "save_text_file",
+        json!({ "path": path, "bytes": content.len(), "append": append }),
+        started,
+        &result,
+    );
+    result
 }
 
 /// Read content from a text file
 #[command]
-pub fn read_text_file(path: String) -> Result<String, String> {
-    fs::read_to_string(&path).map_err(|e| format!("Failed to read file: {}", e))
+pub fn read_text_file(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+) -> Result<String, AppError> {
+    let file = checked_path(&app, &policy, &path)?;
+    read_checked(&file)
+}
+
+/// 讀取 JSON 檔案；提供 schema 時檢查內容格式
+#[command]
+pub fn read_json_file(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+    schema: Option<Value>,
+) -> Result<Value, AppError> {
+    let file = checked_path(&app, &policy, &path)?;
+    let content = read_checked(&file)?;
+    let value: Value = serde_json::from_str(&content).map_err(|e| {
+        AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.invalid_json",
+            &[("path", path.clone()), ("detail", e.to_string())],
+        )
+    })?;
+    if let Some(schema) = &schema {
+        validate_json(&path, &value, schema)?;
+    }
+    Ok(value)
+}
+
+/// 寫入 JSON 檔案 (格式化輸出、atomic)；提供 schema 時先檢查內容格式
+#[command]
+pub fn write_json_file(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+    value: Value,
+    schema: Option<Value>,
+) -> Result<(), AppError> {
+    let file = checked_path(&app, &policy, &path)?;
+    viewer::ensure_writable(&file)?;
+    if let Some(schema) = &schema {
+        validate_json(&path, &value, schema)?;
+    }
+    let started = Instant::now();
+    let content = serde_json::to_string_pretty(&value)?;
+    let result = write_checked(&file, content.as_bytes(), false);
+    record_write(
+        &file,
+        "write_json_file",
+        json!({ "path": path, "bytes": content.len() }),
+        started,
+        &result,
+    );
+    result
+}
+
+/// 寫入專案內的檔案時記錄到操作紀錄
+fn record_write(
+    file: &Path,
+    command: &str,
+    args: Value,
+    started: Instant,
+    result: &Result<(), AppError>,
+) {
+    if let Some(root) = history::project_root_for(file) {
+        history::record_command(&root, command, args, &[file.to_path_buf()], started, result);
+    }
+}
+
+fn validate_json(path: &str, value: &Value, schema: &Value) -> Result<(), AppError> {
+    let mut errors = Vec::new();
+    schema_errors(value, schema, "$", &mut errors);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(AppError::localized(
+        ErrorKind::InvalidInput,
+        "error.json_schema",
+        &[
+            ("path", path.to_string()),
+            ("count", errors.len().to_string()),
+        ],
+    )
+    .with_detail(errors.join("\n")))
+}
+
+/// 簡化的 JSON Schema 檢查，支援 type / required / properties / items / enum
+fn schema_errors(value: &Value, schema: &Value, at: &str, errors: &mut Vec<String>) {
+    let Some(schema) = schema.as_object() else {
+        return;
+    };
+
+    if let Some(expected) = schema.get("type") {
+        let types: Vec<&str> = match expected {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !types.is_empty() && !types.iter().any(|t| type_matches(value, t)) {
+            errors.push(format!("{}: 應為 {}", at, types.join(" | ")));
+            return;
+        }
+    }
+
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            errors.push(format!("{}: 不在允許的值之中", at));
+        }
+    }
+
+    if let Value::Object(map) = value {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for key in required.iter().filter_map(Value::as_str) {
+                if !map.contains_key(key) {
+                    errors.push(format!("{}: 缺少欄位 '{}'", at, key));
+                }
+            }
+        }
+        if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+            for (key, property_schema) in properties {
+                if let Some(field) = map.get(key) {
+                    schema_errors(field, property_schema, &format!("{}.{}", at, key), errors);
+                }
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (index, item) in items.iter().enumerate() {
+            schema_errors(item, item_schema, &format!("{}[{}]", at, index), errors);
+        }
+    }
+}
+
+fn type_matches(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
 }
 
 /// Check if a file exists
 #[command]
-pub fn check_file_exists(path: String) -> Result<bool, String> {
-    Ok(Path::new(&path).exists())
+pub fn check_file_exists(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+) -> Result<bool, AppError> {
+    Ok(checked_path(&app, &policy, &path)?.exists())
+}
+
+/// 開啟對話框讓使用者選擇檔案或資料夾，並授權檔案命令存取
+/// 使用者取消時回傳 None
+#[command]
+pub async fn request_path_access(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    directory: Option<bool>,
+    default_path: Option<String>,
+    title: Option<String>,
+) -> Result<Option<String>, AppError> {
+    let mut dialog = app.dialog().file();
+    if let Some(title) = title {
+        dialog = dialog.set_title(title);
+    }
+    if let Some(default_path) = default_path {
+        dialog = dialog.set_directory(default_path);
+    }
+    let picked = if directory.unwrap_or(false) {
+        dialog.blocking_pick_folder()
+    } else {
+        dialog.blocking_pick_file()
+    };
+
+    let Some(picked) = picked else {
+        return Ok(None);
+    };
+    let path = picked
+        .into_path()
+        .map_err(|e| AppError::invalid_input(e.to_string()))?;
+    policy.grant(&path);
+    Ok(Some(path.to_string_lossy().to_string()))
+}
+
+/// 檢查位置是否可連線、可寫入、是否為網路資料夾，讓前端在開始處理前提示使用者
+#[command]
+pub fn check_locations(paths: Vec<String>) -> Vec<LocationStatus> {
+    paths.iter().map(|p| volume::check(Path::new(p))).collect()
+}
+
+/// 將輸入檔複製到本機工作資料夾 (app data 底下)，回傳複製後的路徑
+/// 複製後的檔案可由檔案命令讀取，所以來源也須在允許存取的範圍內
+#[command]
+pub async fn copy_inputs_to_local(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    file_paths: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    for path in &file_paths {
+        checked_path(&app, &policy, path)?;
+    }
+    let workdir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .join(volume::WORKDIR_NAME);
+    tauri::async_runtime::spawn_blocking(move || volume::copy_to_workdir(&workdir, &file_paths))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+}
+
+/// 刪除檔案 (預設移到回收筒，to_trash 為 false 時直接刪除)
+#[command]
+pub async fn delete_files(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    paths: Vec<String>,
+    to_trash: Option<bool>,
+) -> Result<DeleteResult, AppError> {
+    let files = paths
+        .iter()
+        .map(|p| checked_path(&app, &policy, p))
+        .collect::<Result<Vec<_>, _>>()?;
+    viewer::ensure_all_writable(&files)?;
+    let to_trash = to_trash.unwrap_or(true);
+    let started = Instant::now();
+    let result =
+        tauri::async_runtime::spawn_blocking(move || trash::delete_files(&files, to_trash))
+            .await
+            .map_err(|e| AppError::internal(e.to_string()))?;
+
+    let deleted: Vec<PathBuf> = result.deleted.iter().map(PathBuf::from).collect();
+    if let Some(root) = deleted.first().and_then(|p| history::project_root_for(p)) {
+        history::record_command(
+            &root,
+            "delete_files",
+            json!({ "to_trash": to_trash, "failed": result.failed }),
+            &deleted,
+            started,
+            &Ok::<(), AppError>(()),
+        );
+    }
+    Ok(result)
+}
+
+/// 列出回收筒中原本位於 project 之下的項目
+#[command]
+pub async fn list_trash(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    project: String,
+) -> Result<Vec<TrashedFile>, AppError> {
+    let root = checked_path(&app, &policy, &project)?;
+    tauri::async_runtime::spawn_blocking(move || trash::list_trash(&root))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::unsupported)
+}
+
+/// 從回收筒還原原本位於 project 之下的項目，回傳還原後的路徑
+#[command]
+pub async fn restore_from_trash(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    project: String,
+    ids: Vec<String>,
+) -> Result<Vec<String>, AppError> {
+    if !trash::RESTORE_SUPPORTED {
+        return Err(AppError::localized(
+            ErrorKind::Unsupported,
+            "error.trash_restore_unsupported",
+            &[],
+        ));
+    }
+    let root = checked_path(&app, &policy, &project)?;
+    viewer::ensure_writable(&root)?;
+    tauri::async_runtime::spawn_blocking(move || trash::restore_from_trash(&root, &ids))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(value: Value, schema: Value) -> Vec<String> {
+        let mut errors = Vec::new();
+        schema_errors(&value, &schema, "$", &mut errors);
+        errors
+    }
+
+    #[test]
+    fn type_mismatch_is_reported_and_stops_descent() {
+        let schema = json!({ "type": "object", "required": ["name"] });
+        let found = errors(json!([1, 2]), schema);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].starts_with("$: "));
+
+        assert!(errors(json!(3), json!({ "type": ["string", "integer"] })).is_empty());
+        assert_eq!(errors(json!(1.5), json!({ "type": "integer" })).len(), 1);
+    }
+
+    #[test]
+    fn missing_required_fields_are_listed() {
+        let schema = json!({ "type": "object", "required": ["name", "segments"] });
+        let found = errors(json!({ "name": "a" }), schema);
+        assert_eq!(found.len(), 1);
+        assert!(found[0].contains("'segments'"));
+    }
+
+    #[test]
+    fn values_outside_enum_are_rejected() {
+        let schema = json!({ "enum": ["zh", "en"] });
+        assert!(errors(json!("zh"), schema.clone()).is_empty());
+        assert_eq!(errors(json!("ja"), schema).len(), 1);
+    }
+
+    #[test]
+    fn nested_properties_and_items_report_their_path() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "segments": {
+                    "type": "array",
+                    "items": {
+                        "type": "object",
+                        "required": ["start"],
+                        "properties": { "start": { "type": "number" } }
+                    }
+                }
+            }
+        });
+        let value = json!({ "segments": [{ "start": 1.0 }, { "start": "x" }, {}] });
+        let found = errors(value, schema);
+        assert_eq!(found.len(), 2);
+        assert!(found[0].starts_with("$.segments[1].start: "));
+        assert!(found[1].starts_with("$.segments[2]: "));
+    }
+
+    #[test]
+    fn validate_json_collects_every_error() {
+        let schema = json!({ "type": "object", "required": ["a", "b"] });
+        let err = validate_json("x.json", &json!({}), &schema).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::InvalidInput);
+        assert_eq!(err.params.get("count").map(String::as_str), Some("2"));
+        assert!(validate_json("x.json", &json!({ "a": 1, "b": 2 }), &schema).is_ok());
+    }
+
+    #[test]
+    fn append_writes_after_existing_content() {
+        let dir = std::env::temp_dir().join(format!("file_cmd_append_{}", std::process::id()));
+        let file = dir.join("notes.txt");
+        write_checked(&file, b"first\n", true).unwrap();
+        write_checked(&file, b"second\n", true).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "first\nsecond\n");
+        write_checked(&file, b"reset", false).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "reset");
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
@@ -0,0 +1,100 @@
+// src-tauri/src/commands/job_cmd.rs
+//
+// Tauri commands for the background job queue
+
+use crate::models::AppError;
+use crate::services::access::AccessPolicy;
+use crate::services::history::{self, HistoryEntry};
+use crate::services::jobs::{Job, JobManager, JobSpec, QueueStatus};
+use serde_json::Value;
+use std::path::Path;
+use tauri::{command, AppHandle, State};
+
+/// 加入背景工作，立即回傳工作資訊 (進度透過 `job://event` 通知)
+/// 工作的輸出位置必須在允許存取的範圍內
+#[command]
+pub fn enqueue_job(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    spec: JobSpec,
+    priority: Option<i32>,
+    jobs: State<'_, JobManager>,
+) -> Result<Job, AppError> {
+    for path in spec.output_paths() {
+        policy
+            .check(&app, Path::new(path))
+            .map_err(AppError::permission_denied)?;
+    }
+    Ok(jobs.enqueue(spec, priority.unwrap_or(0)))
+}
+
+/// 列出所有工作 (排隊中、執行中與最近結束的)
+#[command]
+pub fn list_jobs(jobs: State<'_, JobManager>) -> Vec<Job> {
+    jobs.list()
+}
+
+/// 取消工作
+#[command]
+pub fn cancel_job(id: String, jobs: State<'_, JobManager>) -> Result<(), AppError> {
+    jobs.cancel(&id)
+}
+
+/// 暫停佇列 (例如要開視訊會議時)：不再開始新工作，執行中的 FFmpeg 暫停，工作不會被取消
+#[command]
+pub fn pause_queue(jobs: State<'_, JobManager>) -> QueueStatus {
+    jobs.pause()
+}
+
+/// 繼續佇列
+#[command]
+pub fn resume_queue(jobs: State<'_, JobManager>) -> QueueStatus {
+    jobs.resume()
+}
+
+/// 取得佇列狀態 (是否暫停、執行中與排隊中的數量)
+#[command]
+pub fn get_queue_status(jobs: State<'_, JobManager>) -> QueueStatus {
+    jobs.queue_status()
+}
+
+/// 以相同參數重新執行工作 (佇列中已清除時從 project 的操作紀錄讀取)
+/// 報告類工作的 API Key 不會保存，需要重新提供
+#[command]
+pub fn rerun_job(
+    id: String,
+    project: Option<String>,
+    api_key: Option<String>,
+    priority: Option<i32>,
+    jobs: State<'_, JobManager>,
+) -> Result<Job, AppError> {
+    jobs.rerun(
+        &id,
+        project.as_deref().map(Path::new),
+        api_key,
+        priority.unwrap_or(0),
+    )
+}
+
+/// 專案中背景工作的紀錄 (新到舊)：類型、參數、輸出、耗時與錯誤
+#[command]
+pub fn get_job_history(
+    project: String,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    history::load_jobs(Path::new(&project), limit).map_err(AppError::io)
+}
+
+/// 清除已結束的工作紀錄
+#[command]
+pub fn clear_finished_jobs(jobs: State<'_, JobManager>) {
+    jobs.clear_finished();
+}
+
+/// 將工作結果轉為命令回傳的文字訊息
+pub(crate) fn job_result_string(result: Result<Value, AppError>) -> Result<String, AppError> {
+    result.map(|value| match value {
+        Value::String(s) => s,
+        other => other.to_string(),
+    })
+}
@@ -0,0 +1,43 @@
+// src-tauri/src/commands/log_cmd.rs
+//
+// Tauri commands for reading application logs
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::{diagnostics, logging};
+use tauri::command;
+
+/// 預設回傳的日誌行數
+const DEFAULT_TAIL_LINES: usize = 200;
+
+/// 取得最新日誌檔的最後幾行
+#[command]
+pub fn get_log_tail(lines: Option<usize>) -> Result<Vec<String>, AppError> {
+    logging::tail(lines.unwrap_or(DEFAULT_TAIL_LINES)).map_err(AppError::io)
+}
+
+/// 匯出診斷壓縮檔 (當機報告、日誌、去除機密的設定)，回傳壓縮檔路徑
+/// 未指定 output_path 時存到下載資料夾
+#[command]
+pub fn export_diagnostics_bundle(output_path: Option<String>) -> Result<String, AppError> {
+    let path = output_path
+        .map(std::path::PathBuf::from)
+        .unwrap_or_else(diagnostics::default_bundle_path);
+    diagnostics::export_bundle(&path)
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(AppError::io)
+}
+
+/// 以系統檔案管理員開啟日誌資料夾
+#[command]
+pub fn open_log_folder() -> Result<(), AppError> {
+    let dir = logging::log_dir().ok_or_else(|| {
+        AppError::localized(ErrorKind::NotFound, "error.log_not_initialized", &[])
+    })?;
+    tauri_plugin_opener::open_path(dir, None::<&str>).map_err(|e| {
+        AppError::localized(
+            ErrorKind::Io,
+            "error.open_log_folder_failed",
+            &[("detail", e.to_string())],
+        )
+    })
+}
@@ -1,7 +1,16 @@
+pub mod annotation_cmd;
 pub mod app_cmd;
 pub mod audio_cmd;
+pub mod dependency_cmd;
 pub mod file_cmd;
+pub mod job_cmd;
+pub mod log_cmd;
+pub mod model_cmd;
+pub mod pipeline_cmd;
 pub mod player_cmd;
 pub mod project_cmd;
+pub mod recorder_cmd;
 pub mod report_cmd;
+pub mod settings_cmd;
 pub mod silence_cmd;
+pub mod transcript_cmd;
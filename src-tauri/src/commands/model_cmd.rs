@@ -0,0 +1,36 @@
+// src-tauri/src/commands/model_cmd.rs
+//
+// Tauri commands for local STT / VAD model files
+
+use crate::models::AppError;
+use crate::services::stt_models::{self, ModelDownloads, ModelInfo};
+use tauri::{command, AppHandle, State};
+
+/// 列出可下載的模型與安裝狀態
+#[command]
+pub fn list_models(
+    app: AppHandle,
+    downloads: State<'_, ModelDownloads>,
+) -> Result<Vec<ModelInfo>, AppError> {
+    stt_models::list(&app, &downloads)
+}
+
+/// 下載模型到 app data 的 models/ 並驗證 SHA-256 (進度透過 `models://progress` 通知)
+#[command]
+pub async fn download_model(
+    app: AppHandle,
+    downloads: State<'_, ModelDownloads>,
+    id: String,
+) -> Result<String, AppError> {
+    let path = stt_models::download(&app, &downloads, &id).await?;
+    Ok(path.to_string_lossy().to_string())
+}
+
+#[command]
+pub fn delete_model(
+    app: AppHandle,
+    downloads: State<'_, ModelDownloads>,
+    id: String,
+) -> Result<(), AppError> {
+    stt_models::delete(&app, &downloads, &id)
+}
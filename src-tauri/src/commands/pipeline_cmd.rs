@@ -0,0 +1,163 @@
+// src-tauri/src/commands/pipeline_cmd.rs
+//
+// Tauri commands for the one-click pipeline (convert → split → transcribe → redact → report)
+
+use crate::commands::job_cmd::job_result_string;
+use crate::models::{AppError, ErrorKind};
+use crate::services::file_manager::{current_project, CurrentProjectState, ProjectPaths};
+use crate::services::jobs::{JobManager, JobSpec};
+use crate::services::pipeline::{
+    self, DefinitionFormat, DefinitionScope, PipelineDefinition, PipelineEntry, PipelineOptions,
+    PipelineStage, PipelineState,
+};
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, State, Window};
+
+/// 一鍵執行完整流程，進度以 `pipeline://progress` 通知
+/// 專案為呼叫端視窗開啟的專案，沒有時依第一個檔案建立；同一專案以相同輸入再次執行時從中斷處接續
+#[command]
+pub async fn run_pipeline(
+    window: Window,
+    projects: State<'_, CurrentProjectState>,
+    jobs: State<'_, JobManager>,
+    files: Vec<String>,
+    options: Option<PipelineOptions>,
+    api_key: Option<String>,
+) -> Result<String, AppError> {
+    let project_root = current_project(&projects, window.label());
+    enqueue_pipeline(
+        &jobs,
+        project_root,
+        files,
+        options.unwrap_or_default(),
+        api_key.unwrap_or_default(),
+    )
+    .await
+}
+
+/// 以流程定義檔的設定執行 (專案的定義優先於全域的同名定義)
+#[command]
+pub async fn run_named_pipeline(
+    app: AppHandle,
+    window: Window,
+    projects: State<'_, CurrentProjectState>,
+    jobs: State<'_, JobManager>,
+    name: String,
+    files: Vec<String>,
+    api_key: Option<String>,
+) -> Result<String, AppError> {
+    let project_root = current_project(&projects, window.label());
+    let options = pipeline::resolve_definition(&app, project_root.as_deref(), &name)?;
+    enqueue_pipeline(
+        &jobs,
+        project_root,
+        files,
+        options,
+        api_key.unwrap_or_default(),
+    )
+    .await
+}
+
+async fn enqueue_pipeline(
+    jobs: &JobManager,
+    project_root: Option<PathBuf>,
+    files: Vec<String>,
+    options: PipelineOptions,
+    api_key: String,
+) -> Result<String, AppError> {
+    if files.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_files_selected",
+            &[],
+        ));
+    }
+    if PipelineStage::plan(&options).contains(&PipelineStage::Report) && api_key.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.missing_api_key",
+            &[],
+        ));
+    }
+
+    let project_root = match project_root {
+        Some(root) => root,
+        None => ProjectPaths::new(&files[0]).map_err(AppError::io)?.root,
+    };
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Pipeline {
+                file_paths: files,
+                project_root: project_root.to_string_lossy().to_string(),
+                options,
+                api_key,
+            },
+            0,
+        )
+        .await,
+    )
+}
+
+/// 列出可用的流程定義 (全域 + 呼叫端視窗開啟的專案)
+#[command]
+pub fn list_pipelines(
+    app: AppHandle,
+    window: Window,
+    projects: State<'_, CurrentProjectState>,
+) -> Vec<PipelineEntry> {
+    let project_root = current_project(&projects, window.label());
+    pipeline::list_definitions(&app, project_root.as_deref())
+}
+
+/// 保存流程定義到全域或目前的專案，回傳檔案路徑
+#[command]
+pub fn save_pipeline(
+    app: AppHandle,
+    window: Window,
+    projects: State<'_, CurrentProjectState>,
+    definition: PipelineDefinition,
+    scope: DefinitionScope,
+    format: Option<DefinitionFormat>,
+) -> Result<String, AppError> {
+    let project_root = current_project(&projects, window.label());
+    pipeline::save_definition(
+        &app,
+        scope,
+        project_root.as_deref(),
+        &definition,
+        format.unwrap_or_default(),
+    )
+    .map(|path| path.to_string_lossy().to_string())
+}
+
+/// 取得專案最近一次流程的狀態
+#[command]
+pub fn get_pipeline_state(project: String) -> Option<PipelineState> {
+    pipeline::load(Path::new(&project))
+}
+
+/// 以上次的輸入與選項接續未完成的流程
+#[command]
+pub async fn resume_pipeline(
+    jobs: State<'_, JobManager>,
+    project: String,
+    api_key: Option<String>,
+) -> Result<String, AppError> {
+    let state = pipeline::load(Path::new(&project))
+        .filter(|state| !state.is_finished())
+        .ok_or_else(|| {
+            AppError::localized(ErrorKind::NotFound, "error.pipeline_nothing_to_resume", &[])
+        })?;
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Pipeline {
+                file_paths: state.inputs,
+                project_root: project,
+                options: state.options,
+                api_key: api_key.unwrap_or_default(),
+            },
+            0,
+        )
+        .await,
+    )
+}
@@ -2,116 +2,450 @@
 //
 // Tauri commands for audio player control
 
-use crate::services::audio_player::AudioPlayer;
+use crate::commands::job_cmd::job_result_string;
+use crate::models::{AppError, ErrorKind};
+use crate::services::audio_player::{self, AudioPlayer, PlayerState, TrackInfo};
+use crate::services::encryption;
+use crate::services::jobs::{JobManager, JobSpec};
+use crate::services::player_monitor::{self, OutputConfig, OutputDevice, OutputTarget};
+use crate::services::sidecar;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
-use tauri::{command, State};
+use tauri::{command, AppHandle, Emitter, Manager, State};
 
-/// State type for the audio player
-pub type AudioPlayerState = Mutex<Option<AudioPlayer>>;
+/// Emitted while a file that is still being written grows
+pub const DURATION_UPDATED_EVENT: &str = "player://duration-updated";
 
-/// Load an audio track
+/// Payload of DURATION_UPDATED_EVENT
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct DurationUpdate {
+    pub path: String,
+    pub duration: f64,
+    /// False once the file has stopped growing
+    pub growing: bool,
+}
+
+/// State type for the audio player.
+/// An async lock: loading a track happens outside it, so a slow load never blocks
+/// the other player commands.
+pub type AudioPlayerState = tokio::sync::Mutex<Option<AudioPlayer>>;
+
+/// Incremented by every load; a load that finishes after a newer one started is discarded
+static LOAD_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Output settings (monitor device, volumes), applied to every loaded track
+pub type OutputState = Mutex<OutputConfig>;
+
+/// State type for the before/after compare mode
+pub type CompareState = Mutex<Option<CompareTracks>>;
+
+/// Which side of the compare pair is playing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompareSide {
+    Original,
+    Processed,
+}
+
+/// Original and processed versions of the same recording
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct CompareTracks {
+    pub original: String,
+    pub processed: String,
+    pub active: CompareSide,
+}
+
+/// Encrypted project files are decrypted to the scratch dir first
+fn playable_path(app: &AppHandle, path: String) -> Result<String, AppError> {
+    if encryption::is_encrypted_file(Path::new(&path)) {
+        Ok(encryption::open_file(app, Path::new(&path))?
+            .to_string_lossy()
+            .to_string())
+    } else {
+        Ok(path)
+    }
+}
+
+/// Load a player off the async runtime, decoding with FFmpeg when symphonia can't handle the format
+async fn load_player(app: &AppHandle, path: &str) -> Result<AudioPlayer, AppError> {
+    let path = path.to_string();
+    let ffmpeg = sidecar::ffmpeg_executable(app);
+    tauri::async_runtime::spawn_blocking(move || AudioPlayer::load_with_fallback(&path, ffmpeg))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::io)
+}
+
+/// Drop a replaced player off the async runtime (unloading joins its threads)
+fn release_player(player: Option<AudioPlayer>) {
+    if let Some(player) = player {
+        tauri::async_runtime::spawn_blocking(move || drop(player));
+    }
+}
+
+fn no_track_loaded() -> AppError {
+    AppError::localized(ErrorKind::InvalidInput, "error.no_track_loaded", &[])
+}
+
+/// Start a load and return the generation it must still hold to install.
+/// The current player is left alone, so a failed load keeps the previous track
+fn begin_load() -> u64 {
+    LOAD_GENERATION.fetch_add(1, Ordering::SeqCst) + 1
+}
+
+/// Swap in a loaded player unless a newer load has started meanwhile
+async fn install_player(
+    app: &AppHandle,
+    player_state: &AudioPlayerState,
+    generation: u64,
+    mut player: AudioPlayer,
+) -> Result<f64, AppError> {
+    let mut player_guard = player_state.lock().await;
+    if LOAD_GENERATION.load(Ordering::SeqCst) != generation {
+        release_player(Some(player));
+        return Err(AppError::cancelled());
+    }
+    // Read the output settings here rather than at load time, so a change made while
+    // the track was loading is not lost
+    if let Ok(outputs) = app.state::<OutputState>().lock() {
+        player.set_outputs(&outputs);
+    }
+    let duration = player.get_duration();
+    release_player(player_guard.replace(player));
+    Ok(duration)
+}
+
+/// Follow the loaded file if it is still being written (ongoing recording or conversion)
+fn watch_if_growing(app: &AppHandle, player: &mut AudioPlayer, path: &str) {
+    if !audio_player::is_growing_file(Path::new(path)) {
+        return;
+    }
+    tracing::info!("Following growing file: {}", path);
+    let app = app.clone();
+    let path = path.to_string();
+    player.watch_growth(move |duration, growing| {
+        let _ = app.emit(
+            DURATION_UPDATED_EVENT,
+            DurationUpdate {
+                path: path.clone(),
+                duration,
+                growing,
+            },
+        );
+    });
+}
+
+/// Load an audio track (leaves compare mode)
 #[command]
-pub fn load_track(
+pub async fn load_track(
+    app: AppHandle,
     path: String,
     player_state: State<'_, AudioPlayerState>,
-) -> Result<String, String> {
-    let mut player_guard = player_state
+    compare_state: State<'_, CompareState>,
+) -> Result<String, AppError> {
+    let path = playable_path(&app, path)?;
+    let generation = begin_load();
+    let mut player = load_player(&app, &path).await?;
+    watch_if_growing(&app, &mut player, &path);
+    let duration = install_player(&app, &player_state, generation, player).await?;
+    if let Ok(mut compare) = compare_state.lock() {
+        *compare = None;
+    }
+    Ok(format!("{:.2}", duration))
+}
+
+/// Enter compare mode: load the original and remember the processed version
+#[command]
+pub async fn start_compare(
+    app: AppHandle,
+    original: String,
+    processed: String,
+    player_state: State<'_, AudioPlayerState>,
+    compare_state: State<'_, CompareState>,
+) -> Result<String, AppError> {
+    let path = playable_path(&app, original.clone())?;
+    let generation = begin_load();
+    let player = load_player(&app, &path).await?;
+    let duration = install_player(&app, &player_state, generation, player).await?;
+    let mut compare = compare_state
         .lock()
-        .map_err(|_| "無法取得播放器鎖定".to_string())?;
+        .map_err(|_| AppError::localized(ErrorKind::Internal, "error.player_busy", &[]))?;
+    *compare = Some(CompareTracks {
+        original,
+        processed,
+        active: CompareSide::Original,
+    });
+    Ok(format!("{:.2}", duration))
+}
 
-    // Stop existing player if any
-    if let Some(ref mut existing) = *player_guard {
-        existing.stop();
+/// Switch between original and processed, keeping position and play/pause state
+#[command]
+pub async fn switch_compare(
+    app: AppHandle,
+    side: CompareSide,
+    player_state: State<'_, AudioPlayerState>,
+    compare_state: State<'_, CompareState>,
+) -> Result<CompareTracks, AppError> {
+    let compare = compare_state
+        .lock()
+        .map_err(|_| AppError::localized(ErrorKind::Internal, "error.player_busy", &[]))?
+        .clone();
+    let Some(compare) = compare else {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.compare_not_started",
+            &[],
+        ));
+    };
+    if compare.active == side {
+        return Ok(compare);
     }
+    let path = match side {
+        CompareSide::Original => compare.original.clone(),
+        CompareSide::Processed => compare.processed.clone(),
+    };
+    let path = playable_path(&app, path)?;
 
-    // Load new track
-    let player = AudioPlayer::load(&path)?;
-    let duration = player.get_duration();
-    *player_guard = Some(player);
+    // The current side keeps playing while the other one loads; position and
+    // play/pause state are taken at the moment of the swap
+    let generation = begin_load();
+    let mut player = load_player(&app, &path).await?;
+    let mut player_guard = player_state.lock().await;
+    if LOAD_GENERATION.load(Ordering::SeqCst) != generation {
+        release_player(Some(player));
+        return Err(AppError::cancelled());
+    }
+    let (position, was_playing) = player_guard
+        .as_ref()
+        .map(|p| (p.get_position(), p.is_playing()))
+        .unwrap_or((0.0, false));
+    if let Ok(outputs) = app.state::<OutputState>().lock() {
+        player.set_outputs(&outputs);
+    }
+    if was_playing || position > 0.0 {
+        player.start_playback()?;
+        player.seek(position);
+        if !was_playing {
+            player.pause();
+        }
+    }
+    release_player(player_guard.replace(player));
+    drop(player_guard);
 
-    Ok(format!("{:.2}", duration))
+    let mut compare_guard = compare_state
+        .lock()
+        .map_err(|_| AppError::localized(ErrorKind::Internal, "error.player_busy", &[]))?;
+    let Some(compare) = compare_guard.as_mut() else {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.compare_not_started",
+            &[],
+        ));
+    };
+    compare.active = side;
+    Ok(compare.clone())
 }
 
 /// Start playback
 #[command]
-pub fn play(player_state: State<'_, AudioPlayerState>) -> Result<(), String> {
-    let mut player_guard = player_state
-        .lock()
-        .map_err(|_| "無法取得播放器鎖定".to_string())?;
-
-    if let Some(ref mut player) = *player_guard {
-        // Check if playback pipeline is started
-        if !player.is_playing() && player.get_position() == 0.0 {
-            // First time playing - start the pipeline
-            player.start_playback()?;
-        } else {
-            player.play();
-        }
-        Ok(())
+pub async fn play(player_state: State<'_, AudioPlayerState>) -> Result<(), AppError> {
+    let mut player_guard = player_state.lock().await;
+    let player = player_guard.as_mut().ok_or_else(no_track_loaded)?;
+    // Check if playback pipeline is started
+    if !player.is_started() {
+        // First time playing - start the pipeline
+        player.start_playback()?;
     } else {
-        Err("尚未載入音訊檔案".to_string())
+        player.play();
     }
+    Ok(())
 }
 
 /// Pause playback
 #[command]
-pub fn pause(player_state: State<'_, AudioPlayerState>) -> Result<(), String> {
-    let player_guard = player_state
-        .lock()
-        .map_err(|_| "無法取得播放器鎖定".to_string())?;
-
-    if let Some(ref player) = *player_guard {
-        player.pause();
-        Ok(())
-    } else {
-        Err("尚未載入音訊檔案".to_string())
-    }
+pub async fn pause(player_state: State<'_, AudioPlayerState>) -> Result<(), AppError> {
+    let player_guard = player_state.lock().await;
+    player_guard.as_ref().ok_or_else(no_track_loaded)?.pause();
+    Ok(())
 }
 
 /// Seek to a specific position in seconds
 /// This immediately clears the ringbuf and notifies the decoder to seek
 #[command]
-pub fn seek(seconds: f64, player_state: State<'_, AudioPlayerState>) -> Result<(), String> {
-    let player_guard = player_state
-        .lock()
-        .map_err(|_| "無法取得播放器鎖定".to_string())?;
-
-    if let Some(ref player) = *player_guard {
-        player.seek(seconds);
-        Ok(())
-    } else {
-        Err("尚未載入音訊檔案".to_string())
-    }
+pub async fn seek(seconds: f64, player_state: State<'_, AudioPlayerState>) -> Result<(), AppError> {
+    let player_guard = player_state.lock().await;
+    player_guard
+        .as_ref()
+        .ok_or_else(no_track_loaded)?
+        .seek(seconds);
+    Ok(())
 }
 
 /// Get current playback state (position, duration, is_playing)
 #[command]
-pub fn get_playback_state(
+pub async fn get_playback_state(
     player_state: State<'_, AudioPlayerState>,
-) -> Result<PlaybackState, String> {
-    let player_guard = player_state
-        .lock()
-        .map_err(|_| "無法取得播放器鎖定".to_string())?;
-
-    if let Some(ref player) = *player_guard {
-        Ok(PlaybackState {
-            position: player.get_position(),
-            duration: player.get_duration(),
-            is_playing: player.is_playing(),
-        })
-    } else {
-        Ok(PlaybackState {
+) -> Result<PlaybackState, AppError> {
+    let player_guard = player_state.lock().await;
+    Ok(match player_guard.as_ref() {
+        Some(player) => PlaybackState::of(player),
+        None => PlaybackState {
             position: 0.0,
+            position_ms: 0,
+            buffered_ms: 0,
             duration: 0.0,
             is_playing: false,
-        })
+            state: PlayerState::Idle,
+        },
+    })
+}
+
+/// Pause and step by `frames` (negative steps back), one frame = STEP_FRAME_MS
+#[command]
+pub async fn step_frames(
+    frames: i64,
+    player_state: State<'_, AudioPlayerState>,
+) -> Result<PlaybackState, AppError> {
+    let player_guard = player_state.lock().await;
+    let player = player_guard.as_ref().ok_or_else(no_track_loaded)?;
+    player.step_frames(frames);
+    Ok(PlaybackState::of(player))
+}
+
+/// Export start..end (seconds) of the loaded track to dest via the Splitter
+#[command]
+pub async fn export_selection(
+    start: f64,
+    end: f64,
+    dest: String,
+    player_state: State<'_, AudioPlayerState>,
+    jobs: State<'_, JobManager>,
+) -> Result<String, AppError> {
+    let input_path = {
+        let player_guard = player_state.lock().await;
+        let player = player_guard.as_ref().ok_or_else(no_track_loaded)?;
+        let duration = player.get_duration();
+        if !(start >= 0.0 && end > start && (duration <= 0.0 || start < duration)) {
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.invalid_selection",
+                &[
+                    ("start", format!("{:.3}", start)),
+                    ("end", format!("{:.3}", end)),
+                ],
+            ));
+        }
+        player.file_path().to_string_lossy().to_string()
+    };
+
+    let spec = JobSpec::ExportRange {
+        input_path,
+        output_path: dest,
+        start,
+        end,
+    };
+    job_result_string(jobs.enqueue_and_wait(spec, 0).await)
+}
+
+/// List output devices for the monitor output
+#[command]
+pub async fn list_output_devices() -> Result<Vec<OutputDevice>, AppError> {
+    tauri::async_runtime::spawn_blocking(player_monitor::list_output_devices)
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::tool)
+}
+
+/// Current output settings
+#[command]
+pub async fn get_output_config(
+    output_state: State<'_, OutputState>,
+) -> Result<OutputConfig, AppError> {
+    output_state
+        .lock()
+        .map(|outputs| outputs.clone())
+        .map_err(|_| AppError::localized(ErrorKind::Internal, "error.player_busy", &[]))
+}
+
+/// Also play to a second device (None turns the monitor off)
+#[command]
+pub async fn set_monitor_output(
+    device: Option<String>,
+    output_state: State<'_, OutputState>,
+    player_state: State<'_, AudioPlayerState>,
+) -> Result<OutputConfig, AppError> {
+    update_outputs(&output_state, &player_state, |outputs| {
+        outputs.monitor_device = device;
+    })
+    .await
+}
+
+/// Set the volume of the main or monitor output independently
+#[command]
+pub async fn set_output_volume(
+    target: OutputTarget,
+    volume: f32,
+    output_state: State<'_, OutputState>,
+    player_state: State<'_, AudioPlayerState>,
+) -> Result<OutputConfig, AppError> {
+    update_outputs(&output_state, &player_state, |outputs| match target {
+        OutputTarget::Main => outputs.main_volume = volume.clamp(0.0, 2.0),
+        OutputTarget::Monitor => outputs.monitor_volume = volume.clamp(0.0, 2.0),
+    })
+    .await
+}
+
+/// Change the output settings and apply them to the loaded player
+async fn update_outputs(
+    output_state: &OutputState,
+    player_state: &AudioPlayerState,
+    change: impl FnOnce(&mut OutputConfig),
+) -> Result<OutputConfig, AppError> {
+    // Player lock first: the output lock is synchronous and must not be held across an await
+    let mut player_guard = player_state.lock().await;
+    let mut outputs = output_state
+        .lock()
+        .map_err(|_| AppError::localized(ErrorKind::Internal, "error.player_busy", &[]))?;
+    change(&mut outputs);
+    if let Some(player) = player_guard.as_mut() {
+        player.set_outputs(&outputs);
     }
+    Ok(outputs.clone())
+}
+
+/// Format details of the loaded track, or None when nothing is loaded
+#[command]
+pub async fn get_loaded_track_info(
+    player_state: State<'_, AudioPlayerState>,
+) -> Result<Option<TrackInfo>, AppError> {
+    let player_guard = player_state.lock().await;
+    Ok(player_guard.as_ref().map(|player| player.track_info()))
 }
 
 /// Playback state returned to the frontend
 #[derive(serde::Serialize)]
 pub struct PlaybackState {
     pub position: f64,
+    /// Audible position with millisecond precision
+    pub position_ms: u64,
+    /// Decoded ahead of the audible position (queued in the ring buffer), in milliseconds
+    pub buffered_ms: u64,
     pub duration: f64,
     pub is_playing: bool,
+    pub state: PlayerState,
+}
+
+impl PlaybackState {
+    fn of(player: &AudioPlayer) -> Self {
+        let position_ms = player.get_position_ms();
+        Self {
+            position: position_ms as f64 / 1000.0,
+            position_ms,
+            buffered_ms: player.get_buffered_ms(),
+            duration: player.get_duration(),
+            is_playing: player.is_playing(),
+            state: player.state(),
+        }
+    }
 }
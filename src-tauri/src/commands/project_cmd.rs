@@ -1,50 +1,499 @@
-use crate::services::file_manager::{CurrentProjectState, ProjectPaths};
-use tauri::{command, AppHandle, WebviewUrl, WebviewWindowBuilder};
+use crate::models::{AppError, ErrorKind};
+use crate::services::access::AccessPolicy;
+use crate::services::autosave::{self, AutosaveFile, RunMarker};
+use crate::services::backup::{self, BackupInfo};
+use crate::services::encryption::{self, EncryptionKeys, EncryptionStatus};
+use crate::services::file_manager::{
+    self, current_project, set_current_project, validate_project as validate_project_dir,
+    ConflictPolicy, CurrentProjectState, ProjectPaths, PromoteResult, TransferMode,
+    ValidationReport,
+};
+use crate::services::fingerprint::{self, DuplicatePair};
+use crate::services::history::{self, ExportFormat, HistoryEntry};
+use crate::services::jobs::JobManager;
+use crate::services::launch::{LaunchRequest, PendingLaunch};
+use crate::services::manifest::{ProjectInfo, ProjectManifest};
+use crate::services::prefetch;
+use crate::services::project_stats::{self, ProjectStats};
+use crate::services::retention::{self, PurgePlan, PurgeResult, RetentionPolicy};
+use crate::services::search::{self, SearchHit};
+use crate::services::session::{self, SessionState, WindowSession};
+use crate::services::viewer;
+use crate::services::watcher::ProjectWatcherState;
+use serde_json::json;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{command, AppHandle, Emitter, Manager, Window};
+
+/// 專案必須位於新專案的上層資料夾、或使用者透過對話框授權的位置
+fn checked_project_path(
+    app: &AppHandle,
+    policy: &AccessPolicy,
+    path: &str,
+) -> Result<(), AppError> {
+    policy
+        .check_project(app, Path::new(path))
+        .map(|_| ())
+        .map_err(AppError::permission_denied)
+}
 
 #[command]
 pub fn create_project_cmd(
-    _app: AppHandle,
+    app: AppHandle,
+    window: Window,
     state: tauri::State<CurrentProjectState>,
+    watcher_state: tauri::State<ProjectWatcherState>,
+    policy: tauri::State<AccessPolicy>,
     path: String,
-) -> Result<String, String> {
-    let project_paths = ProjectPaths::create(&path).map_err(|e| e.to_string())?;
+) -> Result<String, AppError> {
+    let started = Instant::now();
+    checked_project_path(&app, &policy, &path)?;
+    let project_paths = ProjectPaths::create(&path).map_err(AppError::io)?;
+    history::record_command(
+        &project_paths.root,
+        "create_project",
+        json!({ "path": path }),
+        &[],
+        started,
+        &Ok::<(), AppError>(()),
+    );
 
-    // Update global state
-    let mut current_project = state.lock().map_err(|_| "Failed to lock state")?;
-    *current_project = Some(project_paths.root.clone());
+    // 只更新呼叫端視窗的專案狀態
+    set_current_project(&state, window.label(), project_paths.root.clone())?;
+    viewer::set_viewer(window.label(), &project_paths.root, false);
 
-    Ok(format!("專案建立成功: {}", project_paths.root.display()))
+    start_watching(&app, &watcher_state, window.label(), &project_paths);
+    remember_project(&app, window.label(), &project_paths);
+
+    Ok(crate::tr!(
+        "result.project_created",
+        path = project_paths.root.display()
+    ))
 }
 
+/// 開啟專案；read_only 為 true 時以檢視模式開啟 (不補建資料夾，所有修改都會被拒絕)
+/// 完整性檢查會計算轉檔輸出的雜湊值，在背景執行緒進行，大型專案開啟時不會卡住畫面
 #[command]
-pub fn open_project_cmd(
-    _app: AppHandle,
-    state: tauri::State<CurrentProjectState>,
+pub async fn open_project_cmd(
+    app: AppHandle,
+    window: Window,
+    state: tauri::State<'_, CurrentProjectState>,
+    watcher_state: tauri::State<'_, ProjectWatcherState>,
+    policy: tauri::State<'_, AccessPolicy>,
     path: String,
-) -> Result<String, String> {
+    read_only: Option<bool>,
+) -> Result<String, AppError> {
+    let read_only = read_only.unwrap_or(false);
+    // 開啟的專案會成為檔案命令的存取範圍，必須先確認是允許的位置
+    checked_project_path(&app, &policy, &path)?;
+    // 在補建資料夾之前先檢查專案完整性，結果以事件通知前端
+    let root = PathBuf::from(&path);
+    let validation = tauri::async_runtime::spawn_blocking(move || validate_project_dir(&root))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::not_found)?;
+    let _ = app.emit_to(window.label(), "project://validated", &validation);
+
     // Validate project structure by trying to instantiate ProjectPaths from the given root
-    let project_paths =
-        ProjectPaths::from_root(std::path::PathBuf::from(&path)).map_err(|e| e.to_string())?;
+    // 檢視模式不補建缺少的資料夾
+    let project_paths = if read_only {
+        ProjectPaths::from_existing_root(std::path::PathBuf::from(&path))
+    } else {
+        ProjectPaths::from_root(std::path::PathBuf::from(&path)).map_err(AppError::io)?
+    };
+
+    // 只更新呼叫端視窗的專案狀態
+    set_current_project(&state, window.label(), project_paths.root.clone())?;
+    viewer::set_viewer(window.label(), &project_paths.root, read_only);
+
+    start_watching(&app, &watcher_state, window.label(), &project_paths);
+    remember_project(&app, window.label(), &project_paths);
+    // 背景預先解碼段落開頭，點選播放時可立即出聲
+    prefetch::warm(&project_paths.root);
+
+    let opened = if read_only {
+        crate::tr!(
+            "result.project_opened_read_only",
+            path = project_paths.root.display()
+        )
+    } else {
+        crate::tr!("result.project_opened", path = project_paths.root.display())
+    };
+    if validation.is_ok() {
+        Ok(opened)
+    } else {
+        Ok(format!(
+            "{}\n\n{}",
+            opened,
+            crate::tr!("result.project_issues", summary = validation.summary())
+        ))
+    }
+}
+
+/// 檢查專案完整性 (資料夾、描述檔中的檔案與雜湊值)
+#[command]
+pub async fn validate_project(root: String) -> Result<ValidationReport, AppError> {
+    tauri::async_runtime::spawn_blocking(move || validate_project_dir(Path::new(&root)))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::not_found)
+}
+
+/// 開始監看專案資料夾；監看失敗不影響專案開啟，只記錄錯誤
+fn start_watching(
+    app: &AppHandle,
+    watcher_state: &ProjectWatcherState,
+    label: &str,
+    paths: &ProjectPaths,
+) {
+    if let Ok(mut watcher) = watcher_state.lock() {
+        if let Err(e) = watcher.watch(app, label, &paths.root) {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+/// 記錄視窗開啟的專案，下次啟動時恢復
+fn remember_project(app: &AppHandle, label: &str, paths: &ProjectPaths) {
+    if let Some(sessions) = app.try_state::<SessionState>() {
+        let root = paths.root.to_string_lossy().to_string();
+        sessions.update(label, |s| s.project_root = Some(root));
+    }
+}
+
+/// 取得呼叫端視窗上次的專案與頁面
+#[command]
+pub fn get_window_session(
+    window: Window,
+    sessions: tauri::State<SessionState>,
+) -> Option<WindowSession> {
+    sessions.get(window.label())
+}
 
-    // Update global state
-    let mut current_project = state.lock().map_err(|_| "Failed to lock state")?;
-    *current_project = Some(project_paths.root.clone());
+/// 記錄呼叫端視窗目前的頁面
+#[command]
+pub fn set_window_page(window: Window, sessions: tauri::State<SessionState>, page: String) {
+    sessions.update(window.label(), |s| s.page = Some(page));
+}
 
-    Ok(format!("專案開啟成功: {}", project_paths.root.display()))
+/// 取得從檔案總管或連結開啟時要載入的專案與檔案 (只回傳一次)
+#[command]
+pub fn take_launch_request(pending: tauri::State<PendingLaunch>) -> Option<LaunchRequest> {
+    pending.take()
 }
 
 #[command]
 pub fn get_current_project_cmd(
+    window: Window,
     state: tauri::State<CurrentProjectState>,
-) -> Result<Option<String>, String> {
-    let current_project = state.lock().map_err(|_| "Failed to lock state")?;
-    Ok(current_project
-        .as_ref()
-        .map(|p| p.to_string_lossy().to_string()))
+) -> Result<Option<String>, AppError> {
+    Ok(current_project(&state, window.label()).map(|p| p.to_string_lossy().to_string()))
+}
+
+/// 呼叫端視窗是否以檢視模式 (唯讀) 開啟專案
+#[command]
+pub fn is_viewer_mode(window: Window) -> bool {
+    viewer::is_viewer(window.label())
+}
+
+/// 列出專案備份 (新到舊)
+#[command]
+pub fn list_backups(root: String) -> Result<Vec<BackupInfo>, AppError> {
+    backup::list_backups(std::path::Path::new(&root)).map_err(AppError::io)
+}
+
+/// 將指定備份還原回專案，回傳被還原的檔案
+#[command]
+pub fn restore_backup(
+    app: AppHandle,
+    policy: tauri::State<AccessPolicy>,
+    root: String,
+    backup_id: String,
+) -> Result<Vec<String>, AppError> {
+    checked_project_path(&app, &policy, &root)?;
+    viewer::ensure_writable(Path::new(&root))?;
+    let started = Instant::now();
+    let result = backup::restore_backup(Path::new(&root), &backup_id).map_err(AppError::not_found);
+    let restored: Vec<PathBuf> = result
+        .iter()
+        .flatten()
+        .map(|file| Path::new(&root).join(file))
+        .collect();
+    history::record_command(
+        Path::new(&root),
+        "restore_backup",
+        json!({ "backup_id": backup_id }),
+        &restored,
+        started,
+        &result,
+    );
+    result
+}
+
+/// 在專案的逐字稿、報告與段落檔名中搜尋關鍵字
+#[command]
+pub fn search_project(root: String, query: String) -> Result<Vec<SearchHit>, AppError> {
+    search::search_project(std::path::Path::new(&root), &query).map_err(AppError::invalid_input)
+}
+
+/// 以音訊指紋找出專案內重複匯入的錄音 (指紋快取在專案內，只重算有變更的檔案)
+#[command]
+pub async fn find_duplicates(project: String) -> Result<Vec<DuplicatePair>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || fingerprint::find_duplicates(Path::new(&project)))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::io)
+}
+
+/// 專案統計：各階段音檔分鐘數、片段數、消音秒數、報告字數與 API 用量
+#[command]
+pub async fn get_project_stats(root: String) -> Result<ProjectStats, AppError> {
+    tauri::async_runtime::spawn_blocking(move || project_stats::collect(Path::new(&root)))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::not_found)
+}
+
+/// 讀取專案資訊 (報告提示詞的 {project_name}、{recording_date}、{department})
+#[command]
+pub fn get_project_info(root: String) -> Result<ProjectInfo, AppError> {
+    ProjectManifest::load(Path::new(&root))
+        .map(|m| m.info)
+        .map_err(AppError::io)
+}
+
+/// 更新專案資訊，寫入專案描述檔
+#[command]
+pub fn set_project_info(root: String, info: ProjectInfo) -> Result<ProjectInfo, AppError> {
+    let root = Path::new(&root);
+    viewer::ensure_writable(root)?;
+    ProjectManifest::update(root, |manifest| {
+        manifest.info = info;
+        Ok(manifest.info.clone())
+    })
+    .map_err(AppError::io)
 }
 
+/// 在專案階段之間複製或搬移檔案 (例如挑選 02_split 的檔案放入 03_silence 供報告使用)
+/// files 為空時處理來源階段的所有檔案
 #[command]
-pub async fn new_window_cmd(app: AppHandle) -> Result<(), String> {
+#[allow(clippy::too_many_arguments)]
+pub fn promote_files(
+    app: AppHandle,
+    window: Window,
+    project: String,
+    from_stage: String,
+    to_stage: String,
+    files: Vec<String>,
+    mode: Option<TransferMode>,
+    on_conflict: Option<ConflictPolicy>,
+) -> Result<PromoteResult, AppError> {
+    viewer::ensure_writable(Path::new(&project))?;
+    let started = Instant::now();
+    let paths = ProjectPaths::from_existing_root(PathBuf::from(&project));
+    let mode = mode.unwrap_or_default();
+    let on_conflict = on_conflict.unwrap_or_default();
+    let result =
+        file_manager::promote_files(&paths, &from_stage, &to_stage, &files, mode, on_conflict)
+            .map_err(AppError::invalid_input);
+    let transferred: Vec<PathBuf> = result
+        .iter()
+        .flat_map(|r| r.transferred.iter().map(PathBuf::from))
+        .collect();
+    history::record_command(
+        &paths.root,
+        "promote_files",
+        json!({
+            "from_stage": from_stage,
+            "to_stage": to_stage,
+            "files": files,
+            "mode": mode,
+            "on_conflict": on_conflict,
+        }),
+        &transferred,
+        started,
+        &result,
+    );
+
+    let result = result?;
+    let _ = app.emit_to(window.label(), "project://promoted", &result);
+    Ok(result)
+}
+
+/// 讀取專案操作紀錄 (新到舊)
+#[command]
+pub fn get_history(
+    app: AppHandle,
+    policy: tauri::State<AccessPolicy>,
+    root: String,
+    limit: Option<usize>,
+) -> Result<Vec<HistoryEntry>, AppError> {
+    checked_project_path(&app, &policy, &root)?;
+    history::load(Path::new(&root), limit).map_err(AppError::io)
+}
+
+/// 匯出專案操作紀錄 (jsonl 或 csv)，回傳輸出檔路徑
+#[command]
+pub fn export_history(
+    app: AppHandle,
+    policy: tauri::State<AccessPolicy>,
+    root: String,
+    output_path: String,
+    format: Option<ExportFormat>,
+) -> Result<String, AppError> {
+    checked_project_path(&app, &policy, &root)?;
+    policy
+        .check(&app, Path::new(&output_path))
+        .map_err(AppError::permission_denied)?;
+    viewer::ensure_writable(Path::new(&output_path))?;
+    history::export(
+        Path::new(&root),
+        Path::new(&output_path),
+        format.unwrap_or_default(),
+    )
+    .map(|p| p.to_string_lossy().to_string())
+    .map_err(AppError::io)
+}
+
+/// 啟用專案加密並加密 03_silence、04_report 內既有的檔案，回傳加密的檔案數
+#[command]
+pub async fn enable_project_encryption(
+    app: AppHandle,
+    project: String,
+    passphrase: String,
+) -> Result<usize, AppError> {
+    viewer::ensure_writable(Path::new(&project))?;
+    let started = Instant::now();
+    let root = PathBuf::from(&project);
+    let worker = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        encryption::enable(&worker.state::<EncryptionKeys>(), &root, &passphrase)
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?;
+    history::record_command(
+        Path::new(&project),
+        "enable_project_encryption",
+        json!({}),
+        &[],
+        started,
+        &result,
+    );
+    result
+}
+
+/// 以密碼解鎖加密專案 (金鑰只保存在記憶體)
+#[command]
+pub async fn unlock_project(
+    app: AppHandle,
+    project: String,
+    passphrase: String,
+) -> Result<(), AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        encryption::unlock(
+            &app.state::<EncryptionKeys>(),
+            Path::new(&project),
+            &passphrase,
+        )
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?
+}
+
+/// 鎖定專案並刪除暫存的解密檔
+#[command]
+pub fn lock_project(keys: tauri::State<EncryptionKeys>, project: String) {
+    encryption::lock(&keys, Path::new(&project));
+}
+
+#[command]
+pub fn get_encryption_status(
+    keys: tauri::State<EncryptionKeys>,
+    project: String,
+) -> EncryptionStatus {
+    encryption::status(&keys, Path::new(&project))
+}
+
+/// 自動保存頁面中尚未送出的狀態 (前端每隔幾秒呼叫)
+#[command]
+pub fn save_session_state(
+    project: String,
+    page: String,
+    state: serde_json::Value,
+) -> Result<(), AppError> {
+    // 檢視模式不寫入自動保存檔 (前端定時呼叫，直接略過而不回報錯誤)
+    if viewer::is_read_only(Path::new(&project)) {
+        return Ok(());
+    }
+    autosave::save(Path::new(&project), &page, state).map_err(AppError::io)
+}
+
+/// 上次異常結束時，回傳專案中可恢復的狀態
+#[command]
+pub fn get_recovery_state(
+    marker: tauri::State<RunMarker>,
+    project: String,
+) -> Option<AutosaveFile> {
+    autosave::recovery(&marker, Path::new(&project))
+}
+
+/// 送出或放棄編輯後清除自動保存 (未指定頁面時清除全部)
+#[command]
+pub fn clear_session_state(project: String, page: Option<String>) -> Result<(), AppError> {
+    if viewer::is_read_only(Path::new(&project)) {
+        return Ok(());
+    }
+    autosave::clear(Path::new(&project), page.as_deref()).map_err(AppError::io)
+}
+
+#[command]
+pub fn get_retention_policy(project: String) -> RetentionPolicy {
+    retention::load(Path::new(&project))
+}
+
+#[command]
+pub fn set_retention_policy(
+    app: AppHandle,
+    access: tauri::State<AccessPolicy>,
+    project: String,
+    policy: RetentionPolicy,
+) -> Result<(), AppError> {
+    checked_project_path(&app, &access, &project)?;
+    viewer::ensure_writable(Path::new(&project))?;
+    retention::save(Path::new(&project), &policy).map_err(AppError::invalid_input)
+}
+
+/// 列出依保存期限將被刪除的檔案 (刪除前報告)
+#[command]
+pub fn preview_retention_purge(project: String) -> Result<PurgePlan, AppError> {
+    retention::preview(Path::new(&project)).map_err(AppError::io)
+}
+
+/// 立即依保存期限刪除過期檔案 (專案有工作排隊或執行中時拒絕)
+#[command]
+pub async fn purge_project(
+    app: AppHandle,
+    policy: tauri::State<'_, AccessPolicy>,
+    jobs: tauri::State<'_, JobManager>,
+    project: String,
+) -> Result<PurgeResult, AppError> {
+    checked_project_path(&app, &policy, &project)?;
+    viewer::ensure_writable(Path::new(&project))?;
+    if jobs.has_active_jobs(Path::new(&project)) {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.retention_jobs_active",
+            &[],
+        ));
+    }
+    tauri::async_runtime::spawn_blocking(move || retention::purge(Path::new(&project)))
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::io)
+}
+
+#[command]
+pub async fn new_window_cmd(app: AppHandle) -> Result<(), AppError> {
     let label = format!(
         "window-{}",
         std::time::SystemTime::now()
@@ -53,15 +502,8 @@ pub async fn new_window_cmd(app: AppHandle) -> Result<(), String> {
             .as_millis()
     );
 
-    WebviewWindowBuilder::new(
-        &app,
-        label,
-        WebviewUrl::App("index.html?page=welcome".into()),
-    )
-    .title("STT Agent")
-    .inner_size(1280.0, 800.0)
-    .build()
-    .map_err(|e| format!("Failed to create window: {}", e))?;
+    session::create_window(&app, &label, false)
+        .map_err(|e| AppError::internal(format!("Failed to create window: {}", e)))?;
 
     Ok(())
 }
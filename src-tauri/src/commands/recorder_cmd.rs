@@ -0,0 +1,254 @@
+// src-tauri/src/commands/recorder_cmd.rs
+//
+// Tauri commands for microphone recording
+
+use crate::commands::player_cmd::AudioPlayerState;
+use crate::models::{AppError, ErrorKind};
+use crate::services::file_manager::{current_project, CurrentProjectState};
+use crate::services::loopback;
+use crate::services::manifest::DictationRecord;
+use crate::services::notes::{self, NoteEntry, NoteState};
+use crate::services::overdub::{self, Overdub, OverdubState, OverdubStatus};
+use crate::services::recorder::{
+    self, InputDevice, RecorderState, Recording, RecordingOptions, RecordingResult, RecordingStatus,
+};
+use crate::services::recording_schedule::{RecordingSchedule, ScheduledRecording};
+use crate::services::viewer;
+use std::path::{Path, PathBuf};
+use tauri::{command, AppHandle, State, Window};
+
+fn recorder_busy() -> AppError {
+    AppError::localized(ErrorKind::Internal, "error.recorder_busy", &[])
+}
+
+/// 列出可用的錄音裝置
+#[command]
+pub fn list_input_devices() -> Result<Vec<InputDevice>, AppError> {
+    recorder::list_input_devices().map_err(AppError::tool)
+}
+
+/// 目前平台是否能直接擷取系統音訊 (不支援時前端隱藏選項)
+#[command]
+pub fn is_system_audio_supported() -> bool {
+    loopback::is_supported()
+}
+
+/// 開始錄音，錄音檔存到呼叫端視窗專案的 01_converted
+/// options 未指定的項目使用預設值 (預設裝置、WAV、不錄系統音訊、不限時間)
+#[command]
+pub fn start_recording(
+    app: AppHandle,
+    window: Window,
+    projects: State<'_, CurrentProjectState>,
+    recorder: State<'_, RecorderState>,
+    note: State<'_, NoteState>,
+    options: Option<RecordingOptions>,
+) -> Result<RecordingStatus, AppError> {
+    let mut guard = recorder.lock().map_err(|_| recorder_busy())?;
+    let note_active = note.lock().map(|g| g.is_some()).unwrap_or(true);
+    if guard.is_some() || note_active {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.recording_in_progress",
+            &[],
+        ));
+    }
+
+    let root = current_project(&projects, window.label()).ok_or_else(|| {
+        AppError::localized(ErrorKind::InvalidInput, "error.recording_no_project", &[])
+    })?;
+    viewer::ensure_writable(&root)?;
+    let recording = recorder::start_in_project(app, &root, options.unwrap_or_default())?;
+    let status = recording.status();
+    *guard = Some(recording);
+    Ok(status)
+}
+
+#[command]
+pub fn pause_recording(recorder: State<'_, RecorderState>) -> Result<RecordingStatus, AppError> {
+    let guard = recorder.lock().map_err(|_| recorder_busy())?;
+    let recording = guard.as_ref().ok_or_else(not_recording)?;
+    recording.pause();
+    Ok(recording.status())
+}
+
+#[command]
+pub fn resume_recording(recorder: State<'_, RecorderState>) -> Result<RecordingStatus, AppError> {
+    let guard = recorder.lock().map_err(|_| recorder_busy())?;
+    let recording = guard.as_ref().ok_or_else(not_recording)?;
+    recording.resume();
+    Ok(recording.status())
+}
+
+#[command]
+pub fn get_recording_status(recorder: State<'_, RecorderState>) -> RecordingStatus {
+    recorder
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(Recording::status))
+        .unwrap_or_else(RecordingStatus::idle)
+}
+
+/// 停止錄音並存檔；MP3 格式時以 FFmpeg 轉檔後刪除暫存的 WAV
+#[command]
+pub async fn stop_recording(
+    app: AppHandle,
+    recorder: State<'_, RecorderState>,
+) -> Result<RecordingResult, AppError> {
+    let recording = recorder
+        .lock()
+        .map_err(|_| recorder_busy())?
+        .take()
+        .ok_or_else(not_recording)?;
+    recorder::stop_and_save(&app, recording).await
+}
+
+/// 預約錄音：start_time 到達時在 project 開始錄音，duration 秒後自動停止
+/// start_time 可為 RFC 3339 或本地時間 (YYYY-MM-DDTHH:MM[:SS])
+#[command]
+pub fn schedule_recording(
+    schedule: State<'_, RecordingSchedule>,
+    start_time: String,
+    duration: u64,
+    project: String,
+    options: Option<RecordingOptions>,
+) -> Result<ScheduledRecording, AppError> {
+    viewer::ensure_writable(Path::new(&project))?;
+    schedule.add(
+        &start_time,
+        duration,
+        PathBuf::from(project),
+        options.unwrap_or_default(),
+    )
+}
+
+#[command]
+pub fn list_scheduled_recordings(
+    schedule: State<'_, RecordingSchedule>,
+) -> Vec<ScheduledRecording> {
+    schedule.list()
+}
+
+#[command]
+pub fn cancel_scheduled_recording(
+    schedule: State<'_, RecordingSchedule>,
+    id: String,
+) -> Result<(), AppError> {
+    schedule.cancel(&id)
+}
+
+/// 邊聽邊錄：播放器載入的音檔播放時錄下口述註記，另存到專案的 dictations
+#[command]
+pub async fn start_overdub(
+    app: AppHandle,
+    window: Window,
+    projects: State<'_, CurrentProjectState>,
+    recorder: State<'_, RecorderState>,
+    overdub: State<'_, OverdubState>,
+    note: State<'_, NoteState>,
+    player_state: State<'_, AudioPlayerState>,
+    options: Option<RecordingOptions>,
+) -> Result<OverdubStatus, AppError> {
+    // 先取得播放中的音檔：之後持有的錄音狀態鎖定不能跨越 await
+    let (source, player) = {
+        let player_guard = player_state.lock().await;
+        let player = player_guard.as_ref().ok_or_else(|| {
+            AppError::localized(ErrorKind::InvalidInput, "error.no_track_loaded", &[])
+        })?;
+        (player.file_path().to_path_buf(), player.shared_state())
+    };
+
+    let mut guard = overdub.lock().map_err(|_| recorder_busy())?;
+    let recorder_active = recorder.lock().map(|g| g.is_some()).unwrap_or(true);
+    let note_active = note.lock().map(|g| g.is_some()).unwrap_or(true);
+    if guard.is_some() || recorder_active || note_active {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.recording_in_progress",
+            &[],
+        ));
+    }
+
+    let root = current_project(&projects, window.label()).ok_or_else(|| {
+        AppError::localized(ErrorKind::InvalidInput, "error.recording_no_project", &[])
+    })?;
+    viewer::ensure_writable(&root)?;
+
+    let session = Overdub::start(app, &root, source, player, options.unwrap_or_default())?;
+    let status = session.status();
+    *guard = Some(session);
+    Ok(status)
+}
+
+#[command]
+pub fn get_overdub_status(overdub: State<'_, OverdubState>) -> OverdubStatus {
+    overdub
+        .lock()
+        .ok()
+        .and_then(|guard| guard.as_ref().map(Overdub::status))
+        .unwrap_or_else(OverdubStatus::idle)
+}
+
+/// 停止口述註記並存檔，同步點記錄到專案描述檔
+#[command]
+pub async fn stop_overdub(
+    app: AppHandle,
+    overdub: State<'_, OverdubState>,
+) -> Result<DictationRecord, AppError> {
+    let session = overdub
+        .lock()
+        .map_err(|_| recorder_busy())?
+        .take()
+        .ok_or_else(not_recording)?;
+    overdub::stop_and_save(&app, session).await
+}
+
+/// 按住說話的口述筆記：開始錄音 (錄音或邊聽邊錄進行中時不可開始)
+#[command]
+pub fn start_dictation_note(
+    app: AppHandle,
+    window: Window,
+    projects: State<'_, CurrentProjectState>,
+    recorder: State<'_, RecorderState>,
+    overdub: State<'_, OverdubState>,
+    note: State<'_, NoteState>,
+    options: Option<RecordingOptions>,
+) -> Result<RecordingStatus, AppError> {
+    let mut guard = note.lock().map_err(|_| recorder_busy())?;
+    let recorder_active = recorder.lock().map(|g| g.is_some()).unwrap_or(true);
+    let overdub_active = overdub.lock().map(|g| g.is_some()).unwrap_or(true);
+    if guard.is_some() || recorder_active || overdub_active {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.recording_in_progress",
+            &[],
+        ));
+    }
+
+    let root = current_project(&projects, window.label()).ok_or_else(|| {
+        AppError::localized(ErrorKind::InvalidInput, "error.recording_no_project", &[])
+    })?;
+    viewer::ensure_writable(&root)?;
+    let recording = notes::start(app, &root, options.unwrap_or_default())?;
+    let status = recording.status();
+    *guard = Some(recording);
+    Ok(status)
+}
+
+/// 放開按鍵：停止錄音，轉錄後附加到專案的 notes.md
+#[command]
+pub async fn stop_dictation_note(
+    app: AppHandle,
+    note: State<'_, NoteState>,
+) -> Result<NoteEntry, AppError> {
+    let recording = note
+        .lock()
+        .map_err(|_| recorder_busy())?
+        .take()
+        .ok_or_else(not_recording)?;
+    notes::stop_and_append(&app, recording).await
+}
+
+fn not_recording() -> AppError {
+    AppError::localized(ErrorKind::InvalidInput, "error.not_recording", &[])
+}
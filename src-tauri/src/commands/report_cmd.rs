@@ -1,93 +1,252 @@
 // src-tauri/src/commands/report_cmd.rs
-use crate::services::report::ReportAgent;
-use std::path::Path;
-use tauri::command;
+use crate::commands::job_cmd::job_result_string;
+use crate::models::{AppError, ErrorKind};
+use crate::services::batch_guard;
+use crate::services::deid::{self, DeidCheck};
+use crate::services::experiments::{Experiment, PromptVariant};
+use crate::services::jobs::{JobManager, JobSpec};
+use crate::services::offline_queue::{
+    self, NetworkStatus, OfflineQueue, QueueOutcome, QueuedReport,
+};
+use crate::services::report::{self, PurgeResult, RemoteFile, ReportAgent, ReportSummary};
+use crate::services::report_html;
+use crate::services::settings;
+use crate::services::viewer;
+use std::path::{Path, PathBuf};
+use tauri::{command, State};
 
 /// 生成報告
 /// 處理指定資料夾中的音檔，生成逐字稿報告，並自動轉換為 DOCX
 #[command]
 pub async fn generate_report(
+    jobs: State<'_, JobManager>,
     api_key: String,
     folder_path: String,
     model_name: Option<String>,
     custom_prompt_path: Option<String>,
-) -> Result<String, String> {
+    confirm_token: Option<String>,
+) -> Result<String, AppError> {
+    viewer::ensure_writable(Path::new(&folder_path))?;
     if api_key.is_empty() {
-        return Err("請輸入 Gemini API Key".to_string());
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.missing_api_key",
+            &[],
+        ));
     }
     if folder_path.is_empty() {
-        return Err("請選擇音檔資料夾".to_string());
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_folder_selected",
+            &[],
+        ));
     }
 
-    // 處理自定義 Prompt
-    let custom_prompt = if let Some(path) = custom_prompt_path {
-        if !path.is_empty() {
-            match std::fs::read_to_string(&path) {
-                Ok(content) => Some(content),
-                Err(e) => return Err(format!("讀取自定義 Prompt 檔案失敗: {}", e)),
-            }
-        } else {
-            None
-        }
-    } else {
-        None
-    };
-
-    // 根據資料夾路徑推算輸出路徑 (04_report/report.md)
-    let output_path = if folder_path.contains("02_split") {
-        // 如果選的是 02_split，輸出到 04_report
-        folder_path.replace("02_split", "04_report") + "/report.md"
-    } else {
-        // 否則在同目錄建立 report.md
-        format!("{}/report.md", folder_path)
-    };
-
-    // 1. 生成報告 (Markdown)
-    let agent = ReportAgent::new(api_key);
-    let report_result = agent
-        .process_folder(&folder_path, &output_path, model_name, custom_prompt)
-        .await?;
+    let folder = folder_path.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        batch_guard::check(
+            &batch_guard::folder_files(Path::new(&folder)),
+            confirm_token.as_deref(),
+        )
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))??;
+
+    let custom_prompt = read_prompt(custom_prompt_path)?;
+
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::Report {
+                folder_path,
+                model_name,
+                custom_prompt,
+                api_key,
+            },
+            0,
+        )
+        .await,
+    )
+}
+
+/// 讀取自定義 Prompt 檔 (未指定時為 None)
+fn read_prompt(custom_prompt_path: Option<String>) -> Result<Option<String>, AppError> {
+    match custom_prompt_path.filter(|path| !path.is_empty()) {
+        Some(path) => std::fs::read_to_string(&path).map(Some).map_err(|e| {
+            AppError::localized(
+                ErrorKind::Io,
+                "error.prompt_read_failed",
+                &[("detail", e.to_string())],
+            )
+        }),
+        None => Ok(None),
+    }
+}
+
+/// 排入報告但不等待結果：沒有網路時保存到離線佇列，恢復連線後自動開始
+#[command]
+pub fn queue_report(
+    jobs: State<'_, JobManager>,
+    queue: State<'_, OfflineQueue>,
+    api_key: String,
+    folder_path: String,
+    model_name: Option<String>,
+    custom_prompt_path: Option<String>,
+) -> Result<QueueOutcome, AppError> {
+    viewer::ensure_writable(Path::new(&folder_path))?;
+    if api_key.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.missing_api_key",
+            &[],
+        ));
+    }
+    if folder_path.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.no_folder_selected",
+            &[],
+        ));
+    }
+    let custom_prompt = read_prompt(custom_prompt_path)?;
+    queue.submit(&jobs, folder_path, model_name, custom_prompt, api_key)
+}
 
-    // 2. 自動轉換為 DOCX
-    let docx_result = match convert_md_to_docx_internal(&output_path).await {
-        Ok(docx_path) => format!("\n\n✅ 已自動轉換為 Word 文件: {}", docx_path),
-        Err(e) => format!("\n\n⚠️ Word 轉換失敗 (請確認已安裝 Pandoc): {}", e),
-    };
+#[command]
+pub fn get_network_status(queue: State<'_, OfflineQueue>) -> NetworkStatus {
+    offline_queue::status(&queue)
+}
+
+/// 列出等待連線的報告
+#[command]
+pub fn list_offline_reports(queue: State<'_, OfflineQueue>) -> Vec<QueuedReport> {
+    queue.list()
+}
 
-    Ok(format!("{}{}", report_result, docx_result))
+#[command]
+pub fn remove_offline_report(queue: State<'_, OfflineQueue>, id: String) -> Result<(), AppError> {
+    queue.remove(&id)
 }
 
 /// 將 Markdown 轉換為 DOCX (Command)
 #[command]
-pub async fn convert_md_to_docx(md_path: String) -> Result<String, String> {
-    let docx_path = convert_md_to_docx_internal(&md_path).await?;
-    Ok(format!("轉換成功！\nDOCX 檔案位置: {}", docx_path))
+pub async fn convert_md_to_docx(md_path: String) -> Result<String, AppError> {
+    viewer::ensure_writable(Path::new(&md_path))?;
+    deid::ensure_docx_allowed(Path::new(&md_path), &settings::load().deid)?;
+    let docx_path = report::convert_md_to_docx(&md_path)
+        .await
+        .map_err(AppError::tool)?;
+    Ok(crate::tr!("result.docx_converted", path = docx_path))
 }
 
-/// 內部函數：執行 Pandoc 轉換
-async fn convert_md_to_docx_internal(md_path: &str) -> Result<String, String> {
-    // 驗證檔案存在
-    let md_file = Path::new(md_path);
-    if !md_file.exists() {
-        return Err(format!("找不到檔案: {}", md_path));
+fn require_api_key(api_key: &str) -> Result<(), AppError> {
+    if api_key.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.missing_api_key",
+            &[],
+        ));
     }
+    Ok(())
+}
 
-    // 產生 DOCX 輸出路徑
-    let docx_path = md_path.replace(".md", ".docx");
+/// 列出 Gemini File API 上仍存放的檔案 (含失敗流程遺留的音檔)
+#[command]
+pub async fn list_remote_files(api_key: String) -> Result<Vec<RemoteFile>, AppError> {
+    require_api_key(&api_key)?;
+    ReportAgent::new(api_key)
+        .list_remote_files()
+        .await
+        .map_err(AppError::network)
+}
 
-    // 使用 Pandoc 轉換
-    let output = tokio::process::Command::new("pandoc")
-        .args([md_path, "-o", &docx_path, "--from=markdown", "--to=docx"])
-        .output()
+/// 刪除 Gemini File API 上的遺留檔案
+/// names 指定時只刪除這些檔案；否則刪除建立超過 older_than_hours (預設 2) 小時的檔案，
+/// 避免刪到進行中報告的音檔
+#[command]
+pub async fn purge_remote_files(
+    api_key: String,
+    older_than_hours: Option<f64>,
+    names: Option<Vec<String>>,
+) -> Result<PurgeResult, AppError> {
+    require_api_key(&api_key)?;
+    ReportAgent::new(api_key)
+        .purge_remote_files(
+            older_than_hours.unwrap_or(report::DEFAULT_STALE_HOURS),
+            names,
+        )
         .await
-        .map_err(|e| format!("無法執行 Pandoc: {}。請確認已安裝 Pandoc。", e))?;
+        .map_err(AppError::network)
+}
 
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Pandoc 轉換失敗: {}", stderr));
-    }
+/// 讀取報告的 report.json (處理結果與失敗的音檔)；舊報告沒有時回傳 None
+#[command]
+pub fn get_report_summary(report_path: String) -> Option<ReportSummary> {
+    report::load_summary(Path::new(&report_path))
+}
 
-    Ok(docx_path)
+/// 重新執行報告的去識別化檢查 (規則、姓名清單；提供 api_key 且設定啟用時另請 Gemini 檢查)
+#[command]
+pub async fn check_report_deid(
+    report_path: String,
+    api_key: Option<String>,
+) -> Result<DeidCheck, AppError> {
+    viewer::ensure_writable(Path::new(&report_path))?;
+    deid::check_report(
+        Path::new(&report_path),
+        &settings::load().deid,
+        api_key.as_deref(),
+    )
+    .await
+}
+
+/// 確認去識別化檢查結果，之後才允許轉換 DOCX (設定 block_docx 時)
+#[command]
+pub fn acknowledge_deid_findings(report_path: String) -> Result<DeidCheck, AppError> {
+    viewer::ensure_writable(Path::new(&report_path))?;
+    deid::acknowledge(Path::new(&report_path))
+}
+
+/// Prompt 實驗：同一音檔以多組 Prompt / 模型各生成一次，
+/// 輸出、token 用量與差異摘要寫入 04_report/experiments
+#[command]
+pub async fn run_prompt_experiment(
+    jobs: State<'_, JobManager>,
+    api_key: String,
+    audio_path: String,
+    variants: Vec<PromptVariant>,
+) -> Result<Experiment, AppError> {
+    require_api_key(&api_key)?;
+    let value = jobs
+        .enqueue_and_wait(
+            JobSpec::PromptExperiment {
+                audio_path,
+                variants,
+                api_key,
+            },
+            0,
+        )
+        .await?;
+    serde_json::from_value(value)
+        .map_err(|e| AppError::internal(format!("Failed to parse response: {}", e)))
+}
+
+/// 匯出 HTML 報告：每個音檔段落附播放器，點段落即從對應位置播放
+/// report_path 未指定時使用專案中最新的 report.md
+#[command]
+pub async fn export_report_html(
+    project: String,
+    report_path: Option<String>,
+) -> Result<String, AppError> {
+    viewer::ensure_writable(Path::new(&project))?;
+    tauri::async_runtime::spawn_blocking(move || {
+        report_html::export(
+            Path::new(&project),
+            report_path.filter(|p| !p.is_empty()).map(PathBuf::from),
+        )
+    })
+    .await
+    .map_err(|e| AppError::internal(e.to_string()))?
+    .map(|path| path.to_string_lossy().to_string())
 }
 
 /// 取得預設 Prompt
@@ -98,14 +257,24 @@ pub fn get_default_prompt() -> String {
 
 /// 讀取自定義 Prompt 檔案內容
 #[command]
-pub fn read_custom_prompt(path: String) -> Result<String, String> {
-    std::fs::read_to_string(&path).map_err(|e| format!("無法讀取 Prompt 檔案: {}", e))
+pub fn read_custom_prompt(path: String) -> Result<String, AppError> {
+    std::fs::read_to_string(&path).map_err(|e| {
+        AppError::localized(
+            ErrorKind::Io,
+            "error.prompt_read_failed",
+            &[("detail", e.to_string())],
+        )
+    })
 }
 
 /// 舊的命令 (保留向後相容)
 #[command]
 #[deprecated(note = "使用 generate_report 替代")]
 #[allow(deprecated)]
-pub async fn run_report_cmd(_api_key: String) -> Result<String, String> {
-    Err("請使用新的 generate_report 命令".to_string())
+pub async fn run_report_cmd(_api_key: String) -> Result<String, AppError> {
+    Err(AppError::localized(
+        ErrorKind::Unsupported,
+        "error.deprecated_command",
+        &[("command", "generate_report".to_string())],
+    ))
 }
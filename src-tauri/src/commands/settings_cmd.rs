@@ -0,0 +1,53 @@
+// src-tauri/src/commands/settings_cmd.rs
+//
+// Tauri commands for application settings
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::i18n::{self, Locale};
+use crate::services::jobs::JobManager;
+use crate::services::settings::{
+    self, AppConfig, EmailConfig, WebhookConfig, SETTINGS_CHANGED_EVENT,
+};
+use crate::services::{notifications, shortcuts, webhook};
+use tauri::{command, AppHandle, Emitter, State};
+
+/// 取得目前設定
+#[command]
+pub fn get_settings() -> AppConfig {
+    settings::load()
+}
+
+/// 更新設定並通知所有視窗
+#[command]
+pub fn update_settings(
+    app: AppHandle,
+    jobs: State<'_, JobManager>,
+    config: AppConfig,
+) -> Result<AppConfig, AppError> {
+    let config = config.validate().map_err(AppError::invalid_input)?;
+    shortcuts::validate(&config.shortcuts).map_err(AppError::invalid_input)?;
+    let saved = settings::save(config).map_err(AppError::io)?;
+    jobs.set_max_concurrent(saved.max_concurrent_jobs);
+    i18n::set_locale(Locale::from_language(&saved.language));
+    if let Err(e) = shortcuts::register(&app, &saved.shortcuts) {
+        tracing::warn!("{}", e);
+    }
+    let _ = app.emit(SETTINGS_CHANGED_EVENT, &saved);
+    Ok(saved)
+}
+
+/// 以指定的 webhook 設定發送測試事件 (尚未保存的設定也可測試)
+#[command]
+pub async fn test_webhook(config: WebhookConfig) -> Result<(), AppError> {
+    webhook::send_test(&config).await.map_err(|e| {
+        AppError::localized(ErrorKind::Network, "error.webhook_failed", &[]).with_detail(e)
+    })
+}
+
+/// 以指定的 SMTP 設定寄出測試郵件
+#[command]
+pub async fn test_notification_email(config: EmailConfig) -> Result<(), AppError> {
+    notifications::send_test_email(&config).await.map_err(|e| {
+        AppError::localized(ErrorKind::Network, "error.email_failed", &[]).with_detail(e)
+    })
+}
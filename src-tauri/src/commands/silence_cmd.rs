@@ -1,32 +1,81 @@
+use crate::commands::job_cmd::job_result_string;
+use crate::models::{AppError, ErrorKind};
+use crate::services::file_manager::{current_project, CurrentProjectState};
+use crate::services::jobs::{JobManager, JobSpec};
 use crate::services::silence::{Silence, TranscribeResponse};
-use tauri::{command, AppHandle, State};
+use tauri::{command, State, Window};
 
 // Initialize the Silence service state
 // managed likely in main.rs or lib.rs via .manage(Silence::new())
 
 #[command]
-pub async fn connect_server(ip: String, service: State<'_, Silence>) -> Result<bool, String> {
+pub async fn connect_server(ip: String, service: State<'_, Silence>) -> Result<bool, AppError> {
     Ok(service.check_health(&ip).await)
 }
 
+/// diarize: 區分說話者 (段落帶有 speaker ID，可再以 set_speaker_names 命名)
 #[command]
 pub async fn transcribe_audio(
     ip: String,
     file_path: String,
-    service: State<'_, Silence>,
-) -> Result<TranscribeResponse, String> {
-    service.transcribe(&ip, &file_path).await
+    diarize: Option<bool>,
+    jobs: State<'_, JobManager>,
+) -> Result<TranscribeResponse, AppError> {
+    let value = jobs
+        .enqueue_and_wait(
+            JobSpec::Transcribe {
+                server: ip,
+                file_path,
+                diarize: diarize.unwrap_or(false),
+            },
+            0,
+        )
+        .await?;
+    serde_json::from_value(value)
+        .map_err(|e| AppError::internal(format!("Failed to parse response: {}", e)))
 }
 
 #[command]
 pub async fn silence_audio(
-    app: AppHandle,
     input_path: String,
     output_dir: String,
     segments: Vec<(f64, f64)>, // expects start, end
-    service: State<'_, Silence>,
-) -> Result<String, String> {
-    service
-        .apply_silence_to_segments(&app, &input_path, &output_dir, segments)
-        .await
+    jobs: State<'_, JobManager>,
+) -> Result<String, AppError> {
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::SilenceToDir {
+                input_path,
+                output_dir,
+                segments,
+            },
+            0,
+        )
+        .await,
+    )
+}
+
+/// 將目前專案 02_split 的所有音檔送至 STT 伺服器並儲存逐字稿 (中斷後重新執行會略過已完成的檔案)
+#[command]
+pub async fn transcribe_split_folder(
+    window: Window,
+    state: State<'_, CurrentProjectState>,
+    jobs: State<'_, JobManager>,
+    ip: String,
+    diarize: Option<bool>,
+) -> Result<String, AppError> {
+    let root = current_project(&state, window.label()).ok_or_else(|| {
+        AppError::localized(ErrorKind::InvalidInput, "error.transcribe_no_project", &[])
+    })?;
+    job_result_string(
+        jobs.enqueue_and_wait(
+            JobSpec::TranscribeFolder {
+                server: ip,
+                project_root: root.to_string_lossy().to_string(),
+                diarize: diarize.unwrap_or(false),
+            },
+            0,
+        )
+        .await,
+    )
 }
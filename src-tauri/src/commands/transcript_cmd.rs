@@ -0,0 +1,344 @@
+// src-tauri/src/commands/transcript_cmd.rs
+//
+// Tauri commands for stored transcripts (TranscribeResponse JSON)
+
+use crate::commands::audio_cmd::SegmentInfo;
+use crate::commands::job_cmd::job_result_string;
+use crate::models::{AppError, ErrorKind};
+use crate::services::access::AccessPolicy;
+use crate::services::chapters::{self, Chapter};
+use crate::services::encryption;
+use crate::services::file_manager::{current_project, CurrentProjectState};
+use crate::services::history;
+use crate::services::jobs::{JobManager, JobSpec};
+use crate::services::report::{self, ReportAgent};
+use crate::services::splitter::format_timestamp;
+use crate::services::subtitles::{self, SubtitleFormat};
+use crate::services::transcript::{
+    self, LowConfidenceSegment, SpeakerAction, SpeakerInfo, TranscriptDocument, TranscriptEdit,
+    TranscriptVersion, DEFAULT_CONFIDENCE_THRESHOLD,
+};
+use crate::services::workflows::resolve_project;
+use crate::services::{project_stats, settings, topic_chapters, viewer};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+use tauri::{command, AppHandle, State, Window};
+
+/// 取出的說話者音檔資料夾 (專案根目錄下)
+const EXTRACT_DIR_NAME: &str = "05_extracted";
+
+/// 說話者時段前後保留的秒數，避免切到句首句尾
+const DEFAULT_SPEAKER_PADDING: f64 = 0.2;
+
+fn checked_path(app: &AppHandle, policy: &AccessPolicy, path: &str) -> Result<PathBuf, AppError> {
+    policy
+        .check(app, Path::new(path))
+        .map_err(AppError::permission_denied)
+}
+
+/// 把轉錄 JSON 轉成字幕檔 (SRT / VTT)，輸出到專案的 04_report
+/// speaker_prefix: 在每段字幕前加上說話者名稱 (轉錄結果有說話者時)
+#[command]
+pub fn export_subtitles(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    transcript_json: String,
+    format: Option<SubtitleFormat>,
+    speaker_prefix: Option<bool>,
+) -> Result<String, AppError> {
+    let started = Instant::now();
+    let path = checked_path(&app, &policy, &transcript_json)?;
+    viewer::ensure_writable(&path)?;
+    let format = format.unwrap_or_default();
+    let root = history::project_root_for(&path);
+    let key = match &root {
+        Some(root) => encryption::project_key(&app, root)?,
+        None => None,
+    };
+    let result = subtitles::export(&path, format, speaker_prefix.unwrap_or(false), key.as_ref());
+
+    if let Some(root) = root {
+        let outputs: Vec<_> = result.iter().cloned().collect();
+        history::record_command(
+            &root,
+            "export_subtitles",
+            json!({ "transcript": transcript_json, "format": format }),
+            &outputs,
+            started,
+            &result,
+        );
+    }
+    Ok(result?.to_string_lossy().to_string())
+}
+
+/// 讀取轉錄 JSON 與目前版本號
+#[command]
+pub fn get_transcript(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+) -> Result<TranscriptDocument, AppError> {
+    transcript::open(&checked_path(&app, &policy, &path)?)
+}
+
+/// 編輯轉錄結果 (改名、修正文字、合併 / 分割、調整時間)，全部成功才儲存並保存舊版本
+#[command]
+pub fn edit_transcript(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+    edits: Vec<TranscriptEdit>,
+) -> Result<TranscriptDocument, AppError> {
+    let started = Instant::now();
+    let file = checked_path(&app, &policy, &path)?;
+    viewer::ensure_writable(&file)?;
+    let result = transcript::edit(&file, &edits);
+    record(
+        &file,
+        "edit_transcript",
+        json!({ "path": path, "edits": edits.len() }),
+        started,
+        &result,
+    );
+    result
+}
+
+/// 列出逐字稿中的說話者與說話時間
+#[command]
+pub fn list_speakers(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+) -> Result<Vec<SpeakerInfo>, AppError> {
+    let file = checked_path(&app, &policy, &path)?;
+    Ok(transcript::speakers(&transcript::load(&file)?))
+}
+
+/// 列出信心分數低於 threshold (預設 0.6) 的段落，產生報告前提醒需要仔細聽的位置
+#[command]
+pub fn list_low_confidence_segments(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+    threshold: Option<f64>,
+) -> Result<Vec<LowConfidenceSegment>, AppError> {
+    let file = checked_path(&app, &policy, &path)?;
+    Ok(transcript::low_confidence(
+        &transcript::load(&file)?,
+        threshold.unwrap_or(DEFAULT_CONFIDENCE_THRESHOLD),
+    ))
+}
+
+/// 為說話者 ID 指定顯示名稱 (例如 SPEAKER_00 → 林醫師)，字幕與報告使用此名稱
+#[command]
+pub fn set_speaker_names(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+    names: BTreeMap<String, String>,
+) -> Result<TranscriptDocument, AppError> {
+    let started = Instant::now();
+    let file = checked_path(&app, &policy, &path)?;
+    viewer::ensure_writable(&file)?;
+    let result = transcript::set_speaker_names(&file, &names);
+    record(
+        &file,
+        "set_speaker_names",
+        json!({ "path": path, "names": names }),
+        started,
+        &result,
+    );
+    result
+}
+
+/// 依逐字稿的說話者 (ID 或顯示名稱)，對整個音檔消音或只取出該說話者的聲音
+/// 時段自動由逐字稿產生，消音時與手動消音相同輸出到 03_silence
+#[command]
+#[allow(clippy::too_many_arguments)]
+pub async fn redact_speaker(
+    app: AppHandle,
+    window: Window,
+    policy: State<'_, AccessPolicy>,
+    projects: State<'_, CurrentProjectState>,
+    jobs: State<'_, JobManager>,
+    audio_path: String,
+    transcript_json: String,
+    speaker: String,
+    action: Option<SpeakerAction>,
+    padding: Option<f64>,
+) -> Result<String, AppError> {
+    let file = checked_path(&app, &policy, &transcript_json)?;
+    let transcript = transcript::load(&file)?;
+    let segments = transcript::speaker_ranges(
+        &transcript,
+        &speaker,
+        padding.unwrap_or(DEFAULT_SPEAKER_PADDING).max(0.0),
+    );
+    if segments.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.speaker_not_found",
+            &[("speaker", speaker)],
+        ));
+    }
+
+    let project_root =
+        current_project(&projects, window.label()).map(|p| p.to_string_lossy().to_string());
+    let spec = match action.unwrap_or_default() {
+        SpeakerAction::Silence => JobSpec::Silence {
+            audio_path,
+            project_root,
+            segments,
+        },
+        SpeakerAction::Extract => {
+            let source = Path::new(&audio_path);
+            let stem = source
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let ext = source
+                .extension()
+                .map(|e| e.to_string_lossy().to_string())
+                .unwrap_or_else(|| "mp3".to_string());
+            let label: String = speaker
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect();
+            let output_path = resolve_project(project_root.as_deref(), &audio_path)?
+                .root
+                .join(EXTRACT_DIR_NAME)
+                .join(format!("{}_{}.{}", stem, label, ext));
+            JobSpec::Extract {
+                input_path: audio_path,
+                output_path: output_path.to_string_lossy().to_string(),
+                segments,
+            }
+        }
+    };
+    job_result_string(jobs.enqueue_and_wait(spec, 0).await)
+}
+
+/// 強制對齊：以校正後的文字對齊音檔，重新計算段落與逐字時間 (STT 伺服器的 /align)
+/// 對齊結果寫回轉錄 JSON (保存舊版本)，消音範圍因此與修正後的文字一致
+#[command]
+pub async fn align_transcript(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    jobs: State<'_, JobManager>,
+    ip: String,
+    audio_path: String,
+    transcript_json: String,
+) -> Result<TranscriptDocument, AppError> {
+    let transcript_path = checked_path(&app, &policy, &transcript_json)?;
+    viewer::ensure_writable(&transcript_path)?;
+    let value = jobs
+        .enqueue_and_wait(
+            JobSpec::Align {
+                server: ip,
+                file_path: audio_path,
+                transcript_path: transcript_path.to_string_lossy().to_string(),
+            },
+            0,
+        )
+        .await?;
+    serde_json::from_value(value)
+        .map_err(|e| AppError::internal(format!("Failed to parse response: {}", e)))
+}
+
+#[command]
+pub fn list_transcript_versions(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+) -> Result<Vec<TranscriptVersion>, AppError> {
+    Ok(transcript::list_versions(&checked_path(
+        &app, &policy, &path,
+    )?))
+}
+
+/// 還原指定版本 (目前內容會先保存為新版本，可再還原回來)
+#[command]
+pub fn restore_transcript_version(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    path: String,
+    version: u32,
+) -> Result<TranscriptDocument, AppError> {
+    let started = Instant::now();
+    let file = checked_path(&app, &policy, &path)?;
+    viewer::ensure_writable(&file)?;
+    let result = transcript::restore(&file, version);
+    record(
+        &file,
+        "restore_transcript_version",
+        json!({ "path": path, "version": version }),
+        started,
+        &result,
+    );
+    result
+}
+
+/// 依主題提出的章節，另附可直接放入切割清單的段落
+#[derive(serde::Serialize)]
+pub struct TopicChapters {
+    pub chapters: Vec<Chapter>,
+    pub segments: Vec<SegmentInfo>,
+}
+
+/// 依主題自動分章：請 Gemini 依逐字稿提出章節邊界與標題 (由使用者確認後再送去切割)
+/// model_name 未指定時使用設定中的預設模型
+#[command]
+pub async fn propose_topic_chapters(
+    app: AppHandle,
+    policy: State<'_, AccessPolicy>,
+    api_key: String,
+    transcript_json: String,
+    model_name: Option<String>,
+) -> Result<TopicChapters, AppError> {
+    if api_key.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.missing_api_key",
+            &[],
+        ));
+    }
+    let path = checked_path(&app, &policy, &transcript_json)?;
+    let transcript = transcript::load(&path)?;
+    let model = model_name
+        .or(settings::load().default_model)
+        .unwrap_or_else(|| report::DEFAULT_MODEL.to_string());
+
+    let agent = ReportAgent::new(api_key);
+    let result = topic_chapters::propose(&agent, &model, &transcript).await;
+    if let Some(root) = history::project_root_for(&path) {
+        project_stats::record_api_usage(&root, "chapters", &model, agent.usage());
+    }
+    let found = result.map_err(AppError::api)?;
+    let segments = chapters::to_segments(&found)
+        .into_iter()
+        .zip(&found)
+        .map(|((name, _, _), chapter)| SegmentInfo {
+            name,
+            start_time: format_timestamp(chapter.start),
+            end_time: format_timestamp(chapter.end),
+        })
+        .collect();
+    Ok(TopicChapters {
+        chapters: found,
+        segments,
+    })
+}
+
+fn record<T>(
+    file: &Path,
+    command: &str,
+    args: serde_json::Value,
+    started: Instant,
+    result: &Result<T, AppError>,
+) {
+    if let Some(root) = history::project_root_for(file) {
+        history::record_command(&root, command, args, &[file.to_path_buf()], started, result);
+    }
+}
@@ -4,60 +4,391 @@
 // src-tauri/src/main.rs
 
 use std::sync::Mutex;
+use tauri::Manager;
 use stt_agent_rust_lib::commands;
-use stt_agent_rust_lib::commands::player_cmd::AudioPlayerState;
+use stt_agent_rust_lib::commands::player_cmd::{AudioPlayerState, CompareState, OutputState};
 
 fn main() {
     tauri::Builder::default()
+        // 已在執行時，第二次啟動的參數 (開啟的檔案) 交給原本的程式處理
+        .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
+            stt_agent_rust_lib::services::launch::handle_args(app, &args);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        // Manage AudioPlayer state with Mutex<Option<AudioPlayer>>
-        .manage(Mutex::new(None::<stt_agent_rust_lib::services::AudioPlayer>) as AudioPlayerState)
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        // 保存並恢復每個視窗的大小與位置
+        .plugin(tauri_plugin_window_state::Builder::default().build())
+        // 長時間工作完成時的桌面通知
+        .plugin(tauri_plugin_notification::init())
+        // Manage AudioPlayer state with an async Mutex<Option<AudioPlayer>>
+        .manage(AudioPlayerState::new(None))
+        .manage(CompareState::default())
+        .manage(OutputState::default())
         .manage(stt_agent_rust_lib::services::silence::Silence::new())
+        .manage(stt_agent_rust_lib::services::file_manager::CurrentProjectState::default())
+        .manage(stt_agent_rust_lib::services::launch::PendingLaunch::default())
+        .manage(stt_agent_rust_lib::services::access::AccessPolicy::default())
+        .manage(stt_agent_rust_lib::services::recorder::RecorderState::default())
+        .manage(stt_agent_rust_lib::services::overdub::OverdubState::default())
+        .manage(stt_agent_rust_lib::services::notes::NoteState::default())
+        .manage(stt_agent_rust_lib::services::encryption::EncryptionKeys::default())
+        .manage(stt_agent_rust_lib::services::stt_models::ModelDownloads::default())
         .manage(
-            Mutex::new(None::<std::path::PathBuf>)
-                as stt_agent_rust_lib::services::file_manager::CurrentProjectState,
+            Mutex::new(stt_agent_rust_lib::services::ProjectWatcher::new())
+                as stt_agent_rust_lib::services::watcher::ProjectWatcherState,
         )
+        .setup(|app| {
+            // 刪除上次異常結束時留下的暫存解密檔
+            stt_agent_rust_lib::services::encryption::clear_all_scratch();
+            // 日誌系統 (寫入 app data 目錄下的 logs/)
+            match app.path().app_data_dir() {
+                Ok(dir) => {
+                    if let Err(e) = stt_agent_rust_lib::services::logging::init(&dir) {
+                        eprintln!("無法初始化日誌系統: {}", e);
+                    }
+                    // 當機時寫入 crashes/ (含最近的日誌)
+                    stt_agent_rust_lib::services::diagnostics::install_panic_hook(
+                        &dir,
+                        &app.package_info().version.to_string(),
+                    );
+                }
+                Err(e) => eprintln!("無法取得 app data 目錄: {}", e),
+            }
+
+            // 背景工作佇列 (保存於 app data 目錄，重新啟動後繼續執行)
+            let persist_path = app.path().app_data_dir().ok().map(|d| d.join("jobs.json"));
+            let jobs = stt_agent_rust_lib::services::jobs::JobManager::load(persist_path);
+            let config = stt_agent_rust_lib::services::settings::load();
+            jobs.set_max_concurrent(config.max_concurrent_jobs);
+            // 後端訊息使用設定中的語言
+            stt_agent_rust_lib::services::i18n::set_locale(
+                stt_agent_rust_lib::services::i18n::Locale::from_language(&config.language),
+            );
+            jobs.start(app.handle().clone());
+            app.manage(jobs);
+
+            // 離線報告佇列 (恢復連線後自動排入工作佇列)
+            let offline = stt_agent_rust_lib::services::offline_queue::OfflineQueue::load(
+                app.path().app_data_dir().ok().map(|d| {
+                    d.join(stt_agent_rust_lib::services::offline_queue::OFFLINE_QUEUE_FILE_NAME)
+                }),
+            );
+            app.manage(offline);
+            stt_agent_rust_lib::services::offline_queue::start(app.handle().clone());
+
+            // 預約錄音 (保存於 app data 目錄，重新啟動後時間到仍會開始錄音)
+            let schedule = stt_agent_rust_lib::services::recording_schedule::RecordingSchedule::load(
+                app.path().app_data_dir().ok().map(|d| {
+                    d.join(stt_agent_rust_lib::services::recording_schedule::SCHEDULE_FILE_NAME)
+                }),
+            );
+            app.manage(schedule);
+            stt_agent_rust_lib::services::recording_schedule::start(app.handle().clone());
+
+            // 依各專案的保存期限自動刪除過期檔案
+            stt_agent_rust_lib::services::retention::start(app.handle().clone());
+
+            // 記錄本次執行，下次啟動時可判斷是否異常結束 (提供編輯中狀態的復原)
+            app.manage(stt_agent_rust_lib::services::autosave::RunMarker::start(
+                app.path().app_data_dir().ok().map(|d| {
+                    d.join(stt_agent_rust_lib::services::autosave::RUNNING_MARKER_FILE_NAME)
+                }),
+            ));
+
+            // 恢復上次開啟的視窗 (各視窗的專案與頁面由前端讀取工作階段恢復)
+            let sessions = stt_agent_rust_lib::services::session::SessionState::load(
+                app.path().app_data_dir().ok().map(|d| {
+                    d.join(stt_agent_rust_lib::services::session::SESSION_FILE_NAME)
+                }),
+            );
+            let extra_windows = sessions.extra_windows();
+            app.manage(sessions);
+            for label in extra_windows {
+                if let Err(e) =
+                    stt_agent_rust_lib::services::session::create_window(app.handle(), &label, true)
+                {
+                    tracing::warn!("無法恢復視窗 {}: {}", label, e);
+                }
+            }
+
+            // 全域快捷鍵 (播放控制)
+            if let Err(e) = stt_agent_rust_lib::services::shortcuts::register(
+                app.handle(),
+                &config.shortcuts,
+            ) {
+                tracing::warn!("{}", e);
+            }
+
+            // 從檔案總管開啟檔案或 stt-agent:// 連結啟動
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                #[cfg(any(target_os = "windows", target_os = "linux"))]
+                if let Err(e) = app.deep_link().register_all() {
+                    tracing::warn!("無法註冊 stt-agent:// 連結: {}", e);
+                }
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    for url in event.urls() {
+                        stt_agent_rust_lib::services::launch::handle_url(&handle, &url);
+                    }
+                });
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    for url in urls {
+                        stt_agent_rust_lib::services::launch::handle_url(app.handle(), &url);
+                    }
+                }
+                let args: Vec<String> = std::env::args().collect();
+                stt_agent_rust_lib::services::launch::handle_args(app.handle(), &args);
+            }
+            Ok(())
+        })
+        .on_window_event(|window, event| {
+            // 拖放影音檔：放入目前專案並自動排入轉檔
+            if let tauri::WindowEvent::DragDrop(tauri::DragDropEvent::Drop { paths, .. }) = event {
+                stt_agent_rust_lib::services::ingest::handle_drop(
+                    window.app_handle(),
+                    window.label(),
+                    paths.clone(),
+                );
+            }
+            // 使用者關閉其他視窗時不再恢復；最後一個視窗 (結束程式) 保留到下次啟動
+            if let tauri::WindowEvent::CloseRequested { .. } = event {
+                if window.label() != stt_agent_rust_lib::services::session::MAIN_WINDOW_LABEL
+                    && window.app_handle().webview_windows().len() > 1
+                {
+                    if let Some(sessions) = window
+                        .try_state::<stt_agent_rust_lib::services::session::SessionState>()
+                    {
+                        sessions.remove(window.label());
+                    }
+                }
+            }
+            // 視窗關閉時釋放該視窗的專案狀態與檔案監看
+            if let tauri::WindowEvent::Destroyed = event {
+                let label = window.label();
+                stt_agent_rust_lib::services::file_manager::clear_current_project(
+                    &window.state::<stt_agent_rust_lib::services::file_manager::CurrentProjectState>(),
+                    label,
+                );
+                stt_agent_rust_lib::services::viewer::clear(label);
+                if let Ok(mut watcher) = window
+                    .state::<stt_agent_rust_lib::services::watcher::ProjectWatcherState>()
+                    .lock()
+                {
+                    watcher.stop(label);
+                }
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::audio_cmd::run_convert_cmd,
             commands::audio_cmd::convert_files_to_mp3,
-            commands::audio_cmd::set_project_root_dir,
             #[allow(deprecated)]
             commands::audio_cmd::run_split_cmd,
             commands::audio_cmd::run_silence_cmd,
             commands::audio_cmd::split_audio_segments,
+            commands::audio_cmd::get_audio_chapters,
+            commands::audio_cmd::split_by_chapters,
+            commands::audio_cmd::denoise_file,
+            commands::audio_cmd::enhance_speech,
             commands::audio_cmd::list_audio_files,
+            commands::audio_cmd::probe_folder,
             commands::audio_cmd::apply_silence_command,
+            commands::audio_cmd::check_conversion_space,
+            commands::audio_cmd::analyze_audio_quality,
+            commands::audio_cmd::analyze_channels,
+            commands::audio_cmd::get_waveform,
+            commands::audio_cmd::import_timeline,
+            commands::annotation_cmd::list_annotations,
+            commands::annotation_cmd::add_annotation,
+            commands::annotation_cmd::update_annotation,
+            commands::annotation_cmd::delete_annotations,
+            commands::annotation_cmd::split_by_annotations,
+            commands::annotation_cmd::silence_by_annotations,
+            commands::annotation_cmd::export_timeline,
             #[allow(deprecated)]
             commands::report_cmd::run_report_cmd,
             commands::report_cmd::generate_report,
             commands::report_cmd::get_default_prompt,
             commands::report_cmd::read_custom_prompt,
             commands::report_cmd::convert_md_to_docx,
+            commands::report_cmd::get_report_summary,
+            commands::report_cmd::check_report_deid,
+            commands::report_cmd::acknowledge_deid_findings,
+            commands::report_cmd::export_report_html,
+            commands::report_cmd::run_prompt_experiment,
+            commands::report_cmd::list_remote_files,
+            commands::report_cmd::purge_remote_files,
+            commands::report_cmd::queue_report,
+            commands::report_cmd::get_network_status,
+            commands::report_cmd::list_offline_reports,
+            commands::report_cmd::remove_offline_report,
+            commands::pipeline_cmd::run_pipeline,
+            commands::pipeline_cmd::get_pipeline_state,
+            commands::pipeline_cmd::resume_pipeline,
+            commands::pipeline_cmd::run_named_pipeline,
+            commands::pipeline_cmd::list_pipelines,
+            commands::pipeline_cmd::save_pipeline,
             commands::app_cmd::exit_app,
             commands::app_cmd::uninstall_app,
+            commands::app_cmd::preview_purge,
+            commands::app_cmd::purge_app_data,
             // Audio player commands
             commands::player_cmd::load_track,
+            commands::player_cmd::start_compare,
+            commands::player_cmd::switch_compare,
             commands::player_cmd::play,
             commands::player_cmd::pause,
             commands::player_cmd::seek,
             commands::player_cmd::get_playback_state,
+            commands::player_cmd::get_loaded_track_info,
+            commands::player_cmd::export_selection,
+            commands::player_cmd::step_frames,
+            commands::player_cmd::list_output_devices,
+            commands::player_cmd::get_output_config,
+            commands::player_cmd::set_monitor_output,
+            commands::player_cmd::set_output_volume,
+            commands::recorder_cmd::list_input_devices,
+            commands::recorder_cmd::is_system_audio_supported,
+            commands::recorder_cmd::start_recording,
+            commands::recorder_cmd::pause_recording,
+            commands::recorder_cmd::resume_recording,
+            commands::recorder_cmd::get_recording_status,
+            commands::recorder_cmd::stop_recording,
+            commands::recorder_cmd::schedule_recording,
+            commands::recorder_cmd::list_scheduled_recordings,
+            commands::recorder_cmd::cancel_scheduled_recording,
+            commands::recorder_cmd::start_overdub,
+            commands::recorder_cmd::get_overdub_status,
+            commands::recorder_cmd::stop_overdub,
+            commands::recorder_cmd::start_dictation_note,
+            commands::recorder_cmd::stop_dictation_note,
+            commands::model_cmd::list_models,
+            commands::model_cmd::download_model,
+            commands::model_cmd::delete_model,
+            commands::transcript_cmd::export_subtitles,
+            commands::transcript_cmd::propose_topic_chapters,
+            commands::transcript_cmd::get_transcript,
+            commands::transcript_cmd::edit_transcript,
+            commands::transcript_cmd::align_transcript,
+            commands::transcript_cmd::list_speakers,
+            commands::transcript_cmd::list_low_confidence_segments,
+            commands::transcript_cmd::set_speaker_names,
+            commands::transcript_cmd::redact_speaker,
+            commands::transcript_cmd::list_transcript_versions,
+            commands::transcript_cmd::restore_transcript_version,
             // Silence & Auto-Silence
             commands::silence_cmd::connect_server,
             commands::silence_cmd::transcribe_audio,
+            commands::silence_cmd::transcribe_split_folder,
             commands::silence_cmd::silence_audio,
             // Project Commands
             commands::project_cmd::create_project_cmd,
             commands::project_cmd::open_project_cmd,
             commands::project_cmd::get_current_project_cmd,
+            commands::project_cmd::is_viewer_mode,
+            commands::project_cmd::take_launch_request,
+            commands::project_cmd::get_window_session,
+            commands::project_cmd::set_window_page,
             commands::project_cmd::new_window_cmd,
+            commands::project_cmd::validate_project,
+            commands::project_cmd::list_backups,
+            commands::project_cmd::restore_backup,
+            commands::project_cmd::search_project,
+            commands::project_cmd::find_duplicates,
+            commands::project_cmd::get_project_stats,
+            commands::project_cmd::get_project_info,
+            commands::project_cmd::set_project_info,
+            commands::project_cmd::promote_files,
+            commands::project_cmd::get_history,
+            commands::project_cmd::export_history,
+            commands::project_cmd::enable_project_encryption,
+            commands::project_cmd::unlock_project,
+            commands::project_cmd::lock_project,
+            commands::project_cmd::get_encryption_status,
+            commands::project_cmd::save_session_state,
+            commands::project_cmd::get_recovery_state,
+            commands::project_cmd::clear_session_state,
+            commands::project_cmd::get_retention_policy,
+            commands::project_cmd::set_retention_policy,
+            commands::project_cmd::preview_retention_purge,
+            commands::project_cmd::purge_project,
+            // Job Queue Commands
+            commands::job_cmd::enqueue_job,
+            commands::job_cmd::list_jobs,
+            commands::job_cmd::cancel_job,
+            commands::job_cmd::clear_finished_jobs,
+            commands::job_cmd::pause_queue,
+            commands::job_cmd::resume_queue,
+            commands::job_cmd::get_queue_status,
+            commands::job_cmd::rerun_job,
+            commands::job_cmd::get_job_history,
+            // Settings Commands
+            commands::settings_cmd::get_settings,
+            commands::settings_cmd::update_settings,
+            commands::settings_cmd::test_webhook,
+            commands::settings_cmd::test_notification_email,
+            // Dependency Commands
+            commands::dependency_cmd::check_dependencies,
+            commands::dependency_cmd::check_all_services,
+            // Log Commands
+            commands::log_cmd::get_log_tail,
+            commands::log_cmd::open_log_folder,
+            commands::log_cmd::export_diagnostics_bundle,
             // File Commands
             commands::file_cmd::save_text_file,
             commands::file_cmd::read_text_file,
+            commands::file_cmd::read_json_file,
+            commands::file_cmd::write_json_file,
             commands::file_cmd::check_file_exists,
             commands::file_cmd::ensure_dir_exists,
+            commands::file_cmd::request_path_access,
+            commands::file_cmd::check_locations,
+            commands::file_cmd::copy_inputs_to_local,
+            commands::file_cmd::delete_files,
+            commands::file_cmd::list_trash,
+            commands::file_cmd::restore_from_trash,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // macOS 從 Finder 開啟檔案
+            #[cfg(target_os = "macos")]
+            if let tauri::RunEvent::Opened { urls } = &event {
+                for url in urls {
+                    stt_agent_rust_lib::services::launch::handle_url(app, url);
+                }
+            }
+            // 結束時刪除加密專案的暫存解密檔，並標記為正常結束
+            if let tauri::RunEvent::Exit = event {
+                stt_agent_rust_lib::services::encryption::clear_all_scratch();
+                if let Some(marker) =
+                    app.try_state::<stt_agent_rust_lib::services::autosave::RunMarker>()
+                {
+                    marker.finish();
+                }
+            }
+            // 結束前先取消執行中的工作並等待 FFmpeg 停止，避免輸出檔寫到一半
+            if let tauri::RunEvent::ExitRequested { code, api, .. } = event {
+                let jobs = app
+                    .state::<stt_agent_rust_lib::services::jobs::JobManager>()
+                    .inner()
+                    .clone();
+                if jobs.begin_shutdown() {
+                    api.prevent_exit();
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(async move {
+                        if !jobs
+                            .wait_idle(stt_agent_rust_lib::services::jobs::SHUTDOWN_TIMEOUT)
+                            .await
+                        {
+                            tracing::warn!("等待工作結束逾時，直接結束程式");
+                        }
+                        app.exit(code.unwrap_or(0));
+                    });
+                }
+            }
+        });
 }
@@ -0,0 +1,223 @@
+// src-tauri/src/models/error.rs
+//
+// 命令層統一的錯誤型別。前端依 kind / recoverable 決定顯示方式與是否提供重試，
+// 依 message_key 顯示在地化訊息，不需要再解析中文錯誤字串。
+
+use crate::services::i18n;
+use crate::services::jobs::CANCELLED_MESSAGE;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// 錯誤分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    /// 參數不正確 (例如未選擇檔案、時間格式錯誤)
+    InvalidInput,
+    /// 找不到檔案、專案或工作
+    NotFound,
+    /// 檔案讀寫失敗
+    Io,
+    /// FFmpeg 或 Pandoc 等外部工具執行失敗
+    Tool,
+    /// 無法連線到伺服器
+    Network,
+    /// 外部 API (Gemini) 回傳錯誤
+    Api,
+    /// 磁碟空間不足
+    InsufficientSpace,
+    /// 使用者取消
+    Cancelled,
+    /// 目前平台不支援
+    Unsupported,
+    /// 路徑不在允許存取的範圍內
+    PermissionDenied,
+    /// 批次過大，需帶回確認碼才會執行
+    ConfirmationRequired,
+    /// 其他內部錯誤
+    Internal,
+}
+
+impl ErrorKind {
+    /// 預設的在地化訊息 key
+    pub fn message_key(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidInput => "error.invalid_input",
+            ErrorKind::NotFound => "error.not_found",
+            ErrorKind::Io => "error.io",
+            ErrorKind::Tool => "error.tool",
+            ErrorKind::Network => "error.network",
+            ErrorKind::Api => "error.api",
+            ErrorKind::InsufficientSpace => "error.insufficient_space",
+            ErrorKind::Cancelled => "error.cancelled",
+            ErrorKind::Unsupported => "error.unsupported",
+            ErrorKind::PermissionDenied => "error.permission_denied",
+            ErrorKind::ConfirmationRequired => "error.confirmation_required",
+            ErrorKind::Internal => "error.internal",
+        }
+    }
+
+    /// 重試同一個操作是否可能成功
+    pub fn recoverable(self) -> bool {
+        matches!(
+            self,
+            ErrorKind::Io
+                | ErrorKind::Network
+                | ErrorKind::Api
+                | ErrorKind::InsufficientSpace
+                | ErrorKind::Cancelled
+        )
+    }
+}
+
+/// 回傳給前端的錯誤
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppError {
+    pub kind: ErrorKind,
+    /// 顯示給使用者的訊息 (依設定語言產生)
+    pub message: String,
+    /// 技術細節 (原始錯誤、stderr 等)
+    pub detail: Option<String>,
+    pub recoverable: bool,
+    /// 前端 i18n 使用的訊息 key
+    pub message_key: String,
+    /// 訊息 key 的參數 (例如段落編號、檔名)
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+}
+
+impl AppError {
+    /// 建立錯誤；訊息為取消訊息時一律歸類為 Cancelled，
+    /// 讓 service 層回傳的取消錯誤不會被當成失敗
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        let message = message.into();
+        if message == CANCELLED_MESSAGE {
+            return Self::localized(ErrorKind::Cancelled, "error.cancelled", &[]);
+        }
+        Self {
+            kind,
+            message,
+            detail: None,
+            recoverable: kind.recoverable(),
+            message_key: kind.message_key().to_string(),
+            params: BTreeMap::new(),
+        }
+    }
+
+    /// 以訊息 key 建立錯誤，message 依目前語言產生
+    pub fn localized(kind: ErrorKind, key: &str, params: &[(&str, String)]) -> Self {
+        Self {
+            kind,
+            message: i18n::tr(key, params),
+            detail: None,
+            recoverable: kind.recoverable(),
+            message_key: key.to_string(),
+            params: params
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.clone()))
+                .collect(),
+        }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, message)
+    }
+
+    pub fn io(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Io, message)
+    }
+
+    pub fn tool(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Tool, message)
+    }
+
+    pub fn network(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Network, message)
+    }
+
+    pub fn api(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Api, message)
+    }
+
+    pub fn insufficient_space(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InsufficientSpace, message)
+    }
+
+    pub fn cancelled() -> Self {
+        Self::localized(ErrorKind::Cancelled, "error.cancelled", &[])
+    }
+
+    pub fn unsupported(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Unsupported, message)
+    }
+
+    pub fn permission_denied(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::PermissionDenied, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::Internal, message)
+    }
+
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// 指定比分類預設更精確的訊息 key
+    pub fn with_key(mut self, key: impl Into<String>) -> Self {
+        self.message_key = key.into();
+        self
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.kind == ErrorKind::Cancelled
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.detail {
+            Some(detail) => write!(f, "{} ({})", self.message, detail),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for AppError {}
+
+/// service 層仍以 String 回傳錯誤，未分類的一律視為內部錯誤
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        Self::internal(message)
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        let kind = match e.kind() {
+            std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+            _ => ErrorKind::Io,
+        };
+        Self::new(kind, e.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::internal(format!("Serialization error: {}", e))
+    }
+}
+
+pub type AppResult<T> = Result<T, AppError>;
@@ -1 +1,4 @@
 // 這裡未來放 struct 定義
+pub mod error;
+
+pub use error::{AppError, AppResult, ErrorKind};
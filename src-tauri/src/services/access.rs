@@ -0,0 +1,161 @@
+// src-tauri/src/services/access.rs
+//
+// 檔案命令的路徑存取範圍。前端傳入的路徑只允許位於：
+// - 各視窗開啟中的專案、新專案的上層資料夾 (設定或預設)
+// - app data 目錄
+// - 使用者透過對話框選擇並授權的路徑
+// 避免 webview 讀寫任意檔案。
+// 開啟中的專案會擴大範圍，所以開啟專案本身也要檢查 (check_project)：專案必須位於上層資料夾、
+// app data、授權的路徑，或上次工作階段開啟的專案 (由後端保存，不是前端傳入)。
+
+use crate::services::file_manager::{CurrentProjectState, ProjectPaths};
+use crate::services::session::SessionState;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// 使用者授權的路徑 (程式執行期間有效)
+#[derive(Default)]
+pub struct AccessPolicy {
+    granted: Mutex<Vec<PathBuf>>,
+}
+
+impl AccessPolicy {
+    /// 授權路徑 (資料夾時包含其下所有檔案)
+    pub fn grant(&self, path: &Path) {
+        let Some(path) = normalize(path) else {
+            return;
+        };
+        if let Ok(mut granted) = self.granted.lock() {
+            if !granted.contains(&path) {
+                tracing::debug!("授權存取: {}", path.display());
+                granted.push(path);
+            }
+        }
+    }
+
+    /// 檢查路徑是否在允許範圍內，回傳正規化後的路徑
+    pub fn check(&self, app: &AppHandle, path: &Path) -> Result<PathBuf, String> {
+        let mut roots = self.project_roots(app);
+        if let Some(projects) = app.try_state::<CurrentProjectState>() {
+            if let Ok(map) = projects.lock() {
+                roots.extend(map.values().cloned());
+            }
+        }
+        check_within(path, &roots)
+    }
+
+    /// 檢查要開啟或建立的專案路徑 (開啟中的專案不算在允許範圍內)
+    pub fn check_project(&self, app: &AppHandle, path: &Path) -> Result<PathBuf, String> {
+        check_within(path, &self.project_roots(app))
+    }
+
+    /// 可以開啟專案的範圍
+    fn project_roots(&self, app: &AppHandle) -> Vec<PathBuf> {
+        let mut roots: Vec<PathBuf> = Vec::new();
+        roots.extend(ProjectPaths::root_base().ok());
+        roots.extend(app.path().app_data_dir().ok());
+        if let Ok(granted) = self.granted.lock() {
+            roots.extend(granted.iter().cloned());
+        }
+        if let Some(sessions) = app.try_state::<SessionState>() {
+            roots.extend(sessions.project_roots().into_iter().map(PathBuf::from));
+        }
+        roots
+    }
+}
+
+/// 路徑正規化後必須位於 roots 其中之一 (roots 也先正規化)，回傳正規化後的路徑
+fn check_within(path: &Path, roots: &[PathBuf]) -> Result<PathBuf, String> {
+    let normalized = normalize(path)
+        .ok_or_else(|| format!("路徑必須是絕對路徑且不可包含 '..': {}", path.display()))?;
+    if roots
+        .iter()
+        .filter_map(|root| normalize(root))
+        .any(|root| normalized.starts_with(root))
+    {
+        Ok(normalized)
+    } else {
+        tracing::warn!("拒絕存取範圍外的路徑: {}", path.display());
+        Err(format!("不允許存取此路徑: {}", path.display()))
+    }
+}
+
+/// 正規化路徑：必須是絕對路徑、不可包含 `..`；
+/// 解析最深一層存在的上層目錄 (處理符號連結)，再接上尚未建立的部分
+pub(crate) fn normalize(path: &Path) -> Option<PathBuf> {
+    if !path.is_absolute() || path.components().any(|c| c == Component::ParentDir) {
+        return None;
+    }
+    let mut existing = path;
+    let mut rest: Vec<&std::ffi::OsStr> = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return Some(rest.iter().rev().fold(canonical, |p, part| p.join(part)));
+        }
+        rest.push(existing.file_name()?);
+        existing = existing.parent()?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("stt_agent_access_{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.canonicalize().unwrap()
+    }
+
+    #[test]
+    fn normalize_rejects_relative_and_parent_components() {
+        let root = temp_root("parent");
+        assert_eq!(normalize(Path::new("relative/file.txt")), None);
+        assert_eq!(normalize(&root.join("..").join("etc")), None);
+        assert_eq!(normalize(&root.join("a").join("..").join("b")), None);
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn normalize_keeps_not_yet_created_parts() {
+        let root = temp_root("missing");
+        let path = root.join("new_dir").join("nested").join("file.json");
+        assert_eq!(normalize(&path), Some(path.clone()));
+        assert!(check_within(&path, &[root.clone()]).is_ok());
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn check_rejects_paths_outside_roots() {
+        let root = temp_root("outside");
+        let other = temp_root("outside_other");
+        assert!(check_within(&root.join("report.md"), &[root.clone()]).is_ok());
+        assert!(check_within(&other.join("report.md"), &[root.clone()]).is_err());
+        assert!(check_within(&root.join("..").join("x"), &[root.clone()]).is_err());
+        // 名稱相同開頭的兄弟資料夾不在範圍內
+        let sibling = PathBuf::from(format!("{}_sibling", root.display()));
+        assert!(check_within(&sibling.join("a.txt"), &[root.clone()]).is_err());
+        assert!(check_within(&root.join("a.txt"), &[]).is_err());
+        let _ = std::fs::remove_dir_all(&root);
+        let _ = std::fs::remove_dir_all(&other);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn check_follows_symlinks_out_of_root() {
+        let root = temp_root("symlink");
+        let outside = temp_root("symlink_target");
+        std::fs::write(outside.join("secret.txt"), "x").unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+        assert!(check_within(&root.join("link").join("secret.txt"), &[root.clone()]).is_err());
+        // 尚未建立的檔案也依連結指向的位置判斷
+        assert!(check_within(&root.join("link").join("new.txt"), &[root.clone()]).is_err());
+        // 以連結指定的 root 依實際位置比對
+        assert!(check_within(&outside.join("secret.txt"), &[root.join("link")]).is_ok());
+        let _ = std::fs::remove_dir_all(&root);
+        let _ = std::fs::remove_dir_all(&outside);
+    }
+}
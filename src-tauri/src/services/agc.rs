@@ -0,0 +1,138 @@
+// src-tauri/src/services/agc.rs
+//
+// 錄音的自動增益 (AGC) 與限幅器
+//
+// - 以 RMS 包絡估計音量，慢慢拉高增益讓遠處的說話者也聽得清楚，音量變大時快速降低
+// - 音量低於噪音底時維持目前增益，避免靜音時把背景噪音放大
+// - 最後以限幅器壓住峰值 (咳嗽、碰到麥克風)，輸出不會削波
+// - 各聲道共用同一增益，不影響立體聲定位
+
+use serde::{Deserialize, Serialize};
+
+/// 自動增益設定
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct AgcOptions {
+    /// 目標音量 (dBFS，RMS)
+    #[serde(default = "default_target_db")]
+    pub target_db: f32,
+    /// 最大增益 (dB)
+    #[serde(default = "default_max_gain_db")]
+    pub max_gain_db: f32,
+    /// 另存未處理的原始錄音 (*-raw.wav)
+    #[serde(default)]
+    pub keep_raw: bool,
+}
+
+fn default_target_db() -> f32 {
+    -20.0
+}
+
+fn default_max_gain_db() -> f32 {
+    24.0
+}
+
+impl Default for AgcOptions {
+    fn default() -> Self {
+        Self {
+            target_db: default_target_db(),
+            max_gain_db: default_max_gain_db(),
+            keep_raw: false,
+        }
+    }
+}
+
+/// 最多降低的增益 (dB)
+const MIN_GAIN_DB: f32 = -12.0;
+/// 低於此 RMS 視為靜音，不再提高增益
+const NOISE_FLOOR: f32 = 0.003;
+/// 限幅器的上限 (-1 dBFS)
+const CEILING: f32 = 0.891;
+
+const ENVELOPE_ATTACK_SECS: f32 = 0.01;
+const ENVELOPE_RELEASE_SECS: f32 = 0.3;
+/// 增益上升很慢，避免句子之間的呼吸聲被放大
+const GAIN_RISE_SECS: f32 = 2.0;
+const GAIN_FALL_SECS: f32 = 0.05;
+const LIMITER_RELEASE_SECS: f32 = 0.1;
+
+fn db_to_linear(db: f32) -> f32 {
+    10f32.powf(db / 20.0)
+}
+
+/// 單極濾波器每個樣本的係數
+fn coefficient(secs: f32, sample_rate: u32) -> f32 {
+    1.0 - (-1.0 / (secs * sample_rate as f32)).exp()
+}
+
+pub struct Agc {
+    channels: usize,
+    target: f32,
+    min_gain: f32,
+    max_gain: f32,
+    envelope_attack: f32,
+    envelope_release: f32,
+    gain_rise: f32,
+    gain_fall: f32,
+    limiter_release: f32,
+    /// 均方值包絡
+    envelope: f32,
+    gain: f32,
+    limiter_gain: f32,
+}
+
+impl Agc {
+    pub fn new(options: AgcOptions, channels: usize, sample_rate: u32) -> Self {
+        Self {
+            channels: channels.max(1),
+            target: db_to_linear(options.target_db),
+            min_gain: db_to_linear(MIN_GAIN_DB),
+            max_gain: db_to_linear(options.max_gain_db.max(0.0)),
+            envelope_attack: coefficient(ENVELOPE_ATTACK_SECS, sample_rate),
+            envelope_release: coefficient(ENVELOPE_RELEASE_SECS, sample_rate),
+            gain_rise: coefficient(GAIN_RISE_SECS, sample_rate),
+            gain_fall: coefficient(GAIN_FALL_SECS, sample_rate),
+            limiter_release: coefficient(LIMITER_RELEASE_SECS, sample_rate),
+            envelope: 0.0,
+            gain: 1.0,
+            limiter_gain: 1.0,
+        }
+    }
+
+    /// 原地處理交錯的樣本 (長度為聲道數的倍數)
+    pub fn process(&mut self, samples: &mut [f32]) {
+        for frame in samples.chunks_mut(self.channels) {
+            let mean_square = frame.iter().map(|s| s * s).sum::<f32>() / frame.len() as f32;
+            let coef = if mean_square > self.envelope {
+                self.envelope_attack
+            } else {
+                self.envelope_release
+            };
+            self.envelope += (mean_square - self.envelope) * coef;
+
+            let level = self.envelope.sqrt();
+            if level > NOISE_FLOOR {
+                let desired = (self.target / level).clamp(self.min_gain, self.max_gain);
+                let coef = if desired < self.gain {
+                    self.gain_fall
+                } else {
+                    self.gain_rise
+                };
+                self.gain += (desired - self.gain) * coef;
+            }
+
+            // 限幅器：峰值超過上限時立即壓下，之後慢慢放開
+            let peak = frame.iter().fold(0.0f32, |m, s| m.max(s.abs())) * self.gain;
+            let needed = if peak > CEILING { CEILING / peak } else { 1.0 };
+            if needed < self.limiter_gain {
+                self.limiter_gain = needed;
+            } else {
+                self.limiter_gain += (needed - self.limiter_gain) * self.limiter_release;
+            }
+
+            let gain = self.gain * self.limiter_gain;
+            for sample in frame.iter_mut() {
+                *sample = (*sample * gain).clamp(-CEILING, CEILING);
+            }
+        }
+    }
+}
@@ -0,0 +1,247 @@
+// src-tauri/src/services/annotations.rs
+//
+// 專案標註：錄音上的時段筆記，分成「遮蔽 (redact)」「重點 (important)」「疑問 (question)」。
+// 切割、消音與報告共用同一份資料，取代各頁面各自的段落格式。
+//
+// - 存在專案根目錄的 .annotations.json，音檔路徑以專案相對路徑記錄
+// - 切割：每個標註切成一段，以筆記作為片段名稱
+// - 消音：遮蔽類標註的時段
+// - 報告：重點與疑問附在該錄音的提示詞後，提醒模型特別處理
+
+use crate::services::file_manager::{to_project_relative, write_atomic};
+use crate::services::splitter::format_timestamp;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const ANNOTATIONS_FILE_NAME: &str = ".annotations.json";
+
+/// 同時讀寫標註檔的保護
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AnnotationCategory {
+    /// 需要消音遮蔽的個資或閒談
+    Redact,
+    /// 報告需要特別記錄的內容
+    Important,
+    /// 需要後續確認的內容
+    Question,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Annotation {
+    pub id: u64,
+    /// 音檔 (專案相對路徑)
+    pub file: String,
+    /// 開始與結束時間 (秒)
+    pub start: f64,
+    pub end: f64,
+    pub category: AnnotationCategory,
+    #[serde(default)]
+    pub note: String,
+    pub created_at: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub updated_at: Option<String>,
+}
+
+/// 新增或修改標註時前端傳入的內容
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnnotationDraft {
+    pub file: String,
+    pub start: f64,
+    pub end: f64,
+    pub category: AnnotationCategory,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct AnnotationStore {
+    next_id: u64,
+    annotations: Vec<Annotation>,
+}
+
+fn store_path(root: &Path) -> PathBuf {
+    root.join(ANNOTATIONS_FILE_NAME)
+}
+
+fn load_store(root: &Path) -> AnnotationStore {
+    fs::read_to_string(store_path(root))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn save_store(root: &Path, store: &AnnotationStore) -> Result<(), String> {
+    let json = serde_json::to_vec_pretty(store).map_err(|e| e.to_string())?;
+    write_atomic(&store_path(root), &json).map_err(|e| format!("無法寫入標註: {}", e))
+}
+
+/// 讀取、修改並寫回標註檔
+fn modify<T>(
+    root: &Path,
+    f: impl FnOnce(&mut AnnotationStore) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = STORE_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut store = load_store(root);
+    let result = f(&mut store)?;
+    save_store(root, &store)?;
+    Ok(result)
+}
+
+fn validate(draft: &AnnotationDraft) -> Result<(), String> {
+    if !draft.start.is_finite() || !draft.end.is_finite() || draft.start < 0.0 {
+        return Err("標註時間不是有效的秒數".to_string());
+    }
+    if draft.start >= draft.end {
+        return Err("標註的結束時間必須晚於開始時間".to_string());
+    }
+    Ok(())
+}
+
+/// 標註記錄用的音檔鍵 (專案相對路徑)
+fn file_key(root: &Path, file: &str) -> String {
+    to_project_relative(root, Path::new(file))
+}
+
+/// 列出標註 (依音檔、開始時間排序)；file 不為 None 時只列出該音檔
+pub fn list(root: &Path, file: Option<&Path>) -> Vec<Annotation> {
+    let key = file.map(|f| to_project_relative(root, f));
+    let mut annotations: Vec<Annotation> = load_store(root)
+        .annotations
+        .into_iter()
+        .filter(|a| match &key {
+            Some(key) => &a.file == key,
+            None => true,
+        })
+        .collect();
+    annotations.sort_by(|a, b| a.file.cmp(&b.file).then(a.start.total_cmp(&b.start)));
+    annotations
+}
+
+/// 新增標註
+pub fn add(root: &Path, draft: AnnotationDraft) -> Result<Annotation, String> {
+    validate(&draft)?;
+    modify(root, |store| {
+        store.next_id += 1;
+        let annotation = Annotation {
+            id: store.next_id,
+            file: file_key(root, &draft.file),
+            start: draft.start,
+            end: draft.end,
+            category: draft.category,
+            note: draft.note.trim().to_string(),
+            created_at: chrono::Local::now().to_rfc3339(),
+            updated_at: None,
+        };
+        store.annotations.push(annotation.clone());
+        Ok(annotation)
+    })
+}
+
+/// 修改標註
+pub fn update(root: &Path, id: u64, draft: AnnotationDraft) -> Result<Annotation, String> {
+    validate(&draft)?;
+    modify(root, |store| {
+        let annotation = store
+            .annotations
+            .iter_mut()
+            .find(|a| a.id == id)
+            .ok_or_else(|| format!("找不到標註 #{}", id))?;
+        annotation.file = file_key(root, &draft.file);
+        annotation.start = draft.start;
+        annotation.end = draft.end;
+        annotation.category = draft.category;
+        annotation.note = draft.note.trim().to_string();
+        annotation.updated_at = Some(chrono::Local::now().to_rfc3339());
+        Ok(annotation.clone())
+    })
+}
+
+/// 刪除標註，回傳實際刪除的數量
+pub fn remove(root: &Path, ids: &[u64]) -> Result<usize, String> {
+    modify(root, |store| {
+        let before = store.annotations.len();
+        store.annotations.retain(|a| !ids.contains(&a.id));
+        Ok(before - store.annotations.len())
+    })
+}
+
+/// 切割用的段落 (名稱, 開始, 結束)；沒有筆記時以「類別_序號」命名
+pub fn split_segments(annotations: &[Annotation]) -> Vec<(String, String, String)> {
+    annotations
+        .iter()
+        .enumerate()
+        .map(|(i, a)| {
+            let name = if a.note.is_empty() {
+                format!("{}_{}", category_label(a.category), i + 1)
+            } else {
+                a.note.clone()
+            };
+            (name, format_timestamp(a.start), format_timestamp(a.end))
+        })
+        .collect()
+}
+
+/// 消音用的時段 (只取遮蔽類)
+pub fn redact_ranges(annotations: &[Annotation]) -> Vec<(f64, f64)> {
+    annotations
+        .iter()
+        .filter(|a| a.category == AnnotationCategory::Redact)
+        .map(|a| (a.start, a.end))
+        .collect()
+}
+
+fn category_label(category: AnnotationCategory) -> &'static str {
+    match category {
+        AnnotationCategory::Redact => "redact",
+        AnnotationCategory::Important => "important",
+        AnnotationCategory::Question => "question",
+    }
+}
+
+/// 報告提示詞的附註：列出錄音中的重點、疑問與需省略的時段，沒有標註時回傳空字串
+pub fn prompt_context(annotations: &[Annotation]) -> String {
+    let lines: Vec<String> = annotations
+        .iter()
+        .map(|a| {
+            let kind = match a.category {
+                AnnotationCategory::Redact => "省略 (不得寫入報告)",
+                AnnotationCategory::Important => "重點 (請完整記錄)",
+                AnnotationCategory::Question => "疑問 (請列入待確認事項)",
+            };
+            // 遮蔽類的筆記可能含個資，不送出
+            let note = if a.note.is_empty() || a.category == AnnotationCategory::Redact {
+                String::new()
+            } else {
+                format!("：{}", a.note)
+            };
+            format!(
+                "- {} ~ {} {}{}",
+                format_timestamp(a.start),
+                format_timestamp(a.end),
+                kind,
+                note
+            )
+        })
+        .collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+    format!(
+        "\n\n以下是使用者在這段錄音上的標註 (時間為錄音內的位置)：\n{}",
+        lines.join("\n")
+    )
+}
+
+/// 依檔名找出報告資料夾中某個音檔的標註 (加密專案的報告讀取暫存副本，只能以檔名對應)
+pub fn for_file_name(annotations: &[Annotation], file_name: &str) -> Vec<Annotation> {
+    annotations
+        .iter()
+        .filter(|a| Path::new(&a.file).file_name().and_then(|n| n.to_str()) == Some(file_name))
+        .cloned()
+        .collect()
+}
@@ -6,12 +6,17 @@
 //
 // Note: cpal::Stream is NOT Send+Sync, so we spawn it in a dedicated thread
 // and communicate with it via atomic flags.
+//
+// Lifecycle: Idle (nothing loaded) → Loaded → Playing → Stopped. Every thread a player
+// spawns is joined on the way to Stopped (playback threads) or Idle (growth watcher),
+// so the output device is released and no thread outlives the player.
 
 use std::fs::File;
-use std::path::PathBuf;
-use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
 
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use ringbuf::{
@@ -19,16 +24,49 @@ use ringbuf::{
     HeapRb,
 };
 use symphonia::core::audio::SampleBuffer;
-use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
-use symphonia::core::formats::{FormatOptions, SeekMode, SeekTo};
+use symphonia::core::codecs::{CodecParameters, DecoderOptions, CODEC_TYPE_NULL};
+use symphonia::core::formats::{FormatOptions, FormatReader, SeekMode, SeekTo};
 use symphonia::core::io::MediaSourceStream;
 use symphonia::core::meta::MetadataOptions;
 use symphonia::core::probe::Hint;
 use symphonia::core::units::Time;
 
+use crate::services::player_fallback::{self, FfmpegSource};
+use crate::services::player_monitor::{MonitorOutput, MonitorTap, OutputConfig, OutputTarget};
+use crate::services::prefetch::{self, PrefetchedAudio};
+
 /// Buffer size in samples (per channel). ~50ms at 48kHz = 2400 samples
 const RING_BUFFER_SIZE: usize = 4096;
 
+/// A file modified this recently is treated as still being written
+const GROWING_FILE_WINDOW: Duration = Duration::from_secs(3);
+/// How often a growing file's size is checked
+const GROWTH_POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// A growing file that stops changing for this long is considered finished
+const GROWTH_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
+/// How long the decoder waits at the end of a growing file before reopening it
+const GROWTH_RETRY_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Length of one step for step_frames (10 ms, fine enough for redaction boundaries)
+pub const STEP_FRAME_MS: u64 = 10;
+
+/// How often sleeping player threads check whether they should exit
+const STOP_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Lifecycle of a player
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayerState {
+    /// No track loaded (the player slot is empty)
+    Idle,
+    /// Track probed, no playback threads running
+    Loaded,
+    /// Decoder and output threads running (paused or not)
+    Playing,
+    /// Playback threads joined and the output device released; playback can restart
+    Stopped,
+}
+
 /// Shared state for communication between threads
 /// All fields are atomic, making this struct Send + Sync
 pub struct SharedState {
@@ -38,10 +76,23 @@ pub struct SharedState {
     pub should_stop: AtomicBool,
     /// Seek position in milliseconds (u64::MAX means no seek pending)
     pub seek_position_ms: AtomicU64,
-    /// Current playback position in milliseconds
+    /// End of the audio pushed into the ring buffer, in milliseconds. Runs ahead of
+    /// position_ms() by what is decoded but not yet heard; use buffered_ms() for the difference
     pub current_position_ms: AtomicU64,
+    /// Position the audible clock counts from (last seek), in milliseconds
+    pub position_base_ms: AtomicU64,
+    /// Frames actually sent to the output device since position_base_ms
+    pub played_frames: AtomicU64,
+    /// Sample rate of the frames in the ring buffer (0 before playback starts)
+    pub sample_rate: AtomicU64,
     /// Total duration in milliseconds
     pub duration_ms: AtomicU64,
+    /// The file is still being written (recording / conversion in progress)
+    pub is_growing: AtomicBool,
+    /// Volume of the main output (f32 bits)
+    pub volume_bits: AtomicU32,
+    /// Volume of the monitor output (f32 bits)
+    pub monitor_volume_bits: AtomicU32,
 }
 
 impl Default for SharedState {
@@ -51,17 +102,142 @@ impl Default for SharedState {
 }
 
 impl SharedState {
+    /// Audible position in milliseconds, counted from the frames the output device consumed
+    pub fn position_ms(&self) -> u64 {
+        let base = self.position_base_ms.load(Ordering::Relaxed);
+        let sample_rate = self.sample_rate.load(Ordering::Relaxed);
+        if sample_rate == 0 {
+            return base;
+        }
+        base + self.played_frames.load(Ordering::Relaxed) * 1000 / sample_rate
+    }
+
+    /// Decoded but not yet audible (queued in the ring buffer), in milliseconds
+    pub fn buffered_ms(&self) -> u64 {
+        self.current_position_ms
+            .load(Ordering::Relaxed)
+            .saturating_sub(self.position_ms())
+    }
+
+    pub fn volume(&self) -> f32 {
+        f32::from_bits(self.volume_bits.load(Ordering::Relaxed))
+    }
+
+    pub fn monitor_volume(&self) -> f32 {
+        f32::from_bits(self.monitor_volume_bits.load(Ordering::Relaxed))
+    }
+
+    /// Restart the audible clock at `ms`
+    pub fn reset_position(&self, ms: u64) {
+        self.position_base_ms.store(ms, Ordering::Relaxed);
+        self.played_frames.store(0, Ordering::Relaxed);
+        self.current_position_ms.store(ms, Ordering::Relaxed);
+    }
+
     pub fn new() -> Self {
         Self {
             is_paused: AtomicBool::new(true),
             should_stop: AtomicBool::new(false),
             seek_position_ms: AtomicU64::new(u64::MAX),
             current_position_ms: AtomicU64::new(0),
+            position_base_ms: AtomicU64::new(0),
+            played_frames: AtomicU64::new(0),
+            sample_rate: AtomicU64::new(0),
             duration_ms: AtomicU64::new(0),
+            is_growing: AtomicBool::new(false),
+            volume_bits: AtomicU32::new(1.0f32.to_bits()),
+            monitor_volume_bits: AtomicU32::new(1.0f32.to_bits()),
         }
     }
 }
 
+/// Format details of the loaded file, shown in the UI
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct TrackInfo {
+    pub path: String,
+    /// Whether the file still exists (false after it was moved or the project was switched away)
+    pub exists: bool,
+    pub codec: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// Average bitrate in kbps (file size / duration)
+    pub bitrate_kbps: Option<u32>,
+    pub duration: f64,
+    /// The file is still being written
+    pub growing: bool,
+}
+
+/// Whether the file was modified recently enough that it is probably still being written
+pub fn is_growing_file(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age < GROWING_FILE_WINDOW)
+        .unwrap_or(false)
+}
+
+/// Open the file and return the format reader with its first audio track
+fn open_track(file_path: &PathBuf) -> Result<(Box<dyn FormatReader>, u32, CodecParameters), String> {
+    let file = File::open(file_path).map_err(|e| format!("無法開啟檔案: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = file_path.extension() {
+        hint.with_extension(ext.to_str().unwrap_or(""));
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("無法解析音訊格式: {}", e))?;
+
+    let format = probed.format;
+    let (track_id, codec_params) = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .map(|t| (t.id, t.codec_params.clone()))
+        .ok_or("找不到音訊軌道")?;
+    Ok((format, track_id, codec_params))
+}
+
+/// Probe the first audio track's codec parameters
+fn probe_codec_params(file_path: &PathBuf) -> Result<CodecParameters, String> {
+    open_track(file_path).map(|(_, _, codec_params)| codec_params)
+}
+
+/// Bytes per second of audio, used to estimate a growing file's duration from its size.
+/// PCM is computed from the header; compressed formats are measured over what is written so far.
+fn bytes_per_second(file_path: &PathBuf) -> Option<f64> {
+    let (mut format, track_id, codec_params) = open_track(file_path).ok()?;
+    let is_pcm = symphonia::default::get_codecs()
+        .get_codec(codec_params.codec)
+        .map(|d| d.short_name.starts_with("pcm"))
+        .unwrap_or(false);
+    let bits = codec_params.bits_per_coded_sample.or(codec_params.bits_per_sample);
+    if let (true, Some(bits), Some(sample_rate), Some(channels)) =
+        (is_pcm, bits, codec_params.sample_rate, codec_params.channels)
+    {
+        return Some(sample_rate as f64 * channels.count() as f64 * bits as f64 / 8.0);
+    }
+
+    let time_base = codec_params.time_base?;
+    let mut total_ts = 0u64;
+    while let Ok(packet) = format.next_packet() {
+        if packet.track_id() == track_id {
+            total_ts += packet.dur();
+        }
+    }
+    let seconds = total_ts as f64 * time_base.numer as f64 / time_base.denom as f64;
+    let size = std::fs::metadata(file_path).ok()?.len() as f64;
+    (seconds > 0.0).then(|| size / seconds)
+}
+
 /// Audio Player Handle - only contains Send + Sync types
 /// The actual cpal::Stream lives in a separate thread
 pub struct AudioPlayer {
@@ -73,8 +249,33 @@ pub struct AudioPlayer {
     decoder_handle: Option<JoinHandle<()>>,
     /// Handle to the audio output thread
     audio_handle: Option<JoinHandle<()>>,
-    /// Flag to track if playback has been started
-    playback_started: bool,
+    /// Handle to the growth watcher (lives until the track is unloaded, across stop/start)
+    growth_handle: Option<JoinHandle<()>>,
+    /// Tells the growth watcher to exit
+    growth_stop: Arc<AtomicBool>,
+    state: PlayerState,
+    /// Pre-decoded head of the file (see prefetch.rs), played while the decoder opens the file
+    prefetched: Option<Arc<PrefetchedAudio>>,
+    /// Set when symphonia can't handle the file and FFmpeg decodes it instead
+    fallback: Option<FfmpegSource>,
+    /// Second output device (see player_monitor.rs)
+    monitor_device: Option<String>,
+    /// Frames played by the main output, copied for the monitor
+    monitor_tap: MonitorTap,
+    monitor: Option<MonitorOutput>,
+    /// Where the output thread sends the audio
+    sink: OutputSink,
+}
+
+/// Destination of the main output thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputSink {
+    /// The default output device
+    Device,
+    /// No device: nothing is consumed, so the audible position only moves on seek.
+    /// Lets the playback pipeline run without audio hardware
+    #[cfg(test)]
+    Null,
 }
 
 // Explicitly mark as Send + Sync since we only use atomic types
@@ -85,6 +286,30 @@ impl AudioPlayer {
     /// Load an audio file and prepare for playback
     pub fn load(path: &str) -> Result<Self, String> {
         let file_path = PathBuf::from(path);
+
+        // Prefetched files skip probing entirely
+        if let Some(prefetched) = prefetch::get(&file_path) {
+            let shared_state = Arc::new(SharedState::new());
+            shared_state
+                .duration_ms
+                .store((prefetched.duration_secs * 1000.0) as u64, Ordering::Relaxed);
+            return Ok(Self {
+                file_path,
+                shared_state,
+                decoder_handle: None,
+                audio_handle: None,
+                growth_handle: None,
+                growth_stop: Arc::new(AtomicBool::new(false)),
+                state: PlayerState::Loaded,
+                prefetched: Some(prefetched),
+                fallback: None,
+                monitor_device: None,
+                monitor_tap: MonitorTap::default(),
+                monitor: None,
+                sink: OutputSink::Device,
+            });
+        }
+
         let file = File::open(&file_path).map_err(|e| format!("無法開啟檔案: {}", e))?;
 
         // Create media source stream
@@ -117,6 +342,11 @@ impl AudioPlayer {
         let codec_params = &track.codec_params;
         let sample_rate = codec_params.sample_rate.unwrap_or(44100);
 
+        // Fail early when the codec is unsupported so the FFmpeg fallback can take over
+        symphonia::default::get_codecs()
+            .make(codec_params, &DecoderOptions::default())
+            .map_err(|e| format!("無法建立解碼器: {}", e))?;
+
         // Calculate duration
         let duration_secs = if let Some(n_frames) = codec_params.n_frames {
             n_frames as f64 / sample_rate as f64
@@ -132,45 +362,221 @@ impl AudioPlayer {
             shared_state,
             decoder_handle: None,
             audio_handle: None,
-            playback_started: false,
+            growth_handle: None,
+            growth_stop: Arc::new(AtomicBool::new(false)),
+            state: PlayerState::Loaded,
+            prefetched: None,
+            fallback: None,
+            monitor_device: None,
+            monitor_tap: MonitorTap::default(),
+            monitor: None,
+            sink: OutputSink::Device,
         })
     }
 
-    /// Start the audio playback pipeline
-    pub fn start_playback(&mut self) -> Result<(), String> {
-        if self.playback_started {
-            return Ok(()); // Already started
+    /// Load an audio file, falling back to FFmpeg decoding when symphonia can't handle it
+    pub fn load_with_fallback(path: &str, ffmpeg: Option<PathBuf>) -> Result<Self, String> {
+        let err = match Self::load(path) {
+            Ok(player) => return Ok(player),
+            Err(e) => e,
+        };
+        let Some(executable) = ffmpeg else {
+            return Err(err);
+        };
+        tracing::info!("symphonia can't play {} ({}), decoding with FFmpeg", path, err);
+        let source = FfmpegSource::probe(executable, Path::new(path))
+            .map_err(|fallback_err| format!("{} ({})", err, fallback_err))?;
+
+        let shared_state = Arc::new(SharedState::new());
+        shared_state
+            .duration_ms
+            .store((source.info.duration_secs * 1000.0) as u64, Ordering::Relaxed);
+        Ok(Self {
+            file_path: PathBuf::from(path),
+            shared_state,
+            decoder_handle: None,
+            audio_handle: None,
+            growth_handle: None,
+            growth_stop: Arc::new(AtomicBool::new(false)),
+            state: PlayerState::Loaded,
+            prefetched: None,
+            fallback: Some(source),
+            monitor_device: None,
+            monitor_tap: MonitorTap::default(),
+            monitor: None,
+            sink: OutputSink::Device,
+        })
+    }
+
+    /// Sample rate and channel count, from the prefetch cache or by re-probing the file
+    fn stream_format(&self) -> Result<(u32, u16), String> {
+        if self.fallback.is_some() {
+            return Ok((player_fallback::OUTPUT_SAMPLE_RATE, player_fallback::OUTPUT_CHANNELS));
+        }
+        if let Some(prefetched) = &self.prefetched {
+            return Ok((prefetched.sample_rate, prefetched.channels));
         }
 
         // Re-probe file to get format info
-        let file = File::open(&self.file_path).map_err(|e| format!("無法開啟檔案: {}", e))?;
-        let mss = MediaSourceStream::new(Box::new(file), Default::default());
+        let codec_params = probe_codec_params(&self.file_path)?;
+        let sample_rate = codec_params.sample_rate.unwrap_or(44100);
+        let channels = codec_params.channels.map(|c| c.count() as u16).unwrap_or(2);
+        Ok((sample_rate, channels))
+    }
 
-        let mut hint = Hint::new();
-        if let Some(ext) = self.file_path.extension() {
-            hint.with_extension(ext.to_str().unwrap_or(""));
+    /// Path of the loaded file
+    pub fn file_path(&self) -> &Path {
+        &self.file_path
+    }
+
+    /// Handle on the playback state for threads that follow the player (overdub sync)
+    pub fn shared_state(&self) -> Arc<SharedState> {
+        Arc::clone(&self.shared_state)
+    }
+
+    /// Whether the loaded file is still being written
+    pub fn is_growing(&self) -> bool {
+        self.shared_state.is_growing.load(Ordering::Relaxed)
+    }
+
+    /// Follow a file that is still being written: re-estimate the duration as it grows
+    /// and let the decoder play up to the currently available end.
+    /// `on_update(duration_secs, still_growing)` is called whenever the duration changes
+    /// and once more when the file stops growing.
+    pub fn watch_growth<F>(&mut self, on_update: F)
+    where
+        F: Fn(f64, bool) + Send + 'static,
+    {
+        if self.growth_handle.is_some() {
+            return;
         }
+        let file_path = self.file_path.clone();
+        let shared_state = Arc::clone(&self.shared_state);
+        let growth_stop = Arc::clone(&self.growth_stop);
+        shared_state.is_growing.store(true, Ordering::Relaxed);
+
+        let result = thread::Builder::new()
+            .name("player-growth".to_string())
+            .spawn(move || {
+                let mut rate = bytes_per_second(&file_path);
+                let mut last_size = 0u64;
+                let mut last_change = Instant::now();
+                while !growth_stop.load(Ordering::Relaxed) {
+                    let size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+                    if size != last_size {
+                        last_size = size;
+                        last_change = Instant::now();
+                        if rate.is_none() {
+                            rate = bytes_per_second(&file_path);
+                        }
+                        if let Some(rate) = rate {
+                            let duration_ms = (size as f64 / rate * 1000.0) as u64;
+                            shared_state.duration_ms.store(duration_ms, Ordering::Relaxed);
+                            on_update(duration_ms as f64 / 1000.0, true);
+                        }
+                    } else if last_change.elapsed() >= GROWTH_IDLE_TIMEOUT {
+                        // Finished: take the exact duration from the final header when available
+                        if let Ok(codec_params) = probe_codec_params(&file_path) {
+                            if let (Some(n_frames), Some(sample_rate)) =
+                                (codec_params.n_frames, codec_params.sample_rate)
+                            {
+                                let duration_ms = n_frames * 1000 / sample_rate.max(1) as u64;
+                                shared_state.duration_ms.store(duration_ms, Ordering::Relaxed);
+                            }
+                        }
+                        shared_state.is_growing.store(false, Ordering::Relaxed);
+                        let ms = shared_state.duration_ms.load(Ordering::Relaxed);
+                        on_update(ms as f64 / 1000.0, false);
+                        return;
+                    }
+                    sleep_unless(&growth_stop, GROWTH_POLL_INTERVAL);
+                }
+            });
+        match result {
+            Ok(handle) => self.growth_handle = Some(handle),
+            Err(e) => {
+                tracing::error!("Failed to start growth watcher: {}", e);
+                self.shared_state.is_growing.store(false, Ordering::Relaxed);
+            }
+        }
+    }
 
-        let probed = symphonia::default::get_probe()
-            .format(
-                &hint,
-                mss,
-                &FormatOptions::default(),
-                &MetadataOptions::default(),
-            )
-            .map_err(|e| format!("無法解析音訊格式: {}", e))?;
+    /// Format details of the loaded file (re-probes the header)
+    pub fn track_info(&self) -> TrackInfo {
+        let path = self.file_path.to_string_lossy().to_string();
+        let duration = self.get_duration();
+        let file_size = std::fs::metadata(&self.file_path).map(|m| m.len()).ok();
+        let bitrate_kbps = file_size
+            .filter(|_| duration > 0.0)
+            .map(|size| (size as f64 * 8.0 / duration / 1000.0).round() as u32);
+
+        if let Some(source) = &self.fallback {
+            return TrackInfo {
+                exists: self.file_path.exists(),
+                path,
+                codec: source.info.codec.clone(),
+                sample_rate: source.info.sample_rate,
+                channels: source.info.channels,
+                bitrate_kbps,
+                duration,
+                growing: self.is_growing(),
+            };
+        }
 
-        let format = probed.format;
+        match probe_codec_params(&self.file_path) {
+            Ok(codec_params) => TrackInfo {
+                path,
+                exists: true,
+                codec: symphonia::default::get_codecs()
+                    .get_codec(codec_params.codec)
+                    .map(|d| d.short_name.to_string())
+                    .unwrap_or_else(|| codec_params.codec.to_string()),
+                sample_rate: codec_params.sample_rate.unwrap_or(0),
+                channels: codec_params.channels.map(|c| c.count() as u16).unwrap_or(0),
+                bitrate_kbps,
+                duration,
+                growing: self.is_growing(),
+            },
+            Err(e) => {
+                tracing::warn!("Failed to probe loaded track {}: {}", path, e);
+                let (sample_rate, channels) = self
+                    .prefetched
+                    .as_ref()
+                    .map(|p| (p.sample_rate, p.channels))
+                    .unwrap_or((0, 0));
+                TrackInfo {
+                    exists: self.file_path.exists(),
+                    path,
+                    codec: String::new(),
+                    sample_rate,
+                    channels,
+                    bitrate_kbps,
+                    duration,
+                    growing: self.is_growing(),
+                }
+            }
+        }
+    }
 
-        let track = format
-            .tracks()
-            .iter()
-            .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-            .ok_or("找不到音訊軌道")?;
+    /// Start the audio playback pipeline (Loaded/Stopped → Playing).
+    /// After a stop, playback resumes from the last position.
+    pub fn start_playback(&mut self) -> Result<(), String> {
+        match self.state {
+            PlayerState::Playing => return Ok(()), // Already started
+            PlayerState::Idle => return Err("播放器已卸載".to_string()),
+            PlayerState::Loaded | PlayerState::Stopped => {}
+        }
 
-        let codec_params = &track.codec_params;
-        let sample_rate = codec_params.sample_rate.unwrap_or(44100);
-        let channels = codec_params.channels.map(|c| c.count() as u16).unwrap_or(2);
+        let (sample_rate, channels) = self.stream_format()?;
+
+        // The previous pipeline (if any) was joined in stop(), so the flag can be cleared
+        self.shared_state.should_stop.store(false, Ordering::SeqCst);
+        if self.state == PlayerState::Stopped {
+            let position = self.get_position_ms();
+            if position > 0 && self.shared_state.seek_position_ms.load(Ordering::SeqCst) == u64::MAX {
+                self.shared_state.seek_position_ms.store(position, Ordering::SeqCst);
+            }
+        }
 
         // Create ring buffer
         let ring = HeapRb::<f32>::new(RING_BUFFER_SIZE * channels as usize);
@@ -183,30 +589,103 @@ impl AudioPlayer {
         // Start audio output thread (cpal::Stream lives here, not in AudioPlayer)
         let shared_state_audio = Arc::clone(&self.shared_state);
         let consumer_clone = Arc::clone(&consumer);
-        let audio_handle = thread::spawn(move || {
-            if let Err(e) = run_audio_output_loop(sample_rate, channels, shared_state_audio, consumer_clone) {
-                eprintln!("Audio output error: {}", e);
-            }
-        });
+        let monitor_tap = Arc::clone(&self.monitor_tap);
+        let sink = self.sink;
+        let audio_handle = thread::Builder::new()
+            .name("player-output".to_string())
+            .spawn(move || {
+                let result = match sink {
+                    OutputSink::Device => run_audio_output_loop(sample_rate, channels, shared_state_audio, consumer_clone, monitor_tap),
+                    #[cfg(test)]
+                    OutputSink::Null => {
+                        while !shared_state_audio.should_stop.load(Ordering::Relaxed) {
+                            thread::sleep(std::time::Duration::from_millis(5));
+                        }
+                        Ok(())
+                    }
+                };
+                if let Err(e) = result {
+                    tracing::error!("Audio output error: {}", e);
+                }
+            })
+            .map_err(|e| format!("無法啟動播放執行緒: {}", e))?;
 
         // Start decoder thread
         let file_path = self.file_path.clone();
         let shared_state_decoder = Arc::clone(&self.shared_state);
         let producer_clone = Arc::clone(&producer);
-        let decoder_handle = thread::spawn(move || {
-            if let Err(e) = run_decoder_loop(file_path, sample_rate, channels, shared_state_decoder, producer_clone) {
-                eprintln!("Decoder error: {}", e);
+        let consumer_decoder = Arc::clone(&consumer);
+        let prefetched = self.prefetched.clone();
+        let fallback = self.fallback.clone();
+        self.shared_state.sample_rate.store(sample_rate as u64, Ordering::Relaxed);
+        let decoder = thread::Builder::new()
+            .name("player-decoder".to_string())
+            .spawn(move || {
+                let result = match fallback {
+                    Some(source) => player_fallback::run_decoder_loop(source, shared_state_decoder, producer_clone, consumer_decoder),
+                    None => run_decoder_loop(file_path, sample_rate, channels, shared_state_decoder, producer_clone, consumer_decoder, prefetched),
+                };
+                if let Err(e) = result {
+                    tracing::error!("Decoder error: {}", e);
+                }
+            });
+        let decoder_handle = match decoder {
+            Ok(handle) => handle,
+            Err(e) => {
+                // Don't leave the output thread (and the device) behind
+                self.shared_state.should_stop.store(true, Ordering::SeqCst);
+                let _ = audio_handle.join();
+                return Err(format!("無法啟動解碼執行緒: {}", e));
             }
-        });
+        };
 
         self.audio_handle = Some(audio_handle);
         self.decoder_handle = Some(decoder_handle);
         self.shared_state.is_paused.store(false, Ordering::Relaxed);
-        self.playback_started = true;
+        self.state = PlayerState::Playing;
+        self.start_monitor(sample_rate, channels);
 
         Ok(())
     }
 
+    fn start_monitor(&mut self, sample_rate: u32, channels: u16) {
+        if let Some(device) = self.monitor_device.clone() {
+            self.monitor = Some(MonitorOutput::start(
+                device,
+                sample_rate,
+                channels,
+                Arc::clone(&self.shared_state),
+                Arc::clone(&self.monitor_tap),
+            ));
+        }
+    }
+
+    /// Apply output settings: monitor device and volumes (takes effect immediately when playing)
+    pub fn set_outputs(&mut self, config: &OutputConfig) {
+        self.set_volume(OutputTarget::Main, config.main_volume);
+        self.set_volume(OutputTarget::Monitor, config.monitor_volume);
+        if self.monitor_device == config.monitor_device {
+            return;
+        }
+        self.monitor = None;
+        self.monitor_device = config.monitor_device.clone();
+        if self.state == PlayerState::Playing {
+            let sample_rate = self.shared_state.sample_rate.load(Ordering::Relaxed) as u32;
+            if let Ok((_, channels)) = self.stream_format() {
+                self.start_monitor(sample_rate, channels);
+            }
+        }
+    }
+
+    /// Set the volume of one output (0.0 - 2.0)
+    pub fn set_volume(&self, target: OutputTarget, volume: f32) {
+        let bits = volume.clamp(0.0, 2.0).to_bits();
+        match target {
+            OutputTarget::Main => self.shared_state.volume_bits.store(bits, Ordering::Relaxed),
+            OutputTarget::Monitor => self.shared_state.monitor_volume_bits.store(bits, Ordering::Relaxed),
+        }
+    }
+
     /// Resume playback
     pub fn play(&self) {
         self.shared_state.is_paused.store(false, Ordering::Relaxed);
@@ -220,17 +699,50 @@ impl AudioPlayer {
     /// Seek to a specific position in seconds
     /// This clears the ring buffer and signals the decoder to seek
     pub fn seek(&self, seconds: f64) {
-        let ms = (seconds * 1000.0) as u64;
+        let ms = (seconds.max(0.0) * 1000.0) as u64;
+        self.shared_state.reset_position(ms);
         // Signal decoder to seek (it will clear the buffer)
         self.shared_state
             .seek_position_ms
             .store(ms, Ordering::SeqCst);
     }
 
+    /// Pause and move by `frames` steps of STEP_FRAME_MS, returning the new position in seconds
+    pub fn step_frames(&self, frames: i64) -> f64 {
+        self.pause();
+        let current = self.get_position_ms() as i64;
+        let mut target = (current + frames * STEP_FRAME_MS as i64).max(0) as u64;
+        let duration = self.shared_state.duration_ms.load(Ordering::Relaxed);
+        if duration > 0 {
+            target = target.min(duration);
+        }
+        self.seek(target as f64 / 1000.0);
+        target as f64 / 1000.0
+    }
+
     /// Get current playback position in seconds
     pub fn get_position(&self) -> f64 {
-        let ms = self.shared_state.current_position_ms.load(Ordering::Relaxed);
-        ms as f64 / 1000.0
+        self.get_position_ms() as f64 / 1000.0
+    }
+
+    /// Audible playback position in milliseconds
+    pub fn get_position_ms(&self) -> u64 {
+        self.shared_state.position_ms()
+    }
+
+    /// How far the decoder is ahead of the audible position, in milliseconds
+    pub fn get_buffered_ms(&self) -> u64 {
+        self.shared_state.buffered_ms()
+    }
+
+    /// Whether the playback pipeline (decoder + output threads) is running
+    pub fn is_started(&self) -> bool {
+        self.state == PlayerState::Playing
+    }
+
+    /// Current lifecycle state
+    pub fn state(&self) -> PlayerState {
+        self.state
     }
 
     /// Get total duration in seconds
@@ -244,8 +756,12 @@ impl AudioPlayer {
         !self.shared_state.is_paused.load(Ordering::Relaxed)
     }
 
-    /// Stop and cleanup
+    /// Stop playback (Loaded/Playing → Stopped): join the decoder and output threads
+    /// and release the output devices. The position is kept for the next start_playback.
     pub fn stop(&mut self) {
+        if matches!(self.state, PlayerState::Stopped | PlayerState::Idle) {
+            return;
+        }
         self.shared_state.should_stop.store(true, Ordering::SeqCst);
         self.shared_state.is_paused.store(true, Ordering::Relaxed);
 
@@ -255,29 +771,47 @@ impl AudioPlayer {
         if let Some(handle) = self.audio_handle.take() {
             let _ = handle.join();
         }
-        self.playback_started = false;
+        self.monitor = None;
+        // A seek that raced the stop is applied by the next start_playback
+        self.state = PlayerState::Stopped;
+    }
+
+    /// Stop playback and the growth watcher (→ Idle); the player can't be restarted
+    pub fn unload(&mut self) {
+        self.stop();
+        self.growth_stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.growth_handle.take() {
+            let _ = handle.join();
+        }
+        self.state = PlayerState::Idle;
     }
 }
 
 impl Drop for AudioPlayer {
     fn drop(&mut self) {
-        self.stop();
+        self.unload();
     }
 }
 
-/// Audio output loop running in a separate thread
-/// This is where cpal::Stream lives, keeping it off the main thread
-fn run_audio_output_loop(
+/// Sleep for `duration`, waking early when `flag` is set
+fn sleep_unless(flag: &AtomicBool, duration: Duration) {
+    let deadline = Instant::now() + duration;
+    while !flag.load(Ordering::Relaxed) {
+        let now = Instant::now();
+        if now >= deadline {
+            return;
+        }
+        thread::sleep((deadline - now).min(STOP_POLL_INTERVAL));
+    }
+}
+
+/// Pick a stream config on `device` for the file's sample rate and channel count,
+/// returning the config and the device's channel count
+pub(crate) fn select_output_config(
+    device: &cpal::Device,
     sample_rate: u32,
     channels: u16,
-    shared_state: Arc<SharedState>,
-    consumer: Arc<std::sync::Mutex<ringbuf::HeapCons<f32>>>,
-) -> Result<(), String> {
-    let host = cpal::default_host();
-    let device = host
-        .default_output_device()
-        .ok_or("找不到音訊輸出裝置")?;
-
+) -> Result<(cpal::StreamConfig, u16), String> {
     // Find a config that supports the file's sample rate
     let supported_configs: Vec<_> = device
         .supported_output_configs()
@@ -300,26 +834,43 @@ fn run_audio_output_loop(
             })
         });
     
-    let (config, output_channels) = if let Some(cfg) = matching_config {
+    if let Some(cfg) = matching_config {
         let output_channels = cfg.channels();
         let built_config = cfg.clone().with_sample_rate(cpal::SampleRate(sample_rate)).config();
-        eprintln!(
+        tracing::info!(
             "Audio: file={}Hz/{}ch -> device={}Hz/{}ch",
             sample_rate, channels, sample_rate, output_channels
         );
-        (built_config, output_channels)
+        Ok((built_config, output_channels))
     } else {
         // Fallback: use device default
         let default_cfg = device
             .default_output_config()
             .map_err(|e| format!("無法取得預設音訊設定: {}", e))?;
         let output_channels = default_cfg.channels();
-        eprintln!(
-            "Warning: No matching config for {}Hz/{}ch. Using device default {}Hz/{}ch",
+        tracing::warn!(
+            "No matching config for {}Hz/{}ch. Using device default {}Hz/{}ch",
             sample_rate, channels, default_cfg.sample_rate().0, output_channels
         );
-        (default_cfg.config(), output_channels)
-    };
+        Ok((default_cfg.config(), output_channels))
+    }
+}
+
+/// Audio output loop running in a separate thread
+/// This is where cpal::Stream lives, keeping it off the main thread
+fn run_audio_output_loop(
+    sample_rate: u32,
+    channels: u16,
+    shared_state: Arc<SharedState>,
+    consumer: Arc<std::sync::Mutex<ringbuf::HeapCons<f32>>>,
+    monitor_tap: MonitorTap,
+) -> Result<(), String> {
+    let host = cpal::default_host();
+    let device = host
+        .default_output_device()
+        .ok_or("找不到音訊輸出裝置")?;
+
+    let (config, output_channels) = select_output_config(&device, sample_rate, channels)?;
 
     let shared_state_clone = Arc::clone(&shared_state);
     let consumer_clone = Arc::clone(&consumer);
@@ -337,17 +888,35 @@ fn run_audio_output_loop(
                 }
 
                 let mut cons = consumer_clone.lock().unwrap();
+                let volume = shared_state_clone.volume();
+                // Never block the audio thread on the tap; a missed block only glitches the monitor
+                let mut tap_guard = monitor_tap.try_lock().ok();
                 let file_ch = file_channels as usize;
                 let out_ch = output_channels as usize;
                 
                 // Process frame by frame to handle channel conversion
                 let num_frames = data.len() / out_ch;
                 
+                let mut played = 0u64;
                 for frame_idx in 0..num_frames {
                     // Read one frame of samples from the file (file_channels samples)
                     let mut file_samples = [0.0f32; 8]; // Support up to 8 channels
+                    let mut popped = false;
                     for ch in 0..file_ch.min(8) {
-                        file_samples[ch] = cons.try_pop().unwrap_or(0.0);
+                        if let Some(sample) = cons.try_pop() {
+                            file_samples[ch] = sample;
+                            popped = true;
+                        }
+                    }
+                    // Underruns don't advance the audible clock
+                    if popped {
+                        played += 1;
+                        if let Some(Some(tap)) = tap_guard.as_deref_mut() {
+                            let frame = &file_samples[..file_ch.min(8)];
+                            if tap.vacant_len() >= frame.len() {
+                                tap.push_slice(frame);
+                            }
+                        }
                     }
                     
                     // Write to output channels
@@ -362,11 +931,12 @@ fn run_audio_output_loop(
                             // Mono source - duplicate to all channels
                             file_samples[0]
                         };
-                        data[frame_idx * out_ch + out_ch_idx] = sample;
+                        data[frame_idx * out_ch + out_ch_idx] = sample * volume;
                     }
                 }
+                shared_state_clone.played_frames.fetch_add(played, Ordering::Relaxed);
             },
-            |err| eprintln!("Audio stream error: {}", err),
+            |err| tracing::error!("Audio stream error: {}", err),
             None,
         )
         .map_err(|e| format!("無法建立音訊串流: {}", e))?;
@@ -389,40 +959,45 @@ fn run_decoder_loop(
     _channels: u16,
     shared_state: Arc<SharedState>,
     producer: Arc<std::sync::Mutex<ringbuf::HeapProd<f32>>>,
+    consumer: Arc<std::sync::Mutex<ringbuf::HeapCons<f32>>>,
+    prefetched: Option<Arc<PrefetchedAudio>>,
 ) -> Result<(), String> {
-    // Open file and create decoder
-    let file = File::open(&file_path).map_err(|e| format!("無法開啟檔案: {}", e))?;
-    let mss = MediaSourceStream::new(Box::new(file), Default::default());
-
-    let mut hint = Hint::new();
-    if let Some(ext) = file_path.extension() {
-        hint.with_extension(ext.to_str().unwrap_or(""));
+    // Play the prefetched head first so audio starts before the file is opened;
+    // those samples are skipped once the real decoder catches up
+    let mut skip_samples = 0usize;
+    if let Some(head) = prefetched {
+        let channels = head.channels.max(1) as usize;
+        let sample_rate = head.sample_rate.max(1) as u64;
+        while skip_samples < head.samples.len() {
+            if shared_state.should_stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            // A seek leaves the cached head; the main loop handles it
+            if shared_state.seek_position_ms.load(Ordering::SeqCst) != u64::MAX {
+                break;
+            }
+            if shared_state.is_paused.load(Ordering::Relaxed) {
+                thread::sleep(std::time::Duration::from_millis(10));
+                continue;
+            }
+            skip_samples += producer.lock().unwrap().push_slice(&head.samples[skip_samples..]);
+            let position_ms = (skip_samples / channels) as u64 * 1000 / sample_rate;
+            shared_state.current_position_ms.store(position_ms, Ordering::Relaxed);
+            thread::sleep(std::time::Duration::from_millis(5));
+        }
     }
 
-    let probed = symphonia::default::get_probe()
-        .format(
-            &hint,
-            mss,
-            &FormatOptions::default(),
-            &MetadataOptions::default(),
-        )
-        .map_err(|e| format!("無法解析音訊格式: {}", e))?;
-
-    let mut format = probed.format;
-
-    let track = format
-        .tracks()
-        .iter()
-        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
-        .ok_or("找不到音訊軌道")?;
-
-    let track_id = track.id;
+    // Open file and create decoder
+    let (mut format, mut track_id, codec_params) = open_track(&file_path)?;
 
     let mut decoder = symphonia::default::get_codecs()
-        .make(&track.codec_params, &DecoderOptions::default())
+        .make(&codec_params, &DecoderOptions::default())
         .map_err(|e| format!("無法建立解碼器: {}", e))?;
 
     let mut sample_buf: Option<SampleBuffer<f32>> = None;
+    // End of the last decoded packet, where a growing file is resumed after reopening
+    let mut decoded_until_ms = 0u64;
+    let mut eof_size: Option<u64> = None;
 
     loop {
         // Check if we should stop
@@ -445,14 +1020,16 @@ fn run_decoder_loop(
                     track_id: Some(track_id),
                 },
             ) {
-                eprintln!("Seek error: {}", e);
+                tracing::warn!("Seek error: {}", e);
             }
 
             // Reset decoder
             decoder.reset();
+            skip_samples = 0;
 
-            // Update current position
-            shared_state.current_position_ms.store(seek_ms, Ordering::Relaxed);
+            // Drop what is still queued from before the seek and restart the audible clock
+            consumer.lock().unwrap().clear();
+            shared_state.reset_position(seek_ms);
         }
 
         // Check if paused
@@ -467,16 +1044,51 @@ fn run_decoder_loop(
             Err(symphonia::core::errors::Error::IoError(ref e))
                 if e.kind() == std::io::ErrorKind::UnexpectedEof =>
             {
+                if shared_state.is_growing.load(Ordering::Relaxed) {
+                    // The file is still being written: once it has grown, reopen it
+                    // (its header may only describe the data written so far) and resume
+                    let size = std::fs::metadata(&file_path).map(|m| m.len()).unwrap_or(0);
+                    if eof_size.is_some_and(|eof| size > eof) {
+                        match open_track(&file_path) {
+                            Ok((reader, id, _)) => {
+                                format = reader;
+                                track_id = id;
+                                decoder.reset();
+                                let seek_time = Time::new(
+                                    decoded_until_ms / 1000,
+                                    (decoded_until_ms % 1000) as f64 / 1000.0,
+                                );
+                                if let Err(e) = format.seek(
+                                    SeekMode::Accurate,
+                                    SeekTo::Time {
+                                        time: seek_time,
+                                        track_id: Some(track_id),
+                                    },
+                                ) {
+                                    tracing::warn!("Seek error in growing file: {}", e);
+                                }
+                                eof_size = None;
+                                continue;
+                            }
+                            Err(e) => tracing::warn!("Failed to reopen growing file: {}", e),
+                        }
+                    }
+                    eof_size = Some(size);
+                    thread::sleep(GROWTH_RETRY_INTERVAL);
+                    continue;
+                }
                 // End of stream - Do NOT break, otherwise we can't seek backwards
-                // Just sleep and wait for a seek or stop signal
-                if !shared_state.is_paused.load(Ordering::Relaxed) {
+                // Pause once the buffered tail has been heard, then wait for a seek or stop signal
+                if !shared_state.is_paused.load(Ordering::Relaxed)
+                    && consumer.lock().unwrap().is_empty()
+                {
                      shared_state.is_paused.store(true, Ordering::Relaxed);
                 }
                 thread::sleep(std::time::Duration::from_millis(100));
                 continue;
             }
             Err(e) => {
-                eprintln!("Packet read error: {}", e);
+                tracing::warn!("Packet read error: {}", e);
                 continue;
             }
         };
@@ -494,15 +1106,15 @@ fn run_decoder_loop(
             .and_then(|t| t.codec_params.time_base);
         
         if let Some(tb) = time_base {
-            let position_ms = (packet.ts() as f64 * tb.numer as f64 / tb.denom as f64 * 1000.0) as u64;
-            shared_state.current_position_ms.store(position_ms, Ordering::Relaxed);
+            let to_ms = |ts: u64| (ts as f64 * tb.numer as f64 / tb.denom as f64 * 1000.0) as u64;
+            decoded_until_ms = to_ms(packet.ts() + packet.dur());
         }
 
         // Decode the packet
         let decoded = match decoder.decode(&packet) {
             Ok(decoded) => decoded,
             Err(e) => {
-                eprintln!("Decode error: {}", e);
+                tracing::warn!("Decode error: {}", e);
                 continue;
             }
         };
@@ -518,8 +1130,13 @@ fn run_decoder_loop(
         let buf = sample_buf.as_mut().unwrap();
         buf.copy_interleaved_ref(decoded);
 
-        // Write samples to ring buffer
-        let samples = buf.samples();
+        // Write samples to ring buffer (minus what the prefetched head already played)
+        let mut samples = buf.samples();
+        if skip_samples > 0 {
+            let skipped = skip_samples.min(samples.len());
+            samples = &samples[skipped..];
+            skip_samples -= skipped;
+        }
         let mut prod = producer.lock().unwrap();
 
         for &sample in samples {
@@ -534,7 +1151,131 @@ fn run_decoder_loop(
             }
             let _ = prod.try_push(sample);
         }
+        drop(prod);
+
+        // The packet is fully queued (past the prefetched head): the decoder is now at its end
+        if skip_samples == 0 {
+            shared_state.current_position_ms.store(decoded_until_ms, Ordering::Relaxed);
+        }
     }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One second of silence in its own temp dir
+    fn silent_wav(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "stt_agent_player_{}_{}",
+            name,
+            std::process::id()
+        ));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("silence.wav");
+        let spec = hound::WavSpec {
+            channels: 1,
+            sample_rate: 8000,
+            bits_per_sample: 16,
+            sample_format: hound::SampleFormat::Int,
+        };
+        let mut writer = hound::WavWriter::create(&path, spec).unwrap();
+        for _ in 0..8000 {
+            writer.write_sample(0i16).unwrap();
+        }
+        writer.finalize().unwrap();
+        path
+    }
+
+    /// Loaded with the null output, so the tests need no audio device and the
+    /// position only changes on seek
+    fn load(path: &Path) -> AudioPlayer {
+        let mut player = AudioPlayer::load(path.to_str().unwrap()).unwrap();
+        player.sink = OutputSink::Null;
+        player
+    }
+
+    fn cleanup(path: &Path) {
+        let _ = std::fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    // Every player thread holds a clone of the shared state, so once they are all joined
+    // only the player and the test itself still reference it
+
+    #[test]
+    fn stop_without_playback_leaves_no_threads() {
+        let path = silent_wav("loaded");
+        let mut player = load(&path);
+        assert_eq!(player.state(), PlayerState::Loaded);
+        let state = player.shared_state();
+        player.stop();
+        assert_eq!(player.state(), PlayerState::Stopped);
+        drop(player);
+        assert_eq!(Arc::strong_count(&state), 1);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn repeated_loads_join_playback_threads() {
+        let path = silent_wav("reload");
+        for _ in 0..5 {
+            let mut player = load(&path);
+            let state = player.shared_state();
+            player.start_playback().unwrap();
+            assert_eq!(player.state(), PlayerState::Playing);
+            // A seek racing the stop must not keep the decoder alive
+            player.seek(0.5);
+            player.stop();
+            assert_eq!(player.state(), PlayerState::Stopped);
+            assert_eq!(Arc::strong_count(&state), 2);
+        }
+        cleanup(&path);
+    }
+
+    #[test]
+    fn restart_after_stop_resumes_from_position() {
+        let path = silent_wav("restart");
+        let mut player = load(&path);
+        player.start_playback().unwrap();
+        player.seek(0.5);
+        player.stop();
+        player.start_playback().unwrap();
+        assert_eq!(player.state(), PlayerState::Playing);
+        assert!(!player.shared_state.should_stop.load(Ordering::SeqCst));
+        assert_eq!(player.get_position_ms(), 500);
+        player.stop();
+        cleanup(&path);
+    }
+
+    #[test]
+    fn unload_joins_growth_watcher() {
+        let path = silent_wav("growth");
+        let mut player = load(&path);
+        let state = player.shared_state();
+        player.watch_growth(|_, _| {});
+        player.unload();
+        assert_eq!(player.state(), PlayerState::Idle);
+        assert!(player.start_playback().is_err());
+        assert_eq!(Arc::strong_count(&state), 2);
+        cleanup(&path);
+    }
+
+    #[test]
+    fn position_counts_played_frames_not_decoded_ones() {
+        let state = SharedState::new();
+        state.sample_rate.store(8000, Ordering::Relaxed);
+        state.reset_position(1000);
+        // The decoder has queued 500 ms past the seek, the output consumed 200 ms of it
+        state.current_position_ms.store(1500, Ordering::Relaxed);
+        state.played_frames.store(1600, Ordering::Relaxed);
+        assert_eq!(state.position_ms(), 1200);
+        assert_eq!(state.buffered_ms(), 300);
+
+        // Nothing is buffered right after a seek
+        state.reset_position(3000);
+        assert_eq!(state.position_ms(), 3000);
+        assert_eq!(state.buffered_ms(), 0);
+    }
+}
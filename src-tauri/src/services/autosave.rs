@@ -0,0 +1,127 @@
+// src-tauri/src/services/autosave.rs
+//
+// 編輯中狀態的自動保存與當機復原：前端每隔幾秒把尚未送出的狀態
+// (待切割的段落、待消音的時段、報告選項) 以頁面為單位寫入專案的 .autosave.json。
+// 送出或放棄後清除；程式異常結束時，下次開啟專案可選擇恢復。
+//
+// 是否異常結束以 app data 的 .running 標記判斷：啟動時建立，正常結束時刪除。
+
+use crate::services::file_manager::write_atomic;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const AUTOSAVE_FILE_NAME: &str = ".autosave.json";
+pub const RUNNING_MARKER_FILE_NAME: &str = ".running";
+
+/// 單一頁面保存的狀態
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PageState {
+    pub saved_at: String,
+    pub state: Value,
+}
+
+/// 專案的自動保存內容 (頁面名稱 → 狀態)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutosaveFile {
+    pub pages: BTreeMap<String, PageState>,
+}
+
+fn autosave_path(root: &Path) -> PathBuf {
+    root.join(AUTOSAVE_FILE_NAME)
+}
+
+pub fn load(root: &Path) -> AutosaveFile {
+    fs::read_to_string(autosave_path(root))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn store(root: &Path, file: &AutosaveFile) -> Result<(), String> {
+    let path = autosave_path(root);
+    if file.pages.is_empty() {
+        return match fs::remove_file(&path) {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(format!("無法刪除自動保存檔: {}", e)),
+        };
+    }
+    let content = serde_json::to_vec_pretty(file).map_err(|e| e.to_string())?;
+    write_atomic(&path, &content).map_err(|e| format!("無法寫入自動保存檔: {}", e))
+}
+
+/// 保存頁面狀態 (覆蓋同一頁面先前的內容)
+pub fn save(root: &Path, page: &str, state: Value) -> Result<(), String> {
+    if !root.is_dir() {
+        return Err(format!("專案目錄不存在: {}", root.display()));
+    }
+    let mut file = load(root);
+    file.pages.insert(
+        page.to_string(),
+        PageState {
+            saved_at: chrono::Local::now().to_rfc3339(),
+            state,
+        },
+    );
+    store(root, &file)
+}
+
+/// 清除頁面狀態 (未指定頁面時清除全部)
+pub fn clear(root: &Path, page: Option<&str>) -> Result<(), String> {
+    let mut file = load(root);
+    match page {
+        Some(page) => {
+            file.pages.remove(page);
+        }
+        None => file.pages.clear(),
+    }
+    store(root, &file)
+}
+
+/// 上次執行是否異常結束 (啟動時判斷一次)
+pub struct RunMarker {
+    path: Option<PathBuf>,
+    previous_crashed: bool,
+}
+
+impl RunMarker {
+    /// 檢查上次留下的標記並建立本次的標記
+    pub fn start(path: Option<PathBuf>) -> Self {
+        let previous_crashed = path.as_ref().map(|p| p.exists()).unwrap_or(false);
+        if let Some(path) = &path {
+            if let Err(e) = write_atomic(path, chrono::Local::now().to_rfc3339().as_bytes()) {
+                tracing::warn!("無法建立執行標記: {}", e);
+            }
+        }
+        if previous_crashed {
+            tracing::warn!("上次執行未正常結束，開啟專案時將提供復原");
+        }
+        Self {
+            path,
+            previous_crashed,
+        }
+    }
+
+    pub fn previous_crashed(&self) -> bool {
+        self.previous_crashed
+    }
+
+    /// 正常結束時刪除標記
+    pub fn finish(&self) {
+        if let Some(path) = &self.path {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// 上次異常結束時，回傳專案中尚未送出的狀態
+pub fn recovery(marker: &RunMarker, root: &Path) -> Option<AutosaveFile> {
+    if !marker.previous_crashed() {
+        return None;
+    }
+    Some(load(root)).filter(|file| !file.pages.is_empty())
+}
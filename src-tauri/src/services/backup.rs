@@ -0,0 +1,217 @@
+// src-tauri/src/services/backup.rs
+//
+// 破壞性操作 (消音覆寫、報告覆寫) 前的自動備份。
+// 備份存放於 <專案>/.backups/<時間戳記>/，保留原本的相對路徑結構。
+// 只備份專案內的檔案：還原時依相對路徑寫回，專案外的檔案無法還原到原本的位置。
+// 保留份數見設定的 max_backups。
+
+use crate::services::settings;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const BACKUP_DIR: &str = ".backups";
+
+/// 每個專案最多保留的備份數量預設值，超過時刪除最舊的
+pub const DEFAULT_MAX_BACKUPS: usize = 20;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupInfo {
+    /// 備份資料夾名稱 (時間戳記)，也是 restore_backup 的參數
+    pub id: String,
+    /// 備份內的檔案 (相對於專案根目錄)
+    pub files: Vec<String>,
+}
+
+/// 將即將被覆寫或刪除的檔案複製到新的備份資料夾
+/// 不存在的檔案會被略過；全部都不存在時不建立備份並回傳 None
+/// 檔案不在 root 內時回傳錯誤 (不建立備份)
+pub fn snapshot(root: &Path, files: &[PathBuf]) -> Result<Option<String>, String> {
+    snapshot_keeping(root, files, settings::load().max_backups)
+}
+
+fn snapshot_keeping(root: &Path, files: &[PathBuf], keep: usize) -> Result<Option<String>, String> {
+    let mut existing = Vec::new();
+    for file in files.iter().filter(|f| f.is_file()) {
+        let relative = file
+            .strip_prefix(root)
+            .map_err(|_| format!("無法備份專案外的檔案: {}", file.display()))?;
+        existing.push((file, relative));
+    }
+    if existing.is_empty() {
+        return Ok(None);
+    }
+
+    let id = chrono::Local::now().format("%Y%m%d_%H%M%S_%3f").to_string();
+    let backup_root = root.join(BACKUP_DIR).join(&id);
+
+    for (file, relative) in existing {
+        let dest = backup_root.join(relative);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("無法建立備份目錄: {}", e))?;
+        }
+        fs::copy(file, &dest).map_err(|e| format!("備份檔案失敗 {}: {}", file.display(), e))?;
+    }
+
+    prune(root, keep.max(1))?;
+    Ok(Some(id))
+}
+
+/// 列出專案的所有備份 (新到舊)
+pub fn list_backups(root: &Path) -> Result<Vec<BackupInfo>, String> {
+    let mut backups = Vec::new();
+    for id in backup_ids(root)?.into_iter().rev() {
+        let dir = root.join(BACKUP_DIR).join(&id);
+        let mut files = Vec::new();
+        collect_files(&dir, &dir, &mut files);
+        files.sort();
+        backups.push(BackupInfo { id, files });
+    }
+    Ok(backups)
+}
+
+/// 將備份內容還原回專案 (覆寫現有檔案)
+/// id 必須是 list_backups 列出的其中一個備份
+pub fn restore_backup(root: &Path, id: &str) -> Result<Vec<String>, String> {
+    if !backup_ids(root)?.iter().any(|known| known == id) {
+        return Err(format!("找不到備份: {}", id));
+    }
+    let dir = root.join(BACKUP_DIR).join(id);
+
+    let mut files = Vec::new();
+    collect_files(&dir, &dir, &mut files);
+
+    let mut restored = Vec::new();
+    for relative in files {
+        let dest = root.join(&relative);
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent).map_err(|e| format!("無法建立目錄: {}", e))?;
+        }
+        fs::copy(dir.join(&relative), &dest)
+            .map_err(|e| format!("還原檔案失敗 {}: {}", relative, e))?;
+        restored.push(dest.to_string_lossy().to_string());
+    }
+    Ok(restored)
+}
+
+/// 只保留最新的 keep 份備份
+pub fn prune(root: &Path, keep: usize) -> Result<(), String> {
+    let ids = backup_ids(root)?;
+    if ids.len() <= keep {
+        return Ok(());
+    }
+    for id in &ids[..ids.len() - keep] {
+        let _ = fs::remove_dir_all(root.join(BACKUP_DIR).join(id));
+    }
+    Ok(())
+}
+
+/// 依時間排序 (舊到新) 的備份編號
+fn backup_ids(root: &Path) -> Result<Vec<String>, String> {
+    let dir = root.join(BACKUP_DIR);
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut ids: Vec<String> = fs::read_dir(&dir)
+        .map_err(|e| format!("讀取備份目錄失敗: {}", e))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+        .collect();
+    // 時間戳記格式可直接以字串排序
+    ids.sort();
+    Ok(ids)
+}
+
+fn collect_files(base: &Path, dir: &Path, out: &mut Vec<String>) {
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                collect_files(base, &path, out);
+            } else if let Ok(relative) = path.strip_prefix(base) {
+                out.push(relative.to_string_lossy().to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("stt-backup-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("04_report")).unwrap();
+        root
+    }
+
+    #[test]
+    fn snapshot_and_restore_keep_relative_paths() {
+        let root = temp_root("restore");
+        let report = root.join("04_report").join("report.md");
+        fs::write(&report, "v1").unwrap();
+
+        let id = snapshot_keeping(&root, &[report.clone(), root.join("missing.md")], 5)
+            .unwrap()
+            .unwrap();
+        fs::write(&report, "v2").unwrap();
+        let restored = restore_backup(&root, &id).unwrap();
+
+        assert_eq!(restored, vec![report.to_string_lossy().to_string()]);
+        assert_eq!(fs::read_to_string(&report).unwrap(), "v1");
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn restore_rejects_unknown_ids() {
+        let root = temp_root("unknown_id");
+        let report = root.join("04_report").join("report.md");
+        fs::write(&report, "v1").unwrap();
+        snapshot_keeping(&root, &[report], 5).unwrap().unwrap();
+        for id in ["", ".", "..", "../04_report", "20990101_000000_000"] {
+            assert!(restore_backup(&root, id).is_err(), "id {:?}", id);
+        }
+        // 沒有任何備份資料夾被複製到專案根目錄
+        let mut entries: Vec<String> = fs::read_dir(&root)
+            .unwrap()
+            .flatten()
+            .map(|e| e.file_name().to_string_lossy().to_string())
+            .collect();
+        entries.sort();
+        assert_eq!(entries, vec![BACKUP_DIR, "04_report"]);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn snapshot_rejects_files_outside_root() {
+        let root = temp_root("outside");
+        let outside =
+            std::env::temp_dir().join(format!("stt-backup-out-{}.md", std::process::id()));
+        fs::write(&outside, "x").unwrap();
+
+        assert!(snapshot_keeping(&root, &[outside.clone()], 5).is_err());
+        assert!(backup_ids(&root).unwrap().is_empty());
+        let _ = fs::remove_file(&outside);
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn prune_keeps_newest() {
+        let root = temp_root("prune");
+        for id in [
+            "20240101_000000_000",
+            "20240102_000000_000",
+            "20240103_000000_000",
+        ] {
+            fs::create_dir_all(root.join(BACKUP_DIR).join(id)).unwrap();
+        }
+        prune(&root, 2).unwrap();
+        assert_eq!(
+            backup_ids(&root).unwrap(),
+            vec!["20240102_000000_000", "20240103_000000_000"]
+        );
+        let _ = fs::remove_dir_all(&root);
+    }
+}
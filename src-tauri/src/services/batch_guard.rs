@@ -0,0 +1,99 @@
+// src-tauri/src/services/batch_guard.rs
+//
+// 大批次防呆：轉檔或生成報告的檔案數 / 總長度超過設定門檻時，先回傳
+// ConfirmationRequired 錯誤 (params 含 files、hours、token)，前端確認後帶回 token 才執行，
+// 避免不小心選錯資料夾而送出數百個檔案 (耗時且耗費 API 額度)。
+//
+// token 由檔案清單與總量計算，不需保存狀態；批次內容改變時舊的 token 自然失效。
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::ingest::is_media_file;
+use crate::services::probe;
+use crate::services::settings;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy)]
+pub struct BatchTotals {
+    pub files: usize,
+    pub seconds: f64,
+}
+
+/// 計算檔案數與總長度 (無法讀取長度的檔案以 0 計)
+pub fn totals(paths: &[String]) -> BatchTotals {
+    BatchTotals {
+        files: paths.len(),
+        seconds: paths
+            .iter()
+            .map(|p| probe::cached_duration(p).unwrap_or(0.0))
+            .sum(),
+    }
+}
+
+/// 資料夾內的影音檔
+pub fn folder_files(folder: &Path) -> Vec<String> {
+    let mut files: Vec<PathBuf> = fs::read_dir(folder)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|e| e.path())
+                .filter(|p| p.is_file() && is_media_file(p))
+                .collect()
+        })
+        .unwrap_or_default();
+    files.sort();
+    files
+        .iter()
+        .map(|p| p.to_string_lossy().to_string())
+        .collect()
+}
+
+fn token(paths: &[String], totals: BatchTotals) -> String {
+    let mut sorted: Vec<&String> = paths.iter().collect();
+    sorted.sort();
+    let mut hasher = Sha256::new();
+    for path in sorted {
+        hasher.update(path.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.update(format!("{}:{:.0}", totals.files, totals.seconds).as_bytes());
+    hasher.finalize()[..8]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 批次超過門檻且未帶回正確的確認碼時回傳 ConfirmationRequired
+/// (會讀取每個檔案的長度，請在背景執行緒呼叫)
+pub fn check(paths: &[String], confirm_token: Option<&str>) -> Result<(), AppError> {
+    let config = settings::load().batch_guard;
+    if config.max_files == 0 && config.max_hours <= 0.0 {
+        return Ok(());
+    }
+    let too_many = config.max_files > 0 && paths.len() > config.max_files;
+    let totals = totals(paths);
+    let too_long = config.max_hours > 0.0 && totals.seconds > config.max_hours * 3600.0;
+    if !too_many && !too_long {
+        return Ok(());
+    }
+
+    let expected = token(paths, totals);
+    if confirm_token == Some(expected.as_str()) {
+        tracing::info!(
+            "已確認大批次: {} 個檔案，{:.1} 小時",
+            totals.files,
+            totals.seconds / 3600.0
+        );
+        return Ok(());
+    }
+    Err(AppError::localized(
+        ErrorKind::ConfirmationRequired,
+        "error.confirmation_required",
+        &[
+            ("files", totals.files.to_string()),
+            ("hours", format!("{:.1}", totals.seconds / 3600.0)),
+            ("token", expected),
+        ],
+    ))
+}
@@ -0,0 +1,154 @@
+// src-tauri/src/services/chapters.rs
+//
+// 章節資訊：有聲書 (m4b)、mkv 等容器常內含章節，讀出後可直接依章節切割到 02_split。
+// Sidecar 只附帶 FFmpeg (沒有 ffprobe)，因此以 `ffmpeg -f ffmetadata -` 輸出的 metadata 解析章節。
+
+use crate::services::sidecar::Ffmpeg;
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// 單一章節 (時間以秒計)
+#[derive(Debug, Clone, Serialize)]
+pub struct Chapter {
+    pub index: usize,
+    pub title: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// 讀取音檔的章節，沒有章節時回傳空清單
+pub async fn read_chapters(ffmpeg: &Ffmpeg, path: &str) -> Result<Vec<Chapter>, String> {
+    let output = ffmpeg
+        .run(["-v", "error", "-i", path, "-f", "ffmetadata", "-"], None)
+        .await?;
+    if !output.success() {
+        return Err(format!("無法讀取章節資訊: {}", output.stderr.trim()));
+    }
+    Ok(parse_ffmetadata(&output.stdout))
+}
+
+/// 還原 ffmetadata 的跳脫字元 (`\=`、`\;`、`\#`、`\\`)
+fn unescape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            if let Some(next) = chars.next() {
+                result.push(next);
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+/// 解析 ffmetadata 的 [CHAPTER] 區段
+fn parse_ffmetadata(text: &str) -> Vec<Chapter> {
+    struct Raw {
+        timebase: (f64, f64),
+        start: Option<i64>,
+        end: Option<i64>,
+        title: String,
+    }
+
+    let mut raws: Vec<Raw> = Vec::new();
+    let mut in_chapter = false;
+    for line in text.lines() {
+        let line = line.trim_end();
+        if line.starts_with('[') {
+            in_chapter = line.eq_ignore_ascii_case("[CHAPTER]");
+            if in_chapter {
+                raws.push(Raw {
+                    timebase: (1.0, 1000.0),
+                    start: None,
+                    end: None,
+                    title: String::new(),
+                });
+            }
+            continue;
+        }
+        let Some(raw) = raws.last_mut().filter(|_| in_chapter) else {
+            continue;
+        };
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.to_ascii_uppercase().as_str() {
+            "TIMEBASE" => {
+                if let Some((num, den)) = value.split_once('/') {
+                    if let (Ok(num), Ok(den)) = (num.parse::<f64>(), den.parse::<f64>()) {
+                        if den > 0.0 {
+                            raw.timebase = (num, den);
+                        }
+                    }
+                }
+            }
+            "START" => raw.start = value.parse().ok(),
+            "END" => raw.end = value.parse().ok(),
+            "TITLE" => raw.title = unescape(value).trim().to_string(),
+            _ => {}
+        }
+    }
+
+    raws.into_iter()
+        .filter_map(|raw| {
+            let scale = raw.timebase.0 / raw.timebase.1;
+            let start = raw.start? as f64 * scale;
+            let end = raw.end? as f64 * scale;
+            (end > start).then_some((raw.title, start, end))
+        })
+        .enumerate()
+        .map(|(i, (title, start, end))| Chapter {
+            index: i + 1,
+            title,
+            start,
+            end,
+        })
+        .collect()
+}
+
+/// 章節標題轉為檔名：移除檔案系統不允許的字元，沒有標題時以序號命名，重複的名稱加上序號
+fn file_stem(chapter: &Chapter, used: &mut HashSet<String>) -> String {
+    let cleaned: String = chapter
+        .title
+        .chars()
+        .map(|c| {
+            if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') {
+                '_'
+            } else {
+                c
+            }
+        })
+        .collect();
+    // Windows 不允許結尾是句點或空白
+    let cleaned = cleaned.trim().trim_end_matches('.').trim().to_string();
+    let base = if cleaned.is_empty() {
+        format!("Chapter {:02}", chapter.index)
+    } else {
+        cleaned
+    };
+
+    let mut name = base.clone();
+    let mut n = 2;
+    while !used.insert(name.to_lowercase()) {
+        name = format!("{} ({})", base, n);
+        n += 1;
+    }
+    name
+}
+
+/// 轉為切割工作使用的段落 (name, start_time, end_time)
+pub fn to_segments(chapters: &[Chapter]) -> Vec<(String, String, String)> {
+    let mut used = HashSet::new();
+    chapters
+        .iter()
+        .map(|chapter| {
+            (
+                file_stem(chapter, &mut used),
+                format!("{:.3}", chapter.start),
+                format!("{:.3}", chapter.end),
+            )
+        })
+        .collect()
+}
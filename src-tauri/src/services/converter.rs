@@ -1,73 +1,137 @@
 // src-tauri/src/services/converter.rs
 
-use std::path::Path;
-use tauri::AppHandle;
-use tauri_plugin_shell::ShellExt;
+use crate::services::downmix::Downmix;
+use crate::services::file_manager::{self, OutputCollision};
+use crate::services::jobs::CancelToken;
+use crate::services::settings::StageEncoding;
+use crate::services::sidecar::Ffmpeg;
+use std::path::{Path, PathBuf};
 
-pub struct Converter;
+/// 預設輸出位元率 (kbps)
+const DEFAULT_BITRATE_KBPS: u32 = 192;
+/// 預設取樣率 (Hz)
+const DEFAULT_SAMPLE_RATE: u32 = 44100;
+
+pub struct Converter {
+    cancel: Option<CancelToken>,
+    bitrate_kbps: u32,
+    sample_rate: u32,
+    mono: bool,
+    downmix: Downmix,
+    collision: OutputCollision,
+}
+
+impl Default for Converter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl Converter {
     pub fn new() -> Self {
-        Self
+        Self {
+            cancel: None,
+            bitrate_kbps: DEFAULT_BITRATE_KBPS,
+            sample_rate: DEFAULT_SAMPLE_RATE,
+            mono: false,
+            downmix: Downmix::Keep,
+            collision: OutputCollision::default(),
+        }
+    }
+
+    /// 設定聲道處理 (預設保留原本的聲道)
+    pub fn with_downmix(mut self, downmix: Downmix) -> Self {
+        self.downmix = downmix;
+        self
+    }
+
+    /// 設定輸出位元率 (kbps)，對應設定中的 FFmpeg 轉檔品質
+    pub fn with_bitrate(mut self, kbps: u32) -> Self {
+        self.bitrate_kbps = kbps;
+        self
+    }
+
+    /// 套用設定中的階段編碼 (位元率、單聲道、取樣率)
+    pub fn with_encoding(mut self, encoding: StageEncoding) -> Self {
+        self.bitrate_kbps = encoding.bitrate_kbps;
+        self.sample_rate = encoding.sample_rate.unwrap_or(DEFAULT_SAMPLE_RATE);
+        self.mono = encoding.mono;
+        self
+    }
+
+    /// 設定輸出檔已存在時的處理方式 (預設覆寫)
+    pub fn with_collision(mut self, collision: OutputCollision) -> Self {
+        self.collision = collision;
+        self
+    }
+
+    /// 轉檔輸出路徑 (未處理同名檔案): output_dir/<檔名>.mp3
+    pub fn output_path(input_path: &str, output_dir: &str) -> Option<String> {
+        file_manager::derived_output_path(Path::new(input_path), Path::new(output_dir), "", "mp3")
+            .map(|p| p.to_string_lossy().to_string())
+    }
+
+    /// 綁定工作的取消旗標，取消時中止 FFmpeg
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
     }
 
     /// 將單一檔案轉換成 MP3
     /// 回傳 Ok(輸出檔案路徑) 或 Err(錯誤訊息)
     pub async fn convert_to_mp3(
         &self,
-        app: &AppHandle,
+        ffmpeg: &Ffmpeg,
         input_path: &str,
         output_dir: &str,
     ) -> Result<String, String> {
-        let input = Path::new(input_path);
+        // 建立輸出路徑 (已有同名檔案時依設定覆寫、回報錯誤或改名)
+        let output_path = Self::output_path(input_path, output_dir).ok_or("無法取得檔案名稱")?;
+        let output_path = self
+            .collision
+            .resolve(PathBuf::from(output_path))?
+            .to_string_lossy()
+            .to_string();
 
-        // 取得檔名（不含副檔名）
-        let file_stem = input
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .ok_or("無法取得檔案名稱")?;
-
-        // 建立輸出路徑
-        let output_path = format!("{}/{}.mp3", output_dir, file_stem);
-
-        println!("正在轉檔: {} -> {}", input_path, output_path);
+        tracing::info!("正在轉檔: {} -> {}", input_path, output_path);
 
         // 確保輸出目錄存在
-        std::fs::create_dir_all(output_dir).map_err(|e| format!("無法建立輸出目錄: {}", e))?;
+        std::fs::create_dir_all(file_manager::long_path(Path::new(output_dir)))
+            .map_err(|e| format!("無法建立輸出目錄: {}", e))?;
+
+        let bitrate = format!("{}k", self.bitrate_kbps);
+        let sample_rate = self.sample_rate.to_string();
 
         // 執行 FFmpeg Sidecar
         // 注意：這裡使用 Sidecar，不需要指定完整路徑，Tauri 會自動找到
-        let output = app
-            .shell()
-            .sidecar("ffmpeg")
-            .map_err(|e| format!("無法建立 FFmpeg Sidecar: {}", e))?
-            .args([
-                "-i",
-                input_path, // 輸入檔案
-                "-vn",      // 不要視訊
-                "-acodec",
-                "libmp3lame", // MP3 編碼器
-                "-ab",
-                "192k", // 位元率 192kbps
-                "-ar",
-                "44100", // 取樣率 44.1kHz
-                "-y",    // 覆蓋已存在的檔案
-                &output_path,
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("FFmpeg 執行失敗: {}。請確認已正確配置 Sidecar。", e))?;
-
-        if output.status.success() {
+        let mut args: Vec<&str> = vec![
+            "-i", input_path, // 輸入檔案
+            "-vn",      // 不要視訊
+        ];
+        args.extend_from_slice(self.downmix.ffmpeg_args()); // 聲道處理 (單邊錄音只取一邊等)
+        if self.mono && self.downmix == Downmix::Keep {
+            args.extend_from_slice(&["-ac", "1"]); // 設定要求單聲道
+        }
+        args.extend_from_slice(&[
+            "-acodec",
+            "libmp3lame", // MP3 編碼器
+            "-ab",
+            &bitrate, // 位元率 (預設 192kbps)
+            "-ar",
+            &sample_rate,                 // 取樣率 (預設 44.1kHz)
+            self.collision.ffmpeg_flag(), // -y 覆寫 / -n 不覆寫
+            &output_path,
+        ]);
+        let output = ffmpeg.run(args, self.cancel.as_ref()).await?;
+
+        if output.success() {
             Ok(output_path)
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let exit_code = output.status.code().unwrap_or(-1);
+            let exit_code = output.code.unwrap_or(-1);
 
             Err(format!(
                 "FFmpeg 轉檔失敗 (Exit Code: {})。\nStderr: {}\nStdout: {}",
-                exit_code, stderr, stdout
+                exit_code, output.stderr, output.stdout
             ))
         }
     }
@@ -75,13 +139,13 @@ impl Converter {
     /// 批次轉換多個檔案
     pub async fn convert_files(
         &self,
-        app: &AppHandle,
+        ffmpeg: &Ffmpeg,
         input_paths: Vec<String>,
         output_dir: &str,
     ) -> Vec<Result<String, String>> {
         let mut results = Vec::new();
         for path in input_paths {
-            results.push(self.convert_to_mp3(app, &path, output_dir).await);
+            results.push(self.convert_to_mp3(ffmpeg, &path, output_dir).await);
         }
         results
     }
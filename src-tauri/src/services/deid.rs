@@ -0,0 +1,251 @@
+// src-tauri/src/services/deid.rs
+//
+// 報告的去識別化自我檢查：報告產生後掃描最終的 Markdown，
+// 找出可能外洩的病人姓名、身分證字號、電話號碼等個資。
+//
+// - 內建規則加上設定中的自訂規則 (regex) 與姓名清單
+// - 可另外請 Gemini 找出規則抓不到的個資 (只保留確實出現在報告中的文字)
+// - 結果寫入 report.json 的 deid；設定 block_docx 時，確認檢查結果前不轉換 DOCX
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::report::{self, ReportAgent, ReportSummary};
+use crate::services::settings::DeidConfig;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// 內建規則 (名稱, regex)
+const BUILTIN_PATTERNS: &[(&str, &str)] = &[
+    ("身分證字號", r"(?-u:\b)[A-Z][12]\d{8}(?-u:\b)"),
+    ("手機號碼", r"(?-u:\b)09\d{2}[-\s]?\d{3}[-\s]?\d{3}(?-u:\b)"),
+    (
+        "市話號碼",
+        r"\(0\d{1,2}\)\s?\d{3,4}[-\s]?\d{4}|(?-u:\b)0\d{1,2}-\d{3,4}-?\d{4}(?-u:\b)",
+    ),
+    ("Email", r"[\w.+-]+@[\w-]+(?:\.[\w-]+)+"),
+    ("病歷號", r"病歷(?:號碼?|編號)\s*[:：]?\s*[A-Za-z0-9-]{4,}"),
+];
+
+/// 檢查結果前後保留的字數
+const CONTEXT_CHARS: usize = 30;
+
+const LLM_PROMPT: &str = r#"以下是一份醫療會議紀錄。請找出其中仍可識別特定病人的個人資料
+(病人或家屬的姓名、身分證字號、病歷號、電話、地址、生日等)，醫師與醫療人員的姓名不需列出。
+只輸出 JSON 陣列，格式為 [{"text": "報告中的原文", "reason": "個資類型"}]，沒有時輸出 []。
+
+報告內容：
+"#;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingSource {
+    Pattern,
+    Name,
+    Llm,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeidFinding {
+    pub source: FindingSource,
+    /// 規則名稱或個資類型
+    pub label: String,
+    pub text: String,
+    /// 行號 (從 1 開始)
+    pub line: usize,
+    /// 前後文
+    pub context: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeidCheck {
+    pub checked_at: String,
+    pub findings: Vec<DeidFinding>,
+    /// 使用者確認檢查結果的時間
+    pub acknowledged_at: Option<String>,
+    /// Gemini 檢查失敗的原因 (規則檢查仍然有效)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub llm_error: Option<String>,
+}
+
+impl DeidCheck {
+    /// 有檢查結果且尚未確認
+    pub fn needs_review(&self) -> bool {
+        !self.findings.is_empty() && self.acknowledged_at.is_none()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct LlmFinding {
+    text: String,
+    #[serde(default)]
+    reason: String,
+}
+
+fn context(line: &str, start: usize, end: usize) -> String {
+    let before: String = {
+        let chars: Vec<char> = line[..start].chars().collect();
+        chars[chars.len().saturating_sub(CONTEXT_CHARS)..]
+            .iter()
+            .collect()
+    };
+    let after: String = line[end..].chars().take(CONTEXT_CHARS).collect();
+    format!("{}{}{}", before, &line[start..end], after)
+        .trim()
+        .to_string()
+}
+
+/// 以規則與姓名清單掃描報告內容
+pub fn scan(content: &str, config: &DeidConfig) -> Vec<DeidFinding> {
+    let patterns: Vec<(String, Regex)> = BUILTIN_PATTERNS
+        .iter()
+        .map(|(label, regex)| (label.to_string(), regex.to_string()))
+        .chain(
+            config
+                .patterns
+                .iter()
+                .map(|p| (p.label.clone(), p.regex.clone())),
+        )
+        .filter_map(|(label, regex)| match Regex::new(&regex) {
+            Ok(re) => Some((label, re)),
+            Err(e) => {
+                tracing::warn!("略過無效的去識別化規則 {}: {}", label, e);
+                None
+            }
+        })
+        .collect();
+
+    let mut findings = Vec::new();
+    for (idx, line) in content.lines().enumerate() {
+        for (label, re) in &patterns {
+            for m in re.find_iter(line) {
+                findings.push(DeidFinding {
+                    source: FindingSource::Pattern,
+                    label: label.clone(),
+                    text: m.as_str().to_string(),
+                    line: idx + 1,
+                    context: context(line, m.start(), m.end()),
+                });
+            }
+        }
+        for name in config.names.iter().filter(|n| !n.is_empty()) {
+            for (start, text) in line.match_indices(name.as_str()) {
+                findings.push(DeidFinding {
+                    source: FindingSource::Name,
+                    label: "姓名".to_string(),
+                    text: text.to_string(),
+                    line: idx + 1,
+                    context: context(line, start, start + text.len()),
+                });
+            }
+        }
+    }
+    findings
+}
+
+/// Gemini 回傳的 JSON 陣列 (可能包在 ``` 區塊中)，只保留報告中確實出現的文字
+fn parse_llm_findings(content: &str, response: &str) -> Vec<DeidFinding> {
+    let json = match (response.find('['), response.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => return Vec::new(),
+    };
+    let items: Vec<LlmFinding> = serde_json::from_str(json).unwrap_or_default();
+    items
+        .into_iter()
+        .filter(|item| !item.text.trim().is_empty())
+        .filter_map(|item| {
+            let text = item.text.trim();
+            let (idx, line) = content
+                .lines()
+                .enumerate()
+                .find(|(_, line)| line.contains(text))?;
+            let start = line.find(text)?;
+            Some(DeidFinding {
+                source: FindingSource::Llm,
+                label: item.reason,
+                text: text.to_string(),
+                line: idx + 1,
+                context: context(line, start, start + text.len()),
+            })
+        })
+        .collect()
+}
+
+/// 檢查報告並把結果寫入 report.json；api_key 有值且設定 llm_check 時另外請 Gemini 檢查
+pub async fn check_report(
+    report_path: &Path,
+    config: &DeidConfig,
+    api_key: Option<&str>,
+) -> Result<DeidCheck, AppError> {
+    let content = fs::read_to_string(report_path)?;
+    let mut findings = scan(&content, config);
+    let mut summary = report::load_summary(report_path).unwrap_or_default();
+
+    let mut llm_error = None;
+    if let Some(api_key) = api_key.filter(|k| config.llm_check && !k.is_empty()) {
+        let model = if summary.model.is_empty() {
+            report::DEFAULT_MODEL.to_string()
+        } else {
+            summary.model.clone()
+        };
+        let prompt = format!("{}{}", LLM_PROMPT, content);
+        match ReportAgent::new(api_key.to_string())
+            .generate_text(&model, &prompt)
+            .await
+        {
+            Ok(response) => findings.extend(parse_llm_findings(&content, &response)),
+            Err(e) => llm_error = Some(e),
+        }
+    }
+
+    // 同一行的同一段文字只列一次 (規則與 Gemini 可能都找到)
+    let mut seen = std::collections::HashSet::new();
+    findings.retain(|f| seen.insert((f.line, f.text.clone())));
+    findings.sort_by_key(|f| f.line);
+
+    let check = DeidCheck {
+        checked_at: chrono::Local::now().to_rfc3339(),
+        findings,
+        acknowledged_at: None,
+        llm_error,
+    };
+    summary.deid = Some(check.clone());
+    report::save_summary(report_path, &summary).map_err(AppError::io)?;
+    Ok(check)
+}
+
+fn load_check(report_path: &Path) -> Option<(ReportSummary, DeidCheck)> {
+    let summary = report::load_summary(report_path)?;
+    let check = summary.deid.clone()?;
+    Some((summary, check))
+}
+
+/// 確認檢查結果 (之後允許轉換 DOCX)
+pub fn acknowledge(report_path: &Path) -> Result<DeidCheck, AppError> {
+    let (mut summary, mut check) = load_check(report_path).ok_or_else(|| {
+        AppError::localized(
+            ErrorKind::NotFound,
+            "error.deid_not_checked",
+            &[("path", report_path.display().to_string())],
+        )
+    })?;
+    check.acknowledged_at = Some(chrono::Local::now().to_rfc3339());
+    summary.deid = Some(check.clone());
+    report::save_summary(report_path, &summary).map_err(AppError::io)?;
+    Ok(check)
+}
+
+/// 設定 block_docx 時，有未確認的檢查結果就不允許轉換 DOCX
+pub fn ensure_docx_allowed(report_path: &Path, config: &DeidConfig) -> Result<(), AppError> {
+    if !config.block_docx {
+        return Ok(());
+    }
+    match load_check(report_path) {
+        Some((_, check)) if check.needs_review() => Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.deid_unacknowledged",
+            &[("count", check.findings.len().to_string())],
+        )),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,166 @@
+// src-tauri/src/services/denoise.rs
+//
+// 降噪前處理：病房錄音常有空調等背景噪音，直接轉錄的辨識率明顯較差。
+// 以 FFmpeg afftdn 或 arnndn (RNNoise 模型隨安裝包放在 resources/denoise) 處理後輸出到 01b_cleaned。
+// 選擇 RNNoise 但找不到模型時改用 afftdn。
+
+use crate::services::ffmpeg_filter;
+use crate::services::file_manager;
+use crate::services::jobs::CancelToken;
+use crate::services::settings::{DenoiseConfig, DenoiseMethod};
+use crate::services::sidecar::Ffmpeg;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// 預設輸出位元率 (kbps)
+const DEFAULT_BITRATE_KBPS: u32 = 192;
+
+/// 安裝包內 RNNoise 模型所在的資料夾 (相對於 resource 目錄)
+const MODEL_DIR: &str = "denoise";
+
+/// 隨安裝包附帶的 RNNoise 模型 (.rnnn)，沒有時回傳 None
+pub fn bundled_model(app: &AppHandle) -> Option<PathBuf> {
+    let dir = app.path().resource_dir().ok()?.join(MODEL_DIR);
+    let mut models: Vec<PathBuf> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.extension()
+                .map(|e| e.eq_ignore_ascii_case("rnnn"))
+                .unwrap_or(false)
+        })
+        .collect();
+    models.sort();
+    models.into_iter().next()
+}
+
+pub struct Denoiser {
+    cancel: Option<CancelToken>,
+    config: DenoiseConfig,
+    model: Option<PathBuf>,
+    bitrate_kbps: u32,
+}
+
+impl Denoiser {
+    pub fn new(config: DenoiseConfig) -> Self {
+        Self {
+            cancel: None,
+            config,
+            model: None,
+            bitrate_kbps: DEFAULT_BITRATE_KBPS,
+        }
+    }
+
+    /// RNNoise 模型路徑
+    pub fn with_model(mut self, model: Option<PathBuf>) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// 設定輸出位元率 (kbps)，對應設定中的 FFmpeg 轉檔品質
+    pub fn with_bitrate(mut self, kbps: u32) -> Self {
+        self.bitrate_kbps = kbps;
+        self
+    }
+
+    /// 綁定工作的取消旗標，取消時中止 FFmpeg
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 依設定組出 FFmpeg 音訊濾鏡
+    fn filter(&self) -> Result<String, String> {
+        let nr = ffmpeg_filter::number(self.config.strength_db as f64, 0.01, 97.0, 2)?;
+        let afftdn = format!("afftdn=nr={}:nf=-50", nr);
+        Ok(match (self.config.method, &self.model) {
+            (DenoiseMethod::Rnnoise, Some(model)) => {
+                format!("arnndn=m={}", ffmpeg_filter::path_value(model))
+            }
+            (DenoiseMethod::Rnnoise, None) => {
+                tracing::warn!("找不到 RNNoise 模型，改用 afftdn 降噪");
+                afftdn
+            }
+            (DenoiseMethod::Afftdn, _) => afftdn,
+        })
+    }
+
+    /// 降噪單一檔案，輸出為 output_dir/<檔名>.mp3
+    /// 回傳 Ok(輸出檔案路徑) 或 Err(錯誤訊息)
+    pub async fn denoise(
+        &self,
+        ffmpeg: &Ffmpeg,
+        input_path: &str,
+        output_dir: &str,
+    ) -> Result<String, String> {
+        let output_path = file_manager::derived_output_path(
+            Path::new(input_path),
+            Path::new(output_dir),
+            "",
+            "mp3",
+        )
+        .ok_or("無法取得檔案名稱")?
+        .to_string_lossy()
+        .to_string();
+        if Path::new(&output_path) == Path::new(input_path) {
+            return Err("輸出檔案與來源相同，請選擇其他階段的檔案".to_string());
+        }
+
+        let filter = self.filter()?;
+        tracing::info!("正在降噪 ({}): {} -> {}", filter, input_path, output_path);
+        render_mp3(
+            ffmpeg,
+            self.cancel.as_ref(),
+            input_path,
+            &output_path,
+            &filter,
+            self.bitrate_kbps,
+        )
+        .await
+    }
+}
+
+/// 套用音訊濾鏡並輸出為 MP3 (降噪與人聲強化共用)
+pub(crate) async fn render_mp3(
+    ffmpeg: &Ffmpeg,
+    cancel: Option<&CancelToken>,
+    input_path: &str,
+    output_path: &str,
+    filter: &str,
+    bitrate_kbps: u32,
+) -> Result<String, String> {
+    if let Some(parent) = Path::new(output_path).parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("無法建立輸出目錄: {}", e))?;
+    }
+    let bitrate = format!("{}k", bitrate_kbps);
+    let output = ffmpeg
+        .run(
+            [
+                "-i",
+                input_path,
+                "-vn",
+                "-af",
+                filter,
+                "-acodec",
+                "libmp3lame",
+                "-ab",
+                &bitrate,
+                "-y",
+                output_path,
+            ],
+            cancel,
+        )
+        .await?;
+
+    if output.success() {
+        Ok(output_path.to_string())
+    } else {
+        Err(format!(
+            "FFmpeg 濾鏡處理失敗 (Exit Code: {})。\nStderr: {}",
+            output.code.unwrap_or(-1),
+            output.stderr
+        ))
+    }
+}
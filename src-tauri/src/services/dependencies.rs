@@ -0,0 +1,142 @@
+// src-tauri/src/services/dependencies.rs
+//
+// 外部相依元件檢查 (FFmpeg、Pandoc)。
+// Sidecar 遺失時也會使用使用者手動放在 app data 目錄 (bin/ffmpeg) 的 FFmpeg。
+
+use crate::services::dictaphone::{self, FormatSupport};
+use crate::services::sidecar::{self, Ffmpeg};
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::{AppHandle, Manager};
+
+/// 與 tauri.conf.json 的 identifier 相同，CLI 模式用來推算 app data 目錄
+pub(crate) const APP_IDENTIFIER: &str = "com.jason.stt-agent-rust";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyStatus {
+    pub name: String,
+    pub available: bool,
+    pub version: Option<String>,
+    pub path: Option<String>,
+    /// "sidecar" / "installed" / "system"
+    pub source: Option<String>,
+    pub error: Option<String>,
+}
+
+impl DependencyStatus {
+    fn missing(name: &str, error: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            available: false,
+            version: None,
+            path: None,
+            source: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DependencyReport {
+    pub ffmpeg: DependencyStatus,
+    /// 報告轉換為 DOCX 時使用
+    pub pandoc: DependencyStatus,
+    /// 錄音筆專用格式 (DSS / DS2) 是否可以轉檔
+    pub dictaphone: Vec<FormatSupport>,
+}
+
+/// 手動安裝的 FFmpeg 放置位置: <app data>/bin/ffmpeg
+pub fn installed_ffmpeg_path(app: &AppHandle) -> Option<PathBuf> {
+    let path = app
+        .path()
+        .app_data_dir()
+        .ok()?
+        .join("bin")
+        .join(sidecar::executable_name("ffmpeg"));
+    path.exists().then_some(path)
+}
+
+/// 沒有 AppHandle 時 (CLI) 尋找手動安裝的 FFmpeg
+pub fn installed_ffmpeg_path_headless() -> Option<PathBuf> {
+    let path = dirs::data_dir()?
+        .join(APP_IDENTIFIER)
+        .join("bin")
+        .join(sidecar::executable_name("ffmpeg"));
+    path.exists().then_some(path)
+}
+
+/// 檢查所有相依元件
+pub async fn check_dependencies(app: &AppHandle) -> DependencyReport {
+    DependencyReport {
+        ffmpeg: check_ffmpeg(app).await,
+        pandoc: check_pandoc().await,
+        dictaphone: dictaphone::support(&Ffmpeg::from(app)).await,
+    }
+}
+
+/// 實際執行 `ffmpeg -version` 確認可用
+pub async fn check_ffmpeg(app: &AppHandle) -> DependencyStatus {
+    let (path, source) = match sidecar::bundled_ffmpeg_path() {
+        Some(path) => (path, "sidecar"),
+        None => match installed_ffmpeg_path(app) {
+            Some(path) => (path, "installed"),
+            None => return DependencyStatus::missing("ffmpeg", "找不到 FFmpeg Sidecar"),
+        },
+    };
+
+    match sidecar::run_ffmpeg(app, ["-version"], None).await {
+        Ok(output) if output.success() => DependencyStatus {
+            name: "ffmpeg".to_string(),
+            available: true,
+            version: parse_version(&output.stdout, "ffmpeg version"),
+            path: Some(path.to_string_lossy().to_string()),
+            source: Some(source.to_string()),
+            error: None,
+        },
+        Ok(output) => DependencyStatus {
+            path: Some(path.to_string_lossy().to_string()),
+            source: Some(source.to_string()),
+            ..DependencyStatus::missing(
+                "ffmpeg",
+                format!(
+                    "FFmpeg 無法執行 (Exit Code: {:?}): {}",
+                    output.code, output.stderr
+                ),
+            )
+        },
+        Err(e) => DependencyStatus {
+            path: Some(path.to_string_lossy().to_string()),
+            source: Some(source.to_string()),
+            ..DependencyStatus::missing("ffmpeg", e)
+        },
+    }
+}
+
+pub async fn check_pandoc() -> DependencyStatus {
+    match tokio::process::Command::new("pandoc")
+        .arg("--version")
+        .output()
+        .await
+    {
+        Ok(output) if output.status.success() => DependencyStatus {
+            name: "pandoc".to_string(),
+            available: true,
+            version: parse_version(&String::from_utf8_lossy(&output.stdout), "pandoc"),
+            path: None,
+            source: Some("system".to_string()),
+            error: None,
+        },
+        Ok(output) => DependencyStatus::missing(
+            "pandoc",
+            String::from_utf8_lossy(&output.stderr).to_string(),
+        ),
+        Err(e) => DependencyStatus::missing("pandoc", format!("找不到 Pandoc: {}", e)),
+    }
+}
+
+/// 從版本輸出的第一行取出版本號，例如 "ffmpeg version 6.1 Copyright..." → "6.1"
+fn parse_version(output: &str, prefix: &str) -> Option<String> {
+    let first_line = output.lines().next()?;
+    let rest = first_line.strip_prefix(prefix)?.trim();
+    rest.split_whitespace().next().map(|v| v.to_string())
+}
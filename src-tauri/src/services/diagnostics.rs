@@ -0,0 +1,203 @@
+// src-tauri/src/services/diagnostics.rs
+//
+// 當機報告與診斷資料：
+// - panic 時把訊息、backtrace、最近的日誌與版本資訊寫到 app data 的 crashes/
+// - 匯出診斷壓縮檔 (當機報告 + 日誌 + 去除機密的設定)，讓使用者附在問題回報中
+
+use crate::services::{logging, settings};
+use serde::Serialize;
+use serde_json::Value;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+pub const CRASH_DIR_NAME: &str = "crashes";
+
+/// 當機報告附帶的日誌行數
+const CRASH_LOG_LINES: usize = 200;
+
+/// 設定中名稱含有這些字的欄位視為機密，匯出時遮蔽
+const SECRET_KEY_HINTS: &[&str] = &["key", "token", "secret", "password"];
+
+static APP_DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+static APP_VERSION: OnceLock<String> = OnceLock::new();
+
+#[derive(Debug, Serialize)]
+struct CrashReport {
+    timestamp: String,
+    app_version: String,
+    os: String,
+    arch: String,
+    thread: String,
+    message: String,
+    location: Option<String>,
+    backtrace: String,
+    log_tail: Vec<String>,
+}
+
+/// 安裝 panic hook；保留原本的 hook (輸出到 stderr)
+pub fn install_panic_hook(app_data_dir: &Path, app_version: &str) {
+    let _ = APP_DATA_DIR.set(app_data_dir.to_path_buf());
+    let _ = APP_VERSION.set(app_version.to_string());
+
+    let previous = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| info.payload().downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic".to_string());
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()));
+
+        tracing::error!(
+            "panic: {} ({})",
+            message,
+            location.as_deref().unwrap_or("?")
+        );
+        if let Err(e) = write_crash_report(message, location) {
+            eprintln!("無法寫入當機報告: {}", e);
+        }
+        previous(info);
+    }));
+}
+
+fn write_crash_report(message: String, location: Option<String>) -> Result<PathBuf, String> {
+    let dir = crash_dir().ok_or("app data 目錄未設定")?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let now = chrono::Local::now();
+    let report = CrashReport {
+        timestamp: now.to_rfc3339(),
+        app_version: APP_VERSION.get().cloned().unwrap_or_default(),
+        os: std::env::consts::OS.to_string(),
+        arch: std::env::consts::ARCH.to_string(),
+        thread: std::thread::current()
+            .name()
+            .unwrap_or("unnamed")
+            .to_string(),
+        message,
+        location,
+        backtrace: std::backtrace::Backtrace::force_capture().to_string(),
+        log_tail: logging::tail(CRASH_LOG_LINES).unwrap_or_default(),
+    };
+
+    let path = dir.join(format!("crash-{}.json", now.format("%Y%m%d-%H%M%S")));
+    let content = serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?;
+    fs::write(&path, content).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+fn crash_dir() -> Option<PathBuf> {
+    APP_DATA_DIR.get().map(|dir| dir.join(CRASH_DIR_NAME))
+}
+
+/// 匯出診斷壓縮檔，回傳壓縮檔路徑
+/// 內容：crashes/ 當機報告、logs/ 日誌、config.json (機密欄位已遮蔽)、system.json
+pub fn export_bundle(output_path: &Path) -> Result<PathBuf, String> {
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("無法建立輸出資料夾: {}", e))?;
+    }
+    let file = fs::File::create(output_path).map_err(|e| format!("無法建立壓縮檔: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let mut add = |name: &str, content: &[u8]| -> Result<(), String> {
+        zip.start_file(name, options)
+            .map_err(|e| format!("無法寫入壓縮檔: {}", e))?;
+        zip.write_all(content)
+            .map_err(|e| format!("無法寫入壓縮檔: {}", e))
+    };
+
+    for (name, path) in files_in(crash_dir().as_deref()) {
+        if let Ok(content) = fs::read(&path) {
+            add(&format!("{}/{}", CRASH_DIR_NAME, name), &content)?;
+        }
+    }
+    for (name, path) in files_in(logging::log_dir()) {
+        if let Ok(content) = fs::read(&path) {
+            add(&format!("{}/{}", logging::LOG_DIR_NAME, name), &content)?;
+        }
+    }
+
+    let mut config = serde_json::to_value(settings::load()).map_err(|e| e.to_string())?;
+    strip_secrets(&mut config);
+    add(
+        "config.json",
+        serde_json::to_string_pretty(&config)
+            .map_err(|e| e.to_string())?
+            .as_bytes(),
+    )?;
+
+    let system = serde_json::json!({
+        "app_version": APP_VERSION.get(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "exported_at": chrono::Local::now().to_rfc3339(),
+    });
+    add(
+        "system.json",
+        serde_json::to_string_pretty(&system)
+            .map_err(|e| e.to_string())?
+            .as_bytes(),
+    )?;
+
+    zip.finish().map_err(|e| format!("無法完成壓縮檔: {}", e))?;
+    Ok(output_path.to_path_buf())
+}
+
+/// 預設的匯出位置: 下載資料夾/stt-agent-diagnostics-<時間>.zip
+pub fn default_bundle_path() -> PathBuf {
+    let dir = dirs::download_dir()
+        .or_else(dirs::home_dir)
+        .unwrap_or_else(|| PathBuf::from("."));
+    dir.join(format!(
+        "stt-agent-diagnostics-{}.zip",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
+
+fn files_in(dir: Option<&Path>) -> Vec<(String, PathBuf)> {
+    let Some(entries) = dir.and_then(|d| fs::read_dir(d).ok()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(String, PathBuf)> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .filter_map(|p| Some((p.file_name()?.to_string_lossy().to_string(), p)))
+        .collect();
+    files.sort();
+    files
+}
+
+/// 遮蔽機密欄位，以及網址中的帳號密碼
+fn strip_secrets(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, field) in map.iter_mut() {
+                let lower = key.to_lowercase();
+                if SECRET_KEY_HINTS.iter().any(|hint| lower.contains(hint)) && !field.is_null() {
+                    *field = Value::String("<redacted>".to_string());
+                } else {
+                    strip_secrets(field);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(strip_secrets),
+        Value::String(text) => {
+            if let Ok(mut url) = tauri::Url::parse(text) {
+                if !url.username().is_empty() || url.password().is_some() {
+                    let _ = url.set_username("");
+                    let _ = url.set_password(None);
+                    *text = url.to_string();
+                }
+            }
+        }
+        _ => {}
+    }
+}
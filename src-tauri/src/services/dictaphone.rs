@@ -0,0 +1,188 @@
+// src-tauri/src/services/dictaphone.rs
+//
+// 錄音筆專用格式 (Olympus / Philips 的 DSS、DS2)：匯入時依檔頭辨識，轉檔時選擇可用的解碼方式。
+//
+// - DSS：FFmpeg 內建解碼器 (dss_sp)，一般的 FFmpeg 即可轉檔
+// - DS2 (DSS Pro)：官方 FFmpeg 沒有解碼器，需要支援的 FFmpeg 版本，或設定 decode 外掛先轉成 WAV
+// - 轉檔前先以目前的 FFmpeg 試解開頭一秒，無法解碼時改用 decode 外掛 (見 services::plugins)
+// - 都不可用時回報明確的原因，而不是 FFmpeg 的錯誤輸出；加密的 DS2 需先以廠商軟體解密
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::jobs::CancelToken;
+use crate::services::plugins;
+use crate::services::sidecar::Ffmpeg;
+use serde::Serialize;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DictaphoneFormat {
+    Dss,
+    Ds2,
+}
+
+impl DictaphoneFormat {
+    pub fn label(self) -> &'static str {
+        match self {
+            DictaphoneFormat::Dss => "DSS",
+            DictaphoneFormat::Ds2 => "DS2",
+        }
+    }
+
+    /// FFmpeg 中對應的解碼器 (官方版本沒有 DS2 解碼器，只能以實際試解判斷)
+    fn ffmpeg_decoders(self) -> &'static [&'static str] {
+        match self {
+            DictaphoneFormat::Dss => &["dss_sp"],
+            DictaphoneFormat::Ds2 => &[],
+        }
+    }
+}
+
+/// 轉換能力 (相依元件檢查時回報)
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatSupport {
+    pub format: DictaphoneFormat,
+    /// 目前的 FFmpeg 有對應的解碼器
+    pub ffmpeg: bool,
+    /// 已啟用的 decode 外掛
+    pub plugins: Vec<String>,
+    pub available: bool,
+}
+
+/// 依檔頭辨識 (第 2~4 個位元組為 "dss" 或 "ds2")，無法讀取時依副檔名
+pub fn detect(path: &Path) -> Option<DictaphoneFormat> {
+    let mut header = [0u8; 4];
+    if File::open(path)
+        .and_then(|mut file| file.read_exact(&mut header))
+        .is_ok()
+    {
+        match &header[1..] {
+            b"dss" => return Some(DictaphoneFormat::Dss),
+            b"ds2" => return Some(DictaphoneFormat::Ds2),
+            _ => {}
+        }
+    }
+    match path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .as_deref()
+    {
+        Some("dss") => Some(DictaphoneFormat::Dss),
+        Some("ds2") => Some(DictaphoneFormat::Ds2),
+        _ => None,
+    }
+}
+
+/// 各格式目前可用的轉換方式
+pub async fn support(ffmpeg: &Ffmpeg) -> Vec<FormatSupport> {
+    let decoders = match ffmpeg.run(["-hide_banner", "-decoders"], None).await {
+        Ok(output) if output.success() => output.stdout,
+        _ => String::new(),
+    };
+    let has_decoder = |name: &str| {
+        decoders
+            .lines()
+            .any(|line| line.split_whitespace().nth(1) == Some(name))
+    };
+    let plugins = plugins::decoders();
+    [DictaphoneFormat::Dss, DictaphoneFormat::Ds2]
+        .into_iter()
+        .map(|format| {
+            let ffmpeg = format.ffmpeg_decoders().iter().any(|d| has_decoder(d));
+            FormatSupport {
+                format,
+                ffmpeg,
+                plugins: plugins.clone(),
+                available: ffmpeg || !plugins.is_empty(),
+            }
+        })
+        .collect()
+}
+
+/// 以目前的 FFmpeg 試解開頭一秒
+async fn ffmpeg_decodes(
+    ffmpeg: &Ffmpeg,
+    path: &Path,
+    cancel: &CancelToken,
+) -> Result<bool, AppError> {
+    let input = path.to_string_lossy().to_string();
+    let args = [
+        "-v",
+        "error",
+        "-i",
+        input.as_str(),
+        "-t",
+        "1",
+        "-f",
+        "null",
+        "-",
+    ];
+    let result = ffmpeg.run(args, Some(cancel)).await;
+    if cancel.is_cancelled() {
+        return Err(AppError::cancelled());
+    }
+    Ok(result.is_ok_and(|output| output.success()))
+}
+
+/// decode 外掛轉出的暫存 WAV，結束時 (drop) 刪除
+pub struct DecodedFile {
+    dir: PathBuf,
+    pub path: PathBuf,
+}
+
+impl Drop for DecodedFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// 暫存 WAV 與原檔同名 (轉檔輸出的 MP3 檔名因此不變)
+fn decoded_file(path: &Path) -> Result<DecodedFile, AppError> {
+    let stem = path
+        .file_stem()
+        .ok_or_else(|| AppError::invalid_input(path.display().to_string()))?;
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    let id = format!("{}-{}", std::process::id(), nanos);
+    let dir = std::env::temp_dir().join("stt_agent_decode").join(id);
+    std::fs::create_dir_all(&dir)?;
+    let mut name = stem.to_os_string();
+    name.push(".wav");
+    Ok(DecodedFile {
+        path: dir.join(name),
+        dir,
+    })
+}
+
+/// 轉檔前的準備：FFmpeg 可直接解碼時回傳 None，否則以 decode 外掛轉成暫存 WAV
+pub async fn prepare(
+    ffmpeg: &Ffmpeg,
+    format: DictaphoneFormat,
+    path: &Path,
+    root: Option<&Path>,
+    cancel: &CancelToken,
+) -> Result<Option<DecodedFile>, AppError> {
+    if ffmpeg_decodes(ffmpeg, path, cancel).await? {
+        return Ok(None);
+    }
+    if plugins::decoders().is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::Unsupported,
+            "error.dictaphone_unsupported",
+            &[("format", format.label().to_string())],
+        ));
+    }
+    let decoded = decoded_file(path)?;
+    let plugin = plugins::decode(path, &decoded.path, root, cancel).await?;
+    tracing::info!(
+        "以外掛 {} 轉換 {} 錄音: {}",
+        plugin,
+        format.label(),
+        path.display()
+    );
+    Ok(Some(decoded))
+}
@@ -0,0 +1,136 @@
+// src-tauri/src/services/downmix.rs
+//
+// 立體聲智慧轉單聲道：分析左右聲道，判斷錄音是
+// - 雙單聲道 (左右相同)：取平均，不損失任何內容
+// - 單邊錄音 (只有左邊接麥克風)：只取有聲音的那一邊，避免平均後音量減半並混入另一邊的底噪
+// - 真正的立體聲：保留立體聲
+// 轉檔時依分析結果選擇 FFmpeg 參數，而不是一律平均。
+
+use crate::services::probe;
+use serde::Serialize;
+
+/// 一邊的音量低於另一邊的此比例 (約 -30 dB) 視為沒有接麥克風
+const SILENT_SIDE_RATIO: f64 = 0.03;
+/// 絕對音量低於此值的聲道視為無聲
+const SILENT_RMS: f64 = 1e-4;
+/// 左右差異的音量低於較大聲道的此比例 (約 -26 dB) 視為雙單聲道
+const DUAL_MONO_RATIO: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChannelLayout {
+    Mono,
+    DualMono,
+    Stereo,
+    LeftOnly,
+    RightOnly,
+}
+
+/// 轉檔時採用的聲道處理
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Downmix {
+    /// 保留原本的聲道
+    Keep,
+    Left,
+    Right,
+    Average,
+}
+
+impl Downmix {
+    /// 對應的 FFmpeg 參數
+    pub fn ffmpeg_args(self) -> &'static [&'static str] {
+        match self {
+            Downmix::Keep => &[],
+            Downmix::Left => &["-af", "pan=mono|c0=c0"],
+            Downmix::Right => &["-af", "pan=mono|c0=c1"],
+            Downmix::Average => &["-ac", "1"],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChannelAnalysis {
+    pub channels: u16,
+    pub layout: ChannelLayout,
+    pub downmix: Downmix,
+    /// 左右聲道的 RMS (dBFS)，單聲道時兩者相同
+    pub left_db: f64,
+    pub right_db: f64,
+    /// 左右聲道的相關係數 (-1 ~ 1)
+    pub correlation: f64,
+}
+
+fn to_db(rms: f64) -> f64 {
+    if rms > 0.0 {
+        20.0 * rms.log10()
+    } else {
+        -120.0
+    }
+}
+
+/// 分析音檔的聲道配置 (會解碼整個檔案，請在背景執行緒呼叫)
+pub fn analyze(path: &str) -> Result<ChannelAnalysis, String> {
+    let mut sum_l = 0.0f64;
+    let mut sum_r = 0.0f64;
+    let mut sum_lr = 0.0f64;
+    let mut sum_diff = 0.0f64;
+    let mut frames = 0u64;
+
+    let info = probe::decode_samples(path, |block, info| {
+        let channels = info.channels as usize;
+        if channels < 2 {
+            return;
+        }
+        for frame in block.chunks_exact(channels) {
+            let l = frame[0] as f64;
+            let r = frame[1] as f64;
+            sum_l += l * l;
+            sum_r += r * r;
+            sum_lr += l * r;
+            sum_diff += (l - r) * (l - r);
+        }
+        frames += (block.len() / channels) as u64;
+    })?;
+
+    if info.channels < 2 || frames == 0 {
+        return Ok(ChannelAnalysis {
+            channels: info.channels,
+            layout: ChannelLayout::Mono,
+            downmix: Downmix::Keep,
+            left_db: 0.0,
+            right_db: 0.0,
+            correlation: 1.0,
+        });
+    }
+
+    let n = frames as f64;
+    let rms_l = (sum_l / n).sqrt();
+    let rms_r = (sum_r / n).sqrt();
+    let rms_diff = (sum_diff / n).sqrt();
+    let correlation = if sum_l > 0.0 && sum_r > 0.0 {
+        sum_lr / (sum_l.sqrt() * sum_r.sqrt())
+    } else {
+        0.0
+    };
+    let louder = rms_l.max(rms_r);
+
+    let (layout, downmix) = if louder < SILENT_RMS || rms_diff <= louder * DUAL_MONO_RATIO {
+        (ChannelLayout::DualMono, Downmix::Average)
+    } else if rms_r < SILENT_RMS.max(rms_l * SILENT_SIDE_RATIO) {
+        (ChannelLayout::LeftOnly, Downmix::Left)
+    } else if rms_l < SILENT_RMS.max(rms_r * SILENT_SIDE_RATIO) {
+        (ChannelLayout::RightOnly, Downmix::Right)
+    } else {
+        (ChannelLayout::Stereo, Downmix::Keep)
+    };
+
+    Ok(ChannelAnalysis {
+        channels: info.channels,
+        layout,
+        downmix,
+        left_db: to_db(rms_l),
+        right_db: to_db(rms_r),
+        correlation,
+    })
+}
@@ -0,0 +1,658 @@
+// src-tauri/src/services/encryption.rs
+//
+// 專案加密 (選用)：03_silence 與 04_report 的檔案以專案密碼加密後保存，
+// 筆電遺失時病患資料 (PHI) 不會外洩。
+//
+// - 金鑰由密碼經 Argon2id 推導，設定 (salt、參數、驗證用密文) 存在專案的 .encryption.json
+// - 檔案以 AES-256-GCM 分段加密 (STREAM 結構，每段 1 MiB)，存為 <原檔名>.enc
+// - 解鎖後金鑰只保存在記憶體 (EncryptionKeys)；播放與生成報告時解密到暫存資料夾
+//   (使用者快取目錄下，權限 0700)，鎖定專案、啟動與結束程式時刪除暫存資料夾
+// - 啟用時一併加密子資料夾 (報告實驗) 與 .backups 內的備份；之後寫入 04_report 的檔案
+//   (字幕、時間軸) 以 write_protected 直接寫成加密檔
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::backup;
+use crate::services::dependencies::APP_IDENTIFIER;
+use crate::services::file_manager::{write_atomic, ProjectPaths};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+pub const ENCRYPTION_FILE_NAME: &str = ".encryption.json";
+
+/// 加密檔的副檔名 (附加在原檔名之後)
+pub const ENCRYPTED_EXTENSION: &str = "enc";
+
+const MAGIC: &[u8; 8] = b"STTENC1\0";
+const NONCE_PREFIX_LEN: usize = 7;
+const CHUNK_SIZE: usize = 1024 * 1024;
+const TAG_LEN: usize = 16;
+/// 用來驗證密碼的已知明文
+const CHECK_PLAINTEXT: &[u8] = b"stt-agent-project-key";
+
+pub type ProjectKey = [u8; 32];
+
+/// 已解鎖專案的金鑰 (專案根目錄 → 金鑰)
+#[derive(Default)]
+pub struct EncryptionKeys(Mutex<HashMap<PathBuf, ProjectKey>>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EncryptionConfig {
+    version: u32,
+    /// Argon2id 的 salt 與參數
+    salt: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    /// 以金鑰加密 CHECK_PLAINTEXT 的結果，用來判斷密碼是否正確
+    check: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EncryptionStatus {
+    pub enabled: bool,
+    pub unlocked: bool,
+}
+
+fn config_path(root: &Path) -> PathBuf {
+    root.join(ENCRYPTION_FILE_NAME)
+}
+
+fn load_config(root: &Path) -> Option<EncryptionConfig> {
+    fs::read_to_string(config_path(root))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+}
+
+/// 專案是否已啟用加密
+pub fn is_enabled(root: &Path) -> bool {
+    config_path(root).is_file()
+}
+
+/// 是否為加密檔
+pub fn is_encrypted_file(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.eq_ignore_ascii_case(ENCRYPTED_EXTENSION))
+        .unwrap_or(false)
+}
+
+/// 加密檔的路徑 (<原檔名>.enc)
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(ENCRYPTED_EXTENSION);
+    path.with_file_name(name)
+}
+
+/// 加密檔對應的原檔名
+fn plain_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Vec<u8>> {
+    (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+fn derive_key(passphrase: &str, config: &EncryptionConfig) -> Result<ProjectKey, AppError> {
+    let salt =
+        from_hex(&config.salt).ok_or_else(|| AppError::internal("加密設定的 salt 格式錯誤"))?;
+    let params = Params::new(
+        config.memory_kib,
+        config.iterations,
+        config.parallelism,
+        Some(32),
+    )
+    .map_err(|e| AppError::internal(format!("加密參數錯誤: {}", e)))?;
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+        .map_err(|e| AppError::internal(format!("無法推導金鑰: {}", e)))?;
+    Ok(key)
+}
+
+fn cipher(key: &ProjectKey) -> Aes256Gcm {
+    Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key))
+}
+
+/// STREAM nonce：前綴 (7) + 段落編號 (4, big endian) + 是否為最後一段 (1)
+fn chunk_nonce(prefix: &[u8], index: u32, last: bool) -> [u8; 12] {
+    let mut nonce = [0u8; 12];
+    nonce[..NONCE_PREFIX_LEN].copy_from_slice(prefix);
+    nonce[NONCE_PREFIX_LEN..11].copy_from_slice(&index.to_be_bytes());
+    nonce[11] = last as u8;
+    nonce
+}
+
+fn crypto_error(path: &Path) -> AppError {
+    AppError::localized(
+        ErrorKind::InvalidInput,
+        "error.decrypt_failed",
+        &[("path", path.to_string_lossy().to_string())],
+    )
+}
+
+/// 加密檔案內容，寫到 dest
+fn encrypt_to(key: &ProjectKey, source: &Path, dest: &Path) -> Result<(), AppError> {
+    let input = fs::File::open(source)?;
+    let total = input.metadata()?.len() as usize;
+    encrypt_stream(key, input, total, dest)
+}
+
+/// 加密 total 位元組的輸入，寫到 dest
+/// 暫存檔與資料夾都同步到磁碟後才改名，呼叫端之後刪除原檔時不會只剩下不完整的加密檔
+fn encrypt_stream(
+    key: &ProjectKey,
+    input: impl Read,
+    total: usize,
+    dest: &Path,
+) -> Result<(), AppError> {
+    let tmp = dest.with_extension("enc.tmp");
+    let result = write_sealed(key, input, total, &tmp)
+        .and_then(|()| fs::rename(&tmp, dest).map_err(AppError::from))
+        .and_then(|()| sync_dir(dest).map_err(AppError::from));
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    result
+}
+
+fn write_sealed(
+    key: &ProjectKey,
+    mut input: impl Read,
+    total: usize,
+    path: &Path,
+) -> Result<(), AppError> {
+    let cipher = cipher(key);
+    let mut prefix = [0u8; NONCE_PREFIX_LEN];
+    OsRng.fill_bytes(&mut prefix);
+
+    let chunks = total.div_ceil(CHUNK_SIZE).max(1);
+    let mut output = std::io::BufWriter::new(fs::File::create(path)?);
+    output.write_all(MAGIC)?;
+    output.write_all(&prefix)?;
+
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    for index in 0..chunks {
+        let len = (total - index * CHUNK_SIZE).min(CHUNK_SIZE);
+        input.read_exact(&mut buffer[..len])?;
+        let nonce = chunk_nonce(&prefix, index as u32, index + 1 == chunks);
+        let sealed = cipher
+            .encrypt(Nonce::from_slice(&nonce), &buffer[..len])
+            .map_err(|_| AppError::internal("加密失敗"))?;
+        output.write_all(&sealed)?;
+    }
+    output
+        .into_inner()
+        .map_err(|e| e.into_error())?
+        .sync_all()?;
+    Ok(())
+}
+
+/// 同步檔案所在的資料夾 (讓 rename 寫入磁碟)；Windows 無法開啟資料夾同步，略過
+fn sync_dir(path: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(parent) = path.parent() {
+        fs::File::open(parent)?.sync_all()?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+    Ok(())
+}
+
+/// 解密加密檔，寫到 dest；失敗時 (例如驗證失敗) 刪除已寫出的部分明文
+fn decrypt_to(key: &ProjectKey, source: &Path, dest: &Path) -> Result<(), AppError> {
+    let cipher = cipher(key);
+    let mut input = fs::File::open(source)?;
+    let mut header = [0u8; MAGIC.len() + NONCE_PREFIX_LEN];
+    input
+        .read_exact(&mut header)
+        .map_err(|_| crypto_error(source))?;
+    if &header[..MAGIC.len()] != MAGIC {
+        return Err(crypto_error(source));
+    }
+    let prefix = header[MAGIC.len()..].to_vec();
+
+    let body = input.metadata()?.len() as usize - header.len();
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let result = decrypt_body(&cipher, &prefix, &mut input, body, source, dest);
+    if result.is_err() {
+        let _ = fs::remove_file(dest);
+    }
+    result
+}
+
+fn decrypt_body(
+    cipher: &Aes256Gcm,
+    prefix: &[u8],
+    input: &mut impl Read,
+    body: usize,
+    source: &Path,
+    dest: &Path,
+) -> Result<(), AppError> {
+    let sealed_chunk = CHUNK_SIZE + TAG_LEN;
+    let chunks = body.div_ceil(sealed_chunk).max(1);
+    let mut output = std::io::BufWriter::new(fs::File::create(dest)?);
+    let mut buffer = vec![0u8; sealed_chunk];
+    for index in 0..chunks {
+        let len = (body - index * sealed_chunk).min(sealed_chunk);
+        input.read_exact(&mut buffer[..len])?;
+        let nonce = chunk_nonce(prefix, index as u32, index + 1 == chunks);
+        let plain = cipher
+            .decrypt(Nonce::from_slice(&nonce), &buffer[..len])
+            .map_err(|_| crypto_error(source))?;
+        output.write_all(&plain)?;
+    }
+    output.flush()?;
+    Ok(())
+}
+
+/// 為專案啟用加密並加密 03_silence、04_report 內既有的檔案，回傳加密的檔案數
+pub fn enable(keys: &EncryptionKeys, root: &Path, passphrase: &str) -> Result<usize, AppError> {
+    if is_enabled(root) {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.encryption_already_enabled",
+            &[],
+        ));
+    }
+    if passphrase.chars().count() < 8 {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.passphrase_too_short",
+            &[],
+        ));
+    }
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let mut config = EncryptionConfig {
+        version: 1,
+        salt: to_hex(&salt),
+        memory_kib: Params::DEFAULT_M_COST,
+        iterations: Params::DEFAULT_T_COST,
+        parallelism: Params::DEFAULT_P_COST,
+        check: String::new(),
+    };
+    let key = derive_key(passphrase, &config)?;
+    let mut nonce = [0u8; 12];
+    OsRng.fill_bytes(&mut nonce);
+    let check = cipher(&key)
+        .encrypt(Nonce::from_slice(&nonce), CHECK_PLAINTEXT)
+        .map_err(|_| AppError::internal("加密失敗"))?;
+    config.check = to_hex(&[nonce.as_slice(), &check].concat());
+
+    write_atomic(&config_path(root), &serde_json::to_vec_pretty(&config)?)?;
+    store_key(keys, root, key);
+    seal_project(&key, root)
+}
+
+/// 寫入受保護資料夾 (04_report) 的檔案：專案已啟用加密時直接寫成加密檔，不留下明文，
+/// 回傳實際寫入的路徑
+pub fn write_protected(
+    key: Option<&ProjectKey>,
+    path: &Path,
+    contents: &[u8],
+) -> Result<PathBuf, AppError> {
+    let Some(key) = key else {
+        write_atomic(path, contents)?;
+        return Ok(path.to_path_buf());
+    };
+    let target = encrypted_path(path);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    encrypt_stream(key, contents, contents.len(), &target)?;
+    Ok(target)
+}
+
+/// 以密碼解鎖專案
+pub fn unlock(keys: &EncryptionKeys, root: &Path, passphrase: &str) -> Result<(), AppError> {
+    let config = load_config(root).ok_or_else(|| {
+        AppError::localized(ErrorKind::InvalidInput, "error.encryption_not_enabled", &[])
+    })?;
+    let key = derive_key(passphrase, &config)?;
+    let check = from_hex(&config.check).filter(|c| c.len() > 12);
+    let valid = check
+        .map(|c| {
+            cipher(&key)
+                .decrypt(Nonce::from_slice(&c[..12]), &c[12..])
+                .map(|plain| plain == CHECK_PLAINTEXT)
+                .unwrap_or(false)
+        })
+        .unwrap_or(false);
+    if !valid {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.wrong_passphrase",
+            &[],
+        ));
+    }
+    store_key(keys, root, key);
+    Ok(())
+}
+
+/// 鎖定專案：忘記金鑰並刪除暫存的解密檔
+pub fn lock(keys: &EncryptionKeys, root: &Path) {
+    if let Ok(mut keys) = keys.0.lock() {
+        keys.remove(root);
+    }
+    let _ = fs::remove_dir_all(scratch_path(root));
+}
+
+pub fn status(keys: &EncryptionKeys, root: &Path) -> EncryptionStatus {
+    EncryptionStatus {
+        enabled: is_enabled(root),
+        unlocked: key_of(keys, root).is_some(),
+    }
+}
+
+fn store_key(keys: &EncryptionKeys, root: &Path, key: ProjectKey) {
+    if let Ok(mut keys) = keys.0.lock() {
+        keys.insert(root.to_path_buf(), key);
+    }
+}
+
+fn key_of(keys: &EncryptionKeys, root: &Path) -> Option<ProjectKey> {
+    keys.0.lock().ok()?.get(root).copied()
+}
+
+/// 專案的金鑰：未啟用加密時為 None，已啟用但尚未解鎖時回傳錯誤
+pub fn project_key(app: &AppHandle, root: &Path) -> Result<Option<ProjectKey>, AppError> {
+    if !is_enabled(root) {
+        return Ok(None);
+    }
+    key_of(&app.state::<EncryptionKeys>(), root)
+        .map(Some)
+        .ok_or_else(|| {
+            AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.project_locked",
+                &[("path", root.to_string_lossy().to_string())],
+            )
+        })
+}
+
+/// 加密資料夾內尚未加密的檔案 (原檔在加密完成後刪除)，回傳加密的檔案
+pub fn seal_dir(key: &ProjectKey, dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(Vec::new());
+    };
+    let mut sealed = Vec::new();
+    for path in entries.flatten().map(|e| e.path()) {
+        if !path.is_file() || is_encrypted_file(&path) {
+            continue;
+        }
+        let target = encrypted_path(&path);
+        encrypt_to(key, &path, &target)?;
+        fs::remove_file(&path)?;
+        sealed.push(target);
+    }
+    Ok(sealed)
+}
+
+/// 加密資料夾與其子資料夾內尚未加密的檔案，回傳加密的檔案數
+fn seal_tree(key: &ProjectKey, dir: &Path) -> Result<usize, AppError> {
+    let mut count = seal_dir(key, dir)?.len();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Ok(count);
+    };
+    for path in entries.flatten().map(|e| e.path()) {
+        if path.is_dir() {
+            count += seal_tree(key, &path)?;
+        }
+    }
+    Ok(count)
+}
+
+/// 加密專案中受保護的資料夾 (03_silence、04_report，含報告實驗) 與備份
+fn seal_project(key: &ProjectKey, root: &Path) -> Result<usize, AppError> {
+    let paths = ProjectPaths::from_existing_root(root.to_path_buf());
+    let mut count = 0;
+    for dir in [&paths.silence, &paths.report] {
+        count += seal_tree(key, dir)?;
+    }
+    // 備份是受保護檔案被覆寫前的副本；還原時加密檔回到原位置，仍可解密
+    count += seal_tree(key, &root.join(backup::BACKUP_DIR))?;
+    Ok(count)
+}
+
+/// 加密單一檔案到 dest_dir/<檔名>.enc，回傳加密檔路徑
+pub fn seal_file(key: &ProjectKey, source: &Path, dest_dir: &Path) -> Result<PathBuf, AppError> {
+    let target = encrypted_path(&dest_dir.join(source.file_name().unwrap_or_default()));
+    encrypt_to(key, source, &target)?;
+    Ok(target)
+}
+
+/// 暫存解密檔的上層資料夾 (使用者快取目錄下，不放在所有使用者共用的系統暫存目錄)
+fn scratch_base() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(APP_IDENTIFIER)
+        .join("scratch")
+}
+
+fn scratch_path(root: &Path) -> PathBuf {
+    let digest = Sha256::digest(root.to_string_lossy().as_bytes());
+    scratch_base().join(to_hex(&digest[..8]))
+}
+
+/// 專案的暫存資料夾 (依專案路徑區分)，建立後只有目前使用者可以存取
+pub fn scratch_dir(root: &Path) -> Result<PathBuf, AppError> {
+    let dir = scratch_path(root);
+    create_private_dir(&scratch_base())?;
+    create_private_dir(&dir)?;
+    Ok(dir)
+}
+
+/// 建立資料夾並限制為只有擁有者可存取 (已存在時也收緊權限)
+fn create_private_dir(dir: &Path) -> std::io::Result<()> {
+    let mut builder = fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// 刪除所有暫存的解密檔 (啟動時清除上次異常結束留下的檔案，結束時再清一次)
+pub fn clear_all_scratch() {
+    let _ = fs::remove_dir_all(scratch_base());
+    // 舊版放在系統暫存目錄
+    let _ = fs::remove_dir_all(std::env::temp_dir().join("stt_agent_scratch"));
+}
+
+/// 解密單一檔案到暫存資料夾，回傳暫存檔路徑
+pub fn open_file(app: &AppHandle, path: &Path) -> Result<PathBuf, AppError> {
+    let root = ProjectPaths::find_root(path).ok_or_else(|| crypto_error(path))?;
+    let key = project_key(app, &root)?.ok_or_else(|| crypto_error(path))?;
+    let dest = scratch_dir(&root)?.join("open").join(plain_name(path));
+    decrypt_to(&key, path, &dest)?;
+    Ok(dest)
+}
+
+/// 將資料夾 (含加密檔) 解密 / 複製到暫存資料夾，回傳暫存資料夾
+pub fn open_folder(key: &ProjectKey, root: &Path, folder: &Path) -> Result<PathBuf, AppError> {
+    let dest = scratch_dir(root)?.join(folder.file_name().unwrap_or_default());
+    let _ = fs::remove_dir_all(&dest);
+    fs::create_dir_all(&dest)?;
+    for path in fs::read_dir(folder)?.flatten().map(|e| e.path()) {
+        if !path.is_file() {
+            continue;
+        }
+        if is_encrypted_file(&path) {
+            decrypt_to(key, &path, &dest.join(plain_name(&path)))?;
+        } else if let Some(name) = path.file_name() {
+            fs::copy(&path, dest.join(name))?;
+        }
+    }
+    Ok(dest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "stt_agent_encryption_{}_{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn sample(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 31 % 251) as u8).collect()
+    }
+
+    /// 加密後解密，回傳解密結果
+    fn round_trip(dir: &Path, data: &[u8]) -> Vec<u8> {
+        let key = [7u8; 32];
+        let plain = dir.join("plain.bin");
+        fs::write(&plain, data).unwrap();
+        let sealed = seal_file(&key, &plain, dir).unwrap();
+        let opened = dir.join("opened.bin");
+        decrypt_to(&key, &sealed, &opened).unwrap();
+        fs::read(&opened).unwrap()
+    }
+
+    #[test]
+    fn round_trip_keeps_content() {
+        let dir = temp_dir("round_trip");
+        for len in [0, 1, CHUNK_SIZE, 2 * CHUNK_SIZE + 5] {
+            let data = sample(len);
+            assert_eq!(round_trip(&dir, &data), data, "length {}", len);
+        }
+        assert!(!dir.join("plain.bin.enc.tmp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn write_protected_leaves_no_plaintext() {
+        let dir = temp_dir("protected");
+        let key = [3u8; 32];
+        let path = dir.join("subtitles.srt");
+        let written =
+            write_protected(Some(&key), &path, b"1\n00:00:00,000 --> 00:00:01,000\n").unwrap();
+        assert_eq!(written, encrypted_path(&path));
+        assert!(!path.exists());
+        let opened = dir.join("opened.srt");
+        decrypt_to(&key, &written, &opened).unwrap();
+        assert!(fs::read_to_string(&opened).unwrap().starts_with("1\n"));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    /// 解密失敗時回傳錯誤且不留下部分明文
+    fn assert_rejected(key: &ProjectKey, sealed: &Path, dir: &Path) {
+        let opened = dir.join("rejected.bin");
+        let error = decrypt_to(key, sealed, &opened).unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InvalidInput);
+        assert!(!opened.exists());
+    }
+
+    #[test]
+    fn tampered_file_is_rejected() {
+        let dir = temp_dir("tamper");
+        let key = [9u8; 32];
+        let plain = dir.join("plain.bin");
+        fs::write(&plain, sample(2 * CHUNK_SIZE + 5)).unwrap();
+        let sealed = seal_file(&key, &plain, &dir).unwrap();
+
+        // 最後一段被改：前兩段已寫出，仍須刪除
+        let mut bytes = fs::read(&sealed).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        let tampered = dir.join("tampered.enc");
+        fs::write(&tampered, &bytes).unwrap();
+        assert_rejected(&key, &tampered, &dir);
+
+        // 錯誤的金鑰
+        assert_rejected(&[1u8; 32], &sealed, &dir);
+
+        // 不是加密檔
+        assert_rejected(&key, &plain, &dir);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn truncated_file_is_rejected() {
+        let dir = temp_dir("truncate");
+        let key = [5u8; 32];
+        let plain = dir.join("plain.bin");
+        fs::write(&plain, sample(2 * CHUNK_SIZE + 5)).unwrap();
+        let sealed = seal_file(&key, &plain, &dir).unwrap();
+        let bytes = fs::read(&sealed).unwrap();
+        let header = MAGIC.len() + NONCE_PREFIX_LEN;
+        let truncated = dir.join("truncated.enc");
+
+        // 少了最後一整段 (剩下的段落本身都完整)
+        fs::write(&truncated, &bytes[..header + 2 * (CHUNK_SIZE + TAG_LEN)]).unwrap();
+        assert_rejected(&key, &truncated, &dir);
+
+        // 截斷在段落中間
+        fs::write(&truncated, &bytes[..bytes.len() - 3]).unwrap();
+        assert_rejected(&key, &truncated, &dir);
+
+        // 只剩檔頭
+        fs::write(&truncated, &bytes[..header]).unwrap();
+        assert_rejected(&key, &truncated, &dir);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_dir_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+        let dir = temp_dir("private");
+        let scratch = dir.join("scratch").join("project");
+        create_private_dir(&scratch).unwrap();
+        let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&scratch), 0o700);
+        assert_eq!(mode(&dir.join("scratch")), 0o700);
+
+        // 已存在且權限較寬的資料夾也會收緊
+        fs::set_permissions(&scratch, fs::Permissions::from_mode(0o755)).unwrap();
+        create_private_dir(&scratch).unwrap();
+        assert_eq!(mode(&scratch), 0o700);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn seal_dir_replaces_plaintext() {
+        let dir = temp_dir("seal_dir");
+        let key = [2u8; 32];
+        fs::write(dir.join("report.md"), "病歷摘要").unwrap();
+        let sealed = seal_dir(&key, &dir).unwrap();
+        assert_eq!(sealed, vec![dir.join("report.md.enc")]);
+        assert!(!dir.join("report.md").exists());
+        // 已加密的檔案不再加密
+        assert!(seal_dir(&key, &dir).unwrap().is_empty());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,73 @@
+// src-tauri/src/services/enhance.rs
+//
+// 人聲強化：高通濾除低頻隆隆聲、壓縮拉近音量、去齒音，取代原本到 Audacity 手動處理的流程。
+// 輸出為 01b_cleaned/<檔名>_enhanced.mp3，原始檔保留，可在播放器的比較模式切換試聽。
+
+use crate::services::denoise::render_mp3;
+use crate::services::file_manager;
+use crate::services::jobs::CancelToken;
+use crate::services::sidecar::Ffmpeg;
+use std::path::Path;
+
+/// 預設輸出位元率 (kbps)
+const DEFAULT_BITRATE_KBPS: u32 = 192;
+
+/// 針對語音調整的濾鏡鏈：高通 → 壓縮 → 去齒音
+const SPEECH_FILTER: &str = "highpass=f=90:poles=2,\
+acompressor=threshold=-20dB:ratio=3:attack=10:release=150:makeup=3,\
+deesser=i=0.4:m=0.5:f=0.5";
+
+/// 輸出檔名的後綴
+const OUTPUT_SUFFIX: &str = "_enhanced";
+
+#[derive(Default)]
+pub struct SpeechEnhancer {
+    cancel: Option<CancelToken>,
+    bitrate_kbps: Option<u32>,
+}
+
+impl SpeechEnhancer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 設定輸出位元率 (kbps)，對應設定中的 FFmpeg 轉檔品質
+    pub fn with_bitrate(mut self, kbps: u32) -> Self {
+        self.bitrate_kbps = Some(kbps);
+        self
+    }
+
+    /// 綁定工作的取消旗標，取消時中止 FFmpeg
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 強化單一檔案，輸出為 output_dir/<檔名>_enhanced.mp3
+    pub async fn enhance(
+        &self,
+        ffmpeg: &Ffmpeg,
+        input_path: &str,
+        output_dir: &str,
+    ) -> Result<String, String> {
+        let output_path = file_manager::derived_output_path(
+            Path::new(input_path),
+            Path::new(output_dir),
+            OUTPUT_SUFFIX,
+            "mp3",
+        )
+        .ok_or("無法取得檔案名稱")?
+        .to_string_lossy()
+        .to_string();
+        tracing::info!("正在強化人聲: {} -> {}", input_path, output_path);
+        render_mp3(
+            ffmpeg,
+            self.cancel.as_ref(),
+            input_path,
+            &output_path,
+            SPEECH_FILTER,
+            self.bitrate_kbps.unwrap_or(DEFAULT_BITRATE_KBPS),
+        )
+        .await
+    }
+}
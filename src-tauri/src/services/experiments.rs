@@ -0,0 +1,338 @@
+// src-tauri/src/services/experiments.rs
+//
+// Prompt 實驗：同一個音檔以多組 Prompt / 模型各生成一次，比較輸出與 token 用量。
+//
+// - 結果寫入 <專案>/04_report/experiments/<時間>-<音檔名>/
+//   (不在專案內時寫入音檔旁的 experiments/)
+// - 每組的 Prompt 與輸出各存一個檔案，summary.md 列出用量與和第一組 (基準) 的差異
+// - experiment.json 保存完整結果，供之後比較
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::encryption;
+use crate::services::file_manager::{write_atomic, ProjectPaths};
+use crate::services::history;
+use crate::services::report::{PromptRun, TokenUsage, DEFAULT_MODEL, DEFAULT_PROMPT};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// 實驗結果的資料夾 (報告資料夾下)
+pub const EXPERIMENTS_DIR: &str = "experiments";
+
+/// 一組要比較的設定；model 與 Prompt 未指定時使用預設值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptVariant {
+    #[serde(default)]
+    pub label: Option<String>,
+    #[serde(default)]
+    pub model: Option<String>,
+    /// Prompt 內容 (優先於 prompt_path)
+    #[serde(default)]
+    pub prompt: Option<String>,
+    #[serde(default)]
+    pub prompt_path: Option<String>,
+}
+
+/// 與基準輸出的逐行差異
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffSummary {
+    pub baseline: String,
+    pub same_lines: usize,
+    pub added_lines: usize,
+    pub removed_lines: usize,
+    /// 0.0 ~ 1.0
+    pub similarity: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VariantResult {
+    pub label: String,
+    pub model: String,
+    pub prompt_file: String,
+    pub output_file: Option<String>,
+    pub usage: Option<TokenUsage>,
+    pub elapsed_ms: u64,
+    pub chars: usize,
+    pub error: Option<String>,
+    /// 與第一組的差異 (第一組與失敗的組為 None)
+    pub diff: Option<DiffSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Experiment {
+    pub created_at: String,
+    pub audio: String,
+    pub dir: String,
+    pub variants: Vec<VariantResult>,
+}
+
+/// 已決定模型與 Prompt 內容的一組設定
+pub struct ResolvedVariant {
+    pub label: String,
+    pub model: String,
+    pub prompt: String,
+}
+
+/// 檢查設定並讀入 Prompt；至少需要兩組
+pub fn resolve(
+    variants: &[PromptVariant],
+    default_model: Option<String>,
+) -> Result<Vec<ResolvedVariant>, AppError> {
+    if variants.len() < 2 {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.experiment_variants",
+            &[],
+        ));
+    }
+    let default_model = default_model.unwrap_or_else(|| DEFAULT_MODEL.to_string());
+    variants
+        .iter()
+        .enumerate()
+        .map(|(idx, variant)| {
+            let prompt = match (&variant.prompt, &variant.prompt_path) {
+                (Some(prompt), _) if !prompt.trim().is_empty() => prompt.clone(),
+                (_, Some(path)) if !path.is_empty() => {
+                    std::fs::read_to_string(path).map_err(|e| {
+                        AppError::localized(
+                            ErrorKind::Io,
+                            "error.prompt_read_failed",
+                            &[("detail", e.to_string())],
+                        )
+                    })?
+                }
+                _ => DEFAULT_PROMPT.to_string(),
+            };
+            Ok(ResolvedVariant {
+                label: variant
+                    .label
+                    .clone()
+                    .filter(|l| !l.trim().is_empty())
+                    .unwrap_or_else(|| variant_letter(idx)),
+                model: variant
+                    .model
+                    .clone()
+                    .filter(|m| !m.trim().is_empty())
+                    .unwrap_or_else(|| default_model.clone()),
+                prompt,
+            })
+        })
+        .collect()
+}
+
+/// A, B, C ... Z, V27, V28 ...
+fn variant_letter(idx: usize) -> String {
+    if idx < 26 {
+        ((b'A' + idx as u8) as char).to_string()
+    } else {
+        format!("V{}", idx + 1)
+    }
+}
+
+fn file_safe(text: &str) -> String {
+    text.trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// 實驗資料夾：<專案>/04_report/experiments/<時間>-<音檔名>
+pub fn experiment_dir(audio_path: &Path) -> Result<PathBuf, AppError> {
+    let base = match history::project_root_for(audio_path) {
+        Some(root) => {
+            if encryption::is_enabled(&root) {
+                return Err(AppError::localized(
+                    ErrorKind::Unsupported,
+                    "error.experiment_encrypted",
+                    &[],
+                ));
+            }
+            ProjectPaths::from_existing_root(root).report
+        }
+        None => audio_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    let stem = audio_path
+        .file_stem()
+        .map(|s| file_safe(&s.to_string_lossy()))
+        .unwrap_or_else(|| "audio".to_string());
+    Ok(base.join(EXPERIMENTS_DIR).join(format!(
+        "{}-{}",
+        chrono::Local::now().format("%Y%m%d-%H%M%S"),
+        stem
+    )))
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// 以最長共同子序列比較兩份輸出的非空白行
+fn diff(baseline_label: &str, baseline: &str, text: &str) -> DiffSummary {
+    let a = lines(baseline);
+    let b = lines(text);
+    let mut table = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            table[i][j] = if a[i] == b[j] {
+                table[i + 1][j + 1] + 1
+            } else {
+                table[i + 1][j].max(table[i][j + 1])
+            };
+        }
+    }
+    let same = table[0][0];
+    let total = a.len() + b.len();
+    DiffSummary {
+        baseline: baseline_label.to_string(),
+        same_lines: same,
+        added_lines: b.len() - same,
+        removed_lines: a.len() - same,
+        similarity: if total == 0 {
+            1.0
+        } else {
+            2.0 * same as f64 / total as f64
+        },
+    }
+}
+
+fn summary_markdown(experiment: &Experiment) -> String {
+    let audio = Path::new(&experiment.audio)
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut out = format!(
+        "# Prompt 實驗：{}\n\n時間: {}\n\n| 版本 | 模型 | 輸入 tokens | 輸出 tokens | 總 tokens | 字數 | 耗時 (秒) | 相似度 | 新增行 | 刪除行 |\n|---|---|---|---|---|---|---|---|---|---|\n",
+        audio, experiment.created_at
+    );
+    for variant in &experiment.variants {
+        let usage = variant.usage.unwrap_or_default();
+        let (similarity, added, removed) = match &variant.diff {
+            Some(diff) => (
+                format!("{:.0}%", diff.similarity * 100.0),
+                diff.added_lines.to_string(),
+                diff.removed_lines.to_string(),
+            ),
+            None => ("-".to_string(), "-".to_string(), "-".to_string()),
+        };
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {:.1} | {} | {} | {} |\n",
+            variant.label,
+            variant.model,
+            usage.prompt_tokens,
+            usage.output_tokens,
+            usage.total_tokens,
+            variant.chars,
+            variant.elapsed_ms as f64 / 1000.0,
+            similarity,
+            added,
+            removed
+        ));
+    }
+    if let Some(baseline) = experiment.variants.first() {
+        out.push_str(&format!(
+            "\n相似度與增減行數以 {} 為基準 (逐行比較，忽略空白行)\n",
+            baseline.label
+        ));
+    }
+
+    out.push_str("\n## 輸出檔案\n\n");
+    for variant in &experiment.variants {
+        match (&variant.output_file, &variant.error) {
+            (Some(file), _) => out.push_str(&format!(
+                "- {}: [{}]({}) (Prompt: [{}]({}))\n",
+                variant.label, file, file, variant.prompt_file, variant.prompt_file
+            )),
+            (None, Some(error)) => out.push_str(&format!(
+                "- {}: ⚠️ 失敗 — {}\n",
+                variant.label,
+                error.replace('\n', " ")
+            )),
+            (None, None) => {}
+        }
+    }
+    out
+}
+
+/// 寫入每組的 Prompt 與輸出、summary.md 與 experiment.json；回傳實驗結果與所有輸出檔
+pub fn save(
+    dir: &Path,
+    audio_path: &Path,
+    variants: &[ResolvedVariant],
+    runs: Vec<Result<PromptRun, String>>,
+) -> Result<(Experiment, Vec<PathBuf>), AppError> {
+    std::fs::create_dir_all(dir)?;
+    let mut produced = Vec::new();
+    let mut results: Vec<VariantResult> = Vec::new();
+    let mut baseline: Option<(String, String)> = None;
+
+    for (idx, (variant, run)) in variants.iter().zip(runs).enumerate() {
+        let name = format!("{:02}-{}", idx + 1, file_safe(&variant.label));
+        let prompt_file = format!("{}.prompt.txt", name);
+        write_atomic(&dir.join(&prompt_file), variant.prompt.as_bytes())?;
+        produced.push(dir.join(&prompt_file));
+
+        let result = match run {
+            Ok(run) => {
+                let output_file = format!("{}.md", name);
+                write_atomic(&dir.join(&output_file), run.text.as_bytes())?;
+                produced.push(dir.join(&output_file));
+                let diff = match &baseline {
+                    Some((label, text)) => Some(diff(label, text, &run.text)),
+                    None if idx == 0 => {
+                        baseline = Some((variant.label.clone(), run.text.clone()));
+                        None
+                    }
+                    None => None,
+                };
+                VariantResult {
+                    label: variant.label.clone(),
+                    model: variant.model.clone(),
+                    prompt_file,
+                    output_file: Some(output_file),
+                    usage: Some(run.usage),
+                    elapsed_ms: run.elapsed_ms,
+                    chars: run.text.chars().count(),
+                    error: None,
+                    diff,
+                }
+            }
+            Err(error) => VariantResult {
+                label: variant.label.clone(),
+                model: variant.model.clone(),
+                prompt_file,
+                output_file: None,
+                usage: None,
+                elapsed_ms: 0,
+                chars: 0,
+                error: Some(error),
+                diff: None,
+            },
+        };
+        results.push(result);
+    }
+
+    let experiment = Experiment {
+        created_at: chrono::Local::now().to_rfc3339(),
+        audio: audio_path.to_string_lossy().to_string(),
+        dir: dir.to_string_lossy().to_string(),
+        variants: results,
+    };
+    let summary = dir.join("summary.md");
+    write_atomic(&summary, summary_markdown(&experiment).as_bytes())?;
+    produced.push(summary);
+    let json = dir.join("experiment.json");
+    write_atomic(&json, &serde_json::to_vec_pretty(&experiment)?)?;
+    produced.push(json);
+    Ok((experiment, produced))
+}
@@ -0,0 +1,183 @@
+// src-tauri/src/services/ffmpeg_filter.rs
+//
+// 組出 FFmpeg 音訊濾鏡字串。時段、強度等數值來自逐字稿編輯或設定檔，
+// 一律先檢查再格式化，避免 NaN、負數或奇怪的字串讓濾鏡語法壞掉或被插入其他濾鏡。
+//
+// - 數值只接受有限值並限制範圍，輸出固定小數位數
+// - 字串參數 (模型路徑等) 依 FFmpeg 的兩層跳脫規則處理
+// - 消音、保留時段、淡入淡出、提示音共用這裡的檢查
+
+use std::path::Path;
+
+/// 時間參數上限 (秒)，超過一週的錄音視為資料錯誤
+const MAX_SECONDS: f64 = 7.0 * 24.0 * 3600.0;
+
+/// 提示音頻率範圍 (Hz)
+const BEEP_HZ: (f64, f64) = (20.0, 20_000.0);
+
+/// 淡入或淡出
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fade {
+    In,
+    Out,
+}
+
+/// 檢查數值在 [min, max] 內並以固定小數位數輸出
+pub fn number(value: f64, min: f64, max: f64, decimals: usize) -> Result<String, String> {
+    if !value.is_finite() {
+        return Err(format!("濾鏡參數不是有效數字: {}", value));
+    }
+    if value < min || value > max {
+        return Err(format!("濾鏡參數 {} 超出範圍 {} ~ {}", value, min, max));
+    }
+    Ok(format!("{:.*}", decimals, value))
+}
+
+/// 時間點 (秒，三位小數)
+pub fn seconds(value: f64) -> Result<String, String> {
+    number(value, 0.0, MAX_SECONDS, 3)
+}
+
+/// 多個時段組成 `between(t,a,b)+...` 表達式；時段必須 start < end
+fn between_expr(ranges: &[(f64, f64)]) -> Result<String, String> {
+    if ranges.is_empty() {
+        return Err("沒有指定時段".to_string());
+    }
+    let parts = ranges
+        .iter()
+        .map(|&(start, end)| {
+            let (s, e) = (seconds(start)?, seconds(end)?);
+            if start >= end {
+                return Err(format!("時段結束必須晚於開始: {} ~ {}", s, e));
+            }
+            Ok(format!("between(t,{},{})", s, e))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(parts.join("+"))
+}
+
+/// 將指定時段消音
+pub fn mute_ranges(ranges: &[(f64, f64)]) -> Result<String, String> {
+    Ok(format!(
+        "volume=enable='{}':volume=0",
+        between_expr(ranges)?
+    ))
+}
+
+/// 只保留指定時段並讓片段相連 (aselect 保留樣本，asetpts 重新計算時間)
+pub fn keep_ranges(ranges: &[(f64, f64)]) -> Result<String, String> {
+    Ok(format!(
+        "aselect='{}',asetpts=N/SR/TB",
+        between_expr(ranges)?
+    ))
+}
+
+/// 從 start 秒開始的淡入或淡出
+pub fn fade(kind: Fade, start: f64, duration: f64) -> Result<String, String> {
+    let t = match kind {
+        Fade::In => "in",
+        Fade::Out => "out",
+    };
+    Ok(format!(
+        "afade=t={}:st={}:d={}",
+        t,
+        seconds(start)?,
+        number(duration, 0.001, MAX_SECONDS, 3)?
+    ))
+}
+
+/// 在指定時段以提示音取代原音 (用於遮蔽個資)：原音消音後混入同時段的正弦波
+pub fn beep_ranges(ranges: &[(f64, f64)], frequency_hz: f64) -> Result<String, String> {
+    let expr = between_expr(ranges)?;
+    let freq = number(frequency_hz, BEEP_HZ.0, BEEP_HZ.1, 0)?;
+    Ok(format!(
+        "volume=enable='{expr}':volume=0[muted];\
+         sine=f={freq}:sample_rate=44100,volume=enable='not({expr})':volume=0[beep];\
+         [muted][beep]amix=inputs=2:duration=first:normalize=0"
+    ))
+}
+
+/// 跳脫濾鏡參數值：先跳脫參數層 (`\ ' :`)，再跳脫濾鏡圖層 (`\ ' [ ] , ;`)
+pub fn escape_value(value: &str) -> String {
+    let escape = |s: &str, special: &[char]| {
+        let mut out = String::with_capacity(s.len());
+        for c in s.chars() {
+            if special.contains(&c) {
+                out.push('\\');
+            }
+            out.push(c);
+        }
+        out
+    };
+    let option = escape(value, &['\\', '\'', ':']);
+    escape(&option, &['\\', '\'', '[', ']', ',', ';'])
+}
+
+/// 濾鏡參數中的路徑：統一使用 `/` 再跳脫
+pub fn path_value(path: &Path) -> String {
+    escape_value(&path.to_string_lossy().replace('\\', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mute_ranges_builds_volume_filter() {
+        assert_eq!(
+            mute_ranges(&[(1.0, 2.5), (10.25, 12.0)]).unwrap(),
+            "volume=enable='between(t,1.000,2.500)+between(t,10.250,12.000)':volume=0"
+        );
+    }
+
+    #[test]
+    fn keep_ranges_builds_select_filter() {
+        assert_eq!(
+            keep_ranges(&[(0.0, 3.0)]).unwrap(),
+            "aselect='between(t,0.000,3.000)',asetpts=N/SR/TB"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        assert!(mute_ranges(&[]).is_err());
+        assert!(mute_ranges(&[(f64::NAN, 1.0)]).is_err());
+        assert!(mute_ranges(&[(0.0, f64::INFINITY)]).is_err());
+        assert!(mute_ranges(&[(-1.0, 1.0)]).is_err());
+        assert!(mute_ranges(&[(2.0, 1.0)]).is_err());
+        assert!(keep_ranges(&[(1.0, 1.0)]).is_err());
+        assert!(keep_ranges(&[(0.0, MAX_SECONDS + 1.0)]).is_err());
+    }
+
+    #[test]
+    fn fade_and_beep_filters() {
+        assert_eq!(
+            fade(Fade::Out, 58.0, 2.0).unwrap(),
+            "afade=t=out:st=58.000:d=2.000"
+        );
+        assert!(fade(Fade::In, 0.0, 0.0).is_err());
+        let beep = beep_ranges(&[(1.0, 2.0)], 1000.0).unwrap();
+        assert!(beep.contains("sine=f=1000:"));
+        assert!(beep.contains("not(between(t,1.000,2.000))"));
+        assert!(beep_ranges(&[(1.0, 2.0)], 5.0).is_err());
+    }
+
+    #[test]
+    fn number_checks_range() {
+        assert_eq!(number(12.0, 0.01, 97.0, 2).unwrap(), "12.00");
+        assert!(number(0.0, 0.01, 97.0, 2).is_err());
+        assert!(number(f64::NAN, 0.0, 1.0, 2).is_err());
+    }
+
+    #[test]
+    fn escapes_filter_values() {
+        assert_eq!(escape_value("plain.rnnn"), "plain.rnnn");
+        assert_eq!(escape_value("C:/a b/m.rnnn"), "C\\\\:/a b/m.rnnn");
+        assert_eq!(escape_value("x,volume=0;[y]"), "x\\,volume=0\\;\\[y\\]");
+        assert_eq!(escape_value("it's"), "it\\\\\\'s");
+        assert_eq!(
+            path_value(Path::new("C:\\models\\std.rnnn")),
+            "C\\\\:/models/std.rnnn"
+        );
+    }
+}
@@ -1,4 +1,8 @@
+use crate::services::encryption;
+use crate::services::manifest::{hash_file, ProjectManifest};
+use crate::services::settings;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -6,51 +10,371 @@ use std::path::{Path, PathBuf};
 pub struct ProjectPaths {
     pub root: PathBuf,
     pub converted: PathBuf,
+    /// 降噪後的錄音 (選用階段，需要時才建立)
+    pub cleaned: PathBuf,
     pub split: PathBuf,
     pub silence: PathBuf,
     pub report: PathBuf,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
-pub struct AppConfig {
-    pub custom_project_root: Option<String>,
+/// 各視窗目前開啟的專案 (視窗 label → 專案根目錄)
+/// 多個視窗可各自開啟不同專案而不互相覆蓋
+pub type CurrentProjectState = std::sync::Mutex<HashMap<String, PathBuf>>;
+
+/// 取得指定視窗目前開啟的專案
+pub fn current_project(state: &CurrentProjectState, label: &str) -> Option<PathBuf> {
+    state.lock().ok().and_then(|map| map.get(label).cloned())
 }
 
-pub type CurrentProjectState = std::sync::Mutex<Option<PathBuf>>;
+/// 設定指定視窗目前開啟的專案
+pub fn set_current_project(
+    state: &CurrentProjectState,
+    label: &str,
+    root: PathBuf,
+) -> Result<(), String> {
+    let mut map = state.lock().map_err(|_| "Failed to lock state")?;
+    map.insert(label.to_string(), root);
+    Ok(())
+}
 
-impl ProjectPaths {
-    fn get_config_path() -> PathBuf {
-        let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
-        config_dir.join("stt_agent_rust").join("config.json")
+/// 視窗關閉時移除其專案狀態
+pub fn clear_current_project(state: &CurrentProjectState, label: &str) {
+    if let Ok(mut map) = state.lock() {
+        map.remove(label);
+    }
+}
+
+/// 逐字稿 JSON 存放的隱藏資料夾 (<專案>/.silence_reg/<檔名>.json)
+pub const TRANSCRIPT_DIR: &str = ".silence_reg";
+
+/// 專案階段資料夾名稱
+pub const STAGE_DIRS: [&str; 4] = ["01_converted", "02_split", "03_silence", "04_report"];
+
+/// 降噪階段資料夾 (選用，不在 STAGE_DIRS 內，舊專案沒有此資料夾也視為完整)
+pub const CLEANED_DIR: &str = "01b_cleaned";
+
+/// 將路徑轉為相對於專案根目錄、以 `/` 分隔的形式，供 sidecar JSON 儲存
+/// 讓專案在不同電腦 (或不同磁碟代號) 間搬移後仍能正確開啟；不在專案內的路徑保留原樣
+pub fn to_project_relative(root: &Path, path: &Path) -> String {
+    match path.strip_prefix(root) {
+        Ok(relative) => relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect::<Vec<_>>()
+            .join("/"),
+        Err(_) => path.to_string_lossy().to_string(),
+    }
+}
+
+/// 將 sidecar JSON 內記錄的路徑還原為絕對路徑
+/// 舊版資料可能仍為絕對路徑，直接沿用
+pub fn resolve_project_path(root: &Path, stored: &str) -> PathBuf {
+    let stored_path = Path::new(stored);
+    if stored_path.is_absolute() {
+        return stored_path.to_path_buf();
+    }
+    stored
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty())
+        .fold(root.to_path_buf(), |acc, part| acc.join(part))
+}
+
+/// 搬移檔案時目的地已有同名檔案的處理方式
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictPolicy {
+    /// 保留目的地檔案，略過來源檔
+    #[default]
+    Skip,
+    /// 覆寫目的地檔案
+    Overwrite,
+    /// 自動加上 (1)、(2) 等後綴
+    Rename,
+}
+
+/// 轉檔、切割、消音的輸出檔已存在時的處理方式
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputCollision {
+    /// 覆寫既有檔案
+    #[default]
+    Overwrite,
+    /// 回報錯誤，不產生輸出
+    Error,
+    /// 自動加上 (1)、(2) 等後綴
+    Rename,
+}
+
+impl OutputCollision {
+    /// 決定實際的輸出路徑；Error 且檔案已存在時回傳錯誤訊息
+    /// 加密專案中的 .enc 檔也算已存在
+    pub fn resolve(self, path: PathBuf) -> Result<PathBuf, String> {
+        if !output_exists(&path) {
+            return Ok(path);
+        }
+        match self {
+            OutputCollision::Overwrite => Ok(path),
+            OutputCollision::Error => Err(crate::tr!("error.output_exists", path = path.display())),
+            OutputCollision::Rename => Ok(unique_path_by(&path, output_exists)),
+        }
+    }
+
+    /// FFmpeg 的覆寫參數：Overwrite 為 -y，其他為 -n (輸出檔已存在時 FFmpeg 直接失敗，不會覆寫)
+    pub fn ffmpeg_flag(self) -> &'static str {
+        match self {
+            OutputCollision::Overwrite => "-y",
+            OutputCollision::Error | OutputCollision::Rename => "-n",
+        }
+    }
+}
+
+fn output_exists(path: &Path) -> bool {
+    path.exists() || encryption::encrypted_path(path).exists()
+}
+
+/// 輸出檔被自動改名時的說明 (requested 與 actual 相同時為 None)
+pub fn renamed_note(requested: &str, actual: &str) -> Option<String> {
+    (requested != actual)
+        .then(|| crate::tr!("result.output_renamed", from = requested, to = actual))
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferMode {
+    #[default]
+    Copy,
+    Move,
+}
+
+/// 階段間搬移檔案的結果
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct PromoteResult {
+    pub from_stage: String,
+    pub to_stage: String,
+    /// 成功複製/搬移後的檔案路徑
+    pub transferred: Vec<String>,
+    /// 因同名衝突而略過的檔案
+    pub skipped: Vec<String>,
+    /// 失敗的檔案與原因
+    pub failed: Vec<String>,
+}
+
+/// 將檔案從一個階段資料夾複製或搬移到另一個階段
+/// files 為來源階段內的檔名；空陣列代表該階段的所有檔案
+pub fn promote_files(
+    paths: &ProjectPaths,
+    from_stage: &str,
+    to_stage: &str,
+    files: &[String],
+    mode: TransferMode,
+    on_conflict: ConflictPolicy,
+) -> Result<PromoteResult, String> {
+    let from_dir = paths.stage_dir(from_stage)?;
+    let to_dir = paths.stage_dir(to_stage)?;
+    if from_dir == to_dir {
+        return Err("來源與目的階段相同".to_string());
     }
+    fs::create_dir_all(&to_dir).map_err(|e| format!("無法建立目的資料夾: {}", e))?;
+
+    let names: Vec<String> = if files.is_empty() {
+        fs::read_dir(&from_dir)
+            .map_err(|e| format!("讀取來源資料夾失敗: {}", e))?
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().is_file())
+            .filter_map(|entry| entry.file_name().to_str().map(|s| s.to_string()))
+            .collect()
+    } else {
+        files.to_vec()
+    };
+
+    let mut result = PromoteResult {
+        from_stage: from_stage.to_string(),
+        to_stage: to_stage.to_string(),
+        ..Default::default()
+    };
 
-    fn load_config() -> AppConfig {
-        let config_path = Self::get_config_path();
-        if config_path.exists() {
-            if let Ok(content) = fs::read_to_string(&config_path) {
-                if let Ok(config) = serde_json::from_str(&content) {
-                    return config;
+    for name in names {
+        // 只接受單純檔名，避免跳出階段資料夾
+        let file_name = match Path::new(&name).file_name() {
+            Some(f) => f.to_owned(),
+            None => {
+                result.failed.push(format!("{}: 無效的檔名", name));
+                continue;
+            }
+        };
+        let src = from_dir.join(&file_name);
+        if !src.is_file() {
+            result.failed.push(format!("{}: 檔案不存在", name));
+            continue;
+        }
+
+        let mut dest = to_dir.join(&file_name);
+        if dest.exists() {
+            match on_conflict {
+                ConflictPolicy::Skip => {
+                    result.skipped.push(dest.to_string_lossy().to_string());
+                    continue;
                 }
+                ConflictPolicy::Overwrite => {}
+                ConflictPolicy::Rename => dest = unique_path(&dest),
             }
         }
-        AppConfig::default()
+
+        let outcome = match mode {
+            TransferMode::Copy => fs::copy(&src, &dest).map(|_| ()),
+            // rename 跨磁碟會失敗，改以複製後刪除
+            TransferMode::Move => fs::rename(&src, &dest)
+                .or_else(|_| fs::copy(&src, &dest).and_then(|_| fs::remove_file(&src))),
+        };
+        match outcome {
+            Ok(()) => result.transferred.push(dest.to_string_lossy().to_string()),
+            Err(e) => result.failed.push(format!("{}: {}", name, e)),
+        }
     }
 
-    pub fn set_custom_root(path: String) -> Result<(), String> {
-        let config_path = Self::get_config_path();
-        let config_dir = config_path.parent().expect("Config dir should have parent");
+    Ok(result)
+}
+
+/// 產生不重複的檔名: name.mp3 → name (1).mp3 → name (2).mp3 ...
+pub fn unique_path(path: &Path) -> PathBuf {
+    unique_path_by(path, Path::exists)
+}
 
-        if !config_dir.exists() {
-            fs::create_dir_all(config_dir).map_err(|e| format!("無法建立設定目錄: {}", e))?;
+fn unique_path_by(path: &Path, taken: impl Fn(&Path) -> bool) -> PathBuf {
+    if !taken(path) {
+        return path.to_path_buf();
+    }
+    let parent = path.parent().unwrap_or_else(|| Path::new("."));
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    let mut n = 1;
+    loop {
+        let candidate = parent.join(format!("{} ({}){}", stem, n, ext));
+        if !taken(&candidate) {
+            return candidate;
         }
+        n += 1;
+    }
+}
+
+/// 先寫入同目錄的暫存檔再改名，避免寫到一半中斷時留下損毀的檔案
+pub fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.tmp", file_name));
+    {
+        let mut file = fs::File::create(&tmp)?;
+        std::io::Write::write_all(&mut file, contents)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
 
-        let mut config = Self::load_config();
-        config.custom_project_root = Some(path);
+/// 專案完整性檢查結果
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ValidationReport {
+    pub root: String,
+    /// 缺少的階段資料夾
+    pub missing_dirs: Vec<String>,
+    /// 描述檔有紀錄但實際不存在的檔案
+    pub missing_files: Vec<String>,
+    /// 雜湊值與描述檔不符的檔案
+    pub corrupt_files: Vec<String>,
+    /// 描述檔本身無法讀取時的錯誤
+    pub manifest_error: Option<String>,
+}
 
-        let content = serde_json::to_string_pretty(&config)
-            .map_err(|e| format!("Serialization error: {}", e))?;
-        fs::write(config_path, content).map_err(|e| format!("無法寫入設定檔: {}", e))?;
-        Ok(())
+impl ValidationReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing_dirs.is_empty()
+            && self.missing_files.is_empty()
+            && self.corrupt_files.is_empty()
+            && self.manifest_error.is_none()
+    }
+
+    pub fn summary(&self) -> String {
+        if self.is_ok() {
+            return crate::tr!("validation.ok");
+        }
+        let mut lines = Vec::new();
+        if let Some(e) = &self.manifest_error {
+            lines.push(crate::tr!("validation.manifest_error", detail = e));
+        }
+        for d in &self.missing_dirs {
+            lines.push(crate::tr!("validation.missing_dir", dir = d));
+        }
+        for f in &self.missing_files {
+            lines.push(crate::tr!("validation.missing_file", file = f));
+        }
+        for f in &self.corrupt_files {
+            lines.push(crate::tr!("validation.corrupt_file", file = f));
+        }
+        lines.join("\n")
+    }
+}
+
+/// 檢查專案結構：階段資料夾是否存在、描述檔中的檔案是否存在且雜湊相符
+pub fn validate_project(root: &Path) -> Result<ValidationReport, String> {
+    if !root.is_dir() {
+        return Err(format!("專案目錄不存在: {}", root.display()));
+    }
+
+    let mut report = ValidationReport {
+        root: root.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+
+    for dir in STAGE_DIRS {
+        if !root.join(dir).is_dir() {
+            report.missing_dirs.push(dir.to_string());
+        }
+    }
+
+    match ProjectManifest::load(root) {
+        Ok(manifest) => {
+            for record in &manifest.conversions {
+                let output = Path::new(&record.output);
+                if !output.is_file() {
+                    report.missing_files.push(record.output.clone());
+                    continue;
+                }
+                match hash_file(output) {
+                    Ok(hash) if hash == record.sha256 => {}
+                    _ => report.corrupt_files.push(record.output.clone()),
+                }
+            }
+        }
+        Err(e) => report.manifest_error = Some(e),
+    }
+
+    Ok(report)
+}
+
+impl ProjectPaths {
+    /// 路徑位於專案階段資料夾內時，回傳專案根目錄
+    pub fn find_root(path: &Path) -> Option<PathBuf> {
+        path.ancestors()
+            .find(|ancestor| {
+                ancestor
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|name| STAGE_DIRS.contains(&name) || name == CLEANED_DIR)
+            })
+            .and_then(|stage| stage.parent())
+            .map(Path::to_path_buf)
     }
 
     /// 根據來源檔案路徑，建立專案資料夾結構
@@ -60,21 +384,8 @@ impl ProjectPaths {
 
         // 1. 嘗試偵測是否已在專案結構中 (01_converted, 02_split, 03_silence, 04_report)
         // 這樣可以確保後續處理 (如 Silence, Split) 輸出到正確的專案資料夾，而不是新建一個
-        for ancestor in path.ancestors() {
-            if let Some(name) = ancestor.file_name().and_then(|s| s.to_str()) {
-                if ["01_converted", "02_split", "03_silence", "04_report"].contains(&name) {
-                    if let Some(parent) = ancestor.parent() {
-                        let project_root = parent.to_path_buf();
-                        return Ok(Self {
-                            converted: project_root.join("01_converted"),
-                            split: project_root.join("02_split"),
-                            silence: project_root.join("03_silence"),
-                            report: project_root.join("04_report"),
-                            root: project_root,
-                        });
-                    }
-                }
-            }
+        if let Some(project_root) = Self::find_root(path) {
+            return Ok(Self::from_existing_root(project_root));
         }
 
         // 2. 如果不在專案結構中，則視為新專案，依照檔名建立
@@ -83,28 +394,12 @@ impl ProjectPaths {
             .and_then(|s| s.to_str())
             .ok_or_else(|| "無法解析檔案名稱，請確認路徑是否正確".to_string())?;
 
-        let config = Self::load_config();
-
-        let root_base = if let Some(custom) = config.custom_project_root {
-            PathBuf::from(custom)
-        } else {
-            // 預設路徑邏輯
-            if cfg!(target_os = "linux") {
-                dirs::home_dir()
-                    .ok_or_else(|| "無法找到家目錄 (Home)".to_string())?
-                    .join("STT_Agent_Projects")
-            } else {
-                dirs::document_dir()
-                    .ok_or_else(|| "無法找到系統文件夾 (Documents)".to_string())?
-                    .join("STT_Agent_Projects")
-            }
-        };
-
-        let project_root = root_base.join(stem);
+        let project_root = Self::root_base()?.join(stem);
 
         // 3. 定義子資料夾結構
         let paths = Self {
             converted: project_root.join("01_converted"),
+            cleaned: project_root.join(CLEANED_DIR),
             split: project_root.join("02_split"),
             silence: project_root.join("03_silence"),
             report: project_root.join("04_report"),
@@ -114,6 +409,24 @@ impl ProjectPaths {
         Ok(paths)
     }
 
+    /// 新專案的上層資料夾：設定檔 > 系統預設
+    pub fn root_base() -> Result<PathBuf, String> {
+        if let Some(custom) = settings::load().custom_project_root {
+            return Ok(PathBuf::from(custom));
+        }
+
+        // 預設路徑邏輯
+        if cfg!(target_os = "linux") {
+            Ok(dirs::home_dir()
+                .ok_or_else(|| "無法找到家目錄 (Home)".to_string())?
+                .join("STT_Agent_Projects"))
+        } else {
+            Ok(dirs::document_dir()
+                .ok_or_else(|| "無法找到系統文件夾 (Documents)".to_string())?
+                .join("STT_Agent_Projects"))
+        }
+    }
+
     /// 自動建立所有需要的資料夾
     pub fn create_all_dirs(&self) -> Result<(), String> {
         fs::create_dir_all(&self.root).map_err(|e| format!("無法建立專案根目錄: {}", e))?;
@@ -130,6 +443,7 @@ impl ProjectPaths {
         let paths = Self {
             root: root.to_path_buf(),
             converted: root.join("01_converted"),
+            cleaned: root.join(CLEANED_DIR),
             split: root.join("02_split"),
             silence: root.join("03_silence"),
             report: root.join("04_report"),
@@ -139,14 +453,161 @@ impl ProjectPaths {
     }
 
     pub fn from_root(root: PathBuf) -> Result<Self, String> {
-        let paths = Self {
+        let paths = Self::from_existing_root(root);
+        paths.create_all_dirs()?;
+        Ok(paths)
+    }
+
+    /// 以階段名稱取得資料夾，接受完整名稱 (02_split) 或簡稱 (split)
+    pub fn stage_dir(&self, stage: &str) -> Result<PathBuf, String> {
+        match stage {
+            "01_converted" | "converted" => Ok(self.converted.clone()),
+            "01b_cleaned" | "cleaned" => Ok(self.cleaned.clone()),
+            "02_split" | "split" => Ok(self.split.clone()),
+            "03_silence" | "silence" => Ok(self.silence.clone()),
+            "04_report" | "report" => Ok(self.report.clone()),
+            _ => Err(format!("未知的專案階段: {}", stage)),
+        }
+    }
+
+    /// 依專案根目錄組出各階段路徑，不建立任何資料夾 (唯讀操作使用)
+    pub fn from_existing_root(root: PathBuf) -> Self {
+        Self {
             converted: root.join("01_converted"),
+            cleaned: root.join(CLEANED_DIR),
             split: root.join("02_split"),
             silence: root.join("03_silence"),
             report: root.join("04_report"),
             root,
-        };
-        paths.create_all_dirs()?;
-        Ok(paths)
+        }
+    }
+}
+
+/// 由輸入檔名產生輸出檔路徑：<output_dir>/<檔名 (不含副檔名)><suffix>.<ext>
+/// 以 PathBuf 組合，不經過字串格式化，中文、空白與非 UTF-8 檔名都能保留
+pub fn derived_output_path(
+    input: &Path,
+    output_dir: &Path,
+    suffix: &str,
+    ext: &str,
+) -> Option<PathBuf> {
+    let mut name = input.file_stem()?.to_os_string();
+    name.push(suffix);
+    name.push(".");
+    name.push(ext);
+    Some(output_dir.join(name))
+}
+
+/// Windows 的 MAX_PATH (以 UTF-16 字元計)
+#[cfg(any(windows, test))]
+const MAX_PATH: usize = 260;
+
+/// 超過 MAX_PATH 的絕對路徑改用 \\?\ 形式 (UNC 路徑為 \\?\UNC\)；其他路徑回傳 None
+#[cfg(any(windows, test))]
+fn extended_length_path(path: &str) -> Option<String> {
+    if path.encode_utf16().count() < MAX_PATH
+        || path.starts_with(r"\\?\")
+        || path.starts_with(r"\\.\")
+    {
+        return None;
+    }
+    let path = path.replace('/', "\\");
+    if let Some(unc) = path.strip_prefix(r"\\") {
+        return Some(format!(r"\\?\UNC\{}", unc));
+    }
+    let bytes = path.as_bytes();
+    // 只有 C:\ 形式的絕對路徑可以加上前綴，相對路徑維持原樣
+    (bytes.len() > 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && bytes[2] == b'\\')
+        .then(|| format!(r"\\?\{}", path))
+}
+
+/// 讓長路徑可以交給檔案 API 與 FFmpeg：Windows 上超過 260 字元的絕對路徑加上 \\?\ 前綴，
+/// 其他平台原樣回傳
+pub fn long_path(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        if let Some(extended) = path.to_str().and_then(extended_length_path) {
+            return PathBuf::from(extended);
+        }
+    }
+    path.to_path_buf()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derived_output_path_keeps_cjk_and_spaces() {
+        let input = Path::new("/資料/專案 一/01_converted/會議 錄音 2024-05-01.m4a");
+        let output_dir = Path::new("/資料/專案 一/02_split");
+        assert_eq!(
+            derived_output_path(input, output_dir, "", "mp3").unwrap(),
+            Path::new("/資料/專案 一/02_split/會議 錄音 2024-05-01.mp3")
+        );
+        assert_eq!(
+            derived_output_path(input, output_dir, "_silenced", "m4a").unwrap(),
+            Path::new("/資料/專案 一/02_split/會議 錄音 2024-05-01_silenced.m4a")
+        );
+    }
+
+    #[test]
+    fn derived_output_path_keeps_inner_dots() {
+        let input = Path::new("/錄音/病例討論.第二場.wav");
+        assert_eq!(
+            derived_output_path(input, Path::new("/輸出"), "_enhanced", "mp3").unwrap(),
+            Path::new("/輸出/病例討論.第二場_enhanced.mp3")
+        );
+        assert!(derived_output_path(Path::new("/"), Path::new("/輸出"), "", "mp3").is_none());
+    }
+
+    #[test]
+    fn project_paths_join_cjk_root() {
+        let paths = ProjectPaths::from_existing_root(PathBuf::from("/案例/晨會 討論"));
+        assert_eq!(paths.split, Path::new("/案例/晨會 討論/02_split"));
+        assert_eq!(
+            paths.report.join("report.md"),
+            Path::new("/案例/晨會 討論/04_report/report.md")
+        );
+    }
+
+    #[test]
+    fn short_paths_are_not_extended() {
+        assert_eq!(extended_length_path(r"C:\錄音\會議.mp3"), None);
+        // 100 個中文字在 UTF-8 超過 260 bytes，但 UTF-16 只有 100 個字元
+        let cjk = format!(r"C:\{}.mp3", "錄".repeat(100));
+        assert!(cjk.len() > MAX_PATH);
+        assert_eq!(extended_length_path(&cjk), None);
+    }
+
+    #[test]
+    fn long_drive_paths_get_prefix() {
+        let long = format!("C:/專案/{}/會議 錄音.mp3", "長".repeat(MAX_PATH));
+        let extended = extended_length_path(&long).unwrap();
+        assert!(extended.starts_with(r"\\?\C:\專案\"));
+        assert!(extended.ends_with(r"\會議 錄音.mp3"));
+        assert!(!extended.contains('/'));
+    }
+
+    #[test]
+    fn long_unc_paths_get_unc_prefix() {
+        let long = format!(r"\\server\share\{}\錄音.mp3", "a".repeat(MAX_PATH));
+        let extended = extended_length_path(&long).unwrap();
+        assert!(extended.starts_with(r"\\?\UNC\server\share\"));
+    }
+
+    #[test]
+    fn prefixed_and_relative_paths_are_unchanged() {
+        let prefixed = format!(r"\\?\C:\{}", "a".repeat(MAX_PATH));
+        assert_eq!(extended_length_path(&prefixed), None);
+        let relative = format!(r"錄音\{}", "a".repeat(MAX_PATH));
+        assert_eq!(extended_length_path(&relative), None);
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn long_path_is_identity_off_windows() {
+        let long = PathBuf::from(format!("/錄音/{}", "長".repeat(MAX_PATH)));
+        assert_eq!(long_path(&long), long);
     }
 }
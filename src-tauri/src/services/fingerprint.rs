@@ -0,0 +1,361 @@
+// src-tauri/src/services/fingerprint.rs
+//
+// 音訊指紋：找出同一場看診被匯入兩次的錄音 (例如錄音筆與手機各錄一份)，
+// 避免重複轉錄與計費。
+//
+// 作法與 Chromaprint / Philips 指紋相同：
+// - 轉為 8 kHz 單聲道，每 128ms 取一個 256ms 的音框做 FFT
+// - 300~2000 Hz 分成 33 個對數頻帶，以相鄰頻帶能量差在時間上的變化產生 32-bit 子指紋
+// - 比對時以相同子指紋投票找出時間差，再以該時間差計算位元相似度
+//
+// 指紋快取在專案的 .fingerprints.json，以 (路徑, 修改時間, 大小) 判斷是否需要重算。
+
+use crate::services::file_manager::{to_project_relative, write_atomic, ProjectPaths};
+use crate::services::ingest::is_media_file;
+use crate::services::probe;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+pub const FINGERPRINT_FILE_NAME: &str = ".fingerprints.json";
+
+const SAMPLE_RATE: u32 = 8000;
+const FRAME_SIZE: usize = 2048;
+const HOP_SIZE: usize = 1024;
+const BANDS: usize = 33;
+const MIN_FREQ: f32 = 300.0;
+const MAX_FREQ: f32 = 2000.0;
+
+/// 同一個子指紋最多記錄的位置數 (靜音等重複內容不參與投票)
+const MAX_POSITIONS: usize = 8;
+/// 時間差至少要有幾個完全相同的子指紋才進一步比對
+const MIN_VOTES: usize = 8;
+/// 重疊長度至少佔較短錄音的比例
+const MIN_OVERLAP_RATIO: f64 = 0.5;
+/// 位元相似度門檻 (隨機內容約 0.5)
+const SIMILARITY_THRESHOLD: f64 = 0.75;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct CachedFingerprint {
+    modified: u64,
+    size: u64,
+    fingerprint: Vec<u32>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct FingerprintCache {
+    /// 專案相對路徑 → 指紋
+    files: BTreeMap<String, CachedFingerprint>,
+}
+
+/// 疑似重複的兩個錄音
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicatePair {
+    pub a: String,
+    pub b: String,
+    /// 位元相似度 (0~1)
+    pub similarity: f64,
+    /// b 在 a 中開始的位置 (秒，負值表示 b 較早開始)
+    pub offset: f64,
+    /// 重疊長度 (秒)
+    pub overlap: f64,
+}
+
+/// 計算音檔指紋
+pub fn compute(path: &str) -> Result<Vec<u32>, String> {
+    let mut builder = FingerprintBuilder::new();
+    probe::decode_samples(path, |block, info| {
+        builder.push(block, info.channels, info.sample_rate)
+    })?;
+    Ok(builder.finish())
+}
+
+struct FingerprintBuilder {
+    /// 降取樣用的累加器
+    acc: f32,
+    acc_count: u32,
+    phase: u64,
+    samples: Vec<f32>,
+    window: Vec<f32>,
+    edges: Vec<usize>,
+    previous: Option<Vec<f32>>,
+    fingerprint: Vec<u32>,
+}
+
+impl FingerprintBuilder {
+    fn new() -> Self {
+        let window = (0..FRAME_SIZE)
+            .map(|i| 0.5 - 0.5 * (2.0 * std::f32::consts::PI * i as f32 / FRAME_SIZE as f32).cos())
+            .collect();
+        let bin_hz = SAMPLE_RATE as f32 / FRAME_SIZE as f32;
+        let edges = (0..=BANDS)
+            .map(|i| {
+                let freq = MIN_FREQ * (MAX_FREQ / MIN_FREQ).powf(i as f32 / BANDS as f32);
+                (freq / bin_hz).round() as usize
+            })
+            .collect();
+        Self {
+            acc: 0.0,
+            acc_count: 0,
+            phase: 0,
+            samples: Vec::with_capacity(FRAME_SIZE * 2),
+            window,
+            edges,
+            previous: None,
+            fingerprint: Vec::new(),
+        }
+    }
+
+    /// 轉單聲道並以區間平均降取樣到 8 kHz
+    fn push(&mut self, block: &[f32], channels: u16, rate: u32) {
+        let channels = channels.max(1) as usize;
+        for frame in block.chunks_exact(channels) {
+            self.acc += frame.iter().sum::<f32>() / channels as f32;
+            self.acc_count += 1;
+            self.phase += SAMPLE_RATE as u64;
+            if self.phase >= rate as u64 {
+                self.phase -= rate as u64;
+                self.samples.push(self.acc / self.acc_count as f32);
+                self.acc = 0.0;
+                self.acc_count = 0;
+                if self.samples.len() >= FRAME_SIZE {
+                    self.process_frame();
+                    self.samples.drain(..HOP_SIZE);
+                }
+            }
+        }
+    }
+
+    fn process_frame(&mut self) {
+        let mut re: Vec<f32> = self
+            .samples
+            .iter()
+            .zip(&self.window)
+            .map(|(s, w)| s * w)
+            .collect();
+        let mut im = vec![0.0f32; FRAME_SIZE];
+        fft(&mut re, &mut im);
+
+        let energies: Vec<f32> = self
+            .edges
+            .windows(2)
+            .map(|edge| {
+                (edge[0]..edge[1].max(edge[0] + 1))
+                    .map(|k| re[k] * re[k] + im[k] * im[k])
+                    .sum()
+            })
+            .collect();
+        if let Some(previous) = &self.previous {
+            let mut bits = 0u32;
+            for m in 0..BANDS - 1 {
+                let diff = (energies[m] - energies[m + 1]) - (previous[m] - previous[m + 1]);
+                if diff > 0.0 {
+                    bits |= 1 << m;
+                }
+            }
+            self.fingerprint.push(bits);
+        }
+        self.previous = Some(energies);
+    }
+
+    fn finish(self) -> Vec<u32> {
+        self.fingerprint
+    }
+}
+
+/// 原地 radix-2 FFT (長度須為 2 的次方)
+fn fft(re: &mut [f32], im: &mut [f32]) {
+    let n = re.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+        if i < j {
+            re.swap(i, j);
+            im.swap(i, j);
+        }
+    }
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * std::f32::consts::PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (sin, cos) = (angle * k as f32).sin_cos();
+                let a = start + k;
+                let b = a + len / 2;
+                let tr = re[b] * cos - im[b] * sin;
+                let ti = re[b] * sin + im[b] * cos;
+                re[b] = re[a] - tr;
+                im[b] = im[a] - ti;
+                re[a] += tr;
+                im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+}
+
+/// 子指紋對應的秒數
+fn frames_to_seconds(frames: i64) -> f64 {
+    frames as f64 * HOP_SIZE as f64 / SAMPLE_RATE as f64
+}
+
+/// 比對兩個指紋，回傳 (相似度, b 相對 a 的位移, 重疊的子指紋數)
+fn compare(a: &[u32], b: &[u32]) -> Option<(f64, i64, usize)> {
+    let mut index: HashMap<u32, Vec<usize>> = HashMap::new();
+    for (i, &value) in a.iter().enumerate() {
+        if value == 0 || value == u32::MAX {
+            continue;
+        }
+        let positions = index.entry(value).or_default();
+        if positions.len() < MAX_POSITIONS {
+            positions.push(i);
+        }
+    }
+
+    let mut votes: HashMap<i64, usize> = HashMap::new();
+    for (j, value) in b.iter().enumerate() {
+        for &i in index.get(value).into_iter().flatten() {
+            *votes.entry(i as i64 - j as i64).or_default() += 1;
+        }
+    }
+    let (offset, count) = votes.into_iter().max_by_key(|(_, count)| *count)?;
+    if count < MIN_VOTES {
+        return None;
+    }
+
+    let start_a = offset.max(0) as usize;
+    let start_b = (-offset).max(0) as usize;
+    let overlap = a
+        .len()
+        .saturating_sub(start_a)
+        .min(b.len().saturating_sub(start_b));
+    if overlap == 0 || (overlap as f64) < a.len().min(b.len()) as f64 * MIN_OVERLAP_RATIO {
+        return None;
+    }
+    let errors: u32 = a[start_a..start_a + overlap]
+        .iter()
+        .zip(&b[start_b..start_b + overlap])
+        .map(|(x, y)| (x ^ y).count_ones())
+        .sum();
+    let similarity = 1.0 - errors as f64 / (overlap as f64 * (BANDS - 1) as f64);
+    Some((similarity, offset, overlap))
+}
+
+fn cache_path(root: &Path) -> PathBuf {
+    root.join(FINGERPRINT_FILE_NAME)
+}
+
+fn load_cache(root: &Path) -> FingerprintCache {
+    fs::read_to_string(cache_path(root))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((modified, metadata.len()))
+}
+
+/// 計算專案 01_converted 內所有錄音的指紋 (已快取且未變更的檔案略過)
+/// 回傳 (檔案, 指紋)
+pub fn index(root: &Path) -> Result<Vec<(PathBuf, Vec<u32>)>, String> {
+    let converted = ProjectPaths::from_existing_root(root.to_path_buf()).converted;
+    let mut files: Vec<PathBuf> = match fs::read_dir(&converted) {
+        Ok(entries) => entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && is_media_file(p))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    files.sort();
+
+    let mut cache = load_cache(root);
+    let mut changed = false;
+    let mut result = Vec::new();
+    for file in files {
+        let Some((modified, size)) = file_stamp(&file) else {
+            continue;
+        };
+        let key = to_project_relative(root, &file);
+        let cached = cache
+            .files
+            .get(&key)
+            .filter(|c| c.modified == modified && c.size == size);
+        let fingerprint = match cached {
+            Some(cached) => cached.fingerprint.clone(),
+            None => match compute(&file.to_string_lossy()) {
+                Ok(fingerprint) => {
+                    cache.files.insert(
+                        key,
+                        CachedFingerprint {
+                            modified,
+                            size,
+                            fingerprint: fingerprint.clone(),
+                        },
+                    );
+                    changed = true;
+                    fingerprint
+                }
+                Err(e) => {
+                    tracing::warn!("無法計算音訊指紋 {}: {}", file.display(), e);
+                    continue;
+                }
+            },
+        };
+        result.push((file, fingerprint));
+    }
+
+    // 移除已不存在的檔案
+    let before = cache.files.len();
+    cache.files.retain(|key, _| {
+        result
+            .iter()
+            .any(|(f, _)| &to_project_relative(root, f) == key)
+    });
+    changed |= cache.files.len() != before;
+
+    if changed {
+        let json = serde_json::to_vec(&cache).map_err(|e| e.to_string())?;
+        write_atomic(&cache_path(root), &json).map_err(|e| format!("無法寫入指紋快取: {}", e))?;
+    }
+    Ok(result)
+}
+
+/// 找出專案內疑似重複的錄音
+pub fn find_duplicates(root: &Path) -> Result<Vec<DuplicatePair>, String> {
+    let files = index(root)?;
+    let mut pairs = Vec::new();
+    for (i, (path_a, a)) in files.iter().enumerate() {
+        for (path_b, b) in &files[i + 1..] {
+            let Some((similarity, offset, overlap)) = compare(a, b) else {
+                continue;
+            };
+            if similarity >= SIMILARITY_THRESHOLD {
+                pairs.push(DuplicatePair {
+                    a: path_a.to_string_lossy().to_string(),
+                    b: path_b.to_string_lossy().to_string(),
+                    similarity,
+                    offset: frames_to_seconds(offset),
+                    overlap: frames_to_seconds(overlap as i64),
+                });
+            }
+        }
+    }
+    pairs.sort_by(|x, y| y.similarity.total_cmp(&x.similarity));
+    Ok(pairs)
+}
@@ -0,0 +1,164 @@
+// src-tauri/src/services/health.rs
+//
+// 啟動診斷：一次檢查所有外部服務 (FFmpeg、Pandoc、STT 伺服器、Gemini、本機模型)，
+// 各項同時檢查，任一項失敗不影響其他項目。
+
+use crate::services::dependencies::{self, DependencyStatus};
+use crate::services::report::ReportAgent;
+use crate::services::settings;
+use crate::services::silence::Silence;
+use crate::services::stt_models::{self, ModelDownloads, ModelKind};
+use serde::Serialize;
+use std::time::Instant;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceState {
+    Ok,
+    Error,
+    /// 未設定 (沒有 STT 伺服器位址、沒有 API Key 等)，不算錯誤
+    NotConfigured,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceCheck {
+    pub service: String,
+    pub state: ServiceState,
+    /// 版本、位址或錯誤訊息
+    pub detail: Option<String>,
+    /// 檢查花費的時間 (毫秒)
+    pub latency_ms: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ServiceReport {
+    pub checks: Vec<ServiceCheck>,
+    /// 所有已設定的服務都正常
+    pub all_ok: bool,
+    pub checked_at: String,
+}
+
+fn check(
+    service: &str,
+    started: Instant,
+    state: ServiceState,
+    detail: Option<String>,
+) -> ServiceCheck {
+    ServiceCheck {
+        service: service.to_string(),
+        state,
+        detail,
+        latency_ms: started.elapsed().as_millis() as u64,
+    }
+}
+
+fn from_dependency(status: DependencyStatus, started: Instant) -> ServiceCheck {
+    let name = status.name.clone();
+    if status.available {
+        check(&name, started, ServiceState::Ok, status.version)
+    } else {
+        check(&name, started, ServiceState::Error, status.error)
+    }
+}
+
+/// STT 伺服器位址沒有協定時補上 http://
+fn server_url(server: &str) -> String {
+    if server.starts_with("http://") || server.starts_with("https://") {
+        server.to_string()
+    } else {
+        format!("http://{}", server)
+    }
+}
+
+async fn check_stt_server(server: Option<String>) -> ServiceCheck {
+    let started = Instant::now();
+    let Some(server) = server.filter(|s| !s.trim().is_empty()) else {
+        return check("stt_server", started, ServiceState::NotConfigured, None);
+    };
+    let url = server_url(server.trim());
+    if Silence::new().check_health(&url).await {
+        check("stt_server", started, ServiceState::Ok, Some(url))
+    } else {
+        check(
+            "stt_server",
+            started,
+            ServiceState::Error,
+            Some(format!("無法連線到 STT 伺服器: {}", url)),
+        )
+    }
+}
+
+async fn check_gemini(api_key: Option<String>) -> ServiceCheck {
+    let started = Instant::now();
+    let Some(api_key) = api_key.filter(|k| !k.trim().is_empty()) else {
+        return check("gemini", started, ServiceState::NotConfigured, None);
+    };
+    match ReportAgent::new(api_key.trim().to_string())
+        .check_api_key()
+        .await
+    {
+        Ok(()) => check("gemini", started, ServiceState::Ok, None),
+        Err(e) => check("gemini", started, ServiceState::Error, Some(e)),
+    }
+}
+
+/// 本機 STT 模型：至少下載了一個 Whisper 模型
+fn check_local_models(app: &AppHandle, downloads: &ModelDownloads) -> ServiceCheck {
+    let started = Instant::now();
+    match stt_models::list(app, downloads) {
+        Ok(models) => {
+            let installed: Vec<String> = models
+                .into_iter()
+                .filter(|m| m.installed && m.kind == ModelKind::Stt)
+                .map(|m| m.id)
+                .collect();
+            if installed.is_empty() {
+                check("local_models", started, ServiceState::NotConfigured, None)
+            } else {
+                check(
+                    "local_models",
+                    started,
+                    ServiceState::Ok,
+                    Some(installed.join(", ")),
+                )
+            }
+        }
+        Err(e) => check(
+            "local_models",
+            started,
+            ServiceState::Error,
+            Some(e.to_string()),
+        ),
+    }
+}
+
+/// 檢查所有服務；stt_server 未指定時使用設定中的位址
+pub async fn check_all(
+    app: &AppHandle,
+    downloads: &ModelDownloads,
+    stt_server: Option<String>,
+    api_key: Option<String>,
+) -> ServiceReport {
+    let started = Instant::now();
+    let stt_server = stt_server.or_else(|| settings::load().stt_server);
+    let (ffmpeg, pandoc, stt, gemini) = tokio::join!(
+        dependencies::check_ffmpeg(app),
+        dependencies::check_pandoc(),
+        check_stt_server(stt_server),
+        check_gemini(api_key),
+    );
+
+    let checks = vec![
+        from_dependency(ffmpeg, started),
+        from_dependency(pandoc, started),
+        stt,
+        gemini,
+        check_local_models(app, downloads),
+    ];
+    ServiceReport {
+        all_ok: checks.iter().all(|c| c.state != ServiceState::Error),
+        checks,
+        checked_at: chrono::Local::now().to_rfc3339(),
+    }
+}
@@ -0,0 +1,318 @@
+// src-tauri/src/services/history.rs
+//
+// 專案操作紀錄 (稽核軌跡)：每個會修改檔案的命令與背景工作，
+// 都會在專案根目錄的 history.jsonl 附加一行紀錄 (命令、參數摘要、輸出檔、耗時、結果)。
+// 檔案只附加不改寫，供臨床稽核與問題排查使用。
+// 背景工作的紀錄保存完整的工作內容 (JobSpec，不含 API Key)，可依此重新執行。
+
+use crate::models::AppError;
+use crate::services::file_manager::{to_project_relative, ProjectPaths};
+use crate::services::jobs::{Job, JobSpec};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+
+pub const HISTORY_FILE_NAME: &str = "history.jsonl";
+
+/// 避免多個工作同時附加時行與行交錯
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryOutcome {
+    Success,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub timestamp: String,
+    /// 命令名稱，背景工作為 job:<kind>
+    pub command: String,
+    #[serde(default)]
+    pub job_id: Option<String>,
+    /// 參數摘要 (不含檔案內容與 API Key)
+    #[serde(default)]
+    pub args: Value,
+    /// 產生或修改的檔案 (專案內為相對路徑)
+    #[serde(default)]
+    pub outputs: Vec<String>,
+    pub duration_ms: u64,
+    pub outcome: HistoryOutcome,
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportFormat {
+    #[default]
+    Jsonl,
+    Csv,
+}
+
+pub fn history_path(root: &Path) -> PathBuf {
+    root.join(HISTORY_FILE_NAME)
+}
+
+/// 附加一筆紀錄
+pub fn append(root: &Path, entry: &HistoryEntry) -> Result<(), String> {
+    let mut line = serde_json::to_string(entry).map_err(|e| e.to_string())?;
+    line.push('\n');
+    let _guard = APPEND_LOCK.lock().map_err(|_| "history lock poisoned")?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(history_path(root))
+        .map_err(|e| format!("無法寫入操作紀錄: {}", e))?;
+    file.write_all(line.as_bytes())
+        .and_then(|_| file.sync_data())
+        .map_err(|e| format!("無法寫入操作紀錄: {}", e))
+}
+
+/// 讀取紀錄 (新到舊)，limit 為 None 時全部回傳；無法解析的行略過
+pub fn load(root: &Path, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+    let path = history_path(root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(&path).map_err(|e| format!("無法讀取操作紀錄: {}", e))?;
+    let entries = content
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| match serde_json::from_str(line) {
+            Ok(entry) => Some(entry),
+            Err(e) => {
+                tracing::warn!("略過無法解析的操作紀錄: {}", e);
+                None
+            }
+        })
+        .take(limit.unwrap_or(usize::MAX))
+        .collect();
+    Ok(entries)
+}
+
+/// 讀取背景工作的紀錄 (新到舊)
+pub fn load_jobs(root: &Path, limit: Option<usize>) -> Result<Vec<HistoryEntry>, String> {
+    Ok(load(root, None)?
+        .into_iter()
+        .filter(|entry| entry.job_id.is_some())
+        .take(limit.unwrap_or(usize::MAX))
+        .collect())
+}
+
+/// 從紀錄中找出工作的內容 (重新執行用)；舊版紀錄無法解析時為 None
+pub fn find_job_spec(root: &Path, job_id: &str) -> Result<Option<JobSpec>, String> {
+    Ok(load(root, None)?
+        .into_iter()
+        .find(|entry| entry.job_id.as_deref() == Some(job_id))
+        .and_then(|entry| serde_json::from_value(entry.args).ok()))
+}
+
+/// 匯出紀錄 (舊到新)，回傳輸出檔路徑
+pub fn export(root: &Path, output_path: &Path, format: ExportFormat) -> Result<PathBuf, String> {
+    let mut entries = load(root, None)?;
+    entries.reverse();
+
+    let content = match format {
+        ExportFormat::Jsonl => entries
+            .iter()
+            .filter_map(|e| serde_json::to_string(e).ok())
+            .map(|line| line + "\n")
+            .collect::<String>(),
+        ExportFormat::Csv => {
+            let mut csv =
+                String::from("timestamp,command,job_id,args,outputs,duration_ms,outcome,error\n");
+            for e in &entries {
+                let outcome = serde_json::to_value(e.outcome)
+                    .ok()
+                    .and_then(|v| v.as_str().map(str::to_string))
+                    .unwrap_or_default();
+                let fields = [
+                    e.timestamp.clone(),
+                    e.command.clone(),
+                    e.job_id.clone().unwrap_or_default(),
+                    e.args.to_string(),
+                    e.outputs.join("; "),
+                    e.duration_ms.to_string(),
+                    outcome,
+                    e.error.clone().unwrap_or_default(),
+                ];
+                let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+                csv.push_str(&row.join(","));
+                csv.push('\n');
+            }
+            csv
+        }
+    };
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("無法建立輸出資料夾: {}", e))?;
+    }
+    fs::write(output_path, content).map_err(|e| format!("無法匯出操作紀錄: {}", e))?;
+    Ok(output_path.to_path_buf())
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 記錄命令的執行結果
+pub fn record_command<T>(
+    root: &Path,
+    command: &str,
+    args: Value,
+    outputs: &[PathBuf],
+    started: Instant,
+    result: &Result<T, AppError>,
+) {
+    let entry = HistoryEntry {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        command: command.to_string(),
+        job_id: None,
+        args,
+        outputs: outputs
+            .iter()
+            .map(|p| to_project_relative(root, p))
+            .collect(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        outcome: outcome_of(result),
+        error: result.as_ref().err().map(|e| e.message.clone()),
+    };
+    if let Err(e) = append(root, &entry) {
+        tracing::warn!("{}", e);
+    }
+}
+
+/// 記錄背景工作的執行結果，寫入工作涉及的每個專案
+pub fn record_job(
+    job: &Job,
+    outputs: &[PathBuf],
+    started: Instant,
+    result: &Result<Value, AppError>,
+) {
+    let mut roots: Vec<PathBuf> = job_projects(&job.spec);
+    for output in outputs {
+        if let Some(root) = ProjectPaths::find_root(output) {
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+    }
+
+    let args = serde_json::to_value(&job.spec).unwrap_or(Value::Null);
+    for root in roots.iter().filter(|r| r.is_dir()) {
+        let entry = HistoryEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            command: format!("job:{}", job.kind),
+            job_id: Some(job.id.clone()),
+            args: args.clone(),
+            outputs: outputs
+                .iter()
+                .filter(|p| p.starts_with(root))
+                .map(|p| to_project_relative(root, p))
+                .collect(),
+            duration_ms: started.elapsed().as_millis() as u64,
+            outcome: outcome_of(result),
+            error: result.as_ref().err().map(|e| e.message.clone()),
+        };
+        if let Err(e) = append(root, &entry) {
+            tracing::warn!("{}", e);
+        }
+    }
+}
+
+/// 工作內容指定的專案 (未指定時依輸入檔位置推算既有專案)
+pub(crate) fn job_projects(spec: &JobSpec) -> Vec<PathBuf> {
+    let explicit = |root: &Option<String>, path: &str| match root {
+        Some(root) => Some(PathBuf::from(root)),
+        None => ProjectPaths::find_root(Path::new(path)),
+    };
+    let roots = match spec {
+        JobSpec::Convert {
+            file_paths,
+            project_root,
+        } => file_paths
+            .iter()
+            .filter_map(|path| explicit(project_root, path))
+            .collect(),
+        JobSpec::Split {
+            audio_path,
+            project_root,
+            ..
+        }
+        | JobSpec::Silence {
+            audio_path,
+            project_root,
+            ..
+        }
+        | JobSpec::Denoise {
+            audio_path,
+            project_root,
+        }
+        | JobSpec::Enhance {
+            audio_path,
+            project_root,
+        } => explicit(project_root, audio_path).into_iter().collect(),
+        JobSpec::SilenceToDir { output_dir, .. } => project_root_for(Path::new(output_dir))
+            .into_iter()
+            .collect(),
+        JobSpec::Extract { output_path, .. } | JobSpec::ExportRange { output_path, .. } => {
+            project_root_for(Path::new(output_path))
+                .into_iter()
+                .collect()
+        }
+        JobSpec::Report { folder_path, .. } => project_root_for(Path::new(folder_path))
+            .into_iter()
+            .collect(),
+        JobSpec::PromptExperiment { audio_path, .. } => project_root_for(Path::new(audio_path))
+            .into_iter()
+            .collect(),
+        JobSpec::Transcribe { .. } => Vec::new(),
+        JobSpec::Pipeline { project_root, .. } | JobSpec::TranscribeFolder { project_root, .. } => {
+            vec![PathBuf::from(project_root)]
+        }
+        JobSpec::Align {
+            transcript_path, ..
+        } => project_root_for(Path::new(transcript_path))
+            .into_iter()
+            .collect(),
+    };
+    let mut unique: Vec<PathBuf> = Vec::new();
+    for root in roots {
+        if !unique.contains(&root) {
+            unique.push(root);
+        }
+    }
+    unique
+}
+
+/// 路徑所屬的專案：位於階段資料夾內，或位於有描述檔/操作紀錄的專案根目錄下
+pub fn project_root_for(path: &Path) -> Option<PathBuf> {
+    ProjectPaths::find_root(path).or_else(|| {
+        path.ancestors()
+            .find(|dir| {
+                dir.join(crate::services::manifest::MANIFEST_FILE).is_file()
+                    || history_path(dir).is_file()
+            })
+            .map(Path::to_path_buf)
+    })
+}
+
+fn outcome_of<T>(result: &Result<T, AppError>) -> HistoryOutcome {
+    match result {
+        Ok(_) => HistoryOutcome::Success,
+        Err(e) if e.is_cancelled() => HistoryOutcome::Cancelled,
+        Err(_) => HistoryOutcome::Failed,
+    }
+}
@@ -0,0 +1,782 @@
+// src-tauri/src/services/i18n.rs
+//
+// 後端訊息的多語系表。錯誤與結果以 key + 參數表示，依設定中的語言產生文字；
+// 前端也可以直接使用 AppError 的 message_key / params 自行翻譯。
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    ZhTw,
+    En,
+}
+
+impl Locale {
+    /// 設定中的語言代碼 ("zh" / "en")
+    pub fn from_language(language: &str) -> Self {
+        match language {
+            "en" => Locale::En,
+            _ => Locale::ZhTw,
+        }
+    }
+}
+
+static CURRENT_LOCALE: AtomicU8 = AtomicU8::new(0);
+
+pub fn set_locale(locale: Locale) {
+    let value = match locale {
+        Locale::ZhTw => 0,
+        Locale::En => 1,
+    };
+    CURRENT_LOCALE.store(value, Ordering::Relaxed);
+}
+
+pub fn current_locale() -> Locale {
+    match CURRENT_LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        _ => Locale::ZhTw,
+    }
+}
+
+/// 以目前語言產生訊息，`{name}` 會被對應的參數取代
+/// 找不到 key 時直接回傳 key，方便發現漏掉的翻譯
+pub fn tr(key: &str, params: &[(&str, String)]) -> String {
+    let Some((_, zh, en)) = MESSAGES.iter().find(|(k, _, _)| *k == key) else {
+        return key.to_string();
+    };
+    let template = match current_locale() {
+        Locale::ZhTw => zh,
+        Locale::En => en,
+    };
+    params
+        .iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// `tr!("key", name = value, ...)`
+#[macro_export]
+macro_rules! tr {
+    ($key:expr) => {
+        $crate::services::i18n::tr($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::services::i18n::tr($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+/// (key, 繁體中文, English)
+const MESSAGES: &[(&str, &str, &str)] = &[
+    // 錯誤分類 (AppError 預設訊息)
+    ("error.invalid_input", "輸入資料不正確", "Invalid input"),
+    ("error.not_found", "找不到指定的項目", "Not found"),
+    ("error.io", "檔案讀寫失敗", "File operation failed"),
+    ("error.tool", "外部工具執行失敗", "External tool failed"),
+    ("error.network", "無法連線到伺服器", "Cannot connect to the server"),
+    ("error.api", "API 呼叫失敗", "API request failed"),
+    ("error.insufficient_space", "磁碟空間不足", "Not enough disk space"),
+    ("error.cancelled", "工作已取消", "Job cancelled"),
+    ("error.unsupported", "目前平台不支援此功能", "Not supported on this platform"),
+    (
+        "error.confirmation_required",
+        "此批次共 {files} 個檔案、約 {hours} 小時，確定要執行嗎？",
+        "This batch has {files} files (about {hours} hours). Run it anyway?",
+    ),
+    (
+        "error.permission_denied",
+        "不允許存取此路徑",
+        "Access to this path is not allowed",
+    ),
+    ("error.internal", "發生內部錯誤", "Internal error"),
+    // 輸入驗證
+    ("error.no_files_selected", "未選擇任何檔案", "No files selected"),
+    ("error.no_track_loaded", "未載入音訊檔案", "No audio file loaded"),
+    ("error.no_segments", "未設定任何段落", "No segments defined"),
+    ("error.no_chapters", "此音檔沒有章節資訊", "This file has no chapter metadata"),
+    (
+        "error.invalid_selection",
+        "選取範圍無效：{start} - {end}",
+        "Invalid selection: {start} - {end}",
+    ),
+    (
+        "error.compare_not_started",
+        "尚未進入比較模式",
+        "Compare mode has not been started",
+    ),
+    (
+        "error.segment_name_empty",
+        "第 {index} 個段落名稱不能為空",
+        "Segment {index} needs a name",
+    ),
+    (
+        "error.segment_time_incomplete",
+        "第 {index} 個段落 '{name}' 的時間不完整",
+        "Segment {index} '{name}' has an incomplete time range",
+    ),
+    ("error.no_silence_ranges", "未設定任何消音時段", "No silence ranges defined"),
+    (
+        "error.invalid_start_time",
+        "開始時間格式錯誤: {detail}",
+        "Invalid start time: {detail}",
+    ),
+    (
+        "error.invalid_end_time",
+        "結束時間格式錯誤: {detail}",
+        "Invalid end time: {detail}",
+    ),
+    (
+        "error.start_after_end",
+        "開始時間必須小於結束時間 ({start}-{end})",
+        "Start time must be before end time ({start}-{end})",
+    ),
+    ("error.dir_not_found", "目錄不存在或無效", "Directory does not exist or is invalid"),
+    (
+        "error.deid_not_checked",
+        "此報告尚未進行去識別化檢查: {path}",
+        "This report has not been checked for identifiers: {path}",
+    ),
+    (
+        "error.experiment_variants",
+        "Prompt 實驗至少需要兩組 Prompt / 模型",
+        "A prompt experiment needs at least two prompt / model variants",
+    ),
+    (
+        "error.experiment_encrypted",
+        "加密專案的音檔無法進行 Prompt 實驗",
+        "Prompt experiments are not available for encrypted projects",
+    ),
+    (
+        "error.html_export_encrypted",
+        "加密專案的報告無法匯出為 HTML",
+        "Reports in encrypted projects cannot be exported to HTML",
+    ),
+    (
+        "error.report_not_found",
+        "找不到報告: {path}",
+        "Report not found: {path}",
+    ),
+    (
+        "error.deid_unacknowledged",
+        "去識別化檢查有 {count} 處結果尚未確認，確認後才能轉換 Word 文件",
+        "{count} de-identification findings have not been acknowledged; acknowledge them before converting to Word",
+    ),
+    ("error.missing_api_key", "請輸入 Gemini API Key", "Please enter a Gemini API key"),
+    (
+        "error.missing_api_key_resumed",
+        "未提供 Gemini API Key，請重新送出報告工作",
+        "No Gemini API key available, please submit the report job again",
+    ),
+    ("error.no_folder_selected", "請選擇音檔資料夾", "Please select an audio folder"),
+    (
+        "error.prompt_read_failed",
+        "無法讀取 Prompt 檔案: {detail}",
+        "Cannot read prompt file: {detail}",
+    ),
+    (
+        "error.file_not_found",
+        "找不到檔案: {path}",
+        "File not found: {path}",
+    ),
+    (
+        "error.trash_restore_exists",
+        "原位置已有同名檔案，無法還原: {path}",
+        "A file already exists at the original location: {path}",
+    ),
+    (
+        "error.trash_item_not_in_project",
+        "回收筒中沒有此專案的項目: {id}",
+        "No trashed item from this project matches: {id}",
+    ),
+    (
+        "error.trash_restore_unsupported",
+        "此系統不支援從回收筒還原，請使用檔案管理員",
+        "Restoring from the trash is not supported on this system, please use the file manager",
+    ),
+    (
+        "error.read_only_project",
+        "專案以檢視模式開啟，無法修改: {path}",
+        "The project is open in read-only viewer mode and cannot be changed: {path}",
+    ),
+    (
+        "error.output_exists",
+        "輸出檔案已存在: {path}",
+        "Output file already exists: {path}",
+    ),
+    (
+        "error.deprecated_command",
+        "請使用新的 {command} 命令",
+        "Please use the new {command} command",
+    ),
+    // 應用程式與工作佇列
+    (
+        "error.uninstaller_not_found",
+        "找不到解除安裝程式 (uninstall.exe)",
+        "Uninstaller (uninstall.exe) not found",
+    ),
+    (
+        "error.uninstall_package_manager",
+        "請透過套件管理員移除：\n\n{command}",
+        "Please uninstall via your package manager:\n\n{command}",
+    ),
+    (
+        "error.purge_projects_unconfirmed",
+        "刪除專案資料夾前需要確認路徑: {path}",
+        "Confirm the project folder path before deleting it: {path}",
+    ),
+    ("error.purge_failed", "部分資料無法刪除", "Some data could not be deleted"),
+    ("error.player_busy", "無法取得播放器鎖定", "Audio player is busy"),
+    ("error.log_not_initialized", "日誌系統尚未初始化", "Logging is not initialized"),
+    (
+        "error.open_log_folder_failed",
+        "無法開啟日誌資料夾: {detail}",
+        "Cannot open log folder: {detail}",
+    ),
+    (
+        "error.file_too_large",
+        "檔案過大 ({size})，上限為 {limit}: {path}",
+        "File is too large ({size}, limit {limit}): {path}",
+    ),
+    (
+        "error.invalid_json",
+        "JSON 格式錯誤: {path} ({detail})",
+        "Invalid JSON in {path} ({detail})",
+    ),
+    (
+        "error.json_schema",
+        "JSON 內容不符合格式: {path} ({count} 個錯誤)",
+        "JSON does not match the expected schema: {path} ({count} errors)",
+    ),
+    (
+        "error.location_unreachable",
+        "無法存取位置，請確認磁碟已連接: {path}",
+        "Location is not reachable, check that the drive is connected: {path}",
+    ),
+    (
+        "error.network_share_unreachable",
+        "網路資料夾沒有回應，請確認網路連線，或先將檔案複製到本機: {path}",
+        "Network share is not responding. Check the connection or copy the files to this computer first: {path}",
+    ),
+    (
+        "error.location_read_only",
+        "位置為唯讀，無法寫入 (例如鎖定的記憶卡或唯讀分享)，請將檔案複製到本機後再處理: {path}",
+        "Location is read-only (e.g. a locked memory card or read-only share). Copy the files to this computer first: {path}",
+    ),
+    ("error.recorder_busy", "無法取得錄音器鎖定", "Recorder is busy"),
+    ("error.recording_in_progress", "已經在錄音中", "A recording is already in progress"),
+    ("error.not_recording", "目前沒有在錄音", "Not recording"),
+    (
+        "error.notes_no_stt_server",
+        "請先在設定中指定 STT 伺服器再錄製口述筆記",
+        "Set an STT server in Settings before dictating notes",
+    ),
+    (
+        "error.note_no_speech",
+        "口述筆記中沒有辨識到語音，錄音檔已保留",
+        "No speech was recognized in the note; the recording was kept",
+    ),
+    (
+        "error.recording_no_project",
+        "請先開啟或建立專案再錄音",
+        "Open or create a project before recording",
+    ),
+    (
+        "error.schedule_invalid_time",
+        "無法解析預約時間: {time}",
+        "Invalid scheduled time: {time}",
+    ),
+    (
+        "error.schedule_invalid_duration",
+        "錄音時間必須大於 0",
+        "Recording duration must be greater than zero",
+    ),
+    (
+        "error.schedule_in_past",
+        "預約時段已經結束: {time}",
+        "The scheduled session has already ended: {time}",
+    ),
+    ("error.schedule_not_found", "找不到預約錄音: {id}", "Scheduled recording not found: {id}"),
+    ("error.model_unknown", "未知的模型: {id}", "Unknown model: {id}"),
+    (
+        "error.model_downloading",
+        "模型正在下載中: {id}",
+        "Model is already downloading: {id}",
+    ),
+    (
+        "error.model_checksum",
+        "模型檔驗證失敗 (SHA-256 不符)，已刪除下載的檔案: {id}",
+        "Model checksum mismatch (SHA-256); the download was discarded: {id}",
+    ),
+    (
+        "error.model_unverified",
+        "模型沒有可驗證的 SHA-256，無法下載: {id}",
+        "The model has no pinned SHA-256 to verify against and cannot be downloaded: {id}",
+    ),
+    (
+        "error.encryption_already_enabled",
+        "此專案已啟用加密",
+        "Encryption is already enabled for this project",
+    ),
+    (
+        "error.encryption_not_enabled",
+        "此專案未啟用加密",
+        "Encryption is not enabled for this project",
+    ),
+    (
+        "error.passphrase_too_short",
+        "專案密碼至少需要 8 個字元",
+        "The project passphrase must be at least 8 characters",
+    ),
+    ("error.wrong_passphrase", "專案密碼錯誤", "Wrong project passphrase"),
+    (
+        "error.project_locked",
+        "專案已加密，請先輸入密碼解鎖: {path}",
+        "The project is encrypted; unlock it with its passphrase first: {path}",
+    ),
+    (
+        "error.decrypt_failed",
+        "無法解密檔案 (檔案損毀或金鑰不符): {path}",
+        "Cannot decrypt the file (corrupted or wrong key): {path}",
+    ),
+    (
+        "error.offline_report_not_found",
+        "找不到離線佇列中的報告: {id}",
+        "Queued offline report not found: {id}",
+    ),
+    (
+        "error.webhook_failed",
+        "無法送出 webhook 通知",
+        "Failed to deliver the webhook notification",
+    ),
+    (
+        "error.email_failed",
+        "無法寄出通知郵件",
+        "Failed to send the notification email",
+    ),
+    (
+        "error.transcript_segment_index",
+        "段落 {index} 不存在",
+        "Segment {index} does not exist",
+    ),
+    (
+        "error.transcript_invalid_time",
+        "段落 {index} 的時間無效",
+        "Segment {index} has an invalid time",
+    ),
+    (
+        "error.speaker_not_found",
+        "逐字稿中沒有說話者 {speaker} 的段落",
+        "No segments from speaker {speaker} in the transcript",
+    ),
+    (
+        "error.pipeline_not_found",
+        "找不到流程定義: {name}",
+        "Pipeline definition not found: {name}",
+    ),
+    (
+        "error.pipeline_invalid_name",
+        "流程名稱不可為空白",
+        "Pipeline name cannot be empty",
+    ),
+    (
+        "error.pipeline_no_project",
+        "請先開啟專案再保存專案流程",
+        "Open a project before saving a project pipeline",
+    ),
+    (
+        "error.pipeline_nothing_to_resume",
+        "這個專案沒有未完成的流程",
+        "This project has no unfinished pipeline",
+    ),
+    (
+        "error.pipeline_no_files",
+        "沒有任何檔案轉檔成功，流程已停止",
+        "No file was converted, the pipeline stopped",
+    ),
+    (
+        "error.plugin_failed",
+        "外掛 {name} 執行失敗 ({hook})",
+        "Plugin {name} failed ({hook})",
+    ),
+    (
+        "error.plugin_timeout",
+        "外掛 {name} 超過 {secs} 秒仍未結束，已終止",
+        "Plugin {name} did not finish within {secs} seconds and was stopped",
+    ),
+    (
+        "error.plugin_no_output",
+        "外掛 {name} 沒有產生轉換後的檔案",
+        "Plugin {name} did not produce a converted file",
+    ),
+    (
+        "error.plugin_no_decoder",
+        "沒有啟用的 decode 外掛",
+        "No decode plugin is enabled",
+    ),
+    (
+        "error.dictaphone_unsupported",
+        "無法轉換 {format} 錄音筆格式：目前的 FFmpeg 沒有此格式的解碼器，也沒有設定 decode 外掛",
+        "Cannot convert {format} dictation recordings: the current FFmpeg has no decoder for this format and no decode plugin is configured",
+    ),
+    (
+        "error.alignment_mismatch",
+        "對齊結果的段落數 ({actual}) 與逐字稿 ({expected}) 不一致",
+        "Alignment returned {actual} segments but the transcript has {expected}",
+    ),
+    (
+        "error.transcript_version_not_found",
+        "找不到轉錄版本 {version}",
+        "Transcript version {version} not found",
+    ),
+    ("error.job_queue_closed", "工作佇列已關閉", "Job queue has shut down"),
+    ("error.job_queue_busy", "無法取得工作佇列鎖定", "Job queue is busy"),
+    ("error.job_not_found", "找不到工作: {id}", "Job not found: {id}"),
+    (
+        "error.retention_jobs_active",
+        "專案有工作排隊或執行中，請等工作結束後再刪除過期檔案",
+        "The project has queued or running jobs; purge expired files after they finish",
+    ),
+    (
+        "error.job_already_finished",
+        "工作已結束，無法取消",
+        "Job has already finished",
+    ),
+    (
+        "error.insufficient_space_detail",
+        "磁碟空間不足: {target} 需要約 {required}，但只剩 {available}",
+        "Not enough disk space: {target} needs about {required} but only {available} is free",
+    ),
+    // 拖放檔案
+    ("drop.not_found", "找不到檔案", "File not found"),
+    (
+        "drop.unsupported_type",
+        "不支援的檔案類型",
+        "Unsupported file type",
+    ),
+    // 口述筆記 (notes.md)
+    ("notes.title", "口述筆記", "Dictated notes"),
+    // 錄音品質檢查
+    (
+        "quality.clipping",
+        "削波 (爆音) 樣本佔 {percent}%",
+        "{percent}% of samples are clipped",
+    ),
+    (
+        "quality.digital_silence",
+        "共有 {seconds} 秒完全無聲 (數位靜音)",
+        "{seconds} s of digital silence",
+    ),
+    (
+        "quality.dropouts",
+        "偵測到 {count} 次斷訊",
+        "{count} dropouts detected",
+    ),
+    (
+        "quality.dc_offset",
+        "直流偏移 {level}",
+        "DC offset of {level}",
+    ),
+    (
+        "quality.corrupted",
+        "{count} 個音訊封包損毀無法解碼",
+        "{count} audio packets are corrupted",
+    ),
+    (
+        "error.quality_analysis",
+        "無法分析音檔品質: {detail}",
+        "Cannot analyze audio quality: {detail}",
+    ),
+    (
+        "error.channel_analysis",
+        "無法分析聲道: {detail}",
+        "Cannot analyze channels: {detail}",
+    ),
+    (
+        "error.annotations_no_project",
+        "請先開啟或建立專案再使用標註",
+        "Open or create a project before using annotations",
+    ),
+    (
+        "error.no_annotations",
+        "這個音檔沒有符合的標註",
+        "This audio file has no matching annotations",
+    ),
+    (
+        "error.transcribe_no_project",
+        "請先開啟或建立專案再整批轉錄",
+        "Open or create a project before batch transcription",
+    ),
+    (
+        "error.no_split_files",
+        "02_split 內沒有音檔",
+        "There are no audio files in 02_split",
+    ),
+    (
+        "error.timeline_empty",
+        "檔案中沒有可用的時間區段",
+        "The file contains no usable time ranges",
+    ),
+    (
+        "error.waveform",
+        "無法讀取波形: {detail}",
+        "Cannot load waveform: {detail}",
+    ),
+    // 處理結果
+    ("result.project_created", "專案建立成功: {path}", "Project created: {path}"),
+    ("result.project_opened", "專案開啟成功: {path}", "Project opened: {path}"),
+    (
+        "result.project_opened_read_only",
+        "已以檢視模式開啟專案 (唯讀): {path}",
+        "Project opened in read-only viewer mode: {path}",
+    ),
+    (
+        "result.project_issues",
+        "⚠️ 專案檢查發現問題:\n{summary}",
+        "⚠️ Project check found problems:\n{summary}",
+    ),
+    (
+        "result.docx_converted",
+        "轉換成功！\nDOCX 檔案位置: {path}",
+        "Converted!\nDOCX file: {path}",
+    ),
+    (
+        "result.convert_summary",
+        "轉檔完成！成功: {success} 個，失敗: {failed} 個\n檔案位置: {location}\n(已依照檔名自動分類專案資料夾)\n\n{details}",
+        "Conversion finished! Succeeded: {success}, failed: {failed}\nLocation: {location}\n(Project folders are named after each file)\n\n{details}",
+    ),
+    (
+        "result.convert_path_error",
+        "✗ {file} - 路徑錯誤: {detail}",
+        "✗ {file} - invalid path: {detail}",
+    ),
+    (
+        "result.convert_mkdir_error",
+        "✗ {file} - 無法建立資料夾: {detail}",
+        "✗ {file} - cannot create folders: {detail}",
+    ),
+    (
+        "result.split_summary",
+        "切割完成！共產生 {count} 個檔案\n輸出目錄: {dir}\n\n{files}",
+        "Split finished! {count} files created\nOutput folder: {dir}\n\n{files}",
+    ),
+    (
+        "result.output_renamed",
+        "↻ 檔案已存在，改名輸出: {from} → {to}",
+        "↻ File already existed, saved as: {from} → {to}",
+    ),
+    (
+        "result.folder_transcribed",
+        "整批轉錄完成！完成 {done} 個，略過 {skipped} 個 (已有逐字稿)，失敗 {failed} 個",
+        "Batch transcription finished! {done} done, {skipped} skipped (already transcribed), {failed} failed",
+    ),
+    (
+        "result.split_transcribed",
+        "已同時轉錄 {done} 個片段，失敗 {failed} 個 (逐字稿存於 .silence_reg)",
+        "Transcribed {done} segments while splitting, {failed} failed (transcripts saved in .silence_reg)",
+    ),
+    (
+        "result.pipeline_done",
+        "流程完成！處理了 {files} 個檔案，{transcripts} 份逐字稿\n報告: {report}",
+        "Pipeline finished! {files} files processed, {transcripts} transcripts\nReport: {report}",
+    ),
+    (
+        "result.silence_done",
+        "消音處理完成！\n輸出檔案: {path}",
+        "Silencing finished!\nOutput file: {path}",
+    ),
+    (
+        "result.report_docx_done",
+        "\n\n✅ 已自動轉換為 Word 文件: {path}",
+        "\n\n✅ Converted to Word document: {path}",
+    ),
+    (
+        "result.report_docx_blocked_deid",
+        "\n\n⛔ 去識別化檢查有未確認的結果，暫不轉換 Word 文件；確認後再轉換",
+        "\n\n⛔ De-identification findings need review; Word conversion is on hold until they are acknowledged",
+    ),
+    (
+        "result.deid_findings",
+        "\n\n⚠️ 去識別化檢查發現 {count} 處可能的個資，請確認",
+        "\n\n⚠️ De-identification check found {count} possible personal identifiers; please review",
+    ),
+    (
+        "result.report_docx_failed",
+        "\n\n⚠️ Word 轉換失敗 (請確認已安裝 Pandoc): {detail}",
+        "\n\n⚠️ Word conversion failed (is Pandoc installed?): {detail}",
+    ),
+    (
+        "result.denoise_summary",
+        "降噪完成！\n輸出檔案: {file}",
+        "Noise reduction finished!\nOutput file: {file}",
+    ),
+    (
+        "result.denoise_failed",
+        "✗ {file} - 降噪失敗: {detail}",
+        "✗ {file} - noise reduction failed: {detail}",
+    ),
+    (
+        "result.converter_ready",
+        "Converter 已就緒，輸出目錄: {dir}",
+        "Converter ready, output folder: {dir}",
+    ),
+    // 工作進度
+    ("progress.denoising", "降噪中", "Reducing noise"),
+    ("progress.enhancing", "強化人聲中", "Enhancing speech"),
+    (
+        "progress.converting",
+        "轉檔中 ({current}/{total})",
+        "Converting ({current}/{total})",
+    ),
+    ("progress.splitting", "切割 {count} 個段落", "Splitting {count} segments"),
+    ("progress.silencing", "消音處理中", "Applying silence"),
+    (
+        "progress.pipeline_transcribing",
+        "轉錄中 ({current}/{total}) {file}",
+        "Transcribing ({current}/{total}) {file}",
+    ),
+    (
+        "progress.pipeline_redacting",
+        "依規則消音 ({current}/{total})，{count} 個段落",
+        "Redacting by rules ({current}/{total}), {count} segments",
+    ),
+    (
+        "progress.experiment",
+        "Prompt 實驗 ({current}/{total}) {label}",
+        "Prompt experiment ({current}/{total}) {label}",
+    ),
+    (
+        "progress.reporting",
+        "正在處理 ({current}/{total}) {file}",
+        "Processing ({current}/{total}) {file}",
+    ),
+    // 完成通知
+    ("notify.kind.report", "報告", "Report"),
+    ("notify.kind.pipeline", "一鍵流程", "Pipeline"),
+    ("notify.kind.other", "背景工作", "Background job"),
+    ("notify.completed_title", "{kind}已完成", "{kind} finished"),
+    (
+        "notify.completed_body",
+        "耗時 {minutes} 分鐘。{message}",
+        "Took {minutes} min. {message}",
+    ),
+    ("notify.failed_title", "{kind}失敗", "{kind} failed"),
+    (
+        "notify.failed_body",
+        "耗時 {minutes} 分鐘後失敗: {error}",
+        "Failed after {minutes} min: {error}",
+    ),
+    (
+        "notify.email_attachment",
+        "附件為產生的報告: {file}",
+        "The generated report is attached: {file}",
+    ),
+    // 專案完整性檢查
+    ("validation.ok", "專案檢查通過", "Project check passed"),
+    (
+        "validation.manifest_error",
+        "描述檔錯誤: {detail}",
+        "Manifest error: {detail}",
+    ),
+    (
+        "validation.missing_dir",
+        "缺少資料夾: {dir}",
+        "Missing folder: {dir}",
+    ),
+    (
+        "validation.missing_file",
+        "檔案遺失: {file}",
+        "Missing file: {file}",
+    ),
+    (
+        "validation.corrupt_file",
+        "檔案內容已變更或毀損: {file}",
+        "File was changed or is corrupted: {file}",
+    ),
+    // 設定檢查
+    ("settings.stage_upload", "上傳", "upload"),
+    (
+        "settings.bitrate_range",
+        "{stage} 的位元率必須介於 8 到 320 kbps",
+        "The {stage} bitrate must be between 8 and 320 kbps",
+    ),
+    (
+        "settings.sample_rate_range",
+        "{stage} 的取樣率必須介於 8000 到 48000 Hz",
+        "The {stage} sample rate must be between 8000 and 48000 Hz",
+    ),
+    (
+        "settings.unsupported_language",
+        "不支援的語言: {language}",
+        "Unsupported language: {language}",
+    ),
+    (
+        "settings.concurrent_jobs_range",
+        "同時執行的工作數量必須介於 1 到 {max}",
+        "Concurrent jobs must be between 1 and {max}",
+    ),
+    (
+        "settings.parallel_uploads_range",
+        "同時上傳的檔案數必須介於 1 到 {max}",
+        "Parallel uploads must be between 1 and {max}",
+    ),
+    (
+        "settings.max_backups_range",
+        "備份保留份數必須介於 1 到 {max}",
+        "Backups to keep must be between 1 and {max}",
+    ),
+    (
+        "settings.webhook_url_scheme",
+        "Webhook 網址必須以 http:// 或 https:// 開頭: {url}",
+        "The webhook URL must start with http:// or https://: {url}",
+    ),
+    (
+        "settings.webhook_url_required",
+        "啟用 webhook 時必須填寫網址",
+        "Enter a URL to enable the webhook",
+    ),
+    (
+        "settings.batch_hours_range",
+        "批次總長度上限必須大於或等於 0",
+        "The batch length limit must be 0 or more",
+    ),
+    (
+        "settings.denoise_strength_range",
+        "降噪量必須介於 0.01 到 97 dB",
+        "Noise reduction must be between 0.01 and 97 dB",
+    ),
+    (
+        "settings.deid_pattern_invalid",
+        "去識別化規則格式錯誤 ({label}): {detail}",
+        "Invalid de-identification pattern ({label}): {detail}",
+    ),
+    (
+        "settings.toc_depth_range",
+        "目錄層級必須介於 1 到 6",
+        "Table of contents depth must be between 1 and 6",
+    ),
+    (
+        "settings.plugin_incomplete",
+        "外掛必須填寫名稱與執行檔",
+        "Plugins need a name and a command",
+    ),
+    (
+        "settings.plugin_timeout",
+        "外掛 {name} 的逾時必須大於 0 秒",
+        "The timeout for plugin {name} must be more than 0 seconds",
+    ),
+    (
+        "settings.ffmpeg_threads_range",
+        "FFmpeg 執行緒數量必須介於 0 (自動) 到 {max}",
+        "FFmpeg threads must be between 0 (auto) and {max}",
+    ),
+    (
+        "settings.http_timeout",
+        "連線逾時必須大於 0 秒",
+        "Connection timeouts must be more than 0 seconds",
+    ),
+    (
+        "settings.email_incomplete",
+        "啟用電子郵件通知時必須填寫 SMTP 伺服器、寄件人與收件人",
+        "Email notifications need an SMTP server, a sender and recipients",
+    ),
+];
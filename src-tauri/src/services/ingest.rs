@@ -0,0 +1,200 @@
+// src-tauri/src/services/ingest.rs
+//
+// 拖放檔案到視窗時的處理：檢查檔案類型，放入該視窗目前的專案並排入轉檔工作，
+// 轉檔結束後檢查錄音品質與重複匯入，以 `drop://ingested` 通知該視窗產生了哪些檔案、
+// 哪些錄音可能無法使用或已經匯入過。
+
+use crate::models::AppError;
+use crate::services::file_manager::{current_project, CurrentProjectState};
+use crate::services::fingerprint::{self, DuplicatePair};
+use crate::services::jobs::{JobManager, JobSpec};
+use crate::services::quality::{self, QualityReport};
+use crate::services::workflows::resolve_project;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const INGESTED_EVENT: &str = "drop://ingested";
+
+/// 可轉檔的影音格式 (與轉檔頁面的檔案選擇器相同)
+pub const MEDIA_EXTENSIONS: &[&str] = &[
+    "mp4", "mkv", "avi", "mov", "wmv", "flv", "webm", "mp3", "wav", "flac", "aac", "ogg", "m4a",
+    "m4b", "wma", "amr", "dss", "ds2",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedFile {
+    pub path: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IngestResult {
+    pub job_id: Option<String>,
+    /// 拖放時視窗開啟的專案；沒有專案時依檔名建立
+    pub project_root: Option<String>,
+    pub accepted: Vec<String>,
+    pub rejected: Vec<RejectedFile>,
+    /// 轉檔產生的 MP3
+    pub created: Vec<String>,
+    /// 品質檢查發現問題的 MP3
+    pub flagged: Vec<QualityReport>,
+    /// 與專案內其他錄音疑似重複 (同一場錄音匯入兩次)
+    pub duplicates: Vec<DuplicatePair>,
+    pub error: Option<AppError>,
+}
+
+/// 處理拖放事件 (在背景執行，不阻塞視窗事件)
+pub fn handle_drop(app: &AppHandle, window_label: &str, paths: Vec<PathBuf>) {
+    let project_root = current_project(&app.state::<CurrentProjectState>(), window_label)
+        .map(|p| p.to_string_lossy().to_string());
+    let (accepted, rejected) = classify(&paths);
+    tracing::info!(
+        "拖放檔案: {} 個可轉檔，{} 個略過",
+        accepted.len(),
+        rejected.len()
+    );
+
+    let mut result = IngestResult {
+        job_id: None,
+        project_root,
+        accepted,
+        rejected,
+        created: Vec::new(),
+        flagged: Vec::new(),
+        duplicates: Vec::new(),
+        error: None,
+    };
+
+    if result.accepted.is_empty() {
+        let _ = app.emit_to(window_label, INGESTED_EVENT, &result);
+        return;
+    }
+
+    let app = app.clone();
+    let label = window_label.to_string();
+    tauri::async_runtime::spawn(async move {
+        let jobs = app.state::<JobManager>().inner().clone();
+        let spec = JobSpec::Convert {
+            file_paths: result.accepted.clone(),
+            project_root: result.project_root.clone(),
+        };
+        let job = jobs.enqueue(spec, 0);
+        result.job_id = Some(job.id.clone());
+
+        if let Err(e) = jobs.wait(&job.id).await {
+            result.error = Some(e);
+        }
+        result.created = converted_outputs(result.project_root.as_deref(), &result.accepted);
+        let created = result.created.clone();
+        result.flagged = tauri::async_runtime::spawn_blocking(move || check_quality(&created))
+            .await
+            .unwrap_or_default();
+        if let Some(root) = result.project_root.clone() {
+            let created = result.created.clone();
+            result.duplicates =
+                tauri::async_runtime::spawn_blocking(move || check_duplicates(&root, &created))
+                    .await
+                    .unwrap_or_default();
+        }
+        let _ = app.emit_to(label.as_str(), INGESTED_EVENT, &result);
+    });
+}
+
+/// 檢查轉出的 MP3，只回傳有問題的檔案
+fn check_quality(paths: &[String]) -> Vec<QualityReport> {
+    paths
+        .iter()
+        .filter_map(|path| match quality::analyze(path) {
+            Ok(report) => Some(report),
+            Err(e) => {
+                tracing::warn!("無法檢查錄音品質 {}: {}", path, e);
+                None
+            }
+        })
+        .filter(|report| !report.issues.is_empty())
+        .collect()
+}
+
+/// 找出與這次轉出的檔案重複的錄音
+fn check_duplicates(root: &str, created: &[String]) -> Vec<DuplicatePair> {
+    match fingerprint::find_duplicates(Path::new(root)) {
+        Ok(pairs) => pairs
+            .into_iter()
+            .filter(|pair| created.contains(&pair.a) || created.contains(&pair.b))
+            .collect(),
+        Err(e) => {
+            tracing::warn!("無法比對重複錄音 {}: {}", root, e);
+            Vec::new()
+        }
+    }
+}
+
+/// 分成可轉檔與略過的檔案；資料夾只展開第一層
+fn classify(paths: &[PathBuf]) -> (Vec<String>, Vec<RejectedFile>) {
+    let mut accepted = Vec::new();
+    let mut rejected = Vec::new();
+
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            match std::fs::read_dir(path) {
+                Ok(entries) => {
+                    let mut children: Vec<PathBuf> = entries
+                        .filter_map(|e| e.ok())
+                        .map(|e| e.path())
+                        .filter(|p| p.is_file())
+                        .collect();
+                    children.sort();
+                    files.extend(children);
+                }
+                Err(e) => rejected.push(RejectedFile {
+                    path: path.to_string_lossy().to_string(),
+                    reason: e.to_string(),
+                }),
+            }
+        } else {
+            files.push(path.clone());
+        }
+    }
+
+    for file in files {
+        let path = file.to_string_lossy().to_string();
+        if !file.is_file() {
+            rejected.push(RejectedFile {
+                path,
+                reason: crate::tr!("drop.not_found"),
+            });
+        } else if !is_media_file(&file) {
+            rejected.push(RejectedFile {
+                path,
+                reason: crate::tr!("drop.unsupported_type"),
+            });
+        } else if !accepted.contains(&path) {
+            accepted.push(path);
+        }
+    }
+
+    (accepted, rejected)
+}
+
+pub fn is_media_file(path: &Path) -> bool {
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| MEDIA_EXTENSIONS.contains(&ext.as_str()))
+}
+
+/// 依轉檔規則推算輸出檔，只列出實際存在的檔案
+fn converted_outputs(project_root: Option<&str>, inputs: &[String]) -> Vec<String> {
+    inputs
+        .iter()
+        .filter_map(|input| {
+            let paths = resolve_project(project_root, input).ok()?;
+            let stem = Path::new(input).file_stem()?.to_string_lossy().to_string();
+            let output = paths.converted.join(format!("{}.mp3", stem));
+            output
+                .exists()
+                .then(|| output.to_string_lossy().to_string())
+        })
+        .collect()
+}
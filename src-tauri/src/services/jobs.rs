@@ -0,0 +1,1078 @@
+// src-tauri/src/services/jobs.rs
+//
+// 背景工作佇列：所有耗時操作 (轉檔、切割、消音、報告、逐字稿) 都以工作的形式排入佇列，
+// 具備工作編號、優先順序、進度、取消，並保存到 app data 目錄，重新啟動後可繼續執行。
+//
+// - JobSpec: 工作內容 (可序列化，用於保存與重新執行)
+// - JobManager: 佇列與背景 worker，狀態變化時發出 `job://event`
+// - CancelToken: 取消旗標，傳給各 service 以中止 FFmpeg / 處理迴圈
+// - 暫停佇列：不再開始新工作，執行中的 FFmpeg 暫停 (見 sidecar.rs)，不取消任何工作
+// - 互動工作 (使用者在畫面上等待結果的單檔操作) 有獨立的執行名額，不會排在長時間的報告 / 轉檔之後
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::experiments::PromptVariant;
+use crate::services::file_manager::write_atomic;
+use crate::services::pipeline::PipelineOptions;
+use crate::services::{history, notifications, sidecar, webhook, workflows};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::{oneshot, Notify};
+
+pub const JOB_EVENT: &str = "job://event";
+/// 佇列暫停 / 繼續時發出，內容為 QueueStatus
+pub const QUEUE_EVENT: &str = "job://queue";
+
+/// 工作被取消時回傳的錯誤訊息
+pub const CANCELLED_MESSAGE: &str = "工作已取消";
+
+/// 同時執行的工作數量 (預設值，可由設定調整，不含互動工作)
+const DEFAULT_MAX_CONCURRENT_JOBS: usize = 1;
+
+/// 互動工作另外可同時執行的數量
+const INTERACTIVE_JOB_SLOTS: usize = 1;
+
+/// 結束程式時等待執行中工作停止的時間
+pub const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 保存時最多保留的已結束工作數量
+const MAX_FINISHED_JOBS: usize = 100;
+
+/// 取消旗標 (可跨執行緒複製)
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    inner: Arc<CancelInner>,
+}
+
+#[derive(Default)]
+struct CancelInner {
+    cancelled: AtomicBool,
+    notify: Notify,
+    /// 佇列工作的旗標：佇列暫停時，此工作啟動的 FFmpeg 一併暫停
+    job: bool,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 佇列工作使用的取消旗標
+    fn for_job() -> Self {
+        Self {
+            inner: Arc::new(CancelInner {
+                job: true,
+                ..CancelInner::default()
+            }),
+        }
+    }
+
+    /// 是否為佇列工作的旗標 (見 sidecar.rs 的暫停)
+    pub fn is_job(&self) -> bool {
+        self.inner.job
+    }
+
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::SeqCst);
+        self.inner.notify.notify_waiters();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::SeqCst)
+    }
+
+    /// 等待直到被取消
+    pub async fn cancelled(&self) {
+        loop {
+            // 先註冊再檢查，避免錯過 notify_waiters
+            let notified = self.inner.notify.notified();
+            if self.is_cancelled() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+/// 切割時同時轉錄的設定
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscribeOnSplit {
+    pub server: String,
+    #[serde(default)]
+    pub diarize: bool,
+}
+
+/// 工作內容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JobSpec {
+    /// 轉檔為 MP3 (project_root 為 None 時依檔名建立專案)
+    Convert {
+        file_paths: Vec<String>,
+        project_root: Option<String>,
+    },
+    /// 依段落切割 (name, start_time, end_time)
+    Split {
+        audio_path: String,
+        project_root: Option<String>,
+        segments: Vec<(String, String, String)>,
+        /// 每切出一段就送去轉錄 (與切割同時進行)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        transcribe: Option<TranscribeOnSplit>,
+    },
+    /// 專案流程的手動消音 (輸出到 03_silence，並整理 03_silence 內的原始檔)
+    Silence {
+        audio_path: String,
+        project_root: Option<String>,
+        segments: Vec<(f64, f64)>,
+    },
+    /// 消音並輸出到指定資料夾
+    SilenceToDir {
+        input_path: String,
+        output_dir: String,
+        segments: Vec<(f64, f64)>,
+    },
+    /// 只保留指定時段並串接成新檔 (例如取出單一說話者的聲音)
+    Extract {
+        input_path: String,
+        output_path: String,
+        segments: Vec<(f64, f64)>,
+    },
+    /// 從播放器選取的時段直接輸出成檔案 (秒)
+    ExportRange {
+        input_path: String,
+        output_path: String,
+        start: f64,
+        end: f64,
+    },
+    /// 降噪並輸出到專案的 01b_cleaned
+    Denoise {
+        audio_path: String,
+        project_root: Option<String>,
+    },
+    /// 人聲強化 (高通、壓縮、去齒音)，輸出到專案的 01b_cleaned
+    Enhance {
+        audio_path: String,
+        project_root: Option<String>,
+    },
+    /// 生成報告並轉為 DOCX
+    Report {
+        folder_path: String,
+        model_name: Option<String>,
+        custom_prompt: Option<String>,
+        /// API Key 不寫入磁碟
+        #[serde(default, skip_serializing)]
+        api_key: String,
+    },
+    /// 送至 STT 伺服器取得逐字稿
+    Transcribe {
+        server: String,
+        file_path: String,
+        /// 區分說話者
+        #[serde(default)]
+        diarize: bool,
+    },
+    /// 將專案 02_split 內的所有音檔送至 STT 伺服器 (限制同時上傳數，已有逐字稿的略過)
+    TranscribeFolder {
+        server: String,
+        project_root: String,
+        #[serde(default)]
+        diarize: bool,
+    },
+    /// 以校正後的逐字稿對齊音檔，更新段落與逐字時間
+    Align {
+        server: String,
+        file_path: String,
+        transcript_path: String,
+    },
+    /// Prompt 實驗：同一音檔以多組 Prompt / 模型生成並比較，輸出到 04_report/experiments
+    PromptExperiment {
+        audio_path: String,
+        variants: Vec<PromptVariant>,
+        /// API Key 不寫入磁碟
+        #[serde(default, skip_serializing)]
+        api_key: String,
+    },
+    /// 一鍵流程：轉檔 → 切割 → 轉錄 → 消音 → 報告 (狀態保存在專案的 pipeline.json)
+    Pipeline {
+        file_paths: Vec<String>,
+        project_root: String,
+        options: PipelineOptions,
+        /// API Key 不寫入磁碟
+        #[serde(default, skip_serializing)]
+        api_key: String,
+    },
+}
+
+impl JobSpec {
+    /// 工作使用的 API Key (不保存，重新執行時需再次提供)
+    pub fn api_key_mut(&mut self) -> Option<&mut String> {
+        match self {
+            JobSpec::Report { api_key, .. }
+            | JobSpec::PromptExperiment { api_key, .. }
+            | JobSpec::Pipeline { api_key, .. } => Some(api_key),
+            _ => None,
+        }
+    }
+
+    /// 執行時一定需要 API Key
+    pub fn requires_api_key(&self) -> bool {
+        match self {
+            JobSpec::Report { .. } | JobSpec::PromptExperiment { .. } => true,
+            JobSpec::Pipeline { options, .. } => options.report,
+            _ => false,
+        }
+    }
+
+    /// 工作會寫入的位置 (輸出檔、輸出資料夾，或輸出所在的專案與檔案)，加入佇列前檢查存取範圍
+    pub fn output_paths(&self) -> Vec<&str> {
+        match self {
+            JobSpec::Convert { project_root, .. }
+            | JobSpec::Split { project_root, .. }
+            | JobSpec::Silence { project_root, .. }
+            | JobSpec::Denoise { project_root, .. }
+            | JobSpec::Enhance { project_root, .. } => {
+                project_root.as_deref().into_iter().collect()
+            }
+            JobSpec::SilenceToDir { output_dir, .. } => vec![output_dir],
+            JobSpec::Extract { output_path, .. } | JobSpec::ExportRange { output_path, .. } => {
+                vec![output_path]
+            }
+            JobSpec::Report { folder_path, .. } => vec![folder_path],
+            JobSpec::Transcribe { file_path, .. } => vec![file_path],
+            JobSpec::TranscribeFolder { project_root, .. }
+            | JobSpec::Pipeline { project_root, .. } => vec![project_root],
+            JobSpec::Align {
+                file_path,
+                transcript_path,
+                ..
+            } => vec![file_path, transcript_path],
+            JobSpec::PromptExperiment { audio_path, .. } => vec![audio_path],
+        }
+    }
+
+    /// 使用者在畫面上等待結果的單檔操作，使用互動工作的名額
+    pub fn is_interactive(&self) -> bool {
+        matches!(
+            self,
+            JobSpec::Transcribe { .. }
+                | JobSpec::Align { .. }
+                | JobSpec::SilenceToDir { .. }
+                | JobSpec::Extract { .. }
+                | JobSpec::ExportRange { .. }
+        )
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            JobSpec::Convert { .. } => "convert",
+            JobSpec::Split { .. } | JobSpec::ExportRange { .. } => "split",
+            JobSpec::Silence { .. } | JobSpec::SilenceToDir { .. } => "silence",
+            JobSpec::Extract { .. } => "extract",
+            JobSpec::Denoise { .. } => "denoise",
+            JobSpec::Enhance { .. } => "enhance",
+            JobSpec::Report { .. } => "report",
+            JobSpec::Transcribe { .. } | JobSpec::TranscribeFolder { .. } => "transcribe",
+            JobSpec::Align { .. } => "align",
+            JobSpec::PromptExperiment { .. } => "experiment",
+            JobSpec::Pipeline { .. } => "pipeline",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Completed | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub id: String,
+    pub kind: String,
+    pub spec: JobSpec,
+    /// 數字越大越優先
+    pub priority: i32,
+    pub status: JobStatus,
+    /// 0.0 ~ 1.0
+    pub progress: f32,
+    pub message: Option<String>,
+    pub result: Option<Value>,
+    pub error: Option<AppError>,
+    pub created_at: String,
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+}
+
+type JobResult = Result<Value, AppError>;
+
+/// 佇列狀態
+#[derive(Debug, Clone, Serialize)]
+pub struct QueueStatus {
+    pub paused: bool,
+    pub running: usize,
+    pub queued: usize,
+    /// 此平台能暫停執行中的 FFmpeg (否則暫停後會等目前的 FFmpeg 執行完)
+    pub can_suspend: bool,
+}
+
+struct QueueState {
+    jobs: Vec<Job>,
+    tokens: HashMap<String, CancelToken>,
+    waiters: HashMap<String, Vec<oneshot::Sender<JobResult>>>,
+    running: usize,
+    /// 執行中的互動工作 (包含在 running 內)
+    running_interactive: usize,
+    app: Option<AppHandle>,
+}
+
+struct Inner {
+    state: Mutex<QueueState>,
+    wake: Notify,
+    /// 所有執行中的工作都結束時通知 (關閉程式時等待用)
+    idle: Notify,
+    /// 關閉中：不再啟動新工作，被中斷的工作保留在佇列中
+    shutting_down: AtomicBool,
+    /// 暫停中：不啟動新工作 (不保存，重新啟動後自動繼續)
+    paused: AtomicBool,
+    persist_path: Option<PathBuf>,
+    next_seq: AtomicU64,
+    max_concurrent: AtomicUsize,
+}
+
+/// 工作佇列 (可複製，內部共用同一份狀態)
+#[derive(Clone)]
+pub struct JobManager {
+    inner: Arc<Inner>,
+}
+
+/// 執行中工作的上下文，傳給 workflows
+#[derive(Clone)]
+pub struct JobContext {
+    pub app: AppHandle,
+    pub id: String,
+    pub cancel: CancelToken,
+    manager: JobManager,
+    /// 工作產生的檔案，完成時寫入專案操作紀錄
+    outputs: Arc<Mutex<Vec<PathBuf>>>,
+    /// 進度對應到整體工作的範圍 (多階段工作的子流程使用)
+    range: (f32, f32),
+}
+
+impl JobContext {
+    /// 更新工作進度 (0.0 ~ 1.0) 與訊息
+    pub fn progress(&self, progress: f32, message: impl Into<String>) {
+        let message = message.into();
+        let (start, end) = self.range;
+        let progress = start + progress.clamp(0.0, 1.0) * (end - start);
+        self.manager.update(&self.id, |job| {
+            job.progress = progress;
+            job.message = Some(message);
+        });
+    }
+
+    /// 子流程的上下文：子流程回報的 0.0 ~ 1.0 對應到整體的 start ~ end
+    pub fn with_range(&self, start: f32, end: f32) -> JobContext {
+        let (outer_start, outer_end) = self.range;
+        let span = outer_end - outer_start;
+        JobContext {
+            range: (outer_start + start * span, outer_start + end * span),
+            ..self.clone()
+        }
+    }
+
+    /// 記錄工作產生或修改的檔案
+    pub fn record_output(&self, path: impl Into<PathBuf>) {
+        if let Ok(mut outputs) = self.outputs.lock() {
+            outputs.push(path.into());
+        }
+    }
+
+    fn outputs(&self) -> Vec<PathBuf> {
+        self.outputs
+            .lock()
+            .map(|outputs| outputs.clone())
+            .unwrap_or_default()
+    }
+
+    pub fn check_cancelled(&self) -> Result<(), AppError> {
+        if self.cancel.is_cancelled() {
+            Err(AppError::cancelled())
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl JobManager {
+    /// 建立佇列，並讀取上次保存的工作
+    /// 上次關閉時仍在執行的工作會重新排入佇列；需要 API Key 的工作 (Key 不保存)
+    /// 標記為失敗，由使用者提供 Key 後重新執行
+    pub fn load(persist_path: Option<PathBuf>) -> Self {
+        let mut jobs: Vec<Job> = persist_path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        for job in &mut jobs {
+            if job.status.is_finished() {
+                continue;
+            }
+            if job.spec.requires_api_key() {
+                job.status = JobStatus::Failed;
+                job.error = Some(AppError::localized(
+                    ErrorKind::InvalidInput,
+                    "error.missing_api_key_resumed",
+                    &[],
+                ));
+                job.finished_at = Some(chrono::Local::now().to_rfc3339());
+            } else if job.status == JobStatus::Running {
+                job.status = JobStatus::Queued;
+                job.progress = 0.0;
+                job.started_at = None;
+            }
+        }
+
+        Self {
+            inner: Arc::new(Inner {
+                state: Mutex::new(QueueState {
+                    jobs,
+                    tokens: HashMap::new(),
+                    waiters: HashMap::new(),
+                    running: 0,
+                    running_interactive: 0,
+                    app: None,
+                }),
+                wake: Notify::new(),
+                idle: Notify::new(),
+                shutting_down: AtomicBool::new(false),
+                paused: AtomicBool::new(false),
+                persist_path,
+                next_seq: AtomicU64::new(0),
+                max_concurrent: AtomicUsize::new(DEFAULT_MAX_CONCURRENT_JOBS),
+            }),
+        }
+    }
+
+    /// 啟動背景 worker
+    pub fn start(&self, app: AppHandle) {
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.app = Some(app.clone());
+        }
+        let manager = self.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                match manager.take_next() {
+                    Some((job, cancel)) => {
+                        let ctx = JobContext {
+                            app: app.clone(),
+                            id: job.id.clone(),
+                            cancel,
+                            manager: manager.clone(),
+                            outputs: Arc::default(),
+                            range: (0.0, 1.0),
+                        };
+                        let worker = manager.clone();
+                        tauri::async_runtime::spawn(async move {
+                            let started = Instant::now();
+                            // 在獨立的 task 執行：workflows panic 時仍會結束工作並釋放執行名額
+                            let (run_ctx, spec) = (ctx.clone(), job.spec.clone());
+                            let result = tauri::async_runtime::spawn(async move {
+                                workflows::execute(&run_ctx, &spec).await
+                            })
+                            .await
+                            .unwrap_or_else(|e| {
+                                tracing::error!("工作 {} 異常結束: {}", job.id, e);
+                                Err(AppError::internal(e.to_string()))
+                            });
+                            history::record_job(&job, &ctx.outputs(), started, &result);
+                            webhook::notify_job(&job, &ctx.outputs(), &result);
+                            notifications::notify_job(
+                                &ctx.app,
+                                &job,
+                                &ctx.outputs(),
+                                started.elapsed(),
+                                &result,
+                            );
+                            worker.finish(&job.id, result);
+                        });
+                    }
+                    None => manager.inner.wake.notified().await,
+                }
+            }
+        });
+    }
+
+    /// 加入工作，立即回傳工作資訊
+    pub fn enqueue(&self, spec: JobSpec, priority: i32) -> Job {
+        let now = chrono::Local::now();
+        let seq = self.inner.next_seq.fetch_add(1, Ordering::Relaxed);
+        let job = Job {
+            id: format!("job-{}-{}", now.timestamp_millis(), seq),
+            kind: spec.kind().to_string(),
+            spec,
+            priority,
+            status: JobStatus::Queued,
+            progress: 0.0,
+            message: None,
+            result: None,
+            error: None,
+            created_at: now.to_rfc3339(),
+            started_at: None,
+            finished_at: None,
+        };
+
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.jobs.push(job.clone());
+            self.emit(&state, &job);
+            self.persist(&state);
+        }
+        self.inner.wake.notify_one();
+        job
+    }
+
+    /// 以相同內容重新執行工作：先找佇列中的紀錄，找不到時從專案操作紀錄讀取
+    /// api_key 有值時取代原本的 API Key (保存的紀錄不含 API Key)
+    pub fn rerun(
+        &self,
+        id: &str,
+        project: Option<&Path>,
+        api_key: Option<String>,
+        priority: i32,
+    ) -> Result<Job, AppError> {
+        let previous = self.get(id).map(|job| job.spec);
+        let mut spec = match (previous, project) {
+            (Some(spec), _) => spec,
+            (None, Some(root)) => history::find_job_spec(root, id)
+                .map_err(AppError::io)?
+                .ok_or_else(|| {
+                    AppError::localized(
+                        ErrorKind::NotFound,
+                        "error.job_not_found",
+                        &[("id", id.to_string())],
+                    )
+                })?,
+            (None, None) => {
+                return Err(AppError::localized(
+                    ErrorKind::NotFound,
+                    "error.job_not_found",
+                    &[("id", id.to_string())],
+                ))
+            }
+        };
+        if let (Some(slot), Some(key)) = (spec.api_key_mut(), api_key.filter(|k| !k.is_empty())) {
+            *slot = key;
+        }
+        if spec.requires_api_key() && spec.api_key_mut().map(|k| k.is_empty()).unwrap_or(true) {
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.missing_api_key",
+                &[],
+            ));
+        }
+        let job = self.enqueue(spec, priority);
+        tracing::info!("重新執行工作 {} → {}", id, job.id);
+        Ok(job)
+    }
+
+    /// 加入工作並等待完成，回傳工作結果
+    /// 供原本同步等待結果的命令使用
+    pub async fn enqueue_and_wait(&self, spec: JobSpec, priority: i32) -> JobResult {
+        let job = self.enqueue(spec, priority);
+        self.wait(&job.id).await
+    }
+
+    /// 等待工作完成，回傳工作結果
+    pub async fn wait(&self, id: &str) -> JobResult {
+        let (tx, rx) = oneshot::channel();
+        if let Ok(mut state) = self.inner.state.lock() {
+            // 工作可能在登記前就已結束
+            match state.jobs.iter().find(|j| j.id == id) {
+                Some(done) if done.status.is_finished() => return job_outcome(done),
+                Some(_) => state.waiters.entry(id.to_string()).or_default().push(tx),
+                None => {
+                    return Err(AppError::localized(
+                        ErrorKind::NotFound,
+                        "error.job_not_found",
+                        &[("id", id.to_string())],
+                    ))
+                }
+            }
+        }
+        rx.await.unwrap_or_else(|_| {
+            Err(AppError::localized(
+                ErrorKind::Internal,
+                "error.job_queue_closed",
+                &[],
+            ))
+        })
+    }
+
+    /// 調整同時執行的工作數量 (至少 1)
+    pub fn set_max_concurrent(&self, max: usize) {
+        self.inner
+            .max_concurrent
+            .store(max.max(1), Ordering::Relaxed);
+        // 上限提高時立即開始排隊中的工作
+        self.inner.wake.notify_one();
+    }
+
+    pub fn list(&self) -> Vec<Job> {
+        self.inner
+            .state
+            .lock()
+            .map(|state| state.jobs.clone())
+            .unwrap_or_default()
+    }
+
+    /// 專案是否有排隊中或執行中的工作
+    pub fn has_active_jobs(&self, root: &Path) -> bool {
+        self.inner
+            .state
+            .lock()
+            .map(|state| {
+                state.jobs.iter().any(|job| {
+                    !job.status.is_finished()
+                        && history::job_projects(&job.spec)
+                            .iter()
+                            .any(|project| project == root)
+                })
+            })
+            .unwrap_or(false)
+    }
+
+    pub fn get(&self, id: &str) -> Option<Job> {
+        self.inner
+            .state
+            .lock()
+            .ok()
+            .and_then(|state| state.jobs.iter().find(|j| j.id == id).cloned())
+    }
+
+    /// 取消工作：排隊中直接標記取消，執行中則發出取消訊號
+    pub fn cancel(&self, id: &str) -> Result<(), AppError> {
+        let mut state =
+            self.inner.state.lock().map_err(|_| {
+                AppError::localized(ErrorKind::Internal, "error.job_queue_busy", &[])
+            })?;
+        let status = state
+            .jobs
+            .iter()
+            .find(|j| j.id == id)
+            .map(|j| j.status)
+            .ok_or_else(|| {
+                AppError::localized(
+                    ErrorKind::NotFound,
+                    "error.job_not_found",
+                    &[("id", id.to_string())],
+                )
+            })?;
+
+        match status {
+            JobStatus::Queued => {
+                drop(state);
+                self.complete(id, Err(AppError::cancelled()), false);
+                Ok(())
+            }
+            JobStatus::Running => {
+                if let Some(token) = state.tokens.get(id) {
+                    token.cancel();
+                }
+                Ok(())
+            }
+            _ => Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.job_already_finished",
+                &[],
+            )),
+        }
+    }
+
+    /// 暫停佇列：不再開始新工作，執行中工作的 FFmpeg 暫停 (工作不會被取消)
+    pub fn pause(&self) -> QueueStatus {
+        if !self.inner.paused.swap(true, Ordering::SeqCst) {
+            let suspended = sidecar::pause_processes();
+            tracing::info!("工作佇列已暫停 (暫停 {} 個 FFmpeg 行程)", suspended);
+        }
+        self.emit_queue_status()
+    }
+
+    /// 繼續佇列：恢復暫停的 FFmpeg 並開始排隊中的工作
+    pub fn resume(&self) -> QueueStatus {
+        if self.inner.paused.swap(false, Ordering::SeqCst) {
+            sidecar::resume_processes();
+            tracing::info!("工作佇列已繼續");
+        }
+        self.inner.wake.notify_one();
+        self.emit_queue_status()
+    }
+
+    pub fn queue_status(&self) -> QueueStatus {
+        let (running, queued) = self
+            .inner
+            .state
+            .lock()
+            .map(|state| {
+                let queued = state
+                    .jobs
+                    .iter()
+                    .filter(|j| j.status == JobStatus::Queued)
+                    .count();
+                (state.running, queued)
+            })
+            .unwrap_or_default();
+        QueueStatus {
+            paused: self.inner.paused.load(Ordering::SeqCst),
+            running,
+            queued,
+            can_suspend: sidecar::can_suspend(),
+        }
+    }
+
+    fn emit_queue_status(&self) -> QueueStatus {
+        let status = self.queue_status();
+        if let Ok(state) = self.inner.state.lock() {
+            if let Some(app) = &state.app {
+                let _ = app.emit(QUEUE_EVENT, &status);
+            }
+        }
+        status
+    }
+
+    /// 開始關閉：不再啟動新工作，並取消執行中的工作 (FFmpeg 會隨之結束)
+    /// 已在關閉中時回傳 false
+    pub fn begin_shutdown(&self) -> bool {
+        if self.inner.shutting_down.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        // 暫停中的 FFmpeg 需先繼續才能正常結束
+        sidecar::resume_processes();
+        if let Ok(state) = self.inner.state.lock() {
+            for token in state.tokens.values() {
+                token.cancel();
+            }
+        }
+        true
+    }
+
+    /// 等待執行中的工作結束 (最多 timeout) 並保存佇列，逾時回傳 false
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        let wait = async {
+            loop {
+                // 先註冊再檢查，避免錯過 notify_waiters
+                let notified = self.inner.idle.notified();
+                if self.running_count() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        };
+        let idle = tokio::time::timeout(timeout, wait).await.is_ok();
+        if let Ok(state) = self.inner.state.lock() {
+            self.persist(&state);
+        }
+        idle
+    }
+
+    fn running_count(&self) -> usize {
+        self.inner
+            .state
+            .lock()
+            .map(|state| state.running)
+            .unwrap_or(0)
+    }
+
+    /// 移除所有已結束的工作紀錄
+    pub fn clear_finished(&self) {
+        if let Ok(mut state) = self.inner.state.lock() {
+            state.jobs.retain(|j| !j.status.is_finished());
+            self.persist(&state);
+        }
+    }
+
+    /// 取出下一個要執行的工作 (優先順序高者先，同優先順序依加入順序)
+    /// 一般工作與互動工作各自計算同時執行的上限
+    fn take_next(&self) -> Option<(Job, CancelToken)> {
+        if self.inner.shutting_down.load(Ordering::SeqCst)
+            || self.inner.paused.load(Ordering::SeqCst)
+        {
+            return None;
+        }
+        let mut state = self.inner.state.lock().ok()?;
+        let background_free = state.running - state.running_interactive
+            < self.inner.max_concurrent.load(Ordering::Relaxed);
+        let interactive_free = state.running_interactive < INTERACTIVE_JOB_SLOTS;
+        if !background_free && !interactive_free {
+            return None;
+        }
+
+        let mut best: Option<usize> = None;
+        for (idx, job) in state.jobs.iter().enumerate() {
+            if job.status != JobStatus::Queued {
+                continue;
+            }
+            let free = if job.spec.is_interactive() {
+                interactive_free
+            } else {
+                background_free
+            };
+            if !free {
+                continue;
+            }
+            match best {
+                Some(b) if state.jobs[b].priority >= job.priority => {}
+                _ => best = Some(idx),
+            }
+        }
+        let idx = best?;
+
+        let token = CancelToken::for_job();
+        let job = {
+            let job = &mut state.jobs[idx];
+            job.status = JobStatus::Running;
+            job.started_at = Some(chrono::Local::now().to_rfc3339());
+            job.clone()
+        };
+        state.tokens.insert(job.id.clone(), token.clone());
+        state.running += 1;
+        if job.spec.is_interactive() {
+            state.running_interactive += 1;
+        }
+        self.emit(&state, &job);
+        self.persist(&state);
+        Some((job, token))
+    }
+
+    fn finish(&self, id: &str, result: JobResult) {
+        self.complete(id, result, true);
+        self.inner.wake.notify_one();
+    }
+
+    fn complete(&self, id: &str, result: JobResult, was_running: bool) {
+        let Ok(mut state) = self.inner.state.lock() else {
+            return;
+        };
+        let cancelled = state
+            .tokens
+            .remove(id)
+            .map(|t| t.is_cancelled())
+            .unwrap_or(false);
+        if was_running {
+            let interactive = state
+                .jobs
+                .iter()
+                .any(|j| j.id == id && j.spec.is_interactive());
+            if interactive {
+                state.running_interactive = state.running_interactive.saturating_sub(1);
+            }
+            state.running = state.running.saturating_sub(1);
+            if state.running == 0 {
+                self.inner.idle.notify_waiters();
+            }
+        }
+        let interrupted = self.inner.shutting_down.load(Ordering::SeqCst)
+            && matches!(&result, Err(e) if e.is_cancelled());
+
+        let Some(job) = state.jobs.iter_mut().find(|j| j.id == id) else {
+            return;
+        };
+        match &result {
+            // 因關閉程式而中斷的工作，下次啟動時重新執行
+            Err(_) if interrupted => {
+                job.status = JobStatus::Queued;
+                job.progress = 0.0;
+                job.message = None;
+                job.started_at = None;
+            }
+            Ok(value) => {
+                job.status = JobStatus::Completed;
+                job.progress = 1.0;
+                job.result = Some(value.clone());
+            }
+            Err(e) => {
+                job.status = if cancelled || e.is_cancelled() {
+                    JobStatus::Cancelled
+                } else {
+                    JobStatus::Failed
+                };
+                job.error = Some(e.clone());
+            }
+        }
+        if !interrupted {
+            job.finished_at = Some(chrono::Local::now().to_rfc3339());
+        }
+        let job = job.clone();
+
+        if let Some(waiters) = state.waiters.remove(id) {
+            for tx in waiters {
+                let _ = tx.send(result.clone());
+            }
+        }
+
+        // 只保留最近的已結束工作
+        let finished = state.jobs.iter().filter(|j| j.status.is_finished()).count();
+        if finished > MAX_FINISHED_JOBS {
+            let mut to_drop = finished - MAX_FINISHED_JOBS;
+            state.jobs.retain(|j| {
+                if to_drop > 0 && j.status.is_finished() {
+                    to_drop -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+
+        self.emit(&state, &job);
+        self.persist(&state);
+    }
+
+    fn update(&self, id: &str, f: impl FnOnce(&mut Job)) {
+        if let Ok(mut state) = self.inner.state.lock() {
+            if let Some(job) = state.jobs.iter_mut().find(|j| j.id == id) {
+                f(job);
+                let job = job.clone();
+                self.emit(&state, &job);
+            }
+        }
+    }
+
+    fn emit(&self, state: &QueueState, job: &Job) {
+        if let Some(app) = &state.app {
+            let _ = app.emit(JOB_EVENT, job);
+        }
+    }
+
+    fn persist(&self, state: &QueueState) {
+        let Some(path) = &self.inner.persist_path else {
+            return;
+        };
+        match serde_json::to_string_pretty(&state.jobs) {
+            Ok(content) => {
+                if let Err(e) = write_atomic(path, content.as_bytes()) {
+                    tracing::error!("無法保存工作佇列: {}", e);
+                }
+            }
+            Err(e) => tracing::error!("無法序列化工作佇列: {}", e),
+        }
+    }
+}
+
+fn job_outcome(job: &Job) -> JobResult {
+    match job.status {
+        JobStatus::Completed => Ok(job.result.clone().unwrap_or(Value::Null)),
+        _ => Err(job.error.clone().unwrap_or_else(AppError::cancelled)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> JobSpec {
+        JobSpec::Report {
+            folder_path: "/tmp/project".into(),
+            model_name: None,
+            custom_prompt: None,
+            api_key: "key".into(),
+        }
+    }
+
+    fn transcribe() -> JobSpec {
+        JobSpec::Transcribe {
+            server: "127.0.0.1".into(),
+            file_path: "/tmp/a.mp3".into(),
+            diarize: false,
+        }
+    }
+
+    #[test]
+    fn output_paths_cover_where_jobs_write() {
+        assert_eq!(report().output_paths(), vec!["/tmp/project"]);
+        let export = JobSpec::ExportRange {
+            input_path: "/tmp/a.mp3".into(),
+            output_path: "/elsewhere/b.mp3".into(),
+            start: 0.0,
+            end: 1.0,
+        };
+        assert_eq!(export.output_paths(), vec!["/elsewhere/b.mp3"]);
+        let convert = JobSpec::Convert {
+            file_paths: vec!["/tmp/a.wav".into()],
+            project_root: None,
+        };
+        assert!(convert.output_paths().is_empty());
+    }
+
+    #[test]
+    fn interactive_job_does_not_wait_for_background_slot() {
+        let manager = JobManager::load(None);
+        let long = manager.enqueue(report(), 0);
+        let queued = manager.enqueue(report(), 0);
+        let (started, _) = manager.take_next().unwrap();
+        assert_eq!(started.id, long.id);
+        assert!(manager.take_next().is_none());
+
+        let short = manager.enqueue(transcribe(), 0);
+        let (started, _) = manager.take_next().unwrap();
+        assert_eq!(started.id, short.id);
+        // 互動名額已滿、一般名額仍被報告佔用
+        manager.enqueue(transcribe(), 0);
+        assert!(manager.take_next().is_none());
+
+        manager.finish(&long.id, Ok(Value::Null));
+        let (started, _) = manager.take_next().unwrap();
+        assert_eq!(started.id, queued.id);
+    }
+
+    #[test]
+    fn restored_jobs_without_api_key_are_failed() {
+        let path = std::env::temp_dir().join(format!("stt-jobs-{}.json", std::process::id()));
+        let manager = JobManager::load(Some(path.clone()));
+        let report = manager.enqueue(report(), 0);
+        let running = manager.enqueue(transcribe(), 0);
+        let queued = manager.enqueue(transcribe(), 0);
+        manager.take_next().unwrap();
+        manager.take_next().unwrap();
+
+        let restored = JobManager::load(Some(path.clone()));
+        let _ = fs::remove_file(&path);
+        let report = restored.get(&report.id).unwrap();
+        assert_eq!(report.status, JobStatus::Failed);
+        assert_eq!(report.error.map(|e| e.kind), Some(ErrorKind::InvalidInput));
+        let running = restored.get(&running.id).unwrap();
+        assert_eq!(running.status, JobStatus::Queued);
+        assert!(running.started_at.is_none());
+        assert_eq!(restored.get(&queued.id).unwrap().status, JobStatus::Queued);
+    }
+
+    #[test]
+    fn finishing_interactive_job_frees_its_slot() {
+        let manager = JobManager::load(None);
+        let first = manager.enqueue(transcribe(), 0);
+        let second = manager.enqueue(transcribe(), 0);
+        manager.take_next().unwrap();
+        manager.finish(&first.id, Ok(Value::Null));
+        let (started, _) = manager.take_next().unwrap();
+        assert_eq!(started.id, second.id);
+    }
+}
@@ -0,0 +1,116 @@
+// src-tauri/src/services/launch.rs
+//
+// 從檔案總管「開啟檔案」或 `stt-agent://` 連結啟動時的處理：
+// 找出 (或建立) 對應的專案，交給主視窗開啟專案並載入播放器。
+//
+//   stt-agent://open?path=/path/to/recording.mp3
+//   stt-agent://open?path=/path/to/project
+
+use crate::services::access::AccessPolicy;
+use crate::services::file_manager::ProjectPaths;
+use crate::services::ingest;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Url};
+
+pub const LAUNCH_EVENT: &str = "launch://open";
+
+pub const DEEP_LINK_SCHEME: &str = "stt-agent";
+
+/// 主視窗要開啟的專案與檔案
+#[derive(Debug, Clone, Serialize)]
+pub struct LaunchRequest {
+    pub project_root: String,
+    /// 要載入播放器的檔案；開啟資料夾時為 None
+    pub file_path: Option<String>,
+}
+
+/// 前端尚未準備好時暫存的開啟要求，前端載入後以 take_launch_request 取得
+#[derive(Default)]
+pub struct PendingLaunch(Mutex<Option<LaunchRequest>>);
+
+impl PendingLaunch {
+    pub fn take(&self) -> Option<LaunchRequest> {
+        self.0.lock().ok().and_then(|mut pending| pending.take())
+    }
+}
+
+/// 處理命令列參數中的檔案 (第一個參數為執行檔本身，連結由 deep link plugin 處理)
+pub fn handle_args(app: &AppHandle, args: &[String]) {
+    let target = args
+        .iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-') && !arg.starts_with(&format!("{}:", DEEP_LINK_SCHEME)))
+        .map(PathBuf::from)
+        .find(|path| path.exists());
+    if let Some(path) = target {
+        dispatch(app, &path);
+    }
+}
+
+/// 處理 `stt-agent://` 連結與 macOS 傳入的 file:// URL
+pub fn handle_url(app: &AppHandle, url: &Url) {
+    let path = match url.scheme() {
+        "file" => url.to_file_path().ok(),
+        DEEP_LINK_SCHEME => url
+            .query_pairs()
+            .find(|(key, _)| key == "path")
+            .map(|(_, value)| PathBuf::from(value.as_ref())),
+        _ => None,
+    };
+    match path {
+        Some(path) => dispatch(app, &path),
+        None => tracing::warn!("無法處理的連結: {}", url),
+    }
+}
+
+fn dispatch(app: &AppHandle, path: &Path) {
+    let request = match resolve(path) {
+        Ok(request) => request,
+        Err(e) => {
+            tracing::warn!("無法開啟 {}: {}", path.display(), e);
+            return;
+        }
+    };
+    tracing::info!("開啟 {:?}", request);
+
+    // 使用者從檔案總管或連結開啟的位置，視同以對話框授權
+    if let Some(policy) = app.try_state::<AccessPolicy>() {
+        policy.grant(Path::new(&request.project_root));
+        if let Some(file) = &request.file_path {
+            policy.grant(Path::new(file));
+        }
+    }
+
+    if let Ok(mut pending) = app.state::<PendingLaunch>().0.lock() {
+        *pending = Some(request.clone());
+    }
+    let _ = app.emit_to("main", LAUNCH_EVENT, &request);
+
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
+
+/// 資料夾視為專案根目錄；影音檔依轉檔規則找到 (或建立) 所屬專案
+fn resolve(path: &Path) -> Result<LaunchRequest, String> {
+    if path.is_dir() {
+        let paths = ProjectPaths::from_root(path.to_path_buf())?;
+        return Ok(LaunchRequest {
+            project_root: paths.root.to_string_lossy().to_string(),
+            file_path: None,
+        });
+    }
+
+    if !ingest::is_media_file(path) {
+        return Err("不支援的檔案類型".to_string());
+    }
+    let paths = ProjectPaths::new(&path.to_string_lossy())?;
+    paths.create_all_dirs()?;
+    Ok(LaunchRequest {
+        project_root: paths.root.to_string_lossy().to_string(),
+        file_path: Some(path.to_string_lossy().to_string()),
+    })
+}
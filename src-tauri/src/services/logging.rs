@@ -0,0 +1,88 @@
+// src-tauri/src/services/logging.rs
+//
+// 應用程式日誌：同時輸出到終端機與 app data 目錄下的 logs/ (每日輪替，保留 7 天)，
+// 讓使用者回報問題時可以直接提供日誌檔。
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{self, Rotation};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, EnvFilter};
+
+pub const LOG_DIR_NAME: &str = "logs";
+const LOG_FILE_PREFIX: &str = "stt-agent";
+const LOG_FILE_SUFFIX: &str = "log";
+const MAX_LOG_FILES: usize = 7;
+
+/// 未設定 RUST_LOG 時的預設層級
+const DEFAULT_FILTER: &str = "info,stt_agent_rust_lib=debug";
+
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+/// 背景寫入執行緒的 guard，必須存活到程式結束才不會遺失日誌
+static LOG_GUARD: OnceLock<WorkerGuard> = OnceLock::new();
+
+/// 初始化日誌系統，只能呼叫一次
+pub fn init(app_data_dir: &Path) -> Result<(), String> {
+    let log_dir = app_data_dir.join(LOG_DIR_NAME);
+    fs::create_dir_all(&log_dir).map_err(|e| format!("無法建立日誌資料夾: {}", e))?;
+
+    let appender = rolling::Builder::new()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(MAX_LOG_FILES)
+        .build(&log_dir)
+        .map_err(|e| format!("無法建立日誌檔: {}", e))?;
+    let (file_writer, guard) = tracing_appender::non_blocking(appender);
+
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer().with_writer(std::io::stdout))
+        .with(fmt::layer().with_ansi(false).with_writer(file_writer))
+        .try_init()
+        .map_err(|e| format!("日誌系統已初始化: {}", e))?;
+
+    let _ = LOG_DIR.set(log_dir);
+    let _ = LOG_GUARD.set(guard);
+    Ok(())
+}
+
+/// 日誌資料夾 (尚未初始化時為 None)
+pub fn log_dir() -> Option<&'static Path> {
+    LOG_DIR.get().map(|p| p.as_path())
+}
+
+/// 最新的日誌檔 (檔名含日期，依檔名排序即為時間順序)
+pub fn latest_log_file() -> Option<PathBuf> {
+    let dir = log_dir()?;
+    fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.file_name()
+                    .and_then(|n| n.to_str())
+                    .map(|n| n.starts_with(LOG_FILE_PREFIX))
+                    .unwrap_or(false)
+        })
+        .max()
+}
+
+/// 讀取最新日誌檔的最後幾行
+pub fn tail(lines: usize) -> Result<Vec<String>, String> {
+    let Some(path) = latest_log_file() else {
+        return Ok(Vec::new());
+    };
+    let content = fs::read(&path).map_err(|e| format!("無法讀取日誌檔: {}", e))?;
+    let content = String::from_utf8_lossy(&content);
+    let all: Vec<&str> = content.lines().collect();
+    let start = all.len().saturating_sub(lines);
+    Ok(all[start..].iter().map(|l| l.to_string()).collect())
+}
@@ -0,0 +1,194 @@
+// src-tauri/src/services/loopback.rs
+//
+// 系統音訊 (喇叭輸出) 擷取，用於錄下視訊看診時對方的聲音：
+// - Windows: WASAPI loopback (在輸出裝置上建立輸入串流)
+// - Linux: PulseAudio / PipeWire 的 monitor source，以 parec 讀取
+// - macOS: 系統不提供 loopback，需安裝虛擬音訊裝置 (例如 BlackHole) 後當作麥克風選擇
+//
+// 擷取到的樣本以 Vec<f32> (interleaved) 傳給錄音迴圈。
+
+use serde::{Deserialize, Serialize};
+use std::sync::mpsc;
+
+/// 系統音訊的錄製方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SystemAudioMode {
+    /// 只錄麥克風
+    #[default]
+    Off,
+    /// 與麥克風混成同一個檔案
+    Mix,
+    /// 另存成 *-system.wav
+    Separate,
+}
+
+/// 進行中的系統音訊擷取，drop 時停止
+pub struct LoopbackCapture {
+    pub sample_rate: u32,
+    pub channels: u16,
+    source: Source,
+}
+
+// 保存串流 / 子程序本身，drop 時停止擷取 (各平台只用到其中一種)
+#[allow(dead_code)]
+enum Source {
+    Stream(cpal::Stream),
+    Process(std::process::Child),
+}
+
+impl Drop for LoopbackCapture {
+    fn drop(&mut self) {
+        if let Source::Process(child) = &mut self.source {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// 目前平台是否支援系統音訊擷取
+pub fn is_supported() -> bool {
+    cfg!(any(target_os = "windows", target_os = "linux"))
+}
+
+/// 開始擷取預設輸出裝置的聲音
+#[cfg(target_os = "windows")]
+pub fn open(tx: mpsc::Sender<Vec<f32>>) -> Result<LoopbackCapture, String> {
+    use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+
+    let device = cpal::default_host()
+        .default_output_device()
+        .ok_or("找不到音訊輸出裝置")?;
+    let config = device
+        .default_output_config()
+        .map_err(|e| format!("無法取得輸出裝置設定: {}", e))?;
+    // WASAPI: 在輸出裝置上建立輸入串流即為 loopback
+    let stream = crate::services::recorder::build_input_stream(&device, &config, tx)?;
+    stream
+        .play()
+        .map_err(|e| format!("無法開始擷取系統音訊: {}", e))?;
+    Ok(LoopbackCapture {
+        sample_rate: config.sample_rate().0,
+        channels: config.channels(),
+        source: Source::Stream(stream),
+    })
+}
+
+#[cfg(target_os = "linux")]
+const PAREC_RATE: u32 = 48_000;
+#[cfg(target_os = "linux")]
+const PAREC_CHANNELS: u16 = 2;
+
+/// 開始擷取預設輸出裝置的聲音 (預設 sink 的 monitor source)
+#[cfg(target_os = "linux")]
+pub fn open(tx: mpsc::Sender<Vec<f32>>) -> Result<LoopbackCapture, String> {
+    use std::io::Read;
+    use std::process::{Command, Stdio};
+
+    let sink = Command::new("pactl")
+        .arg("get-default-sink")
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_string())
+        .filter(|s| !s.is_empty())
+        .ok_or("找不到 PulseAudio / PipeWire 輸出裝置 (需要 pactl)")?;
+    let monitor = format!("{}.monitor", sink);
+
+    let mut child = Command::new("parec")
+        .args([
+            format!("--device={}", monitor),
+            "--format=float32le".to_string(),
+            format!("--rate={}", PAREC_RATE),
+            format!("--channels={}", PAREC_CHANNELS),
+            "--raw".to_string(),
+        ])
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("無法啟動 parec 擷取系統音訊: {}", e))?;
+    let mut stdout = child.stdout.take().ok_or("無法讀取 parec 輸出")?;
+
+    std::thread::Builder::new()
+        .name("loopback".to_string())
+        .spawn(move || {
+            // 約 20ms 一次
+            let mut buf = vec![0u8; (PAREC_RATE / 50) as usize * PAREC_CHANNELS as usize * 4];
+            loop {
+                match stdout.read(&mut buf) {
+                    Ok(0) | Err(_) => break,
+                    Ok(n) => {
+                        let samples = buf[..n - n % 4]
+                            .chunks_exact(4)
+                            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                            .collect();
+                        if tx.send(samples).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        })
+        .map_err(|e| format!("無法啟動系統音訊執行緒: {}", e))?;
+
+    tracing::info!("擷取系統音訊: {}", monitor);
+    Ok(LoopbackCapture {
+        sample_rate: PAREC_RATE,
+        channels: PAREC_CHANNELS,
+        source: Source::Process(child),
+    })
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux")))]
+pub fn open(_tx: mpsc::Sender<Vec<f32>>) -> Result<LoopbackCapture, String> {
+    Err(
+        "此平台不支援直接擷取系統音訊，請安裝虛擬音訊裝置 (例如 BlackHole) 並選擇為錄音裝置"
+            .to_string(),
+    )
+}
+
+/// 把系統音訊轉成單聲道並重新取樣到錄音的取樣率，與麥克風混音時使用
+pub struct Resampler {
+    from_rate: u32,
+    to_rate: u32,
+    channels: usize,
+    /// 目前位置 (以輸入取樣為單位的小數)
+    position: f64,
+    last: f32,
+}
+
+impl Resampler {
+    pub fn new(from_rate: u32, to_rate: u32, channels: u16) -> Self {
+        Self {
+            from_rate,
+            to_rate,
+            channels: channels.max(1) as usize,
+            position: 0.0,
+            last: 0.0,
+        }
+    }
+
+    /// 線性內插 (語音用途已足夠)
+    pub fn process(&mut self, interleaved: &[f32], out: &mut Vec<f32>) {
+        let mono: Vec<f32> = interleaved
+            .chunks(self.channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect();
+        let step = self.from_rate as f64 / self.to_rate as f64;
+        while self.position < mono.len() as f64 {
+            let index = self.position.floor() as usize;
+            let frac = (self.position - index as f64) as f32;
+            let prev = if index == 0 {
+                self.last
+            } else {
+                mono[index - 1]
+            };
+            out.push(prev + (mono[index] - prev) * frac);
+            self.position += step;
+        }
+        self.position -= mono.len() as f64;
+        if let Some(&last) = mono.last() {
+            self.last = last;
+        }
+    }
+}
@@ -0,0 +1,266 @@
+// src-tauri/src/services/manifest.rs
+//
+// 專案描述檔 (manifest.json)，記錄轉檔來源、輸出檔與雜湊值，
+// 供專案完整性檢查使用；另記錄邊聽邊錄的口述註記與播放檔的同步點。
+// 專案資訊 (名稱、錄音日期、科別) 供報告提示詞的模板變數使用。
+
+use crate::services::file_manager::{resolve_project_path, to_project_relative, write_atomic};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, OnceLock};
+
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// 各專案描述檔的鎖 (轉檔、錄音、口述註記可能同時更新同一個專案)
+static UPDATE_LOCKS: OnceLock<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = OnceLock::new();
+
+fn update_lock(root: &Path) -> Arc<Mutex<()>> {
+    let mut locks = UPDATE_LOCKS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    locks.entry(root.to_path_buf()).or_default().clone()
+}
+
+/// 單筆轉檔紀錄
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionRecord {
+    /// 原始檔案路徑 (專案外的檔案保留絕對路徑)
+    pub source: String,
+    /// 轉檔輸出路徑 (01_converted 內，儲存時為相對路徑)
+    pub output: String,
+    /// 輸出檔的 SHA-256
+    pub sha256: String,
+    pub converted_at: String,
+}
+
+/// 口述註記與播放檔的對應：口述檔的 dictation_offset 秒對應到播放檔的 source_offset 秒
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SyncPoint {
+    pub dictation_offset: f64,
+    pub source_offset: f64,
+    /// 此時播放器是否在播放 (暫停時播放檔位置不前進)
+    pub playing: bool,
+}
+
+/// 邊聽邊錄的口述註記
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DictationRecord {
+    /// 錄音時播放的音檔
+    pub source: String,
+    /// 口述錄音檔 (儲存時為相對路徑)
+    pub output: String,
+    /// 開始錄音與每次跳轉、暫停、繼續播放時的同步點
+    pub sync: Vec<SyncPoint>,
+    pub recorded_at: String,
+}
+
+/// 專案資訊 (報告提示詞可用 {project_name}、{recording_date}、{department} 引用)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectInfo {
+    /// 未填時使用專案資料夾名稱
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project_name: Option<String>,
+    /// 未填時使用最早一筆轉檔紀錄的日期 (YYYY-MM-DD)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recording_date: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub department: Option<String>,
+}
+
+impl ProjectInfo {
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ProjectManifest {
+    #[serde(default, skip_serializing_if = "ProjectInfo::is_empty")]
+    pub info: ProjectInfo,
+    #[serde(default)]
+    pub conversions: Vec<ConversionRecord>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dictations: Vec<DictationRecord>,
+}
+
+impl ProjectManifest {
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(MANIFEST_FILE)
+    }
+
+    /// 讀取專案描述檔，不存在時回傳空的描述檔
+    /// 檔案內的相對路徑會還原為絕對路徑
+    pub fn load(root: &Path) -> Result<Self, String> {
+        let path = Self::path(root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("無法讀取專案描述檔: {}", e))?;
+        let mut manifest: Self =
+            serde_json::from_str(&content).map_err(|e| format!("專案描述檔格式錯誤: {}", e))?;
+        for record in &mut manifest.conversions {
+            record.source = absolutize(root, &record.source);
+            record.output = absolutize(root, &record.output);
+        }
+        for record in &mut manifest.dictations {
+            record.source = absolutize(root, &record.source);
+            record.output = absolutize(root, &record.output);
+        }
+        Ok(manifest)
+    }
+
+    /// 儲存專案描述檔，專案內的路徑一律以相對路徑寫入
+    pub fn save(&self, root: &Path) -> Result<(), String> {
+        let mut stored = self.clone();
+        for record in &mut stored.conversions {
+            record.source = relativize(root, &record.source);
+            record.output = relativize(root, &record.output);
+        }
+        for record in &mut stored.dictations {
+            record.source = relativize(root, &record.source);
+            record.output = relativize(root, &record.output);
+        }
+        let content = serde_json::to_string_pretty(&stored)
+            .map_err(|e| format!("Serialization error: {}", e))?;
+        write_atomic(&Self::path(root), content.as_bytes())
+            .map_err(|e| format!("無法寫入專案描述檔: {}", e))
+    }
+
+    /// 讀取、修改並寫回專案描述檔；同一專案的更新依序進行，不會互相覆蓋
+    pub fn update<T>(
+        root: &Path,
+        change: impl FnOnce(&mut Self) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let lock = update_lock(root);
+        let _guard = lock.lock().unwrap_or_else(|e| e.into_inner());
+        let mut manifest = Self::load(root)?;
+        let value = change(&mut manifest)?;
+        manifest.save(root)?;
+        Ok(value)
+    }
+
+    /// 新增 (或取代同一輸出檔的) 轉檔紀錄
+    pub fn record_conversion(&mut self, source: &str, output: &Path) -> Result<(), String> {
+        let output_str = output.to_string_lossy().to_string();
+        let record = ConversionRecord {
+            source: source.to_string(),
+            output: output_str.clone(),
+            sha256: hash_file(output)?,
+            converted_at: chrono::Local::now().to_rfc3339(),
+        };
+        self.conversions.retain(|r| r.output != output_str);
+        self.conversions.push(record);
+        Ok(())
+    }
+
+    /// 新增口述註記紀錄
+    pub fn record_dictation(&mut self, record: DictationRecord) {
+        self.dictations.retain(|r| r.output != record.output);
+        self.dictations.push(record);
+    }
+
+    /// 報告提示詞的模板變數 (未填的欄位使用預設值，科別未填時為空字串)
+    pub fn template_vars(&self, root: &Path) -> BTreeMap<String, String> {
+        let non_empty = |value: &Option<String>| {
+            value
+                .as_deref()
+                .map(str::trim)
+                .filter(|v| !v.is_empty())
+                .map(str::to_string)
+        };
+        let project_name = non_empty(&self.info.project_name).unwrap_or_else(|| {
+            root.file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default()
+        });
+        let recording_date = non_empty(&self.info.recording_date).unwrap_or_else(|| {
+            self.conversions
+                .iter()
+                .map(|c| c.converted_at.get(..10).unwrap_or_default())
+                .min()
+                .unwrap_or_default()
+                .to_string()
+        });
+        BTreeMap::from([
+            ("project_name".to_string(), project_name),
+            ("recording_date".to_string(), recording_date),
+            (
+                "department".to_string(),
+                non_empty(&self.info.department).unwrap_or_default(),
+            ),
+        ])
+    }
+}
+
+fn absolutize(root: &Path, stored: &str) -> String {
+    resolve_project_path(root, stored)
+        .to_string_lossy()
+        .to_string()
+}
+
+fn relativize(root: &Path, path: &str) -> String {
+    to_project_relative(root, Path::new(path))
+}
+
+/// 計算檔案 SHA-256 (串流讀取，避免大檔一次載入記憶體)
+pub fn hash_file(path: &Path) -> Result<String, String> {
+    let mut file = fs::File::open(path).map_err(|e| format!("無法開啟檔案: {}", e))?;
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = file
+            .read(&mut buf)
+            .map_err(|e| format!("讀取檔案失敗: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn concurrent_updates_keep_every_record() {
+        let root = std::env::temp_dir().join(format!("stt_agent_manifest_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(&root).unwrap();
+
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let root = root.clone();
+                std::thread::spawn(move || {
+                    ProjectManifest::update(&root, |m| {
+                        m.record_dictation(DictationRecord {
+                            source: root.join("a.mp3").to_string_lossy().to_string(),
+                            output: root
+                                .join(format!("note{}.wav", i))
+                                .to_string_lossy()
+                                .to_string(),
+                            sync: Vec::new(),
+                            recorded_at: String::new(),
+                        });
+                        Ok(())
+                    })
+                    .unwrap();
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        assert_eq!(ProjectManifest::load(&root).unwrap().dictations.len(), 8);
+        assert!(!root.join(".manifest.json.tmp").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
@@ -1,5 +1,7 @@
 pub mod converter;
 pub mod report;
+pub mod report_html;
+pub mod report_index;
 pub mod silence;
 pub mod splitter;
 pub mod audio_player;
@@ -11,3 +13,67 @@ pub use splitter::Splitter;
 pub mod file_manager;
 pub use file_manager::ProjectPaths;
 pub use audio_player::AudioPlayer;
+pub mod watcher;
+pub use watcher::ProjectWatcher;
+pub mod manifest;
+pub mod notifications;
+pub mod access;
+pub mod agc;
+pub mod annotations;
+pub mod autosave;
+pub mod backup;
+pub mod batch_guard;
+pub mod chapters;
+pub mod topic_chapters;
+pub mod deid;
+pub mod experiments;
+pub mod denoise;
+pub mod dependencies;
+pub mod diagnostics;
+pub mod dictaphone;
+pub mod downmix;
+pub mod encryption;
+pub mod enhance;
+pub mod ffmpeg_filter;
+pub mod fingerprint;
+pub mod health;
+pub mod history;
+pub mod search;
+pub mod session;
+pub mod settings;
+pub mod shortcuts;
+pub mod probe;
+pub mod project_stats;
+pub mod quality;
+pub mod recorder;
+pub mod recording_schedule;
+pub mod retention;
+pub mod storage;
+pub mod stt_models;
+pub mod subtitles;
+pub mod timeline_export;
+pub mod timeline_import;
+pub mod transcript;
+pub mod trash;
+pub mod uninstall;
+pub mod upload_alias;
+pub mod viewer;
+pub mod volume;
+pub mod waveform;
+pub mod webhook;
+pub mod jobs;
+pub mod i18n;
+pub mod ingest;
+pub mod launch;
+pub mod logging;
+pub mod loopback;
+pub mod offline_queue;
+pub mod overdub;
+pub mod notes;
+pub mod pipeline;
+pub mod plugins;
+pub mod player_fallback;
+pub mod player_monitor;
+pub mod prefetch;
+pub mod sidecar;
+pub mod workflows;
@@ -0,0 +1,171 @@
+// src-tauri/src/services/notes.rs
+//
+// 口述筆記 (按住說話)：在專案內快速錄下簡短的語音筆記，轉錄後附加到專案的 notes.md，
+// 讓醫師不必離開程式就能補充口述內容。
+//
+// - 錄音存到 <專案>/dictations/note-<時間>.wav (與邊聽邊錄的口述註記同一資料夾)
+// - 以設定中的 STT 伺服器轉錄；未設定伺服器時不開始錄音
+// - notes.md 只附加，每則筆記以「## 時間」開頭並連結錄音檔
+// - 轉錄失敗或沒有辨識到語音時保留錄音檔，不寫入 notes.md
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::history;
+use crate::services::jobs::CancelToken;
+use crate::services::overdub::DICTATION_DIR;
+use crate::services::plugins;
+use crate::services::recorder::{Recording, RecordingFormat, RecordingOptions};
+use crate::services::settings::PluginHook;
+use crate::services::silence::Silence;
+use crate::services::{settings, volume};
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+pub const NOTES_FILE_NAME: &str = "notes.md";
+
+/// 進行中的口述筆記錄音 (None = 未錄音)
+pub type NoteState = Mutex<Option<Recording>>;
+
+/// 避免同時附加時筆記交錯
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// 附加到 notes.md 的一則筆記
+#[derive(Debug, Clone, Serialize)]
+pub struct NoteEntry {
+    pub recorded_at: String,
+    pub text: String,
+    /// 錄音檔完整路徑
+    pub audio: String,
+    pub duration: f64,
+    pub notes_path: String,
+}
+
+pub fn notes_path(root: &Path) -> PathBuf {
+    root.join(NOTES_FILE_NAME)
+}
+
+/// 筆記錄音檔路徑: <專案>/dictations/note-<時間>.wav
+fn note_audio_path(root: &Path) -> PathBuf {
+    root.join(DICTATION_DIR).join(format!(
+        "note-{}.wav",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
+
+/// 設定中的 STT 伺服器
+fn stt_server() -> Result<String, AppError> {
+    settings::load()
+        .stt_server
+        .filter(|s| !s.trim().is_empty())
+        .ok_or_else(|| {
+            AppError::localized(ErrorKind::InvalidInput, "error.notes_no_stt_server", &[])
+        })
+}
+
+/// 開始錄製口述筆記 (一律錄成 WAV，轉錄前不做有損轉檔)
+pub fn start(
+    app: AppHandle,
+    project_root: &Path,
+    options: RecordingOptions,
+) -> Result<Recording, AppError> {
+    stt_server()?;
+    volume::ensure_writable(project_root)?;
+    let options = RecordingOptions {
+        format: RecordingFormat::Wav,
+        ..options
+    };
+    Recording::start(
+        app,
+        options,
+        note_audio_path(project_root),
+        project_root.to_path_buf(),
+    )
+    .map_err(AppError::tool)
+}
+
+/// 筆記的 Markdown：標題為時間，內文後附錄音檔的相對連結
+fn render(entry: &NoteEntry, audio: &Path) -> String {
+    let name = audio
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!(
+        "## {}\n\n{}\n\n[{}]({}/{})\n\n",
+        entry.recorded_at, entry.text, name, DICTATION_DIR, name
+    )
+}
+
+/// 附加筆記到 notes.md (檔案不存在時建立並加上標題)
+fn append(root: &Path, content: &str) -> Result<PathBuf, AppError> {
+    let path = notes_path(root);
+    let _guard = APPEND_LOCK
+        .lock()
+        .map_err(|e| AppError::internal(e.to_string()))?;
+    let is_new = std::fs::metadata(&path)
+        .map(|m| m.len() == 0)
+        .unwrap_or(true);
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    if is_new {
+        file.write_all(format!("# {}\n\n", crate::tr!("notes.title")).as_bytes())?;
+    }
+    file.write_all(content.as_bytes())?;
+    Ok(path)
+}
+
+/// 停止錄音，轉錄後附加到專案的 notes.md
+pub async fn stop_and_append(app: &AppHandle, recording: Recording) -> Result<NoteEntry, AppError> {
+    let started = Instant::now();
+    let root = recording.project_root().to_path_buf();
+    let finished = tauri::async_runtime::spawn_blocking(move || recording.finish())
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::io)?;
+    let audio = finished.wav_path;
+    let detail = audio.display().to_string();
+
+    let server = stt_server().map_err(|e| e.with_detail(detail.clone()))?;
+    plugins::run_hook(
+        PluginHook::PreUpload,
+        Some(&root),
+        &[audio.clone()],
+        &CancelToken::new(),
+    )
+    .await
+    .map_err(|e| e.with_detail(detail.clone()))?;
+    let response = app
+        .state::<Silence>()
+        .transcribe(&server, &audio.to_string_lossy(), false)
+        .await
+        .map_err(|e| AppError::network(e).with_detail(detail.clone()))?;
+    let text = response.full_text.trim().to_string();
+    if text.is_empty() {
+        return Err(
+            AppError::localized(ErrorKind::InvalidInput, "error.note_no_speech", &[])
+                .with_detail(detail),
+        );
+    }
+
+    let mut entry = NoteEntry {
+        recorded_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+        text,
+        audio: audio.to_string_lossy().to_string(),
+        duration: finished.duration,
+        notes_path: String::new(),
+    };
+    let notes = append(&root, &render(&entry, &audio))?;
+    entry.notes_path = notes.to_string_lossy().to_string();
+
+    history::record_command(
+        &root,
+        "dictation_note",
+        serde_json::json!({ "duration": entry.duration }),
+        &[audio, notes],
+        started,
+        &Ok::<(), AppError>(()),
+    );
+    Ok(entry)
+}
@@ -0,0 +1,216 @@
+// src-tauri/src/services/notifications.rs
+//
+// 長時間工作完成通知：數小時的報告或流程結束時，即使視窗已縮小也能得知。
+// - 桌面通知 (tauri-plugin-notification)
+// - 選用的 SMTP 電子郵件，可附上產生的報告
+//
+// 只通知執行時間超過設定分鐘數的工作；被取消的工作不通知。
+
+use crate::models::AppError;
+use crate::services::jobs::Job;
+use crate::services::settings::{self, EmailConfig, SmtpSecurity};
+use lettre::message::header::ContentType;
+use lettre::message::{Attachment, Mailbox, MultiPart, SinglePart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// 附件大小上限 (多數郵件伺服器限制在 25 MB 左右)
+const MAX_ATTACHMENT_BYTES: u64 = 20 * 1024 * 1024;
+
+/// 通知內容
+struct Notice {
+    title: String,
+    body: String,
+    /// 要附在郵件中的報告
+    attachment: Option<PathBuf>,
+}
+
+/// 工作結束時呼叫：執行時間夠長時發出桌面通知與郵件
+pub fn notify_job(
+    app: &AppHandle,
+    job: &Job,
+    outputs: &[PathBuf],
+    elapsed: Duration,
+    result: &Result<Value, AppError>,
+) {
+    if matches!(result, Err(e) if e.is_cancelled()) {
+        return;
+    }
+    let config = settings::load().notifications;
+    if elapsed < Duration::from_secs(config.min_minutes * 60) {
+        return;
+    }
+    if !config.desktop && !config.email.enabled {
+        return;
+    }
+
+    let notice = build_notice(job, outputs, elapsed, result);
+    if config.desktop {
+        if let Err(e) = app
+            .notification()
+            .builder()
+            .title(&notice.title)
+            .body(&notice.body)
+            .show()
+        {
+            tracing::warn!("無法顯示桌面通知: {}", e);
+        }
+    }
+    if config.email.enabled {
+        let email = config.email;
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = send_email(&email, &notice).await {
+                tracing::warn!("無法寄出通知郵件: {}", e);
+            }
+        });
+    }
+}
+
+fn build_notice(
+    job: &Job,
+    outputs: &[PathBuf],
+    elapsed: Duration,
+    result: &Result<Value, AppError>,
+) -> Notice {
+    let kind = match job.kind.as_str() {
+        "report" => crate::tr!("notify.kind.report"),
+        "pipeline" => crate::tr!("notify.kind.pipeline"),
+        _ => crate::tr!("notify.kind.other"),
+    };
+    let minutes = (elapsed.as_secs_f64() / 60.0).round();
+    match result {
+        Ok(value) => {
+            let message = match value {
+                Value::String(s) => s.clone(),
+                Value::Null => String::new(),
+                other => other.to_string(),
+            };
+            Notice {
+                title: crate::tr!("notify.completed_title", kind = kind),
+                body: crate::tr!(
+                    "notify.completed_body",
+                    minutes = minutes,
+                    message = message
+                ),
+                attachment: report_attachment(outputs),
+            }
+        }
+        Err(e) => Notice {
+            title: crate::tr!("notify.failed_title", kind = kind),
+            body: crate::tr!("notify.failed_body", minutes = minutes, error = e.message),
+            attachment: None,
+        },
+    }
+}
+
+/// 工作產生的報告：優先 DOCX，其次 Markdown
+fn report_attachment(outputs: &[PathBuf]) -> Option<PathBuf> {
+    let with_extension = |ext: &str| {
+        outputs
+            .iter()
+            .rev()
+            .find(|p| {
+                p.extension()
+                    .map(|e| e.eq_ignore_ascii_case(ext))
+                    .unwrap_or(false)
+            })
+            .cloned()
+    };
+    with_extension("docx").or_else(|| with_extension("md"))
+}
+
+fn attachment_type(path: &Path) -> ContentType {
+    let mime = match path.extension().and_then(|e| e.to_str()) {
+        Some("docx") => "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+        Some("md") => "text/markdown; charset=utf-8",
+        _ => "application/octet-stream",
+    };
+    ContentType::parse(mime).unwrap_or(ContentType::TEXT_PLAIN)
+}
+
+async fn send_email(config: &EmailConfig, notice: &Notice) -> Result<(), String> {
+    let from: Mailbox = config
+        .from
+        .parse()
+        .map_err(|e| format!("寄件人格式錯誤: {}", e))?;
+    let mut builder = Message::builder().from(from).subject(&notice.title);
+    for to in &config.to {
+        let to: Mailbox = to
+            .parse()
+            .map_err(|e| format!("收件人格式錯誤 {}: {}", to, e))?;
+        builder = builder.to(to);
+    }
+
+    let attachment = notice
+        .attachment
+        .as_ref()
+        .filter(|_| config.attach_report)
+        .filter(|path| {
+            fs::metadata(path)
+                .map(|m| m.len() <= MAX_ATTACHMENT_BYTES)
+                .unwrap_or(false)
+        });
+    let message = match attachment {
+        Some(path) => {
+            let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let content = fs::read(path).map_err(|e| format!("無法讀取附件: {}", e))?;
+            let body = format!(
+                "{}\n\n{}",
+                notice.body,
+                crate::tr!("notify.email_attachment", file = file_name)
+            );
+            builder.multipart(
+                MultiPart::mixed()
+                    .singlepart(SinglePart::plain(body))
+                    .singlepart(Attachment::new(file_name).body(content, attachment_type(path))),
+            )
+        }
+        None => builder.body(notice.body.clone()),
+    }
+    .map_err(|e| e.to_string())?;
+
+    let transport = match config.security {
+        SmtpSecurity::StartTls => {
+            AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.smtp_host)
+        }
+        SmtpSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.smtp_host),
+        SmtpSecurity::None => Ok(AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(
+            &config.smtp_host,
+        )),
+    }
+    .map_err(|e| e.to_string())?
+    .port(config.smtp_port);
+    let transport = match (&config.username, &config.password) {
+        (Some(username), Some(password)) => {
+            transport.credentials(Credentials::new(username.clone(), password.clone()))
+        }
+        _ => transport,
+    }
+    .build();
+
+    transport.send(message).await.map_err(|e| e.to_string())?;
+    tracing::info!("已寄出通知郵件: {}", notice.title);
+    Ok(())
+}
+
+/// 寄出測試郵件，確認 SMTP 設定正確
+pub async fn send_test_email(config: &EmailConfig) -> Result<(), String> {
+    let notice = Notice {
+        title: crate::tr!(
+            "notify.completed_title",
+            kind = crate::tr!("notify.kind.other")
+        ),
+        body: "STT Agent test".to_string(),
+        attachment: None,
+    };
+    send_email(config, &notice).await
+}
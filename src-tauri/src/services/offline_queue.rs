@@ -0,0 +1,246 @@
+// src-tauri/src/services/offline_queue.rs
+//
+// 離線報告佇列：行動門診整天沒有網路時，報告先連同所有參數 (含 API Key) 保存在
+// app data 的 offline_queue.json，背景執行緒定期檢查能否連上 Gemini，
+// 恢復連線後自動排入工作佇列。連線狀態變化時發出 `network://status`。
+//
+// API Key 只為了離線排隊而保存，排入工作佇列後即從檔案移除。
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::file_manager::write_atomic;
+use crate::services::jobs::{JobManager, JobSpec};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const OFFLINE_QUEUE_FILE_NAME: &str = "offline_queue.json";
+
+/// 連線狀態變化時發出的事件 (payload 為 NetworkStatus)
+pub const NETWORK_STATUS_EVENT: &str = "network://status";
+
+/// 用來判斷能否連上 Gemini 的網址 (任何 HTTP 回應都視為可連線)
+const PROBE_URL: &str = "https://generativelanguage.googleapis.com/";
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+/// 檢查間隔
+const TICK: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueuedReport {
+    pub id: String,
+    pub folder_path: String,
+    pub model_name: Option<String>,
+    pub custom_prompt: Option<String>,
+    /// 前端不會收到 API Key
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    pub api_key: String,
+    pub queued_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkStatus {
+    pub online: bool,
+    pub checked_at: String,
+    /// 等待連線的報告數量
+    pub pending: usize,
+}
+
+/// 排入報告的結果：有網路時直接排入工作佇列
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum QueueOutcome {
+    Started { job_id: String },
+    Waiting { id: String },
+}
+
+pub struct OfflineQueue {
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<QueuedReport>>,
+    /// 上次檢查的結果 (啟動時先假設有網路)
+    online: AtomicBool,
+}
+
+impl OfflineQueue {
+    /// 讀取保存的佇列，檔案不存在或格式錯誤時為空
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries: Mutex::new(entries),
+            online: AtomicBool::new(true),
+        }
+    }
+
+    pub fn is_online(&self) -> bool {
+        self.online.load(Ordering::SeqCst)
+    }
+
+    /// 列出等待中的報告 (不含 API Key)
+    pub fn list(&self) -> Vec<QueuedReport> {
+        self.entries
+            .lock()
+            .map(|entries| {
+                entries
+                    .iter()
+                    .map(|entry| QueuedReport {
+                        api_key: String::new(),
+                        ..entry.clone()
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn pending(&self) -> usize {
+        self.entries.lock().map(|e| e.len()).unwrap_or(0)
+    }
+
+    /// 有網路時直接排入工作佇列，否則保存等待連線
+    pub fn submit(
+        &self,
+        jobs: &JobManager,
+        folder_path: String,
+        model_name: Option<String>,
+        custom_prompt: Option<String>,
+        api_key: String,
+    ) -> Result<QueueOutcome, AppError> {
+        if self.is_online() {
+            let job = jobs.enqueue(
+                JobSpec::Report {
+                    folder_path,
+                    model_name,
+                    custom_prompt,
+                    api_key,
+                },
+                0,
+            );
+            return Ok(QueueOutcome::Started { job_id: job.id });
+        }
+        let now = chrono::Local::now();
+        let entry = QueuedReport {
+            id: format!("offline-{}", now.timestamp_millis()),
+            folder_path,
+            model_name,
+            custom_prompt,
+            api_key,
+            queued_at: now.to_rfc3339(),
+        };
+        let id = entry.id.clone();
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| AppError::internal("無法取得離線佇列"))?;
+        entries.push(entry);
+        self.persist(&entries)?;
+        tracing::info!("目前沒有網路，報告已排入離線佇列: {}", id);
+        Ok(QueueOutcome::Waiting { id })
+    }
+
+    /// 移除等待中的報告
+    pub fn remove(&self, id: &str) -> Result<(), AppError> {
+        let mut entries = self
+            .entries
+            .lock()
+            .map_err(|_| AppError::internal("無法取得離線佇列"))?;
+        let before = entries.len();
+        entries.retain(|e| e.id != id);
+        if entries.len() == before {
+            return Err(AppError::localized(
+                ErrorKind::NotFound,
+                "error.offline_report_not_found",
+                &[("id", id.to_string())],
+            ));
+        }
+        self.persist(&entries)
+    }
+
+    /// 取出所有等待中的報告並清空檔案
+    fn drain(&self) -> Vec<QueuedReport> {
+        let Ok(mut entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let drained = std::mem::take(&mut *entries);
+        if !drained.is_empty() {
+            if let Err(e) = self.persist(&entries) {
+                tracing::error!("無法保存離線佇列: {}", e);
+            }
+        }
+        drained
+    }
+
+    fn persist(&self, entries: &[QueuedReport]) -> Result<(), AppError> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        write_atomic(path, &serde_json::to_vec_pretty(entries)?)?;
+        Ok(())
+    }
+}
+
+/// 能否連上 Gemini
+async fn check_online(client: &reqwest::Client) -> bool {
+    client.head(PROBE_URL).send().await.is_ok()
+}
+
+/// 啟動連線檢查迴圈：狀態變化時發出事件，恢復連線時排入等待中的報告
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let client = match reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() {
+            Ok(client) => client,
+            Err(e) => {
+                tracing::error!("無法建立連線檢查用的 HTTP client: {}", e);
+                return;
+            }
+        };
+        let mut first = true;
+        loop {
+            let online = check_online(&client).await;
+            let queue = app.state::<OfflineQueue>();
+            let changed = queue.online.swap(online, Ordering::SeqCst) != online;
+            if changed || first {
+                tracing::info!("網路狀態: {}", if online { "已連線" } else { "離線" });
+                let _ = app.emit(
+                    NETWORK_STATUS_EVENT,
+                    NetworkStatus {
+                        online,
+                        checked_at: chrono::Local::now().to_rfc3339(),
+                        pending: queue.pending(),
+                    },
+                );
+            }
+            if online {
+                let jobs = app.state::<JobManager>();
+                for entry in queue.drain() {
+                    tracing::info!("已恢復連線，開始離線佇列中的報告: {}", entry.folder_path);
+                    jobs.enqueue(
+                        JobSpec::Report {
+                            folder_path: entry.folder_path,
+                            model_name: entry.model_name,
+                            custom_prompt: entry.custom_prompt,
+                            api_key: entry.api_key,
+                        },
+                        0,
+                    );
+                }
+            }
+            first = false;
+            tokio::time::sleep(TICK).await;
+        }
+    });
+}
+
+/// 目前的連線狀態
+pub fn status(queue: &OfflineQueue) -> NetworkStatus {
+    NetworkStatus {
+        online: queue.is_online(),
+        checked_at: chrono::Local::now().to_rfc3339(),
+        pending: queue.pending(),
+    }
+}
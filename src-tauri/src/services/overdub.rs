@@ -0,0 +1,235 @@
+// src-tauri/src/services/overdub.rs
+//
+// 邊聽邊錄 (口述註記)：播放錄音檔的同時以麥克風錄下醫師的口述註記。
+//
+// - 口述另存為獨立檔案 <專案>/dictations/<播放檔名>-dictation-<時間>.wav，不混入原始錄音
+// - 同步執行緒定時比對播放位置與錄音長度，播放器跳轉、暫停或繼續時記下同步點
+//   (口述檔第 N 秒對應播放檔第 M 秒)，停止時寫入專案描述檔 (manifest.json)
+// - 同步只追蹤開始時載入的音檔；中途換檔後的口述仍記錄在原音檔
+
+use crate::models::AppError;
+use crate::services::audio_player::SharedState;
+use crate::services::history;
+use crate::services::manifest::{DictationRecord, ProjectManifest, SyncPoint};
+use crate::services::recorder::{self, Recording, RecordingClock, RecordingOptions};
+use crate::services::volume;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+
+/// 口述註記的資料夾 (專案根目錄下)
+pub const DICTATION_DIR: &str = "dictations";
+
+/// 同步執行緒的取樣間隔
+const SYNC_INTERVAL: Duration = Duration::from_millis(100);
+/// 播放位置與預期位置差超過此值視為跳轉 (秒)
+const SYNC_TOLERANCE_SECS: f64 = 0.25;
+
+/// 進行中的口述註記 (None = 未錄音)
+pub type OverdubState = Mutex<Option<Overdub>>;
+
+/// 口述註記狀態 (給前端顯示)
+#[derive(Debug, Clone, Serialize)]
+pub struct OverdubStatus {
+    pub recording: bool,
+    pub elapsed: f64,
+    pub source: Option<String>,
+    pub path: Option<String>,
+}
+
+impl OverdubStatus {
+    pub fn idle() -> Self {
+        Self {
+            recording: false,
+            elapsed: 0.0,
+            source: None,
+            path: None,
+        }
+    }
+}
+
+/// 進行中的口述註記
+pub struct Overdub {
+    recording: Recording,
+    source: PathBuf,
+    tracker: SyncTracker,
+}
+
+/// 同步執行緒；被丟棄時停止
+struct SyncTracker {
+    points: Arc<Mutex<Vec<SyncPoint>>>,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl SyncTracker {
+    fn start(player: Arc<SharedState>, clock: RecordingClock) -> Result<Self, AppError> {
+        let points = Arc::new(Mutex::new(Vec::new()));
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_points = Arc::clone(&points);
+        let thread_stop = Arc::clone(&stop);
+        let handle = thread::Builder::new()
+            .name("overdub-sync".to_string())
+            .spawn(move || run_sync_loop(player, clock, thread_points, thread_stop))
+            .map_err(|e| AppError::internal(e.to_string()))?;
+        Ok(Self {
+            points,
+            stop,
+            handle: Some(handle),
+        })
+    }
+
+    /// 停止同步並取得所有同步點
+    fn finish(mut self) -> Vec<SyncPoint> {
+        self.join();
+        self.points.lock().map(|p| p.clone()).unwrap_or_default()
+    }
+
+    fn join(&mut self) {
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for SyncTracker {
+    fn drop(&mut self) {
+        self.join();
+    }
+}
+
+/// 口述錄音檔路徑: <專案>/dictations/<播放檔名>-dictation-<時間>.wav
+fn dictation_path(root: &Path, source: &Path) -> PathBuf {
+    let stem = source
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "audio".to_string());
+    root.join(DICTATION_DIR).join(format!(
+        "{}-dictation-{}.wav",
+        stem,
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
+
+impl Overdub {
+    /// 開始錄製口述註記，同步 player 目前播放的 source
+    pub fn start(
+        app: AppHandle,
+        project_root: &Path,
+        source: PathBuf,
+        player: Arc<SharedState>,
+        options: RecordingOptions,
+    ) -> Result<Self, AppError> {
+        volume::ensure_writable(project_root)?;
+        let recording = Recording::start(
+            app,
+            options,
+            dictation_path(project_root, &source),
+            project_root.to_path_buf(),
+        )
+        .map_err(AppError::tool)?;
+        tracing::info!("開始口述註記: {}", source.display());
+
+        let tracker = SyncTracker::start(player, recording.clock())?;
+        Ok(Self {
+            recording,
+            source,
+            tracker,
+        })
+    }
+
+    pub fn status(&self) -> OverdubStatus {
+        let status = self.recording.status();
+        OverdubStatus {
+            recording: true,
+            elapsed: status.elapsed,
+            source: Some(self.source.to_string_lossy().to_string()),
+            path: status.path,
+        }
+    }
+}
+
+/// 同步迴圈：與上一個同步點推算的播放位置不符 (跳轉) 或播放狀態改變時記下新同步點
+fn run_sync_loop(
+    player: Arc<SharedState>,
+    clock: RecordingClock,
+    sync: Arc<Mutex<Vec<SyncPoint>>>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut last: Option<SyncPoint> = None;
+    while !stop.load(Ordering::Relaxed) {
+        let point = SyncPoint {
+            dictation_offset: clock.elapsed(),
+            source_offset: player.position_ms() as f64 / 1000.0,
+            playing: !player.is_paused.load(Ordering::Relaxed)
+                && !player.should_stop.load(Ordering::Relaxed),
+        };
+        let changed = match &last {
+            None => true,
+            Some(prev) => {
+                let expected = if prev.playing {
+                    prev.source_offset + (point.dictation_offset - prev.dictation_offset)
+                } else {
+                    prev.source_offset
+                };
+                prev.playing != point.playing
+                    || (point.source_offset - expected).abs() > SYNC_TOLERANCE_SECS
+            }
+        };
+        if changed {
+            if let Ok(mut points) = sync.lock() {
+                points.push(point.clone());
+            }
+            last = Some(point);
+        }
+        thread::sleep(SYNC_INTERVAL);
+    }
+}
+
+/// 停止口述註記並存檔，同步點寫入專案描述檔
+pub async fn stop_and_save(app: &AppHandle, overdub: Overdub) -> Result<DictationRecord, AppError> {
+    let started = Instant::now();
+    let Overdub {
+        recording,
+        source,
+        tracker,
+    } = overdub;
+    let sync = tracker.finish();
+    let root = recording.project_root().to_path_buf();
+    let format = recording.format();
+
+    let finished = tauri::async_runtime::spawn_blocking(move || recording.finish())
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::io)?;
+    let path = recorder::encode_recording(app, finished.wav_path, format).await?;
+
+    let record = DictationRecord {
+        source: source.to_string_lossy().to_string(),
+        output: path.to_string_lossy().to_string(),
+        sync,
+        recorded_at: chrono::Local::now().to_rfc3339(),
+    };
+    let manifest_result = ProjectManifest::update(&root, |m| {
+        m.record_dictation(record.clone());
+        Ok(())
+    });
+    if let Err(e) = manifest_result {
+        tracing::warn!("無法更新專案描述檔: {}", e);
+    }
+
+    history::record_command(
+        &root,
+        "stop_overdub",
+        serde_json::json!({ "source": record.source, "duration": finished.duration }),
+        &[path],
+        started,
+        &Ok::<(), AppError>(()),
+    );
+    Ok(record)
+}
@@ -0,0 +1,417 @@
+// src-tauri/src/services/pipeline.rs
+//
+// 一鍵流程：轉檔 → (自動切割) → 轉錄 → 依規則消音 → 報告，以單一工作執行。
+// 進度保存在專案的 pipeline.json，每個階段 (與階段內的每個檔案) 完成後寫入檢查點，
+// 中斷或失敗後以相同輸入重新執行時，從未完成的地方接續。
+//
+// 實際的處理流程在 workflows::run_pipeline，這裡只定義選項、狀態與消音規則。
+//
+// 流程定義檔 (JSON / YAML) 讓部門統一並分享流程設定，放在：
+// - 全域：app data 的 pipelines/
+// - 專案：<專案>/pipelines/ (同名時優先於全域)
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::file_manager::write_atomic;
+use crate::services::silence::TranscribeResponse;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const PIPELINE_STATE_FILE_NAME: &str = "pipeline.json";
+
+/// 流程定義檔的資料夾 (app data 與專案根目錄下)
+pub const PIPELINES_DIR_NAME: &str = "pipelines";
+
+/// 狀態更新事件 (payload 為 PipelineState)
+pub const PIPELINE_EVENT: &str = "pipeline://progress";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PipelineOptions {
+    /// 自動切割的每段長度 (分鐘)；None 時不切割，直接複製到 02_split
+    pub split_minutes: Option<f64>,
+    /// STT 伺服器；None 時略過轉錄與消音
+    pub server: Option<String>,
+    pub diarize: bool,
+    pub redaction: RedactionRules,
+    /// 最後生成報告
+    pub report: bool,
+    pub model_name: Option<String>,
+    pub custom_prompt: Option<String>,
+    /// 只執行這些階段 (轉檔與切割一定會執行)；None 時依其他選項決定
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stages: Option<Vec<PipelineStage>>,
+}
+
+/// 依逐字稿自動消音的規則
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RedactionRules {
+    /// 段落文字包含任一關鍵字即消音 (不分大小寫)
+    pub keywords: Vec<String>,
+    /// 這些說話者 (ID 或顯示名稱) 的段落全部消音
+    pub speakers: Vec<String>,
+    /// 消音範圍前後延伸的秒數
+    pub padding: f64,
+}
+
+impl RedactionRules {
+    pub fn is_empty(&self) -> bool {
+        self.keywords.iter().all(|k| k.trim().is_empty()) && self.speakers.is_empty()
+    }
+
+    /// 符合規則的段落時間 (已合併重疊的範圍)
+    pub fn ranges(&self, transcript: &TranscribeResponse) -> Vec<(f64, f64)> {
+        let keywords: Vec<String> = self
+            .keywords
+            .iter()
+            .map(|k| k.trim().to_lowercase())
+            .filter(|k| !k.is_empty())
+            .collect();
+        let mut ranges: Vec<(f64, f64)> = transcript
+            .segments
+            .iter()
+            .filter(|segment| {
+                let text = segment.text.to_lowercase();
+                let speaker_match = segment.speaker.as_deref().is_some_and(|id| {
+                    let name = transcript.speaker_name(segment).unwrap_or(id);
+                    self.speakers.iter().any(|s| s == id || s == name)
+                });
+                speaker_match || keywords.iter().any(|k| text.contains(k.as_str()))
+            })
+            .map(|s| ((s.start - self.padding).max(0.0), s.end + self.padding))
+            .collect();
+        ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut merged: Vec<(f64, f64)> = Vec::new();
+        for (start, end) in ranges {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PipelineStage {
+    Convert,
+    Split,
+    Transcribe,
+    Redact,
+    Report,
+}
+
+impl PipelineStage {
+    /// 依選項列出要執行的階段
+    pub fn plan(options: &PipelineOptions) -> Vec<PipelineStage> {
+        let mut stages = vec![PipelineStage::Convert, PipelineStage::Split];
+        if options.server.is_some() {
+            stages.push(PipelineStage::Transcribe);
+            if !options.redaction.is_empty() {
+                stages.push(PipelineStage::Redact);
+            }
+        }
+        if options.report {
+            stages.push(PipelineStage::Report);
+        }
+        if let Some(only) = &options.stages {
+            stages.retain(|stage| {
+                matches!(stage, PipelineStage::Convert | PipelineStage::Split)
+                    || only.contains(stage)
+            });
+        }
+        stages
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineState {
+    pub inputs: Vec<String>,
+    pub options: PipelineOptions,
+    pub stages: Vec<PipelineStage>,
+    pub completed: Vec<PipelineStage>,
+    pub current: Option<PipelineStage>,
+    /// 01_converted 的 MP3
+    #[serde(default)]
+    pub converted: Vec<String>,
+    /// 02_split 中要處理的檔案
+    #[serde(default)]
+    pub split: Vec<String>,
+    /// 已完成轉錄的檔案 → 逐字稿 JSON
+    #[serde(default)]
+    pub transcripts: Vec<(String, String)>,
+    /// 已處理消音的檔案 (含不需消音的檔案)
+    #[serde(default)]
+    pub redacted: Vec<String>,
+    #[serde(default)]
+    pub report: Option<String>,
+    #[serde(default)]
+    pub error: Option<AppError>,
+    pub started_at: String,
+    pub updated_at: String,
+}
+
+impl PipelineState {
+    pub fn new(inputs: Vec<String>, options: PipelineOptions) -> Self {
+        let now = chrono::Local::now().to_rfc3339();
+        Self {
+            stages: PipelineStage::plan(&options),
+            inputs,
+            options,
+            completed: Vec::new(),
+            current: None,
+            converted: Vec::new(),
+            split: Vec::new(),
+            transcripts: Vec::new(),
+            redacted: Vec::new(),
+            report: None,
+            error: None,
+            started_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.stages.iter().all(|s| self.completed.contains(s))
+    }
+
+    /// 與上次相同的輸入與選項、且尚未完成時可接續
+    pub fn can_resume(&self, inputs: &[String], options: &PipelineOptions) -> bool {
+        !self.is_finished() && self.inputs == inputs && &self.options == options
+    }
+
+    pub fn is_done(&self, stage: PipelineStage) -> bool {
+        self.completed.contains(&stage)
+    }
+
+    pub fn transcript_for(&self, file: &str) -> Option<&str> {
+        self.transcripts
+            .iter()
+            .find(|(audio, _)| audio == file)
+            .map(|(_, json)| json.as_str())
+    }
+
+    /// 目前進度 (0.0 ~ 1.0) 對應的階段範圍
+    pub fn stage_range(&self, stage: PipelineStage) -> (f32, f32) {
+        let count = self.stages.len().max(1) as f32;
+        let index = self.stages.iter().position(|s| *s == stage).unwrap_or(0) as f32;
+        (index / count, (index + 1.0) / count)
+    }
+}
+
+pub fn state_path(root: &Path) -> PathBuf {
+    root.join(PIPELINE_STATE_FILE_NAME)
+}
+
+/// 讀取專案的流程狀態 (沒有執行過時為 None)
+pub fn load(root: &Path) -> Option<PipelineState> {
+    let content = fs::read_to_string(state_path(root)).ok()?;
+    match serde_json::from_str(&content) {
+        Ok(state) => Some(state),
+        Err(e) => {
+            tracing::warn!("無法解析流程狀態 {}: {}", root.display(), e);
+            None
+        }
+    }
+}
+
+/// 寫入檢查點並通知前端
+pub fn save(app: &AppHandle, root: &Path, state: &mut PipelineState) -> Result<(), AppError> {
+    state.updated_at = chrono::Local::now().to_rfc3339();
+    let json = serde_json::to_vec_pretty(state)?;
+    write_atomic(&state_path(root), &json)?;
+    let _ = app.emit(PIPELINE_EVENT, &*state);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionScope {
+    Global,
+    Project,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DefinitionFormat {
+    #[default]
+    Json,
+    Yaml,
+}
+
+/// 流程定義檔
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineDefinition {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub options: PipelineOptions,
+    /// 報告 Prompt 檔 (相對於定義檔所在資料夾)，options.custom_prompt 未指定時使用
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_file: Option<String>,
+}
+
+/// 列出的流程定義 (含來源)
+#[derive(Debug, Clone, Serialize)]
+pub struct PipelineEntry {
+    #[serde(flatten)]
+    pub definition: PipelineDefinition,
+    pub scope: DefinitionScope,
+    pub path: String,
+    pub stages: Vec<PipelineStage>,
+}
+
+fn definitions_dir(
+    app: &AppHandle,
+    scope: DefinitionScope,
+    project: Option<&Path>,
+) -> Result<PathBuf, AppError> {
+    match scope {
+        DefinitionScope::Global => app
+            .path()
+            .app_data_dir()
+            .map(|d| d.join(PIPELINES_DIR_NAME))
+            .map_err(|e| AppError::internal(format!("無法取得 app data 目錄: {}", e))),
+        DefinitionScope::Project => project
+            .map(|root| root.join(PIPELINES_DIR_NAME))
+            .ok_or_else(|| {
+                AppError::localized(ErrorKind::InvalidInput, "error.pipeline_no_project", &[])
+            }),
+    }
+}
+
+fn parse_definition(path: &Path) -> Result<PipelineDefinition, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("yaml") | Some("yml") => serde_yaml::from_str(&content).map_err(|e| e.to_string()),
+        _ => serde_json::from_str(&content).map_err(|e| e.to_string()),
+    }
+}
+
+fn read_definitions(dir: &Path, scope: DefinitionScope) -> Vec<PipelineEntry> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            matches!(
+                p.extension().and_then(|e| e.to_str()),
+                Some("json") | Some("yaml") | Some("yml")
+            )
+        })
+        .collect();
+    files.sort();
+    files
+        .into_iter()
+        .filter_map(|path| match parse_definition(&path) {
+            Ok(definition) => Some(PipelineEntry {
+                stages: PipelineStage::plan(&definition.options),
+                definition,
+                scope,
+                path: path.to_string_lossy().to_string(),
+            }),
+            Err(e) => {
+                tracing::warn!("無法讀取流程定義 {}: {}", path.display(), e);
+                None
+            }
+        })
+        .collect()
+}
+
+/// 列出全域與專案的流程定義；同名時專案的定義取代全域的
+pub fn list_definitions(app: &AppHandle, project: Option<&Path>) -> Vec<PipelineEntry> {
+    let mut entries = definitions_dir(app, DefinitionScope::Global, None)
+        .map(|dir| read_definitions(&dir, DefinitionScope::Global))
+        .unwrap_or_default();
+    if let Ok(dir) = definitions_dir(app, DefinitionScope::Project, project) {
+        for entry in read_definitions(&dir, DefinitionScope::Project) {
+            entries.retain(|e| e.definition.name != entry.definition.name);
+            entries.push(entry);
+        }
+    }
+    entries.sort_by(|a, b| a.definition.name.cmp(&b.definition.name));
+    entries
+}
+
+/// 依名稱取得流程定義，並把 prompt_file 讀入 options.custom_prompt
+pub fn resolve_definition(
+    app: &AppHandle,
+    project: Option<&Path>,
+    name: &str,
+) -> Result<PipelineOptions, AppError> {
+    let entry = list_definitions(app, project)
+        .into_iter()
+        .find(|e| e.definition.name == name)
+        .ok_or_else(|| {
+            AppError::localized(
+                ErrorKind::NotFound,
+                "error.pipeline_not_found",
+                &[("name", name.to_string())],
+            )
+        })?;
+    let mut options = entry.definition.options;
+    if options.custom_prompt.is_none() {
+        if let Some(prompt_file) = &entry.definition.prompt_file {
+            let base = Path::new(&entry.path).parent().unwrap_or(Path::new("."));
+            let prompt = fs::read_to_string(base.join(prompt_file)).map_err(|e| {
+                AppError::localized(
+                    ErrorKind::Io,
+                    "error.prompt_read_failed",
+                    &[("detail", e.to_string())],
+                )
+            })?;
+            options.custom_prompt = Some(prompt);
+        }
+    }
+    Ok(options)
+}
+
+/// 保存流程定義，回傳檔案路徑 (檔名取自名稱)
+pub fn save_definition(
+    app: &AppHandle,
+    scope: DefinitionScope,
+    project: Option<&Path>,
+    definition: &PipelineDefinition,
+    format: DefinitionFormat,
+) -> Result<PathBuf, AppError> {
+    let file_stem: String = definition
+        .name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    if file_stem.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.pipeline_invalid_name",
+            &[],
+        ));
+    }
+    let dir = definitions_dir(app, scope, project)?;
+    fs::create_dir_all(&dir)?;
+    let (path, content) = match format {
+        DefinitionFormat::Json => (
+            dir.join(format!("{}.json", file_stem)),
+            serde_json::to_string_pretty(definition)?,
+        ),
+        DefinitionFormat::Yaml => (
+            dir.join(format!("{}.yaml", file_stem)),
+            serde_yaml::to_string(definition).map_err(|e| AppError::internal(e.to_string()))?,
+        ),
+    };
+    write_atomic(&path, content.as_bytes())?;
+    Ok(path)
+}
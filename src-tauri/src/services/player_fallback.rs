@@ -0,0 +1,278 @@
+// src-tauri/src/services/player_fallback.rs
+//
+// FFmpeg decoding fallback for the audio player
+// - Used when symphonia can't probe or decode a file (AMR, WMA, raw PCM dumps from dictaphones...)
+// - FFmpeg decodes to interleaved f32 on stdout, which is fed into the same ring buffer
+// - Seeking restarts FFmpeg with -ss
+
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use ringbuf::traits::{Consumer, Observer, Producer};
+
+use crate::services::audio_player::SharedState;
+
+/// Output format of the fallback decoder (matches what most output devices accept)
+pub const OUTPUT_SAMPLE_RATE: u32 = 48000;
+pub const OUTPUT_CHANNELS: u16 = 2;
+
+/// Headerless dumps are assumed to be 16 kHz mono s16le, the common dictaphone format
+const RAW_PCM_EXTENSIONS: [&str; 2] = ["pcm", "raw"];
+const RAW_PCM_INPUT: [&str; 6] = ["-f", "s16le", "-ar", "16000", "-ac", "1"];
+const RAW_PCM_BYTES_PER_SECOND: f64 = 16000.0 * 2.0;
+
+/// Source format reported by FFmpeg
+#[derive(Debug, Clone, Default)]
+pub struct SourceInfo {
+    pub codec: String,
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub duration_secs: f64,
+}
+
+/// A file played through FFmpeg instead of symphonia
+#[derive(Debug, Clone)]
+pub struct FfmpegSource {
+    executable: PathBuf,
+    path: PathBuf,
+    pub info: SourceInfo,
+}
+
+fn is_raw_pcm(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| RAW_PCM_EXTENSIONS.contains(&e.to_ascii_lowercase().as_str()))
+        .unwrap_or(false)
+}
+
+fn command(executable: &Path) -> Command {
+    #[allow(unused_mut)]
+    let mut command = Command::new(executable);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console window while playing
+        command.creation_flags(0x0800_0000);
+    }
+    command
+}
+
+/// Parse "Duration: 00:01:02.34" from FFmpeg's banner
+fn parse_duration(stderr: &str) -> Option<f64> {
+    let rest = stderr.split("Duration: ").nth(1)?;
+    let time = rest.split(',').next()?.trim();
+    let mut parts = time.split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Parse "Stream #0:0: Audio: amr_nb (samr / 0x726D6173), 8000 Hz, mono, fltp"
+fn parse_audio_stream(stderr: &str, info: &mut SourceInfo) {
+    let Some(line) = stderr.lines().find(|l| l.contains("Audio: ")) else {
+        return;
+    };
+    let Some(desc) = line.split("Audio: ").nth(1) else {
+        return;
+    };
+    info.codec = desc
+        .split([' ', ','])
+        .next()
+        .unwrap_or_default()
+        .to_string();
+    for field in desc.split(", ").map(str::trim) {
+        if let Some(hz) = field.strip_suffix(" Hz") {
+            info.sample_rate = hz.parse().unwrap_or(0);
+        } else if field == "mono" {
+            info.channels = 1;
+        } else if field.starts_with("stereo") {
+            info.channels = 2;
+        } else if let Some(n) = field.strip_suffix(" channels") {
+            info.channels = n.parse().unwrap_or(0);
+        }
+    }
+}
+
+impl FfmpegSource {
+    /// Ask FFmpeg whether it can read the file
+    pub fn probe(executable: PathBuf, path: &Path) -> Result<Self, String> {
+        let mut args: Vec<&str> = vec!["-hide_banner"];
+        if is_raw_pcm(path) {
+            args.extend(RAW_PCM_INPUT);
+        }
+        // Without an output FFmpeg exits with an error but still prints the input format
+        let output = command(&executable)
+            .args(&args)
+            .arg("-i")
+            .arg(path)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("FFmpeg 執行失敗: {}", e))?;
+        let stderr = String::from_utf8_lossy(&output.stderr);
+
+        let mut info = SourceInfo::default();
+        parse_audio_stream(&stderr, &mut info);
+        if info.codec.is_empty() {
+            return Err(format!("FFmpeg 也無法解析此檔案: {}", path.display()));
+        }
+        info.duration_secs = parse_duration(&stderr).unwrap_or(0.0);
+        if info.duration_secs == 0.0 && is_raw_pcm(path) {
+            let size = std::fs::metadata(path).map(|m| m.len()).unwrap_or(0);
+            info.duration_secs = size as f64 / RAW_PCM_BYTES_PER_SECOND;
+        }
+
+        Ok(Self {
+            executable,
+            path: path.to_path_buf(),
+            info,
+        })
+    }
+
+    /// Start FFmpeg decoding from `start_ms`
+    fn spawn(&self, start_ms: u64) -> Result<DecodeProcess, String> {
+        let mut cmd = command(&self.executable);
+        cmd.args(["-v", "error", "-nostdin"]);
+        if start_ms > 0 {
+            cmd.args(["-ss", &format!("{:.3}", start_ms as f64 / 1000.0)]);
+        }
+        if is_raw_pcm(&self.path) {
+            cmd.args(RAW_PCM_INPUT);
+        }
+        let sample_rate = OUTPUT_SAMPLE_RATE.to_string();
+        let channels = OUTPUT_CHANNELS.to_string();
+        let mut child = cmd
+            .arg("-i")
+            .arg(&self.path)
+            .args([
+                "-vn",
+                "-f",
+                "f32le",
+                "-ar",
+                &sample_rate,
+                "-ac",
+                &channels,
+                "-",
+            ])
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("FFmpeg 執行失敗: {}", e))?;
+        let stdout = child.stdout.take().ok_or("無法讀取 FFmpeg 輸出")?;
+        Ok(DecodeProcess { child, stdout })
+    }
+}
+
+/// Running FFmpeg decoder, killed when dropped
+struct DecodeProcess {
+    child: Child,
+    stdout: ChildStdout,
+}
+
+impl Drop for DecodeProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Decoder loop for the fallback path; same contract as the symphonia decoder loop
+pub fn run_decoder_loop(
+    source: FfmpegSource,
+    shared_state: Arc<SharedState>,
+    producer: Arc<Mutex<ringbuf::HeapProd<f32>>>,
+    consumer: Arc<Mutex<ringbuf::HeapCons<f32>>>,
+) -> Result<(), String> {
+    let channels = OUTPUT_CHANNELS as u64;
+    let sample_rate = OUTPUT_SAMPLE_RATE as u64;
+
+    let mut process = source.spawn(0)?;
+    let mut start_ms = 0u64;
+    let mut pushed_samples = 0u64;
+    let mut pending: Vec<u8> = Vec::new();
+    let mut read_buf = vec![0u8; 16 * 1024];
+    let mut samples: Vec<f32> = Vec::new();
+
+    loop {
+        if shared_state.should_stop.load(Ordering::Relaxed) {
+            break;
+        }
+
+        // Seeking restarts FFmpeg at the new position
+        let seek_ms = shared_state
+            .seek_position_ms
+            .swap(u64::MAX, Ordering::SeqCst);
+        if seek_ms != u64::MAX {
+            drop(process);
+            process = source.spawn(seek_ms)?;
+            start_ms = seek_ms;
+            pushed_samples = 0;
+            pending.clear();
+            // Drop what is still queued from before the seek and restart the audible clock
+            consumer.lock().unwrap().clear();
+            shared_state.reset_position(seek_ms);
+        }
+
+        if shared_state.is_paused.load(Ordering::Relaxed) {
+            thread::sleep(std::time::Duration::from_millis(10));
+            continue;
+        }
+
+        let n = match process.stdout.read(&mut read_buf) {
+            Ok(n) => n,
+            Err(e) => {
+                tracing::warn!("FFmpeg read error: {}", e);
+                0
+            }
+        };
+        if n == 0 {
+            // End of stream - keep the thread alive so seeking backwards still works,
+            // pausing once the buffered tail has been heard
+            if consumer.lock().unwrap().is_empty() {
+                shared_state.is_paused.store(true, Ordering::Relaxed);
+            }
+            thread::sleep(std::time::Duration::from_millis(100));
+            continue;
+        }
+
+        pending.extend_from_slice(&read_buf[..n]);
+        let whole = pending.len() / 4 * 4;
+        samples.clear();
+        samples.extend(
+            pending[..whole]
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+        );
+        pending.drain(..whole);
+
+        let mut offset = 0;
+        while offset < samples.len() {
+            if shared_state.should_stop.load(Ordering::Relaxed) {
+                return Ok(());
+            }
+            if shared_state.seek_position_ms.load(Ordering::SeqCst) != u64::MAX {
+                break;
+            }
+            let mut prod = producer.lock().unwrap();
+            if prod.is_full() {
+                drop(prod);
+                thread::sleep(std::time::Duration::from_micros(100));
+                continue;
+            }
+            offset += prod.push_slice(&samples[offset..]);
+        }
+
+        pushed_samples += offset as u64;
+        let position_ms = start_ms + pushed_samples / channels * 1000 / sample_rate;
+        shared_state
+            .current_position_ms
+            .store(position_ms, Ordering::Relaxed);
+    }
+
+    Ok(())
+}
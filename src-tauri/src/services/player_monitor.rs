@@ -0,0 +1,219 @@
+// src-tauri/src/services/player_monitor.rs
+//
+// Second output device for the player (e.g. the clinician's headset and the room speaker)
+// - The main output callback copies every frame it plays into a tap ring buffer
+// - A second cpal stream on the monitor device plays from the tap with its own volume
+// - The main device stays the clock; the monitor pads or drops frames to keep latency bounded
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use ringbuf::{
+    traits::{Consumer, Observer, Split},
+    HeapCons, HeapProd, HeapRb,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::services::audio_player::{select_output_config, SharedState};
+
+/// Tap filled by the main output callback; None while no monitor is running
+pub type MonitorTap = Arc<Mutex<Option<HeapProd<f32>>>>;
+
+/// Capacity of the tap in milliseconds
+const TAP_CAPACITY_MS: u32 = 300;
+/// Frames buffered before the monitor starts playing, absorbing callback jitter
+const PRIME_MS: u32 = 20;
+/// Beyond this the monitor skips ahead so it doesn't drift behind the main device
+const MAX_LATENCY_MS: u32 = 120;
+
+/// Output device as listed to the frontend
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputDevice {
+    pub name: String,
+    pub is_default: bool,
+}
+
+/// Which output a volume applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputTarget {
+    Main,
+    Monitor,
+}
+
+/// Output settings kept across track loads
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputConfig {
+    /// Second device to play to (None = main output only)
+    pub monitor_device: Option<String>,
+    pub main_volume: f32,
+    pub monitor_volume: f32,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            monitor_device: None,
+            main_volume: 1.0,
+            monitor_volume: 1.0,
+        }
+    }
+}
+
+/// List output devices
+pub fn list_output_devices() -> Result<Vec<OutputDevice>, String> {
+    let host = cpal::default_host();
+    let default_name = host.default_output_device().and_then(|d| d.name().ok());
+    let devices = host
+        .output_devices()
+        .map_err(|e| format!("無法列出音訊輸出裝置: {}", e))?;
+
+    Ok(devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            Some(OutputDevice {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                name,
+            })
+        })
+        .collect())
+}
+
+fn find_output_device(name: &str) -> Result<cpal::Device, String> {
+    cpal::default_host()
+        .output_devices()
+        .map_err(|e| format!("無法列出音訊輸出裝置: {}", e))?
+        .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+        .ok_or_else(|| format!("找不到音訊輸出裝置: {}", name))
+}
+
+/// Running monitor stream; stopped and detached from the tap when dropped
+pub struct MonitorOutput {
+    tap: MonitorTap,
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MonitorOutput {
+    /// Start playing the tap on `device_name`
+    pub fn start(
+        device_name: String,
+        sample_rate: u32,
+        channels: u16,
+        shared_state: Arc<SharedState>,
+        tap: MonitorTap,
+    ) -> Self {
+        let capacity = (sample_rate * TAP_CAPACITY_MS / 1000) as usize * channels as usize;
+        let (producer, consumer) = HeapRb::<f32>::new(capacity.max(1)).split();
+        if let Ok(mut slot) = tap.lock() {
+            *slot = Some(producer);
+        }
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_thread = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            if let Err(e) = run_monitor_loop(
+                &device_name,
+                sample_rate,
+                channels,
+                shared_state,
+                consumer,
+                stop_thread,
+            ) {
+                tracing::error!("Monitor output error ({}): {}", device_name, e);
+            }
+        });
+
+        Self {
+            tap,
+            stop,
+            handle: Some(handle),
+        }
+    }
+}
+
+impl Drop for MonitorOutput {
+    fn drop(&mut self) {
+        if let Ok(mut slot) = self.tap.lock() {
+            *slot = None;
+        }
+        self.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn run_monitor_loop(
+    device_name: &str,
+    sample_rate: u32,
+    channels: u16,
+    shared_state: Arc<SharedState>,
+    mut consumer: HeapCons<f32>,
+    stop: Arc<AtomicBool>,
+) -> Result<(), String> {
+    let device = find_output_device(device_name)?;
+    let (config, output_channels) = select_output_config(&device, sample_rate, channels)?;
+
+    // The main output only taps the first 8 channels of each frame
+    let file_ch = (channels as usize).min(8);
+    let out_ch = output_channels as usize;
+    let prime_samples = (sample_rate * PRIME_MS / 1000) as usize * file_ch;
+    let max_samples = (sample_rate * MAX_LATENCY_MS / 1000) as usize * file_ch;
+    let mut primed = false;
+    let state = Arc::clone(&shared_state);
+
+    let stream = device
+        .build_output_stream(
+            &config,
+            move |data: &mut [f32], _: &cpal::OutputCallbackInfo| {
+                let queued = consumer.occupied_len();
+                if !primed && queued < prime_samples {
+                    data.fill(0.0);
+                    return;
+                }
+                primed = true;
+                // Skip whole frames when the monitor has fallen behind the main device
+                if queued > max_samples {
+                    let excess = (queued - prime_samples) / file_ch * file_ch;
+                    consumer.skip(excess);
+                }
+
+                let volume = state.monitor_volume();
+                for frame in data.chunks_mut(out_ch) {
+                    let mut file_samples = [0.0f32; 8];
+                    if consumer.occupied_len() >= file_ch {
+                        for sample in file_samples.iter_mut().take(file_ch) {
+                            *sample = consumer.try_pop().unwrap_or(0.0);
+                        }
+                    } else {
+                        // Underrun: wait for the buffer to refill before resuming
+                        primed = false;
+                    }
+                    for (out_idx, out) in frame.iter_mut().enumerate() {
+                        let sample = if out_idx < file_ch {
+                            file_samples[out_idx]
+                        } else if file_ch >= 2 {
+                            (file_samples[0] + file_samples[1]) / 2.0
+                        } else {
+                            file_samples[0]
+                        };
+                        *out = sample * volume;
+                    }
+                }
+            },
+            |err| tracing::error!("Monitor stream error: {}", err),
+            None,
+        )
+        .map_err(|e| format!("無法建立音訊串流: {}", e))?;
+
+    stream.play().map_err(|e| format!("無法開始播放: {}", e))?;
+    tracing::info!("Monitor output started on {}", device_name);
+
+    while !stop.load(Ordering::Relaxed) && !shared_state.should_stop.load(Ordering::Relaxed) {
+        thread::sleep(std::time::Duration::from_millis(50));
+    }
+    Ok(())
+}
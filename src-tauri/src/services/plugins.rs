@@ -0,0 +1,238 @@
+// src-tauri/src/services/plugins.rs
+//
+// 外掛：在流程的固定位置執行院所自己的外部程式 (例如自訂的去識別化或歸檔工具)，不必修改本程式。
+//
+// - 在設定的 plugins 中登記執行檔、參數與要掛上的位置 (hooks)
+// - post_convert: 轉檔完成後；pre_upload: 音檔上傳到 STT 伺服器或 Gemini 之前；
+//   post_report: 報告 (Markdown / JSON / DOCX) 產生後；
+//   decode: 把 FFmpeg 無法解碼的錄音筆格式 (DSS / DS2) 轉成 WAV (寫到 JSON 中的 output)
+// - 程式從 stdin 收到一個 JSON：{"hook", "files", "project": {"root", 模板變數...}}，
+//   可直接修改收到的檔案 (例如上傳前消音)，結束碼 0 視為成功
+// - 工作目錄為專案根目錄；取消工作或逾時會終止外掛
+// - 外掛失敗時：required 的外掛讓該檔案 (或整個工作) 失敗，其餘只記錄警告
+// - 目前只支援外部執行檔 (不支援 WASM)
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::jobs::CancelToken;
+use crate::services::manifest::ProjectManifest;
+use crate::services::settings::{self, PluginConfig, PluginHook};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// 錯誤訊息保留的 stderr 長度 (字元)
+const MAX_STDERR_CHARS: usize = 500;
+
+/// 傳給外掛的專案資訊
+#[derive(Debug, Serialize)]
+struct PluginProject {
+    root: PathBuf,
+    /// 專案描述檔的模板變數 (project_name、recording_date、department)
+    #[serde(flatten)]
+    vars: BTreeMap<String, String>,
+}
+
+/// 寫入外掛 stdin 的 JSON
+#[derive(Debug, Serialize)]
+struct PluginInput<'a> {
+    hook: PluginHook,
+    files: &'a [PathBuf],
+    /// decode 外掛要寫出的 WAV 路徑
+    #[serde(skip_serializing_if = "Option::is_none")]
+    output: Option<&'a Path>,
+    project: Option<PluginProject>,
+}
+
+/// 設定中掛在此位置且已啟用的外掛
+fn plugins_for(hook: PluginHook) -> Vec<PluginConfig> {
+    settings::load()
+        .plugins
+        .into_iter()
+        .filter(|p| p.enabled && p.hooks.contains(&hook))
+        .collect()
+}
+
+fn tail(text: &str) -> String {
+    let text = text.trim();
+    let count = text.chars().count();
+    if count <= MAX_STDERR_CHARS {
+        return text.to_string();
+    }
+    text.chars().skip(count - MAX_STDERR_CHARS).collect()
+}
+
+fn plugin_error(plugin: &PluginConfig, hook: PluginHook, detail: impl Into<String>) -> AppError {
+    AppError::localized(
+        ErrorKind::Tool,
+        "error.plugin_failed",
+        &[
+            ("name", plugin.name.clone()),
+            ("hook", hook.as_str().to_string()),
+        ],
+    )
+    .with_detail(detail)
+}
+
+/// 執行一個外掛，等待結束或取消
+async fn run_plugin(
+    plugin: &PluginConfig,
+    hook: PluginHook,
+    input: &[u8],
+    root: Option<&Path>,
+    cancel: &CancelToken,
+) -> Result<(), AppError> {
+    let mut command = tokio::process::Command::new(&plugin.command);
+    command
+        .args(&plugin.args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    if let Some(root) = root {
+        command.current_dir(root);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| plugin_error(plugin, hook, format!("{}: {}", plugin.command, e)))?;
+
+    // 外掛不讀 stdin 就結束時寫入會失敗，不視為錯誤 (以結束碼判斷)
+    if let Some(mut stdin) = child.stdin.take() {
+        if let Err(e) = stdin.write_all(input).await {
+            tracing::debug!("外掛 {} 未讀取輸入: {}", plugin.name, e);
+        }
+    }
+
+    let timeout = Duration::from_secs(plugin.timeout_secs);
+    let output = tokio::select! {
+        output = tokio::time::timeout(timeout, child.wait_with_output()) => match output {
+            Ok(output) => output.map_err(|e| plugin_error(plugin, hook, e.to_string()))?,
+            Err(_) => {
+                return Err(AppError::localized(
+                    ErrorKind::Tool,
+                    "error.plugin_timeout",
+                    &[
+                        ("name", plugin.name.clone()),
+                        ("secs", plugin.timeout_secs.to_string()),
+                    ],
+                ))
+            }
+        },
+        _ = cancel.cancelled() => return Err(AppError::cancelled()),
+    };
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    if !stdout.trim().is_empty() {
+        tracing::info!(
+            "外掛 {} ({}): {}",
+            plugin.name,
+            hook.as_str(),
+            stdout.trim()
+        );
+    }
+    if !output.status.success() {
+        let stderr = tail(&String::from_utf8_lossy(&output.stderr));
+        let detail = match output.status.code() {
+            Some(code) => format!("exit code {}: {}", code, stderr),
+            None => format!("terminated: {}", stderr),
+        };
+        return Err(plugin_error(plugin, hook, detail));
+    }
+    Ok(())
+}
+
+fn project_info(root: Option<&Path>) -> Option<PluginProject> {
+    root.map(|root| PluginProject {
+        root: root.to_path_buf(),
+        vars: ProjectManifest::load(root)
+            .map_err(|e| tracing::warn!("無法讀取專案描述檔: {}", e))
+            .unwrap_or_default()
+            .template_vars(root),
+    })
+}
+
+/// 依序執行掛在 hook 的外掛；required 的外掛失敗時回傳錯誤並停止，其餘只記錄警告
+pub async fn run_hook(
+    hook: PluginHook,
+    root: Option<&Path>,
+    files: &[PathBuf],
+    cancel: &CancelToken,
+) -> Result<(), AppError> {
+    let plugins = plugins_for(hook);
+    if plugins.is_empty() || files.is_empty() {
+        return Ok(());
+    }
+    let input = PluginInput {
+        hook,
+        files,
+        output: None,
+        project: project_info(root),
+    };
+    let json = serde_json::to_vec(&input)?;
+
+    for plugin in &plugins {
+        if cancel.is_cancelled() {
+            return Err(AppError::cancelled());
+        }
+        match run_plugin(plugin, hook, &json, root, cancel).await {
+            Ok(()) => {}
+            Err(e) if e.kind == ErrorKind::Cancelled || plugin.required => return Err(e),
+            Err(e) => tracing::warn!("外掛 {} 失敗，繼續處理: {}", plugin.name, e),
+        }
+    }
+    Ok(())
+}
+
+/// 已啟用的 decode 外掛名稱
+pub fn decoders() -> Vec<String> {
+    plugins_for(PluginHook::Decode)
+        .into_iter()
+        .map(|p| p.name)
+        .collect()
+}
+
+/// 以 decode 外掛把 input 轉成 output (WAV)，依序嘗試到有外掛產生檔案為止，回傳使用的外掛名稱
+pub async fn decode(
+    input: &Path,
+    output: &Path,
+    root: Option<&Path>,
+    cancel: &CancelToken,
+) -> Result<String, AppError> {
+    let files = [input.to_path_buf()];
+    let json = serde_json::to_vec(&PluginInput {
+        hook: PluginHook::Decode,
+        files: &files,
+        output: Some(output),
+        project: project_info(root),
+    })?;
+
+    let mut last_error = None;
+    for plugin in plugins_for(PluginHook::Decode) {
+        // 上一個外掛留下的不完整檔案不算成功
+        let _ = std::fs::remove_file(output);
+        let result = run_plugin(&plugin, PluginHook::Decode, &json, root, cancel).await;
+        let produced = std::fs::metadata(output).is_ok_and(|m| m.len() > 0);
+        let error = match result {
+            Ok(()) if produced => return Ok(plugin.name),
+            Ok(()) => AppError::localized(
+                ErrorKind::Tool,
+                "error.plugin_no_output",
+                &[("name", plugin.name.clone())],
+            ),
+            Err(e) if e.kind == ErrorKind::Cancelled => return Err(e),
+            Err(e) => e,
+        };
+        tracing::warn!(
+            "外掛 {} 無法轉換 {}: {}",
+            plugin.name,
+            input.display(),
+            error
+        );
+        last_error = Some(error);
+    }
+    Err(last_error.unwrap_or_else(|| {
+        AppError::localized(ErrorKind::Unsupported, "error.plugin_no_decoder", &[])
+    }))
+}
@@ -0,0 +1,161 @@
+// src-tauri/src/services/prefetch.rs
+//
+// 播放預先解碼：開啟專案後在背景把 02_split / 03_silence 每個檔案開頭幾秒解碼到記憶體，
+// 播放器載入時直接使用快取的取樣率與長度，開始播放時先送出快取的樣本，
+// 開檔、探測與建立解碼器在背景完成，逐一點選短段落時能立即開始播放。
+//
+// 快取以修改時間判斷是否過期，總大小有上限 (最久未使用的先移除)。
+
+use crate::services::file_manager::ProjectPaths;
+use crate::services::ingest::is_media_file;
+use crate::services::probe;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+/// 每個檔案預先解碼的長度 (秒)
+const PREFETCH_SECONDS: f64 = 3.0;
+/// 快取總大小上限 (位元組)
+const MAX_CACHE_BYTES: usize = 96 * 1024 * 1024;
+/// 預先解碼的階段資料夾
+const PREFETCH_STAGES: [&str; 2] = ["02_split", "03_silence"];
+
+/// 檔案開頭的解碼結果 (interleaved f32)
+#[derive(Debug)]
+pub struct PrefetchedAudio {
+    pub sample_rate: u32,
+    pub channels: u16,
+    pub duration_secs: f64,
+    pub samples: Vec<f32>,
+}
+
+struct Entry {
+    path: PathBuf,
+    modified: SystemTime,
+    audio: Arc<PrefetchedAudio>,
+}
+
+/// 依使用順序排列，最近使用的在最後
+static ENTRIES: Mutex<Vec<Entry>> = Mutex::new(Vec::new());
+/// 切換專案時遞增，讓舊的背景預先解碼提早結束
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// 取得快取 (檔案修改過則視為沒有快取)
+pub fn get(path: &Path) -> Option<Arc<PrefetchedAudio>> {
+    let modified = modified(path)?;
+    let mut entries = ENTRIES.lock().ok()?;
+    let index = entries.iter().position(|e| e.path == path)?;
+    if entries[index].modified != modified {
+        entries.remove(index);
+        return None;
+    }
+    let entry = entries.remove(index);
+    let audio = Arc::clone(&entry.audio);
+    entries.push(entry);
+    Some(audio)
+}
+
+fn contains(path: &Path, modified: SystemTime) -> bool {
+    ENTRIES
+        .lock()
+        .map(|entries| {
+            entries
+                .iter()
+                .any(|e| e.path == path && e.modified == modified)
+        })
+        .unwrap_or(false)
+}
+
+fn insert(path: PathBuf, modified: SystemTime, audio: PrefetchedAudio) {
+    let Ok(mut entries) = ENTRIES.lock() else {
+        return;
+    };
+    entries.retain(|e| e.path != path);
+    entries.push(Entry {
+        path,
+        modified,
+        audio: Arc::new(audio),
+    });
+    let size = |entries: &Vec<Entry>| -> usize {
+        entries
+            .iter()
+            .map(|e| e.audio.samples.len() * std::mem::size_of::<f32>())
+            .sum()
+    };
+    while entries.len() > 1 && size(&entries) > MAX_CACHE_BYTES {
+        entries.remove(0);
+    }
+}
+
+/// 解碼檔案開頭 PREFETCH_SECONDS 秒
+fn decode_head(path: &Path) -> Result<PrefetchedAudio, String> {
+    let path_str = path.to_string_lossy();
+    let duration_secs = probe::audio_duration(&path_str)?;
+    let mut samples = Vec::new();
+    let mut limit = None;
+    let info = probe::decode_samples_while(&path_str, |block, info| {
+        let limit = *limit.get_or_insert_with(|| {
+            (PREFETCH_SECONDS * info.sample_rate as f64) as usize * info.channels as usize
+        });
+        let take = block.len().min(limit - samples.len());
+        samples.extend_from_slice(&block[..take]);
+        samples.len() < limit
+    })?;
+    Ok(PrefetchedAudio {
+        sample_rate: info.sample_rate,
+        channels: info.channels,
+        duration_secs,
+        samples,
+    })
+}
+
+/// 在背景預先解碼專案內的檔案 (取代先前專案的預先解碼)
+pub fn warm(root: &Path) {
+    let generation = GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+    let paths = ProjectPaths::from_existing_root(root.to_path_buf());
+    let mut files: Vec<PathBuf> = PREFETCH_STAGES
+        .iter()
+        .filter_map(|stage| paths.stage_dir(stage).ok())
+        .filter_map(|dir| fs::read_dir(dir).ok())
+        .flat_map(|entries| entries.flatten().map(|e| e.path()))
+        .filter(|p| p.is_file() && is_media_file(p))
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return;
+    }
+
+    let result = std::thread::Builder::new()
+        .name("prefetch".to_string())
+        .spawn(move || {
+            let mut count = 0;
+            for file in files {
+                if GENERATION.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                let Some(modified) = modified(&file) else {
+                    continue;
+                };
+                if contains(&file, modified) {
+                    continue;
+                }
+                match decode_head(&file) {
+                    Ok(audio) => {
+                        insert(file, modified, audio);
+                        count += 1;
+                    }
+                    Err(e) => tracing::debug!("預先解碼失敗 {}: {}", file.display(), e),
+                }
+            }
+            tracing::info!("已預先解碼 {} 個檔案", count);
+        });
+    if let Err(e) = result {
+        tracing::error!("無法啟動預先解碼執行緒: {}", e);
+    }
+}
@@ -0,0 +1,414 @@
+// src-tauri/src/services/probe.rs
+//
+// 音檔資訊探測 (時長等)，供報告、空間估算等共用
+
+/// 取得音檔長度（秒）— 使用 symphonia 原生解析，不依賴外部程式
+pub fn audio_duration(file_path: &str) -> Result<f64, String> {
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let file = std::fs::File::open(file_path).map_err(|e| format!("無法開啟音檔: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = std::path::Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("無法解析音檔格式: {}", e))?;
+
+    let reader = probed.format;
+
+    // 嘗試從預設 track 取得時長
+    if let Some(track) = reader.default_track() {
+        if let Some(n_frames) = track.codec_params.n_frames {
+            if let Some(tb) = track.codec_params.time_base {
+                let time = tb.calc_time(n_frames);
+                return Ok(time.seconds as f64 + time.frac);
+            }
+        }
+        // 備用：嘗試從 sample_rate 和 n_frames 推算
+        if let (Some(n_frames), Some(sample_rate)) =
+            (track.codec_params.n_frames, track.codec_params.sample_rate)
+        {
+            if sample_rate > 0 {
+                return Ok(n_frames as f64 / sample_rate as f64);
+            }
+        }
+    }
+
+    Err("無法從音檔取得時長資訊".to_string())
+}
+
+/// 解碼結果資訊
+#[derive(Debug, Clone, Copy)]
+pub struct DecodeInfo {
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// 解碼失敗而略過的封包數 (檔案損毀的跡象)
+    pub decode_errors: usize,
+    pub packets: usize,
+}
+
+/// 逐段解碼整個音檔，以 interleaved f32 樣本 (與目前的取樣率 / 聲道數) 呼叫 on_block
+/// 損毀的封包會略過並計入 decode_errors，不中止解碼
+pub fn decode_samples(
+    file_path: &str,
+    mut on_block: impl FnMut(&[f32], &DecodeInfo),
+) -> Result<DecodeInfo, String> {
+    decode_samples_while(file_path, |block, info| {
+        on_block(block, info);
+        true
+    })
+}
+
+/// 與 decode_samples 相同，on_block 回傳 false 時提早結束 (只需要開頭時使用)
+pub fn decode_samples_while(
+    file_path: &str,
+    mut on_block: impl FnMut(&[f32], &DecodeInfo) -> bool,
+) -> Result<DecodeInfo, String> {
+    use symphonia::core::audio::SampleBuffer;
+    use symphonia::core::codecs::{DecoderOptions, CODEC_TYPE_NULL};
+    use symphonia::core::errors::Error;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let file = std::fs::File::open(file_path).map_err(|e| format!("無法開啟音檔: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+
+    let mut hint = Hint::new();
+    if let Some(ext) = std::path::Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        hint.with_extension(ext);
+    }
+
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("無法解析音檔格式: {}", e))?;
+    let mut format = probed.format;
+
+    let track = format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .ok_or("找不到音訊軌道")?;
+    let track_id = track.id;
+    let mut info = DecodeInfo {
+        sample_rate: track.codec_params.sample_rate.unwrap_or(0),
+        channels: track
+            .codec_params
+            .channels
+            .map(|c| c.count() as u16)
+            .unwrap_or(0),
+        decode_errors: 0,
+        packets: 0,
+    };
+    let mut decoder = symphonia::default::get_codecs()
+        .make(&track.codec_params, &DecoderOptions::default())
+        .map_err(|e| format!("無法建立解碼器: {}", e))?;
+
+    let mut sample_buf: Option<SampleBuffer<f32>> = None;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(Error::IoError(ref e)) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(Error::ResetRequired) => break,
+            Err(e) => {
+                // 容器層的錯誤 (檔案截斷等) 無法繼續讀取
+                tracing::warn!("讀取封包失敗 {}: {}", file_path, e);
+                info.decode_errors += 1;
+                break;
+            }
+        };
+        if packet.track_id() != track_id {
+            continue;
+        }
+        info.packets += 1;
+
+        let decoded = match decoder.decode(&packet) {
+            Ok(decoded) => decoded,
+            Err(Error::DecodeError(_)) | Err(Error::IoError(_)) => {
+                info.decode_errors += 1;
+                continue;
+            }
+            Err(e) => return Err(format!("解碼失敗: {}", e)),
+        };
+        let spec = *decoded.spec();
+        info.sample_rate = spec.rate;
+        info.channels = spec.channels.count() as u16;
+
+        let buf = sample_buf
+            .get_or_insert_with(|| SampleBuffer::<f32>::new(decoded.capacity() as u64, spec));
+        if buf.capacity() < decoded.capacity() * spec.channels.count() {
+            *buf = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+        }
+        buf.copy_interleaved_ref(decoded);
+        if !on_block(buf.samples(), &info) {
+            break;
+        }
+    }
+
+    if info.sample_rate == 0 || info.channels == 0 {
+        return Err("無法取得音檔取樣率".to_string());
+    }
+    Ok(info)
+}
+
+/// 探測快取檔 (專案根目錄)
+pub const PROBE_CACHE_FILE_NAME: &str = ".probe_cache.json";
+
+/// probe_folder 探測的音檔副檔名
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "wav", "flac", "m4a", "aac", "ogg", "mp4"];
+
+/// 同時讀寫快取檔的保護
+static CACHE_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+/// 單一音檔的探測結果
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AudioInfo {
+    pub path: String,
+    pub size: u64,
+    /// 修改時間 (Unix 秒)
+    pub modified: u64,
+    pub duration: Option<f64>,
+    pub codec: Option<String>,
+    pub sample_rate: Option<u32>,
+    pub channels: Option<u16>,
+    /// 無法解析時的原因
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+struct ProbeCache {
+    /// 專案相對路徑 → 探測結果
+    files: std::collections::BTreeMap<String, AudioInfo>,
+}
+
+/// 取得檔案的 (大小, 修改時間)
+fn file_stamp(path: &std::path::Path) -> Result<(u64, u64), String> {
+    let metadata = std::fs::metadata(path).map_err(|e| format!("無法讀取檔案資訊: {}", e))?;
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    Ok((metadata.len(), modified))
+}
+
+/// 探測單一音檔的時長、編碼、取樣率 (不使用快取)
+pub fn probe_file(file_path: &str) -> AudioInfo {
+    let (size, modified) = file_stamp(std::path::Path::new(file_path)).unwrap_or((0, 0));
+    let mut info = AudioInfo {
+        path: file_path.to_string(),
+        size,
+        modified,
+        duration: None,
+        codec: None,
+        sample_rate: None,
+        channels: None,
+        error: None,
+    };
+    match probe_params(file_path) {
+        Ok(params) => {
+            info.codec = symphonia::default::get_codecs()
+                .get_codec(params.codec)
+                .map(|d| d.short_name.to_string());
+            info.sample_rate = params.sample_rate;
+            info.channels = params.channels.map(|c| c.count() as u16);
+            info.duration = audio_duration(file_path).ok();
+        }
+        Err(e) => info.error = Some(e),
+    }
+    info
+}
+
+fn probe_params(file_path: &str) -> Result<symphonia::core::codecs::CodecParameters, String> {
+    use symphonia::core::codecs::CODEC_TYPE_NULL;
+    use symphonia::core::formats::FormatOptions;
+    use symphonia::core::io::MediaSourceStream;
+    use symphonia::core::meta::MetadataOptions;
+    use symphonia::core::probe::Hint;
+
+    let file = std::fs::File::open(file_path).map_err(|e| format!("無法開啟音檔: {}", e))?;
+    let mss = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    if let Some(ext) = std::path::Path::new(file_path)
+        .extension()
+        .and_then(|e| e.to_str())
+    {
+        hint.with_extension(ext);
+    }
+    let probed = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )
+        .map_err(|e| format!("無法解析音檔格式: {}", e))?;
+    probed
+        .format
+        .tracks()
+        .iter()
+        .find(|t| t.codec_params.codec != CODEC_TYPE_NULL)
+        .map(|t| t.codec_params.clone())
+        .ok_or_else(|| "找不到音訊軌道".to_string())
+}
+
+/// 檔案所屬專案的根目錄 (路徑本身是專案根目錄也算)
+fn project_root_of(path: &std::path::Path) -> Option<std::path::PathBuf> {
+    crate::services::ProjectPaths::find_root(path).or_else(|| {
+        path.ancestors()
+            .find(|p| {
+                p.join(crate::services::file_manager::STAGE_DIRS[0])
+                    .is_dir()
+            })
+            .map(|p| p.to_path_buf())
+    })
+}
+
+fn load_cache(root: &std::path::Path) -> ProbeCache {
+    std::fs::read_to_string(root.join(PROBE_CACHE_FILE_NAME))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// 將新的探測結果併入快取 (其他檔案的紀錄保留)
+fn store_cache(root: &std::path::Path, infos: &[AudioInfo]) {
+    use crate::services::file_manager::{to_project_relative, write_atomic};
+
+    let Ok(_guard) = CACHE_LOCK.lock() else {
+        return;
+    };
+    let mut cache = load_cache(root);
+    for info in infos {
+        let key = to_project_relative(root, std::path::Path::new(&info.path));
+        cache.files.insert(key, info.clone());
+    }
+    // 移除已刪除的檔案
+    cache
+        .files
+        .retain(|key, _| crate::services::file_manager::resolve_project_path(root, key).exists());
+    match serde_json::to_vec(&cache) {
+        Ok(json) => {
+            if let Err(e) = write_atomic(&root.join(PROBE_CACHE_FILE_NAME), &json) {
+                tracing::warn!("無法寫入探測快取 {}: {}", root.display(), e);
+            }
+        }
+        Err(e) => tracing::warn!("無法序列化探測快取: {}", e),
+    }
+}
+
+/// 快取中 (路徑, 大小, 修改時間) 相符的結果
+fn cached(root: &std::path::Path, cache: &ProbeCache, path: &std::path::Path) -> Option<AudioInfo> {
+    let (size, modified) = file_stamp(path).ok()?;
+    let key = crate::services::file_manager::to_project_relative(root, path);
+    cache
+        .files
+        .get(&key)
+        .filter(|info| info.size == size && info.modified == modified)
+        .map(|info| AudioInfo {
+            path: path.to_string_lossy().to_string(),
+            ..info.clone()
+        })
+}
+
+/// 取得音檔長度，專案內的檔案優先使用探測快取
+pub fn cached_duration(file_path: &str) -> Result<f64, String> {
+    let path = std::path::Path::new(file_path);
+    let Some(root) = project_root_of(path) else {
+        return audio_duration(file_path);
+    };
+    if let Some(duration) = cached(&root, &load_cache(&root), path).and_then(|i| i.duration) {
+        return Ok(duration);
+    }
+    let info = probe_file(file_path);
+    store_cache(&root, std::slice::from_ref(&info));
+    info.duration.ok_or_else(|| {
+        info.error
+            .unwrap_or_else(|| "無法從音檔取得時長資訊".to_string())
+    })
+}
+
+/// 同時探測資料夾內所有音檔 (不含子資料夾)，結果依檔名排序
+/// 資料夾位於專案內時，以 (路徑, 修改時間) 快取在專案的 .probe_cache.json
+pub fn probe_folder(dir: &std::path::Path) -> Result<Vec<AudioInfo>, String> {
+    let mut files: Vec<std::path::PathBuf> = std::fs::read_dir(dir)
+        .map_err(|e| format!("無法讀取資料夾: {}", e))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .map(|ext| ext.to_string_lossy().to_lowercase())
+                    .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.as_str()))
+        })
+        .collect();
+    files.sort();
+
+    let root = project_root_of(dir);
+    let cache = root.as_deref().map(load_cache).unwrap_or_default();
+    let mut results: Vec<Option<AudioInfo>> = files
+        .iter()
+        .map(|path| root.as_deref().and_then(|r| cached(r, &cache, path)))
+        .collect();
+
+    let pending: Vec<usize> = (0..files.len()).filter(|&i| results[i].is_none()).collect();
+    if !pending.is_empty() {
+        let workers = std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(4)
+            .min(pending.len());
+        let chunk_size = pending.len().div_ceil(workers);
+        let probed: Vec<(usize, AudioInfo)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = pending
+                .chunks(chunk_size)
+                .map(|chunk| {
+                    let files = &files;
+                    scope.spawn(move || {
+                        chunk
+                            .iter()
+                            .map(|&i| (i, probe_file(&files[i].to_string_lossy())))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|h| h.join().unwrap_or_default())
+                .collect()
+        });
+        if let Some(root) = &root {
+            let infos: Vec<AudioInfo> = probed.iter().map(|(_, info)| info.clone()).collect();
+            store_cache(root, &infos);
+        }
+        for (i, info) in probed {
+            results[i] = Some(info);
+        }
+    }
+
+    Ok(results.into_iter().flatten().collect())
+}
@@ -0,0 +1,235 @@
+// src-tauri/src/services/project_stats.rs
+//
+// 專案統計：個案摘要與主管匯出用的數字。
+//
+// - 各階段 (01_converted、01b_cleaned、02_split、03_silence) 的音檔數與總分鐘數 (使用探測快取)
+// - 片段數：02_split 的音檔數
+// - 消音秒數：操作紀錄中成功的消音工作，加上自動流程依規則消音的段落
+// - 報告字數：04_report/report.md (中日韓文字每字計一，其餘以空白分詞)
+// - API 用量：報告、Prompt 實驗與主題分章的 token 用量，記在專案根目錄的 api_usage.jsonl (只附加)
+
+use crate::services::file_manager::ProjectPaths;
+use crate::services::history;
+use crate::services::jobs::JobSpec;
+use crate::services::report::TokenUsage;
+use crate::services::{pipeline, probe, transcript};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const API_USAGE_FILE_NAME: &str = "api_usage.jsonl";
+
+/// 避免同時附加時行與行交錯
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// 一次 Gemini 呼叫批次的用量
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiUsageRecord {
+    pub timestamp: String,
+    /// 用途 (report、experiment、chapters)
+    pub source: String,
+    pub model: String,
+    pub usage: TokenUsage,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StageStats {
+    /// 資料夾名稱 (例如 02_split)
+    pub stage: String,
+    pub files: usize,
+    pub minutes: f64,
+    /// 無法取得長度的檔案 (損毀或格式不支援)，不計入分鐘數
+    pub unknown_duration: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ApiSpend {
+    pub total: TokenUsage,
+    /// 模型 → 用量
+    pub by_model: BTreeMap<String, TokenUsage>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectStats {
+    pub root: String,
+    pub stages: Vec<StageStats>,
+    pub segments: usize,
+    pub redacted_seconds: f64,
+    /// 沒有報告 (或報告已加密) 時為 None
+    pub report_words: Option<usize>,
+    pub api: ApiSpend,
+}
+
+fn usage_path(root: &Path) -> PathBuf {
+    root.join(API_USAGE_FILE_NAME)
+}
+
+/// 記錄 API 用量 (沒有用量時不記錄)
+pub fn record_api_usage(root: &Path, source: &str, model: &str, usage: TokenUsage) {
+    if usage.total_tokens == 0 {
+        return;
+    }
+    let record = ApiUsageRecord {
+        timestamp: chrono::Local::now().to_rfc3339(),
+        source: source.to_string(),
+        model: model.to_string(),
+        usage,
+    };
+    let result = (|| -> Result<(), String> {
+        let mut line = serde_json::to_string(&record).map_err(|e| e.to_string())?;
+        line.push('\n');
+        let _guard = APPEND_LOCK.lock().map_err(|e| e.to_string())?;
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(usage_path(root))
+            .and_then(|mut file| file.write_all(line.as_bytes()))
+            .map_err(|e| e.to_string())
+    })();
+    if let Err(e) = result {
+        tracing::warn!("無法記錄 API 用量: {}", e);
+    }
+}
+
+/// 讀取 API 用量紀錄 (舊到新)；無法解析的行略過
+pub fn load_api_usage(root: &Path) -> Vec<ApiUsageRecord> {
+    fs::read_to_string(usage_path(root))
+        .map(|content| {
+            content
+                .lines()
+                .filter_map(|line| serde_json::from_str(line).ok())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn stage_stats(dir: &Path) -> StageStats {
+    let infos = if dir.is_dir() {
+        probe::probe_folder(dir).unwrap_or_default()
+    } else {
+        Vec::new()
+    };
+    let seconds: f64 = infos.iter().filter_map(|i| i.duration).sum();
+    StageStats {
+        stage: dir
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        files: infos.len(),
+        minutes: seconds / 60.0,
+        unknown_duration: infos.iter().filter(|i| i.duration.is_none()).count(),
+    }
+}
+
+/// 時段總長 (重疊的部分只計一次)
+fn covered_seconds(ranges: &[(f64, f64)]) -> f64 {
+    let mut ranges: Vec<(f64, f64)> = ranges.iter().copied().filter(|(s, e)| e > s).collect();
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+    let mut total = 0.0;
+    let mut current: Option<(f64, f64)> = None;
+    for (start, end) in ranges {
+        current = match current {
+            Some((s, e)) if start <= e => Some((s, e.max(end))),
+            Some((s, e)) => {
+                total += e - s;
+                Some((start, end))
+            }
+            None => Some((start, end)),
+        };
+    }
+    if let Some((s, e)) = current {
+        total += e - s;
+    }
+    total
+}
+
+/// 消音秒數：成功的手動消音工作 + 最近一次自動流程的規則消音
+fn redacted_seconds(root: &Path) -> f64 {
+    let manual: f64 = history::load_jobs(root, None)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|entry| entry.outcome == history::HistoryOutcome::Success)
+        .filter_map(|entry| serde_json::from_value::<JobSpec>(entry.args).ok())
+        .map(|spec| match spec {
+            JobSpec::Silence { segments, .. } | JobSpec::SilenceToDir { segments, .. } => {
+                covered_seconds(&segments)
+            }
+            _ => 0.0,
+        })
+        .sum();
+    let pipeline: f64 = pipeline::load(root)
+        .map(|state| {
+            state
+                .transcripts
+                .iter()
+                .filter(|(file, _)| state.redacted.contains(file))
+                .filter_map(|(_, json)| transcript::load(Path::new(json)).ok())
+                .map(|t| covered_seconds(&state.options.redaction.ranges(&t)))
+                .sum()
+        })
+        .unwrap_or(0.0);
+    manual + pipeline
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xAC00..=0xD7AF | 0xF900..=0xFAFF)
+}
+
+/// 字數：中日韓文字每字計一，其他文字以空白分詞
+pub fn word_count(text: &str) -> usize {
+    let mut count = 0;
+    let mut in_word = false;
+    for c in text.chars() {
+        if is_cjk(c) {
+            count += 1;
+            in_word = false;
+        } else if c.is_alphanumeric() {
+            if !in_word {
+                count += 1;
+            }
+            in_word = true;
+        } else {
+            in_word = false;
+        }
+    }
+    count
+}
+
+/// 彙整專案統計
+pub fn collect(root: &Path) -> Result<ProjectStats, String> {
+    if !root.is_dir() {
+        return Err(format!("專案資料夾不存在: {}", root.display()));
+    }
+    let paths = ProjectPaths::from_existing_root(root.to_path_buf());
+    let split = stage_stats(&paths.split);
+    let segments = split.files;
+    let mut stages = vec![stage_stats(&paths.converted)];
+    if paths.cleaned.is_dir() {
+        stages.push(stage_stats(&paths.cleaned));
+    }
+    stages.push(split);
+    stages.push(stage_stats(&paths.silence));
+
+    let report_words = fs::read_to_string(paths.report.join("report.md"))
+        .ok()
+        .map(|text| word_count(&text));
+
+    let mut api = ApiSpend::default();
+    for record in load_api_usage(root) {
+        api.total += record.usage;
+        *api.by_model.entry(record.model).or_default() += record.usage;
+    }
+
+    Ok(ProjectStats {
+        root: root.to_string_lossy().to_string(),
+        stages,
+        segments,
+        redacted_seconds: redacted_seconds(root),
+        report_words,
+        api,
+    })
+}
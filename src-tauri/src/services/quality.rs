@@ -0,0 +1,268 @@
+// src-tauri/src/services/quality.rs
+//
+// 錄音品質檢查：在送出轉錄 / 報告 (會花 API 額度) 之前找出無法使用的錄音。
+// 偵測項目：
+// - 削波 (clipping)：樣本貼近滿刻度
+// - 長時間數位靜音：樣本完全為 0 (麥克風未接、錄音軟體靜音)
+// - 斷訊 (dropout)：有聲音的段落中間突然出現短暫的 0
+// - 直流偏移 (DC offset)
+// - 損毀的封包 (解碼失敗)
+
+use crate::services::probe;
+use serde::Serialize;
+
+/// 視為削波的振幅
+const CLIP_LEVEL: f32 = 0.999;
+/// 視為數位靜音的振幅 (低於 16-bit 的 1 LSB)
+const ZERO_LEVEL: f32 = 1.0 / 65536.0;
+/// 數位靜音最短長度 (秒)
+const SILENCE_MIN_SECONDS: f64 = 2.0;
+/// 斷訊最短長度 (秒)，更短的 0 可能只是波形過零
+const DROPOUT_MIN_SECONDS: f64 = 0.01;
+/// 間隔小於此值的削波合併為同一段 (秒)
+const CLIP_MERGE_SECONDS: f64 = 0.05;
+/// 各類時段最多回報的筆數
+const MAX_RANGES: usize = 200;
+
+/// 削波比例超過此值即提出警告 / 判定為無法使用
+const CLIP_WARN_RATIO: f64 = 0.001;
+const CLIP_SEVERE_RATIO: f64 = 0.01;
+/// 數位靜音佔全長比例超過此值判定為無法使用
+const SILENCE_SEVERE_RATIO: f64 = 0.9;
+/// 斷訊次數超過此值判定為無法使用
+const DROPOUT_SEVERE_COUNT: usize = 20;
+/// 直流偏移超過此值即提出警告
+const DC_WARN_LEVEL: f64 = 0.02;
+/// 損毀封包比例超過此值判定為無法使用
+const CORRUPT_SEVERE_RATIO: f64 = 0.01;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityIssueKind {
+    Clipping,
+    DigitalSilence,
+    Dropouts,
+    DcOffset,
+    Corrupted,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QualityIssue {
+    pub kind: QualityIssueKind,
+    /// 嚴重到錄音無法使用
+    pub severe: bool,
+    pub message: String,
+}
+
+/// 時段 (秒)
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct TimeRange {
+    pub start: f64,
+    pub end: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QualityReport {
+    pub path: String,
+    pub duration: f64,
+    pub sample_rate: u32,
+    pub channels: u16,
+    /// 最大振幅 (0~1)
+    pub peak: f32,
+    pub clipped_samples: u64,
+    pub clipped_ratio: f64,
+    pub clipped_ranges: Vec<TimeRange>,
+    pub silences: Vec<TimeRange>,
+    pub dropouts: Vec<TimeRange>,
+    /// 各聲道的直流偏移
+    pub dc_offset: Vec<f64>,
+    pub decode_errors: usize,
+    pub issues: Vec<QualityIssue>,
+    /// 沒有嚴重問題
+    pub usable: bool,
+}
+
+/// 逐樣本累計的狀態
+#[derive(Default)]
+struct Analyzer {
+    sample_rate: f64,
+    frames: u64,
+    samples: u64,
+    peak: f32,
+    sums: Vec<f64>,
+    clipped: u64,
+    clip_ranges: Vec<TimeRange>,
+    /// 目前連續數位靜音的起點 (frame)
+    zero_start: Option<u64>,
+    silences: Vec<TimeRange>,
+    dropouts: Vec<TimeRange>,
+    dropout_count: usize,
+}
+
+impl Analyzer {
+    fn push(&mut self, block: &[f32], channels: u16) {
+        let channels = channels.max(1) as usize;
+        if self.sums.len() != channels {
+            self.sums.resize(channels, 0.0);
+        }
+        for frame in block.chunks_exact(channels) {
+            let mut silent = true;
+            let mut clipped = false;
+            for (ch, &sample) in frame.iter().enumerate() {
+                let level = sample.abs();
+                self.peak = self.peak.max(level);
+                self.sums[ch] += sample as f64;
+                if level >= CLIP_LEVEL {
+                    self.clipped += 1;
+                    clipped = true;
+                }
+                if level > ZERO_LEVEL {
+                    silent = false;
+                }
+            }
+            if clipped {
+                self.mark_clip();
+            }
+            if silent {
+                self.zero_start.get_or_insert(self.frames);
+            } else if let Some(start) = self.zero_start.take() {
+                self.close_zero_run(start, false);
+            }
+            self.frames += 1;
+            self.samples += channels as u64;
+        }
+    }
+
+    fn time(&self, frame: u64) -> f64 {
+        frame as f64 / self.sample_rate
+    }
+
+    fn mark_clip(&mut self) {
+        let now = self.time(self.frames);
+        let end = now + 1.0 / self.sample_rate;
+        match self.clip_ranges.last_mut() {
+            Some(last) if now - last.end <= CLIP_MERGE_SECONDS => last.end = end,
+            _ if self.clip_ranges.len() < MAX_RANGES => {
+                self.clip_ranges.push(TimeRange { start: now, end })
+            }
+            _ => {}
+        }
+    }
+
+    /// 一段數位靜音結束：夠長的是靜音，夾在聲音中間的短 0 是斷訊
+    fn close_zero_run(&mut self, start: u64, at_end: bool) {
+        let range = TimeRange {
+            start: self.time(start),
+            end: self.time(self.frames),
+        };
+        let length = range.end - range.start;
+        if length >= SILENCE_MIN_SECONDS {
+            if self.silences.len() < MAX_RANGES {
+                self.silences.push(range);
+            }
+        } else if length >= DROPOUT_MIN_SECONDS && start > 0 && !at_end {
+            self.dropout_count += 1;
+            if self.dropouts.len() < MAX_RANGES {
+                self.dropouts.push(range);
+            }
+        }
+    }
+
+    fn finish(mut self, path: &str, info: probe::DecodeInfo) -> QualityReport {
+        self.sample_rate = info.sample_rate as f64;
+        if let Some(start) = self.zero_start.take() {
+            self.close_zero_run(start, true);
+        }
+        let duration = self.time(self.frames);
+        let clipped_ratio = if self.samples > 0 {
+            self.clipped as f64 / self.samples as f64
+        } else {
+            0.0
+        };
+        let dc_offset: Vec<f64> = self
+            .sums
+            .iter()
+            .map(|sum| {
+                if self.frames > 0 {
+                    sum / self.frames as f64
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        let mut issues = Vec::new();
+        if clipped_ratio >= CLIP_WARN_RATIO {
+            issues.push(QualityIssue {
+                kind: QualityIssueKind::Clipping,
+                severe: clipped_ratio >= CLIP_SEVERE_RATIO,
+                message: crate::tr!(
+                    "quality.clipping",
+                    percent = format!("{:.2}", clipped_ratio * 100.0)
+                ),
+            });
+        }
+        let silent: f64 = self.silences.iter().map(|r| r.end - r.start).sum();
+        if !self.silences.is_empty() {
+            issues.push(QualityIssue {
+                kind: QualityIssueKind::DigitalSilence,
+                severe: duration <= 0.0 || silent / duration >= SILENCE_SEVERE_RATIO,
+                message: crate::tr!("quality.digital_silence", seconds = silent.round()),
+            });
+        }
+        if self.dropout_count > 0 {
+            issues.push(QualityIssue {
+                kind: QualityIssueKind::Dropouts,
+                severe: self.dropout_count > DROPOUT_SEVERE_COUNT,
+                message: crate::tr!("quality.dropouts", count = self.dropout_count),
+            });
+        }
+        if let Some(max_dc) = dc_offset
+            .iter()
+            .map(|dc| dc.abs())
+            .max_by(|a, b| a.total_cmp(b))
+            .filter(|dc| *dc >= DC_WARN_LEVEL)
+        {
+            issues.push(QualityIssue {
+                kind: QualityIssueKind::DcOffset,
+                severe: false,
+                message: crate::tr!("quality.dc_offset", level = format!("{:.3}", max_dc)),
+            });
+        }
+        if info.decode_errors > 0 || self.frames == 0 {
+            issues.push(QualityIssue {
+                kind: QualityIssueKind::Corrupted,
+                severe: info.packets == 0
+                    || info.decode_errors as f64 / info.packets as f64 >= CORRUPT_SEVERE_RATIO,
+                message: crate::tr!("quality.corrupted", count = info.decode_errors),
+            });
+        }
+
+        QualityReport {
+            path: path.to_string(),
+            duration,
+            sample_rate: info.sample_rate,
+            channels: info.channels,
+            peak: self.peak,
+            clipped_samples: self.clipped,
+            clipped_ratio,
+            clipped_ranges: self.clip_ranges,
+            silences: self.silences,
+            dropouts: self.dropouts,
+            dc_offset,
+            decode_errors: info.decode_errors,
+            usable: !issues.iter().any(|i| i.severe),
+            issues,
+        }
+    }
+}
+
+/// 解碼整個音檔並產生品質報告 (長檔案需數秒，請在背景執行緒呼叫)
+pub fn analyze(path: &str) -> Result<QualityReport, String> {
+    let mut analyzer = Analyzer::default();
+    let info = probe::decode_samples(path, |block, info| {
+        analyzer.sample_rate = info.sample_rate as f64;
+        analyzer.push(block, info.channels)
+    })?;
+    Ok(analyzer.finish(path, info))
+}
@@ -0,0 +1,1041 @@
+// src-tauri/src/services/recorder.rs
+//
+// 麥克風錄音：直接在 App 內錄下診間對話，存到專案的 01_converted。
+//
+// - Recording Thread: cpal 輸入串流 (cpal::Stream 不是 Send，與播放器相同放在獨立執行緒)
+//   收到的樣本在同一執行緒寫入 WAV，並定時發出音量事件給前端顯示
+// - 可同時擷取系統音訊 (視訊看診時對方的聲音)，混入同一檔案或另存 *-system.wav
+// - 語音偵測 (VAD): 只寫入有聲音的片段，略過的靜音位置記錄在 *-markers.json，
+//   適合整天的病房錄音 (另存的系統音訊檔不受影響，仍完整錄製)
+// - 自動增益 (AGC) 與限幅器：遠處的說話者拉高音量、近距離咳嗽不削波，
+//   可另存未處理的原始錄音 *-raw.wav (見 agc.rs)
+// - 選擇 MP3 時，停止錄音後再以 FFmpeg 轉檔 (stop_and_save)
+// - 可設定最長錄音時間，到時由排程執行緒自動停止並存檔 (見 recording_schedule.rs)
+
+use crate::models::AppError;
+use crate::services::agc::{Agc, AgcOptions};
+use crate::services::file_manager::{write_atomic, ProjectPaths};
+use crate::services::history;
+use crate::services::loopback::{self, Resampler, SystemAudioMode};
+use crate::services::manifest::ProjectManifest;
+use crate::services::settings;
+use crate::services::sidecar::Ffmpeg;
+use crate::services::volume;
+use crate::services::Converter;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+/// 錄音中的音量 (約每 100ms 一次)
+pub const LEVEL_EVENT: &str = "recorder://level";
+
+/// 排程開始錄音 (payload: RecordingStatus)
+pub const STARTED_EVENT: &str = "recorder://started";
+
+/// 到達最長時間自動停止並存檔 (payload: RecordingResult)
+pub const STOPPED_EVENT: &str = "recorder://stopped";
+
+/// 音量事件間隔
+const LEVEL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// 定時更新 WAV 檔頭，程式當掉時已錄的內容仍可播放
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// 錄音檔最多保留的聲道數
+const MAX_CHANNELS: u16 = 2;
+
+/// 語音偵測的判斷單位
+const VAD_BLOCK: Duration = Duration::from_millis(20);
+
+/// 偵測到語音時一併寫入之前的片段，避免切掉句首
+const VAD_PREROLL: Duration = Duration::from_millis(300);
+
+pub type RecorderState = Mutex<Option<Recording>>;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordingFormat {
+    #[default]
+    Wav,
+    Mp3,
+}
+
+/// 語音偵測 (能量門檻) 設定
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct VadOptions {
+    /// RMS 門檻 (0.0 ~ 1.0)，低於此值視為靜音
+    #[serde(default = "default_vad_threshold")]
+    pub threshold: f32,
+    /// 音量低於門檻後繼續錄製的時間 (毫秒)，避免句中停頓被切掉
+    #[serde(default = "default_vad_hangover_ms")]
+    pub hangover_ms: u64,
+}
+
+fn default_vad_threshold() -> f32 {
+    0.02
+}
+
+fn default_vad_hangover_ms() -> u64 {
+    1500
+}
+
+impl Default for VadOptions {
+    fn default() -> Self {
+        Self {
+            threshold: default_vad_threshold(),
+            hangover_ms: default_vad_hangover_ms(),
+        }
+    }
+}
+
+/// 略過靜音後恢復錄音的位置
+#[derive(Debug, Clone, Serialize)]
+pub struct VadMarker {
+    /// 在錄音檔中的位置 (秒)
+    pub offset: f64,
+    /// 恢復錄音的實際時間
+    pub time: String,
+    /// 略過的靜音長度 (秒)
+    pub skipped: f64,
+}
+
+/// 開始錄音的選項 (命令與排程共用)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingOptions {
+    /// None 時使用系統預設裝置
+    pub device: Option<String>,
+    pub format: RecordingFormat,
+    pub system_audio: SystemAudioMode,
+    /// 指定時只寫入偵測到語音的片段
+    pub vad: Option<VadOptions>,
+    /// 指定時以自動增益與限幅器處理錄音
+    pub agc: Option<AgcOptions>,
+    /// 最長錄音時間 (秒)，到時自動停止
+    pub max_duration: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InputDevice {
+    pub name: String,
+    pub is_default: bool,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelEvent {
+    /// 0.0 ~ 1.0
+    pub rms: f32,
+    pub peak: f32,
+    /// 已錄下的秒數 (不含暫停)
+    pub elapsed: f64,
+    pub paused: bool,
+    /// 語音偵測開啟時，目前是否正在寫入
+    pub voice: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingStatus {
+    pub recording: bool,
+    pub paused: bool,
+    pub elapsed: f64,
+    pub device: Option<String>,
+    pub path: Option<String>,
+    pub format: RecordingFormat,
+    pub system_audio: SystemAudioMode,
+    pub vad: Option<VadOptions>,
+    pub agc: Option<AgcOptions>,
+    /// 距離自動停止的秒數
+    pub remaining: Option<f64>,
+}
+
+impl RecordingStatus {
+    pub fn idle() -> Self {
+        Self {
+            recording: false,
+            paused: false,
+            elapsed: 0.0,
+            device: None,
+            path: None,
+            format: RecordingFormat::default(),
+            system_audio: SystemAudioMode::default(),
+            vad: None,
+            agc: None,
+            remaining: None,
+        }
+    }
+}
+
+/// 停止錄音後的結果
+#[derive(Debug, Clone, Serialize)]
+pub struct RecordingResult {
+    pub path: String,
+    /// 系統音訊另存時的檔案
+    pub system_path: Option<String>,
+    /// 語音偵測的靜音位置記錄
+    pub markers_path: Option<String>,
+    /// 自動增益前的原始錄音
+    pub raw_path: Option<String>,
+    pub duration: f64,
+    pub format: RecordingFormat,
+}
+
+/// 錄音執行緒完成後的檔案 (MP3 轉檔前)
+pub struct FinishedRecording {
+    pub wav_path: PathBuf,
+    pub system_path: Option<PathBuf>,
+    pub markers_path: Option<PathBuf>,
+    pub raw_path: Option<PathBuf>,
+    pub duration: f64,
+}
+
+/// 錄音執行緒與命令之間共用的狀態
+struct Shared {
+    paused: AtomicBool,
+    stop: AtomicBool,
+    frames_written: AtomicU64,
+}
+
+/// 錄音已寫入的長度，供其他執行緒讀取 (口述註記的同步)
+#[derive(Clone)]
+pub struct RecordingClock {
+    shared: Arc<Shared>,
+    sample_rate: u32,
+}
+
+impl RecordingClock {
+    pub fn elapsed(&self) -> f64 {
+        self.shared.frames_written.load(Ordering::Relaxed) as f64 / self.sample_rate as f64
+    }
+}
+
+/// 進行中的錄音 (只保存 Send + Sync 的資料，串流在錄音執行緒內)
+pub struct Recording {
+    shared: Arc<Shared>,
+    handle: Option<JoinHandle<Result<(), String>>>,
+    wav_path: PathBuf,
+    options: RecordingOptions,
+    /// 自動停止的時間 (以實際經過時間計算，含暫停)
+    stop_at: Option<Instant>,
+    device_name: String,
+    sample_rate: u32,
+    project_root: PathBuf,
+}
+
+/// 列出可用的錄音裝置
+pub fn list_input_devices() -> Result<Vec<InputDevice>, String> {
+    let host = cpal::default_host();
+    let default_name = host.default_input_device().and_then(|d| d.name().ok());
+    let devices = host
+        .input_devices()
+        .map_err(|e| format!("無法列出錄音裝置: {}", e))?;
+
+    Ok(devices
+        .filter_map(|device| {
+            let name = device.name().ok()?;
+            let config = device.default_input_config().ok()?;
+            Some(InputDevice {
+                is_default: default_name.as_deref() == Some(name.as_str()),
+                name,
+                sample_rate: config.sample_rate().0,
+                channels: config.channels(),
+            })
+        })
+        .collect())
+}
+
+fn find_input_device(name: Option<&str>) -> Result<cpal::Device, String> {
+    let host = cpal::default_host();
+    match name {
+        Some(name) => host
+            .input_devices()
+            .map_err(|e| format!("無法列出錄音裝置: {}", e))?
+            .find(|d| d.name().map(|n| n == name).unwrap_or(false))
+            .ok_or_else(|| format!("找不到錄音裝置: {}", name)),
+        None => host
+            .default_input_device()
+            .ok_or_else(|| "找不到錄音裝置".to_string()),
+    }
+}
+
+/// 新錄音檔的路徑: <output_dir>/recording-<時間>.wav
+pub fn recording_path(output_dir: &Path) -> PathBuf {
+    output_dir.join(format!(
+        "recording-{}.wav",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    ))
+}
+
+/// 系統音訊另存的路徑: recording-<時間>-system.wav
+fn system_path(wav_path: &Path) -> PathBuf {
+    let stem = wav_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    wav_path.with_file_name(format!("{}-system.wav", stem))
+}
+
+/// 原始錄音的路徑: recording-<時間>-raw.wav
+fn raw_path(wav_path: &Path) -> PathBuf {
+    let stem = wav_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    wav_path.with_file_name(format!("{}-raw.wav", stem))
+}
+
+/// 語音偵測記錄的路徑: recording-<時間>-markers.json
+fn markers_path(wav_path: &Path) -> PathBuf {
+    let stem = wav_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    wav_path.with_file_name(format!("{}-markers.json", stem))
+}
+
+impl Recording {
+    /// 開始錄音，寫入 wav_path；裝置無法開啟時回傳錯誤
+    pub fn start(
+        app: AppHandle,
+        options: RecordingOptions,
+        wav_path: PathBuf,
+        project_root: PathBuf,
+    ) -> Result<Self, String> {
+        let device = find_input_device(options.device.as_deref())?;
+        let device_name = device.name().unwrap_or_else(|_| "unknown".to_string());
+        let config = device
+            .default_input_config()
+            .map_err(|e| format!("無法取得錄音裝置設定: {}", e))?;
+        let sample_rate = config.sample_rate().0;
+
+        if let Some(parent) = wav_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("無法建立錄音資料夾: {}", e))?;
+        }
+
+        let shared = Arc::new(Shared {
+            paused: AtomicBool::new(false),
+            stop: AtomicBool::new(false),
+            frames_written: AtomicU64::new(0),
+        });
+
+        // 等待錄音執行緒開啟裝置，失敗時直接回報
+        let (ready_tx, ready_rx) = mpsc::channel::<Result<(), String>>();
+        let thread_shared = Arc::clone(&shared);
+        let thread_path = wav_path.clone();
+        let thread_options = options.clone();
+        let handle = thread::Builder::new()
+            .name("recorder".to_string())
+            .spawn(move || {
+                run_recording_loop(
+                    app,
+                    device,
+                    config,
+                    thread_options,
+                    thread_path,
+                    thread_shared,
+                    ready_tx,
+                )
+            })
+            .map_err(|e| format!("無法啟動錄音執行緒: {}", e))?;
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => {}
+            Ok(Err(e)) => {
+                let _ = handle.join();
+                return Err(e);
+            }
+            Err(_) => {
+                let _ = handle.join();
+                return Err("錄音執行緒意外結束".to_string());
+            }
+        }
+
+        tracing::info!("開始錄音: {} -> {}", device_name, wav_path.display());
+        Ok(Self {
+            shared,
+            handle: Some(handle),
+            wav_path,
+            stop_at: options
+                .max_duration
+                .map(|secs| Instant::now() + Duration::from_secs(secs)),
+            options,
+            device_name,
+            sample_rate,
+            project_root,
+        })
+    }
+
+    pub fn pause(&self) {
+        self.shared.paused.store(true, Ordering::SeqCst);
+    }
+
+    pub fn resume(&self) {
+        self.shared.paused.store(false, Ordering::SeqCst);
+    }
+
+    pub fn elapsed(&self) -> f64 {
+        self.clock().elapsed()
+    }
+
+    pub fn clock(&self) -> RecordingClock {
+        RecordingClock {
+            shared: Arc::clone(&self.shared),
+            sample_rate: self.sample_rate,
+        }
+    }
+
+    pub fn project_root(&self) -> &Path {
+        &self.project_root
+    }
+
+    pub fn format(&self) -> RecordingFormat {
+        self.options.format
+    }
+
+    /// 已到達最長錄音時間
+    pub fn auto_stop_due(&self) -> bool {
+        self.stop_at.is_some_and(|at| Instant::now() >= at)
+    }
+
+    pub fn status(&self) -> RecordingStatus {
+        RecordingStatus {
+            recording: true,
+            paused: self.shared.paused.load(Ordering::SeqCst),
+            elapsed: self.elapsed(),
+            device: Some(self.device_name.clone()),
+            path: Some(self.wav_path.to_string_lossy().to_string()),
+            format: self.options.format,
+            system_audio: self.options.system_audio,
+            vad: self.options.vad,
+            agc: self.options.agc,
+            remaining: self
+                .stop_at
+                .map(|at| at.saturating_duration_since(Instant::now()).as_secs_f64()),
+        }
+    }
+
+    /// 停止錄音並完成 WAV 檔
+    pub fn finish(mut self) -> Result<FinishedRecording, String> {
+        self.shared.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            handle
+                .join()
+                .map_err(|_| "錄音執行緒異常結束".to_string())??;
+        }
+        let duration = self.elapsed();
+        tracing::info!("錄音結束: {} ({:.1}s)", self.wav_path.display(), duration);
+        let system_path = (self.options.system_audio == SystemAudioMode::Separate)
+            .then(|| system_path(&self.wav_path))
+            .filter(|p| p.exists());
+        let markers_path = self
+            .options
+            .vad
+            .map(|_| markers_path(&self.wav_path))
+            .filter(|p| p.exists());
+        let raw_path = self
+            .options
+            .agc
+            .filter(|agc| agc.keep_raw)
+            .map(|_| raw_path(&self.wav_path))
+            .filter(|p| p.exists());
+        Ok(FinishedRecording {
+            wav_path: self.wav_path.clone(),
+            system_path,
+            markers_path,
+            raw_path,
+            duration,
+        })
+    }
+}
+
+impl Drop for Recording {
+    fn drop(&mut self) {
+        // 未呼叫 finish 就被丟棄時 (例如關閉程式)，仍讓執行緒完成 WAV 檔
+        self.shared.stop.store(true, Ordering::SeqCst);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// 在專案的 01_converted 開始新錄音 (命令與排程共用)
+pub fn start_in_project(
+    app: AppHandle,
+    project_root: &Path,
+    options: RecordingOptions,
+) -> Result<Recording, AppError> {
+    let paths = ProjectPaths::from_existing_root(project_root.to_path_buf());
+    volume::ensure_writable(&paths.converted)?;
+    Recording::start(
+        app,
+        options,
+        recording_path(&paths.converted),
+        paths.root.clone(),
+    )
+    .map_err(AppError::tool)
+}
+
+/// 停止錄音並存檔：MP3 格式時以 FFmpeg 轉檔，記錄到專案描述檔與操作紀錄
+pub async fn stop_and_save(
+    app: &AppHandle,
+    recording: Recording,
+) -> Result<RecordingResult, AppError> {
+    let started = Instant::now();
+    let root = recording.project_root().to_path_buf();
+    let format = recording.format();
+
+    let FinishedRecording {
+        wav_path,
+        system_path: system_wav,
+        markers_path,
+        raw_path: raw_wav,
+        duration,
+    } = tauri::async_runtime::spawn_blocking(move || recording.finish())
+        .await
+        .map_err(|e| AppError::internal(e.to_string()))?
+        .map_err(AppError::io)?;
+
+    let path = finalize_file(app, &root, wav_path, format).await?;
+    let system_path = match system_wav {
+        Some(wav) => Some(finalize_file(app, &root, wav, format).await?),
+        None => None,
+    };
+    // 原始錄音保留 WAV，不做有損轉檔
+    let raw_path = match raw_wav {
+        Some(wav) => Some(finalize_file(app, &root, wav, RecordingFormat::Wav).await?),
+        None => None,
+    };
+
+    let result = RecordingResult {
+        path: path.to_string_lossy().to_string(),
+        system_path: system_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
+        markers_path: markers_path
+            .as_ref()
+            .map(|p| p.to_string_lossy().to_string()),
+        raw_path: raw_path.as_ref().map(|p| p.to_string_lossy().to_string()),
+        duration,
+        format,
+    };
+    let outputs: Vec<PathBuf> = std::iter::once(path)
+        .chain(system_path)
+        .chain(markers_path)
+        .chain(raw_path)
+        .collect();
+    history::record_command(
+        &root,
+        "stop_recording",
+        serde_json::json!({ "format": format, "duration": duration }),
+        &outputs,
+        started,
+        &Ok::<(), AppError>(()),
+    );
+    Ok(result)
+}
+
+/// 依格式完成錄音檔，並記錄到專案描述檔
+async fn finalize_file(
+    app: &AppHandle,
+    root: &Path,
+    wav_path: PathBuf,
+    format: RecordingFormat,
+) -> Result<PathBuf, AppError> {
+    let path = encode_recording(app, wav_path, format).await?;
+
+    // 與轉檔相同記錄到專案描述檔，供完整性檢查使用
+    let record_result = ProjectManifest::update(root, |m| m.record_conversion("recording", &path));
+    if let Err(e) = record_result {
+        tracing::warn!("無法更新專案描述檔: {}", e);
+    }
+    Ok(path)
+}
+
+/// 依格式完成錄音檔 (MP3 時以 FFmpeg 轉檔後刪除暫存的 WAV)
+pub async fn encode_recording(
+    app: &AppHandle,
+    wav_path: PathBuf,
+    format: RecordingFormat,
+) -> Result<PathBuf, AppError> {
+    Ok(match format {
+        RecordingFormat::Wav => wav_path,
+        RecordingFormat::Mp3 => {
+            let output_dir = wav_path
+                .parent()
+                .map(|p| p.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let mp3 = Converter::new()
+                .with_encoding(settings::load().converted_encoding())
+                .convert_to_mp3(&Ffmpeg::from(app), &wav_path.to_string_lossy(), &output_dir)
+                .await
+                // 轉檔失敗時保留 WAV，錄音內容不會遺失
+                .map_err(|e| AppError::tool(e).with_detail(wav_path.display().to_string()))?;
+            let _ = std::fs::remove_file(&wav_path);
+            PathBuf::from(mp3)
+        }
+    })
+}
+
+/// 錄音迴圈：開啟輸入串流，把樣本寫入 WAV 直到收到停止訊號
+fn run_recording_loop(
+    app: AppHandle,
+    device: cpal::Device,
+    config: cpal::SupportedStreamConfig,
+    options: RecordingOptions,
+    wav_path: PathBuf,
+    shared: Arc<Shared>,
+    ready: mpsc::Sender<Result<(), String>>,
+) -> Result<(), String> {
+    let RecordingOptions {
+        system_audio,
+        vad,
+        agc,
+        ..
+    } = options;
+    let input_channels = config.channels();
+    let channels = input_channels.min(MAX_CHANNELS);
+    let sample_rate = config.sample_rate().0;
+    let spec = wav_spec(channels, sample_rate);
+
+    // 啟動失敗時通知 start() 並結束執行緒
+    let fail = |e: String| {
+        let _ = ready.send(Err(e.clone()));
+        Err(e)
+    };
+
+    let (tx, rx) = mpsc::channel::<Vec<f32>>();
+    let stream = match build_input_stream(&device, &config, tx) {
+        Ok(stream) => stream,
+        Err(e) => return fail(e),
+    };
+    let mut writer = match hound::WavWriter::create(&wav_path, spec) {
+        Ok(writer) => writer,
+        Err(e) => return fail(format!("無法建立錄音檔: {}", e)),
+    };
+
+    // 系統音訊 (cpal::Stream 不是 Send，與麥克風一樣在此執行緒建立)
+    let (system_tx, system_rx) = mpsc::channel::<Vec<f32>>();
+    let system = match system_audio {
+        SystemAudioMode::Off => None,
+        _ => match loopback::open(system_tx) {
+            Ok(capture) => Some(capture),
+            Err(e) => return fail(e),
+        },
+    };
+    let mut system_writer = match (&system, system_audio) {
+        (Some(capture), SystemAudioMode::Separate) => {
+            let spec = wav_spec(capture.channels.min(MAX_CHANNELS), capture.sample_rate);
+            match hound::WavWriter::create(system_path(&wav_path), spec) {
+                Ok(writer) => Some(writer),
+                Err(e) => return fail(format!("無法建立錄音檔: {}", e)),
+            }
+        }
+        _ => None,
+    };
+    let mut resampler = system
+        .as_ref()
+        .map(|c| Resampler::new(c.sample_rate, sample_rate, c.channels));
+    let mut system_queue: VecDeque<f32> = VecDeque::new();
+    let mut gate = vad.map(|options| VoiceGate::new(options, channels as usize, sample_rate));
+    let mut agc_stage = agc.map(|options| Agc::new(options, channels as usize, sample_rate));
+    let mut raw_writer = match agc.filter(|options| options.keep_raw) {
+        Some(_) => match hound::WavWriter::create(raw_path(&wav_path), spec) {
+            Ok(writer) => Some(writer),
+            Err(e) => return fail(format!("無法建立錄音檔: {}", e)),
+        },
+        None => None,
+    };
+
+    if let Err(e) = stream.play() {
+        return fail(format!("無法開始錄音: {}", e));
+    }
+    let _ = ready.send(Ok(()));
+
+    let mut meter = LevelMeter::default();
+    let mut last_level = Instant::now();
+    let mut last_flush = Instant::now();
+    let mut result = Ok(());
+    let mut frame_buf: Vec<f32> = Vec::with_capacity(channels as usize);
+    // 本次要寫入的樣本 (經語音偵測過濾)
+    let mut pending: Vec<f32> = Vec::new();
+
+    while !shared.stop.load(Ordering::SeqCst) {
+        let chunk = match rx.recv_timeout(LEVEL_INTERVAL) {
+            Ok(chunk) => chunk,
+            Err(mpsc::RecvTimeoutError::Timeout) => Vec::new(),
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        };
+        let paused = shared.paused.load(Ordering::SeqCst);
+
+        // 系統音訊：另存時直接寫入，混音時轉成錄音取樣率後排隊等待麥克風樣本
+        while let Ok(system_chunk) = system_rx.try_recv() {
+            if let (Some(writer), Some(capture)) = (system_writer.as_mut(), system.as_ref()) {
+                if paused {
+                    continue;
+                }
+                let system_channels = capture.channels as usize;
+                let keep = system_channels.min(MAX_CHANNELS as usize);
+                for frame in system_chunk.chunks(system_channels) {
+                    for &sample in frame.iter().take(keep) {
+                        if let Err(e) = writer.write_sample(to_i16(sample)) {
+                            result = Err(format!("無法寫入錄音檔: {}", e));
+                        }
+                    }
+                }
+            } else if let Some(resampler) = resampler.as_mut() {
+                let mut mono = Vec::new();
+                resampler.process(&system_chunk, &mut mono);
+                system_queue.extend(mono);
+            }
+        }
+        // 兩個裝置的時脈不同步，佇列超過 1 秒時丟掉最舊的部分
+        if system_queue.len() > sample_rate as usize {
+            let excess = system_queue.len() - sample_rate as usize;
+            system_queue.drain(..excess);
+        }
+
+        for frame in chunk.chunks(input_channels as usize) {
+            frame_buf.clear();
+            frame_buf.extend(frame.iter().take(channels as usize));
+            if system_audio == SystemAudioMode::Mix {
+                let system_sample = system_queue.pop_front().unwrap_or(0.0);
+                for sample in frame_buf.iter_mut() {
+                    *sample += system_sample;
+                }
+            }
+            meter.add(&frame_buf);
+            if paused {
+                continue;
+            }
+            match gate.as_mut() {
+                Some(gate) => gate.push(&frame_buf, &mut pending),
+                None => pending.extend_from_slice(&frame_buf),
+            }
+        }
+        if let Err(e) = write_processed(
+            &mut writer,
+            raw_writer.as_mut(),
+            agc_stage.as_mut(),
+            &mut pending,
+            channels,
+            &shared,
+        ) {
+            result = Err(e);
+        }
+        if result.is_err() {
+            break;
+        }
+
+        if last_level.elapsed() >= LEVEL_INTERVAL {
+            let (rms, peak) = meter.take();
+            let _ = app.emit(
+                LEVEL_EVENT,
+                LevelEvent {
+                    rms,
+                    peak,
+                    elapsed: shared.frames_written.load(Ordering::Relaxed) as f64
+                        / sample_rate as f64,
+                    paused,
+                    voice: gate.as_ref().map(VoiceGate::is_open).unwrap_or(true),
+                },
+            );
+            last_level = Instant::now();
+        }
+        if last_flush.elapsed() >= FLUSH_INTERVAL {
+            let _ = writer.flush();
+            if let Some(system_writer) = system_writer.as_mut() {
+                let _ = system_writer.flush();
+            }
+            if let Some(raw_writer) = raw_writer.as_mut() {
+                let _ = raw_writer.flush();
+            }
+            last_flush = Instant::now();
+        }
+    }
+
+    drop(stream);
+    drop(system);
+    if let Some(gate) = gate.as_mut() {
+        gate.flush(&mut pending);
+        if let Err(e) = write_processed(
+            &mut writer,
+            raw_writer.as_mut(),
+            agc_stage.as_mut(),
+            &mut pending,
+            channels,
+            &shared,
+        ) {
+            result = Err(e);
+        }
+        if let Err(e) = gate.save_markers(&markers_path(&wav_path)) {
+            tracing::warn!("無法寫入語音偵測記錄: {}", e);
+        }
+    }
+    if let Some(system_writer) = system_writer {
+        system_writer
+            .finalize()
+            .map_err(|e| format!("無法完成錄音檔: {}", e))?;
+    }
+    if let Some(raw_writer) = raw_writer {
+        raw_writer
+            .finalize()
+            .map_err(|e| format!("無法完成錄音檔: {}", e))?;
+    }
+    writer
+        .finalize()
+        .map_err(|e| format!("無法完成錄音檔: {}", e))?;
+    result
+}
+
+/// 寫入待寫樣本：先另存原始樣本，再經自動增益處理後寫入錄音檔
+/// (語音偵測以原始音量判斷，自動增益只處理實際寫入的片段)
+fn write_processed<W: std::io::Write + std::io::Seek>(
+    writer: &mut hound::WavWriter<W>,
+    raw_writer: Option<&mut hound::WavWriter<W>>,
+    agc: Option<&mut Agc>,
+    pending: &mut Vec<f32>,
+    channels: u16,
+    shared: &Shared,
+) -> Result<(), String> {
+    if let Some(raw_writer) = raw_writer {
+        for &sample in pending.iter() {
+            raw_writer
+                .write_sample(to_i16(sample))
+                .map_err(|e| format!("無法寫入錄音檔: {}", e))?;
+        }
+    }
+    if let Some(agc) = agc {
+        agc.process(pending);
+    }
+    write_pending(writer, pending, channels, shared)
+}
+
+/// 寫入待寫樣本並更新已錄製的長度
+fn write_pending<W: std::io::Write + std::io::Seek>(
+    writer: &mut hound::WavWriter<W>,
+    pending: &mut Vec<f32>,
+    channels: u16,
+    shared: &Shared,
+) -> Result<(), String> {
+    for &sample in pending.iter() {
+        writer
+            .write_sample(to_i16(sample))
+            .map_err(|e| format!("無法寫入錄音檔: {}", e))?;
+    }
+    shared.frames_written.fetch_add(
+        (pending.len() / channels as usize) as u64,
+        Ordering::Relaxed,
+    );
+    pending.clear();
+    Ok(())
+}
+
+fn wav_spec(channels: u16, sample_rate: u32) -> hound::WavSpec {
+    hound::WavSpec {
+        channels,
+        sample_rate,
+        bits_per_sample: 16,
+        sample_format: hound::SampleFormat::Int,
+    }
+}
+
+pub(crate) fn build_input_stream(
+    device: &cpal::Device,
+    config: &cpal::SupportedStreamConfig,
+    tx: mpsc::Sender<Vec<f32>>,
+) -> Result<cpal::Stream, String> {
+    let stream_config = config.config();
+    let on_error = |e: cpal::StreamError| tracing::error!("錄音串流錯誤: {}", e);
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                let _ = tx.send(data.to_vec());
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[i16], _: &cpal::InputCallbackInfo| {
+                let _ = tx.send(data.iter().map(|&s| s as f32 / i16::MAX as f32).collect());
+            },
+            on_error,
+            None,
+        ),
+        cpal::SampleFormat::U16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[u16], _: &cpal::InputCallbackInfo| {
+                let _ = tx.send(
+                    data.iter()
+                        .map(|&s| (s as f32 - 32768.0) / 32768.0)
+                        .collect(),
+                );
+            },
+            on_error,
+            None,
+        ),
+        other => return Err(format!("不支援的錄音格式: {:?}", other)),
+    };
+    stream.map_err(|e| format!("無法開啟錄音裝置: {}", e))
+}
+
+fn to_i16(sample: f32) -> i16 {
+    (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16
+}
+
+/// 累積一段時間內的音量
+#[derive(Default)]
+struct LevelMeter {
+    sum_squares: f64,
+    count: u64,
+    peak: f32,
+}
+
+impl LevelMeter {
+    fn add(&mut self, frame: &[f32]) {
+        for &sample in frame {
+            self.sum_squares += (sample as f64) * (sample as f64);
+            self.peak = self.peak.max(sample.abs());
+        }
+        self.count += frame.len() as u64;
+    }
+
+    /// 回傳 (rms, peak) 並重新開始累積
+    fn take(&mut self) -> (f32, f32) {
+        let rms = if self.count > 0 {
+            (self.sum_squares / self.count as f64).sqrt() as f32
+        } else {
+            0.0
+        };
+        let peak = self.peak;
+        *self = Self::default();
+        (rms.min(1.0), peak.min(1.0))
+    }
+}
+
+/// 能量門檻的語音偵測：以 20ms 為單位判斷，靜音超過 hangover 後停止寫入，
+/// 再次偵測到語音時記錄略過的長度
+struct VoiceGate {
+    options: VadOptions,
+    channels: usize,
+    sample_rate: u32,
+    block_frames: usize,
+    hangover_blocks: u32,
+    preroll_blocks: usize,
+    block: Vec<f32>,
+    /// 關閉期間最近的片段，開啟時一併寫入
+    preroll: VecDeque<Vec<f32>>,
+    open: bool,
+    hold: u32,
+    frames_output: u64,
+    frames_skipped: u64,
+    markers: Vec<VadMarker>,
+}
+
+impl VoiceGate {
+    fn new(options: VadOptions, channels: usize, sample_rate: u32) -> Self {
+        let block_frames = (sample_rate as u128 * VAD_BLOCK.as_millis() / 1000).max(1) as usize;
+        let block_ms = VAD_BLOCK.as_millis() as u64;
+        Self {
+            options,
+            channels,
+            sample_rate,
+            block_frames,
+            hangover_blocks: (options.hangover_ms / block_ms) as u32,
+            preroll_blocks: (VAD_PREROLL.as_millis() as u64 / block_ms) as usize,
+            block: Vec::with_capacity(block_frames * channels),
+            preroll: VecDeque::new(),
+            open: false,
+            hold: 0,
+            frames_output: 0,
+            frames_skipped: 0,
+            markers: Vec::new(),
+        }
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// 加入一個 frame；區塊滿時把要寫入的樣本放到 out
+    fn push(&mut self, frame: &[f32], out: &mut Vec<f32>) {
+        self.block.extend_from_slice(frame);
+        if self.block.len() < self.block_frames * self.channels {
+            return;
+        }
+        let block = std::mem::take(&mut self.block);
+        let rms = (block.iter().map(|s| s * s).sum::<f32>() / block.len() as f32).sqrt();
+
+        if rms >= self.options.threshold {
+            if !self.open {
+                self.reopen(out);
+            }
+            self.open = true;
+            self.hold = self.hangover_blocks;
+        } else if self.open {
+            if self.hold == 0 {
+                self.open = false;
+            } else {
+                self.hold -= 1;
+            }
+        }
+
+        if self.open {
+            self.frames_output += (block.len() / self.channels) as u64;
+            out.extend_from_slice(&block);
+        } else {
+            self.preroll.push_back(block);
+            if self.preroll.len() > self.preroll_blocks {
+                if let Some(dropped) = self.preroll.pop_front() {
+                    self.frames_skipped += (dropped.len() / self.channels) as u64;
+                }
+            }
+        }
+    }
+
+    /// 恢復寫入：先寫入 preroll，有略過靜音時加上記錄
+    fn reopen(&mut self, out: &mut Vec<f32>) {
+        if self.frames_skipped > 0 {
+            let preroll_frames: usize = self.preroll.iter().map(|b| b.len() / self.channels).sum();
+            let preroll_secs = preroll_frames as f64 / self.sample_rate as f64;
+            let time = chrono::Local::now()
+                - chrono::Duration::milliseconds((preroll_secs * 1000.0) as i64);
+            self.markers.push(VadMarker {
+                offset: self.frames_output as f64 / self.sample_rate as f64,
+                time: time.to_rfc3339(),
+                skipped: self.frames_skipped as f64 / self.sample_rate as f64,
+            });
+            self.frames_skipped = 0;
+        }
+        for block in self.preroll.drain(..) {
+            self.frames_output += (block.len() / self.channels) as u64;
+            out.extend_from_slice(&block);
+        }
+    }
+
+    /// 停止錄音時寫入尚未判斷完的區塊
+    fn flush(&mut self, out: &mut Vec<f32>) {
+        if self.open {
+            self.frames_output += (self.block.len() / self.channels) as u64;
+            out.append(&mut self.block);
+        }
+    }
+
+    fn save_markers(&self, path: &Path) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(&serde_json::json!({
+            "threshold": self.options.threshold,
+            "hangover_ms": self.options.hangover_ms,
+            "markers": self.markers,
+        }))
+        .map_err(|e| e.to_string())?;
+        write_atomic(path, content.as_bytes()).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,270 @@
+// src-tauri/src/services/recording_schedule.rs
+//
+// 預約錄音：在指定時間自動開始、經過指定時間後自動停止 (固定的門診時段)。
+// 預約保存在 app data 的 recording_schedule.json，時間到之前重新啟動程式仍會執行。
+// 背景執行緒每秒檢查一次，同時負責停止已到達最長時間的錄音 (包含手動開始的錄音)。
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::file_manager::write_atomic;
+use crate::services::recorder::{
+    self, RecorderState, Recording, RecordingOptions, STARTED_EVENT, STOPPED_EVENT,
+};
+use chrono::{DateTime, Local, NaiveDateTime};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const SCHEDULE_FILE_NAME: &str = "recording_schedule.json";
+
+/// 檢查間隔
+const TICK: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledRecording {
+    pub id: String,
+    /// 開始時間 (RFC 3339)
+    pub start_time: String,
+    /// 錄音秒數
+    pub duration: u64,
+    pub project: PathBuf,
+    #[serde(default)]
+    pub options: RecordingOptions,
+}
+
+impl ScheduledRecording {
+    fn start(&self) -> Option<DateTime<Local>> {
+        parse_start_time(&self.start_time)
+    }
+}
+
+pub struct RecordingSchedule {
+    path: Option<PathBuf>,
+    entries: Mutex<Vec<ScheduledRecording>>,
+}
+
+impl RecordingSchedule {
+    /// 讀取保存的預約，檔案不存在或格式錯誤時為空
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let entries = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            entries: Mutex::new(entries),
+        }
+    }
+
+    /// 依開始時間排序
+    pub fn list(&self) -> Vec<ScheduledRecording> {
+        let mut entries = self.entries.lock().map(|e| e.clone()).unwrap_or_default();
+        entries.sort_by_key(|e| e.start());
+        entries
+    }
+
+    pub fn add(
+        &self,
+        start_time: &str,
+        duration: u64,
+        project: PathBuf,
+        options: RecordingOptions,
+    ) -> Result<ScheduledRecording, AppError> {
+        let start = parse_start_time(start_time).ok_or_else(|| {
+            AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.schedule_invalid_time",
+                &[("time", start_time.to_string())],
+            )
+        })?;
+        if duration == 0 {
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.schedule_invalid_duration",
+                &[],
+            ));
+        }
+        if start + chrono::Duration::seconds(duration as i64) <= Local::now() {
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.schedule_in_past",
+                &[("time", start.format("%Y-%m-%d %H:%M").to_string())],
+            ));
+        }
+        if !project.is_dir() {
+            return Err(AppError::localized(
+                ErrorKind::NotFound,
+                "error.dir_not_found",
+                &[],
+            ));
+        }
+
+        let entry = ScheduledRecording {
+            id: format!("rec-{}", Local::now().timestamp_millis()),
+            start_time: start.to_rfc3339(),
+            duration,
+            project,
+            options,
+        };
+        let mut entries = self.entries.lock().map_err(|_| schedule_busy())?;
+        entries.push(entry.clone());
+        self.save(&entries);
+        tracing::info!("預約錄音: {} ({}s)", entry.start_time, duration);
+        Ok(entry)
+    }
+
+    pub fn cancel(&self, id: &str) -> Result<(), AppError> {
+        let mut entries = self.entries.lock().map_err(|_| schedule_busy())?;
+        let before = entries.len();
+        entries.retain(|e| e.id != id);
+        if entries.len() == before {
+            return Err(AppError::localized(
+                ErrorKind::NotFound,
+                "error.schedule_not_found",
+                &[("id", id.to_string())],
+            ));
+        }
+        self.save(&entries);
+        Ok(())
+    }
+
+    /// 取出已到開始時間的預約 (無法解析時間的一併移除)
+    fn take_due(&self, now: DateTime<Local>) -> Vec<ScheduledRecording> {
+        let Ok(mut entries) = self.entries.lock() else {
+            return Vec::new();
+        };
+        let (due, pending): (Vec<_>, Vec<_>) = entries
+            .drain(..)
+            .partition(|e| !matches!(e.start(), Some(start) if start > now));
+        *entries = pending;
+        if !due.is_empty() {
+            self.save(&entries);
+        }
+        due
+    }
+
+    fn save(&self, entries: &[ScheduledRecording]) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        match serde_json::to_string_pretty(entries) {
+            Ok(content) => {
+                if let Err(e) = write_atomic(path, content.as_bytes()) {
+                    tracing::warn!("無法保存預約錄音: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("無法序列化預約錄音: {}", e),
+        }
+    }
+}
+
+fn schedule_busy() -> AppError {
+    AppError::localized(ErrorKind::Internal, "error.recorder_busy", &[])
+}
+
+/// 接受 RFC 3339 或本地時間 (YYYY-MM-DDTHH:MM[:SS]，前端 datetime-local 的格式)
+fn parse_start_time(text: &str) -> Option<DateTime<Local>> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(text) {
+        return Some(time.with_timezone(&Local));
+    }
+    [
+        "%Y-%m-%dT%H:%M:%S",
+        "%Y-%m-%dT%H:%M",
+        "%Y-%m-%d %H:%M:%S",
+        "%Y-%m-%d %H:%M",
+    ]
+    .iter()
+    .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+    .and_then(|naive| naive.and_local_timezone(Local).earliest())
+}
+
+/// 啟動背景檢查執行緒
+pub fn start(app: AppHandle) {
+    let result = std::thread::Builder::new()
+        .name("recording-schedule".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(TICK);
+            auto_stop(&app);
+            if let Some(schedule) = app.try_state::<RecordingSchedule>() {
+                for entry in schedule.take_due(Local::now()) {
+                    start_scheduled(&app, entry);
+                }
+            }
+        });
+    if let Err(e) = result {
+        tracing::error!("無法啟動預約錄音執行緒: {}", e);
+    }
+}
+
+/// 到達最長錄音時間時停止並存檔
+fn auto_stop(app: &AppHandle) {
+    let Some(state) = app.try_state::<RecorderState>() else {
+        return;
+    };
+    let recording = {
+        let Ok(mut guard) = state.lock() else {
+            return;
+        };
+        if !guard.as_ref().is_some_and(Recording::auto_stop_due) {
+            return;
+        }
+        guard.take()
+    };
+    let Some(recording) = recording else {
+        return;
+    };
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match recorder::stop_and_save(&app, recording).await {
+            Ok(result) => {
+                tracing::info!("已到達錄音時間，自動停止: {}", result.path);
+                let _ = app.emit(STOPPED_EVENT, result);
+            }
+            Err(e) => tracing::error!("自動停止錄音失敗: {}", e),
+        }
+    });
+}
+
+/// 開始預約的錄音；已超過結束時間 (例如當時沒有開啟程式) 或正在錄音時略過
+fn start_scheduled(app: &AppHandle, entry: ScheduledRecording) {
+    let Some(start) = entry.start() else {
+        tracing::warn!("預約錄音時間無效，略過: {}", entry.start_time);
+        return;
+    };
+    let end = start + chrono::Duration::seconds(entry.duration as i64);
+    let remaining = (end - Local::now()).num_seconds();
+    if remaining <= 0 {
+        tracing::warn!("預約錄音已過結束時間，略過: {}", entry.start_time);
+        return;
+    }
+
+    let Some(state) = app.try_state::<RecorderState>() else {
+        return;
+    };
+    let Ok(mut guard) = state.lock() else {
+        return;
+    };
+    if guard.is_some() {
+        tracing::warn!("已經在錄音中，略過預約錄音: {}", entry.start_time);
+        return;
+    }
+
+    let mut options = entry.options;
+    options.max_duration = Some(remaining as u64);
+    match recorder::start_in_project(app.clone(), &entry.project, options) {
+        Ok(recording) => {
+            let status = recording.status();
+            *guard = Some(recording);
+            tracing::info!("開始預約錄音: {}", entry.project.display());
+            let _ = app.emit(STARTED_EVENT, status);
+        }
+        Err(e) => tracing::error!("無法開始預約錄音: {}", e),
+    }
+}
@@ -1,8 +1,195 @@
 // src-tauri/src/services/report.rs
 
+use crate::services::annotations::{self, Annotation};
+use crate::services::deid::DeidCheck;
+use crate::services::jobs::{CancelToken, CANCELLED_MESSAGE};
+use crate::services::report_index::{self, ChunkSpan, ParagraphLink};
+use crate::services::settings::{self, DocxConfig, HttpConfig, StageEncoding};
+use crate::services::sidecar::{self, Ffmpeg};
+use crate::services::{manifest, probe, transcript, upload_alias};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+/// 長檔分段時，切點往前後搜尋靜音的範圍 (秒)
+const SPLIT_SEARCH_SECONDS: f64 = 30.0;
+/// 視為靜音的音量 (dB) 與最短長度 (秒)
+const SPLIT_SILENCE_NOISE_DB: i32 = -35;
+const SPLIT_SILENCE_MIN_SECONDS: f64 = 0.3;
+
+const UPLOAD_URL: &str = "https://generativelanguage.googleapis.com/upload/v1beta/files";
+/// 每次上傳的分段大小 (Resumable Upload 要求為 256 KiB 的倍數)
+const UPLOAD_CHUNK_SIZE: u64 = 8 * 1024 * 1024;
+/// 網路中斷後最多連續重試的次數 (間隔 2、4、8... 秒)
+const UPLOAD_MAX_RETRIES: u32 = 5;
+
+/// 上傳進度事件
+pub const UPLOAD_PROGRESS_EVENT: &str = "report://upload-progress";
+
+/// 未指定模型時使用的 Gemini 模型
+pub const DEFAULT_MODEL: &str = "gemini-3.1-pro-preview";
+
+const FILES_URL: &str = "https://generativelanguage.googleapis.com/v1beta/files";
+/// 清理遠端檔案時，建立超過此時間的檔案視為失敗流程遺留 (未指定時)
+pub const DEFAULT_STALE_HOURS: f64 = 2.0;
+/// 雙語報告每次翻譯的最大字數 (依段落切開，避免超過模型輸出上限)
+const TRANSLATION_CHUNK_CHARS: usize = 6000;
+
+/// 解析 FFmpeg silencedetect 輸出的靜音區間 (silence_start / silence_end)
+fn parse_silences(stderr: &str) -> Vec<(f64, f64)> {
+    let value_after = |line: &str, key: &str| -> Option<f64> {
+        let rest = &line[line.find(key)? + key.len()..];
+        rest.split_whitespace().next()?.parse().ok()
+    };
+    let mut silences = Vec::new();
+    let mut start = None;
+    for line in stderr.lines() {
+        if let Some(value) = value_after(line, "silence_start:") {
+            start = Some(value);
+        } else if let Some(end) = value_after(line, "silence_end:") {
+            if let Some(start) = start.take() {
+                silences.push((start.max(0.0), end));
+            }
+        }
+    }
+    silences
+}
+
+/// 報告中處理失敗的音檔 (寫入 report.json 的 errors)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportFileError {
+    /// 檔名 (與報告中的個案來源相同)
+    pub file: String,
+    /// 音檔完整路徑，重新生成時使用
+    pub path: String,
+    pub error: String,
+    pub failed_at: String,
+}
+
+/// 報告中一個音檔的內容 (重新生成時，音檔與提示詞都沒變的段落直接沿用)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReportSection {
+    pub file: String,
+    /// 音檔內容的 SHA-256
+    pub audio_hash: String,
+    /// 模型與提示詞 (含標註) 的 SHA-256
+    pub prompt_hash: String,
+    pub text: String,
+    /// 雙語報告的英文翻譯
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub translation: Option<String>,
+    /// 內文各段對應的音檔時間範圍 (短檔只有一段)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub chunks: Vec<ChunkSpan>,
+}
+
+/// 沒有分段紀錄 (舊報告) 時，整個音檔視為一段；無法取得長度時為空
+fn whole_file_chunk(audio_path: &Path) -> Vec<ChunkSpan> {
+    probe::cached_duration(&audio_path.to_string_lossy())
+        .map(|duration| {
+            vec![ChunkSpan {
+                start: 0.0,
+                end: duration,
+                offset: 0,
+            }]
+        })
+        .unwrap_or_default()
+}
+
+/// 段落沿用的判斷依據：模型與實際送出的提示詞
+fn prompt_hash(model: &str, prompt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(model.as_bytes());
+    hasher.update([0]);
+    hasher.update(prompt.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+/// 代入提示詞的模板變數：{名稱} 換成對應的值，未定義的名稱保留原樣 (提示詞中可能有 JSON 範例)
+pub fn fill_template(template: &str, vars: &BTreeMap<String, String>) -> String {
+    vars.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// 與 report.md 同名的 report.json：記錄處理結果，供後續工具與重新生成失敗的檔案使用
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReportSummary {
+    pub generated_at: String,
+    pub model: String,
+    /// 音檔來源資料夾
+    #[serde(default)]
+    pub folder: String,
+    /// 成功處理的檔名 (依報告順序)
+    pub succeeded: Vec<String>,
+    #[serde(default)]
+    pub errors: Vec<ReportFileError>,
+    /// 雙語報告 (逐字稿後附英文翻譯)
+    #[serde(default)]
+    pub bilingual: bool,
+    /// 去識別化自我檢查的結果 (未檢查時為 None)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deid: Option<DeidCheck>,
+    /// 各音檔的報告內容與雜湊 (舊報告沒有時為空，全部重新生成)
+    #[serde(default)]
+    pub sections: Vec<ReportSection>,
+    /// 本次沿用上次結果、未重新送出的檔名
+    #[serde(default)]
+    pub reused: Vec<String>,
+    /// 報告段落與音檔時間的對應 (點段落播放用)
+    #[serde(default)]
+    pub paragraphs: Vec<ParagraphLink>,
+}
+
+/// 儲存報告的 report.json
+pub fn save_summary(report_path: &Path, summary: &ReportSummary) -> Result<(), String> {
+    let content =
+        serde_json::to_string_pretty(summary).map_err(|e| format!("儲存報告失敗: {}", e))?;
+    fs::write(summary_path(report_path), content).map_err(|e| format!("儲存報告失敗: {}", e))
+}
+
+/// report.md 對應的 report.json 路徑
+pub fn summary_path(report_path: &Path) -> std::path::PathBuf {
+    report_path.with_extension("json")
+}
+
+/// 讀取報告的 report.json (舊報告沒有時為 None)
+pub fn load_summary(report_path: &Path) -> Option<ReportSummary> {
+    read_summary(&summary_path(report_path))
+}
+
+/// 讀取指定路徑的 report.json (加密專案解密後的暫存檔)
+pub fn read_summary(path: &Path) -> Option<ReportSummary> {
+    let content = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+/// 報告附錄的錯誤表格 (表格內不能有換行與未跳脫的 |)
+fn errors_appendix(errors: &[ReportFileError]) -> String {
+    let cell = |text: &str| {
+        text.replace('|', "\\|")
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+    let mut appendix = String::from("## 附錄：處理錯誤\n\n| # | 檔案 | 錯誤 |\n|---|---|---|\n");
+    for (idx, error) in errors.iter().enumerate() {
+        appendix.push_str(&format!(
+            "| {} | {} | {} |\n",
+            idx + 1,
+            cell(&error.file),
+            cell(&error.error)
+        ));
+    }
+    appendix
+}
 
 // Gemini File API 回應結構
 #[derive(Debug, Deserialize)]
@@ -27,10 +214,126 @@ struct GetFileResponse {
     state: String,
 }
 
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListFilesResponse {
+    #[serde(default)]
+    files: Vec<ApiFile>,
+    next_page_token: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiFile {
+    name: String,
+    display_name: Option<String>,
+    /// File API 以字串表示大小
+    size_bytes: Option<String>,
+    create_time: Option<String>,
+    expiration_time: Option<String>,
+    state: Option<String>,
+}
+
+/// 仍存放在 Gemini File API 上的檔案
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteFile {
+    /// files/xxxx
+    pub name: String,
+    pub display_name: Option<String>,
+    pub size_bytes: Option<u64>,
+    pub create_time: Option<String>,
+    /// Google 自動刪除的時間 (上傳後 48 小時)
+    pub expiration_time: Option<String>,
+    pub state: Option<String>,
+    /// 匿名上傳的檔案對應的本機檔案 (依對照表)
+    pub local_path: Option<String>,
+}
+
+impl From<ApiFile> for RemoteFile {
+    fn from(file: ApiFile) -> Self {
+        Self {
+            name: file.name,
+            display_name: file.display_name,
+            size_bytes: file.size_bytes.and_then(|s| s.parse().ok()),
+            create_time: file.create_time,
+            expiration_time: file.expiration_time,
+            state: file.state,
+            local_path: None,
+        }
+    }
+}
+
+impl RemoteFile {
+    /// 建立時間早於 cutoff (無法判斷建立時間時視為過期)
+    fn created_before(&self, cutoff: chrono::DateTime<chrono::Utc>) -> bool {
+        self.create_time
+            .as_deref()
+            .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+            .map(|t| t.with_timezone(&chrono::Utc) < cutoff)
+            .unwrap_or(true)
+    }
+}
+
+/// 清理遠端檔案的結果
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PurgeResult {
+    pub deleted: Vec<String>,
+    /// (檔名, 錯誤訊息)
+    pub failed: Vec<(String, String)>,
+    /// 仍保留的檔案 (未過期，可能是進行中的報告)
+    pub kept: usize,
+}
+
 // Gemini Generate Content 回應結構
 #[derive(Debug, Deserialize)]
 struct GenerateResponse {
     candidates: Option<Vec<Candidate>>,
+    #[serde(rename = "usageMetadata", default)]
+    usage_metadata: Option<UsageMetadata>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct UsageMetadata {
+    prompt_token_count: u64,
+    candidates_token_count: u64,
+    total_token_count: u64,
+}
+
+/// Gemini 回報的 token 用量
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct TokenUsage {
+    /// 輸入 (音檔 + Prompt)
+    pub prompt_tokens: u64,
+    /// 輸出
+    pub output_tokens: u64,
+    pub total_tokens: u64,
+}
+
+impl std::ops::AddAssign for TokenUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.prompt_tokens += other.prompt_tokens;
+        self.output_tokens += other.output_tokens;
+        self.total_tokens += other.total_tokens;
+    }
+}
+
+impl From<UsageMetadata> for TokenUsage {
+    fn from(usage: UsageMetadata) -> Self {
+        Self {
+            prompt_tokens: usage.prompt_token_count,
+            output_tokens: usage.candidates_token_count,
+            total_tokens: usage.total_token_count,
+        }
+    }
+}
+
+/// 以同一音檔執行一組 Prompt 的結果
+#[derive(Debug, Clone)]
+pub struct PromptRun {
+    pub text: String,
+    pub usage: TokenUsage,
+    pub elapsed_ms: u64,
 }
 
 #[derive(Debug, Deserialize)]
@@ -93,19 +396,178 @@ pub const DEFAULT_PROMPT: &str = r#"
                - 保持專業術語的準確性。
         "#;
 
+pub const TRANSLATION_PROMPT: &str = r#"
+            請將以下繁體中文醫學會議逐字紀錄翻譯成英文，供國際個案討論使用。
+            1. 逐句完整翻譯，**嚴禁摘要或省略**，段落與順序與原文一致。
+            2. 講者格式保留為【講者名稱】，講者名稱可音譯但不可更改。
+            3. 醫學術語使用標準英文術語；原文中括號內的英文術語直接沿用。
+            4. 遮罩的病患名字 (XXX) 保持不變。
+            5. 只輸出翻譯結果，不要加上任何說明。
+
+            逐字紀錄：
+        "#;
+
+/// 依段落把文字切成不超過 max_chars 的片段 (單一段落過長時單獨成為一段)
+fn split_paragraphs(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    for paragraph in text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        if !current.is_empty() && current.chars().count() + paragraph.chars().count() > max_chars {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push_str("\n\n");
+        }
+        current.push_str(paragraph.trim());
+    }
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+/// 報告進度回呼: (目前第幾個檔案, 總數, 檔名)
+pub type ReportProgress = Arc<dyn Fn(usize, usize, &str) + Send + Sync>;
+
+/// 上傳進度回呼: (檔名, 已上傳 bytes, 總 bytes)
+pub type UploadProgress = Arc<dyn Fn(&str, u64, u64) + Send + Sync>;
+
+/// 上傳進度事件的內容
+#[derive(Debug, Clone, Serialize)]
+pub struct UploadProgressEvent {
+    pub job_id: String,
+    pub file: String,
+    pub uploaded: u64,
+    pub total: u64,
+}
+
+/// 分段上傳失敗：暫時性的錯誤 (網路、5xx) 會查詢進度後重試
+enum UploadError {
+    Transient(String),
+    Fatal(String),
+}
+
+/// 查詢上傳進度的結果
+enum UploadStatus {
+    /// 伺服器已收到的 bytes
+    Active(u64),
+    /// 已完成 (中斷前最後一段其實已送達)
+    Final(UploadResponse),
+}
+
 pub struct ReportAgent {
     api_key: String,
     client: reqwest::Client,
+    /// 單次生成請求的時間上限
+    request_timeout: Duration,
+    cancel: Option<CancelToken>,
+    progress: Option<ReportProgress>,
+    upload_progress: Option<UploadProgress>,
+    bilingual: bool,
+    /// 專案標註 (重點、疑問等附在對應錄音的提示詞後)
+    annotations: Vec<Annotation>,
+    /// 上次報告的段落 (None 時讀取輸出位置既有的 report.json)
+    previous: Option<Vec<ReportSection>>,
+    /// 累計的 token 用量
+    usage: std::sync::Mutex<TokenUsage>,
+    /// 上傳前的壓縮設定 (None 時上傳原檔)
+    upload_encoding: Option<StageEncoding>,
+    /// 提示詞的模板變數 (專案名稱、錄音日期、科別)
+    template_vars: BTreeMap<String, String>,
+    /// 逐字稿資料夾 (<檔名>.json)，用來推算報告段落的時間
+    transcript_dir: Option<PathBuf>,
+    /// 長檔切割、找靜音切點、上傳前壓縮使用的 FFmpeg (預設為 PATH 上的 ffmpeg)
+    ffmpeg: Ffmpeg,
 }
 
 impl ReportAgent {
     pub fn new(api_key: String) -> Self {
+        let settings = settings::load();
+        let config = settings.http;
         Self {
             api_key,
-            client: reqwest::Client::new(),
+            client: build_client(&config),
+            request_timeout: Duration::from_secs(config.request_timeout_secs),
+            cancel: None,
+            progress: None,
+            upload_progress: None,
+            bilingual: false,
+            annotations: Vec::new(),
+            previous: None,
+            usage: std::sync::Mutex::new(TokenUsage::default()),
+            upload_encoding: settings.encoding.upload,
+            template_vars: BTreeMap::new(),
+            transcript_dir: None,
+            ffmpeg: Ffmpeg::Binary(PathBuf::from(sidecar::executable_name("ffmpeg"))),
         }
     }
 
+    /// 指定 FFmpeg (app 內使用 Sidecar，CLI 使用解析出的執行檔)
+    pub fn with_ffmpeg(mut self, ffmpeg: Ffmpeg) -> Self {
+        self.ffmpeg = ffmpeg;
+        self
+    }
+
+    /// 綁定工作的取消旗標，每個檔案開始處理前檢查
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// 設定進度回呼
+    pub fn with_progress(mut self, progress: ReportProgress) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// 設定上傳進度回呼 (每上傳一段呼叫一次)
+    pub fn with_upload_progress(mut self, progress: UploadProgress) -> Self {
+        self.upload_progress = Some(progress);
+        self
+    }
+
+    /// 雙語報告：每個音檔的逐字稿再翻譯一次英文，兩者並列
+    pub fn with_bilingual(mut self, bilingual: bool) -> Self {
+        self.bilingual = bilingual;
+        self
+    }
+
+    /// 設定專案標註，以檔名對應到資料夾中的音檔
+    pub fn with_annotations(mut self, annotations: Vec<Annotation>) -> Self {
+        self.annotations = annotations;
+        self
+    }
+
+    /// 設定提示詞的模板變數，生成前代入 (見 manifest::ProjectManifest::template_vars)
+    pub fn with_template_vars(mut self, vars: BTreeMap<String, String>) -> Self {
+        self.template_vars = vars;
+        self
+    }
+
+    /// 指定逐字稿資料夾，有對應逐字稿的音檔依逐字稿時間建立段落索引
+    pub fn with_transcript_dir(mut self, dir: PathBuf) -> Self {
+        self.transcript_dir = Some(dir);
+        self
+    }
+
+    /// 指定上次報告的段落 (報告不是寫在原位置時，例如加密專案的暫存資料夾)
+    pub fn with_previous(mut self, sections: Vec<ReportSection>) -> Self {
+        self.previous = Some(sections);
+        self
+    }
+
+    /// 到目前為止所有 Gemini 請求的 token 用量
+    pub fn usage(&self) -> TokenUsage {
+        self.usage.lock().map(|u| *u).unwrap_or_default()
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancel
+            .as_ref()
+            .map(|c| c.is_cancelled())
+            .unwrap_or(false)
+    }
+
     /// 處理資料夾中的所有音檔，生成報告
     pub async fn process_folder(
         &self,
@@ -115,8 +577,8 @@ impl ReportAgent {
         custom_prompt: Option<String>,
     ) -> Result<String, String> {
         // 0. 決定模型 (預設 gemini-3.1-pro-preview)
-        let model = model_name.unwrap_or_else(|| "gemini-3.1-pro-preview".to_string());
-        println!("使用模型: {}", model);
+        let model = model_name.unwrap_or_else(|| DEFAULT_MODEL.to_string());
+        tracing::info!("使用模型: {}", model);
         // 1. 列出音檔
         let audio_extensions = ["mp3", "wav", "aac", "flac", "ogg", "m4a"];
         let folder = Path::new(folder_path);
@@ -151,60 +613,205 @@ impl ReportAgent {
 
         // 3. 初始化報告
         let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
-        let mut report_content =
-            format!("# 醫學會議精煉報告\n\n生成時間: {}\n\n---\n\n", timestamp);
+        let title = if self.bilingual {
+            "醫學會議精煉報告 (中英對照 / Bilingual)"
+        } else {
+            "醫學會議精煉報告"
+        };
+        let mut report_content = format!("# {}\n\n生成時間: {}\n\n---\n\n", title, timestamp);
 
-        // 決定使用的 Prompt
-        let prompt = custom_prompt.unwrap_or_else(|| DEFAULT_PROMPT.to_string());
+        // 決定使用的 Prompt，並代入專案資訊
+        let prompt = fill_template(
+            custom_prompt.as_deref().unwrap_or(DEFAULT_PROMPT),
+            &self.template_vars,
+        );
+
+        // 上次的報告：音檔與提示詞都沒變的段落直接沿用，不再送出
+        let previous = match &self.previous {
+            Some(sections) => sections.clone(),
+            None => load_summary(Path::new(output_path))
+                .map(|summary| summary.sections)
+                .unwrap_or_default(),
+        };
 
         // 4. 處理每個音檔
         let total = audio_files.len();
+        let mut succeeded = Vec::new();
+        let mut errors = Vec::new();
+        let mut sections = Vec::new();
+        let mut reused = Vec::new();
         for (idx, audio_path) in audio_files.iter().enumerate() {
             let filename = audio_path
                 .file_name()
                 .map(|s| s.to_string_lossy().to_string())
                 .unwrap_or_default();
 
-            println!("🎙️ 正在處理 ({}/{}) {}...", idx + 1, total, filename);
+            if self.is_cancelled() {
+                return Err(CANCELLED_MESSAGE.to_string());
+            }
+            if let Some(progress) = &self.progress {
+                progress(idx, total, &filename);
+            }
 
-            match self
-                .process_single_file(audio_path.to_str().unwrap_or_default(), &model, &prompt)
-                .await
-            {
-                Ok(text) => {
+            tracing::info!("🎙️ 正在處理 ({}/{}) {}...", idx + 1, total, filename);
+
+            let file_prompt = format!(
+                "{}{}",
+                prompt,
+                annotations::prompt_context(&annotations::for_file_name(
+                    &self.annotations,
+                    &filename
+                ))
+            );
+            // 無法計算雜湊時不沿用，也不記錄
+            let audio_hash = manifest::hash_file(audio_path).unwrap_or_default();
+            let prompt_hash = prompt_hash(&model, &file_prompt);
+            let unchanged = previous.iter().find(|s| {
+                !audio_hash.is_empty()
+                    && s.file == filename
+                    && s.audio_hash == audio_hash
+                    && s.prompt_hash == prompt_hash
+            });
+            let result = match unchanged {
+                Some(section) => {
+                    tracing::info!("   -> 音檔與提示詞未變更，沿用上次的結果");
+                    reused.push(filename.clone());
+                    let chunks = if section.chunks.is_empty() {
+                        whole_file_chunk(audio_path)
+                    } else {
+                        section.chunks.clone()
+                    };
+                    Ok((section.text.clone(), chunks))
+                }
+                None => {
+                    self.process_single_file(&audio_path.to_string_lossy(), &model, &file_prompt)
+                        .await
+                }
+            };
+            match result {
+                Ok((text, chunks)) if self.bilingual => {
+                    if self.is_cancelled() {
+                        return Err(CANCELLED_MESSAGE.to_string());
+                    }
+                    let previous_translation = unchanged.and_then(|s| s.translation.clone());
+                    let translation = match previous_translation {
+                        Some(translation) => Some(translation),
+                        None => {
+                            tracing::info!("   -> 翻譯英文...");
+                            match self.translate(&text, &model).await {
+                                Ok(translation) => Some(translation),
+                                Err(e) => {
+                                    errors.push(ReportFileError {
+                                        file: filename.clone(),
+                                        path: audio_path.to_string_lossy().to_string(),
+                                        error: format!("英文翻譯失敗: {}", e),
+                                        failed_at: chrono::Local::now().to_rfc3339(),
+                                    });
+                                    None
+                                }
+                            }
+                        }
+                    };
+                    report_content.push_str(&format!(
+                        "## 【個案來源：{}】\n\n### 逐字紀錄 (繁體中文)\n\n{}\n\n### English Translation\n\n{}\n\n---\n\n",
+                        filename,
+                        text,
+                        translation
+                            .as_deref()
+                            .unwrap_or("> ⚠️ 英文翻譯失敗，詳見附錄「處理錯誤」")
+                    ));
+                    sections.push(ReportSection {
+                        file: filename.clone(),
+                        audio_hash: audio_hash.clone(),
+                        prompt_hash,
+                        text,
+                        translation,
+                        chunks,
+                    });
+                    succeeded.push(filename);
+                }
+                Ok((text, chunks)) => {
                     report_content.push_str(&format!(
                         "## 【個案來源：{}】\n\n{}\n\n---\n\n",
                         filename, text
                     ));
+                    sections.push(ReportSection {
+                        file: filename.clone(),
+                        audio_hash: audio_hash.clone(),
+                        prompt_hash,
+                        text,
+                        translation: None,
+                        chunks,
+                    });
+                    succeeded.push(filename);
                 }
                 Err(e) => {
+                    // 錯誤內容集中在附錄與 report.json，正文只留提示
                     report_content.push_str(&format!(
-                        "## 【個案來源：{}】\n\n[處理錯誤] {}\n\n---\n\n",
-                        filename, e
+                        "## 【個案來源：{}】\n\n> ⚠️ 處理失敗，詳見附錄「處理錯誤」\n\n---\n\n",
+                        filename
                     ));
+                    errors.push(ReportFileError {
+                        file: filename,
+                        path: audio_path.to_string_lossy().to_string(),
+                        error: e,
+                        failed_at: chrono::Local::now().to_rfc3339(),
+                    });
                 }
             }
         }
+        if !errors.is_empty() {
+            report_content.push_str(&errors_appendix(&errors));
+        }
 
-        // 5. 儲存報告
+        // 5. 儲存報告與 report.json
         fs::write(output_path, &report_content).map_err(|e| format!("儲存報告失敗: {}", e))?;
+        let paragraphs = report_index::build(&report_content, &sections, |file| {
+            let dir = self.transcript_dir.as_ref()?;
+            transcript::load(&dir.join(format!("{}.json", file))).ok()
+        });
+        let failed = errors.len();
+        let reused_count = reused.len();
+        let summary = ReportSummary {
+            generated_at: chrono::Local::now().to_rfc3339(),
+            model,
+            folder: folder_path.to_string(),
+            succeeded,
+            errors,
+            bilingual: self.bilingual,
+            deid: None,
+            sections,
+            reused,
+            paragraphs,
+        };
+        save_summary(Path::new(output_path), &summary)?;
 
-        Ok(format!(
+        let mut message = format!(
             "報告生成完成！\n處理了 {} 個音檔\n輸出位置: {}",
             total, output_path
-        ))
+        );
+        if reused_count > 0 {
+            message.push_str(&format!(
+                "\n其中 {} 個音檔與提示詞未變更，沿用上次的結果",
+                reused_count
+            ));
+        }
+        if failed > 0 {
+            message.push_str(&format!("\n⚠️ {} 個音檔處理失敗 (詳見報告附錄)", failed));
+        }
+        Ok(message)
     }
 
-    /// 處理單一音檔
+    /// 處理單一音檔，回傳內文與各段的時間範圍
     /// 短檔案直接處理，長檔案（>24分鐘）分段處理
     async fn process_single_file(
         &self,
         file_path: &str,
         model_name: &str,
         prompt: &str,
-    ) -> Result<String, String> {
+    ) -> Result<(String, Vec<ChunkSpan>), String> {
         // 取得音檔長度
-        let duration = Self::get_audio_duration_sync(file_path)?;
+        let duration = probe::cached_duration(file_path)?;
         let duration_min = duration / 60.0;
 
         // 閾值：24 分鐘
@@ -212,21 +819,27 @@ impl ReportAgent {
 
         if duration_min < SPLIT_THRESHOLD_MIN {
             // 短檔案：直接處理
-            println!("   -> {:.1} 分鐘 (短檔)，直接生成報告...", duration_min);
+            tracing::info!("   -> {:.1} 分鐘 (短檔)，直接生成報告...", duration_min);
 
             let file_uri = self.upload_file(file_path).await?;
             let result = self.generate_content(&file_uri, model_name, prompt).await?;
             let _ = self.delete_file(&file_uri).await;
 
-            Ok(result)
+            let chunks = vec![ChunkSpan {
+                start: 0.0,
+                end: duration,
+                offset: 0,
+            }];
+            Ok((result, chunks))
         } else {
             // 長檔案：分段處理
-            println!(
+            tracing::info!(
                 "   -> ⚠️ {:.1} 分鐘 (長檔)，啟動「分段聽寫」模式...",
                 duration_min
             );
 
             let mut full_transcript = String::new();
+            let mut chunks = Vec::new();
             let segment_count = 3;
             let segment_duration = duration / segment_count as f64;
 
@@ -235,27 +848,42 @@ impl ReportAgent {
             let temp_dir = parent.join("temp_split_process");
             fs::create_dir_all(&temp_dir).map_err(|e| format!("建立暫存目錄失敗: {}", e))?;
 
+            // 切點對齊到目標位置前後最近的靜音，避免切在句子中間
+            let mut boundaries = vec![0.0];
+            for i in 1..segment_count {
+                let target = i as f64 * segment_duration;
+                let point = self.find_split_point(file_path, target).await?;
+                let previous = *boundaries.last().unwrap_or(&0.0);
+                boundaries.push(if point > previous { point } else { target });
+            }
+            boundaries.push(duration);
+
             for i in 0..segment_count {
-                let start_sec = i as f64 * segment_duration;
-                let end_sec = ((i + 1) as f64 * segment_duration).min(duration);
+                let start_sec = boundaries[i];
+                let end_sec = boundaries[i + 1];
 
-                println!("      正在聽寫第 {}/{} 段...", i + 1, segment_count);
+                tracing::info!("      正在聽寫第 {}/{} 段...", i + 1, segment_count);
 
                 // 使用 FFmpeg 切割
                 let segment_path = temp_dir.join(format!("part_{}.mp3", i + 1));
                 self.split_audio_segment(
                     file_path,
-                    segment_path.to_str().unwrap(),
+                    &segment_path.to_string_lossy(),
                     start_sec,
                     end_sec,
                 )
                 .await?;
 
                 // 上傳並處理分段
-                let file_uri = self.upload_file(segment_path.to_str().unwrap()).await?;
+                let file_uri = self.upload_file(&segment_path.to_string_lossy()).await?;
                 let part_text = self.generate_content(&file_uri, model_name, prompt).await?;
                 let _ = self.delete_file(&file_uri).await;
 
+                chunks.push(ChunkSpan {
+                    start: start_sec,
+                    end: end_sec,
+                    offset: full_transcript.chars().count() + 1,
+                });
                 full_transcript.push_str(&format!("\n{}\n", part_text));
 
                 // 刪除暫存分段
@@ -268,58 +896,76 @@ impl ReportAgent {
             // 清理暫存目錄
             let _ = fs::remove_dir(&temp_dir);
 
-            Ok(full_transcript)
+            Ok((full_transcript, chunks))
         }
     }
 
-    /// 取得音檔長度（秒）— 使用 symphonia 原生解析，不依賴外部程式
-    fn get_audio_duration_sync(file_path: &str) -> Result<f64, String> {
-        use symphonia::core::formats::FormatOptions;
-        use symphonia::core::io::MediaSourceStream;
-        use symphonia::core::meta::MetadataOptions;
-        use symphonia::core::probe::Hint;
-
-        let file = std::fs::File::open(file_path).map_err(|e| format!("無法開啟音檔: {}", e))?;
-        let mss = MediaSourceStream::new(Box::new(file), Default::default());
-
-        let mut hint = Hint::new();
-        if let Some(ext) = std::path::Path::new(file_path)
-            .extension()
-            .and_then(|e| e.to_str())
-        {
-            hint.with_extension(ext);
+    /// 把逐字稿翻譯成英文 (長逐字稿依段落分次翻譯)
+    async fn translate(&self, text: &str, model_name: &str) -> Result<String, String> {
+        let mut translated = Vec::new();
+        for chunk in split_paragraphs(text, TRANSLATION_CHUNK_CHARS) {
+            let prompt = format!("{}{}", TRANSLATION_PROMPT, chunk);
+            translated.push(self.generate_text(model_name, &prompt).await?);
         }
+        Ok(translated.join("\n\n"))
+    }
 
-        let probed = symphonia::default::get_probe()
-            .format(
-                &hint,
-                mss,
-                &FormatOptions::default(),
-                &MetadataOptions::default(),
+    /// 在 target 前後 SPLIT_SEARCH_SECONDS 內以 silencedetect 找出最接近的靜音，
+    /// 回傳靜音的中點；找不到靜音 (或 FFmpeg 失敗) 時回傳 target，只有取消時回傳錯誤
+    async fn find_split_point(&self, input_path: &str, target: f64) -> Result<f64, String> {
+        let window_start = (target - SPLIT_SEARCH_SECONDS).max(0.0);
+        let filter = format!(
+            "silencedetect=noise={}dB:d={}",
+            SPLIT_SILENCE_NOISE_DB, SPLIT_SILENCE_MIN_SECONDS
+        );
+        let output = self
+            .ffmpeg
+            .run(
+                [
+                    "-hide_banner",
+                    "-ss",
+                    &format!("{:.2}", window_start),
+                    "-t",
+                    &format!("{:.2}", target + SPLIT_SEARCH_SECONDS - window_start),
+                    "-i",
+                    input_path,
+                    "-af",
+                    &filter,
+                    "-f",
+                    "null",
+                    "-",
+                ],
+                self.cancel.as_ref(),
             )
-            .map_err(|e| format!("無法解析音檔格式: {}", e))?;
-
-        let reader = probed.format;
-
-        // 嘗試從預設 track 取得時長
-        if let Some(track) = reader.default_track() {
-            if let Some(n_frames) = track.codec_params.n_frames {
-                if let Some(tb) = track.codec_params.time_base {
-                    let time = tb.calc_time(n_frames);
-                    return Ok(time.seconds as f64 + time.frac);
-                }
+            .await;
+        let stderr = match output {
+            Ok(output) if output.success() => output.stderr,
+            Ok(output) => {
+                tracing::warn!("silencedetect 失敗，改用等分切點: {}", output.stderr);
+                return Ok(target);
             }
-            // 備用：嘗試從 sample_rate 和 n_frames 推算
-            if let (Some(n_frames), Some(sample_rate)) =
-                (track.codec_params.n_frames, track.codec_params.sample_rate)
-            {
-                if sample_rate > 0 {
-                    return Ok(n_frames as f64 / sample_rate as f64);
-                }
+            Err(e) if e == CANCELLED_MESSAGE => return Err(e),
+            Err(e) => {
+                tracing::warn!("無法執行 ffmpeg silencedetect，改用等分切點: {}", e);
+                return Ok(target);
             }
-        }
+        };
 
-        Err("無法從音檔取得時長資訊".to_string())
+        // -ss 在 -i 之前時輸出時間從 0 開始，需加回視窗起點
+        let best = parse_silences(&stderr)
+            .into_iter()
+            .map(|(start, end)| window_start + (start + end) / 2.0)
+            .min_by(|a, b| (a - target).abs().total_cmp(&(b - target).abs()));
+        Ok(match best {
+            Some(point) => {
+                tracing::info!("      切點 {:.1}s 對齊到靜音 {:.1}s", target, point);
+                point
+            }
+            None => {
+                tracing::info!("      {:.1}s 附近沒有靜音，使用等分切點", target);
+                target
+            }
+        })
     }
 
     /// 使用 FFmpeg 切割音檔片段
@@ -333,42 +979,94 @@ impl ReportAgent {
         let start_str = format!("{:.2}", start_sec);
         let duration_str = format!("{:.2}", end_sec - start_sec);
 
-        let output = tokio::process::Command::new("ffmpeg")
-            .args([
-                "-y",
-                "-i",
-                input_path,
-                "-ss",
-                &start_str,
-                "-t",
-                &duration_str,
-                "-c",
-                "copy",
-                output_path,
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("無法執行 ffmpeg: {}", e))?;
+        let output = self
+            .ffmpeg
+            .run(
+                [
+                    "-y",
+                    "-i",
+                    input_path,
+                    "-ss",
+                    &start_str,
+                    "-t",
+                    &duration_str,
+                    "-c",
+                    "copy",
+                    output_path,
+                ],
+                self.cancel.as_ref(),
+            )
+            .await?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            return Err(format!("ffmpeg 切割失敗: {}", stderr));
+        if !output.success() {
+            return Err(format!("ffmpeg 切割失敗: {}", output.stderr));
         }
 
         Ok(())
     }
 
-    /// 上傳檔案到 Gemini File API (使用 Resumable Upload 協議)
+    /// 依設定壓縮上傳用的副本 (例如 48 kbps 單聲道)，輸出到來源旁的暫存資料夾
+    async fn compress_for_upload(
+        &self,
+        input_path: &str,
+        encoding: &StageEncoding,
+    ) -> Result<std::path::PathBuf, String> {
+        let input = Path::new(input_path);
+        let temp_dir = input
+            .parent()
+            .unwrap_or(Path::new("."))
+            .join("temp_upload_process");
+        fs::create_dir_all(&temp_dir).map_err(|e| format!("建立暫存目錄失敗: {}", e))?;
+        let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+        let output_path = temp_dir.join(format!("{}.mp3", stem));
+
+        let bitrate = format!("{}k", encoding.bitrate_kbps);
+        let sample_rate = encoding.sample_rate.map(|r| r.to_string());
+        let output_str = output_path.to_string_lossy().to_string();
+        let mut args = vec!["-y", "-i", input_path, "-vn", "-b:a", bitrate.as_str()];
+        if encoding.mono {
+            args.extend(["-ac", "1"]);
+        }
+        if let Some(rate) = &sample_rate {
+            args.extend(["-ar", rate.as_str()]);
+        }
+        args.push(output_str.as_str());
+
+        let output = self.ffmpeg.run(&args, self.cancel.as_ref()).await?;
+        if !output.success() {
+            return Err(format!("ffmpeg 壓縮失敗: {}", output.stderr));
+        }
+        Ok(output_path)
+    }
+
+    /// 上傳檔案到 Gemini File API；設定了上傳編碼時先壓縮，上傳後刪除壓縮檔
     async fn upload_file(&self, file_path: &str) -> Result<String, String> {
+        let Some(encoding) = self.upload_encoding else {
+            return self.upload_original(file_path).await;
+        };
+        let compressed = self.compress_for_upload(file_path, &encoding).await?;
+        let result = self.upload_original(&compressed.to_string_lossy()).await;
+        let _ = fs::remove_file(&compressed);
+        if let Some(dir) = compressed.parent() {
+            let _ = fs::remove_dir(dir);
+        }
+        result
+    }
+
+    /// 上傳檔案到 Gemini File API (使用 Resumable Upload 協議)
+    /// 檔案分段上傳，網路中斷時查詢伺服器已收到的位置後從該處接續
+    async fn upload_original(&self, file_path: &str) -> Result<String, String> {
         let path = Path::new(file_path);
         let file_name = path
             .file_name()
             .map(|s| s.to_string_lossy().to_string())
             .unwrap_or_else(|| "audio.mp3".to_string());
 
-        // 讀取檔案
-        let file_bytes = fs::read(file_path).map_err(|e| format!("讀取檔案失敗: {}", e))?;
-        let file_size = file_bytes.len();
+        let mut file = fs::File::open(file_path).map_err(|e| format!("讀取檔案失敗: {}", e))?;
+        let file_size = file
+            .metadata()
+            .map_err(|e| format!("讀取檔案失敗: {}", e))?
+            .len();
 
         // 決定 MIME type
         let mime_type = match path.extension().and_then(|e| e.to_str()) {
@@ -381,9 +1079,86 @@ impl ReportAgent {
             _ => "audio/mpeg",
         };
 
-        // Step 1: 初始化 Resumable Upload
-        const UPLOAD_URL: &str = "https://generativelanguage.googleapis.com/upload/v1beta/files";
+        // Step 1: 初始化 Resumable Upload (匿名上傳時以隨機名稱作為遠端顯示名稱)
+        let upload_url = self
+            .start_upload(&upload_alias::upload_name(path), file_size, mime_type)
+            .await?;
+
+        // Step 2: 分段上傳檔案內容
+        let mut offset = 0u64;
+        let mut retries = 0u32;
+        let upload_result = loop {
+            if self.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+                return Err(CANCELLED_MESSAGE.to_string());
+            }
+            let len = (file_size - offset).min(UPLOAD_CHUNK_SIZE);
+            let last = offset + len >= file_size;
+            let mut chunk = vec![0u8; len as usize];
+            file.seek(SeekFrom::Start(offset))
+                .and_then(|_| file.read_exact(&mut chunk))
+                .map_err(|e| format!("讀取檔案失敗: {}", e))?;
+
+            let error = match self.upload_chunk(&upload_url, offset, chunk, last).await {
+                Ok(Some(result)) => {
+                    self.report_upload(&file_name, file_size, file_size);
+                    break result;
+                }
+                Ok(None) => {
+                    offset += len;
+                    retries = 0;
+                    self.report_upload(&file_name, offset, file_size);
+                    continue;
+                }
+                Err(UploadError::Fatal(e)) => return Err(format!("上傳失敗: {}", e)),
+                Err(UploadError::Transient(e)) => e,
+            };
+
+            retries += 1;
+            if retries > UPLOAD_MAX_RETRIES {
+                return Err(format!("上傳檔案失敗: {}", error));
+            }
+            tracing::warn!(
+                "上傳中斷 ({} / {} bytes)，{} 秒後重試 ({}/{}): {}",
+                offset,
+                file_size,
+                2u64.pow(retries),
+                retries,
+                UPLOAD_MAX_RETRIES,
+                error
+            );
+            tokio::time::sleep(Duration::from_secs(2u64.pow(retries))).await;
+            match self.query_upload(&upload_url).await {
+                Ok(UploadStatus::Final(result)) => break result,
+                Ok(UploadStatus::Active(received)) => offset = received.min(file_size),
+                // 查詢失敗時從原本的位置重送
+                Err(e) => tracing::warn!("無法查詢上傳進度: {}", e),
+            }
+        };
+
+        // 等待檔案處理完成
+        let file_name = &upload_result.file.name;
+        let file_uri = upload_result.file.uri;
+
+        for _ in 0..120 {
+            let state = self.get_file_state(file_name).await?;
+            if state == "ACTIVE" {
+                return Ok(file_uri);
+            } else if state == "FAILED" {
+                return Err("檔案處理失敗".to_string());
+            }
+            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+        }
+
+        Err("檔案處理超時".to_string())
+    }
 
+    /// 開始 Resumable Upload，回傳上傳 URL
+    async fn start_upload(
+        &self,
+        file_name: &str,
+        file_size: u64,
+        mime_type: &str,
+    ) -> Result<String, String> {
         let init_url = format!("{UPLOAD_URL}?key={}", self.api_key);
 
         let metadata = serde_json::json!({
@@ -411,50 +1186,98 @@ impl ReportAgent {
         }
 
         // 取得上傳 URL
-        let upload_url = init_response
+        init_response
             .headers()
             .get("x-goog-upload-url")
             .and_then(|v| v.to_str().ok())
-            .ok_or("無法取得上傳 URL")?
-            .to_string();
+            .map(|v| v.to_string())
+            .ok_or_else(|| "無法取得上傳 URL".to_string())
+    }
 
-        // Step 2: 上傳檔案內容
-        let upload_response = self
+    /// 上傳一段內容；最後一段同時 finalize 並回傳檔案資訊
+    async fn upload_chunk(
+        &self,
+        upload_url: &str,
+        offset: u64,
+        chunk: Vec<u8>,
+        last: bool,
+    ) -> Result<Option<UploadResponse>, UploadError> {
+        let command = if last { "upload, finalize" } else { "upload" };
+        let response = self
             .client
-            .post(&upload_url)
-            .header("X-Goog-Upload-Command", "upload, finalize")
-            .header("X-Goog-Upload-Offset", "0")
-            .header("Content-Length", file_size.to_string())
-            .body(file_bytes)
+            .post(upload_url)
+            .header("X-Goog-Upload-Command", command)
+            .header("X-Goog-Upload-Offset", offset.to_string())
+            .header("Content-Length", chunk.len().to_string())
+            .body(chunk)
             .send()
             .await
-            .map_err(|e| format!("上傳檔案失敗: {}", e))?;
+            .map_err(|e| UploadError::Transient(e.to_string()))?;
 
-        if !upload_response.status().is_success() {
-            let error_text = upload_response.text().await.unwrap_or_default();
-            return Err(format!("上傳失敗: {}", error_text));
+        let status = response.status();
+        if !status.is_success() {
+            let error_text = response.text().await.unwrap_or_default();
+            let message = format!("{} {}", status, error_text);
+            return Err(
+                if status.is_server_error()
+                    || status == reqwest::StatusCode::REQUEST_TIMEOUT
+                    || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+                {
+                    UploadError::Transient(message)
+                } else {
+                    UploadError::Fatal(message)
+                },
+            );
         }
-
-        let upload_result: UploadResponse = upload_response
+        if !last {
+            return Ok(None);
+        }
+        response
             .json()
             .await
-            .map_err(|e| format!("解析上傳回應失敗: {}", e))?;
-
-        // 等待檔案處理完成
-        let file_name = &upload_result.file.name;
-        let file_uri = upload_result.file.uri;
+            .map(Some)
+            .map_err(|e| UploadError::Fatal(format!("解析上傳回應失敗: {}", e)))
+    }
 
-        for _ in 0..120 {
-            let state = self.get_file_state(file_name).await?;
-            if state == "ACTIVE" {
-                return Ok(file_uri);
-            } else if state == "FAILED" {
-                return Err("檔案處理失敗".to_string());
-            }
-            tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    /// 查詢伺服器已收到的 bytes
+    async fn query_upload(&self, upload_url: &str) -> Result<UploadStatus, String> {
+        let response = self
+            .client
+            .post(upload_url)
+            .header("X-Goog-Upload-Command", "query")
+            .header("Content-Length", "0")
+            .send()
+            .await
+            .map_err(|e| e.to_string())?;
+        if !response.status().is_success() {
+            return Err(response.status().to_string());
         }
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .map(|v| v.to_string())
+        };
+        let upload_status = header("x-goog-upload-status");
+        let received = header("x-goog-upload-size-received");
+        if upload_status.as_deref() == Some("final") {
+            return response
+                .json()
+                .await
+                .map(UploadStatus::Final)
+                .map_err(|e| format!("解析上傳回應失敗: {}", e));
+        }
+        received
+            .and_then(|v| v.parse().ok())
+            .map(UploadStatus::Active)
+            .ok_or_else(|| "回應缺少 X-Goog-Upload-Size-Received".to_string())
+    }
 
-        Err("檔案處理超時".to_string())
+    fn report_upload(&self, file_name: &str, uploaded: u64, total: u64) {
+        if let Some(progress) = &self.upload_progress {
+            progress(file_name, uploaded, total);
+        }
     }
 
     /// 取得檔案狀態
@@ -492,6 +1315,120 @@ impl ReportAgent {
         Ok(())
     }
 
+    /// 列出帳號在 File API 上仍存放的所有檔案
+    pub async fn list_remote_files(&self) -> Result<Vec<RemoteFile>, String> {
+        let mut files = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut url = format!("{FILES_URL}?pageSize=100&key={}", self.api_key);
+            if let Some(token) = &page_token {
+                url.push_str(&format!("&pageToken={}", token));
+            }
+            let response = self
+                .client
+                .get(&url)
+                .send()
+                .await
+                .map_err(|e| format!("無法列出遠端檔案: {}", e))?;
+            if !response.status().is_success() {
+                return Err(format!("無法列出遠端檔案: {}", response.status()));
+            }
+            let page: ListFilesResponse = response
+                .json()
+                .await
+                .map_err(|e| format!("解析遠端檔案清單失敗: {}", e))?;
+            files.extend(page.files.into_iter().map(RemoteFile::from));
+            match page.next_page_token.filter(|t| !t.is_empty()) {
+                Some(token) => page_token = Some(token),
+                None => break,
+            }
+        }
+        let aliases = upload_alias::list();
+        for file in &mut files {
+            file.local_path = file
+                .display_name
+                .as_ref()
+                .and_then(|name| aliases.iter().rev().find(|a| &a.alias == name))
+                .map(|a| a.path.clone());
+        }
+        Ok(files)
+    }
+
+    /// 刪除遠端檔案 (name 為 files/xxxx)，失敗時回傳錯誤
+    pub async fn delete_remote_file(&self, name: &str) -> Result<(), String> {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/{}?key={}",
+            name, self.api_key
+        );
+        let response = self
+            .client
+            .delete(&url)
+            .send()
+            .await
+            .map_err(|e| format!("刪除遠端檔案失敗: {}", e))?;
+        if response.status().is_success() || response.status() == reqwest::StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(format!("刪除遠端檔案失敗: {}", response.status()))
+        }
+    }
+
+    /// 清理遠端檔案：names 指定時只刪除這些檔案，否則刪除建立超過 stale_hours 小時的檔案
+    /// (失敗的報告流程不會刪除已上傳的音檔，會留在 Google 端直到 48 小時後過期)
+    pub async fn purge_remote_files(
+        &self,
+        stale_hours: f64,
+        names: Option<Vec<String>>,
+    ) -> Result<PurgeResult, String> {
+        let files = self.list_remote_files().await?;
+        let cutoff =
+            chrono::Utc::now() - chrono::Duration::seconds((stale_hours.max(0.0) * 3600.0) as i64);
+        let mut result = PurgeResult::default();
+        for file in files {
+            let selected = match &names {
+                Some(names) => names.contains(&file.name),
+                None => file.created_before(cutoff),
+            };
+            if !selected {
+                result.kept += 1;
+                continue;
+            }
+            match self.delete_remote_file(&file.name).await {
+                Ok(()) => result.deleted.push(file.name),
+                Err(e) => result.failed.push((file.name, e)),
+            }
+        }
+        tracing::info!(
+            "(Report) 清理遠端檔案: 刪除 {}，失敗 {}，保留 {}",
+            result.deleted.len(),
+            result.failed.len(),
+            result.kept
+        );
+        Ok(result)
+    }
+
+    /// 確認 Gemini 連得上且 API Key 有效 (列出模型，不消耗額度)
+    pub async fn check_api_key(&self) -> Result<(), String> {
+        let url = format!(
+            "https://generativelanguage.googleapis.com/v1beta/models?pageSize=1&key={}",
+            self.api_key
+        );
+        let response = self
+            .client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(5))
+            .send()
+            .await
+            .map_err(|e| format!("無法連線到 Gemini: {}", e))?;
+        match response.status() {
+            status if status.is_success() => Ok(()),
+            reqwest::StatusCode::BAD_REQUEST
+            | reqwest::StatusCode::UNAUTHORIZED
+            | reqwest::StatusCode::FORBIDDEN => Err("Gemini API Key 無效".to_string()),
+            status => Err(format!("Gemini 回應錯誤: {}", status)),
+        }
+    }
+
     /// 使用 Gemini 生成內容
     async fn generate_content(
         &self,
@@ -499,35 +1436,57 @@ impl ReportAgent {
         model_name: &str,
         prompt: &str,
     ) -> Result<String, String> {
+        let parts = vec![
+            RequestPart::FileData {
+                file_data: FileData {
+                    mime_type: "audio/mpeg".to_string(),
+                    file_uri: file_uri.to_string(),
+                },
+            },
+            RequestPart::Text {
+                text: prompt.to_string(),
+            },
+        ];
+        self.generate(model_name, parts).await
+    }
+
+    /// 以純文字 Prompt 呼叫 Gemini (不含音檔，例如檢查報告內容)
+    pub async fn generate_text(&self, model_name: &str, prompt: &str) -> Result<String, String> {
+        let parts = vec![RequestPart::Text {
+            text: prompt.to_string(),
+        }];
+        self.generate(model_name, parts).await
+    }
+
+    async fn generate(&self, model_name: &str, parts: Vec<RequestPart>) -> Result<String, String> {
+        self.generate_with_usage(model_name, parts)
+            .await
+            .map(|(text, _)| text)
+    }
+
+    async fn generate_with_usage(
+        &self,
+        model_name: &str,
+        parts: Vec<RequestPart>,
+    ) -> Result<(String, TokenUsage), String> {
         let url = format!(
             "https://generativelanguage.googleapis.com/v1beta/models/{}:generateContent?key={}",
             model_name, self.api_key
         );
 
         let request = GenerateRequest {
-            contents: vec![RequestContent {
-                parts: vec![
-                    RequestPart::FileData {
-                        file_data: FileData {
-                            mime_type: "audio/mpeg".to_string(),
-                            file_uri: file_uri.to_string(),
-                        },
-                    },
-                    RequestPart::Text {
-                        text: prompt.to_string(),
-                    },
-                ],
-            }],
+            contents: vec![RequestContent { parts }],
         };
 
         let response = self
             .client
             .post(&url)
             .header("Content-Type", "application/json")
+            .timeout(self.request_timeout)
             .json(&request)
             .send()
             .await
-            .map_err(|e| format!("API 請求失敗: {}", e))?;
+            .map_err(|e| self.request_error("API 請求失敗", e))?;
 
         if !response.status().is_success() {
             let error_text = response.text().await.unwrap_or_default();
@@ -537,8 +1496,15 @@ impl ReportAgent {
         let result: GenerateResponse = response
             .json()
             .await
-            .map_err(|e| format!("解析回應失敗: {}", e))?;
-
+            .map_err(|e| self.request_error("解析回應失敗", e))?;
+
+        let usage = result
+            .usage_metadata
+            .map(TokenUsage::from)
+            .unwrap_or_default();
+        if let Ok(mut total) = self.usage.lock() {
+            *total += usage;
+        }
         let text = result
             .candidates
             .and_then(|c| c.into_iter().next())
@@ -546,13 +1512,185 @@ impl ReportAgent {
             .and_then(|p| p.text)
             .unwrap_or_else(|| "[無內容]".to_string());
 
-        Ok(text)
+        Ok((text, usage))
+    }
+
+    /// 上傳音檔一次，依序以每組 (模型, Prompt) 生成內容 (Prompt 實驗用)
+    /// 單組失敗不影響其他組；取消時停止並回傳錯誤
+    pub async fn run_prompts(
+        &self,
+        file_path: &str,
+        variants: &[(String, String)],
+    ) -> Result<Vec<Result<PromptRun, String>>, String> {
+        let file_uri = self.upload_file(file_path).await?;
+        let mut runs = Vec::new();
+        for (idx, (model, prompt)) in variants.iter().enumerate() {
+            if self.is_cancelled() {
+                let _ = self.delete_file(&file_uri).await;
+                return Err(CANCELLED_MESSAGE.to_string());
+            }
+            if let Some(progress) = &self.progress {
+                progress(idx, variants.len(), model);
+            }
+            let started = std::time::Instant::now();
+            let parts = vec![
+                RequestPart::FileData {
+                    file_data: FileData {
+                        mime_type: "audio/mpeg".to_string(),
+                        file_uri: file_uri.clone(),
+                    },
+                },
+                RequestPart::Text {
+                    text: fill_template(prompt, &self.template_vars),
+                },
+            ];
+            runs.push(
+                self.generate_with_usage(model, parts)
+                    .await
+                    .map(|(text, usage)| PromptRun {
+                        text,
+                        usage,
+                        elapsed_ms: started.elapsed().as_millis() as u64,
+                    }),
+            );
+        }
+        let _ = self.delete_file(&file_uri).await;
+        Ok(runs)
+    }
+
+    /// 逾時的請求回報設定的上限，方便調整設定
+    fn request_error(&self, context: &str, e: reqwest::Error) -> String {
+        if e.is_timeout() {
+            format!(
+                "{}: Gemini 回應逾時 (上限 {} 秒，可在設定中調整)",
+                context,
+                self.request_timeout.as_secs()
+            )
+        } else {
+            format!("{}: {}", context, e)
+        }
     }
 
     // 舊的 execute 方法 (保留向後相容)
     #[deprecated(note = "使用 process_folder 替代")]
     pub async fn execute(&self) -> Result<String, String> {
-        println!("(Report) 正在呼叫 Gemini 生成報告 (Service Layer)...");
+        tracing::debug!("(Report) 正在呼叫 Gemini 生成報告 (Service Layer)...");
         Ok("請使用 process_folder 方法".to_string())
     }
 }
+
+/// 依設定建立 HTTP client：連線 / 讀取逾時、HTTP/2 keep-alive 與連線池
+fn build_client(config: &HttpConfig) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(config.connect_timeout_secs))
+        .read_timeout(Duration::from_secs(config.read_timeout_secs))
+        .pool_idle_timeout(Duration::from_secs(config.pool_idle_secs))
+        .tcp_keepalive(Duration::from_secs(60));
+    if config.keep_alive_secs > 0 {
+        builder = builder
+            .http2_keep_alive_interval(Duration::from_secs(config.keep_alive_secs))
+            .http2_keep_alive_timeout(Duration::from_secs(20))
+            .http2_keep_alive_while_idle(true);
+    }
+    builder.build().unwrap_or_else(|e| {
+        tracing::warn!("無法依設定建立 HTTP client，改用預設值: {}", e);
+        reqwest::Client::new()
+    })
+}
+
+/// 使用 Pandoc 將 Markdown 轉換為 DOCX，回傳 DOCX 路徑
+pub async fn convert_md_to_docx(md_path: &str) -> Result<String, String> {
+    // 驗證檔案存在
+    let md_file = Path::new(md_path);
+    if !md_file.exists() {
+        return Err(format!("找不到檔案: {}", md_path));
+    }
+
+    // 產生 DOCX 輸出路徑
+    let docx_path = md_file.with_extension("docx").to_string_lossy().to_string();
+
+    let config = settings::load().docx;
+    let markdown = fs::read_to_string(md_file).map_err(|e| format!("無法讀取報告: {}", e))?;
+    let markdown = if config.page_breaks {
+        insert_page_breaks(&markdown)
+    } else {
+        markdown
+    };
+
+    // 使用 Pandoc 轉換 (調整過的 Markdown 由 stdin 傳入，不改動 report.md)
+    let mut child = tokio::process::Command::new("pandoc")
+        .args(pandoc_args(md_file, &docx_path, &config))
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("無法執行 Pandoc: {}。請確認已安裝 Pandoc。", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(markdown.as_bytes())
+            .await
+            .map_err(|e| format!("無法傳送報告給 Pandoc: {}", e))?;
+    }
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| format!("無法執行 Pandoc: {}。請確認已安裝 Pandoc。", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Pandoc 轉換失敗: {}", stderr));
+    }
+
+    Ok(docx_path)
+}
+
+/// Word 分頁 (Pandoc 的 OpenXML 原始區塊)
+const DOCX_PAGE_BREAK: &str =
+    "```{=openxml}\n<w:p><w:r><w:br w:type=\"page\"/></w:r></w:p>\n```\n\n";
+
+/// 第二個以後的 ## 標題 (每個音檔一節，以及附錄) 前插入分頁
+fn insert_page_breaks(markdown: &str) -> String {
+    let mut out = String::with_capacity(markdown.len());
+    let mut seen_section = false;
+    let mut in_code = false;
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if !in_code && line.starts_with("## ") {
+            if seen_section {
+                out.push_str(DOCX_PAGE_BREAK);
+            }
+            seen_section = true;
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out
+}
+
+/// Pandoc 參數：有目錄或章節編號時，報告的 # 標題改為文件標題，## 音檔段落成為第一層章節
+fn pandoc_args(md_file: &Path, docx_path: &str, config: &DocxConfig) -> Vec<String> {
+    let mut args = vec![
+        "-o".to_string(),
+        docx_path.to_string(),
+        "--from=markdown".to_string(),
+        "--to=docx".to_string(),
+    ];
+    if let Some(dir) = md_file.parent() {
+        args.push(format!("--resource-path={}", dir.display()));
+    }
+    if config.toc || config.number_sections {
+        args.push("--shift-heading-level-by=-1".to_string());
+    }
+    if config.toc {
+        args.push("--toc".to_string());
+        args.push("--metadata=toc-title=目錄".to_string());
+        args.push(format!("--toc-depth={}", config.toc_depth));
+    }
+    if config.number_sections {
+        args.push("--number-sections".to_string());
+    }
+    args.extend(config.pandoc_args.iter().cloned());
+    args
+}
@@ -0,0 +1,322 @@
+// src-tauri/src/services/report_html.rs
+//
+// 把報告 (report.md) 匯出成單一 HTML 檔，供審閱者在瀏覽器中邊讀邊聽。
+//
+// - 每個「個案來源」段落嵌入對應音檔的播放器 (以相對路徑連結，不複製音檔)
+// - 每個段落前有時間錨點 (音檔#t=秒數)，點段落即從該位置播放
+// - 時間依段落在該音檔逐字稿中的字數比例推算，為約略位置
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::encryption;
+use crate::services::file_manager::{write_atomic, ProjectPaths};
+use crate::services::probe;
+use crate::services::report;
+use pulldown_cmark::{html, Options, Parser};
+use std::path::{Component, Path, PathBuf};
+
+/// 報告中每個音檔段落的標題前綴
+pub(crate) const SOURCE_HEADING: &str = "## 【個案來源：";
+
+const STYLE: &str = r#"
+body { font-family: "Noto Sans TC", "Microsoft JhengHei", sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; line-height: 1.7; color: #222; }
+section.source { border-top: 1px solid #ddd; padding-top: 1em; }
+section.source audio { width: 100%; position: sticky; top: 0; background: #fff; z-index: 1; }
+.para { display: flex; gap: 0.75em; cursor: pointer; border-radius: 4px; }
+.para:hover { background: #f3f7ff; }
+.para.playing { background: #e3edff; }
+.para a.time { flex: none; font-family: monospace; color: #2a62c9; text-decoration: none; padding-top: 1em; }
+.para .body { flex: 1; }
+table { border-collapse: collapse; }
+td, th { border: 1px solid #ccc; padding: 0.25em 0.5em; }
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll('.para[data-start]').forEach(function (para) {
+  para.addEventListener('click', function (event) {
+    var audio = document.getElementById(para.dataset.audio);
+    if (!audio) return;
+    event.preventDefault();
+    document.querySelectorAll('.para.playing').forEach(function (p) { p.classList.remove('playing'); });
+    para.classList.add('playing');
+    audio.currentTime = parseFloat(para.dataset.start);
+    audio.play();
+  });
+});
+"#;
+
+/// 報告中的一個區塊 (標題前的內容或某個音檔的段落)
+struct Section {
+    /// 音檔名稱 (標題前的內容為 None)
+    source: Option<String>,
+    heading: String,
+    body: String,
+}
+
+/// 依「個案來源」標題切開報告；其他 ## 標題 (附錄) 自成一段
+fn split_sections(markdown: &str) -> Vec<Section> {
+    let mut sections = vec![Section {
+        source: None,
+        heading: String::new(),
+        body: String::new(),
+    }];
+    for line in markdown.lines() {
+        if line.starts_with("## ") {
+            let source = line
+                .strip_prefix(SOURCE_HEADING)
+                .map(|rest| rest.trim_end().trim_end_matches('】').to_string());
+            sections.push(Section {
+                source,
+                heading: line.to_string(),
+                body: String::new(),
+            });
+        } else if let Some(section) = sections.last_mut() {
+            section.body.push_str(line);
+            section.body.push('\n');
+        }
+    }
+    sections
+}
+
+fn render_markdown(markdown: &str) -> String {
+    let mut out = String::new();
+    html::push_html(
+        &mut out,
+        Parser::new_ext(
+            markdown,
+            Options::ENABLE_TABLES | Options::ENABLE_STRIKETHROUGH,
+        ),
+    );
+    out
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 路徑轉成 URL (保留中文，只編碼會破壞連結的字元)；絕對路徑使用 file:///
+fn url_path(path: &Path) -> String {
+    let joined = path
+        .components()
+        .filter(|c| !matches!(c, Component::RootDir))
+        .map(|c| {
+            c.as_os_str()
+                .to_string_lossy()
+                .chars()
+                .map(|ch| match ch {
+                    ' ' => "%20".to_string(),
+                    '#' => "%23".to_string(),
+                    '?' => "%3F".to_string(),
+                    '%' => "%25".to_string(),
+                    '"' => "%22".to_string(),
+                    _ => ch.to_string(),
+                })
+                .collect::<String>()
+        })
+        .collect::<Vec<_>>()
+        .join("/");
+    if path.is_absolute() {
+        format!("file:///{}", joined)
+    } else {
+        joined
+    }
+}
+
+fn format_time(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (h, m, s) = (total / 3600, total / 60 % 60, total % 60);
+    if h > 0 {
+        format!("{}:{:02}:{:02}", h, m, s)
+    } else {
+        format!("{:02}:{:02}", m, s)
+    }
+}
+
+/// 段落是否為可對應音檔的文字 (標題、分隔線、提示區塊不算)
+pub(crate) fn is_spoken(block: &str) -> bool {
+    let trimmed = block.trim_start();
+    !(trimmed.starts_with('#')
+        || trimmed.starts_with('>')
+        || trimmed.starts_with('|')
+        || trimmed.starts_with("---"))
+}
+
+/// 一個音檔段落的 HTML：播放器加上可點擊的段落
+/// 段落時間以字數比例推算；雙語報告的每個小節 (### 標題) 各自從頭計算
+fn render_source(idx: usize, section: &Section, audio: Option<(String, f64)>) -> String {
+    let audio_id = format!("audio-{}", idx);
+    let mut out = format!(
+        "<section class=\"source\" id=\"source-{}\">\n{}",
+        idx,
+        render_markdown(&section.heading)
+    );
+    let Some((src, duration)) = audio else {
+        out.push_str("<p><em>找不到對應的音檔</em></p>\n");
+        out.push_str(&render_markdown(&section.body));
+        out.push_str("</section>\n");
+        return out;
+    };
+    out.push_str(&format!(
+        "<audio id=\"{}\" controls preload=\"metadata\" src=\"{}\"></audio>\n",
+        audio_id,
+        escape(&src)
+    ));
+
+    let blocks: Vec<&str> = section
+        .body
+        .split("\n\n")
+        .filter(|b| !b.trim().is_empty())
+        .collect();
+    // 依 ### 小節分組，計算每組的總字數
+    let mut groups: Vec<Vec<&str>> = vec![Vec::new()];
+    for block in blocks {
+        if block.trim_start().starts_with("### ") {
+            groups.push(Vec::new());
+        }
+        if let Some(group) = groups.last_mut() {
+            group.push(block);
+        }
+    }
+    for group in groups {
+        let total: usize = group
+            .iter()
+            .filter(|b| is_spoken(b))
+            .map(|b| b.chars().count())
+            .sum();
+        let mut offset = 0usize;
+        for block in group {
+            if !is_spoken(block) || total == 0 {
+                out.push_str(&render_markdown(block));
+                continue;
+            }
+            let start = duration * offset as f64 / total as f64;
+            offset += block.chars().count();
+            out.push_str(&format!(
+                "<div class=\"para\" data-audio=\"{}\" data-start=\"{:.1}\"><a class=\"time\" href=\"{}#t={:.1}\">{}</a><div class=\"body\">{}</div></div>\n",
+                audio_id,
+                start,
+                escape(&src),
+                start,
+                format_time(start),
+                render_markdown(block)
+            ));
+        }
+    }
+    out.push_str("</section>\n");
+    out
+}
+
+/// 報告對應的音檔資料夾：report.json 有記錄時使用，否則 04_report 對應 02_split，其他為報告所在資料夾
+fn source_folder(paths: &ProjectPaths, report_path: &Path) -> PathBuf {
+    if let Some(folder) = report::load_summary(report_path)
+        .map(|s| s.folder)
+        .filter(|f| !f.is_empty())
+    {
+        return PathBuf::from(folder);
+    }
+    let report_dir = report_path.parent().unwrap_or(Path::new("."));
+    if report_dir == paths.report {
+        paths.split.clone()
+    } else {
+        report_dir.to_path_buf()
+    }
+}
+
+/// 專案中最新的報告 (04_report 或 03_silence 中的 report.md)
+fn latest_report(paths: &ProjectPaths) -> Option<PathBuf> {
+    [
+        paths.report.join("report.md"),
+        paths.silence.join("report.md"),
+    ]
+    .into_iter()
+    .filter(|p| p.is_file())
+    .max_by_key(|p| p.metadata().and_then(|m| m.modified()).ok())
+}
+
+/// 匯出 HTML 報告 (與 report.md 同名的 .html)，回傳輸出路徑
+/// report_path 未指定時使用專案中最新的報告
+pub fn export(root: &Path, report_path: Option<PathBuf>) -> Result<PathBuf, AppError> {
+    if encryption::is_enabled(root) {
+        return Err(AppError::localized(
+            ErrorKind::Unsupported,
+            "error.html_export_encrypted",
+            &[],
+        ));
+    }
+    let paths = ProjectPaths::from_existing_root(root.to_path_buf());
+    let report_path = report_path
+        .or_else(|| latest_report(&paths))
+        .filter(|p| p.is_file())
+        .ok_or_else(|| {
+            AppError::localized(
+                ErrorKind::NotFound,
+                "error.report_not_found",
+                &[("path", root.display().to_string())],
+            )
+        })?;
+    let markdown = std::fs::read_to_string(&report_path)?;
+    let report_dir = report_path.parent().unwrap_or(Path::new("."));
+    let folder = source_folder(&paths, &report_path);
+
+    let title = markdown
+        .lines()
+        .find_map(|l| l.strip_prefix("# "))
+        .unwrap_or("Report")
+        .trim()
+        .to_string();
+    let mut body = String::new();
+    for (idx, section) in split_sections(&markdown).iter().enumerate() {
+        match &section.source {
+            Some(source) => {
+                let audio_path = folder.join(source);
+                let audio = audio_path.is_file().then(|| {
+                    let relative = pathdiff(&audio_path, report_dir);
+                    let duration =
+                        probe::cached_duration(&audio_path.to_string_lossy()).unwrap_or(0.0);
+                    (url_path(&relative), duration)
+                });
+                body.push_str(&render_source(idx, section, audio));
+            }
+            None => {
+                body.push_str(&render_markdown(&section.heading));
+                body.push_str(&render_markdown(&section.body));
+            }
+        }
+    }
+
+    let document = format!(
+        "<!DOCTYPE html>\n<html lang=\"zh-Hant\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}<script>{}</script>\n</body>\n</html>\n",
+        escape(&title),
+        STYLE,
+        body,
+        SCRIPT
+    );
+    let output = report_path.with_extension("html");
+    write_atomic(&output, document.as_bytes())?;
+    Ok(output)
+}
+
+/// path 相對於 base 的路徑 (兩者在同一專案內)
+fn pathdiff(path: &Path, base: &Path) -> PathBuf {
+    let path_parts: Vec<_> = path.components().collect();
+    let base_parts: Vec<_> = base.components().collect();
+    let common = path_parts
+        .iter()
+        .zip(base_parts.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        // 不同磁碟機，只能使用絕對路徑
+        return path.to_path_buf();
+    }
+    let mut relative = PathBuf::new();
+    for _ in common..base_parts.len() {
+        relative.push("..");
+    }
+    for part in &path_parts[common..] {
+        relative.push(part);
+    }
+    relative
+}
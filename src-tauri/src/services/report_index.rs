@@ -0,0 +1,225 @@
+// src-tauri/src/services/report_index.rs
+//
+// 報告段落與音檔時間的對應索引 (寫入 report.json 的 paragraphs)，供檢視器點段落播放。
+//
+// - 報告每個「個案來源」段落內的文字區塊 (以空行分隔) 對應到該音檔的約略時間範圍
+// - 長檔分段聽寫時，依每段 (chunk) 的時間範圍與在內文中的位置定位，不會跨段
+// - 有該音檔的逐字稿時，段內依逐字稿的字數分布換算時間 (說話快慢不均時較準確)；
+//   沒有時依字數比例平均分配
+// - 雙語報告的英文翻譯依相同比例對應 (與中文各自從頭計算)
+
+use crate::services::report::ReportSection;
+use crate::services::report_html::{is_spoken, SOURCE_HEADING};
+use crate::services::silence::TranscribeResponse;
+use serde::{Deserialize, Serialize};
+
+/// 雙語報告中英文翻譯的小節標題
+const TRANSLATION_HEADING: &str = "### English Translation";
+
+/// 長檔分段聽寫時一段的時間範圍與在內文中的起點 (字元數)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ChunkSpan {
+    pub start: f64,
+    pub end: f64,
+    pub offset: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParagraphTiming {
+    /// 依字數比例推算
+    Estimate,
+    /// 依逐字稿時間推算
+    Transcript,
+}
+
+/// 報告中的一個段落與對應的音檔時間
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ParagraphLink {
+    /// 段落第一行在 report.md 的行號 (從 1 開始)
+    pub line: usize,
+    /// 音檔名稱 (與個案來源相同)
+    pub file: String,
+    /// 約略的開始與結束時間 (秒)
+    pub start: f64,
+    pub end: f64,
+    /// 是否為雙語報告的英文翻譯
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub translation: bool,
+    pub timing: ParagraphTiming,
+}
+
+/// 音檔段落中以空行分隔的文字區塊
+struct Block {
+    line: usize,
+    text: String,
+    translation: bool,
+}
+
+/// 依「個案來源」標題切開報告，回傳 (檔名, 區塊)
+fn source_blocks(markdown: &str) -> Vec<(String, Vec<Block>)> {
+    let mut sources: Vec<(String, Vec<Block>)> = Vec::new();
+    let mut in_source = false;
+    let mut translation = false;
+    let mut current: Option<Block> = None;
+    let flush = |sources: &mut Vec<(String, Vec<Block>)>, block: &mut Option<Block>| {
+        if let (Some((_, blocks)), Some(block)) = (sources.last_mut(), block.take()) {
+            blocks.push(block);
+        }
+    };
+    for (idx, line) in markdown.lines().enumerate() {
+        if line.starts_with("## ") {
+            flush(&mut sources, &mut current);
+            in_source = match line.strip_prefix(SOURCE_HEADING) {
+                Some(rest) => {
+                    let file = rest.trim_end().trim_end_matches('】').to_string();
+                    sources.push((file, Vec::new()));
+                    true
+                }
+                None => false,
+            };
+            translation = false;
+            continue;
+        }
+        if !in_source {
+            continue;
+        }
+        if line.trim().is_empty() {
+            flush(&mut sources, &mut current);
+            continue;
+        }
+        if line.starts_with(TRANSLATION_HEADING) {
+            translation = true;
+        }
+        match current.as_mut() {
+            Some(block) => {
+                block.text.push('\n');
+                block.text.push_str(line);
+            }
+            None => {
+                current = Some(Block {
+                    line: idx + 1,
+                    text: line.to_string(),
+                    translation,
+                })
+            }
+        }
+    }
+    flush(&mut sources, &mut current);
+    sources
+}
+
+/// 逐字稿在 start..end 之間的段落 (開始時間, 結束時間, 字數)
+fn transcript_window(
+    transcript: &TranscribeResponse,
+    start: f64,
+    end: f64,
+) -> Vec<(f64, f64, usize)> {
+    transcript
+        .segments
+        .iter()
+        .filter(|s| s.end > start && s.start < end)
+        .map(|s| (s.start.max(start), s.end.min(end), s.text.chars().count()))
+        .filter(|(_, _, chars)| *chars > 0)
+        .collect()
+}
+
+/// 內文位置 (0.0 ~ 1.0) 換算為音檔時間
+fn time_at(
+    fraction: f64,
+    chunks: &[ChunkSpan],
+    text_len: usize,
+    transcript: Option<&TranscribeResponse>,
+) -> (f64, bool) {
+    let position = fraction.clamp(0.0, 1.0) * text_len as f64;
+    let idx = chunks
+        .iter()
+        .rposition(|c| c.offset as f64 <= position)
+        .unwrap_or(0);
+    let Some(chunk) = chunks.get(idx) else {
+        return (0.0, false);
+    };
+    let chunk_end = chunks.get(idx + 1).map(|c| c.offset).unwrap_or(text_len);
+    let chunk_len = chunk_end.saturating_sub(chunk.offset).max(1) as f64;
+    let local = ((position - chunk.offset as f64) / chunk_len).clamp(0.0, 1.0);
+
+    if let Some(transcript) = transcript {
+        let window = transcript_window(transcript, chunk.start, chunk.end);
+        let total: usize = window.iter().map(|(_, _, chars)| chars).sum();
+        if total > 0 {
+            let target = local * total as f64;
+            let mut seen = 0.0;
+            for (start, end, chars) in &window {
+                let chars = *chars as f64;
+                if seen + chars >= target {
+                    return (start + (end - start) * (target - seen) / chars, true);
+                }
+                seen += chars;
+            }
+            return (
+                window.last().map(|(_, end, _)| *end).unwrap_or(chunk.end),
+                true,
+            );
+        }
+    }
+    (chunk.start + (chunk.end - chunk.start) * local, false)
+}
+
+/// 建立報告段落索引；transcript_for 依檔名取得逐字稿 (沒有時回傳 None)
+pub fn build(
+    markdown: &str,
+    sections: &[ReportSection],
+    transcript_for: impl Fn(&str) -> Option<TranscribeResponse>,
+) -> Vec<ParagraphLink> {
+    let mut links = Vec::new();
+    for (file, blocks) in source_blocks(markdown) {
+        let Some(section) = sections.iter().find(|s| s.file == file) else {
+            continue;
+        };
+        if section.chunks.is_empty() {
+            continue;
+        }
+        let transcript = transcript_for(&file);
+        let text_len = section.text.chars().count();
+        for translation in [false, true] {
+            let spoken: Vec<&Block> = blocks
+                .iter()
+                .filter(|b| b.translation == translation && is_spoken(&b.text))
+                .collect();
+            let total: usize = spoken.iter().map(|b| b.text.chars().count()).sum();
+            if total == 0 {
+                continue;
+            }
+            let mut offset = 0usize;
+            for block in spoken {
+                let len = block.text.chars().count();
+                let (start, by_transcript) = time_at(
+                    offset as f64 / total as f64,
+                    &section.chunks,
+                    text_len,
+                    transcript.as_ref(),
+                );
+                offset += len;
+                let (end, _) = time_at(
+                    offset as f64 / total as f64,
+                    &section.chunks,
+                    text_len,
+                    transcript.as_ref(),
+                );
+                links.push(ParagraphLink {
+                    line: block.line,
+                    file: file.clone(),
+                    start,
+                    end: end.max(start),
+                    translation,
+                    timing: if by_transcript {
+                        ParagraphTiming::Transcript
+                    } else {
+                        ParagraphTiming::Estimate
+                    },
+                });
+            }
+        }
+    }
+    links
+}
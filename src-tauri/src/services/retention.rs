@@ -0,0 +1,327 @@
+// src-tauri/src/services/retention.rs
+//
+// 保存期限：依專案的 retention.json 自動刪除過期的檔案
+// (例如報告完成 30 天後刪除 01_converted 的原始錄音)，滿足儲存空間與隱私規範。
+//
+// - 每條規則指定階段資料夾與天數，可要求「已產生報告」才刪除
+// - 已有報告時天數從最新報告的修改時間起算 (報告之後才修改的檔案從檔案的修改時間起算)
+// - preview 先列出將刪除的檔案 (刪除前報告)，purge 才實際刪除
+// - 背景執行緒每小時檢查專案根目錄下所有啟用保存期限的專案，略過有工作排隊或執行中的專案
+
+use crate::services::file_manager::{write_atomic, ProjectPaths};
+use crate::services::history;
+use crate::services::jobs::JobManager;
+use crate::services::viewer;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const RETENTION_FILE_NAME: &str = "retention.json";
+
+/// 自動刪除完成時發出的事件 (payload 為 PurgeResult)
+pub const PURGED_EVENT: &str = "retention://purged";
+
+/// 檢查間隔
+const TICK: Duration = Duration::from_secs(60 * 60);
+
+/// 單一階段的保存規則
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionRule {
+    /// 階段資料夾 (01_converted、02_split、03_silence、04_report)
+    pub stage: String,
+    /// 報告完成 (沒有報告時為檔案修改) 後保留的天數，至少 1 天
+    pub days: u32,
+    /// 專案已有報告時才刪除
+    #[serde(default = "default_true")]
+    pub require_report: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// 啟用自動刪除 (停用時仍可手動預覽與刪除)
+    pub enabled: bool,
+    pub rules: Vec<RetentionRule>,
+}
+
+/// 將被刪除的檔案
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeCandidate {
+    pub path: String,
+    pub stage: String,
+    pub size: u64,
+    /// 距報告完成或檔案修改 (取較晚者) 的天數
+    pub age_days: u64,
+}
+
+/// 刪除前報告
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgePlan {
+    pub project: String,
+    pub has_report: bool,
+    pub files: Vec<PurgeCandidate>,
+    pub total_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeResult {
+    pub project: String,
+    pub removed: Vec<String>,
+    pub freed_bytes: u64,
+    pub failed: Vec<String>,
+}
+
+fn policy_path(root: &Path) -> PathBuf {
+    root.join(RETENTION_FILE_NAME)
+}
+
+/// 讀取專案的保存期限設定，未設定時為空 (不刪除任何檔案)
+pub fn load(root: &Path) -> RetentionPolicy {
+    fs::read_to_string(policy_path(root))
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+pub fn save(root: &Path, policy: &RetentionPolicy) -> Result<(), String> {
+    let paths = ProjectPaths::from_existing_root(root.to_path_buf());
+    for rule in &policy.rules {
+        paths.stage_dir(&rule.stage)?;
+        if rule.days == 0 {
+            return Err(format!("{} 的保留天數必須至少 1 天", rule.stage));
+        }
+    }
+    let content = serde_json::to_vec_pretty(policy).map_err(|e| e.to_string())?;
+    write_atomic(&policy_path(root), &content).map_err(|e| format!("無法寫入保存期限設定: {}", e))
+}
+
+/// 04_report 內最新報告的修改時間 (沒有報告時為 None)
+fn report_time(paths: &ProjectPaths) -> Option<SystemTime> {
+    fs::read_dir(&paths.report)
+        .ok()?
+        .flatten()
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            [".md", ".docx", ".md.enc", ".docx.enc"]
+                .iter()
+                .any(|ext| name.ends_with(ext))
+        })
+        .filter_map(|e| e.metadata().and_then(|m| m.modified()).ok())
+        .max()
+}
+
+fn age_days(modified: SystemTime) -> u64 {
+    SystemTime::now()
+        .duration_since(modified)
+        .map(|d| d.as_secs() / 86_400)
+        .unwrap_or(0)
+}
+
+/// 依設定列出將被刪除的檔案
+pub fn preview(root: &Path) -> Result<PurgePlan, String> {
+    let policy = load(root);
+    let paths = ProjectPaths::from_existing_root(root.to_path_buf());
+    let report_time = report_time(&paths);
+    let has_report = report_time.is_some();
+    let mut files = Vec::new();
+    for rule in &policy.rules {
+        // 天數為 0 的規則 (舊版設定) 不刪除任何檔案
+        if rule.days == 0 || (rule.require_report && !has_report) {
+            continue;
+        }
+        let dir = paths.stage_dir(&rule.stage)?;
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            if !metadata.is_file() {
+                continue;
+            }
+            let Ok(modified) = metadata.modified() else {
+                continue;
+            };
+            let age = age_days(report_time.map_or(modified, |report| report.max(modified)));
+            if age >= rule.days as u64 {
+                files.push(PurgeCandidate {
+                    path: entry.path().to_string_lossy().to_string(),
+                    stage: rule.stage.clone(),
+                    size: metadata.len(),
+                    age_days: age,
+                });
+            }
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    files.dedup_by(|a, b| a.path == b.path);
+    Ok(PurgePlan {
+        project: root.to_string_lossy().to_string(),
+        has_report,
+        total_bytes: files.iter().map(|f| f.size).sum(),
+        files,
+    })
+}
+
+/// 刪除過期的檔案並寫入專案操作紀錄
+pub fn purge(root: &Path) -> Result<PurgeResult, String> {
+    let started = Instant::now();
+    let plan = preview(root)?;
+    let mut result = PurgeResult {
+        project: plan.project.clone(),
+        removed: Vec::new(),
+        freed_bytes: 0,
+        failed: Vec::new(),
+    };
+    for file in plan.files {
+        match fs::remove_file(&file.path) {
+            Ok(()) => {
+                result.freed_bytes += file.size;
+                result.removed.push(file.path);
+            }
+            Err(e) => result.failed.push(format!("{}: {}", file.path, e)),
+        }
+    }
+    if !result.removed.is_empty() || !result.failed.is_empty() {
+        let removed: Vec<PathBuf> = result.removed.iter().map(PathBuf::from).collect();
+        history::record_command(
+            root,
+            "retention_purge",
+            json!({ "freed_bytes": result.freed_bytes, "failed": result.failed }),
+            &removed,
+            started,
+            &Ok::<(), crate::models::AppError>(()),
+        );
+    }
+    Ok(result)
+}
+
+/// 專案根目錄下啟用自動刪除的專案
+fn scheduled_projects() -> Vec<PathBuf> {
+    let Ok(base) = ProjectPaths::root_base() else {
+        return Vec::new();
+    };
+    let Ok(entries) = fs::read_dir(base) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|root| policy_path(root).is_file() && load(root).enabled)
+        .filter(|root| !viewer::is_read_only(root))
+        .collect()
+}
+
+/// 啟動背景檢查執行緒 (啟動時先檢查一次)
+pub fn start(app: AppHandle) {
+    let result = std::thread::Builder::new()
+        .name("retention".to_string())
+        .spawn(move || loop {
+            let jobs = app.state::<JobManager>();
+            for root in scheduled_projects() {
+                // 工作可能正在讀寫將被刪除的檔案，下次檢查再處理
+                if jobs.has_active_jobs(&root) {
+                    tracing::info!(
+                        "專案有工作排隊或執行中，略過保存期限檢查: {}",
+                        root.display()
+                    );
+                    continue;
+                }
+                match purge(&root) {
+                    Ok(result) if !result.removed.is_empty() => {
+                        tracing::info!(
+                            "已依保存期限刪除 {} 個檔案: {}",
+                            result.removed.len(),
+                            root.display()
+                        );
+                        let _ = app.emit(PURGED_EVENT, &result);
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("保存期限檢查失敗 {}: {}", root.display(), e),
+                }
+            }
+            std::thread::sleep(TICK);
+        });
+    if let Err(e) = result {
+        tracing::error!("無法啟動保存期限執行緒: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    fn temp_project(name: &str) -> PathBuf {
+        let root =
+            std::env::temp_dir().join(format!("stt-retention-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("01_converted")).unwrap();
+        fs::create_dir_all(root.join("04_report")).unwrap();
+        root
+    }
+
+    fn write_aged(path: &Path, days: u64) {
+        fs::write(path, b"data").unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - DAY * days as u32)
+            .unwrap();
+    }
+
+    fn converted_rule(days: u32) -> RetentionRule {
+        RetentionRule {
+            stage: "01_converted".into(),
+            days,
+            require_report: true,
+        }
+    }
+
+    #[test]
+    fn save_rejects_zero_days() {
+        let root = temp_project("zero");
+        let policy = RetentionPolicy {
+            enabled: true,
+            rules: vec![converted_rule(0)],
+        };
+        assert!(save(&root, &policy).is_err());
+        assert!(!policy_path(&root).exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn age_counts_from_the_report() {
+        let root = temp_project("age");
+        let policy = RetentionPolicy {
+            enabled: true,
+            rules: vec![converted_rule(30)],
+        };
+        save(&root, &policy).unwrap();
+        let recording = root.join("01_converted").join("a.mp3");
+        let report = root.join("04_report").join("report.md");
+
+        // 錄音很舊，但報告剛完成
+        write_aged(&recording, 60);
+        write_aged(&report, 0);
+        assert!(preview(&root).unwrap().files.is_empty());
+
+        // 報告完成 40 天後
+        write_aged(&report, 40);
+        let plan = preview(&root).unwrap();
+        assert_eq!(plan.files.len(), 1);
+        assert_eq!(plan.files[0].age_days, 40);
+
+        // 報告之後才修改的檔案從自己的修改時間起算
+        write_aged(&recording, 10);
+        assert!(preview(&root).unwrap().files.is_empty());
+        let _ = fs::remove_dir_all(&root);
+    }
+}
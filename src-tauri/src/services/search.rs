@@ -0,0 +1,150 @@
+// src-tauri/src/services/search.rs
+//
+// 專案內全文搜尋：逐字稿 JSON (.silence_reg)、報告 (04_report/*.md) 與段落檔名
+
+use crate::services::file_manager::{ProjectPaths, TRANSCRIPT_DIR};
+use crate::services::silence::TranscribeResponse;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// 搜尋結果前後保留的字數
+const SNIPPET_CONTEXT_CHARS: usize = 30;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchHit {
+    /// 命中所在的檔案
+    pub file: String,
+    /// "transcript" | "report" | "segment"
+    pub kind: String,
+    /// 命中位置 (字元偏移量，以該段文字為基準)
+    pub offset: usize,
+    /// 對應音檔的時間點 (秒)，僅逐字稿有
+    pub timestamp: Option<f64>,
+    /// 對應的音檔名稱，僅逐字稿有
+    pub audio_file: Option<String>,
+    /// 命中前後的文字片段
+    pub snippet: String,
+}
+
+pub fn search_project(root: &Path, query: &str) -> Result<Vec<SearchHit>, String> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Err("搜尋關鍵字不能為空".to_string());
+    }
+    if !root.is_dir() {
+        return Err(format!("專案目錄不存在: {}", root.display()));
+    }
+
+    let paths = ProjectPaths::from_existing_root(root.to_path_buf());
+    let mut hits = Vec::new();
+
+    search_transcripts(&root.join(TRANSCRIPT_DIR), &query, &mut hits);
+    search_reports(&paths.report, &query, &mut hits);
+    for dir in [&paths.split, &paths.silence] {
+        search_segment_names(dir, &query, &mut hits);
+    }
+
+    Ok(hits)
+}
+
+fn search_transcripts(dir: &Path, query: &str, hits: &mut Vec<SearchHit>) {
+    for path in files_with_extension(dir, "json") {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let Ok(transcript) = serde_json::from_str::<TranscribeResponse>(&content) else {
+            continue;
+        };
+        for segment in &transcript.segments {
+            for offset in find_all(&segment.text, query) {
+                hits.push(SearchHit {
+                    file: path.to_string_lossy().to_string(),
+                    kind: "transcript".to_string(),
+                    offset,
+                    timestamp: Some(segment.start),
+                    audio_file: Some(transcript.filename.clone()),
+                    snippet: snippet(&segment.text, offset, query.chars().count()),
+                });
+            }
+        }
+    }
+}
+
+fn search_reports(dir: &Path, query: &str, hits: &mut Vec<SearchHit>) {
+    for path in files_with_extension(dir, "md") {
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for offset in find_all(&content, query) {
+            hits.push(SearchHit {
+                file: path.to_string_lossy().to_string(),
+                kind: "report".to_string(),
+                offset,
+                timestamp: None,
+                audio_file: None,
+                snippet: snippet(&content, offset, query.chars().count()),
+            });
+        }
+    }
+}
+
+fn search_segment_names(dir: &Path, query: &str, hits: &mut Vec<SearchHit>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if !path.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(offset) = find_all(&name, query).into_iter().next() {
+            hits.push(SearchHit {
+                file: path.to_string_lossy().to_string(),
+                kind: "segment".to_string(),
+                offset,
+                timestamp: None,
+                audio_file: None,
+                snippet: name,
+            });
+        }
+    }
+}
+
+fn files_with_extension(dir: &Path, ext: &str) -> Vec<std::path::PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<_> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.extension()
+                    .map(|e| e.to_string_lossy().eq_ignore_ascii_case(ext))
+                    .unwrap_or(false)
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+/// 不分大小寫找出所有命中的字元偏移量 (query 需已轉為小寫)
+fn find_all(text: &str, query: &str) -> Vec<usize> {
+    let lower = text.to_lowercase();
+    lower
+        .match_indices(query)
+        .map(|(idx, _)| lower[..idx].chars().count())
+        .collect()
+}
+
+fn snippet(text: &str, char_offset: usize, match_len: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let start = char_offset.saturating_sub(SNIPPET_CONTEXT_CHARS);
+    let end = (char_offset + match_len + SNIPPET_CONTEXT_CHARS).min(chars.len());
+    chars[start.min(end)..end]
+        .iter()
+        .collect::<String>()
+        .replace('\n', " ")
+}
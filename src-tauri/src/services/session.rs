@@ -0,0 +1,132 @@
+// src-tauri/src/services/session.rs
+//
+// 視窗工作階段：記錄每個視窗最後開啟的專案與頁面 (保存在 app data 的 session.json)，
+// 下次啟動時重新開啟上次的視窗並恢復狀態。視窗大小與位置由 window-state plugin 保存。
+
+use crate::services::file_manager::write_atomic;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+
+pub const SESSION_FILE_NAME: &str = "session.json";
+
+/// 主視窗 (tauri.conf.json 建立，永遠存在)
+pub const MAIN_WINDOW_LABEL: &str = "main";
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct WindowSession {
+    pub label: String,
+    pub project_root: Option<String>,
+    /// 前端頁面 (convert / split / silence / ...)
+    pub page: Option<String>,
+}
+
+pub struct SessionState {
+    path: Option<PathBuf>,
+    windows: Mutex<Vec<WindowSession>>,
+}
+
+impl SessionState {
+    /// 讀取上次保存的工作階段，檔案不存在或格式錯誤時為空
+    pub fn load(path: Option<PathBuf>) -> Self {
+        let windows = path
+            .as_ref()
+            .and_then(|p| fs::read_to_string(p).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            windows: Mutex::new(windows),
+        }
+    }
+
+    pub fn get(&self, label: &str) -> Option<WindowSession> {
+        self.windows
+            .lock()
+            .ok()?
+            .iter()
+            .find(|w| w.label == label)
+            .cloned()
+    }
+
+    /// 更新視窗狀態 (不存在時新增) 並保存
+    pub fn update(&self, label: &str, f: impl FnOnce(&mut WindowSession)) {
+        let Ok(mut windows) = self.windows.lock() else {
+            return;
+        };
+        let index = match windows.iter().position(|w| w.label == label) {
+            Some(index) => index,
+            None => {
+                windows.push(WindowSession {
+                    label: label.to_string(),
+                    ..Default::default()
+                });
+                windows.len() - 1
+            }
+        };
+        f(&mut windows[index]);
+        self.save(&windows);
+    }
+
+    /// 使用者關閉視窗時移除，下次啟動不再開啟
+    pub fn remove(&self, label: &str) {
+        if let Ok(mut windows) = self.windows.lock() {
+            windows.retain(|w| w.label != label);
+            self.save(&windows);
+        }
+    }
+
+    /// 各視窗上次開啟的專案
+    pub fn project_roots(&self) -> Vec<String> {
+        self.windows
+            .lock()
+            .map(|windows| {
+                windows
+                    .iter()
+                    .filter_map(|w| w.project_root.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// 需要重新開啟的視窗 (主視窗除外)
+    pub fn extra_windows(&self) -> Vec<String> {
+        self.windows
+            .lock()
+            .map(|windows| {
+                windows
+                    .iter()
+                    .filter(|w| w.label != MAIN_WINDOW_LABEL)
+                    .map(|w| w.label.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn save(&self, windows: &[WindowSession]) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_string_pretty(windows)
+            .map_err(|e| e.to_string())
+            .and_then(|content| write_atomic(path, content.as_bytes()).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            tracing::error!("無法保存視窗狀態: {}", e);
+        }
+    }
+}
+
+/// 建立專案視窗；restore 為 false 時開啟歡迎頁，true 時由前端讀取工作階段恢復
+pub fn create_window(app: &AppHandle, label: &str, restore: bool) -> tauri::Result<WebviewWindow> {
+    let url = if restore {
+        "index.html"
+    } else {
+        "index.html?page=welcome"
+    };
+    WebviewWindowBuilder::new(app, label, WebviewUrl::App(url.into()))
+        .title("STT Agent")
+        .inner_size(1280.0, 800.0)
+        .build()
+}
@@ -0,0 +1,645 @@
+// src-tauri/src/services/settings.rs
+//
+// 應用程式設定 (config.json)：STT 伺服器、預設模型、外觀、語言、
+// 背景工作數量、FFmpeg 轉檔品質、預設專案路徑、完成通知的 webhook 與桌面 / 電子郵件通知、
+// 降噪前處理、輸出檔同名時的處理方式。
+
+use crate::services::file_manager::{write_atomic, OutputCollision};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// 設定變更時發出的事件 (payload 為新的 AppConfig)
+pub const SETTINGS_CHANGED_EVENT: &str = "settings://changed";
+
+/// 背景工作同時執行數量上限
+pub const MAX_CONCURRENT_JOBS_LIMIT: usize = 8;
+
+/// 整批轉錄同時上傳數的上限 (避免壓垮 STT 伺服器)
+pub const MAX_STT_PARALLEL_UPLOADS: usize = 8;
+
+/// 每個專案保留的自動備份份數上限
+pub const MAX_BACKUPS_LIMIT: usize = 200;
+
+/// FFmpeg 執行緒數量上限
+const MAX_FFMPEG_THREADS: u32 = 64;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+/// FFmpeg 轉檔品質
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FfmpegPreset {
+    /// 128 kbps，檔案較小
+    Compact,
+    /// 192 kbps
+    #[default]
+    Standard,
+    /// 320 kbps
+    High,
+}
+
+impl FfmpegPreset {
+    pub fn bitrate_kbps(self) -> u32 {
+        match self {
+            FfmpegPreset::Compact => 128,
+            FfmpegPreset::Standard => 192,
+            FfmpegPreset::High => 320,
+        }
+    }
+}
+
+/// 一個階段的輸出編碼 (MP3)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StageEncoding {
+    pub bitrate_kbps: u32,
+    /// 轉為單聲道
+    #[serde(default)]
+    pub mono: bool,
+    /// 取樣率 (Hz)，未指定時轉檔為 44100，其他階段保留原本的取樣率
+    #[serde(default)]
+    pub sample_rate: Option<u32>,
+}
+
+impl StageEncoding {
+    pub fn from_preset(preset: FfmpegPreset) -> Self {
+        Self {
+            bitrate_kbps: preset.bitrate_kbps(),
+            mono: false,
+            sample_rate: None,
+        }
+    }
+
+    fn validate(&self, stage: &str) -> Result<(), String> {
+        if !(8..=320).contains(&self.bitrate_kbps) {
+            return Err(crate::tr!("settings.bitrate_range", stage = stage));
+        }
+        if let Some(rate) = self.sample_rate {
+            if !(8000..=48000).contains(&rate) {
+                return Err(crate::tr!("settings.sample_rate_range", stage = stage));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 各階段的輸出編碼 (例如 01_converted 以 192k 保存、上傳用的副本 48k 單聲道)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EncodingConfig {
+    /// 01_converted (未設定時依 ffmpeg_preset)
+    pub converted: Option<StageEncoding>,
+    /// 02_split (未設定時直接複製，不重新編碼)
+    pub split: Option<StageEncoding>,
+    /// 上傳到 Gemini 前先壓縮 (未設定時上傳原檔)
+    pub upload: Option<StageEncoding>,
+}
+
+/// 全域快捷鍵 (在其他程式中也能控制播放)，格式例如 "CommandOrControl+Alt+Space"
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutConfig {
+    pub enabled: bool,
+    pub play_pause: String,
+    /// 倒退 5 秒
+    pub back: String,
+    /// 快轉 5 秒
+    pub forward: String,
+    /// 在目前位置加上標記
+    pub marker: String,
+}
+
+impl Default for ShortcutConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            play_pause: "CommandOrControl+Alt+Space".to_string(),
+            back: "CommandOrControl+Alt+Left".to_string(),
+            forward: "CommandOrControl+Alt+Right".to_string(),
+            marker: "CommandOrControl+Alt+M".to_string(),
+        }
+    }
+}
+
+/// 報告 / 流程完成或失敗時 POST 通知的 webhook
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub enabled: bool,
+    pub url: Option<String>,
+    /// 簽章用的密鑰 (HMAC-SHA256，放在 X-STT-Agent-Signature 標頭)
+    pub secret: Option<String>,
+}
+
+/// SMTP 連線加密方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SmtpSecurity {
+    /// 連線後升級為 TLS (通常為 587 埠)
+    #[default]
+    StartTls,
+    /// 直接以 TLS 連線 (通常為 465 埠)
+    Tls,
+    /// 不加密 (僅限內部網路的轉送伺服器)
+    None,
+}
+
+/// 以電子郵件通知完成的工作
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub enabled: bool,
+    pub smtp_host: String,
+    pub smtp_port: u16,
+    pub security: SmtpSecurity,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub from: String,
+    /// 收件人 (可多位)
+    pub to: Vec<String>,
+    /// 附上產生的報告 (DOCX，沒有時為 Markdown)
+    pub attach_report: bool,
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            smtp_host: String::new(),
+            smtp_port: 587,
+            security: SmtpSecurity::default(),
+            username: None,
+            password: None,
+            from: String::new(),
+            to: Vec::new(),
+            attach_report: true,
+        }
+    }
+}
+
+/// 長時間工作完成時的通知
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotificationConfig {
+    /// 桌面通知
+    pub desktop: bool,
+    /// 執行超過此分鐘數的工作才通知
+    pub min_minutes: u64,
+    pub email: EmailConfig,
+}
+
+impl Default for NotificationConfig {
+    fn default() -> Self {
+        Self {
+            desktop: true,
+            min_minutes: 5,
+            email: EmailConfig::default(),
+        }
+    }
+}
+
+/// 大批次處理前要求確認的門檻 (0 表示不限制)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BatchGuardConfig {
+    pub max_files: usize,
+    /// 總長度 (小時)
+    pub max_hours: f64,
+}
+
+impl Default for BatchGuardConfig {
+    fn default() -> Self {
+        Self {
+            max_files: 50,
+            max_hours: 5.0,
+        }
+    }
+}
+
+/// 降噪方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum DenoiseMethod {
+    /// FFmpeg afftdn (頻域降噪，適合空調等穩定的背景噪音)
+    #[default]
+    Afftdn,
+    /// FFmpeg arnndn (RNNoise 神經網路模型，隨安裝包附帶)
+    Rnnoise,
+}
+
+/// 降噪前處理 (輸出到 01b_cleaned)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DenoiseConfig {
+    /// 轉檔完成後自動降噪
+    pub on_convert: bool,
+    pub method: DenoiseMethod,
+    /// afftdn 的降噪量 (dB)
+    pub strength_db: f32,
+}
+
+impl Default for DenoiseConfig {
+    fn default() -> Self {
+        Self {
+            on_convert: false,
+            method: DenoiseMethod::default(),
+            strength_db: 12.0,
+        }
+    }
+}
+
+/// 自訂的去識別化檢查規則
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeidPattern {
+    /// 顯示在檢查結果中的名稱 (例如 "病歷號")
+    pub label: String,
+    pub regex: String,
+}
+
+/// 報告產生後的去識別化自我檢查
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeidConfig {
+    pub enabled: bool,
+    /// 內建規則 (身分證字號、電話、Email、病歷號) 以外的規則
+    pub patterns: Vec<DeidPattern>,
+    /// 不應出現在報告中的姓名 (病人、家屬)
+    pub names: Vec<String>,
+    /// 另外請 Gemini 找出規則抓不到的個資
+    pub llm_check: bool,
+    /// 有未確認的檢查結果時不轉換 DOCX
+    pub block_docx: bool,
+}
+
+impl Default for DeidConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            patterns: Vec::new(),
+            names: Vec::new(),
+            llm_check: false,
+            block_docx: false,
+        }
+    }
+}
+
+/// 報告轉 DOCX (Pandoc) 的版面
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocxConfig {
+    /// 文件開頭加入目錄
+    pub toc: bool,
+    /// 目錄列出的標題層級
+    pub toc_depth: u8,
+    /// 章節編號 (每個音檔一節)
+    pub number_sections: bool,
+    /// 每個音檔的段落從新的一頁開始
+    pub page_breaks: bool,
+    /// 額外傳給 Pandoc 的參數 (例如 --reference-doc=範本.docx)
+    pub pandoc_args: Vec<String>,
+}
+
+impl Default for DocxConfig {
+    fn default() -> Self {
+        Self {
+            toc: true,
+            toc_depth: 2,
+            number_sections: true,
+            page_breaks: true,
+            pandoc_args: Vec::new(),
+        }
+    }
+}
+
+/// 外掛執行的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PluginHook {
+    /// 轉檔完成後 (01_converted)
+    PostConvert,
+    /// 音檔上傳到 STT 伺服器或 Gemini 之前
+    PreUpload,
+    /// 報告產生後
+    PostReport,
+    /// FFmpeg 無法解碼的錄音筆格式 (DSS / DS2) 先轉成 WAV
+    Decode,
+}
+
+impl PluginHook {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PluginHook::PostConvert => "post_convert",
+            PluginHook::PreUpload => "pre_upload",
+            PluginHook::PostReport => "post_report",
+            PluginHook::Decode => "decode",
+        }
+    }
+}
+
+/// 外部程式外掛 (見 services::plugins)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PluginConfig {
+    pub name: String,
+    /// 執行檔路徑
+    pub command: String,
+    pub args: Vec<String>,
+    pub hooks: Vec<PluginHook>,
+    pub enabled: bool,
+    /// 超過此時間 (秒) 未結束即終止
+    pub timeout_secs: u64,
+    /// 失敗時停止處理 (否則只記錄警告)
+    pub required: bool,
+}
+
+impl Default for PluginConfig {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            command: String::new(),
+            args: Vec::new(),
+            hooks: Vec::new(),
+            enabled: true,
+            timeout_secs: 300,
+            required: false,
+        }
+    }
+}
+
+/// 背景處理的資源限制，避免批次處理時電腦變得難以使用
+/// (同時執行的工作數量見 max_concurrent_jobs)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThrottleConfig {
+    /// FFmpeg 最多使用的執行緒數量 (0 = 由 FFmpeg 決定)
+    pub ffmpeg_threads: u32,
+    /// 以較低的優先權執行 FFmpeg (Unix: nice；Windows: 低於標準)
+    pub low_priority: bool,
+}
+
+/// Gemini API 的連線設定 (秒)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HttpConfig {
+    /// 建立連線的逾時
+    pub connect_timeout_secs: u64,
+    /// 連線停住 (收不到任何資料) 超過此時間即放棄；生成期間伺服器不送資料，需大於最長的生成時間
+    pub read_timeout_secs: u64,
+    /// 單次生成請求的總時間上限 (長音檔的生成可能需要數分鐘)
+    pub request_timeout_secs: u64,
+    /// HTTP/2 keep-alive ping 的間隔 (0 = 不送)
+    pub keep_alive_secs: u64,
+    /// 閒置連線保留在連線池的時間，之後的請求與重試可直接沿用
+    pub pool_idle_secs: u64,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout_secs: 15,
+            read_timeout_secs: 600,
+            request_timeout_secs: 900,
+            keep_alive_secs: 30,
+            pool_idle_secs: 90,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// 預設專案路徑 (未設定時使用 文件/STT_Agent_Projects，Linux 為家目錄下)
+    pub custom_project_root: Option<String>,
+    /// STT 伺服器位址 (例如 192.168.1.10:8000)
+    pub stt_server: Option<String>,
+    /// 報告使用的預設 Gemini 模型
+    pub default_model: Option<String>,
+    pub theme: Theme,
+    /// 介面語言 ("zh" 或 "en")
+    pub language: String,
+    /// 背景工作同時執行數量
+    pub max_concurrent_jobs: usize,
+    pub ffmpeg_preset: FfmpegPreset,
+    /// 各階段的輸出編碼 (覆寫 ffmpeg_preset)
+    pub encoding: EncodingConfig,
+    pub shortcuts: ShortcutConfig,
+    pub webhook: WebhookConfig,
+    pub notifications: NotificationConfig,
+    pub batch_guard: BatchGuardConfig,
+    pub denoise: DenoiseConfig,
+    /// 轉檔時分析左右聲道，依錄音方式選擇轉單聲道的方式 (取單邊 / 平均 / 保留立體聲)
+    pub smart_downmix: bool,
+    pub deid: DeidConfig,
+    pub docx: DocxConfig,
+    /// 報告同時輸出繁體中文逐字紀錄與英文翻譯 (國際個案討論用)
+    pub bilingual_report: bool,
+    pub http: HttpConfig,
+    pub throttle: ThrottleConfig,
+    /// 轉檔、切割、消音的輸出檔已存在時的處理方式
+    pub output_collision: OutputCollision,
+    /// 整批轉錄時同時上傳到 STT 伺服器的檔案數
+    pub stt_parallel_uploads: usize,
+    /// 上傳到 Gemini 與 STT 伺服器時以隨機名稱取代檔名 (對照表留在本機)
+    pub anonymize_uploads: bool,
+    /// 在轉檔後、上傳前、報告後執行的外部程式
+    pub plugins: Vec<PluginConfig>,
+    /// 每個專案保留的自動備份份數 (消音、報告覆寫前的備份)，超過時刪除最舊的
+    pub max_backups: usize,
+}
+
+impl Default for AppConfig {
+    fn default() -> Self {
+        Self {
+            custom_project_root: None,
+            stt_server: None,
+            default_model: None,
+            theme: Theme::default(),
+            language: "zh".to_string(),
+            max_concurrent_jobs: 1,
+            ffmpeg_preset: FfmpegPreset::default(),
+            encoding: EncodingConfig::default(),
+            shortcuts: ShortcutConfig::default(),
+            webhook: WebhookConfig::default(),
+            notifications: NotificationConfig::default(),
+            batch_guard: BatchGuardConfig::default(),
+            denoise: DenoiseConfig::default(),
+            smart_downmix: false,
+            deid: DeidConfig::default(),
+            docx: DocxConfig::default(),
+            bilingual_report: false,
+            http: HttpConfig::default(),
+            throttle: ThrottleConfig::default(),
+            output_collision: OutputCollision::default(),
+            stt_parallel_uploads: 2,
+            anonymize_uploads: false,
+            plugins: Vec::new(),
+            max_backups: crate::services::backup::DEFAULT_MAX_BACKUPS,
+        }
+    }
+}
+
+impl AppConfig {
+    /// 轉檔 (01_converted) 使用的編碼
+    pub fn converted_encoding(&self) -> StageEncoding {
+        self.encoding
+            .converted
+            .unwrap_or_else(|| StageEncoding::from_preset(self.ffmpeg_preset))
+    }
+
+    /// 檢查設定值並整理空白字串
+    pub fn validate(mut self) -> Result<Self, String> {
+        if !["zh", "en"].contains(&self.language.as_str()) {
+            return Err(crate::tr!(
+                "settings.unsupported_language",
+                language = self.language
+            ));
+        }
+        if self.max_concurrent_jobs == 0 || self.max_concurrent_jobs > MAX_CONCURRENT_JOBS_LIMIT {
+            return Err(crate::tr!(
+                "settings.concurrent_jobs_range",
+                max = MAX_CONCURRENT_JOBS_LIMIT
+            ));
+        }
+        if self.stt_parallel_uploads == 0 || self.stt_parallel_uploads > MAX_STT_PARALLEL_UPLOADS {
+            return Err(crate::tr!(
+                "settings.parallel_uploads_range",
+                max = MAX_STT_PARALLEL_UPLOADS
+            ));
+        }
+        if self.max_backups == 0 || self.max_backups > MAX_BACKUPS_LIMIT {
+            return Err(crate::tr!(
+                "settings.max_backups_range",
+                max = MAX_BACKUPS_LIMIT
+            ));
+        }
+        for (stage, encoding) in [
+            ("01_converted".to_string(), &self.encoding.converted),
+            ("02_split".to_string(), &self.encoding.split),
+            (crate::tr!("settings.stage_upload"), &self.encoding.upload),
+        ] {
+            if let Some(encoding) = encoding {
+                encoding.validate(&stage)?;
+            }
+        }
+        self.custom_project_root = non_empty(self.custom_project_root);
+        self.stt_server = non_empty(self.stt_server);
+        self.default_model = non_empty(self.default_model);
+        self.webhook.url = non_empty(self.webhook.url);
+        self.webhook.secret = non_empty(self.webhook.secret);
+        if let Some(url) = &self.webhook.url {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(crate::tr!("settings.webhook_url_scheme", url = url));
+            }
+        }
+        if self.webhook.enabled && self.webhook.url.is_none() {
+            return Err(crate::tr!("settings.webhook_url_required"));
+        }
+        if !self.batch_guard.max_hours.is_finite() || self.batch_guard.max_hours < 0.0 {
+            return Err(crate::tr!("settings.batch_hours_range"));
+        }
+        if !(0.01..=97.0).contains(&self.denoise.strength_db) {
+            return Err(crate::tr!("settings.denoise_strength_range"));
+        }
+        self.deid.names = self
+            .deid
+            .names
+            .iter()
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty())
+            .collect();
+        for pattern in &self.deid.patterns {
+            if let Err(e) = regex::Regex::new(&pattern.regex) {
+                return Err(crate::tr!(
+                    "settings.deid_pattern_invalid",
+                    label = pattern.label,
+                    detail = e
+                ));
+            }
+        }
+        if !(1..=6).contains(&self.docx.toc_depth) {
+            return Err(crate::tr!("settings.toc_depth_range"));
+        }
+        self.docx.pandoc_args = self
+            .docx
+            .pandoc_args
+            .iter()
+            .map(|arg| arg.trim().to_string())
+            .filter(|arg| !arg.is_empty())
+            .collect();
+        for plugin in &mut self.plugins {
+            plugin.name = plugin.name.trim().to_string();
+            plugin.command = plugin.command.trim().to_string();
+            if plugin.name.is_empty() || plugin.command.is_empty() {
+                return Err(crate::tr!("settings.plugin_incomplete"));
+            }
+            if plugin.timeout_secs == 0 {
+                return Err(crate::tr!("settings.plugin_timeout", name = plugin.name));
+            }
+        }
+        if self.throttle.ffmpeg_threads > MAX_FFMPEG_THREADS {
+            return Err(crate::tr!(
+                "settings.ffmpeg_threads_range",
+                max = MAX_FFMPEG_THREADS
+            ));
+        }
+        if self.http.connect_timeout_secs == 0
+            || self.http.read_timeout_secs == 0
+            || self.http.request_timeout_secs == 0
+        {
+            return Err(crate::tr!("settings.http_timeout"));
+        }
+        let email = &mut self.notifications.email;
+        email.smtp_host = email.smtp_host.trim().to_string();
+        email.from = email.from.trim().to_string();
+        email.username = non_empty(email.username.take());
+        email.password = non_empty(email.password.take());
+        email.to = email
+            .to
+            .iter()
+            .map(|to| to.trim().to_string())
+            .filter(|to| !to.is_empty())
+            .collect();
+        if email.enabled
+            && (email.smtp_host.is_empty() || email.from.is_empty() || email.to.is_empty())
+        {
+            return Err(crate::tr!("settings.email_incomplete"));
+        }
+        Ok(self)
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+pub fn config_path() -> PathBuf {
+    let config_dir = dirs::config_dir().unwrap_or_else(|| PathBuf::from("."));
+    config_dir.join("stt_agent_rust").join("config.json")
+}
+
+/// 讀取設定，檔案不存在或格式錯誤時使用預設值
+pub fn load() -> AppConfig {
+    fs::read_to_string(config_path())
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// 驗證並保存設定，回傳實際寫入的內容
+pub fn save(config: AppConfig) -> Result<AppConfig, String> {
+    let config = config.validate()?;
+    let content =
+        serde_json::to_string_pretty(&config).map_err(|e| format!("Serialization error: {}", e))?;
+    write_atomic(&config_path(), content.as_bytes())
+        .map_err(|e| format!("無法寫入設定檔: {}", e))?;
+    Ok(config)
+}
@@ -0,0 +1,129 @@
+// src-tauri/src/services/shortcuts.rs
+//
+// 全域快捷鍵：在 Word 等其他程式中打逐字稿時，也能控制播放器
+// (播放/暫停、倒退 5 秒、快轉 5 秒、標記目前位置)。
+
+use crate::commands::player_cmd::AudioPlayerState;
+use crate::services::settings::ShortcutConfig;
+use crate::services::AudioPlayer;
+use serde::Serialize;
+use std::str::FromStr;
+use tauri::{AppHandle, Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+/// 快捷鍵觸發後通知前端更新播放狀態 (標記由前端加入段落)
+pub const TRANSPORT_EVENT: &str = "shortcut://transport";
+
+const SKIP_SECONDS: f64 = 5.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportAction {
+    PlayPause,
+    Back,
+    Forward,
+    Marker,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransportEvent {
+    pub action: TransportAction,
+    pub position: f64,
+    pub is_playing: bool,
+}
+
+fn bindings(config: &ShortcutConfig) -> [(&str, TransportAction); 4] {
+    [
+        (config.play_pause.as_str(), TransportAction::PlayPause),
+        (config.back.as_str(), TransportAction::Back),
+        (config.forward.as_str(), TransportAction::Forward),
+        (config.marker.as_str(), TransportAction::Marker),
+    ]
+}
+
+/// 檢查快捷鍵格式，並確認沒有重複
+pub fn validate(config: &ShortcutConfig) -> Result<(), String> {
+    let mut seen: Vec<Shortcut> = Vec::new();
+    for (accelerator, _) in bindings(config) {
+        if accelerator.trim().is_empty() {
+            continue;
+        }
+        let shortcut = Shortcut::from_str(accelerator)
+            .map_err(|e| format!("快捷鍵格式錯誤 '{}': {}", accelerator, e))?;
+        if seen.contains(&shortcut) {
+            return Err(format!("快捷鍵重複: {}", accelerator));
+        }
+        seen.push(shortcut);
+    }
+    Ok(())
+}
+
+/// 依設定重新註冊快捷鍵；空白的快捷鍵不註冊
+pub fn register(app: &AppHandle, config: &ShortcutConfig) -> Result<(), String> {
+    let manager = app.global_shortcut();
+    manager
+        .unregister_all()
+        .map_err(|e| format!("無法取消快捷鍵: {}", e))?;
+    if !config.enabled {
+        return Ok(());
+    }
+
+    for (accelerator, action) in bindings(config) {
+        if accelerator.trim().is_empty() {
+            continue;
+        }
+        manager
+            .on_shortcut(accelerator, move |app, _shortcut, event| {
+                if event.state == ShortcutState::Pressed {
+                    perform(app, action);
+                }
+            })
+            // 快捷鍵可能已被其他程式佔用，記錄後繼續註冊其他按鍵
+            .unwrap_or_else(|e| tracing::warn!("無法註冊快捷鍵 {}: {}", accelerator, e));
+    }
+    Ok(())
+}
+
+/// 快捷鍵回呼在主執行緒上，等待播放器鎖定的部分交給背景工作
+fn perform(app: &AppHandle, action: TransportAction) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let state = app.state::<AudioPlayerState>();
+        let mut guard = state.lock().await;
+        apply(&app, guard.as_mut(), action);
+    });
+}
+
+fn apply(app: &AppHandle, player: Option<&mut AudioPlayer>, action: TransportAction) {
+    // 尚未載入音檔時忽略
+    let Some(player) = player else {
+        return;
+    };
+
+    match action {
+        TransportAction::PlayPause => {
+            if player.is_playing() {
+                player.pause();
+            } else if !player.is_started() {
+                // 第一次播放需要先啟動播放管線
+                if let Err(e) = player.start_playback() {
+                    tracing::warn!("無法開始播放: {}", e);
+                }
+            } else {
+                player.play();
+            }
+        }
+        TransportAction::Back => player.seek((player.get_position() - SKIP_SECONDS).max(0.0)),
+        TransportAction::Forward => {
+            player.seek((player.get_position() + SKIP_SECONDS).min(player.get_duration()))
+        }
+        TransportAction::Marker => {}
+    }
+
+    let event = TransportEvent {
+        action,
+        position: player.get_position(),
+        is_playing: player.is_playing(),
+    };
+    let _ = app.emit(TRANSPORT_EVENT, event);
+}
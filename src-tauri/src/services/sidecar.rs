@@ -0,0 +1,528 @@
+// src-tauri/src/services/sidecar.rs
+//
+// FFmpeg Sidecar 執行器：以 spawn 方式執行，工作被取消時可中止子行程，
+// 而不是只能等 `output()` 跑完。
+// 安裝包內的 Sidecar 不存在時，改用下載到 app data 目錄的 FFmpeg (見 dependencies.rs)。
+// CLI 模式沒有 AppHandle，改以 `Ffmpeg::Binary` 直接執行指定的執行檔。
+// 工作佇列暫停時，佇列工作 (CancelToken::for_job) 執行中的 FFmpeg 在 Unix 上以 SIGSTOP 暫停
+// (其他平台讓它執行完)，新的 FFmpeg 等到佇列繼續才啟動；章節讀取、錄音轉檔等不經佇列的 FFmpeg 不受影響。
+// 背景處理 (Ffmpeg::run) 依設定限制 FFmpeg 執行緒數量並降低行程優先權，避免電腦變得難以使用。
+
+use crate::services::dependencies;
+use crate::services::file_manager;
+use crate::services::jobs::{CancelToken, CANCELLED_MESSAGE};
+use crate::services::settings::{self, ThrottleConfig};
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::AppHandle;
+
+/// 執行檔名稱 (Windows 加上 .exe)
+pub fn executable_name(name: &str) -> String {
+    if cfg!(windows) {
+        format!("{}.exe", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// 隨安裝包附帶的 FFmpeg Sidecar 路徑 (與主程式放在同一個資料夾)
+pub fn bundled_ffmpeg_path() -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let path = exe.parent()?.join(executable_name("ffmpeg"));
+    path.exists().then_some(path)
+}
+
+/// FFmpeg 執行檔路徑 (需要直接以 std::process 串接輸出時使用，例如播放器的解碼備援)
+pub fn ffmpeg_executable(app: &AppHandle) -> Option<PathBuf> {
+    bundled_ffmpeg_path().or_else(|| dependencies::installed_ffmpeg_path(app))
+}
+
+/// 要執行的 FFmpeg：優先使用 Sidecar，其次使用已下載安裝的版本
+fn resolve_ffmpeg(app: &AppHandle) -> Result<PathBuf, String> {
+    ffmpeg_executable(app).ok_or_else(|| "找不到 FFmpeg，請在相依元件檢查中下載安裝".to_string())
+}
+
+/// 佇列是否暫停中
+static PAUSED: AtomicBool = AtomicBool::new(false);
+/// 佇列工作執行中的 FFmpeg 行程 ID (子行程回收前移除)
+static RUNNING: Mutex<Vec<u32>> = Mutex::new(Vec::new());
+/// 暫停中等待繼續的檢查間隔
+const HOLD_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 此平台能否暫停執行中的行程
+pub fn can_suspend() -> bool {
+    cfg!(unix)
+}
+
+#[cfg(unix)]
+fn signal(pid: u32, signal: libc::c_int) {
+    // SAFETY: 只對自己啟動且尚未回收的子行程送出訊號 (RUNNING 在回收前就已移除 pid)
+    unsafe {
+        libc::kill(pid as libc::pid_t, signal);
+    }
+}
+
+#[cfg(unix)]
+fn suspend(pid: u32) {
+    signal(pid, libc::SIGSTOP);
+}
+
+#[cfg(not(unix))]
+fn suspend(_pid: u32) {}
+
+#[cfg(unix)]
+fn resume(pid: u32) {
+    signal(pid, libc::SIGCONT);
+}
+
+#[cfg(not(unix))]
+fn resume(_pid: u32) {}
+
+/// 降低行程優先權 (Unix: nice 10；Windows: 低於標準)
+#[cfg(unix)]
+fn lower_priority(pid: u32) {
+    // SAFETY: 只調整自己啟動的子行程
+    let result = unsafe { libc::setpriority(libc::PRIO_PROCESS, pid as libc::id_t, 10) };
+    if result != 0 {
+        tracing::warn!("無法降低 FFmpeg 優先權 (pid {})", pid);
+    }
+}
+
+#[cfg(windows)]
+fn lower_priority(pid: u32) {
+    use windows_sys::Win32::Foundation::CloseHandle;
+    use windows_sys::Win32::System::Threading::{
+        OpenProcess, SetPriorityClass, BELOW_NORMAL_PRIORITY_CLASS, PROCESS_SET_INFORMATION,
+    };
+    // SAFETY: 開啟自己啟動的子行程，用完即關閉 handle
+    unsafe {
+        let handle = OpenProcess(PROCESS_SET_INFORMATION, 0, pid);
+        if handle.is_null() {
+            tracing::warn!("無法降低 FFmpeg 優先權 (pid {})", pid);
+            return;
+        }
+        SetPriorityClass(handle, BELOW_NORMAL_PRIORITY_CLASS);
+        CloseHandle(handle);
+    }
+}
+
+#[cfg(not(any(unix, windows)))]
+fn lower_priority(_pid: u32) {}
+
+/// 加上執行緒限制：-threads 放在最後一個參數 (輸出檔) 之前，作為編碼的選項
+/// 沒有輸入檔的指令 (例如 -version) 不變
+fn throttled_args<I, S>(args: I, throttle: &ThrottleConfig) -> Vec<OsString>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    let mut args: Vec<OsString> = args
+        .into_iter()
+        .map(|a| a.as_ref().to_os_string())
+        .collect();
+    if throttle.ffmpeg_threads > 0 && args.iter().any(|a| a == "-i") {
+        let at = args.len() - 1;
+        args.splice(
+            at..at,
+            [
+                OsString::from("-threads"),
+                OsString::from(throttle.ffmpeg_threads.to_string()),
+            ],
+        );
+    }
+    args
+}
+
+/// 絕對路徑的參數經過 file_manager::long_path，Windows 上超過 260 字元的輸入/輸出檔也能開啟
+fn long_path_args<I, S>(args: I) -> Vec<OsString>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    args.into_iter()
+        .map(|arg| {
+            let path = Path::new(arg.as_ref());
+            if path.is_absolute() {
+                file_manager::long_path(path).into_os_string()
+            } else {
+                arg.as_ref().to_os_string()
+            }
+        })
+        .collect()
+}
+
+/// 暫停佇列工作的 FFmpeg：之後啟動的行程會等待，回傳被暫停的執行中行程數量
+pub fn pause_processes() -> usize {
+    PAUSED.store(true, Ordering::SeqCst);
+    let Ok(running) = RUNNING.lock() else {
+        return 0;
+    };
+    if !can_suspend() {
+        return 0;
+    }
+    for pid in running.iter() {
+        suspend(*pid);
+    }
+    running.len()
+}
+
+/// 繼續執行被暫停與等待中的 FFmpeg
+pub fn resume_processes() {
+    PAUSED.store(false, Ordering::SeqCst);
+    if let Ok(running) = RUNNING.lock() {
+        for pid in running.iter() {
+            resume(*pid);
+        }
+    }
+}
+
+/// 佇列暫停中時等待繼續 (取消時立即返回錯誤)
+async fn wait_until_resumed(cancel: &CancelToken) -> Result<(), String> {
+    while PAUSED.load(Ordering::SeqCst) {
+        if cancel.is_cancelled() {
+            return Err(CANCELLED_MESSAGE.to_string());
+        }
+        tokio::time::sleep(HOLD_POLL_INTERVAL).await;
+    }
+    Ok(())
+}
+
+/// 登記佇列工作執行中的行程，drop 時移除 (須在回收子行程之前)
+struct TrackedProcess(Option<u32>);
+
+impl TrackedProcess {
+    fn new(pid: Option<u32>) -> Self {
+        if let (Some(pid), Ok(mut running)) = (pid, RUNNING.lock()) {
+            running.push(pid);
+            // 啟動的同時佇列剛好被暫停
+            if PAUSED.load(Ordering::SeqCst) {
+                suspend(pid);
+            }
+        }
+        Self(pid)
+    }
+}
+
+impl Drop for TrackedProcess {
+    fn drop(&mut self) {
+        if let (Some(pid), Ok(mut running)) = (self.0, RUNNING.lock()) {
+            running.retain(|p| *p != pid);
+        }
+    }
+}
+
+/// 指令的輸出檔 (最後一個參數)；取消時刪除未完成的檔案
+/// 沒有輸入檔、輸出到 stdout 或 null 的指令沒有輸出檔
+struct PartialOutput {
+    path: PathBuf,
+    /// 執行前已存在的檔案的修改時間 (未被覆寫時保留)
+    modified: Option<std::time::SystemTime>,
+}
+
+impl PartialOutput {
+    fn find(args: &[OsString]) -> Option<Self> {
+        let last = args.last()?;
+        let path = Path::new(last);
+        let is_input = args.windows(2).any(|w| w[0] == "-i" && w[1] == *last);
+        if !path.is_absolute() || is_input || !args.iter().any(|a| a == "-i") {
+            return None;
+        }
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        Some(Self {
+            path: path.to_path_buf(),
+            modified,
+        })
+    }
+
+    fn discard(&self) {
+        let current = std::fs::metadata(&self.path).and_then(|m| m.modified());
+        match current {
+            Ok(current) if Some(current) == self.modified => {}
+            Ok(_) => {
+                if let Err(e) = std::fs::remove_file(&self.path) {
+                    tracing::warn!("無法刪除未完成的輸出檔 {}: {}", self.path.display(), e);
+                }
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// 取消時刪除未完成的輸出檔並回傳取消錯誤
+fn cancelled(partial: Option<&PartialOutput>) -> String {
+    if let Some(partial) = partial {
+        partial.discard();
+    }
+    CANCELLED_MESSAGE.to_string()
+}
+
+/// Sidecar 執行結果
+#[derive(Debug)]
+pub struct SidecarOutput {
+    pub code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl SidecarOutput {
+    pub fn success(&self) -> bool {
+        self.code == Some(0)
+    }
+}
+
+/// FFmpeg 的執行方式
+#[derive(Clone)]
+pub enum Ffmpeg {
+    /// 執行 Sidecar (或已下載安裝的版本)
+    App(AppHandle),
+    /// 直接執行指定的執行檔，不需要 Tauri 執行環境
+    Binary(PathBuf),
+}
+
+impl From<&AppHandle> for Ffmpeg {
+    fn from(app: &AppHandle) -> Self {
+        Ffmpeg::App(app.clone())
+    }
+}
+
+impl Ffmpeg {
+    /// 執行 FFmpeg 並收集輸出，傳入 cancel 時取消會立即終止 FFmpeg
+    pub async fn run<I, S>(
+        &self,
+        args: I,
+        cancel: Option<&CancelToken>,
+    ) -> Result<SidecarOutput, String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<std::ffi::OsStr>,
+    {
+        let throttle = settings::load().throttle;
+        let args = throttled_args(args, &throttle);
+        match self {
+            Ffmpeg::App(app) => {
+                run_binary(&resolve_ffmpeg(app)?, args, cancel, throttle.low_priority).await
+            }
+            Ffmpeg::Binary(path) => run_binary(path, args, cancel, throttle.low_priority).await,
+        }
+    }
+}
+
+/// 以 tokio 直接執行 FFmpeg；取消時終止子行程，並刪除未完成的輸出檔
+async fn run_binary<I, S>(
+    path: &PathBuf,
+    args: I,
+    cancel: Option<&CancelToken>,
+    low_priority: bool,
+) -> Result<SidecarOutput, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    if cancel.map(|c| c.is_cancelled()).unwrap_or(false) {
+        return Err(CANCELLED_MESSAGE.to_string());
+    }
+    // 只有佇列工作的 FFmpeg 隨佇列暫停
+    let job = cancel.filter(|c| c.is_job());
+    if let Some(token) = job {
+        wait_until_resumed(token).await?;
+    }
+
+    let args = long_path_args(args);
+    let partial = PartialOutput::find(&args);
+    let mut command = tokio::process::Command::new(path);
+    command
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    #[cfg(windows)]
+    {
+        // CREATE_NO_WINDOW: 不跳出主控台視窗
+        command.creation_flags(0x0800_0000);
+    }
+    let mut child = command
+        .spawn()
+        .map_err(|e| format!("FFmpeg 執行失敗 ({}): {}", path.display(), e))?;
+    if let (true, Some(pid)) = (low_priority, child.id()) {
+        lower_priority(pid);
+    }
+    let tracked = job.map(|_| TrackedProcess::new(child.id()));
+
+    let stdout = read_pipe(child.stdout.take());
+    let stderr = read_pipe(child.stderr.take());
+    let finished = async {
+        match cancel {
+            Some(token) => {
+                tokio::select! {
+                    _ = exited(&mut child) => false,
+                    _ = token.cancelled() => {
+                        let _ = child.start_kill();
+                        true
+                    }
+                }
+            }
+            None => {
+                exited(&mut child).await;
+                false
+            }
+        }
+    };
+    let (stdout, stderr, was_cancelled) = tokio::join!(stdout, stderr, finished);
+
+    // 先取消登記再回收，暫停時不會對已回收 (pid 可能被重複使用) 的行程送出訊號
+    drop(tracked);
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| format!("FFmpeg 執行失敗: {}", e))?;
+    if was_cancelled {
+        return Err(cancelled(partial.as_ref()));
+    }
+
+    Ok(SidecarOutput {
+        code: status.code(),
+        stdout,
+        stderr,
+    })
+}
+
+/// 等待子行程結束但不回收 (Unix: waitid + WNOWAIT，行程保持 zombie 狀態，pid 不會被重複使用)
+#[cfg(unix)]
+async fn exited(child: &mut tokio::process::Child) {
+    let Some(pid) = child.id() else {
+        return;
+    };
+    let _ = tokio::task::spawn_blocking(move || loop {
+        // SAFETY: info 由 waitid 填寫；只等待自己的子行程
+        let mut info: libc::siginfo_t = unsafe { std::mem::zeroed() };
+        let result = unsafe {
+            libc::waitid(
+                libc::P_PID,
+                pid as libc::id_t,
+                &mut info,
+                libc::WEXITED | libc::WNOWAIT,
+            )
+        };
+        if result == 0 || std::io::Error::last_os_error().kind() != std::io::ErrorKind::Interrupted
+        {
+            break;
+        }
+    })
+    .await;
+}
+
+/// 其他平台不會對行程送出訊號，直接等待結束
+#[cfg(not(unix))]
+async fn exited(child: &mut tokio::process::Child) {
+    let _ = child.wait().await;
+}
+
+/// 讀取子行程的 stdout / stderr
+async fn read_pipe<R>(pipe: Option<R>) -> String
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    use tokio::io::AsyncReadExt;
+
+    let mut buf = Vec::new();
+    if let Some(mut pipe) = pipe {
+        let _ = pipe.read_to_end(&mut buf).await;
+    }
+    String::from_utf8_lossy(&buf).to_string()
+}
+
+/// 執行 FFmpeg Sidecar 並收集輸出
+/// 傳入 cancel 時，取消會立即終止 FFmpeg、刪除未完成的輸出檔並回傳錯誤
+pub async fn run_ffmpeg<I, S>(
+    app: &AppHandle,
+    args: I,
+    cancel: Option<&CancelToken>,
+) -> Result<SidecarOutput, String>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    run_binary(&resolve_ffmpeg(app)?, args, cancel, false).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<OsString> {
+        list.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn partial_output_is_the_last_absolute_argument() {
+        let out = std::env::temp_dir().join("stt-partial-out.mp3");
+        let out = out.to_str().unwrap();
+        let found = PartialOutput::find(&args(&["-i", "/in.wav", "-y", out])).unwrap();
+        assert_eq!(found.path, Path::new(out));
+
+        assert!(PartialOutput::find(&args(&["-version"])).is_none());
+        assert!(PartialOutput::find(&args(&["-i", "/in.wav", "-f", "null", "-"])).is_none());
+        assert!(PartialOutput::find(&args(&["-y", "-i", "/in.wav"])).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn processes_outside_the_queue_ignore_pause() {
+        let sh = PathBuf::from("/bin/sh");
+        let token = CancelToken::new();
+        pause_processes();
+        let output = tauri::async_runtime::block_on(async {
+            tokio::time::timeout(
+                Duration::from_secs(5),
+                run_binary(&sh, ["-c", "echo ok"], Some(&token), false),
+            )
+            .await
+        });
+        resume_processes();
+        let output = output.expect("paused").unwrap();
+        assert!(output.success());
+        assert_eq!(output.stdout.trim(), "ok");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cancel_stops_the_process() {
+        let sh = PathBuf::from("/bin/sh");
+        let token = CancelToken::new();
+        let canceller = token.clone();
+        let result = tauri::async_runtime::block_on(async {
+            tokio::spawn(async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                canceller.cancel();
+            });
+            tokio::time::timeout(
+                Duration::from_secs(5),
+                run_binary(&sh, ["-c", "exec sleep 30"], Some(&token), false),
+            )
+            .await
+        });
+        assert_eq!(result.expect("not killed").unwrap_err(), CANCELLED_MESSAGE);
+    }
+
+    #[test]
+    fn discard_keeps_untouched_existing_output() {
+        let path = std::env::temp_dir().join(format!("stt-partial-{}.mp3", std::process::id()));
+        std::fs::write(&path, b"previous").unwrap();
+        let partial =
+            PartialOutput::find(&args(&["-i", "/in.wav", path.to_str().unwrap()])).unwrap();
+        partial.discard();
+        assert!(path.exists());
+
+        let fresh = PartialOutput {
+            path: path.clone(),
+            modified: None,
+        };
+        fresh.discard();
+        assert!(!path.exists());
+    }
+}
@@ -1,7 +1,11 @@
+use crate::services::ffmpeg_filter;
+use crate::services::file_manager::{self, OutputCollision};
+use crate::services::jobs::CancelToken;
+use crate::services::sidecar::Ffmpeg;
+use crate::services::upload_alias;
 use serde::{Deserialize, Serialize};
-use std::path::Path;
-use tauri::AppHandle;
-use tauri_plugin_shell::ShellExt;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Segment {
@@ -11,6 +15,36 @@ pub struct Segment {
     pub name: String,
     pub start_idx: Option<usize>,
     pub end_idx: Option<usize>,
+    /// 說話者 ID (以 diarize 轉錄時由伺服器提供，例如 SPEAKER_00)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speaker: Option<String>,
+    /// 伺服器提供的辨識信心分數 (0.0 ~ 1.0，舊版伺服器沒有)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confidence: Option<f64>,
+    /// 逐字時間 (對齊後才有)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub words: Vec<Word>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Word {
+    pub word: String,
+    pub start: f64,
+    pub end: f64,
+}
+
+/// /align 回傳的段落時間 (與送出的段落順序相同)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AlignedSegment {
+    pub start: f64,
+    pub end: f64,
+    #[serde(default)]
+    pub words: Vec<Word>,
+}
+
+#[derive(Deserialize, Debug)]
+struct AlignResponse {
+    segments: Vec<AlignedSegment>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -19,19 +53,54 @@ pub struct TranscribeResponse {
     pub duration: f64,
     pub segments: Vec<Segment>,
     pub full_text: String,
+    /// 說話者 ID → 顯示名稱 (例如 SPEAKER_00 → 林醫師)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub speakers: BTreeMap<String, String>,
+}
+
+impl TranscribeResponse {
+    /// 段落的說話者顯示名稱 (未命名時為 ID)
+    pub fn speaker_name<'a>(&'a self, segment: &'a Segment) -> Option<&'a str> {
+        let id = segment.speaker.as_deref()?;
+        Some(self.speakers.get(id).map(String::as_str).unwrap_or(id))
+    }
+}
+
+/// 上傳的音檔 (檔名依匿名上傳設定決定)
+async fn upload_part(file_path: &Path) -> Result<reqwest::multipart::Part, String> {
+    Ok(reqwest::multipart::Part::file(file_path)
+        .await
+        .map_err(|e| format!("Failed to create multipart form: {}", e))?
+        .file_name(upload_alias::upload_name(file_path)))
 }
 
 pub struct Silence {
     http_client: reqwest::Client,
+    cancel: Option<CancelToken>,
+    collision: OutputCollision,
 }
 
 impl Silence {
     pub fn new() -> Self {
         Self {
             http_client: reqwest::Client::new(),
+            cancel: None,
+            collision: OutputCollision::default(),
         }
     }
 
+    /// 設定消音輸出檔已存在時的處理方式 (預設覆寫)
+    pub fn with_collision(mut self, collision: OutputCollision) -> Self {
+        self.collision = collision;
+        self
+    }
+
+    /// 綁定工作的取消旗標，取消時中止 FFmpeg
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
     pub async fn check_health(&self, ip: &str) -> bool {
         let url = format!("{}/health", ip.trim_end_matches('/'));
         match self
@@ -46,10 +115,12 @@ impl Silence {
         }
     }
 
+    /// diarize: 要求伺服器區分說話者，段落帶有 speaker ID
     pub async fn transcribe(
         &self,
         ip: &str,
         file_path: &str,
+        diarize: bool,
     ) -> Result<TranscribeResponse, String> {
         let url = format!("{}/transcribe", ip.trim_end_matches('/'));
 
@@ -59,10 +130,56 @@ impl Silence {
         }
 
         // Create multipart form
-        let form = reqwest::multipart::Form::new()
-            .file("file", file_path)
+        let form = reqwest::multipart::Form::new().part("file", upload_part(file_path).await?);
+
+        let mut request = self.http_client.post(&url).multipart(form);
+        if diarize {
+            request = request.query(&[("diarize", "true")]);
+        }
+        let resp = request
+            .send()
+            .await
+            .map_err(|e| format!("Request failed: {}", e))?;
+
+        if !resp.status().is_success() {
+            return Err(format!("Server returned error: {}", resp.status()));
+        }
+
+        let mut result = resp
+            .json::<TranscribeResponse>()
             .await
-            .map_err(|e| format!("Failed to create multipart form: {}", e))?;
+            .map_err(|e| format!("Failed to parse response: {}", e))?;
+        // 匿名上傳時伺服器回傳的是隨機名稱，改回原檔名
+        if let Some(name) = file_path.file_name() {
+            result.filename = name.to_string_lossy().to_string();
+        }
+
+        Ok(result)
+    }
+
+    /// 強制對齊：送出音檔與已校正的段落文字，由 STT 伺服器重新計算段落與逐字時間
+    pub async fn align(
+        &self,
+        ip: &str,
+        file_path: &str,
+        segments: &[Segment],
+    ) -> Result<Vec<AlignedSegment>, String> {
+        let url = format!("{}/align", ip.trim_end_matches('/'));
+
+        let file_path = Path::new(file_path);
+        if !file_path.exists() {
+            return Err(format!("File not found: {:?}", file_path));
+        }
+
+        let transcript = serde_json::json!({
+            "segments": segments
+                .iter()
+                .map(|s| serde_json::json!({ "start": s.start, "end": s.end, "text": s.text }))
+                .collect::<Vec<_>>(),
+        });
+        let form = reqwest::multipart::Form::new()
+            .part("file", upload_part(file_path).await?)
+            .text("transcript", transcript.to_string());
 
         let resp = self
             .http_client
@@ -77,22 +194,79 @@ impl Silence {
         }
 
         let result = resp
-            .json::<TranscribeResponse>()
+            .json::<AlignResponse>()
             .await
             .map_err(|e| format!("Failed to parse response: {}", e))?;
 
-        Ok(result)
+        Ok(result.segments)
     }
 
     pub fn execute(&self) {
-        println!("(Silence) 正在執行音訊消音處理 (Service Layer)...");
+        tracing::debug!("(Silence) 正在執行音訊消音處理 (Service Layer)...");
+    }
+
+    /// 消音輸出檔路徑: output_dir/原檔名_silenced.副檔名
+    pub fn silenced_output_path(input_path: &str, output_dir: &str) -> String {
+        let input = Path::new(input_path);
+        let ext = input
+            .extension()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| "mp3".to_string());
+        file_manager::derived_output_path(input, Path::new(output_dir), "_silenced", &ext)
+            .unwrap_or_else(|| Path::new(output_dir).join(format!("output_silenced.{}", ext)))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// 只保留指定時段並依序串接，輸出到 output_path (已存在時依設定改名或覆寫)，回傳實際輸出路徑
+    pub async fn extract_segments(
+        &self,
+        ffmpeg: &Ffmpeg,
+        input_path: &str,
+        output_path: &str,
+        segments: &[(f64, f64)],
+    ) -> Result<String, String> {
+        if segments.is_empty() {
+            return Err("沒有指定時段".to_string());
+        }
+        if let Some(parent) = Path::new(output_path).parent() {
+            std::fs::create_dir_all(parent).map_err(|e| format!("無法建立輸出目錄: {}", e))?;
+        }
+
+        let output_path = self
+            .collision
+            .resolve(PathBuf::from(output_path))?
+            .to_string_lossy()
+            .to_string();
+        let filter_arg = ffmpeg_filter::keep_ranges(segments)?;
+
+        let output = ffmpeg
+            .run(
+                [
+                    "-i",
+                    input_path,
+                    "-af",
+                    &filter_arg,
+                    "-vn",
+                    self.collision.ffmpeg_flag(),
+                    &output_path,
+                ],
+                self.cancel.as_ref(),
+            )
+            .await?;
+
+        if output.success() {
+            Ok(output_path)
+        } else {
+            Err(format!("FFmpeg 擷取片段失敗: {}", output.stderr))
+        }
     }
 
     /// 對多個時段進行消音處理
     /// segments: Vec<(startTime, endTime)> (單位：秒，支援小數)
     pub async fn apply_silence_to_segments(
         &self,
-        app: &AppHandle,
+        ffmpeg: &Ffmpeg,
         input_path: &str,
         output_dir: &str,
         segments: Vec<(f64, f64)>,
@@ -101,56 +275,43 @@ impl Silence {
             return Err("沒有指定消音時段".to_string());
         }
 
-        let input_path_obj = Path::new(input_path);
-        let file_stem = input_path_obj
-            .file_stem()
-            .and_then(|s| s.to_str())
-            .unwrap_or("output");
-        let ext = input_path_obj
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("mp3");
-
         // 確保輸出目錄存在
         std::fs::create_dir_all(output_dir).map_err(|e| format!("無法建立輸出目錄: {}", e))?;
 
-        let output_path = format!("{}/{}_silenced.{}", output_dir, file_stem, ext);
+        let output_path = self
+            .collision
+            .resolve(PathBuf::from(Self::silenced_output_path(
+                input_path, output_dir,
+            )))?
+            .to_string_lossy()
+            .to_string();
 
-        // 建構 FFmpeg volume filter
         // 語法: volume=enable='between(t,start1,end1)+between(t,start2,end2)':volume=0
-        let mut filter_parts = Vec::new();
-        for (start, end) in segments {
-            filter_parts.push(format!("between(t,{:.3},{:.3})", start, end));
-        }
-        let filter_expr = filter_parts.join("+");
-        let filter_arg = format!("volume=enable='{}':volume=0", filter_expr);
-
-        println!("Applying Silence Filter: {}", filter_arg);
-
-        let output = app
-            .shell()
-            .sidecar("ffmpeg")
-            .map_err(|e| format!("無法建立 FFmpeg Sidecar: {}", e))?
-            .args([
-                "-i",
-                input_path,
-                "-af",
-                &filter_arg,
-                "-c:v",
-                "copy", // Copy video if present (though usually audio only)
-                // re-encode audio is required for filters to work
-                "-y",
-                &output_path,
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("FFmpeg 執行失敗: {}", e))?;
+        let filter_arg = ffmpeg_filter::mute_ranges(&segments)?;
+
+        tracing::debug!("Applying Silence Filter: {}", filter_arg);
+
+        let output = ffmpeg
+            .run(
+                [
+                    "-i",
+                    input_path,
+                    "-af",
+                    &filter_arg,
+                    "-c:v",
+                    "copy", // Copy video if present (though usually audio only)
+                    // re-encode audio is required for filters to work
+                    self.collision.ffmpeg_flag(),
+                    &output_path,
+                ],
+                self.cancel.as_ref(),
+            )
+            .await?;
 
-        if output.status.success() {
+        if output.success() {
             Ok(output_path)
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!("FFmpeg 消音處理失敗: {}", stderr))
+            Err(format!("FFmpeg 消音處理失敗: {}", output.stderr))
         }
     }
 }
@@ -1,14 +1,80 @@
 // src-tauri/src/services/splitter.rs
 
-use std::path::Path;
-use tauri::AppHandle;
-use tauri_plugin_shell::ShellExt;
+use crate::services::file_manager::OutputCollision;
+use crate::services::jobs::CancelToken;
+use crate::services::settings::StageEncoding;
+use crate::services::sidecar::Ffmpeg;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
-pub struct Splitter;
+/// 每切出一段時呼叫 (參數為輸出檔路徑)
+pub type SplitOutput = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// 秒數轉為 FFmpeg 的 HH:MM:SS.mmm
+pub fn format_timestamp(seconds: f64) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}.{:03}",
+        total_ms / 3_600_000,
+        total_ms / 60_000 % 60,
+        total_ms / 1000 % 60,
+        total_ms % 1000
+    )
+}
+
+#[derive(Default)]
+pub struct Splitter {
+    cancel: Option<CancelToken>,
+    collision: OutputCollision,
+    on_output: Option<SplitOutput>,
+    /// 重新編碼的設定 (None 時直接複製，不重新編碼)
+    encoding: Option<StageEncoding>,
+}
 
 impl Splitter {
     pub fn new() -> Self {
-        Self
+        Self {
+            cancel: None,
+            collision: OutputCollision::default(),
+            on_output: None,
+            encoding: None,
+        }
+    }
+
+    /// 依設定重新編碼輸出 (預設直接複製音訊串流)
+    pub fn with_encoding(mut self, encoding: Option<StageEncoding>) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// 每切出一段就通知 (例如立即送去轉錄)
+    pub fn with_on_output(mut self, on_output: SplitOutput) -> Self {
+        self.on_output = Some(on_output);
+        self
+    }
+
+    /// 設定批次切割時輸出檔已存在的處理方式 (預設覆寫)
+    pub fn with_collision(mut self, collision: OutputCollision) -> Self {
+        self.collision = collision;
+        self
+    }
+
+    /// 段落輸出路徑 (未處理同名檔案): output_dir/段落名稱.副檔名
+    pub fn output_path(input_path: &str, output_dir: &str, name: &str) -> String {
+        let ext = Path::new(input_path)
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or("mp3");
+        Path::new(output_dir)
+            .join(format!("{}.{}", name, ext))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// 綁定工作的取消旗標，取消時中止 FFmpeg
+    pub fn with_cancel(mut self, cancel: CancelToken) -> Self {
+        self.cancel = Some(cancel);
+        self
     }
 
     /// 切割單一段落
@@ -16,15 +82,18 @@ impl Splitter {
     /// 輸出到 output_path
     pub async fn split_segment(
         &self,
-        app: &AppHandle,
+        ffmpeg: &Ffmpeg,
         input_path: &str,
         output_path: &str,
         start_time: &str, // HH:MM:SS 格式
         end_time: &str,   // HH:MM:SS 格式
     ) -> Result<String, String> {
-        println!(
+        tracing::info!(
             "正在切割: {} [{} - {}] -> {}",
-            input_path, start_time, end_time, output_path
+            input_path,
+            start_time,
+            end_time,
+            output_path
         );
 
         // 確保輸出目錄存在
@@ -34,58 +103,89 @@ impl Splitter {
 
         // 執行 FFmpeg Sidecar
         // ffmpeg -i input.mp3 -ss 00:01:00 -to 00:02:30 -c copy output.mp3
-        let output = app
-            .shell()
-            .sidecar("ffmpeg")
-            .map_err(|e| format!("無法建立 FFmpeg Sidecar: {}", e))?
-            .args([
-                "-i",
-                input_path, // 輸入檔案
-                "-ss",
-                start_time, // 開始時間
-                "-to",
-                end_time, // 結束時間
-                "-c",
-                "copy", // 直接複製，不重新編碼（速度快）
-                "-y",   // 覆蓋已存在的檔案
-                output_path,
-            ])
-            .output()
-            .await
-            .map_err(|e| format!("FFmpeg 執行失敗: {}", e))?;
-
-        if output.status.success() {
+        let mut args: Vec<String> = [
+            "-i", input_path, // 輸入檔案
+            "-ss", start_time, // 開始時間
+            "-to", end_time, // 結束時間
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+        match &self.encoding {
+            // 依設定重新編碼 (位元率、單聲道、取樣率)
+            Some(encoding) => {
+                args.extend(["-b:a".to_string(), format!("{}k", encoding.bitrate_kbps)]);
+                if encoding.mono {
+                    args.extend(["-ac".to_string(), "1".to_string()]);
+                }
+                if let Some(rate) = encoding.sample_rate {
+                    args.extend(["-ar".to_string(), rate.to_string()]);
+                }
+            }
+            // 直接複製，不重新編碼（速度快）
+            None => args.extend(["-c".to_string(), "copy".to_string()]),
+        }
+        args.push(self.collision.ffmpeg_flag().to_string()); // -y 覆寫 / -n 不覆寫
+        args.push(output_path.to_string());
+        let output = ffmpeg.run(args, self.cancel.as_ref()).await?;
+
+        if output.success() {
             Ok(output_path.to_string())
         } else {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            Err(format!("FFmpeg 切割失敗: {}", stderr))
+            Err(format!("FFmpeg 切割失敗: {}", output.stderr))
         }
     }
 
+    /// 以秒數指定範圍切割 (播放器選取範圍匯出)
+    pub async fn split_range(
+        &self,
+        ffmpeg: &Ffmpeg,
+        input_path: &str,
+        output_path: &str,
+        start_secs: f64,
+        end_secs: f64,
+    ) -> Result<String, String> {
+        self.split_segment(
+            ffmpeg,
+            input_path,
+            output_path,
+            &format_timestamp(start_secs),
+            &format_timestamp(end_secs),
+        )
+        .await
+    }
+
     /// 批次切割多個段落
     pub async fn split_segments(
         &self,
-        app: &AppHandle,
+        ffmpeg: &Ffmpeg,
         input_path: &str,
         output_dir: &str,
         segments: Vec<(String, String, String)>, // (name, start_time, end_time)
     ) -> Result<Vec<String>, String> {
-        let input = Path::new(input_path);
-
-        // 取得原檔副檔名
-        let ext = input.extension().and_then(|e| e.to_str()).unwrap_or("mp3");
-
         let mut output_files = Vec::new();
 
         for (name, start_time, end_time) in segments {
-            // 輸出檔案路徑: output_dir/段落名稱.副檔名
-            let output_path = format!("{}/{}.{}", output_dir, name, ext);
+            // 輸出檔案路徑: output_dir/段落名稱.副檔名 (已有同名檔案時依設定處理)
+            let output_path = self
+                .collision
+                .resolve(PathBuf::from(Self::output_path(
+                    input_path, output_dir, &name,
+                )))
+                .map_err(|e| format!("切割 '{}' 失敗: {}", name, e))?
+                .to_string_lossy()
+                .to_string();
 
             match self
-                .split_segment(app, input_path, &output_path, &start_time, &end_time)
+                .split_segment(ffmpeg, input_path, &output_path, &start_time, &end_time)
                 .await
             {
-                Ok(path) => output_files.push(path),
+                Ok(path) => {
+                    if let Some(on_output) = &self.on_output {
+                        on_output(&path);
+                    }
+                    output_files.push(path)
+                }
                 Err(e) => return Err(format!("切割 '{}' 失敗: {}", name, e)),
             }
         }
@@ -95,6 +195,6 @@ impl Splitter {
 
     #[deprecated(note = "使用 split_segment 或 split_segments 替代")]
     pub fn execute(&self) {
-        println!("(Split) 正在執行音訊切割 (Service Layer)...");
+        tracing::debug!("(Split) 正在執行音訊切割 (Service Layer)...");
     }
 }
@@ -0,0 +1,150 @@
+// src-tauri/src/services/storage.rs
+//
+// 重度處理 (轉檔、切割、消音) 前的磁碟空間檢查，
+// 避免 FFmpeg 寫到一半因空間不足而失敗。
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::{i18n, probe};
+use serde::Serialize;
+use std::path::Path;
+
+/// 轉檔輸出位元率 (與 Converter 的 -ab 192k 一致)
+const MP3_OUTPUT_BITRATE_BPS: u64 = 192_000;
+
+/// 額外保留的空間 (50 MB)
+const SAFETY_MARGIN_BYTES: u64 = 50 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpaceCheck {
+    /// 檢查的目標資料夾
+    pub target: String,
+    /// 預估需要的空間 (bytes，含保留空間)
+    pub required_bytes: u64,
+    /// 目標磁碟可用空間 (bytes)
+    pub available_bytes: u64,
+    pub sufficient: bool,
+}
+
+impl SpaceCheck {
+    pub fn message(&self) -> String {
+        i18n::tr("error.insufficient_space_detail", &self.message_params())
+    }
+
+    /// 訊息參數：required / available 為顯示用的大小，*_bytes 為原始數值供前端使用
+    fn message_params(&self) -> [(&'static str, String); 5] {
+        [
+            ("target", self.target.clone()),
+            ("required", format_bytes(self.required_bytes)),
+            ("available", format_bytes(self.available_bytes)),
+            ("required_bytes", self.required_bytes.to_string()),
+            ("available_bytes", self.available_bytes.to_string()),
+        ]
+    }
+
+    /// 空間不足時轉為 insufficient_space 錯誤
+    pub fn ensure(self) -> Result<SpaceCheck, AppError> {
+        if self.sufficient {
+            return Ok(self);
+        }
+        Err(AppError::localized(
+            ErrorKind::InsufficientSpace,
+            "error.insufficient_space_detail",
+            &self.message_params(),
+        )
+        .with_detail(format!(
+            "required={} available={}",
+            self.required_bytes, self.available_bytes
+        )))
+    }
+}
+
+/// 檢查目標資料夾所在磁碟是否有足夠空間
+/// 資料夾尚未建立時，往上找第一個存在的上層目錄
+pub fn check_space(target_dir: &Path, estimated_bytes: u64) -> Result<SpaceCheck, AppError> {
+    let existing = target_dir
+        .ancestors()
+        .find(|p| p.exists())
+        .ok_or_else(|| AppError::io(format!("無法找到目標磁碟: {}", target_dir.display())))?;
+
+    let available_bytes = fs2::available_space(existing)
+        .map_err(|e| AppError::io(format!("無法取得磁碟可用空間: {}", e)))?;
+    let required_bytes = estimated_bytes.saturating_add(SAFETY_MARGIN_BYTES);
+
+    Ok(SpaceCheck {
+        target: target_dir.to_string_lossy().to_string(),
+        required_bytes,
+        available_bytes,
+        sufficient: available_bytes >= required_bytes,
+    })
+}
+
+/// 空間不足時回傳 insufficient_space 錯誤 (參數含需要與可用的空間)，供處理流程開始前呼叫
+pub fn ensure_space(target_dir: &Path, estimated_bytes: u64) -> Result<SpaceCheck, AppError> {
+    check_space(target_dir, estimated_bytes)?.ensure()
+}
+
+/// 預估轉成 MP3 後的大小：以時長 × 輸出位元率計算，
+/// 無法取得時長 (例如影片) 時以原檔大小估計
+pub fn estimate_mp3_size(input_path: &str) -> u64 {
+    match probe::cached_duration(input_path) {
+        Ok(duration) => (duration * MP3_OUTPUT_BITRATE_BPS as f64 / 8.0) as u64,
+        Err(_) => file_size(input_path),
+    }
+}
+
+/// 預估切出的片段大小 (直接複製串流)：原檔大小 × 片段時長比例
+pub fn estimate_segment_size(input_path: &str, segment_seconds: f64) -> u64 {
+    let size = file_size(input_path);
+    match probe::cached_duration(input_path) {
+        Ok(duration) if duration > 0.0 => {
+            (size as f64 * (segment_seconds / duration).min(1.0)) as u64
+        }
+        _ => size,
+    }
+}
+
+pub fn file_size(path: &str) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+pub(crate) fn format_bytes(bytes: u64) -> String {
+    const MB: f64 = 1024.0 * 1024.0;
+    if bytes as f64 >= 1024.0 * MB {
+        format!("{:.2} GB", bytes as f64 / (1024.0 * MB))
+    } else {
+        format!("{:.1} MB", bytes as f64 / MB)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn insufficient_space_is_a_structured_error() {
+        let check = SpaceCheck {
+            target: "/data/project".into(),
+            required_bytes: 3 * 1024 * 1024 * 1024,
+            available_bytes: 512 * 1024 * 1024,
+            sufficient: false,
+        };
+        let error = check.ensure().unwrap_err();
+        assert_eq!(error.kind, ErrorKind::InsufficientSpace);
+        assert!(error.recoverable);
+        assert_eq!(error.params["required_bytes"], "3221225472");
+        assert_eq!(error.params["available_bytes"], "536870912");
+        assert_eq!(error.params["required"], "3.00 GB");
+        assert_eq!(error.params["available"], "512.0 MB");
+    }
+
+    #[test]
+    fn ensure_space_reports_the_check() {
+        let dir = std::env::temp_dir();
+        let check = check_space(&dir.join("not-created-yet"), 0).unwrap();
+        assert_eq!(check.required_bytes, SAFETY_MARGIN_BYTES);
+        assert_eq!(
+            ensure_space(&dir, u64::MAX).unwrap_err().kind,
+            ErrorKind::InsufficientSpace
+        );
+    }
+}
@@ -0,0 +1,284 @@
+// src-tauri/src/services/stt_models.rs
+//
+// 本機 STT / VAD 模型檔管理 (供本機 Whisper 後端使用)。
+// 模型檔動輒數 GB，不放進安裝檔，改由使用者在設定中下載到 app data 的 models/。
+//
+// - 下載時先寫入 *.part，同時計算 SHA-256，與目錄中固定的 SHA-256 相符才改名為正式檔名
+// - 目錄未列出 SHA-256 的模型不提供下載 (不信任下載來源自己回報的雜湊)
+
+use crate::models::{AppError, ErrorKind};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// 下載進度事件
+pub const MODEL_PROGRESS_EVENT: &str = "models://progress";
+
+/// app data 底下的模型資料夾
+pub const MODELS_DIR_NAME: &str = "models";
+
+/// 下載中的模型 (避免同一模型同時下載兩次)
+pub type ModelDownloads = Mutex<HashSet<String>>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModelKind {
+    Stt,
+    Vad,
+}
+
+/// 可下載的模型
+struct CatalogEntry {
+    id: &'static str,
+    kind: ModelKind,
+    file_name: &'static str,
+    url: &'static str,
+    /// 約略大小 (bytes)，下載前顯示用
+    size: u64,
+    /// 固定的 SHA-256 (小寫十六進位)；None 時拒絕下載，新增模型時須一併填入
+    sha256: Option<&'static str>,
+}
+
+const CATALOG: &[CatalogEntry] = &[
+    CatalogEntry {
+        id: "whisper-tiny",
+        kind: ModelKind::Stt,
+        file_name: "ggml-tiny.bin",
+        url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-tiny.bin",
+        size: 77_691_713,
+        sha256: None,
+    },
+    CatalogEntry {
+        id: "whisper-base",
+        kind: ModelKind::Stt,
+        file_name: "ggml-base.bin",
+        url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-base.bin",
+        size: 147_951_465,
+        sha256: None,
+    },
+    CatalogEntry {
+        id: "whisper-small",
+        kind: ModelKind::Stt,
+        file_name: "ggml-small.bin",
+        url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-small.bin",
+        size: 487_601_967,
+        sha256: None,
+    },
+    CatalogEntry {
+        id: "whisper-medium",
+        kind: ModelKind::Stt,
+        file_name: "ggml-medium.bin",
+        url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-medium.bin",
+        size: 1_533_763_059,
+        sha256: None,
+    },
+    CatalogEntry {
+        id: "whisper-large-v3",
+        kind: ModelKind::Stt,
+        file_name: "ggml-large-v3.bin",
+        url: "https://huggingface.co/ggerganov/whisper.cpp/resolve/main/ggml-large-v3.bin",
+        size: 3_095_033_483,
+        sha256: None,
+    },
+    CatalogEntry {
+        id: "silero-vad",
+        kind: ModelKind::Vad,
+        file_name: "ggml-silero-v5.1.2.bin",
+        url: "https://huggingface.co/ggml-org/whisper-vad/resolve/main/ggml-silero-v5.1.2.bin",
+        size: 885_098,
+        sha256: None,
+    },
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelInfo {
+    pub id: String,
+    pub kind: ModelKind,
+    pub file_name: String,
+    /// 目錄中的約略大小
+    pub size: u64,
+    pub installed: bool,
+    pub path: Option<String>,
+    /// 已下載檔案的實際大小
+    pub installed_size: Option<u64>,
+    pub downloading: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ModelProgress {
+    pub id: String,
+    pub downloaded: u64,
+    pub total: Option<u64>,
+}
+
+fn find(id: &str) -> Result<&'static CatalogEntry, AppError> {
+    CATALOG.iter().find(|e| e.id == id).ok_or_else(|| {
+        AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.model_unknown",
+            &[("id", id.to_string())],
+        )
+    })
+}
+
+pub fn models_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
+    app.path()
+        .app_data_dir()
+        .map(|d| d.join(MODELS_DIR_NAME))
+        .map_err(|e| AppError::internal(format!("無法取得 app data 目錄: {}", e)))
+}
+
+/// 已下載模型的路徑 (未下載時為 None)
+pub fn model_path(app: &AppHandle, id: &str) -> Option<PathBuf> {
+    let entry = find(id).ok()?;
+    let path = models_dir(app).ok()?.join(entry.file_name);
+    path.is_file().then_some(path)
+}
+
+/// 列出所有可下載的模型與安裝狀態
+pub fn list(app: &AppHandle, downloads: &ModelDownloads) -> Result<Vec<ModelInfo>, AppError> {
+    let dir = models_dir(app)?;
+    let active = downloads.lock().map(|d| d.clone()).unwrap_or_default();
+    Ok(CATALOG
+        .iter()
+        .map(|entry| {
+            let path = dir.join(entry.file_name);
+            let installed_size = fs::metadata(&path).ok().map(|m| m.len());
+            ModelInfo {
+                id: entry.id.to_string(),
+                kind: entry.kind,
+                file_name: entry.file_name.to_string(),
+                size: entry.size,
+                installed: installed_size.is_some(),
+                path: installed_size.map(|_| path.to_string_lossy().to_string()),
+                installed_size,
+                downloading: active.contains(entry.id),
+            }
+        })
+        .collect())
+}
+
+/// 下載模型並驗證 SHA-256，回傳安裝路徑
+pub async fn download(
+    app: &AppHandle,
+    downloads: &ModelDownloads,
+    id: &str,
+) -> Result<PathBuf, AppError> {
+    let entry = find(id)?;
+    let Some(expected) = entry.sha256 else {
+        return Err(AppError::localized(
+            ErrorKind::Unsupported,
+            "error.model_unverified",
+            &[("id", id.to_string())],
+        ));
+    };
+    {
+        let mut active = downloads
+            .lock()
+            .map_err(|_| AppError::internal("無法取得下載狀態"))?;
+        if !active.insert(id.to_string()) {
+            return Err(AppError::localized(
+                ErrorKind::InvalidInput,
+                "error.model_downloading",
+                &[("id", id.to_string())],
+            ));
+        }
+    }
+    let result = download_entry(app, entry, expected).await;
+    if let Ok(mut active) = downloads.lock() {
+        active.remove(id);
+    }
+    result
+}
+
+async fn download_entry(
+    app: &AppHandle,
+    entry: &CatalogEntry,
+    expected: &str,
+) -> Result<PathBuf, AppError> {
+    let dir = models_dir(app)?;
+    fs::create_dir_all(&dir)?;
+    crate::services::storage::ensure_space(&dir, entry.size)?;
+
+    let target = dir.join(entry.file_name);
+    let part = dir.join(format!("{}.part", entry.file_name));
+    tracing::info!("下載模型 {}: {}", entry.id, entry.url);
+
+    let digest = match fetch(app, entry, &part).await {
+        Ok(digest) => digest,
+        Err(e) => {
+            let _ = fs::remove_file(&part);
+            return Err(e);
+        }
+    };
+    if !digest.eq_ignore_ascii_case(expected) {
+        let _ = fs::remove_file(&part);
+        return Err(AppError::localized(
+            ErrorKind::Network,
+            "error.model_checksum",
+            &[("id", entry.id.to_string())],
+        )
+        .with_detail(format!("expected {}, got {}", expected, digest)));
+    }
+
+    fs::rename(&part, &target)?;
+    tracing::info!("模型已安裝到 {}", target.display());
+    Ok(target)
+}
+
+/// 下載到 part 檔，回傳實際的 SHA-256
+async fn fetch(app: &AppHandle, entry: &CatalogEntry, part: &Path) -> Result<String, AppError> {
+    let mut response = reqwest::get(entry.url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| AppError::network(format!("無法下載模型: {}", e)))?;
+
+    let total = response.content_length();
+    let mut file = fs::File::create(part)?;
+    let mut hasher = Sha256::new();
+    let mut downloaded = 0u64;
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| AppError::network(format!("下載中斷: {}", e)))?
+    {
+        file.write_all(&chunk)?;
+        hasher.update(&chunk);
+        downloaded += chunk.len() as u64;
+        let _ = app.emit(
+            MODEL_PROGRESS_EVENT,
+            ModelProgress {
+                id: entry.id.to_string(),
+                downloaded,
+                total,
+            },
+        );
+    }
+    file.flush()?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 刪除已下載的模型 (含未完成的下載檔)
+pub fn delete(app: &AppHandle, downloads: &ModelDownloads, id: &str) -> Result<(), AppError> {
+    let entry = find(id)?;
+    if downloads.lock().map(|d| d.contains(id)).unwrap_or(false) {
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.model_downloading",
+            &[("id", id.to_string())],
+        ));
+    }
+    let dir = models_dir(app)?;
+    let _ = fs::remove_file(dir.join(format!("{}.part", entry.file_name)));
+    let path = dir.join(entry.file_name);
+    if path.exists() {
+        fs::remove_file(&path)?;
+        tracing::info!("已刪除模型 {}", path.display());
+    }
+    Ok(())
+}
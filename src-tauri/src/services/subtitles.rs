@@ -0,0 +1,115 @@
+// src-tauri/src/services/subtitles.rs
+//
+// 由轉錄結果 (TranscribeResponse) 產生字幕檔 (SRT / WebVTT)，
+// 輸出到專案的 04_report，供病例討論會播放錄音時顯示字幕。
+
+use crate::models::AppError;
+use crate::services::encryption::{self, ProjectKey};
+use crate::services::file_manager::ProjectPaths;
+use crate::services::history;
+use crate::services::silence::TranscribeResponse;
+use crate::services::transcript;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubtitleFormat {
+    #[default]
+    Srt,
+    Vtt,
+}
+
+impl SubtitleFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            SubtitleFormat::Srt => "srt",
+            SubtitleFormat::Vtt => "vtt",
+        }
+    }
+}
+
+/// 產生字幕內容；speaker_prefix 時在每段前加上「說話者: 」
+pub fn render(
+    transcript: &TranscribeResponse,
+    format: SubtitleFormat,
+    speaker_prefix: bool,
+) -> String {
+    let mut out = String::new();
+    if format == SubtitleFormat::Vtt {
+        out.push_str("WEBVTT\n\n");
+    }
+    let cues = transcript
+        .segments
+        .iter()
+        .filter(|s| !s.text.trim().is_empty() && s.end > s.start);
+    for (index, segment) in cues.enumerate() {
+        if format == SubtitleFormat::Srt {
+            out.push_str(&format!("{}\n", index + 1));
+        }
+        out.push_str(&format!(
+            "{} --> {}\n",
+            timestamp(segment.start, format),
+            timestamp(segment.end, format)
+        ));
+        let text = segment.text.trim();
+        match transcript.speaker_name(segment).filter(|_| speaker_prefix) {
+            Some(speaker) if format == SubtitleFormat::Vtt => {
+                out.push_str(&format!("<v {}>{}\n\n", speaker, text))
+            }
+            Some(speaker) => out.push_str(&format!("{}: {}\n\n", speaker, text)),
+            None => out.push_str(&format!("{}\n\n", text)),
+        }
+    }
+    out
+}
+
+/// SRT: 00:01:02,345 / VTT: 00:01:02.345
+fn timestamp(seconds: f64, format: SubtitleFormat) -> String {
+    let total_ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    let (h, m, s, ms) = (
+        total_ms / 3_600_000,
+        total_ms / 60_000 % 60,
+        total_ms / 1000 % 60,
+        total_ms % 1000,
+    );
+    let separator = if format == SubtitleFormat::Srt {
+        ','
+    } else {
+        '.'
+    };
+    format!("{:02}:{:02}:{:02}{}{:03}", h, m, s, separator, ms)
+}
+
+/// 讀取轉錄 JSON 並輸出字幕檔，回傳輸出路徑
+/// 輸出到所屬專案的 04_report (不在專案內時放在 JSON 旁邊)；key 為加密專案的金鑰
+pub fn export(
+    transcript_path: &Path,
+    format: SubtitleFormat,
+    speaker_prefix: bool,
+    key: Option<&ProjectKey>,
+) -> Result<PathBuf, AppError> {
+    let transcript = transcript::load(transcript_path)?;
+
+    let output_dir = match history::project_root_for(transcript_path) {
+        Some(root) => ProjectPaths::from_existing_root(root).report,
+        None => transcript_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default(),
+    };
+    fs::create_dir_all(&output_dir)?;
+
+    let stem = Path::new(&transcript.filename)
+        .file_stem()
+        .or_else(|| transcript_path.file_stem())
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "transcript".to_string());
+    let output = output_dir.join(format!("{}.{}", stem, format.extension()));
+    encryption::write_protected(
+        key,
+        &output,
+        render(&transcript, format, speaker_prefix).as_bytes(),
+    )
+}
@@ -0,0 +1,154 @@
+// src-tauri/src/services/timeline_export.rs
+//
+// 將標註 (以及逐字稿段落時間軸) 輸出成外部編輯器可匯入的格式，
+// 讓需要精修的使用者在 Audacity 或 Reaper 等 DAW 內調整後再匯回。
+//
+// - Audacity 標籤軌：每行「開始<TAB>結束<TAB>標籤」，時間為秒
+// - Reaper 區段 CSV：Region/Marker Manager 匯出的格式 (#,Name,Start,End,Length)
+// - 輸出到專案的 04_report
+
+use crate::models::AppError;
+use crate::services::annotations::{Annotation, AnnotationCategory};
+use crate::services::encryption::{self, ProjectKey};
+use crate::services::file_manager::ProjectPaths;
+use crate::services::silence::TranscribeResponse;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineFormat {
+    #[default]
+    AudacityLabels,
+    ReaperRegions,
+}
+
+impl TimelineFormat {
+    /// 輸出檔名的後綴與副檔名
+    pub fn file_suffix(self) -> &'static str {
+        match self {
+            TimelineFormat::AudacityLabels => "labels.txt",
+            TimelineFormat::ReaperRegions => "regions.csv",
+        }
+    }
+}
+
+/// 時間軸上的一個區段
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimelineEntry {
+    pub start: f64,
+    pub end: f64,
+    pub label: String,
+}
+
+/// 標註的標籤：「[類別] 筆記」，匯入時依前綴還原類別
+pub fn annotation_label(annotation: &Annotation) -> String {
+    let tag = match annotation.category {
+        AnnotationCategory::Redact => "[redact]",
+        AnnotationCategory::Important => "[important]",
+        AnnotationCategory::Question => "[question]",
+    };
+    if annotation.note.is_empty() {
+        tag.to_string()
+    } else {
+        format!("{} {}", tag, annotation.note)
+    }
+}
+
+/// 組出時間軸：標註在前，逐字稿段落 (有提供時) 以「[transcript] 文字」接在後面
+pub fn entries(
+    annotations: &[Annotation],
+    transcript: Option<&TranscribeResponse>,
+) -> Vec<TimelineEntry> {
+    let mut entries: Vec<TimelineEntry> = annotations
+        .iter()
+        .map(|a| TimelineEntry {
+            start: a.start,
+            end: a.end,
+            label: annotation_label(a),
+        })
+        .collect();
+    if let Some(transcript) = transcript {
+        entries.extend(
+            transcript
+                .segments
+                .iter()
+                .filter(|s| s.end > s.start)
+                .map(|s| TimelineEntry {
+                    start: s.start,
+                    end: s.end,
+                    label: format!("[transcript] {}", s.text.trim()),
+                }),
+        );
+    }
+    entries.sort_by(|a, b| a.start.total_cmp(&b.start));
+    entries
+}
+
+/// 標籤內的換行與 tab 改為空白 (兩種格式都是一行一筆)
+fn single_line(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect()
+}
+
+/// CSV 欄位：含逗號或引號時加上引號
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// 產生輸出內容
+pub fn render(entries: &[TimelineEntry], format: TimelineFormat) -> String {
+    let mut out = String::new();
+    match format {
+        TimelineFormat::AudacityLabels => {
+            for entry in entries {
+                out.push_str(&format!(
+                    "{:.6}\t{:.6}\t{}\n",
+                    entry.start,
+                    entry.end,
+                    single_line(&entry.label)
+                ));
+            }
+        }
+        TimelineFormat::ReaperRegions => {
+            out.push_str("#,Name,Start,End,Length\n");
+            for (index, entry) in entries.iter().enumerate() {
+                out.push_str(&format!(
+                    "R{},{},{:.3},{:.3},{:.3}\n",
+                    index + 1,
+                    csv_field(&single_line(&entry.label)),
+                    entry.start,
+                    entry.end,
+                    entry.end - entry.start
+                ));
+            }
+        }
+    }
+    out
+}
+
+/// 輸出音檔的時間軸，回傳輸出路徑 (專案 04_report/<音檔名>.labels.txt 或 .regions.csv)
+/// key 為加密專案的金鑰 (輸出為加密檔)
+pub fn export(
+    root: &Path,
+    audio_path: &Path,
+    entries: &[TimelineEntry],
+    format: TimelineFormat,
+    key: Option<&ProjectKey>,
+) -> Result<PathBuf, AppError> {
+    let output_dir = ProjectPaths::from_existing_root(root.to_path_buf()).report;
+    fs::create_dir_all(&output_dir)?;
+    let stem = audio_path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "timeline".to_string());
+    let output = output_dir.join(format!("{}.{}", stem, format.file_suffix()));
+    encryption::write_protected(key, &output, render(entries, format).as_bytes())
+}
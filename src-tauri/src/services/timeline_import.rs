@@ -0,0 +1,180 @@
+// src-tauri/src/services/timeline_import.rs
+//
+// 匯入外部時間軸 (Audacity 標籤軌、SRT、WebVTT)，轉成切割或消音用的段落。
+// 搭配 timeline_export：在 Audacity 等編輯器精修後再匯回。
+//
+// - 依副檔名判斷格式：.txt 為 Audacity 標籤，.srt / .vtt 為字幕
+// - 本程式匯出的「[類別] 筆記」標籤會去掉類別前綴
+// - 重疊的區段：消音時合併為一段，切割時後一段從前一段結束處開始 (完全被包含的略過)
+
+use crate::services::timeline_export::TimelineEntry;
+use crate::services::workflows::parse_time;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// 本程式匯出時加在標籤前的類別
+const EXPORT_TAGS: &[&str] = &["[redact]", "[important]", "[question]", "[transcript]"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimelineSource {
+    AudacityLabels,
+    Srt,
+    Vtt,
+}
+
+impl TimelineSource {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "txt" => Some(TimelineSource::AudacityLabels),
+            "srt" => Some(TimelineSource::Srt),
+            "vtt" => Some(TimelineSource::Vtt),
+            _ => None,
+        }
+    }
+}
+
+/// 去掉匯出時加上的類別前綴
+fn clean_label(label: &str) -> String {
+    let label = label.trim();
+    EXPORT_TAGS
+        .iter()
+        .find_map(|tag| label.strip_prefix(tag))
+        .unwrap_or(label)
+        .trim()
+        .to_string()
+}
+
+/// Audacity 標籤：「開始<TAB>結束<TAB>標籤」，頻率範圍列以 `\` 開頭
+fn parse_audacity(text: &str) -> Result<Vec<TimelineEntry>, String> {
+    let mut entries = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('\\') {
+            continue;
+        }
+        let mut fields = line.splitn(3, '\t');
+        let parse = |value: Option<&str>| -> Result<f64, String> {
+            value
+                .and_then(|v| v.trim().parse::<f64>().ok())
+                .ok_or_else(|| format!("第 {} 行不是有效的 Audacity 標籤", index + 1))
+        };
+        let start = parse(fields.next())?;
+        let end = parse(fields.next())?;
+        entries.push(TimelineEntry {
+            start,
+            end,
+            label: clean_label(fields.next().unwrap_or_default()),
+        });
+    }
+    Ok(entries)
+}
+
+/// 字幕時間：SRT 以 `,` 分隔毫秒，VTT 以 `.`，VTT 可省略小時
+fn parse_cue_time(value: &str) -> Result<f64, String> {
+    parse_time(&value.trim().replace(',', "."))
+}
+
+/// 去掉 VTT 的標記 (<v 說話者>、<i> 等)
+fn strip_tags(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut in_tag = false;
+    for c in text.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            c if !in_tag => out.push(c),
+            _ => {}
+        }
+    }
+    out
+}
+
+/// SRT 與 VTT 共用：找出「開始 --> 結束」列，其後到空行為止是字幕文字
+fn parse_subtitles(text: &str) -> Result<Vec<TimelineEntry>, String> {
+    let mut entries = Vec::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let Some((start, rest)) = line.split_once("-->") else {
+            continue;
+        };
+        // VTT 的時間後面可能接 cue 設定 (align:start 等)
+        let end = rest.split_whitespace().next().unwrap_or_default();
+        let invalid = |_| format!("第 {} 行不是有效的字幕時間", index + 1);
+        let start = parse_cue_time(start).map_err(invalid)?;
+        let end = parse_cue_time(end).map_err(invalid)?;
+        let mut label = Vec::new();
+        for (_, text_line) in lines.by_ref() {
+            if text_line.trim().is_empty() {
+                break;
+            }
+            label.push(strip_tags(text_line).trim().to_string());
+        }
+        entries.push(TimelineEntry {
+            start,
+            end,
+            label: clean_label(&label.join(" ")),
+        });
+    }
+    Ok(entries)
+}
+
+/// 讀取時間軸檔案 (依開始時間排序，略過長度為零或時間無效的區段)
+pub fn parse(path: &Path) -> Result<(TimelineSource, Vec<TimelineEntry>), String> {
+    let source =
+        TimelineSource::from_path(path).ok_or("只支援 Audacity 標籤 (.txt)、SRT 與 VTT 檔案")?;
+    let bytes = std::fs::read(path).map_err(|e| format!("無法讀取檔案: {}", e))?;
+    let text = String::from_utf8_lossy(&bytes);
+    let text = text.trim_start_matches('\u{feff}');
+    let mut entries = match source {
+        TimelineSource::AudacityLabels => parse_audacity(text)?,
+        TimelineSource::Srt | TimelineSource::Vtt => parse_subtitles(text)?,
+    };
+    entries
+        .retain(|e| e.start.is_finite() && e.end.is_finite() && e.start >= 0.0 && e.end > e.start);
+    entries.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok((source, entries))
+}
+
+/// 消音用：重疊或相接的區段合併 (標籤以「 / 」串接)，回傳 (結果, 合併掉的數量)
+pub fn merge_overlaps(entries: &[TimelineEntry]) -> (Vec<TimelineEntry>, usize) {
+    let mut merged: Vec<TimelineEntry> = Vec::new();
+    for entry in entries {
+        match merged.last_mut() {
+            Some(last) if entry.start <= last.end => {
+                last.end = last.end.max(entry.end);
+                if !entry.label.is_empty() && last.label != entry.label {
+                    if !last.label.is_empty() {
+                        last.label.push_str(" / ");
+                    }
+                    last.label.push_str(&entry.label);
+                }
+            }
+            _ => merged.push(entry.clone()),
+        }
+    }
+    let removed = entries.len() - merged.len();
+    (merged, removed)
+}
+
+/// 切割用：後一段與前一段重疊時從前一段結束處開始，完全被包含的略過
+/// 回傳 (結果, 調整或略過的數量)
+pub fn trim_overlaps(entries: &[TimelineEntry]) -> (Vec<TimelineEntry>, usize) {
+    let mut trimmed: Vec<TimelineEntry> = Vec::new();
+    let mut adjusted = 0;
+    for entry in entries {
+        let mut entry = entry.clone();
+        if let Some(last) = trimmed.last() {
+            if entry.end <= last.end {
+                adjusted += 1;
+                continue;
+            }
+            if entry.start < last.end {
+                entry.start = last.end;
+                adjusted += 1;
+            }
+        }
+        trimmed.push(entry);
+    }
+    (trimmed, adjusted)
+}
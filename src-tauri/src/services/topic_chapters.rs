@@ -0,0 +1,138 @@
+// src-tauri/src/services/topic_chapters.rs
+//
+// 依主題自動分章：把長錄音的逐字稿 (不是音檔) 交給 Gemini，依討論主題提出章節邊界與標題，
+// 結果以章節 (chapters::Chapter) 回傳，可直接轉成切割段落。
+//
+// - 逐字稿以「[秒數] 說話者: 文字」逐段列出，章節開始時間對齊到最接近的段落開頭
+// - 章節首尾相接：第一章從 0 秒開始，每章到下一章開始為止，最後一章到錄音結尾
+// - 短於 MIN_CHAPTER_SECS 的章節併入前一章
+
+use crate::services::chapters::Chapter;
+use crate::services::report::ReportAgent;
+use crate::services::silence::TranscribeResponse;
+use serde::Deserialize;
+
+/// 章節的最短長度 (秒)
+pub const MIN_CHAPTER_SECS: f64 = 60.0;
+
+const PROMPT: &str = r#"以下是一段長時間醫療會議 (例如查房、晨會) 的逐字稿，每行開頭的 [數字] 是該段在錄音中的開始秒數。
+請依討論的主題 (例如不同病人、不同議題) 把錄音分成章節，每個章節給一個簡短的繁體中文標題 (20 字以內)。
+章節不要太零碎，同一主題的連續討論放在同一章。
+只輸出 JSON 陣列，依時間排序，格式為 [{"title": "章節標題", "start": 章節開始秒數}]，start 必須是逐字稿中某一行的秒數。
+
+逐字稿：
+"#;
+
+#[derive(Debug, Deserialize)]
+struct Proposal {
+    title: String,
+    start: f64,
+}
+
+/// 逐字稿轉成提示詞內容：每段一行「[秒數] 說話者: 文字」
+fn transcript_lines(transcript: &TranscribeResponse) -> String {
+    transcript
+        .segments
+        .iter()
+        .filter(|s| !s.text.trim().is_empty())
+        .map(|s| match transcript.speaker_name(s) {
+            Some(speaker) => format!("[{:.1}] {}: {}\n", s.start, speaker, s.text.trim()),
+            None => format!("[{:.1}] {}\n", s.start, s.text.trim()),
+        })
+        .collect()
+}
+
+/// 取出回應中的 JSON 陣列 (Gemini 可能包在 ```json 區塊或前後加說明)
+fn parse_proposals(response: &str) -> Result<Vec<Proposal>, String> {
+    let json = match (response.find('['), response.rfind(']')) {
+        (Some(start), Some(end)) if start < end => &response[start..=end],
+        _ => return Err("Gemini 回應中沒有章節清單".to_string()),
+    };
+    serde_json::from_str(json).map_err(|e| format!("無法解析 Gemini 提出的章節: {}", e))
+}
+
+/// 錄音長度：轉錄結果的長度，沒有時以最後一段的結束時間代替
+fn transcript_duration(transcript: &TranscribeResponse) -> f64 {
+    transcript
+        .segments
+        .iter()
+        .map(|s| s.end)
+        .fold(transcript.duration, f64::max)
+}
+
+/// 提案轉為首尾相接的章節：開始時間對齊段落開頭，過短的章節併入前一章
+fn build_chapters(transcript: &TranscribeResponse, proposals: Vec<Proposal>) -> Vec<Chapter> {
+    let duration = transcript_duration(transcript);
+    if duration <= 0.0 {
+        return Vec::new();
+    }
+    let snap = |time: f64| {
+        transcript
+            .segments
+            .iter()
+            .map(|s| s.start)
+            .min_by(|a, b| (a - time).abs().total_cmp(&(b - time).abs()))
+            .unwrap_or(time)
+    };
+    let mut starts: Vec<(f64, String)> = proposals
+        .into_iter()
+        .filter(|p| p.start.is_finite() && p.start < duration)
+        .map(|p| (snap(p.start.max(0.0)), p.title.trim().to_string()))
+        .collect();
+    starts.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if let Some(first) = starts.first_mut() {
+        first.0 = 0.0;
+    }
+
+    let mut kept: Vec<(f64, String)> = Vec::new();
+    for (start, title) in starts {
+        match kept.last() {
+            Some((last, _)) if start - last < MIN_CHAPTER_SECS => {}
+            _ => kept.push((start, title)),
+        }
+    }
+    if kept.len() > 1
+        && kept
+            .last()
+            .is_some_and(|(start, _)| duration - start < MIN_CHAPTER_SECS)
+    {
+        kept.pop();
+    }
+
+    let ends: Vec<f64> = kept
+        .iter()
+        .skip(1)
+        .map(|(start, _)| *start)
+        .chain(std::iter::once(duration))
+        .collect();
+    kept.into_iter()
+        .zip(ends)
+        .enumerate()
+        .map(|(i, ((start, title), end))| Chapter {
+            index: i + 1,
+            title,
+            start,
+            end,
+        })
+        .collect()
+}
+
+/// 請 Gemini 依逐字稿提出主題章節
+pub async fn propose(
+    agent: &ReportAgent,
+    model: &str,
+    transcript: &TranscribeResponse,
+) -> Result<Vec<Chapter>, String> {
+    let lines = transcript_lines(transcript);
+    if lines.is_empty() {
+        return Err("逐字稿沒有內容".to_string());
+    }
+    let response = agent
+        .generate_text(model, &format!("{}{}", PROMPT, lines))
+        .await?;
+    let chapters = build_chapters(transcript, parse_proposals(&response)?);
+    if chapters.is_empty() {
+        return Err("Gemini 沒有提出可用的章節".to_string());
+    }
+    Ok(chapters)
+}
@@ -0,0 +1,465 @@
+// src-tauri/src/services/transcript.rs
+//
+// 轉錄結果 (TranscribeResponse JSON) 的編輯：改名、修正文字、合併 / 分割段落、調整時間。
+// 每次儲存前把目前內容另存為一個版本 (.versions/<檔名>/v0001.json ...)，可查看與還原。
+// 修正後的內容直接寫回原檔，字幕匯出、消音與報告都讀取同一個檔案。
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::file_manager::write_atomic;
+use crate::services::silence::{AlignedSegment, Segment, TranscribeResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 版本資料夾 (與轉錄 JSON 同一層)
+const VERSIONS_DIR_NAME: &str = ".versions";
+
+/// 低於此信心分數的段落需要仔細聽 (未指定門檻時)
+pub const DEFAULT_CONFIDENCE_THRESHOLD: f64 = 0.6;
+
+/// 說話者時段的處理方式
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpeakerAction {
+    /// 在音檔中消音 (輸出到 03_silence)
+    #[default]
+    Silence,
+    /// 只取出該說話者的聲音 (輸出到 05_extracted)
+    Extract,
+}
+
+/// 單一編輯動作 (index 為段落索引，從 0 開始)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum TranscriptEdit {
+    Rename {
+        index: usize,
+        name: String,
+    },
+    SetText {
+        index: usize,
+        text: String,
+    },
+    SetSpeaker {
+        index: usize,
+        speaker: Option<String>,
+    },
+    SetTimes {
+        index: usize,
+        start: f64,
+        end: f64,
+    },
+    /// 與下一段合併
+    Merge {
+        index: usize,
+    },
+    /// 在 at 秒分割；text_at 為文字分割位置 (字元數)，未指定時依時間比例
+    Split {
+        index: usize,
+        at: f64,
+        text_at: Option<usize>,
+    },
+    Delete {
+        index: usize,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptDocument {
+    /// 已保存的版本數 (目前內容為第 version + 1 版)
+    pub version: u32,
+    pub transcript: TranscribeResponse,
+}
+
+/// 說話者統計 (選擇要命名或消音的說話者)
+#[derive(Debug, Clone, Serialize)]
+pub struct SpeakerInfo {
+    pub id: String,
+    pub name: Option<String>,
+    pub segments: usize,
+    /// 說話總秒數
+    pub duration: f64,
+}
+
+/// 信心分數偏低、產生報告前需要再聽一次的段落
+#[derive(Debug, Clone, Serialize)]
+pub struct LowConfidenceSegment {
+    pub index: usize,
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+    pub speaker: Option<String>,
+    pub confidence: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TranscriptVersion {
+    pub version: u32,
+    pub path: String,
+    pub saved_at: Option<String>,
+    pub segments: usize,
+}
+
+pub fn load(path: &Path) -> Result<TranscribeResponse, AppError> {
+    let content = fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|e| {
+        AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.invalid_json",
+            &[
+                ("path", path.display().to_string()),
+                ("detail", e.to_string()),
+            ],
+        )
+    })
+}
+
+pub fn open(path: &Path) -> Result<TranscriptDocument, AppError> {
+    Ok(TranscriptDocument {
+        version: latest_version(path),
+        transcript: load(path)?,
+    })
+}
+
+/// 套用編輯 (全部成功才儲存)，儲存前先保存目前版本
+pub fn edit(path: &Path, edits: &[TranscriptEdit]) -> Result<TranscriptDocument, AppError> {
+    let mut transcript = load(path)?;
+    for edit in edits {
+        apply(&mut transcript.segments, edit)?;
+    }
+    transcript.full_text = full_text(&transcript.segments);
+    save(path, &transcript)
+}
+
+/// 還原指定版本 (還原前的內容也會保存為新版本)
+pub fn restore(path: &Path, version: u32) -> Result<TranscriptDocument, AppError> {
+    let version_path = version_path(path, version);
+    if !version_path.is_file() {
+        return Err(AppError::localized(
+            ErrorKind::NotFound,
+            "error.transcript_version_not_found",
+            &[("version", version.to_string())],
+        ));
+    }
+    let transcript = load(&version_path)?;
+    save(path, &transcript)
+}
+
+/// 套用強制對齊的結果 (文字不變，只更新段落與逐字時間)，儲存前先保存目前版本
+pub fn apply_alignment(
+    path: &Path,
+    aligned: Vec<AlignedSegment>,
+) -> Result<TranscriptDocument, AppError> {
+    let mut transcript = load(path)?;
+    if aligned.len() != transcript.segments.len() {
+        return Err(AppError::localized(
+            ErrorKind::Network,
+            "error.alignment_mismatch",
+            &[
+                ("expected", transcript.segments.len().to_string()),
+                ("actual", aligned.len().to_string()),
+            ],
+        ));
+    }
+    for (segment, aligned) in transcript.segments.iter_mut().zip(aligned) {
+        segment.start = aligned.start;
+        segment.end = aligned.end;
+        segment.words = aligned.words;
+    }
+    save(path, &transcript)
+}
+
+/// 列出逐字稿中的說話者 (依說話時間由長到短)
+pub fn speakers(transcript: &TranscribeResponse) -> Vec<SpeakerInfo> {
+    let mut stats: BTreeMap<&str, (usize, f64)> = BTreeMap::new();
+    for segment in &transcript.segments {
+        if let Some(id) = segment.speaker.as_deref() {
+            let entry = stats.entry(id).or_default();
+            entry.0 += 1;
+            entry.1 += (segment.end - segment.start).max(0.0);
+        }
+    }
+    let mut speakers: Vec<SpeakerInfo> = stats
+        .into_iter()
+        .map(|(id, (segments, duration))| SpeakerInfo {
+            id: id.to_string(),
+            name: transcript.speakers.get(id).cloned(),
+            segments,
+            duration,
+        })
+        .collect();
+    speakers.sort_by(|a, b| b.duration.total_cmp(&a.duration));
+    speakers
+}
+
+/// 信心分數低於 threshold 的段落 (依時間順序)；沒有分數的段落不列出
+pub fn low_confidence(
+    transcript: &TranscribeResponse,
+    threshold: f64,
+) -> Vec<LowConfidenceSegment> {
+    transcript
+        .segments
+        .iter()
+        .enumerate()
+        .filter_map(|(index, segment)| {
+            let confidence = segment.confidence.filter(|c| *c < threshold)?;
+            Some(LowConfidenceSegment {
+                index,
+                start: segment.start,
+                end: segment.end,
+                text: segment.text.clone(),
+                speaker: transcript.speaker_name(segment).map(str::to_string),
+                confidence,
+            })
+        })
+        .collect()
+}
+
+/// 指定說話者 (ID 或顯示名稱) 的所有時段，前後各加 padding 秒，重疊或相鄰的時段合併
+pub fn speaker_ranges(
+    transcript: &TranscribeResponse,
+    speaker: &str,
+    padding: f64,
+) -> Vec<(f64, f64)> {
+    let mut ranges: Vec<(f64, f64)> = transcript
+        .segments
+        .iter()
+        .filter(|s| {
+            s.speaker.as_deref() == Some(speaker) || transcript.speaker_name(s) == Some(speaker)
+        })
+        .map(|s| ((s.start - padding).max(0.0), s.end + padding))
+        .collect();
+    ranges.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+    let mut merged: Vec<(f64, f64)> = Vec::new();
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if start <= last.1 => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
+}
+
+/// 設定說話者顯示名稱 (空字串表示移除)，儲存前先保存目前版本
+pub fn set_speaker_names(
+    path: &Path,
+    names: &BTreeMap<String, String>,
+) -> Result<TranscriptDocument, AppError> {
+    let mut transcript = load(path)?;
+    for (id, name) in names {
+        let name = name.trim();
+        if name.is_empty() {
+            transcript.speakers.remove(id);
+        } else {
+            transcript.speakers.insert(id.clone(), name.to_string());
+        }
+    }
+    save(path, &transcript)
+}
+
+pub fn list_versions(path: &Path) -> Vec<TranscriptVersion> {
+    (1..=latest_version(path))
+        .rev()
+        .filter_map(|version| {
+            let file = version_path(path, version);
+            let saved_at = fs::metadata(&file)
+                .and_then(|m| m.modified())
+                .ok()
+                .map(|t| chrono::DateTime::<chrono::Local>::from(t).to_rfc3339());
+            let segments = load(&file).ok()?.segments.len();
+            Some(TranscriptVersion {
+                version,
+                path: file.to_string_lossy().to_string(),
+                saved_at,
+                segments,
+            })
+        })
+        .collect()
+}
+
+fn save(path: &Path, transcript: &TranscribeResponse) -> Result<TranscriptDocument, AppError> {
+    let version = latest_version(path) + 1;
+    let version_path = version_path(path, version);
+    if let Some(parent) = version_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::copy(path, &version_path)?;
+
+    let content =
+        serde_json::to_string_pretty(transcript).map_err(|e| AppError::internal(e.to_string()))?;
+    write_atomic(path, content.as_bytes())?;
+    Ok(TranscriptDocument {
+        version,
+        transcript: transcript.clone(),
+    })
+}
+
+fn versions_dir(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(VERSIONS_DIR_NAME).join(name)
+}
+
+fn version_path(path: &Path, version: u32) -> PathBuf {
+    versions_dir(path).join(format!("v{:04}.json", version))
+}
+
+fn latest_version(path: &Path) -> u32 {
+    fs::read_dir(versions_dir(path))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter_map(|e| {
+                    let name = e.file_name().to_string_lossy().to_string();
+                    name.strip_prefix('v')?.strip_suffix(".json")?.parse().ok()
+                })
+                .max()
+                .unwrap_or(0)
+        })
+        .unwrap_or(0)
+}
+
+fn full_text(segments: &[Segment]) -> String {
+    segments
+        .iter()
+        .map(|s| s.text.trim())
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn segment_index_error(index: usize) -> AppError {
+    AppError::localized(
+        ErrorKind::InvalidInput,
+        "error.transcript_segment_index",
+        &[("index", index.to_string())],
+    )
+}
+
+fn invalid_time(index: usize) -> AppError {
+    AppError::localized(
+        ErrorKind::InvalidInput,
+        "error.transcript_invalid_time",
+        &[("index", index.to_string())],
+    )
+}
+
+fn apply(segments: &mut Vec<Segment>, edit: &TranscriptEdit) -> Result<(), AppError> {
+    match edit {
+        TranscriptEdit::Rename { index, name } => {
+            segment_mut(segments, *index)?.name = name.clone();
+        }
+        TranscriptEdit::SetText { index, text } => {
+            let segment = segment_mut(segments, *index)?;
+            segment.text = text.clone();
+            // 原本的索引與逐字時間對應舊文字，需重新對齊
+            segment.start_idx = None;
+            segment.end_idx = None;
+            segment.words.clear();
+            // 已人工校正，不再列為低信心段落
+            segment.confidence = None;
+        }
+        TranscriptEdit::SetSpeaker { index, speaker } => {
+            segment_mut(segments, *index)?.speaker = speaker.clone();
+        }
+        TranscriptEdit::SetTimes { index, start, end } => {
+            if !(start.is_finite() && end.is_finite() && *start >= 0.0 && end > start) {
+                return Err(invalid_time(*index));
+            }
+            let segment = segment_mut(segments, *index)?;
+            segment.start = *start;
+            segment.end = *end;
+        }
+        TranscriptEdit::Merge { index } => {
+            if index + 1 >= segments.len() {
+                return Err(segment_index_error(index + 1));
+            }
+            let next = segments.remove(index + 1);
+            let segment = &mut segments[*index];
+            segment.start = segment.start.min(next.start);
+            segment.end = segment.end.max(next.end);
+            segment.text = join_text(&segment.text, &next.text);
+            if segment.name.is_empty() {
+                segment.name = next.name;
+            }
+            segment.words.extend(next.words);
+            segment.confidence = match (segment.confidence, next.confidence) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+            segment.start_idx = None;
+            segment.end_idx = None;
+        }
+        TranscriptEdit::Split { index, at, text_at } => {
+            let segment = segment_mut(segments, *index)?;
+            if !(*at > segment.start && *at < segment.end) {
+                return Err(invalid_time(*index));
+            }
+            let chars: Vec<char> = segment.text.chars().collect();
+            let split_at = text_at
+                .unwrap_or_else(|| {
+                    let ratio = (at - segment.start) / (segment.end - segment.start);
+                    (chars.len() as f64 * ratio).round() as usize
+                })
+                .min(chars.len());
+            let mut second = segment.clone();
+            let (first_words, second_words) = std::mem::take(&mut segment.words)
+                .into_iter()
+                .partition(|w| w.start < *at);
+            segment.words = first_words;
+            second.words = second_words;
+            segment.text = chars[..split_at]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string();
+            segment.end = *at;
+            segment.start_idx = None;
+            segment.end_idx = None;
+            second.text = chars[split_at..]
+                .iter()
+                .collect::<String>()
+                .trim()
+                .to_string();
+            second.start = *at;
+            second.start_idx = None;
+            second.end_idx = None;
+            segments.insert(index + 1, second);
+        }
+        TranscriptEdit::Delete { index } => {
+            if *index >= segments.len() {
+                return Err(segment_index_error(*index));
+            }
+            segments.remove(*index);
+        }
+    }
+    Ok(())
+}
+
+/// 合併文字：英數字之間加空白，中文直接相連
+fn join_text(first: &str, second: &str) -> String {
+    let (first, second) = (first.trim(), second.trim());
+    let needs_space = first
+        .chars()
+        .last()
+        .is_some_and(|c| c.is_ascii_alphanumeric())
+        && second
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphanumeric());
+    if needs_space {
+        format!("{} {}", first, second)
+    } else {
+        format!("{}{}", first, second)
+    }
+}
+
+fn segment_mut(segments: &mut [Segment], index: usize) -> Result<&mut Segment, AppError> {
+    segments
+        .get_mut(index)
+        .ok_or_else(|| segment_index_error(index))
+}
@@ -0,0 +1,174 @@
+// src-tauri/src/services/trash.rs
+//
+// 刪除專案檔案時移到系統資源回收筒，誤刪時還能找回。
+//
+// - delete_files 預設移到回收筒；回收筒無法使用 (例如網路磁碟) 時回報失敗，不會改為直接刪除
+// - Windows 與 Linux (freedesktop) 可列出並還原回收筒中的項目，macOS 只能移入回收筒
+// - 流程中的清理 (03_silence 去重複等) 也經由這裡，不會直接刪除檔案
+
+use crate::services::waveform;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+/// 此平台能否從回收筒還原
+pub const RESTORE_SUPPORTED: bool = cfg!(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+));
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct DeleteResult {
+    /// 已刪除 (或移到回收筒) 的檔案
+    pub deleted: Vec<String>,
+    /// 失敗的檔案與原因
+    pub failed: Vec<String>,
+    pub to_trash: bool,
+    pub can_restore: bool,
+}
+
+/// 回收筒中的項目
+#[derive(Debug, Clone, Serialize)]
+pub struct TrashedFile {
+    /// 還原時使用的識別碼
+    pub id: String,
+    pub name: String,
+    pub original_path: String,
+    /// 刪除時間 (Unix 秒)
+    pub deleted_at: i64,
+}
+
+/// 把單一檔案或資料夾移到回收筒
+pub fn move_to_trash(path: &Path) -> Result<(), String> {
+    trash::delete(path).map_err(|e| e.to_string())
+}
+
+/// 刪除多個檔案；to_trash 為 false 時直接刪除 (無法復原)
+pub fn delete_files(paths: &[PathBuf], to_trash: bool) -> DeleteResult {
+    let mut result = DeleteResult {
+        to_trash,
+        can_restore: to_trash && RESTORE_SUPPORTED,
+        ..Default::default()
+    };
+    for path in paths {
+        let display = path.to_string_lossy().to_string();
+        let outcome = if !path.exists() {
+            Err(crate::tr!("error.file_not_found", path = display))
+        } else if to_trash {
+            move_to_trash(path)
+        } else if path.is_dir() {
+            std::fs::remove_dir_all(path).map_err(|e| e.to_string())
+        } else {
+            std::fs::remove_file(path).map_err(|e| e.to_string())
+        };
+        match outcome {
+            Ok(()) => {
+                // 波形快取跟著音檔移除 (還原後會重新計算)
+                let _ = std::fs::remove_file(waveform::peaks_path(path));
+                result.deleted.push(display)
+            }
+            Err(e) => result.failed.push(format!("{}: {}", display, e)),
+        }
+    }
+    result
+}
+
+#[cfg(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+))]
+mod platform {
+    use super::TrashedFile;
+    use std::path::Path;
+    use trash::os_limited;
+
+    fn to_trashed(item: &trash::TrashItem) -> TrashedFile {
+        let name = Path::new(&item.name).to_string_lossy().to_string();
+        TrashedFile {
+            id: item.id.to_string_lossy().to_string(),
+            original_path: item
+                .original_parent
+                .join(&name)
+                .to_string_lossy()
+                .to_string(),
+            name,
+            deleted_at: item.time_deleted,
+        }
+    }
+
+    pub fn list(within: &Path) -> Result<Vec<TrashedFile>, String> {
+        let items = os_limited::list().map_err(|e| e.to_string())?;
+        let mut files: Vec<TrashedFile> = items
+            .iter()
+            .filter(|item| item.original_parent.starts_with(within))
+            .map(to_trashed)
+            .collect();
+        files.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(files)
+    }
+
+    pub fn restore(within: &Path, ids: &[String]) -> Result<Vec<String>, String> {
+        let items: Vec<trash::TrashItem> = os_limited::list()
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .filter(|item| item.original_parent.starts_with(within))
+            .filter(|item| ids.contains(&item.id.to_string_lossy().to_string()))
+            .collect();
+        // 只還原原本位於 within 之下的項目，其他識別碼一律拒絕
+        if let Some(id) = ids
+            .iter()
+            .find(|id| !items.iter().any(|i| i.id.to_string_lossy() == id.as_str()))
+        {
+            return Err(crate::tr!("error.trash_item_not_in_project", id = id));
+        }
+        let restored = items.iter().map(|i| to_trashed(i).original_path).collect();
+        os_limited::restore_all(items).map_err(|e| match e {
+            trash::Error::RestoreCollision { path, .. } => {
+                crate::tr!("error.trash_restore_exists", path = path.display())
+            }
+            e => e.to_string(),
+        })?;
+        Ok(restored)
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    all(
+        unix,
+        not(target_os = "macos"),
+        not(target_os = "ios"),
+        not(target_os = "android")
+    )
+)))]
+mod platform {
+    use super::TrashedFile;
+    use std::path::Path;
+
+    pub fn list(_within: &Path) -> Result<Vec<TrashedFile>, String> {
+        Err(crate::tr!("error.trash_restore_unsupported"))
+    }
+
+    pub fn restore(_within: &Path, _ids: &[String]) -> Result<Vec<String>, String> {
+        Err(crate::tr!("error.trash_restore_unsupported"))
+    }
+}
+
+/// 回收筒中原本位於 within 之下的項目 (新的在前)
+pub fn list_trash(within: &Path) -> Result<Vec<TrashedFile>, String> {
+    platform::list(within)
+}
+
+/// 依識別碼還原回收筒中原本位於 within 之下的項目，回傳還原後的路徑
+pub fn restore_from_trash(within: &Path, ids: &[String]) -> Result<Vec<String>, String> {
+    platform::restore(within, ids)
+}
@@ -0,0 +1,206 @@
+// src-tauri/src/services/uninstall.rs
+//
+// 解除安裝與資料清除。
+// Windows 執行 NSIS 的 uninstall.exe；macOS 刪除 .app；Linux AppImage 刪除映像檔；
+// 透過套件管理員 (deb / rpm) 安裝的版本只能提示使用者自行移除。
+
+use crate::services::file_manager::ProjectPaths;
+use crate::services::{logging, settings};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 清除項目的分類
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PurgeCategory {
+    /// 設定檔資料夾
+    Config,
+    /// app data (工作佇列、下載的 FFmpeg 等)
+    AppData,
+    /// 日誌
+    Logs,
+    /// 專案根目錄 (需要額外確認)
+    Projects,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgeTarget {
+    pub category: PurgeCategory,
+    pub path: String,
+    pub size_bytes: u64,
+}
+
+/// 清除清單；dry_run 時只列出將被刪除的項目
+#[derive(Debug, Clone, Serialize)]
+pub struct PurgePlan {
+    pub targets: Vec<PurgeTarget>,
+    pub total_bytes: u64,
+    pub dry_run: bool,
+}
+
+impl PurgePlan {
+    pub fn projects_root(&self) -> Option<&str> {
+        self.targets
+            .iter()
+            .find(|t| t.category == PurgeCategory::Projects)
+            .map(|t| t.path.as_str())
+    }
+}
+
+/// 列出要清除的資料夾 (只包含實際存在的項目)
+pub fn plan_purge(app_data_dir: Option<PathBuf>, include_projects: bool) -> PurgePlan {
+    let mut candidates: Vec<(PurgeCategory, PathBuf)> = Vec::new();
+    if let Some(dir) = settings::config_path().parent() {
+        candidates.push((PurgeCategory::Config, dir.to_path_buf()));
+    }
+    if let Some(dir) = app_data_dir {
+        candidates.push((PurgeCategory::AppData, dir));
+    }
+    if let Some(dir) = logging::log_dir() {
+        candidates.push((PurgeCategory::Logs, dir.to_path_buf()));
+    }
+    if include_projects {
+        if let Ok(dir) = ProjectPaths::root_base() {
+            candidates.push((PurgeCategory::Projects, dir));
+        }
+    }
+
+    let mut targets: Vec<PurgeTarget> = Vec::new();
+    let mut included: Vec<PathBuf> = Vec::new();
+    for (category, path) in candidates {
+        // 已包含在其他項目中的資料夾 (例如 app data 內的 logs) 不重複列出
+        if !path.exists() || included.iter().any(|p| path.starts_with(p)) {
+            continue;
+        }
+        targets.push(PurgeTarget {
+            category,
+            path: path.to_string_lossy().to_string(),
+            size_bytes: dir_size(&path),
+        });
+        included.push(path);
+    }
+
+    PurgePlan {
+        total_bytes: targets.iter().map(|t| t.size_bytes).sum(),
+        targets,
+        dry_run: true,
+    }
+}
+
+/// 依清單刪除；個別項目失敗時繼續刪除其他項目，最後回報失敗的路徑
+pub fn purge(mut plan: PurgePlan) -> Result<PurgePlan, String> {
+    let mut failures = Vec::new();
+    for target in &plan.targets {
+        let path = Path::new(&target.path);
+        let result = if path.is_dir() {
+            fs::remove_dir_all(path)
+        } else {
+            fs::remove_file(path)
+        };
+        match result {
+            Ok(()) => tracing::info!("已刪除 {}", target.path),
+            Err(e) => failures.push(format!("{}: {}", target.path, e)),
+        }
+    }
+
+    if failures.is_empty() {
+        plan.dry_run = false;
+        Ok(plan)
+    } else {
+        Err(failures.join("\n"))
+    }
+}
+
+fn dir_size(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| dir_size(&e.path()))
+                .sum()
+        })
+        .unwrap_or(0)
+}
+
+/// 目前安裝方式對應的解除安裝方法
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UninstallMethod {
+    /// Windows NSIS 解除安裝程式
+    Uninstaller(PathBuf),
+    /// macOS .app 或 Linux AppImage，結束程式後直接刪除
+    RemoveBundle(PathBuf),
+    /// deb / rpm 安裝，需由使用者執行套件管理員指令
+    PackageManager(String),
+}
+
+/// 偵測解除安裝方法；開發模式等無法判斷的情況回傳 None
+pub fn detect_method() -> Option<UninstallMethod> {
+    let exe = std::env::current_exe().ok()?;
+
+    if cfg!(target_os = "windows") {
+        let uninstaller = exe.parent()?.join("uninstall.exe");
+        return uninstaller
+            .exists()
+            .then_some(UninstallMethod::Uninstaller(uninstaller));
+    }
+
+    if cfg!(target_os = "macos") {
+        // .../STT Agent.app/Contents/MacOS/stt_agent_rust
+        return exe
+            .ancestors()
+            .find(|p| p.extension().is_some_and(|ext| ext == "app"))
+            .map(|p| UninstallMethod::RemoveBundle(p.to_path_buf()));
+    }
+
+    if let Some(appimage) = std::env::var_os("APPIMAGE") {
+        return Some(UninstallMethod::RemoveBundle(PathBuf::from(appimage)));
+    }
+    if exe.starts_with("/usr") {
+        let command = if Path::new("/var/lib/dpkg").exists() {
+            "sudo apt remove stt-agent"
+        } else {
+            "sudo dnf remove stt-agent"
+        };
+        return Some(UninstallMethod::PackageManager(command.to_string()));
+    }
+    None
+}
+
+/// 啟動解除安裝；呼叫端接著應結束程式
+pub fn start(method: &UninstallMethod) -> Result<(), String> {
+    match method {
+        UninstallMethod::Uninstaller(path) => std::process::Command::new(path)
+            .spawn()
+            .map(|_| ())
+            .map_err(|e| format!("無法啟動解除安裝程式: {}", e)),
+        UninstallMethod::RemoveBundle(path) => remove_after_exit(path),
+        UninstallMethod::PackageManager(command) => Err(command.clone()),
+    }
+}
+
+/// 等目前程式結束後再刪除 (執行中的 bundle 不能先刪)
+#[cfg(unix)]
+fn remove_after_exit(path: &Path) -> Result<(), String> {
+    let script = "while kill -0 \"$1\" 2>/dev/null; do sleep 0.5; done; rm -rf \"$2\"";
+    std::process::Command::new("/bin/sh")
+        .arg("-c")
+        .arg(script)
+        .arg("sh")
+        .arg(std::process::id().to_string())
+        .arg(path)
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("無法啟動移除程序: {}", e))
+}
+
+#[cfg(not(unix))]
+fn remove_after_exit(_path: &Path) -> Result<(), String> {
+    Err("此平台不支援直接移除應用程式".to_string())
+}
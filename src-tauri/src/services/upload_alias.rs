@@ -0,0 +1,96 @@
+// src-tauri/src/services/upload_alias.rs
+//
+// 匿名上傳：送到 Gemini 與 STT 伺服器時以隨機名稱取代檔名，
+// 檔名中的病人姓名不會離開本機 (檔案內容仍會上傳)。
+//
+// - 設定 anonymize_uploads 開啟時生效
+// - 對照表存在設定目錄的 upload_aliases.json，可依遠端名稱查回原檔
+// - 保留副檔名 (伺服器依副檔名判斷格式)
+
+use crate::services::file_manager::write_atomic;
+use crate::services::settings;
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+pub const ALIASES_FILE_NAME: &str = "upload_aliases.json";
+
+/// 對照表保留的筆數 (超過時刪除最舊的)
+const MAX_ALIASES: usize = 5000;
+
+/// 同時讀寫對照表的保護
+static ALIASES_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UploadAlias {
+    /// 上傳時使用的名稱
+    pub alias: String,
+    /// 本機的完整路徑
+    pub path: String,
+    pub created_at: String,
+}
+
+fn aliases_path() -> PathBuf {
+    settings::config_path().with_file_name(ALIASES_FILE_NAME)
+}
+
+/// 讀取對照表 (舊到新)
+pub fn list() -> Vec<UploadAlias> {
+    fs::read_to_string(aliases_path())
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .unwrap_or_default()
+}
+
+/// 依上傳名稱查回本機檔案
+pub fn lookup(alias: &str) -> Option<UploadAlias> {
+    list().into_iter().rev().find(|a| a.alias == alias)
+}
+
+fn random_alias(path: &Path) -> String {
+    let mut bytes = [0u8; 8];
+    OsRng.fill_bytes(&mut bytes);
+    let id: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    match path.extension() {
+        Some(ext) => format!("upload_{}.{}", id, ext.to_string_lossy().to_lowercase()),
+        None => format!("upload_{}", id),
+    }
+}
+
+fn record(alias: &UploadAlias) -> Result<(), String> {
+    let _guard = ALIASES_LOCK.lock().map_err(|e| e.to_string())?;
+    let mut aliases = list();
+    aliases.push(alias.clone());
+    if aliases.len() > MAX_ALIASES {
+        aliases.drain(..aliases.len() - MAX_ALIASES);
+    }
+    let json = serde_json::to_vec_pretty(&aliases).map_err(|e| e.to_string())?;
+    let path = aliases_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    write_atomic(&path, &json).map_err(|e| e.to_string())
+}
+
+/// 上傳時使用的檔名：未開啟匿名上傳時為原檔名，否則產生隨機名稱並記錄對照
+/// 無法記錄對照時仍使用隨機名稱 (不因此送出原檔名)
+pub fn upload_name(path: &Path) -> String {
+    if !settings::load().anonymize_uploads {
+        return path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "audio.mp3".to_string());
+    }
+    let alias = UploadAlias {
+        alias: random_alias(path),
+        path: path.to_string_lossy().to_string(),
+        created_at: chrono::Local::now().to_rfc3339(),
+    };
+    if let Err(e) = record(&alias) {
+        tracing::warn!("無法記錄上傳名稱對照: {}", e);
+    }
+    alias.alias
+}
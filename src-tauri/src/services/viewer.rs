@@ -0,0 +1,74 @@
+// src-tauri/src/services/viewer.rs
+//
+// 檢視模式 (唯讀)：主治醫師在共用磁碟上審閱住院醫師的專案時使用，避免不小心改動專案。
+//
+// - 每次開啟專案時選擇是否以檢視模式開啟，記錄在該視窗上
+// - 檢視模式的專案內，所有會修改檔案的命令與背景工作都由後端拒絕
+// - 播放、逐字稿檢視與報告閱讀不受影響
+// - 同一專案同時在其他視窗以一般模式開啟時，仍以唯讀為準
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::access;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// 以檢視模式開啟的視窗與專案根目錄
+static VIEWERS: Mutex<Vec<(String, PathBuf)>> = Mutex::new(Vec::new());
+
+/// 設定視窗的開啟模式 (一般模式時移除記錄)
+pub fn set_viewer(label: &str, root: &Path, read_only: bool) {
+    if let Ok(mut viewers) = VIEWERS.lock() {
+        viewers.retain(|(l, _)| l != label);
+        if read_only {
+            let root = access::normalize(root).unwrap_or_else(|| root.to_path_buf());
+            viewers.push((label.to_string(), root));
+        }
+    }
+}
+
+/// 視窗關閉時清除記錄
+pub fn clear(label: &str) {
+    if let Ok(mut viewers) = VIEWERS.lock() {
+        viewers.retain(|(l, _)| l != label);
+    }
+}
+
+/// 視窗是否以檢視模式開啟專案
+pub fn is_viewer(label: &str) -> bool {
+    VIEWERS
+        .lock()
+        .map(|viewers| viewers.iter().any(|(l, _)| l == label))
+        .unwrap_or(false)
+}
+
+/// 路徑所在的唯讀專案根目錄
+fn read_only_root(path: &Path) -> Option<PathBuf> {
+    let path = access::normalize(path).unwrap_or_else(|| path.to_path_buf());
+    VIEWERS
+        .lock()
+        .ok()?
+        .iter()
+        .find_map(|(_, root)| path.starts_with(root).then(|| root.clone()))
+}
+
+/// 路徑是否位於以檢視模式開啟的專案內
+pub fn is_read_only(path: &Path) -> bool {
+    read_only_root(path).is_some()
+}
+
+/// 路徑位於唯讀專案內時回傳錯誤
+pub fn ensure_writable(path: &Path) -> Result<(), AppError> {
+    match read_only_root(path) {
+        Some(root) => Err(AppError::localized(
+            ErrorKind::PermissionDenied,
+            "error.read_only_project",
+            &[("path", root.display().to_string())],
+        )),
+        None => Ok(()),
+    }
+}
+
+/// 多個路徑中任一個位於唯讀專案內時回傳錯誤
+pub fn ensure_all_writable<P: AsRef<Path>>(paths: &[P]) -> Result<(), AppError> {
+    paths.iter().try_for_each(|p| ensure_writable(p.as_ref()))
+}
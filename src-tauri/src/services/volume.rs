@@ -0,0 +1,215 @@
+// src-tauri/src/services/volume.rs
+//
+// 處理前檢查來源與輸出位置：
+// - 網路磁碟無法連線時，讀取檔案資訊可能卡住很久，因此以逾時判斷
+// - 唯讀媒體 (鎖定的記憶卡、光碟、唯讀分享) 以實際建立暫存檔判斷
+// 有問題時回傳明確的錯誤，前端可提示使用者先把輸入檔複製到本機工作資料夾。
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::storage;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// 等待網路磁碟回應的時間
+const REACHABLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 本機工作資料夾名稱 (app data 底下)
+pub const WORKDIR_NAME: &str = "workdir";
+
+/// Linux 上視為網路磁碟的檔案系統
+#[cfg(target_os = "linux")]
+const NETWORK_FS_TYPES: &[&str] = &[
+    "nfs",
+    "nfs4",
+    "cifs",
+    "smb3",
+    "smbfs",
+    "fuse.sshfs",
+    "afs",
+    "9p",
+    "davfs",
+    "fuse.rclone",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LocationStatus {
+    pub path: String,
+    /// 位置可在時限內回應 (本機磁碟一律為 true，除非不存在)
+    pub reachable: bool,
+    /// 可在此位置 (或最近的已存在上層目錄) 建立檔案
+    pub writable: bool,
+    /// 位於網路分享資料夾
+    pub network: bool,
+}
+
+/// 檢查位置狀態；不存在的路徑以最近的已存在上層目錄判斷可寫入
+pub fn check(path: &Path) -> LocationStatus {
+    let network = is_network_path(path);
+    let reachable = is_reachable(path);
+    let writable = reachable && is_writable(path);
+    LocationStatus {
+        path: path.to_string_lossy().to_string(),
+        reachable,
+        writable,
+        network,
+    }
+}
+
+/// 確認輸入檔可讀取 (網路磁碟無回應時回傳錯誤)
+pub fn ensure_reachable(path: &Path) -> Result<(), AppError> {
+    if is_reachable(path) {
+        return Ok(());
+    }
+    let key = if is_network_path(path) {
+        "error.network_share_unreachable"
+    } else {
+        "error.location_unreachable"
+    };
+    Err(AppError::localized(
+        ErrorKind::Network,
+        key,
+        &[("path", path.display().to_string())],
+    ))
+}
+
+/// 確認輸出位置可寫入
+pub fn ensure_writable(dir: &Path) -> Result<(), AppError> {
+    ensure_reachable(dir)?;
+    if is_writable(dir) {
+        Ok(())
+    } else {
+        Err(AppError::localized(
+            ErrorKind::PermissionDenied,
+            "error.location_read_only",
+            &[("path", dir.display().to_string())],
+        ))
+    }
+}
+
+/// 把輸入檔複製到本機工作資料夾 (workdir/<時間>/)，回傳新路徑
+pub fn copy_to_workdir(workdir_root: &Path, files: &[String]) -> Result<Vec<String>, AppError> {
+    let target = workdir_root.join(chrono::Local::now().format("%Y%m%d-%H%M%S").to_string());
+    let total: u64 = files.iter().map(|f| storage::file_size(f)).sum();
+    storage::ensure_space(workdir_root, total)?;
+    fs::create_dir_all(&target)?;
+
+    let mut copied = Vec::new();
+    for file in files {
+        let source = Path::new(file);
+        ensure_reachable(source)?;
+        let name = source
+            .file_name()
+            .ok_or_else(|| AppError::invalid_input(format!("無效的檔案路徑: {}", file)))?;
+        let dest = unique_path(&target.join(name));
+        fs::copy(source, &dest)
+            .map_err(|e| AppError::from(e).with_detail(format!("Failed to copy: {}", file)))?;
+        copied.push(dest.to_string_lossy().to_string());
+    }
+    Ok(copied)
+}
+
+/// 同名檔案已存在時加上編號
+fn unique_path(path: &Path) -> PathBuf {
+    if !path.exists() {
+        return path.to_path_buf();
+    }
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let ext = path
+        .extension()
+        .map(|e| format!(".{}", e.to_string_lossy()))
+        .unwrap_or_default();
+    (1..)
+        .map(|n| path.with_file_name(format!("{}_{}{}", stem, n, ext)))
+        .find(|p| !p.exists())
+        .unwrap_or_else(|| path.to_path_buf())
+}
+
+/// 在背景執行緒讀取檔案資訊，逾時視為無法連線 (卡住的執行緒會在系統回應後自行結束)
+/// 尚未建立的輸出資料夾以最近的已存在上層目錄判斷
+fn is_reachable(path: &Path) -> bool {
+    let path = path.to_path_buf();
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(path.ancestors().any(|p| p.exists()));
+    });
+    rx.recv_timeout(REACHABLE_TIMEOUT).unwrap_or(false)
+}
+
+/// 在最近的已存在目錄建立暫存檔，確認可寫入
+fn is_writable(path: &Path) -> bool {
+    let Some(dir) = path.ancestors().find(|p| p.is_dir()) else {
+        return false;
+    };
+    let probe = dir.join(format!(".stt-agent-write-test-{}", std::process::id()));
+    match fs::File::create(&probe) {
+        Ok(_) => {
+            let _ = fs::remove_file(&probe);
+            true
+        }
+        Err(e) => {
+            tracing::debug!("無法寫入 {}: {}", dir.display(), e);
+            false
+        }
+    }
+}
+
+/// 判斷路徑是否位於網路分享資料夾
+pub fn is_network_path(path: &Path) -> bool {
+    let text = path.to_string_lossy();
+    // Windows UNC 路徑: \\server\share 或 \\?\UNC\server\share
+    if (text.starts_with(r"\\") && !text.starts_with(r"\\?\")) || text.starts_with(r"\\?\UNC\") {
+        return true;
+    }
+    network_mount_points()
+        .iter()
+        .any(|mount| path.starts_with(mount))
+}
+
+#[cfg(target_os = "linux")]
+fn network_mount_points() -> Vec<PathBuf> {
+    fs::read_to_string("/proc/mounts")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let _device = fields.next()?;
+            let mount_point = fields.next()?;
+            let fs_type = fields.next()?;
+            NETWORK_FS_TYPES
+                .contains(&fs_type)
+                // /proc/mounts 以 \040 表示空白
+                .then(|| PathBuf::from(mount_point.replace("\\040", " ")))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+fn network_mount_points() -> Vec<PathBuf> {
+    // 格式: //user@server/share on /Volumes/share (smbfs, nodev, nosuid, mounted by user)
+    let Ok(output) = std::process::Command::new("mount").output() else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (_, rest) = line.split_once(" on ")?;
+            let (mount_point, options) = rest.rsplit_once(" (")?;
+            let fs_type = options.split(',').next()?.trim();
+            ["smbfs", "nfs", "afpfs", "webdav"]
+                .contains(&fs_type)
+                .then(|| PathBuf::from(mount_point))
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn network_mount_points() -> Vec<PathBuf> {
+    // Windows 的對應網路磁碟機 (Z:) 無法只靠路徑判斷，以連線逾時處理
+    Vec::new()
+}
@@ -0,0 +1,107 @@
+// src-tauri/src/services/watcher.rs
+//
+// 監看目前開啟專案的資料夾，檔案新增/刪除/修改時發出 `project://changed` 事件，
+// 讓前端在 FFmpeg 或外部工具寫入檔案後自動重新整理。
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter};
+
+pub const PROJECT_CHANGED_EVENT: &str = "project://changed";
+
+/// 傳給前端的檔案變動事件
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectChangeEvent {
+    /// 專案根目錄
+    pub root: String,
+    /// 所在階段資料夾 (01_converted, 02_split ...)，位於根目錄時為 None
+    pub stage: Option<String>,
+    /// 變動的檔案路徑
+    pub path: String,
+    /// "added" | "removed" | "modified"
+    pub kind: String,
+}
+
+/// 每個視窗各自監看自己開啟的專案 (label → 監看器)
+pub struct ProjectWatcher {
+    watchers: HashMap<String, (PathBuf, RecommendedWatcher)>,
+}
+
+pub type ProjectWatcherState = std::sync::Mutex<ProjectWatcher>;
+
+impl Default for ProjectWatcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProjectWatcher {
+    pub fn new() -> Self {
+        Self {
+            watchers: HashMap::new(),
+        }
+    }
+
+    /// 為指定視窗開始監看專案 (會先停止該視窗前一個專案的監看)
+    /// 事件只送往該視窗
+    pub fn watch(&mut self, app: &AppHandle, label: &str, root: &Path) -> Result<(), String> {
+        if let Some((current, _)) = self.watchers.get(label) {
+            if current == root {
+                return Ok(());
+            }
+        }
+        self.stop(label);
+
+        let app_handle = app.clone();
+        let target = label.to_string();
+        let watch_root = root.to_path_buf();
+        let mut watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(event) => {
+                    let kind = match event.kind {
+                        EventKind::Create(_) => "added",
+                        EventKind::Remove(_) => "removed",
+                        EventKind::Modify(_) => "modified",
+                        _ => return,
+                    };
+                    for path in &event.paths {
+                        let payload = ProjectChangeEvent {
+                            root: watch_root.to_string_lossy().to_string(),
+                            stage: stage_of(&watch_root, path),
+                            path: path.to_string_lossy().to_string(),
+                            kind: kind.to_string(),
+                        };
+                        let _ = app_handle.emit_to(target.as_str(), PROJECT_CHANGED_EVENT, payload);
+                    }
+                }
+                Err(e) => tracing::warn!("專案監看錯誤: {}", e),
+            })
+            .map_err(|e| format!("無法建立檔案監看: {}", e))?;
+
+        watcher
+            .watch(root, RecursiveMode::Recursive)
+            .map_err(|e| format!("無法監看專案資料夾: {}", e))?;
+
+        self.watchers
+            .insert(label.to_string(), (root.to_path_buf(), watcher));
+        Ok(())
+    }
+
+    /// 停止指定視窗的監看
+    pub fn stop(&mut self, label: &str) {
+        // Dropping the watcher unregisters all watches
+        self.watchers.remove(label);
+    }
+}
+
+/// 取得檔案位於哪個階段資料夾 (根目錄下第一層)
+fn stage_of(root: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(root).ok()?;
+    let mut components = relative.components();
+    let first = components.next()?;
+    // 直接位於根目錄的檔案不屬於任何階段
+    components.next()?;
+    Some(first.as_os_str().to_string_lossy().to_string())
+}
@@ -0,0 +1,209 @@
+// src-tauri/src/services/waveform.rs
+//
+// 波形峰值：播放器進度列後方顯示的波形。長錄音每次解碼要好幾秒，
+// 算好的峰值存成音檔旁的 <檔名>.peaks，重新開啟專案時直接讀取。
+//
+// - 快取記錄來源的 SHA-256，來源內容改變時自動重算
+// - 另記錄 (修改時間, 大小)，兩者不變時不重新計算雜湊
+// - 檢視模式 (唯讀) 的專案只計算不寫入快取
+
+use crate::services::file_manager::write_atomic;
+use crate::services::{manifest, probe, viewer};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+/// 快取副檔名 (接在原檔名後)
+pub const PEAKS_EXTENSION: &str = "peaks";
+
+/// 每秒的峰值數
+const PEAKS_PER_SECOND: u32 = 50;
+
+/// 快取格式識別與版本
+const MAGIC: &[u8; 8] = b"STTPEAK1";
+
+/// 標頭長度：識別 8 + SHA-256 (hex) 64 + 修改時間 8 + 大小 8 + 每秒峰值數 4 + 峰值數 4
+const HEADER_LEN: usize = 8 + 64 + 8 + 8 + 4 + 4;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Waveform {
+    pub peaks_per_second: u32,
+    /// 每個區間的 (最小值, 最大值)，範圍 -1.0 ~ 1.0
+    pub peaks: Vec<(f32, f32)>,
+    /// 是否由快取讀取
+    pub cached: bool,
+}
+
+impl Waveform {
+    /// 合併為 buckets 個區間 (前端依畫面寬度取用，避免傳送整份峰值)
+    pub fn reduce(mut self, buckets: usize) -> Self {
+        if buckets == 0 || self.peaks.len() <= buckets {
+            return self;
+        }
+        let len = self.peaks.len();
+        self.peaks = (0..buckets)
+            .map(|i| {
+                self.peaks[i * len / buckets..(i + 1) * len / buckets]
+                    .iter()
+                    .fold((0.0f32, 0.0f32), |(lo, hi), &(min, max)| {
+                        (lo.min(min), hi.max(max))
+                    })
+            })
+            .collect();
+        self
+    }
+}
+
+/// 音檔對應的快取路徑
+pub fn peaks_path(audio: &Path) -> PathBuf {
+    let mut name = audio.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(PEAKS_EXTENSION);
+    audio.with_file_name(name)
+}
+
+/// 解碼音檔計算峰值
+pub fn compute(path: &Path) -> Result<Vec<(f32, f32)>, String> {
+    let mut peaks = Vec::new();
+    let (mut lo, mut hi) = (0.0f32, 0.0f32);
+    let mut frames = 0u32;
+    probe::decode_samples(&path.to_string_lossy(), |block, info| {
+        let channels = info.channels.max(1) as usize;
+        let per_peak = (info.sample_rate / PEAKS_PER_SECOND).max(1);
+        for frame in block.chunks(channels) {
+            for &sample in frame {
+                lo = lo.min(sample);
+                hi = hi.max(sample);
+            }
+            frames += 1;
+            if frames >= per_peak {
+                peaks.push((lo.max(-1.0), hi.min(1.0)));
+                (lo, hi, frames) = (0.0, 0.0, 0);
+            }
+        }
+    })?;
+    if frames > 0 {
+        peaks.push((lo.max(-1.0), hi.min(1.0)));
+    }
+    Ok(peaks)
+}
+
+fn file_stamp(path: &Path) -> Option<(u64, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((modified, metadata.len()))
+}
+
+struct PeaksFile {
+    hash: String,
+    modified: u64,
+    size: u64,
+    peaks: Vec<(f32, f32)>,
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    u64::from_le_bytes(bytes.try_into().unwrap_or_default())
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes.try_into().unwrap_or_default())
+}
+
+fn load(path: &Path) -> Option<PeaksFile> {
+    let bytes = fs::read(path).ok()?;
+    if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+        return None;
+    }
+    if read_u32(&bytes[88..92]) != PEAKS_PER_SECOND {
+        return None;
+    }
+    let count = read_u32(&bytes[92..96]) as usize;
+    let body = &bytes[HEADER_LEN..];
+    if body.len() != count * 4 {
+        return None;
+    }
+    let scale = |b: &[u8]| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32;
+    Some(PeaksFile {
+        hash: String::from_utf8(bytes[8..72].to_vec()).ok()?,
+        modified: read_u64(&bytes[72..80]),
+        size: read_u64(&bytes[80..88]),
+        peaks: body
+            .chunks_exact(4)
+            .map(|c| (scale(&c[..2]), scale(&c[2..])))
+            .collect(),
+    })
+}
+
+fn store(path: &Path, file: &PeaksFile) -> std::io::Result<()> {
+    let mut bytes = Vec::with_capacity(HEADER_LEN + file.peaks.len() * 4);
+    bytes.extend_from_slice(MAGIC);
+    bytes.extend_from_slice(file.hash.as_bytes());
+    bytes.extend_from_slice(&file.modified.to_le_bytes());
+    bytes.extend_from_slice(&file.size.to_le_bytes());
+    bytes.extend_from_slice(&PEAKS_PER_SECOND.to_le_bytes());
+    bytes.extend_from_slice(&(file.peaks.len() as u32).to_le_bytes());
+    let quantize = |v: f32| ((v.clamp(-1.0, 1.0) * i16::MAX as f32).round() as i16).to_le_bytes();
+    for &(min, max) in &file.peaks {
+        bytes.extend_from_slice(&quantize(min));
+        bytes.extend_from_slice(&quantize(max));
+    }
+    write_atomic(path, &bytes)
+}
+
+/// 讀取音檔的波形 (快取有效時直接使用，否則重新計算並更新快取)
+pub fn load_or_compute(audio: &Path) -> Result<Waveform, String> {
+    let (modified, size) = file_stamp(audio).ok_or("無法讀取音檔資訊")?;
+    let cache_path = peaks_path(audio);
+    let cached = load(&cache_path);
+    let writable = !viewer::is_read_only(audio);
+
+    if let Some(file) = &cached {
+        if file.modified == modified && file.size == size {
+            return Ok(Waveform {
+                peaks_per_second: PEAKS_PER_SECOND,
+                peaks: file.peaks.clone(),
+                cached: true,
+            });
+        }
+    }
+
+    // 修改時間或大小不同時以內容雜湊確認 (複製或還原備份會改變修改時間)
+    let hash = manifest::hash_file(audio)?;
+    if let Some(mut file) = cached.filter(|f| f.hash == hash) {
+        file.modified = modified;
+        file.size = size;
+        if writable {
+            if let Err(e) = store(&cache_path, &file) {
+                tracing::warn!("無法更新波形快取 {}: {}", cache_path.display(), e);
+            }
+        }
+        return Ok(Waveform {
+            peaks_per_second: PEAKS_PER_SECOND,
+            peaks: file.peaks,
+            cached: true,
+        });
+    }
+
+    let file = PeaksFile {
+        hash,
+        modified,
+        size,
+        peaks: compute(audio)?,
+    };
+    if writable {
+        if let Err(e) = store(&cache_path, &file) {
+            tracing::warn!("無法寫入波形快取 {}: {}", cache_path.display(), e);
+        }
+    }
+    Ok(Waveform {
+        peaks_per_second: PEAKS_PER_SECOND,
+        peaks: file.peaks,
+        cached: false,
+    })
+}
@@ -0,0 +1,146 @@
+// src-tauri/src/services/webhook.rs
+//
+// 完成通知 webhook：報告或一鍵流程結束 (成功或失敗) 時，POST 一份 JSON 到設定的網址，
+// 讓診所系統不必輪詢即可取得完成的報告。
+//
+// - 事件名稱為 "<kind>.completed" / "<kind>.failed" (kind 為 report 或 pipeline)
+// - 設定了密鑰時，以 HMAC-SHA256 對 body 簽章，放在 X-STT-Agent-Signature: sha256=<hex>
+// - 送出失敗會重試數次，最終仍失敗只寫入記錄，不影響工作結果
+
+use crate::models::AppError;
+use crate::services::history;
+use crate::services::jobs::{Job, JobSpec};
+use crate::services::settings::{self, WebhookConfig};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const SIGNATURE_HEADER: &str = "X-STT-Agent-Signature";
+pub const EVENT_HEADER: &str = "X-STT-Agent-Event";
+
+/// 單次請求逾時
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+/// 最多嘗試次數 (間隔 2、4 秒)
+const MAX_ATTEMPTS: u32 = 3;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub event: String,
+    pub job_id: String,
+    pub kind: String,
+    pub success: bool,
+    /// 專案資料夾
+    pub projects: Vec<String>,
+    /// 工作產生的檔案 (報告的 .md / .docx 等)
+    pub outputs: Vec<String>,
+    pub result: Option<Value>,
+    pub error: Option<String>,
+    pub timestamp: String,
+}
+
+/// 需要發送通知的工作種類
+fn is_notified(spec: &JobSpec) -> bool {
+    matches!(spec, JobSpec::Report { .. } | JobSpec::Pipeline { .. })
+}
+
+/// 工作結束時呼叫：符合條件時在背景發送通知 (取消的工作不通知)
+pub fn notify_job(job: &Job, outputs: &[PathBuf], result: &Result<Value, AppError>) {
+    if !is_notified(&job.spec) || matches!(result, Err(e) if e.is_cancelled()) {
+        return;
+    }
+    let config = settings::load().webhook;
+    if !config.enabled || config.url.is_none() {
+        return;
+    }
+    let success = result.is_ok();
+    let payload = WebhookPayload {
+        event: format!(
+            "{}.{}",
+            job.kind,
+            if success { "completed" } else { "failed" }
+        ),
+        job_id: job.id.clone(),
+        kind: job.kind.clone(),
+        success,
+        projects: history::job_projects(&job.spec)
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        outputs: outputs
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        result: result.as_ref().ok().cloned(),
+        error: result.as_ref().err().map(|e| e.message.clone()),
+        timestamp: chrono::Local::now().to_rfc3339(),
+    };
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = deliver(&config, &payload).await {
+            tracing::warn!("Webhook 通知失敗 ({}): {}", payload.event, e);
+        }
+    });
+}
+
+/// 發送測試事件，確認網址與密鑰設定正確
+pub async fn send_test(config: &WebhookConfig) -> Result<(), String> {
+    let payload = WebhookPayload {
+        event: "test".to_string(),
+        job_id: String::new(),
+        kind: "test".to_string(),
+        success: true,
+        projects: Vec::new(),
+        outputs: Vec::new(),
+        result: None,
+        error: None,
+        timestamp: chrono::Local::now().to_rfc3339(),
+    };
+    deliver(config, &payload).await
+}
+
+/// POST 通知，失敗時重試
+async fn deliver(config: &WebhookConfig, payload: &WebhookPayload) -> Result<(), String> {
+    let url = config.url.as_deref().ok_or("未設定 webhook 網址")?;
+    let body = serde_json::to_vec(payload).map_err(|e| e.to_string())?;
+    let client = reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|e| e.to_string())?;
+
+    let mut last_error = String::new();
+    for attempt in 1..=MAX_ATTEMPTS {
+        let mut request = client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(EVENT_HEADER, &payload.event)
+            .body(body.clone());
+        if let Some(secret) = &config.secret {
+            request = request.header(SIGNATURE_HEADER, format!("sha256={}", sign(secret, &body)));
+        }
+        match request.send().await.and_then(|r| r.error_for_status()) {
+            Ok(_) => {
+                tracing::info!("Webhook 已送出: {} → {}", payload.event, url);
+                return Ok(());
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+        if attempt < MAX_ATTEMPTS {
+            tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+        }
+    }
+    Err(last_error)
+}
+
+/// HMAC-SHA256 簽章 (小寫 hex)
+fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC 接受任意長度的密鑰");
+    mac.update(body);
+    mac.finalize()
+        .into_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
@@ -0,0 +1,1746 @@
+// src-tauri/src/services/workflows.rs
+//
+// 各種背景工作的實際處理流程，由 JobManager 的 worker 呼叫。
+// 命令層只負責驗證參數並排入工作，流程細節集中在這裡。
+
+use crate::models::{AppError, ErrorKind};
+use crate::services::annotations;
+use crate::services::backup;
+use crate::services::deid;
+use crate::services::denoise::{self, Denoiser};
+use crate::services::dictaphone;
+use crate::services::downmix::{self, Downmix};
+use crate::services::encryption;
+use crate::services::enhance::SpeechEnhancer;
+use crate::services::experiments::{self, PromptVariant};
+use crate::services::file_manager::{
+    promote_files, renamed_note, write_atomic, ConflictPolicy, ProjectPaths, TransferMode,
+    TRANSCRIPT_DIR,
+};
+use crate::services::history;
+use crate::services::ingest;
+use crate::services::jobs::{CancelToken, JobContext, JobSpec, TranscribeOnSplit};
+use crate::services::manifest::ProjectManifest;
+use crate::services::pipeline::{self, PipelineOptions, PipelineStage, PipelineState};
+use crate::services::plugins;
+use crate::services::probe;
+use crate::services::project_stats;
+use crate::services::report::{self, ReportAgent};
+use crate::services::settings::{self, PluginHook};
+use crate::services::sidecar::Ffmpeg;
+use crate::services::splitter::SplitOutput;
+use crate::services::storage;
+use crate::services::transcript;
+use crate::services::trash;
+use crate::services::viewer;
+use crate::services::volume;
+use crate::services::{Converter, Silence, Splitter};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tauri::{Emitter, Manager};
+
+/// 依工作內容執行對應流程
+pub async fn execute(ctx: &JobContext, spec: &JobSpec) -> Result<Value, AppError> {
+    // 檢視模式 (唯讀) 開啟的專案不執行任何會產生或修改檔案的工作
+    viewer::ensure_all_writable(&history::job_projects(spec))?;
+    preflight(spec)?;
+    match spec {
+        JobSpec::Convert {
+            file_paths,
+            project_root,
+        } => convert_files(ctx, file_paths, project_root.as_deref())
+            .await
+            .map(|(message, _)| Value::String(message)),
+        JobSpec::Split {
+            audio_path,
+            project_root,
+            segments,
+            transcribe,
+        } => split_segments(
+            ctx,
+            audio_path,
+            project_root.as_deref(),
+            segments,
+            transcribe.as_ref(),
+        )
+        .await
+        .map(Value::String),
+        JobSpec::Silence {
+            audio_path,
+            project_root,
+            segments,
+        } => apply_project_silence(ctx, audio_path, project_root.as_deref(), segments)
+            .await
+            .map(Value::String),
+        JobSpec::SilenceToDir {
+            input_path,
+            output_dir,
+            segments,
+        } => {
+            let output_path = Silence::new()
+                .with_cancel(ctx.cancel.clone())
+                .with_collision(settings::load().output_collision)
+                .apply_silence_to_segments(
+                    &Ffmpeg::from(&ctx.app),
+                    input_path,
+                    output_dir,
+                    segments.clone(),
+                )
+                .await
+                .map_err(AppError::tool)?;
+            ctx.record_output(&output_path);
+            Ok(Value::String(output_path))
+        }
+        JobSpec::Extract {
+            input_path,
+            output_path,
+            segments,
+        } => {
+            storage::ensure_space(
+                Path::new(output_path).parent().unwrap_or(Path::new(".")),
+                storage::file_size(input_path),
+            )?;
+            let output_path = Silence::new()
+                .with_cancel(ctx.cancel.clone())
+                .with_collision(settings::load().output_collision)
+                .extract_segments(&Ffmpeg::from(&ctx.app), input_path, output_path, segments)
+                .await
+                .map_err(AppError::tool)?;
+            ctx.record_output(&output_path);
+            Ok(Value::String(output_path))
+        }
+        JobSpec::ExportRange {
+            input_path,
+            output_path,
+            start,
+            end,
+        } => {
+            storage::ensure_space(
+                Path::new(output_path).parent().unwrap_or(Path::new(".")),
+                storage::estimate_segment_size(input_path, end - start),
+            )?;
+            let output_path = Splitter::new()
+                .with_cancel(ctx.cancel.clone())
+                .with_collision(settings::load().output_collision)
+                .split_range(
+                    &Ffmpeg::from(&ctx.app),
+                    input_path,
+                    output_path,
+                    *start,
+                    *end,
+                )
+                .await
+                .map_err(AppError::tool)?;
+            ctx.record_output(&output_path);
+            Ok(Value::String(output_path))
+        }
+        JobSpec::Denoise {
+            audio_path,
+            project_root,
+        } => denoise_file(ctx, audio_path, project_root.as_deref())
+            .await
+            .map(Value::String),
+        JobSpec::Enhance {
+            audio_path,
+            project_root,
+        } => enhance_speech(ctx, audio_path, project_root.as_deref())
+            .await
+            .map(Value::String),
+        JobSpec::Report {
+            folder_path,
+            model_name,
+            custom_prompt,
+            api_key,
+        } => generate_report(
+            ctx,
+            api_key,
+            folder_path,
+            model_name.clone(),
+            custom_prompt.clone(),
+        )
+        .await
+        .map(Value::String),
+        JobSpec::Transcribe {
+            server,
+            file_path,
+            diarize,
+        } => {
+            let audio = Path::new(file_path);
+            plugins::run_hook(
+                PluginHook::PreUpload,
+                ProjectPaths::find_root(audio).as_deref(),
+                &[audio.to_path_buf()],
+                &ctx.cancel,
+            )
+            .await?;
+            let service = ctx.app.state::<Silence>();
+            let response = tokio::select! {
+                response = service.transcribe(server, file_path, *diarize) => {
+                    response.map_err(AppError::network)?
+                }
+                _ = ctx.cancel.cancelled() => return Err(AppError::cancelled()),
+            };
+            Ok(serde_json::to_value(response)?)
+        }
+        JobSpec::TranscribeFolder {
+            server,
+            project_root,
+            diarize,
+        } => transcribe_folder(ctx, server, Path::new(project_root), *diarize)
+            .await
+            .map(Value::String),
+        JobSpec::Align {
+            server,
+            file_path,
+            transcript_path,
+        } => {
+            let path = Path::new(transcript_path);
+            let current = transcript::load(path)?;
+            let audio = Path::new(file_path);
+            plugins::run_hook(
+                PluginHook::PreUpload,
+                ProjectPaths::find_root(audio).as_deref(),
+                &[audio.to_path_buf()],
+                &ctx.cancel,
+            )
+            .await?;
+            let service = ctx.app.state::<Silence>();
+            let aligned = tokio::select! {
+                aligned = service.align(server, file_path, &current.segments) => {
+                    aligned.map_err(AppError::network)?
+                }
+                _ = ctx.cancel.cancelled() => return Err(AppError::cancelled()),
+            };
+            let document = transcript::apply_alignment(path, aligned)?;
+            ctx.record_output(path);
+            Ok(serde_json::to_value(document)?)
+        }
+        JobSpec::PromptExperiment {
+            audio_path,
+            variants,
+            api_key,
+        } => run_prompt_experiment(ctx, audio_path, variants, api_key).await,
+        JobSpec::Pipeline {
+            file_paths,
+            project_root,
+            options,
+            api_key,
+        } => run_pipeline(ctx, file_paths, Path::new(project_root), options, api_key)
+            .await
+            .map(Value::String),
+    }
+}
+
+/// 開始處理前確認輸入檔可讀取、輸出位置可寫入 (網路磁碟斷線、唯讀媒體)
+fn preflight(spec: &JobSpec) -> Result<(), AppError> {
+    match spec {
+        JobSpec::Convert {
+            file_paths,
+            project_root,
+        } => {
+            for path in file_paths {
+                volume::ensure_reachable(Path::new(path))?;
+            }
+            let mut targets: Vec<PathBuf> = Vec::new();
+            for path in file_paths {
+                if let Ok(paths) = resolve_project(project_root.as_deref(), path) {
+                    if !targets.contains(&paths.root) {
+                        targets.push(paths.root);
+                    }
+                }
+            }
+            targets
+                .iter()
+                .try_for_each(|root| volume::ensure_writable(root))
+        }
+        JobSpec::Split {
+            audio_path,
+            project_root,
+            ..
+        }
+        | JobSpec::Silence {
+            audio_path,
+            project_root,
+            ..
+        }
+        | JobSpec::Denoise {
+            audio_path,
+            project_root,
+        }
+        | JobSpec::Enhance {
+            audio_path,
+            project_root,
+        } => {
+            volume::ensure_reachable(Path::new(audio_path))?;
+            volume::ensure_writable(&resolve_project(project_root.as_deref(), audio_path)?.root)
+        }
+        JobSpec::SilenceToDir {
+            input_path,
+            output_dir,
+            ..
+        } => {
+            volume::ensure_reachable(Path::new(input_path))?;
+            volume::ensure_writable(Path::new(output_dir))
+        }
+        JobSpec::Extract {
+            input_path,
+            output_path,
+            ..
+        }
+        | JobSpec::ExportRange {
+            input_path,
+            output_path,
+            ..
+        } => {
+            volume::ensure_reachable(Path::new(input_path))?;
+            volume::ensure_writable(Path::new(output_path).parent().unwrap_or(Path::new(".")))
+        }
+        JobSpec::Report { folder_path, .. } => {
+            volume::ensure_reachable(Path::new(folder_path))?;
+            volume::ensure_writable(&report_dir(folder_path))
+        }
+        JobSpec::Transcribe { file_path, .. } => volume::ensure_reachable(Path::new(file_path)),
+        JobSpec::PromptExperiment { audio_path, .. } => {
+            volume::ensure_reachable(Path::new(audio_path))
+        }
+        JobSpec::Align {
+            file_path,
+            transcript_path,
+            ..
+        } => {
+            volume::ensure_reachable(Path::new(file_path))?;
+            volume::ensure_writable(
+                Path::new(transcript_path)
+                    .parent()
+                    .unwrap_or(Path::new(".")),
+            )
+        }
+        JobSpec::TranscribeFolder { project_root, .. } => {
+            let root = Path::new(project_root);
+            volume::ensure_reachable(root)?;
+            volume::ensure_writable(root)
+        }
+        JobSpec::Pipeline {
+            file_paths,
+            project_root,
+            ..
+        } => {
+            for path in file_paths {
+                volume::ensure_reachable(Path::new(path))?;
+            }
+            volume::ensure_writable(Path::new(project_root))
+        }
+    }
+}
+
+/// 取得專案路徑：有開啟中的專案則使用，否則依檔案位置推算
+pub(crate) fn resolve_project(
+    project_root: Option<&str>,
+    file_path: &str,
+) -> Result<ProjectPaths, AppError> {
+    match project_root {
+        Some(root) => ProjectPaths::from_root(PathBuf::from(root)),
+        None => ProjectPaths::new(file_path),
+    }
+    .map_err(AppError::io)
+}
+
+/// 轉換多個檔案為 MP3，回傳結果訊息與實際的輸出檔 (同名時可能已自動改名)
+async fn convert_files(
+    ctx: &JobContext,
+    file_paths: &[String],
+    project_root: Option<&str>,
+) -> Result<(String, Vec<String>), AppError> {
+    let config = settings::load();
+    let ffmpeg = Ffmpeg::from(&ctx.app);
+    let mut outputs = Vec::new();
+    let mut success_count = 0;
+    let mut fail_count = 0;
+    let mut messages = Vec::new();
+    let total = file_paths.len();
+
+    // 針對每一個檔案，都必須建立其專屬的 Project Folder
+    for (idx, path) in file_paths.iter().enumerate() {
+        ctx.check_cancelled()?;
+        ctx.progress(
+            idx as f32 / total as f32,
+            crate::tr!("progress.converting", current = idx + 1, total = total),
+        );
+
+        // 1. 初始化專案路徑
+        let project_paths = match resolve_project(project_root, path) {
+            Ok(p) => p,
+            Err(e) => {
+                fail_count += 1;
+                messages.push(crate::tr!(
+                    "result.convert_path_error",
+                    file = path,
+                    detail = e
+                ));
+                continue;
+            }
+        };
+
+        // 2. 建立資料夾
+        if let Err(e) = project_paths.create_all_dirs() {
+            fail_count += 1;
+            messages.push(crate::tr!(
+                "result.convert_mkdir_error",
+                file = path,
+                detail = e
+            ));
+            continue;
+        }
+
+        let output_dir = project_paths.converted.to_string_lossy().to_string();
+
+        // 3. 確認磁碟空間足夠，避免 FFmpeg 寫到一半失敗
+        if let Err(e) =
+            storage::ensure_space(&project_paths.converted, storage::estimate_mp3_size(path))
+        {
+            fail_count += 1;
+            messages.push(format!("✗ {} - {}", path, e));
+            continue;
+        }
+
+        // 4. 錄音筆專用格式 (DSS / DS2)：FFmpeg 無法解碼時先以 decode 外掛轉成暫存 WAV
+        let decoded = match dictaphone::detect(Path::new(path)) {
+            Some(format) => {
+                let prepared = dictaphone::prepare(
+                    &ffmpeg,
+                    format,
+                    Path::new(path),
+                    Some(&project_paths.root),
+                    &ctx.cancel,
+                )
+                .await;
+                match prepared {
+                    Ok(decoded) => decoded,
+                    Err(e) => {
+                        ctx.check_cancelled()?;
+                        fail_count += 1;
+                        messages.push(format!("✗ {} - {}", path, e));
+                        continue;
+                    }
+                }
+            }
+            None => None,
+        };
+        let input = decoded
+            .as_ref()
+            .map(|d| d.path.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.clone());
+
+        // 5. 執行單一轉檔 (啟用智慧轉單聲道時先分析左右聲道，無法分析則保留原聲道)
+        let downmix = if config.smart_downmix {
+            let source = input.clone();
+            let analysis = tauri::async_runtime::spawn_blocking(move || downmix::analyze(&source))
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|result| result);
+            match analysis {
+                Ok(analysis) => {
+                    tracing::info!("聲道分析 {}: {:?}", path, analysis.layout);
+                    analysis.downmix
+                }
+                Err(e) => {
+                    tracing::warn!("無法分析聲道 {}: {}", path, e);
+                    Downmix::Keep
+                }
+            }
+        } else {
+            Downmix::Keep
+        };
+        let converter = Converter::new()
+            .with_cancel(ctx.cancel.clone())
+            .with_encoding(config.converted_encoding())
+            .with_downmix(downmix)
+            .with_collision(config.output_collision);
+        let converted = converter.convert_to_mp3(&ffmpeg, &input, &output_dir).await;
+        drop(decoded);
+        match converted {
+            Ok(output_path) => {
+                success_count += 1;
+                messages.push(format!("✓ {}", output_path));
+                if let Some(note) = Converter::output_path(path, &output_dir)
+                    .and_then(|requested| renamed_note(&requested, &output_path))
+                {
+                    messages.push(note);
+                }
+                ctx.record_output(&output_path);
+                outputs.push(output_path.clone());
+
+                // 記錄到專案描述檔，供之後的完整性檢查使用
+                let record_result = ProjectManifest::update(&project_paths.root, |m| {
+                    m.record_conversion(path, Path::new(&output_path))
+                });
+                if let Err(e) = record_result {
+                    tracing::warn!("無法更新專案描述檔: {}", e);
+                }
+
+                // 轉檔後外掛 (required 的外掛失敗時停止整個工作)
+                plugins::run_hook(
+                    PluginHook::PostConvert,
+                    Some(&project_paths.root),
+                    &[PathBuf::from(&output_path)],
+                    &ctx.cancel,
+                )
+                .await?;
+
+                // 6. 設定中啟用轉檔後降噪時，另外輸出到 01b_cleaned (保留原始轉檔)
+                if config.denoise.on_convert {
+                    match run_denoiser(ctx, &output_path, &project_paths).await {
+                        Ok(cleaned) => messages.push(format!("✓ {}", cleaned)),
+                        Err(e) => {
+                            ctx.check_cancelled()?;
+                            messages.push(crate::tr!(
+                                "result.denoise_failed",
+                                file = output_path,
+                                detail = e.message
+                            ));
+                        }
+                    }
+                }
+            }
+            Err(e) => {
+                ctx.check_cancelled()?;
+                fail_count += 1;
+                messages.push(format!("✗ {} - {}", path, e));
+            }
+        }
+    }
+
+    // 7. 計算最後顯示的根目錄路徑
+    let root_path_display = file_paths
+        .first()
+        .and_then(|path| ProjectPaths::new(path).ok())
+        .and_then(|p| p.root.parent().map(|p| p.to_string_lossy().to_string()))
+        .unwrap_or_else(|| "Unknown".to_string());
+
+    let message = crate::tr!(
+        "result.convert_summary",
+        success = success_count,
+        failed = fail_count,
+        location = root_path_display,
+        details = messages.join("\n"),
+    );
+    Ok((message, outputs))
+}
+
+/// 降噪並輸出到 01b_cleaned
+async fn denoise_file(
+    ctx: &JobContext,
+    audio_path: &str,
+    project_root: Option<&str>,
+) -> Result<String, AppError> {
+    let project_paths = resolve_project(project_root, audio_path)?;
+    ctx.progress(0.0, crate::tr!("progress.denoising"));
+    let output_path = run_denoiser(ctx, audio_path, &project_paths).await?;
+    Ok(crate::tr!("result.denoise_summary", file = output_path))
+}
+
+/// 依設定降噪單一檔案並記錄輸出檔 (加密專案的來源檔先解密到暫存資料夾)
+async fn run_denoiser(
+    ctx: &JobContext,
+    audio_path: &str,
+    project_paths: &ProjectPaths,
+) -> Result<String, AppError> {
+    let config = settings::load();
+    let decrypted;
+    let audio_path = if encryption::is_encrypted_file(Path::new(audio_path)) {
+        decrypted = encryption::open_file(&ctx.app, Path::new(audio_path))?
+            .to_string_lossy()
+            .to_string();
+        decrypted.as_str()
+    } else {
+        audio_path
+    };
+
+    std::fs::create_dir_all(&project_paths.cleaned)?;
+    storage::ensure_space(
+        &project_paths.cleaned,
+        storage::estimate_mp3_size(audio_path),
+    )?;
+    let output_path = Denoiser::new(config.denoise)
+        .with_model(denoise::bundled_model(&ctx.app))
+        .with_bitrate(config.ffmpeg_preset.bitrate_kbps())
+        .with_cancel(ctx.cancel.clone())
+        .denoise(
+            &Ffmpeg::from(&ctx.app),
+            audio_path,
+            &project_paths.cleaned.to_string_lossy(),
+        )
+        .await
+        .map_err(AppError::tool)?;
+    ctx.record_output(&output_path);
+    Ok(output_path)
+}
+
+/// 人聲強化並輸出到 01b_cleaned，回傳輸出檔路徑 (供播放器比較模式使用)
+async fn enhance_speech(
+    ctx: &JobContext,
+    audio_path: &str,
+    project_root: Option<&str>,
+) -> Result<String, AppError> {
+    let project_paths = resolve_project(project_root, audio_path)?;
+    let decrypted;
+    let audio_path = if encryption::is_encrypted_file(Path::new(audio_path)) {
+        decrypted = encryption::open_file(&ctx.app, Path::new(audio_path))?
+            .to_string_lossy()
+            .to_string();
+        decrypted.as_str()
+    } else {
+        audio_path
+    };
+
+    ctx.progress(0.0, crate::tr!("progress.enhancing"));
+    std::fs::create_dir_all(&project_paths.cleaned)?;
+    storage::ensure_space(
+        &project_paths.cleaned,
+        storage::estimate_mp3_size(audio_path),
+    )?;
+    let output_path = SpeechEnhancer::new()
+        .with_bitrate(settings::load().ffmpeg_preset.bitrate_kbps())
+        .with_cancel(ctx.cancel.clone())
+        .enhance(
+            &Ffmpeg::from(&ctx.app),
+            audio_path,
+            &project_paths.cleaned.to_string_lossy(),
+        )
+        .await
+        .map_err(AppError::tool)?;
+    ctx.record_output(&output_path);
+    Ok(output_path)
+}
+
+/// 依段落切割音檔到 02_split
+/// 指定 transcribe 時每切出一段就送去轉錄，切割與上傳同時進行
+async fn split_segments(
+    ctx: &JobContext,
+    audio_path: &str,
+    project_root: Option<&str>,
+    segments: &[(String, String, String)],
+    transcribe: Option<&TranscribeOnSplit>,
+) -> Result<String, AppError> {
+    // 使用 ProjectPaths 建立輸出目錄 (02_split)
+    let project_paths = resolve_project(project_root, audio_path)?;
+    project_paths.create_all_dirs().map_err(AppError::io)?;
+    // 加密專案：03_silence 內的來源檔先解密到暫存資料夾，輸出在完成後加密
+    let key = encryption::project_key(&ctx.app, &project_paths.root)?;
+    let decrypted;
+    let audio_path = if encryption::is_encrypted_file(Path::new(audio_path)) {
+        decrypted = encryption::open_file(&ctx.app, Path::new(audio_path))?
+            .to_string_lossy()
+            .to_string();
+        decrypted.as_str()
+    } else {
+        audio_path
+    };
+    let output_dir_str = project_paths.split.to_string_lossy().to_string();
+
+    // 確認磁碟空間足夠 (時間無法解析時以整個檔案大小估計)
+    let total_seconds: Option<f64> = segments
+        .iter()
+        .map(|(_, start, end)| Some(parse_time(end).ok()? - parse_time(start).ok()?))
+        .sum();
+    let estimated = match total_seconds {
+        Some(secs) => storage::estimate_segment_size(audio_path, secs),
+        None => storage::file_size(audio_path),
+    };
+    storage::ensure_space(&project_paths.split, estimated)?;
+
+    ctx.progress(
+        0.0,
+        crate::tr!("progress.splitting", count = segments.len()),
+    );
+
+    // 執行切割 (需要轉錄時，切好的檔案經由 channel 交給轉錄佇列)
+    let split_result = async {
+        match transcribe {
+            Some(options) => {
+                let mut queue = TranscribeQueue::new(
+                    ctx,
+                    &options.server,
+                    &project_paths.root,
+                    options.diarize,
+                )?;
+                let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<PathBuf>();
+                let on_output: SplitOutput = Arc::new(move |path: &str| {
+                    let _ = tx.send(PathBuf::from(path));
+                });
+                // 切割結束時 on_output (連同 tx) 被釋放，接收端隨之結束
+                let split =
+                    run_splitter(ctx, audio_path, &output_dir_str, segments, Some(on_output));
+                let enqueue = async {
+                    while let Some(file) = rx.recv().await {
+                        queue.push(file);
+                    }
+                };
+                let (output_files, ()) = tokio::join!(split, enqueue);
+                let output_files = output_files?;
+                Ok::<_, AppError>((output_files, Some(queue.finish(ctx).await?)))
+            }
+            None => Ok((
+                run_splitter(ctx, audio_path, &output_dir_str, segments, None).await?,
+                None,
+            )),
+        }
+    }
+    .await;
+    // 轉錄佇列結束後才加密；失敗或取消時已切出的檔案也不留明文
+    let sealed = seal_split_outputs(key.as_ref(), &project_paths.split);
+    let (output_files, transcribed) = split_result?;
+    let listed: Vec<String> = if sealed? {
+        output_files
+            .iter()
+            .map(|f| {
+                encryption::encrypted_path(Path::new(f))
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .inspect(|f| ctx.record_output(f))
+            .collect()
+    } else {
+        output_files.clone()
+    };
+
+    // 輸出檔與段落順序相同，改名的檔案另外列出
+    let mut lines = listed;
+    lines.extend(
+        segments
+            .iter()
+            .zip(&output_files)
+            .filter_map(|((name, _, _), actual)| {
+                renamed_note(
+                    &Splitter::output_path(audio_path, &output_dir_str, name),
+                    actual,
+                )
+            }),
+    );
+
+    let mut message = crate::tr!(
+        "result.split_summary",
+        count = output_files.len(),
+        dir = output_dir_str,
+        files = lines.join("\n"),
+    );
+    if let Some((done, failed)) = transcribed {
+        message.push_str("\n\n");
+        message.push_str(&crate::tr!(
+            "result.split_transcribed",
+            done = done,
+            failed = failed.len(),
+        ));
+        for failure in &failed {
+            message.push_str(&format!("\n⚠️ {}", failure));
+        }
+    }
+    Ok(message)
+}
+
+/// 加密專案的 02_split：加密資料夾內的明文檔，回傳是否有加密
+fn seal_split_outputs(
+    key: Option<&encryption::ProjectKey>,
+    split_dir: &Path,
+) -> Result<bool, AppError> {
+    match key {
+        Some(key) => encryption::seal_dir(key, split_dir).map(|_| true),
+        None => Ok(false),
+    }
+}
+
+/// 切割並記錄輸出檔，回傳產生的檔案
+async fn run_splitter(
+    ctx: &JobContext,
+    audio_path: &str,
+    output_dir: &str,
+    segments: &[(String, String, String)],
+    on_output: Option<SplitOutput>,
+) -> Result<Vec<String>, AppError> {
+    let config = settings::load();
+    let mut splitter = Splitter::new()
+        .with_cancel(ctx.cancel.clone())
+        .with_collision(config.output_collision)
+        .with_encoding(config.encoding.split);
+    if let Some(on_output) = on_output {
+        splitter = splitter.with_on_output(on_output);
+    }
+    let output_files = splitter
+        .split_segments(
+            &Ffmpeg::from(&ctx.app),
+            audio_path,
+            output_dir,
+            segments.to_vec(),
+        )
+        .await
+        .map_err(AppError::tool)?;
+    for file in &output_files {
+        ctx.record_output(file);
+    }
+    Ok(output_files)
+}
+
+/// 專案流程的手動消音：輸出到 03_silence 並整理原始檔
+async fn apply_project_silence(
+    ctx: &JobContext,
+    audio_path: &str,
+    project_root: Option<&str>,
+    segments: &[(f64, f64)],
+) -> Result<String, AppError> {
+    // 建立輸出目錄 (03_silence)
+    let project_paths = resolve_project(project_root, audio_path)?;
+    project_paths.create_all_dirs().map_err(AppError::io)?;
+    // 加密專案：03_silence 內的來源檔先解密到暫存資料夾，輸出在完成後加密
+    let key = encryption::project_key(&ctx.app, &project_paths.root)?;
+    let decrypted;
+    let audio_path = if encryption::is_encrypted_file(Path::new(audio_path)) {
+        decrypted = encryption::open_file(&ctx.app, Path::new(audio_path))?
+            .to_string_lossy()
+            .to_string();
+        decrypted.as_str()
+    } else {
+        audio_path
+    };
+    let output_dir_str = project_paths.silence.to_string_lossy().to_string();
+
+    // 檢查 03_silence 是否為空
+    // 規則：若是第一次執行 (03 為空)，將 02_split 下的所有檔案 複製 (Copy) 過來
+    // 這樣 02_split 保留所有原始檔，03_silence 則作為報告用的工作目錄
+    let silence_is_empty = std::fs::read_dir(&project_paths.silence)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(false);
+
+    // 確認磁碟空間：消音輸出約與原檔同大小，首次執行另需複製 02_split
+    let mut estimated = storage::file_size(audio_path);
+    if silence_is_empty {
+        if let Ok(entries) = std::fs::read_dir(&project_paths.split) {
+            estimated += entries
+                .flatten()
+                .filter_map(|e| e.metadata().ok())
+                .map(|m| m.len())
+                .sum::<u64>();
+        }
+    }
+    storage::ensure_space(&project_paths.silence, estimated)?;
+    if silence_is_empty {
+        match promote_files(
+            &project_paths,
+            "02_split",
+            "03_silence",
+            &[],
+            TransferMode::Copy,
+            ConflictPolicy::Skip,
+        ) {
+            Ok(result) => {
+                for failure in &result.failed {
+                    tracing::warn!("Failed to copy file to 03_silence: {}", failure);
+                }
+                let _ = ctx.app.emit("project://promoted", &result);
+            }
+            Err(e) => tracing::warn!("Failed to copy 02_split to 03_silence: {}", e),
+        }
+    }
+
+    // 覆寫既有的消音檔或移除 03_silence 內的原始檔之前，先備份
+    let mut to_backup = vec![PathBuf::from(Silence::silenced_output_path(
+        audio_path,
+        &output_dir_str,
+    ))];
+    if let Some(file_name) = Path::new(audio_path).file_name() {
+        to_backup.push(project_paths.silence.join(file_name));
+    }
+    if key.is_some() {
+        let sealed: Vec<PathBuf> = to_backup
+            .iter()
+            .map(|p| encryption::encrypted_path(p))
+            .collect();
+        to_backup.extend(sealed);
+    }
+    backup::snapshot(&project_paths.root, &to_backup).map_err(AppError::io)?;
+
+    ctx.progress(0.0, crate::tr!("progress.silencing"));
+
+    let output_path = Silence::new()
+        .with_cancel(ctx.cancel.clone())
+        .with_collision(settings::load().output_collision)
+        .apply_silence_to_segments(
+            &Ffmpeg::from(&ctx.app),
+            audio_path,
+            &output_dir_str,
+            segments.to_vec(),
+        )
+        .await
+        .map_err(AppError::tool)?;
+    let renamed = renamed_note(
+        &Silence::silenced_output_path(audio_path, &output_dir_str),
+        &output_path,
+    );
+
+    // 處理完成後，將該檔案的"原始檔"從 03_silence 中移除 (如果存在)
+    // 根據需求：03_silence 應該只保留"已處理的檔案"以及"尚未處理的其他檔案"
+    // 當某個檔案被處理成 xxx_silenced.mp3 後，原本在 03_silence 的 xxx.mp3 就應該移除，避免重複
+    if let Some(file_name) = Path::new(audio_path).file_name() {
+        let original_in_silence = project_paths.silence.join(file_name);
+        if original_in_silence.exists() && original_in_silence.is_file() {
+            // 確認一下不是刪除剛產生的 output_path (雖然檔名應該不同，output 有 suffix)
+            // 這裡簡單檢查一下路徑是否完全相同
+            if original_in_silence.to_string_lossy() != output_path {
+                discard(&original_in_silence);
+            }
+        }
+        let sealed = encryption::encrypted_path(&original_in_silence);
+        if sealed.exists() {
+            discard(&sealed);
+        }
+    }
+
+    let output_path = match key {
+        Some(key) => {
+            encryption::seal_dir(&key, &project_paths.silence)?;
+            encryption::encrypted_path(Path::new(&output_path))
+                .to_string_lossy()
+                .to_string()
+        }
+        None => output_path,
+    };
+    ctx.record_output(&output_path);
+
+    let mut result = crate::tr!("result.silence_done", path = output_path);
+    if let Some(note) = renamed {
+        result.push('\n');
+        result.push_str(&note);
+    }
+    Ok(result)
+}
+
+/// 流程中不再需要的檔案移到回收筒 (02_split 仍保留原檔)；無法移入回收筒時保留檔案
+fn discard(path: &Path) {
+    if let Err(e) = trash::move_to_trash(path) {
+        tracing::warn!("無法將 {} 移到回收筒，保留檔案: {}", path.display(), e);
+    }
+}
+
+/// 報告輸出資料夾：選的是專案的 02_split 時輸出到 04_report，否則在同一資料夾
+fn report_dir(folder_path: &str) -> PathBuf {
+    let folder = Path::new(folder_path);
+    ProjectPaths::find_root(folder)
+        .map(ProjectPaths::from_existing_root)
+        .filter(|paths| paths.split == folder)
+        .map(|paths| paths.report)
+        .unwrap_or_else(|| folder.to_path_buf())
+}
+
+/// 根據資料夾路徑推算報告輸出路徑 (04_report/report.md)
+fn report_output_path(folder_path: &str) -> String {
+    report_dir(folder_path)
+        .join("report.md")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// 生成報告，並自動轉換為 DOCX
+async fn generate_report(
+    ctx: &JobContext,
+    api_key: &str,
+    folder_path: &str,
+    model_name: Option<String>,
+    custom_prompt: Option<String>,
+) -> Result<String, AppError> {
+    if api_key.is_empty() {
+        // 重新啟動後恢復的報告工作不會保存 API Key
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.missing_api_key_resumed",
+            &[],
+        ));
+    }
+
+    let output_path = report_output_path(folder_path);
+    let project_root = ProjectPaths::find_root(Path::new(folder_path));
+    let key = match &project_root {
+        Some(root) => encryption::project_key(&ctx.app, root)?,
+        None => None,
+    };
+
+    // 覆寫既有報告前先備份 (不在專案內時備份到報告所在的資料夾)
+    let backup_root = ProjectPaths::new(&output_path)
+        .map(|p| p.root)
+        .unwrap_or_else(|_| {
+            Path::new(&output_path)
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| PathBuf::from(folder_path))
+        });
+    let mut to_backup = vec![
+        PathBuf::from(&output_path),
+        Path::new(&output_path).with_extension("docx"),
+        report::summary_path(Path::new(&output_path)),
+    ];
+    if key.is_some() {
+        let sealed: Vec<PathBuf> = to_backup
+            .iter()
+            .map(|p| encryption::encrypted_path(p))
+            .collect();
+        to_backup.extend(sealed);
+    }
+    backup::snapshot(&backup_root, &to_backup).map_err(AppError::io)?;
+
+    // 加密專案：來源解密到暫存資料夾，報告先寫在暫存資料夾，完成後加密存回
+    let (input_folder, work_output) = match (&key, &project_root) {
+        (Some(key), Some(root)) => {
+            let input = encryption::open_folder(key, root, Path::new(folder_path))?;
+            let report_dir = encryption::scratch_dir(root)?.join("report");
+            std::fs::create_dir_all(&report_dir)?;
+            (
+                input.to_string_lossy().to_string(),
+                report_dir.join("report.md").to_string_lossy().to_string(),
+            )
+        }
+        _ => (folder_path.to_string(), output_path.clone()),
+    };
+
+    // 未指定模型時使用設定中的預設模型
+    let config = settings::load();
+    let model_name = model_name.or(config.default_model);
+
+    // 1. 生成報告 (Markdown)
+    let progress_ctx = ctx.clone();
+    let agent = ReportAgent::new(api_key.to_string())
+        .with_cancel(ctx.cancel.clone())
+        .with_ffmpeg(Ffmpeg::from(&ctx.app))
+        .with_bilingual(config.bilingual_report)
+        .with_annotations(
+            project_root
+                .as_deref()
+                .map(|root| annotations::list(root, None))
+                .unwrap_or_default(),
+        )
+        .with_template_vars(
+            project_root
+                .as_deref()
+                .map(project_template_vars)
+                .unwrap_or_default(),
+        )
+        .with_progress(Arc::new(move |idx, total, filename| {
+            progress_ctx.progress(
+                idx as f32 / total as f32,
+                crate::tr!(
+                    "progress.reporting",
+                    current = idx + 1,
+                    total = total,
+                    file = filename,
+                ),
+            );
+        }))
+        .with_upload_progress({
+            let app = ctx.app.clone();
+            let job_id = ctx.id.clone();
+            Arc::new(move |file, uploaded, total| {
+                let _ = app.emit(
+                    report::UPLOAD_PROGRESS_EVENT,
+                    report::UploadProgressEvent {
+                        job_id: job_id.clone(),
+                        file: file.to_string(),
+                        uploaded,
+                        total,
+                    },
+                );
+            })
+        });
+    let agent = match &project_root {
+        Some(root) => agent.with_transcript_dir(root.join(TRANSCRIPT_DIR)),
+        None => agent,
+    };
+    // 加密專案的報告寫在暫存資料夾，上次的 report.json 需先解密才能沿用未變更的段落
+    let agent = if key.is_some() {
+        let sealed = encryption::encrypted_path(&report::summary_path(Path::new(&output_path)));
+        let previous = if sealed.is_file() {
+            encryption::open_file(&ctx.app, &sealed)
+                .map_err(|e| tracing::warn!("無法讀取上次的報告，全部重新生成: {}", e))
+                .ok()
+                .and_then(|path| report::read_summary(&path))
+        } else {
+            None
+        };
+        agent.with_previous(previous.map(|s| s.sections).unwrap_or_default())
+    } else {
+        agent
+    };
+    // 上傳前外掛 (加密專案收到的是解密後的暫存檔)
+    let mut uploads: Vec<PathBuf> = std::fs::read_dir(&input_folder)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && ingest::is_media_file(p))
+        .collect();
+    uploads.sort();
+    plugins::run_hook(
+        PluginHook::PreUpload,
+        project_root.as_deref(),
+        &uploads,
+        &ctx.cancel,
+    )
+    .await?;
+
+    let usage_model = model_name
+        .clone()
+        .unwrap_or_else(|| report::DEFAULT_MODEL.to_string());
+    let report_result = agent
+        .process_folder(&input_folder, &work_output, model_name, custom_prompt)
+        .await;
+    // 失敗或取消前已送出的請求也計入用量
+    if let Some(root) = &project_root {
+        project_stats::record_api_usage(root, "report", &usage_model, agent.usage());
+    }
+    let report_result = report_result.map_err(AppError::api)?;
+    let mut produced = vec![PathBuf::from(&work_output)];
+    let summary = report::summary_path(Path::new(&work_output));
+    if summary.exists() {
+        produced.push(summary);
+    }
+
+    // 2. 去識別化自我檢查 (結果寫入 report.json)
+    let deid_config = config.deid;
+    let mut deid_result = String::new();
+    let mut docx_allowed = true;
+    if deid_config.enabled {
+        match deid::check_report(Path::new(&work_output), &deid_config, Some(api_key)).await {
+            Ok(check) => {
+                if !check.findings.is_empty() {
+                    deid_result = crate::tr!("result.deid_findings", count = check.findings.len());
+                }
+                docx_allowed = !(deid_config.block_docx && check.needs_review());
+            }
+            Err(e) => tracing::warn!("去識別化檢查失敗: {}", e),
+        }
+    }
+
+    // 3. 自動轉換為 DOCX
+    let docx_result = if !docx_allowed {
+        crate::tr!("result.report_docx_blocked_deid")
+    } else {
+        match report::convert_md_to_docx(&work_output).await {
+            Ok(docx_path) => {
+                produced.push(PathBuf::from(&docx_path));
+                crate::tr!("result.report_docx_done", path = docx_path)
+            }
+            Err(e) => crate::tr!("result.report_docx_failed", detail = e),
+        }
+    };
+
+    match &key {
+        Some(key) => {
+            let report_dir = Path::new(&output_path)
+                .parent()
+                .unwrap_or(Path::new("."))
+                .to_path_buf();
+            std::fs::create_dir_all(&report_dir)?;
+            let mut sealed = Vec::new();
+            for file in &produced {
+                let path = encryption::seal_file(key, file, &report_dir)?;
+                ctx.record_output(&path);
+                sealed.push(path);
+            }
+            produced = sealed;
+            let _ = std::fs::remove_dir_all(&input_folder);
+            let _ = std::fs::remove_dir_all(
+                Path::new(&work_output)
+                    .parent()
+                    .unwrap_or(Path::new(&work_output)),
+            );
+        }
+        None => {
+            for file in &produced {
+                ctx.record_output(file);
+            }
+        }
+    }
+
+    // 報告後外掛 (加密專案收到的是加密後的檔案)
+    plugins::run_hook(
+        PluginHook::PostReport,
+        project_root.as_deref(),
+        &produced,
+        &ctx.cancel,
+    )
+    .await?;
+
+    Ok(format!("{}{}{}", report_result, deid_result, docx_result))
+}
+
+/// 報告提示詞的模板變數；專案描述檔無法讀取時仍提供預設值
+fn project_template_vars(root: &Path) -> BTreeMap<String, String> {
+    ProjectManifest::load(root)
+        .map_err(|e| tracing::warn!("無法讀取專案描述檔: {}", e))
+        .unwrap_or_default()
+        .template_vars(root)
+}
+
+/// Prompt 實驗：同一音檔以多組 Prompt / 模型生成，結果寫入 04_report/experiments
+async fn run_prompt_experiment(
+    ctx: &JobContext,
+    audio_path: &str,
+    variants: &[PromptVariant],
+    api_key: &str,
+) -> Result<Value, AppError> {
+    if api_key.is_empty() {
+        // 重新啟動後恢復的工作不會保存 API Key
+        return Err(AppError::localized(
+            ErrorKind::InvalidInput,
+            "error.missing_api_key_resumed",
+            &[],
+        ));
+    }
+    let audio = Path::new(audio_path);
+    let resolved = experiments::resolve(variants, settings::load().default_model)?;
+    let dir = experiments::experiment_dir(audio)?;
+
+    let pairs: Vec<(String, String)> = resolved
+        .iter()
+        .map(|v| (v.model.clone(), v.prompt.clone()))
+        .collect();
+    let labels: Vec<String> = resolved.iter().map(|v| v.label.clone()).collect();
+    let progress_ctx = ctx.clone();
+    let agent = ReportAgent::new(api_key.to_string())
+        .with_cancel(ctx.cancel.clone())
+        .with_ffmpeg(Ffmpeg::from(&ctx.app))
+        .with_template_vars(
+            ProjectPaths::find_root(audio)
+                .as_deref()
+                .map(project_template_vars)
+                .unwrap_or_default(),
+        )
+        .with_progress(Arc::new(move |idx, total, _| {
+            progress_ctx.progress(
+                idx as f32 / total as f32,
+                crate::tr!(
+                    "progress.experiment",
+                    current = idx + 1,
+                    total = total,
+                    label = labels.get(idx).map(String::as_str).unwrap_or_default(),
+                ),
+            );
+        }));
+    plugins::run_hook(
+        PluginHook::PreUpload,
+        ProjectPaths::find_root(audio).as_deref(),
+        &[audio.to_path_buf()],
+        &ctx.cancel,
+    )
+    .await?;
+    let runs = agent.run_prompts(audio_path, &pairs).await;
+    if let Some(root) = ProjectPaths::find_root(audio) {
+        for (variant, run) in resolved.iter().zip(runs.iter().flatten()) {
+            if let Ok(run) = run {
+                project_stats::record_api_usage(&root, "experiment", &variant.model, run.usage);
+            }
+        }
+    }
+    let runs = runs.map_err(AppError::api)?;
+
+    let (experiment, produced) = experiments::save(&dir, audio, &resolved, runs)?;
+    for path in &produced {
+        ctx.record_output(path);
+    }
+    Ok(serde_json::to_value(experiment)?)
+}
+
+/// 一鍵流程：依序執行各階段，每個階段完成後寫入 pipeline.json
+/// 以相同輸入與選項重新執行時，已完成的階段與檔案會略過
+async fn run_pipeline(
+    ctx: &JobContext,
+    file_paths: &[String],
+    root: &Path,
+    options: &PipelineOptions,
+    api_key: &str,
+) -> Result<String, AppError> {
+    let project_paths = ProjectPaths::from_root(root.to_path_buf()).map_err(AppError::io)?;
+    let mut state = match pipeline::load(root) {
+        Some(state) if state.can_resume(file_paths, options) => {
+            tracing::info!("接續上次未完成的流程: {}", root.display());
+            state
+        }
+        _ => PipelineState::new(file_paths.to_vec(), options.clone()),
+    };
+    state.error = None;
+    pipeline::save(&ctx.app, root, &mut state)?;
+
+    let result = run_pipeline_stages(ctx, &project_paths, &mut state, api_key).await;
+    if let Err(e) = &result {
+        // 保留 current，讓使用者知道停在哪個階段
+        state.error = Some(e.clone());
+    }
+    if let Err(e) = pipeline::save(&ctx.app, root, &mut state) {
+        tracing::warn!("無法保存流程狀態: {}", e);
+    }
+    result
+}
+
+async fn run_pipeline_stages(
+    ctx: &JobContext,
+    paths: &ProjectPaths,
+    state: &mut PipelineState,
+    api_key: &str,
+) -> Result<String, AppError> {
+    let root = paths.root.clone();
+    let root_str = root.to_string_lossy().to_string();
+
+    for stage in state.stages.clone() {
+        if state.is_done(stage) {
+            continue;
+        }
+        ctx.check_cancelled()?;
+        state.current = Some(stage);
+        pipeline::save(&ctx.app, &root, state)?;
+
+        let (start, end) = state.stage_range(stage);
+        let stage_ctx = ctx.with_range(start, end);
+        match stage {
+            PipelineStage::Convert => {
+                // 使用轉檔實際輸出的檔案 (同名時可能被改名為 "名稱 (1).mp3")
+                let (_, converted) =
+                    convert_files(&stage_ctx, &state.inputs, Some(&root_str)).await?;
+                state.converted = converted;
+                if state.converted.is_empty() {
+                    return Err(AppError::localized(
+                        ErrorKind::Tool,
+                        "error.pipeline_no_files",
+                        &[],
+                    ));
+                }
+            }
+            PipelineStage::Split => {
+                state.split = pipeline_split(&stage_ctx, paths, state).await?;
+            }
+            PipelineStage::Transcribe => pipeline_transcribe(&stage_ctx, &root, state).await?,
+            PipelineStage::Redact => pipeline_redact(&stage_ctx, &root_str, state).await?,
+            PipelineStage::Report => {
+                // 有消音結果時以 03_silence 生成報告，否則使用 02_split
+                let has_silenced = std::fs::read_dir(&paths.silence)
+                    .map(|mut entries| entries.next().is_some())
+                    .unwrap_or(false);
+                let folder = if has_silenced {
+                    &paths.silence
+                } else {
+                    &paths.split
+                };
+                let folder = folder.to_string_lossy().to_string();
+                generate_report(
+                    &stage_ctx,
+                    api_key,
+                    &folder,
+                    state.options.model_name.clone(),
+                    state.options.custom_prompt.clone(),
+                )
+                .await?;
+                state.report = Some(report_output_path(&folder));
+            }
+        }
+        state.completed.push(stage);
+        pipeline::save(&ctx.app, &root, state)?;
+    }
+    state.current = None;
+
+    Ok(crate::tr!(
+        "result.pipeline_done",
+        files = state.split.len(),
+        transcripts = state.transcripts.len(),
+        report = state.report.clone().unwrap_or_else(|| "-".to_string()),
+    ))
+}
+
+/// 切割階段：依設定的長度切成多段，未設定時直接複製到 02_split
+async fn pipeline_split(
+    ctx: &JobContext,
+    paths: &ProjectPaths,
+    state: &PipelineState,
+) -> Result<Vec<String>, AppError> {
+    let Some(minutes) = state.options.split_minutes.filter(|m| *m > 0.0) else {
+        let names: Vec<String> = state
+            .converted
+            .iter()
+            .filter_map(|f| Path::new(f).file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .collect();
+        let result = promote_files(
+            paths,
+            "01_converted",
+            "02_split",
+            &names,
+            TransferMode::Copy,
+            ConflictPolicy::Overwrite,
+        )
+        .map_err(AppError::io)?;
+        if let Some(failure) = result.failed.first() {
+            return Err(AppError::io(failure.clone()));
+        }
+        for file in &result.transferred {
+            ctx.record_output(file);
+        }
+        return Ok(result.transferred);
+    };
+
+    let split_dir = paths.split.to_string_lossy().to_string();
+    let chunk = minutes * 60.0;
+    let total = state.converted.len();
+    let mut files = Vec::new();
+    for (idx, file) in state.converted.iter().enumerate() {
+        ctx.check_cancelled()?;
+        let duration = probe::cached_duration(file).map_err(AppError::tool)?;
+        let count = (duration / chunk).ceil().max(1.0) as usize;
+        let stem = Path::new(file)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let segments: Vec<(String, String, String)> = (0..count)
+            .map(|i| {
+                let start = i as f64 * chunk;
+                let end = ((i + 1) as f64 * chunk).min(duration);
+                (
+                    format!("{}_part{:02}", stem, i + 1),
+                    format!("{:.3}", start),
+                    format!("{:.3}", end),
+                )
+            })
+            .collect();
+        storage::ensure_space(&paths.split, storage::file_size(file))?;
+        ctx.progress(
+            idx as f32 / total as f32,
+            crate::tr!("progress.splitting", count = count),
+        );
+        files.extend(run_splitter(ctx, file, &split_dir, &segments, None).await?);
+    }
+    Ok(files)
+}
+
+/// 轉錄階段：逐字稿存到 .silence_reg/<檔名>.json，每個檔案完成後寫入檢查點
+async fn pipeline_transcribe(
+    ctx: &JobContext,
+    root: &Path,
+    state: &mut PipelineState,
+) -> Result<(), AppError> {
+    let server = state.options.server.clone().unwrap_or_default();
+    let diarize = state.options.diarize;
+    let transcript_dir = root.join(TRANSCRIPT_DIR);
+    std::fs::create_dir_all(&transcript_dir)?;
+    let service = ctx.app.state::<Silence>();
+
+    let files = state.split.clone();
+    let total = files.len();
+    for (idx, file) in files.iter().enumerate() {
+        if state.transcript_for(file).is_some() {
+            continue;
+        }
+        ctx.check_cancelled()?;
+        let name = Path::new(file)
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        ctx.progress(
+            idx as f32 / total as f32,
+            crate::tr!(
+                "progress.pipeline_transcribing",
+                current = idx + 1,
+                total = total,
+                file = name,
+            ),
+        );
+        plugins::run_hook(
+            PluginHook::PreUpload,
+            Some(root),
+            &[PathBuf::from(file)],
+            &ctx.cancel,
+        )
+        .await?;
+        let response = tokio::select! {
+            response = service.transcribe(&server, file, diarize) => {
+                response.map_err(AppError::network)?
+            }
+            _ = ctx.cancel.cancelled() => return Err(AppError::cancelled()),
+        };
+        let json_path = transcript_dir.join(format!("{}.json", name));
+        write_atomic(&json_path, &serde_json::to_vec_pretty(&response)?)?;
+        ctx.record_output(&json_path);
+        state
+            .transcripts
+            .push((file.clone(), json_path.to_string_lossy().to_string()));
+        pipeline::save(&ctx.app, root, state)?;
+    }
+    Ok(())
+}
+
+/// 單一檔案轉錄失敗時的重試次數 (連線中斷、伺服器忙碌)
+const TRANSCRIBE_ATTEMPTS: u32 = 3;
+
+/// 逐字稿比音檔新時視為已完成 (中斷後重新執行會從未完成的檔案繼續)
+fn transcript_up_to_date(audio: &Path, json: &Path) -> bool {
+    let modified = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    matches!((modified(audio), modified(json)), (Some(a), Some(j)) if j >= a)
+}
+
+/// 轉錄一個檔案並寫入逐字稿，失敗時重試
+async fn transcribe_one(
+    app: tauri::AppHandle,
+    server: String,
+    file: PathBuf,
+    json_path: PathBuf,
+    diarize: bool,
+) -> Result<PathBuf, String> {
+    let service = app.state::<Silence>();
+    let mut attempt = 1;
+    let response = loop {
+        match service
+            .transcribe(&server, &file.to_string_lossy(), diarize)
+            .await
+        {
+            Ok(response) => break response,
+            Err(e) if attempt < TRANSCRIBE_ATTEMPTS => {
+                tracing::warn!("轉錄失敗，稍後重試 ({}): {}", file.display(), e);
+                tokio::time::sleep(std::time::Duration::from_secs(2 * attempt as u64)).await;
+                attempt += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    };
+    let json = serde_json::to_vec_pretty(&response).map_err(|e| e.to_string())?;
+    write_atomic(&json_path, &json).map_err(|e| e.to_string())?;
+    Ok(json_path)
+}
+
+/// 並行轉錄佇列：逐字稿存到 .silence_reg/<檔名>.json，同時上傳數依設定限制
+struct TranscribeQueue {
+    app: tauri::AppHandle,
+    server: String,
+    diarize: bool,
+    root: PathBuf,
+    cancel: CancelToken,
+    transcript_dir: PathBuf,
+    semaphore: Arc<tokio::sync::Semaphore>,
+    tasks: tokio::task::JoinSet<(PathBuf, Result<PathBuf, String>)>,
+}
+
+impl TranscribeQueue {
+    fn new(ctx: &JobContext, server: &str, root: &Path, diarize: bool) -> Result<Self, AppError> {
+        let transcript_dir = root.join(TRANSCRIPT_DIR);
+        std::fs::create_dir_all(&transcript_dir)?;
+        let parallel = settings::load().stt_parallel_uploads.max(1);
+        Ok(Self {
+            app: ctx.app.clone(),
+            server: server.to_string(),
+            diarize,
+            root: root.to_path_buf(),
+            cancel: ctx.cancel.clone(),
+            transcript_dir,
+            semaphore: Arc::new(tokio::sync::Semaphore::new(parallel)),
+            tasks: tokio::task::JoinSet::new(),
+        })
+    }
+
+    fn json_path(&self, file: &Path) -> PathBuf {
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        self.transcript_dir.join(format!("{}.json", name))
+    }
+
+    /// 加入一個檔案 (等到有空位時先執行上傳前外掛再上傳)
+    fn push(&mut self, file: PathBuf) {
+        let (app, server, semaphore) = (
+            self.app.clone(),
+            self.server.clone(),
+            self.semaphore.clone(),
+        );
+        let (root, cancel) = (self.root.clone(), self.cancel.clone());
+        let (json, diarize) = (self.json_path(&file), self.diarize);
+        self.tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await;
+            let hook = plugins::run_hook(
+                PluginHook::PreUpload,
+                Some(&root),
+                std::slice::from_ref(&file),
+                &cancel,
+            )
+            .await;
+            let result = match hook {
+                Ok(()) => transcribe_one(app, server, file.clone(), json, diarize).await,
+                Err(e) => Err(e.to_string()),
+            };
+            (file, result)
+        });
+    }
+
+    /// 等待所有檔案完成，回傳 (完成數, 失敗訊息)
+    async fn finish(mut self, ctx: &JobContext) -> Result<(usize, Vec<String>), AppError> {
+        let total = self.tasks.len();
+        let mut finished = 0;
+        let mut failed = Vec::new();
+        loop {
+            let joined = tokio::select! {
+                joined = self.tasks.join_next() => joined,
+                _ = ctx.cancel.cancelled() => {
+                    self.tasks.abort_all();
+                    return Err(AppError::cancelled());
+                }
+            };
+            let Some(joined) = joined else {
+                break;
+            };
+            let (file, result) = joined.map_err(|e| AppError::internal(e.to_string()))?;
+            let name = file
+                .file_name()
+                .map(|n| n.to_string_lossy().to_string())
+                .unwrap_or_default();
+            match result {
+                Ok(json) => ctx.record_output(json),
+                Err(e) => failed.push(format!("{}: {}", name, e)),
+            }
+            finished += 1;
+            ctx.progress(
+                finished as f32 / total as f32,
+                crate::tr!(
+                    "progress.pipeline_transcribing",
+                    current = finished,
+                    total = total,
+                    file = name,
+                ),
+            );
+        }
+        Ok((total - failed.len(), failed))
+    }
+}
+
+/// 將 02_split 內的音檔一次送至 STT 伺服器，逐字稿存到 .silence_reg/<檔名>.json
+/// 同時上傳數依設定限制，共用同一個 HTTP 連線池
+async fn transcribe_folder(
+    ctx: &JobContext,
+    server: &str,
+    root: &Path,
+    diarize: bool,
+) -> Result<String, AppError> {
+    let split_dir = ProjectPaths::from_existing_root(root.to_path_buf()).split;
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&split_dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file() && ingest::is_media_file(p))
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return Err(AppError::localized(
+            ErrorKind::NotFound,
+            "error.no_split_files",
+            &[],
+        ));
+    }
+
+    let mut queue = TranscribeQueue::new(ctx, server, root, diarize)?;
+    let (done, pending): (Vec<PathBuf>, Vec<PathBuf>) = files
+        .into_iter()
+        .partition(|f| transcript_up_to_date(f, &queue.json_path(f)));
+    for file in pending {
+        queue.push(file);
+    }
+    let (transcribed, failed) = queue.finish(ctx).await?;
+
+    let mut message = crate::tr!(
+        "result.folder_transcribed",
+        done = transcribed,
+        skipped = done.len(),
+        failed = failed.len(),
+    );
+    for failure in &failed {
+        message.push_str(&format!("\n⚠️ {}", failure));
+    }
+    Ok(message)
+}
+
+/// 消音階段：依規則找出要消音的段落，輸出到 03_silence
+async fn pipeline_redact(
+    ctx: &JobContext,
+    root: &str,
+    state: &mut PipelineState,
+) -> Result<(), AppError> {
+    let files = state.split.clone();
+    let total = files.len();
+    for (idx, file) in files.iter().enumerate() {
+        if state.redacted.contains(file) {
+            continue;
+        }
+        ctx.check_cancelled()?;
+        if let Some(json) = state.transcript_for(file) {
+            let transcript = transcript::load(Path::new(json))?;
+            let ranges = state.options.redaction.ranges(&transcript);
+            if !ranges.is_empty() {
+                ctx.progress(
+                    idx as f32 / total as f32,
+                    crate::tr!(
+                        "progress.pipeline_redacting",
+                        current = idx + 1,
+                        total = total,
+                        count = ranges.len(),
+                    ),
+                );
+                apply_project_silence(ctx, file, Some(root), &ranges).await?;
+            }
+        }
+        state.redacted.push(file.clone());
+        pipeline::save(&ctx.app, Path::new(root), state)?;
+    }
+    Ok(())
+}
+
+/// Helper to parse "HH:MM:SS.mmm" or "SS.mmm" to seconds
+pub fn parse_time(t: &str) -> Result<f64, String> {
+    let parts: Vec<&str> = t.split(':').collect();
+    match parts.len() {
+        3 => {
+            let h: f64 = parts[0].parse().map_err(|_| "Invalid hour")?;
+            let m: f64 = parts[1].parse().map_err(|_| "Invalid minute")?;
+            let s: f64 = parts[2].parse().map_err(|_| "Invalid second")?;
+            Ok(h * 3600.0 + m * 60.0 + s)
+        }
+        2 => {
+            let m: f64 = parts[0].parse().map_err(|_| "Invalid minute")?;
+            let s: f64 = parts[1].parse().map_err(|_| "Invalid second")?;
+            Ok(m * 60.0 + s)
+        }
+        1 => parts[0]
+            .parse()
+            .map_err(|_| format!("Invalid time format: {}", t)),
+        _ => Err(format!("Invalid time format: {}", t)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_dir_only_redirects_the_project_split_folder() {
+        let project = std::env::temp_dir().join("案件 02_split 備份");
+        let split = project.join("02_split");
+        assert_eq!(
+            report_dir(&split.to_string_lossy()),
+            project.join("04_report")
+        );
+        // 名稱中含有 02_split 的其他資料夾不改寫
+        let silence = project.join("03_silence");
+        assert_eq!(report_dir(&silence.to_string_lossy()), silence);
+        let other = std::env::temp_dir().join("old_02_split");
+        assert_eq!(report_dir(&other.to_string_lossy()), other);
+    }
+
+    #[test]
+    fn split_outputs_are_sealed_in_encrypted_projects() {
+        let dir = std::env::temp_dir().join(format!("workflows_seal_split_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a.mp3"), b"segment").unwrap();
+
+        assert!(!seal_split_outputs(None, &dir).unwrap());
+        assert!(dir.join("a.mp3").exists());
+
+        assert!(seal_split_outputs(Some(&[4u8; 32]), &dir).unwrap());
+        assert!(!dir.join("a.mp3").exists());
+        assert!(dir.join("a.mp3.enc").exists());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
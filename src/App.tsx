@@ -1,6 +1,6 @@
 import { useState, useRef, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
+import { listen } from "@tauri-apps/api/event";
 import { check } from "@tauri-apps/plugin-updater";
 import { relaunch } from "@tauri-apps/plugin-process";
 import "./App.css";
@@ -12,9 +12,28 @@ import { SilencePage } from "./pages/SilencePage";
 import { SilenceAutoPage } from "./pages/SilenceAutoPage";
 import { WelcomePage } from "./pages/WelcomePage";
 import { ReportPage } from "./pages/ReportPage";
+import { RecordPage } from "./pages/RecordPage";
 import { useI18n } from "./i18n";
+import { isAppError, formatError } from "./errors";
 
-type Tab = "welcome" | "convert" | "split" | "silence" | "silence-auto" | "report";
+interface WindowSession {
+  label: string;
+  project_root: string | null;
+  page: string | null;
+}
+
+interface LaunchRequest {
+  project_root: string;
+  file_path: string | null;
+}
+
+interface PurgePlan {
+  targets: { category: string; path: string; size_bytes: number }[];
+  total_bytes: number;
+  dry_run: boolean;
+}
+
+type Tab = "welcome" | "convert" | "record" | "split" | "silence" | "silence-auto" | "report";
 type MenuOpen = "file" | "edit" | null;
 type Theme = "dark" | "light";
 
@@ -36,6 +55,23 @@ const ConvertIcon = () => (
   </svg>
 );
 
+const RecordIcon = () => (
+  <svg
+    width="20"
+    height="20"
+    viewBox="0 0 24 24"
+    fill="none"
+    stroke="currentColor"
+    strokeWidth="2"
+    strokeLinecap="round"
+    strokeLinejoin="round"
+  >
+    <path d="M12 1a3 3 0 0 0-3 3v8a3 3 0 0 0 6 0V4a3 3 0 0 0-3-3z" />
+    <path d="M19 10v2a7 7 0 0 1-14 0v-2" />
+    <line x1="12" y1="19" x2="12" y2="23" />
+  </svg>
+);
+
 const SplitIcon = () => (
   <svg
     width="20"
@@ -92,45 +128,75 @@ const ReportIcon = () => (
 function App() {
   const { language, setLanguage, t } = useI18n();
 
-  const [activeTab, setActiveTab] = useState<Tab>(() => {
-    // Check for query param ?page=welcome
-    const params = new URLSearchParams(window.location.search);
-    if (params.get("page") === "welcome") {
-      return "welcome";
-    }
+  // 新視窗 (?page=welcome) 從歡迎頁開始；其他視窗由後端保存的工作階段恢復
+  const isNewWindow = new URLSearchParams(window.location.search).get("page") === "welcome";
+  const [activeTab, setActiveTab] = useState<Tab>("welcome");
+  const [sessionRestored, setSessionRestored] = useState(isNewWindow);
 
-    // Check localStorage for saved tab
-    const lastTab = localStorage.getItem("app-last-tab");
+  // 從檔案總管或 stt-agent:// 連結開啟的專案與檔案
+  const [launchFile, setLaunchFile] = useState<string | null>(null);
 
-    // User requested: If last closed on Welcome Page -> reopen Welcome Page
-    if (lastTab === "welcome") {
-      return "welcome";
+  const openLaunchRequest = async () => {
+    const request = await invoke<LaunchRequest | null>("take_launch_request");
+    if (!request) {
+      return false;
     }
-
-    // User requested: If last closed in project -> reopen Convert Page (default)
-    // We assume any other tab implies "in project"
-    // Also check if we have a last project to load
-    const lastProject = localStorage.getItem("app-last-project");
-    if (lastProject) {
-      return "convert";
+    await invoke("open_project_cmd", { path: request.project_root });
+    if (request.file_path) {
+      setLaunchFile(request.file_path);
+      setActiveTab("split");
+    } else {
+      setActiveTab("convert");
     }
+    return true;
+  };
 
-    // Fallback
-    return "welcome";
-  });
+  // 恢復此視窗上次的專案與頁面
+  const restoreSession = async () => {
+    const session = await invoke<WindowSession | null>("get_window_session");
+    // 舊版本只保存在 localStorage
+    const lastProject = session ? session.project_root : localStorage.getItem("app-last-project");
+    const lastTab = session ? session.page : localStorage.getItem("app-last-tab");
+    if (!lastProject || lastTab === "welcome") {
+      return;
+    }
+    await invoke("open_project_cmd", { path: lastProject });
+    setActiveTab(lastTab && lastTab !== "welcome" ? (lastTab as Tab) : "convert");
+  };
 
   // Load last project on startup if we are in project mode
   useEffect(() => {
-    const lastProject = localStorage.getItem("app-last-project");
-    if (activeTab !== "welcome" && lastProject) {
-      invoke("open_project_cmd", { path: lastProject }).catch(console.error);
-    }
+    const loadProject = async () => {
+      try {
+        if (await openLaunchRequest().catch(() => false)) {
+          return;
+        }
+        if (!isNewWindow) {
+          await restoreSession();
+        }
+      } catch (err) {
+        console.error("Failed to restore session:", err);
+      } finally {
+        setSessionRestored(true);
+      }
+    };
+    loadProject();
+
+    // 程式已在執行時再次開啟檔案
+    const unlisten = listen("launch://open", () => {
+      openLaunchRequest().catch(console.error);
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
   }, []); // Run once on mount
 
-  // Save activeTab to localStorage whenever it changes
+  // 記錄目前頁面 (恢復完成前不寫入，避免覆蓋上次的頁面)
   useEffect(() => {
-    localStorage.setItem("app-last-tab", activeTab);
-  }, [activeTab]);
+    if (sessionRestored) {
+      invoke("set_window_page", { page: activeTab }).catch(console.error);
+    }
+  }, [activeTab, sessionRestored]);
 
   const [openMenu, setOpenMenu] = useState<MenuOpen>(null);
   const [showAbout, setShowAbout] = useState(false);
@@ -156,6 +222,7 @@ function App() {
     icon: React.ReactNode;
   }[] = [
       { id: "convert", labelKey: "convert", icon: <ConvertIcon /> },
+      { id: "record", labelKey: "record", icon: <RecordIcon /> },
       { id: "split", labelKey: "split", icon: <SplitIcon /> },
       { id: "silence", labelKey: "silence", icon: <SilenceIcon /> },
       { id: "silence-auto", labelKey: "silenceAuto", icon: <SilenceIcon /> },
@@ -191,6 +258,12 @@ function App() {
     return () => document.removeEventListener("mousedown", handleClickOutside);
   }, []);
 
+  // 檢視模式 (唯讀) 開啟的專案：切換頁面時重新確認，顯示提示列
+  const [viewerMode, setViewerMode] = useState(false);
+  useEffect(() => {
+    invoke<boolean>("is_viewer_mode").then(setViewerMode).catch(() => setViewerMode(false));
+  }, [activeTab]);
+
   // 更新字體大小並儲存
   useEffect(() => {
     document.documentElement.style.fontSize = `${fontSize}px`;
@@ -235,15 +308,22 @@ function App() {
 
   const handleUninstall = async () => {
     try {
-      await invoke("uninstall_app");
-    } catch (error) {
-      console.error("Uninstall failed:", error);
-      if (error === "PLATFORM_NOT_SUPPORTED") {
-        alert(
+      // 先列出會被刪除的資料，由使用者決定是否一併清除
+      const plan = await invoke<PurgePlan>("preview_purge");
+      let purgeData = false;
+      if (plan.targets.length > 0) {
+        const list = plan.targets.map((target) => `• ${target.path}`).join("\n");
+        purgeData = confirm(
           language === "zh"
-            ? "Linux 系統請透過套件管理員移除：\n\n終端機執行：\nsudo apt remove stt-agent"
-            : "On Linux, please uninstall via terminal:\n\nsudo apt remove stt-agent",
+            ? `是否一併刪除設定與應用程式資料？\n\n${list}\n\n(專案資料夾不會被刪除)`
+            : `Also delete settings and app data?\n\n${list}\n\n(Project folders are kept)`,
         );
+      }
+      await invoke("uninstall_app", { purgeData });
+    } catch (error) {
+      console.error("Uninstall failed:", error);
+      if (isAppError(error) && error.kind === "unsupported") {
+        alert(formatError(error));
       } else {
         alert(
           language === "zh"
@@ -340,7 +420,7 @@ function App() {
             <div className="dropdown-menu">
               <button className="dropdown-item" onClick={async () => {
                 try {
-                  const selected = await open({ directory: true, multiple: false, title: language === "zh" ? "選擇新建專案位置" : "Select location for new project" });
+                  const selected = await invoke<string | null>("request_path_access", { directory: true, title: language === "zh" ? "選擇新建專案位置" : "Select location for new project" });
                   if (selected && typeof selected === "string") {
                     await invoke("create_project_cmd", { path: selected });
                     saveRecentProject(selected);
@@ -353,7 +433,7 @@ function App() {
               </button>
               <button className="dropdown-item" onClick={async () => {
                 try {
-                  const selected = await open({ directory: true, multiple: false, title: language === "zh" ? "選擇專案資料夾" : "Select project folder" });
+                  const selected = await invoke<string | null>("request_path_access", { directory: true, title: language === "zh" ? "選擇專案資料夾" : "Select project folder" });
                   if (selected && typeof selected === "string") {
                     await invoke("open_project_cmd", { path: selected });
                     saveRecentProject(selected);
@@ -536,6 +616,11 @@ function App() {
         <main className="main-content">
           {/* Content Area - All components stay mounted, hidden with CSS for state persistence */}
           <div className="content-area">
+            {viewerMode && activeTab !== "welcome" && (
+              <div className="viewer-banner" style={{ padding: "6px 12px", marginBottom: "8px", borderRadius: "6px", background: "var(--bg-secondary)", color: "var(--text-secondary)" }}>
+                👁️ {t.viewerModeBanner}
+              </div>
+            )}
             {activeTab === "welcome" && (
               <WelcomePage onProjectOpened={() => setActiveTab("convert")} />
             )}
@@ -544,8 +629,11 @@ function App() {
             >
               <ConvertPage />
             </div>
+            <div style={{ display: activeTab === "record" ? "block" : "none" }}>
+              <RecordPage />
+            </div>
             <div style={{ display: activeTab === "split" ? "block" : "none" }}>
-              <SplitPage />
+              <SplitPage openFile={launchFile} />
             </div>
             <div
               style={{ display: activeTab === "silence" ? "block" : "none" }}
@@ -0,0 +1,78 @@
+import { useEffect, useRef } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { useI18n } from "./i18n";
+
+// 自動保存間隔
+const AUTOSAVE_INTERVAL_MS = 5000;
+
+interface PageState<T> {
+    saved_at: string;
+    state: T;
+}
+
+interface AutosaveFile<T> {
+    pages: Record<string, PageState<T>>;
+}
+
+/**
+ * 每隔幾秒把尚未送出的頁面狀態保存到目前專案 (對應 src-tauri/src/services/autosave.rs)。
+ * 上次異常結束時，開啟頁面會詢問是否恢復。
+ * 回傳的函式在送出 (或放棄) 編輯後呼叫，清除保存的狀態。
+ */
+export function useAutosave<T>(page: string, state: T, dirty: boolean, restore: (state: T) => void) {
+    const { t } = useI18n();
+    const latest = useRef({ state, dirty });
+    latest.current = { state, dirty };
+
+    useEffect(() => {
+        let cancelled = false;
+        (async () => {
+            try {
+                const project = await invoke<string | null>("get_current_project_cmd");
+                if (!project || cancelled) return;
+                const recovery = await invoke<AutosaveFile<T> | null>("get_recovery_state", { project });
+                const saved = recovery?.pages[page];
+                if (!saved || cancelled) return;
+                const when = new Date(saved.saved_at).toLocaleString();
+                if (confirm(t.restoreAutosavePrompt.replace("{time}", when))) {
+                    restore(saved.state);
+                } else {
+                    await invoke("clear_session_state", { project, page });
+                }
+            } catch (e) {
+                console.warn("Failed to read autosave", e);
+            }
+        })();
+        return () => {
+            cancelled = true;
+        };
+        // 只在開啟頁面時檢查一次
+        // eslint-disable-next-line react-hooks/exhaustive-deps
+    }, [page]);
+
+    useEffect(() => {
+        const timer = window.setInterval(async () => {
+            if (!latest.current.dirty) return;
+            try {
+                const project = await invoke<string | null>("get_current_project_cmd");
+                if (project) {
+                    await invoke("save_session_state", { project, page, state: latest.current.state });
+                }
+            } catch (e) {
+                console.warn("Failed to autosave", e);
+            }
+        }, AUTOSAVE_INTERVAL_MS);
+        return () => window.clearInterval(timer);
+    }, [page]);
+
+    return async function clearAutosave() {
+        try {
+            const project = await invoke<string | null>("get_current_project_cmd");
+            if (project) {
+                await invoke("clear_session_state", { project, page });
+            }
+        } catch (e) {
+            console.warn("Failed to clear autosave", e);
+        }
+    };
+}
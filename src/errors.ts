@@ -0,0 +1,55 @@
+import { invoke } from "@tauri-apps/api/core";
+
+// 後端命令回傳的結構化錯誤 (對應 src-tauri/src/models/error.rs)
+export type ErrorKind =
+    | "invalid_input"
+    | "not_found"
+    | "io"
+    | "tool"
+    | "network"
+    | "api"
+    | "insufficient_space"
+    | "cancelled"
+    | "unsupported"
+    | "permission_denied"
+    | "confirmation_required"
+    | "internal";
+
+export interface AppError {
+    kind: ErrorKind;
+    message: string;
+    detail: string | null;
+    recoverable: boolean;
+    message_key: string;
+    // message_key 的參數 (例如段落編號、檔名)
+    params: Record<string, string>;
+}
+
+export function isAppError(err: unknown): err is AppError {
+    return typeof err === "object" && err !== null && "kind" in err && "message" in err;
+}
+
+// 批次過大時後端回傳 confirmation_required (params.token)，使用者確認後帶回確認碼重新呼叫
+export async function invokeConfirmed<T>(command: string, args: Record<string, unknown>): Promise<T> {
+    try {
+        return await invoke<T>(command, args);
+    } catch (err) {
+        if (isAppError(err) && err.kind === "confirmation_required" && confirm(err.message)) {
+            return await invoke<T>(command, { ...args, confirmToken: err.params.token });
+        }
+        throw err;
+    }
+}
+
+// 工作取消，或播放器載入被之後的載入取代 (不需顯示)
+export function isCancelled(err: unknown): boolean {
+    return isAppError(err) && err.kind === "cancelled";
+}
+
+// 將 invoke 拋出的錯誤轉為顯示用文字
+export function formatError(err: unknown): string {
+    if (isAppError(err)) {
+        return err.detail ? `${err.message} (${err.detail})` : err.message;
+    }
+    return String(err);
+}
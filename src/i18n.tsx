@@ -1,4 +1,5 @@
 import { createContext, useContext, useState, useEffect, ReactNode } from "react";
+import { invoke } from "@tauri-apps/api/core";
 
 export type Language = "zh" | "en";
 
@@ -36,6 +37,7 @@ export const translations = {
     split: "切割",
     silence: "消音",
     report: "報告",
+    record: "錄音",
     // About Dialog
     aboutTitle: "關於 會議轉錄助理",
     version: "版本",
@@ -59,6 +61,8 @@ export const translations = {
     filesSelected: "已選擇 {count} 個檔案",
     selectFileError: "選擇檔案錯誤",
     pleaseSelectFile: "請先選擇檔案！",
+    copyToLocalPrompt: "部分檔案位於網路資料夾或唯讀媒體：\n{files}\n\n是否先複製到本機工作資料夾再處理？",
+    copyingToLocal: "正在複製檔案到本機...",
     // SplitPage
     splitTitle: "切割模組",
     splitDescription: "將長錄音切分成小片段，方便後續處理。",
@@ -68,13 +72,57 @@ export const translations = {
     pause: "暫停",
     segmentList: "段落列表",
     addSegment: "新增段落",
+    loadAnnotations: "載入標註",
+    saveAsAnnotations: "存為標註",
+    noRedactAnnotations: "這個音檔沒有遮蔽標註",
+    annotationsLoaded: "已載入 {count} 個遮蔽標註",
+    annotationsSaved: "已將 {count} 個段落存為遮蔽標註",
+    exportAudacityLabels: "將標註匯出為 Audacity 標籤軌",
+    exportReaperRegions: "將標註匯出為 Reaper 區段 CSV",
+    timelineExported: "已匯出時間軸: {path}",
+    importTimeline: "匯入時間軸",
+    topicChapters: "依主題分章",
+    topicChaptersHint: "選擇逐字稿 JSON，由 Gemini 依討論主題提出章節並放入段落清單",
+    proposingChapters: "Gemini 分析逐字稿中...",
+    chaptersProposed: "已依主題提出 {count} 個章節，確認後按切割",
+    batchTranscribeStarted: "整批上傳 02_split 至 STT 伺服器...",
+    timelineFiles: "Audacity 標籤 / 字幕",
+    timelineImported: "已匯入 {count} 個段落 ({adjusted} 個重疊段落已調整)",
+    timelineImportedMerged: "已匯入 {count} 個段落 ({merged} 個重疊段落已合併)",
     segmentName: "段落名稱",
     startTime: "開始時間",
     endTime: "結束時間",
     action: "操作",
     exampleName: "例如：個案1",
     runSplit: "執行切割",
+    transcribeOnSplit: "切割時同時轉錄",
+    errorNoSttServer: "尚未連線過 STT 伺服器，請先在「消音(AI)」頁連線",
     splitting: "執行中...",
+    chaptersFound: "此音檔內含 {count} 個章節",
+    splitByChapters: "依章節切割",
+    denoise: "降噪",
+    enhanceSpeech: "人聲強化",
+    enhanceDone: "人聲強化完成",
+    compareOriginal: "原始",
+    compareProcessed: "處理後",
+    restoreAutosavePrompt: "偵測到上次未正常結束，是否恢復 {time} 自動保存的編輯內容？",
+    channelsMono: "單聲道",
+    channelsStereo: "立體聲",
+    loadedTrackMissing: "先前載入的音檔已不存在，請重新載入",
+    trackGrowing: "寫入中",
+    exportSelection: "匯出選取範圍",
+    exportingSelection: "正在匯出選取範圍...",
+    selectionExported: "已匯出選取範圍",
+    monitorOutput: "同時輸出到",
+    monitorOff: "不使用",
+    dictateNote: "口述註記",
+    stopDictation: "停止口述",
+    dictationSaved: "口述註記已儲存",
+    pushToTalk: "按住說話",
+    pushToTalkHint: "按住錄製口述筆記，放開後轉錄並附加到專案的 notes.md",
+    noteRecording: "錄音中，放開結束",
+    noteTranscribing: "口述筆記轉錄中...",
+    noteSaved: "已附加到口述筆記",
     deleteSegment: "刪除段落",
     needAtLeastOneSegment: "至少需要一個段落",
     errorLoadAudio: "請先載入音訊檔案",
@@ -86,9 +134,48 @@ export const translations = {
     silenceAudioPlayer: "音訊播放器", // New Key
     selectAudioFolder: "選擇資料夾", // New Key
     changeFolder: "更改資料夾", // New Key
+    moveToTrash: "移到資源回收筒",
+    openReadOnly: "以檢視模式開啟 (唯讀)",
+    viewerModeHint: "審閱共用磁碟上的專案時使用：可播放、檢視逐字稿與閱讀報告，但無法修改任何檔案",
+    viewerModeBanner: "檢視模式：此專案為唯讀，無法修改",
+    deleteFileConfirm: "確定要將 {file} 移到資源回收筒嗎？",
+    fileMovedToTrash: "已將 {file} 移到資源回收筒",
     runDetection: "執行消音處理",
     detecting: "處理中...",
     segmentNote: "段落備註 (選填)", // New Key
+    // RecordPage
+    recordTitle: "錄音",
+    recordDescription: "直接錄下對話，錄音檔存到目前專案的 01_converted",
+    recordDevice: "錄音裝置",
+    recordFormat: "檔案格式",
+    recordStart: "開始錄音",
+    recordPause: "暫停",
+    recordResume: "繼續",
+    recordStop: "停止並儲存",
+    recordPaused: "已暫停",
+    recordSystemAudio: "系統音訊 (視訊看診對方的聲音)",
+    recordSystemOff: "不錄製",
+    recordSystemMix: "與麥克風混音",
+    recordSystemSeparate: "另存成獨立檔案",
+    recordVad: "只在偵測到語音時錄音 (略過靜音並記錄位置)",
+    recordVadThreshold: "音量門檻",
+    recordVadSilence: "靜音中，未寫入",
+    recordAgc: "自動增益 (遠處的聲音放大、過大的聲音壓低不爆音)",
+    recordKeepRaw: "另存未處理的原始錄音",
+    recordMaxDuration: "最長錄音時間 (分鐘，0 為不限制)",
+    recordRemaining: "剩餘",
+    recordSchedule: "預約錄音 (程式需保持開啟，重新啟動後仍有效)",
+    recordScheduleAdd: "預約",
+    recordScheduleCancel: "取消預約",
+    recordMinutes: "分鐘",
+    recordNoProject: "請先開啟或建立專案",
+    subtitlesExported: "字幕已匯出",
+    diarize: "區分說話者",
+    qualityWarning: "錄音品質警告",
+    qualityUnusable: "錄音可能無法使用",
+    duplicateRecording: "疑似重複的錄音",
+    recordingSaving: "正在儲存錄音...",
+    recordingSaved: "錄音已儲存",
     // SilenceAutoPage
     silenceAuto: "消音(AI)",
     silenceAutoTitle: "自動消音與人名識別",
@@ -122,6 +209,10 @@ export const translations = {
     hide: "隱藏",
     customPrompt: "自定義 Prompt (選填，.txt)",
     customPromptPlaceholder: "預設使用內建 Prompt，可選 .txt 覆蓋...",
+    projectInfo: "專案資訊 (專案名稱 / 錄音日期 / 科別)",
+    projectInfoHint: "Prompt 中的 {project_name}、{recording_date}、{department} 會代入這些值；未填時使用資料夾名稱與最早的轉檔日期",
+    saveProjectInfo: "儲存",
+    projectInfoSaved: "專案資訊已儲存",
     selectPrompt: "選擇 Prompt",
     generateReport: "生成報告 (自動產出 Word 檔)",
     generating: "生成中...",
@@ -134,6 +225,15 @@ export const translations = {
     errorSelectFolder: "請先選擇音檔資料夾",
     errorSelectReport: "請先選擇報告檔案",
     processingReport: "正在處理音檔並生成報告，這可能需要幾分鐘...",
+    uploadingFile: "上傳中",
+    queueReport: "排入佇列",
+    purgeRemoteFiles: "清理雲端遺留檔案",
+    remoteFilesPurged: "已刪除 {deleted} 個雲端檔案，保留 {kept} 個 (可能是進行中的報告)",
+    exportReportHtml: "匯出 HTML",
+    reportHtmlExported: "已匯出 HTML 報告: {path}",
+    reportQueuedStarted: "報告已排入工作佇列",
+    reportQueuedOffline: "目前沒有網路，報告已保存，恢復連線後會自動開始",
+    offlineStatus: "離線中 (等待中的報告: {count})",
     convertingToDocx: "正在轉換為 DOCX...",
     selectModel: "選擇模型 (Model)",
     defaultSuffix: "(預設)",
@@ -173,6 +273,7 @@ export const translations = {
     split: "Split",
     silence: "Silence",
     report: "Report",
+    record: "Record",
     // About Dialog
     aboutTitle: "About Meeting Transcription Assistant",
     version: "Version",
@@ -196,6 +297,8 @@ export const translations = {
     filesSelected: "{count} file(s) selected",
     selectFileError: "File selection error",
     pleaseSelectFile: "Please select files first!",
+    copyToLocalPrompt: "Some files are on a network share or read-only medium:\n{files}\n\nCopy them to a local working folder before processing?",
+    copyingToLocal: "Copying files to this computer...",
     // SplitPage
     splitTitle: "Split Module",
     splitDescription: "Split long recordings into smaller segments for easier processing.",
@@ -205,13 +308,57 @@ export const translations = {
     pause: "Pause",
     segmentList: "Segment List",
     addSegment: "Add Segment",
+    loadAnnotations: "Load Annotations",
+    saveAsAnnotations: "Save as Annotations",
+    noRedactAnnotations: "This audio file has no redact annotations",
+    annotationsLoaded: "Loaded {count} redact annotations",
+    annotationsSaved: "Saved {count} segments as redact annotations",
+    exportAudacityLabels: "Export annotations as an Audacity label track",
+    exportReaperRegions: "Export annotations as Reaper region CSV",
+    timelineExported: "Timeline exported: {path}",
+    importTimeline: "Import Timeline",
+    topicChapters: "Chapter by Topic",
+    topicChaptersHint: "Pick a transcript JSON; Gemini proposes topic chapters and fills the segment list",
+    proposingChapters: "Gemini is analyzing the transcript...",
+    chaptersProposed: "Proposed {count} topic chapters; review them and press Split",
+    batchTranscribeStarted: "Uploading 02_split to the STT server in one batch...",
+    timelineFiles: "Audacity labels / subtitles",
+    timelineImported: "Imported {count} segments ({adjusted} overlapping segments adjusted)",
+    timelineImportedMerged: "Imported {count} segments ({merged} overlapping segments merged)",
     segmentName: "Segment Name",
     startTime: "Start Time",
     endTime: "End Time",
     action: "Action",
     exampleName: "e.g., Case 1",
     runSplit: "Run Split",
+    transcribeOnSplit: "Transcribe while splitting",
+    errorNoSttServer: "No STT server has been connected yet. Connect one on the Silence(AI) page first",
     splitting: "Processing...",
+    chaptersFound: "This file contains {count} chapters",
+    splitByChapters: "Split by Chapters",
+    denoise: "Reduce Noise",
+    enhanceSpeech: "Enhance Speech",
+    enhanceDone: "Speech enhanced",
+    compareOriginal: "Original",
+    compareProcessed: "Processed",
+    restoreAutosavePrompt: "The app did not close normally last time. Restore the edits autosaved at {time}?",
+    channelsMono: "Mono",
+    channelsStereo: "Stereo",
+    loadedTrackMissing: "The previously loaded file no longer exists. Please load it again.",
+    trackGrowing: "Still being written",
+    exportSelection: "Export selection",
+    exportingSelection: "Exporting selection...",
+    selectionExported: "Selection exported",
+    monitorOutput: "Also play on",
+    monitorOff: "Off",
+    dictateNote: "Dictate note",
+    stopDictation: "Stop dictating",
+    dictationSaved: "Dictation saved",
+    pushToTalk: "Push to talk",
+    pushToTalkHint: "Hold to record a voice note; release to transcribe it into the project's notes.md",
+    noteRecording: "Recording, release to finish",
+    noteTranscribing: "Transcribing note...",
+    noteSaved: "Added to notes",
     deleteSegment: "Delete Segment",
     needAtLeastOneSegment: "At least one segment required",
     errorLoadAudio: "Please load an audio file first",
@@ -223,9 +370,48 @@ export const translations = {
     silenceAudioPlayer: "Audio Player",
     selectAudioFolder: "Select Folder",
     changeFolder: "Change Folder",
+    moveToTrash: "Move to Trash",
+    openReadOnly: "Open in viewer mode (read-only)",
+    viewerModeHint: "For reviewing projects on a shared drive: playback, transcripts and reports work, but no files can be changed",
+    viewerModeBanner: "Viewer mode: this project is read-only",
+    deleteFileConfirm: "Move {file} to the trash?",
+    fileMovedToTrash: "Moved {file} to the trash",
     runDetection: "Run Silence Processor",
     detecting: "Processing...",
     segmentNote: "Segment Note (Optional)",
+    // RecordPage
+    recordTitle: "Record",
+    recordDescription: "Record the conversation directly; files are saved to the current project's 01_converted",
+    recordDevice: "Input device",
+    recordFormat: "File format",
+    recordStart: "Start recording",
+    recordPause: "Pause",
+    recordResume: "Resume",
+    recordStop: "Stop and save",
+    recordPaused: "Paused",
+    recordSystemAudio: "System audio (remote side of video calls)",
+    recordSystemOff: "Don't record",
+    recordSystemMix: "Mix with microphone",
+    recordSystemSeparate: "Save as a separate file",
+    recordVad: "Only record when voice is detected (skip silence and mark gaps)",
+    recordVadThreshold: "Level threshold",
+    recordVadSilence: "silent, not writing",
+    recordAgc: "Automatic gain control (lift distant voices, limit loud peaks)",
+    recordKeepRaw: "Also keep the unprocessed recording",
+    recordMaxDuration: "Maximum duration (minutes, 0 for no limit)",
+    recordRemaining: "remaining",
+    recordSchedule: "Scheduled recording (keep the app running; survives restarts)",
+    recordScheduleAdd: "Schedule",
+    recordScheduleCancel: "Cancel",
+    recordMinutes: "min",
+    recordNoProject: "Open or create a project first",
+    subtitlesExported: "Subtitles exported",
+    diarize: "Identify speakers",
+    qualityWarning: "Recording quality warning",
+    qualityUnusable: "Recording may be unusable",
+    duplicateRecording: "Possible duplicate recording",
+    recordingSaving: "Saving recording...",
+    recordingSaved: "Recording saved",
     // SilenceAutoPage
     silenceAuto: "Silence(AI)",
     silenceAutoTitle: "Auto Silence & NER",
@@ -259,6 +445,10 @@ export const translations = {
     hide: "Hide",
     customPrompt: "Custom Prompt (optional, .txt)",
     customPromptPlaceholder: "Uses default prompt, select .txt to override...",
+    projectInfo: "Project info (name / recording date / department)",
+    projectInfoHint: "{project_name}, {recording_date} and {department} in prompts are replaced with these values; blanks fall back to the folder name and the earliest conversion date",
+    saveProjectInfo: "Save",
+    projectInfoSaved: "Project info saved",
     selectPrompt: "Select Prompt",
     generateReport: "Generate Report (auto-creates Word file)",
     generating: "Generating...",
@@ -271,6 +461,15 @@ export const translations = {
     errorSelectFolder: "Please select an audio folder first",
     errorSelectReport: "Please select a report file first",
     processingReport: "Processing audio and generating report, this may take a few minutes...",
+    uploadingFile: "Uploading",
+    queueReport: "Queue",
+    purgeRemoteFiles: "Clean up cloud files",
+    remoteFilesPurged: "Deleted {deleted} cloud files, kept {kept} (possibly reports in progress)",
+    exportReportHtml: "Export HTML",
+    reportHtmlExported: "Exported HTML report: {path}",
+    reportQueuedStarted: "The report was added to the job queue",
+    reportQueuedOffline: "No network right now; the report was saved and will start automatically when back online",
+    offlineStatus: "Offline (reports waiting: {count})",
     convertingToDocx: "Converting to DOCX...",
     selectModel: "Select Model",
     defaultSuffix: "(Default)",
@@ -297,6 +496,14 @@ export function I18nProvider({ children }: { children: ReactNode }) {
 
   useEffect(() => {
     localStorage.setItem("app-language", language);
+    // 同步到後端設定，讓後端回傳的訊息使用相同語言
+    invoke<Record<string, unknown>>("get_settings")
+      .then((config) => {
+        if (config.language !== language) {
+          return invoke("update_settings", { config: { ...config, language } });
+        }
+      })
+      .catch((err) => console.error("Failed to sync language setting:", err));
   }, [language]);
 
   const t = translations[language];
@@ -1,7 +1,45 @@
-import { useState } from "react";
+import { useEffect, useState } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { getCurrentWebviewWindow } from "@tauri-apps/api/webviewWindow";
 import { open } from "@tauri-apps/plugin-dialog";
 import { useI18n } from "../i18n";
+import { AppError, formatError, invokeConfirmed } from "../errors";
+
+// 後端拖放處理結果 (對應 src-tauri/src/services/ingest.rs)
+interface IngestResult {
+    job_id: string | null;
+    project_root: string | null;
+    accepted: string[];
+    rejected: { path: string; reason: string }[];
+    created: string[];
+    flagged: QualityReport[];
+    duplicates: DuplicatePair[];
+    error: AppError | null;
+}
+
+// 錄音品質檢查 (對應 src-tauri/src/services/quality.rs)
+interface QualityReport {
+    path: string;
+    usable: boolean;
+    issues: { kind: string; severe: boolean; message: string }[];
+}
+
+// 疑似重複的錄音 (對應 src-tauri/src/services/fingerprint.rs)
+interface DuplicatePair {
+    a: string;
+    b: string;
+    similarity: number;
+    offset: number;
+    overlap: number;
+}
+
+// 位置檢查結果 (對應 src-tauri/src/services/volume.rs)
+interface LocationStatus {
+    path: string;
+    reachable: boolean;
+    writable: boolean;
+    network: boolean;
+}
 
 export function ConvertPage() {
     const { t, language } = useI18n();
@@ -9,6 +47,33 @@ export function ConvertPage() {
     const [output, setOutput] = useState("");
     const [loading, setLoading] = useState(false);
 
+    // 拖放的檔案由後端直接排入轉檔，這裡只顯示結果
+    useEffect(() => {
+        const unlisten = getCurrentWebviewWindow().listen<IngestResult>("drop://ingested", (event) => {
+            const { created, rejected, flagged, duplicates, error } = event.payload;
+            const lines = [
+                ...created.map((path) => `✓ ${path}`),
+                ...rejected.map((file) => `✗ ${file.path} - ${file.reason}`),
+                ...flagged.map(
+                    (report) =>
+                        `⚠ ${report.path} - ${report.usable ? t.qualityWarning : t.qualityUnusable}: ${report.issues
+                            .map((issue) => issue.message)
+                            .join(", ")}`
+                ),
+                ...duplicates.map(
+                    (pair) => `⚠ ${t.duplicateRecording}: ${pair.a} ↔ ${pair.b} (${Math.round(pair.similarity * 100)}%)`
+                ),
+            ];
+            if (error) {
+                lines.push(`${t.error}: ${formatError(error)}`);
+            }
+            setOutput(lines.join("\n"));
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [t]);
+
     async function selectFiles() {
         try {
             const currentProject = await invoke<string | null>("get_current_project_cmd");
@@ -18,7 +83,7 @@ export function ConvertPage() {
                 filters: [
                     {
                         name: language === "zh" ? "影音檔案" : "Audio/Video Files",
-                        extensions: ["mp4", "mkv", "avi", "mov", "wmv", "flv", "webm", "mp3", "wav", "flac", "aac", "ogg", "m4a", "wma"],
+                        extensions: ["mp4", "mkv", "avi", "mov", "wmv", "flv", "webm", "mp3", "wav", "flac", "aac", "ogg", "m4a", "m4b", "wma", "amr", "dss", "ds2"],
                     },
                     {
                         name: language === "zh" ? "所有檔案" : "All Files",
@@ -33,8 +98,23 @@ export function ConvertPage() {
                 setOutput(t.filesSelected.replace("{count}", fileList.length.toString()));
             }
         } catch (err) {
-            setOutput(`${t.selectFileError}: ${err}`);
+            setOutput(`${t.selectFileError}: ${formatError(err)}`);
+        }
+    }
+
+    // 網路資料夾或唯讀媒體上的檔案，詢問是否先複製到本機工作資料夾
+    async function localizeInputs(files: string[]): Promise<string[]> {
+        const statuses = await invoke<LocationStatus[]>("check_locations", { paths: files });
+        const risky = statuses.filter((s) => s.network || !s.writable).map((s) => s.path);
+        if (risky.length === 0 || !confirm(t.copyToLocalPrompt.replace("{files}", risky.join("\n")))) {
+            return files;
         }
+        setOutput(t.copyingToLocal);
+        const copied = await invoke<string[]>("copy_inputs_to_local", { filePaths: risky });
+        return files.map((file) => {
+            const index = risky.indexOf(file);
+            return index >= 0 ? copied[index] : file;
+        });
     }
 
     async function runConvert() {
@@ -44,15 +124,16 @@ export function ConvertPage() {
         }
 
         setLoading(true);
-        setOutput(t.converting);
 
         try {
-            const result = await invoke("convert_files_to_mp3", {
-                filePaths: selectedFiles,
+            const filePaths = await localizeInputs(selectedFiles);
+            setOutput(t.converting);
+            const result = await invokeConfirmed("convert_files_to_mp3", {
+                filePaths,
             });
             setOutput(result as string);
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
         } finally {
             setLoading(false);
         }
@@ -0,0 +1,426 @@
+import { useEffect, useState } from "react";
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+import { useI18n } from "../i18n";
+import { formatError } from "../errors";
+
+// 對應 src-tauri/src/services/recorder.rs
+type RecordingFormat = "wav" | "mp3";
+// 對應 src-tauri/src/services/loopback.rs
+type SystemAudioMode = "off" | "mix" | "separate";
+
+interface VadOptions {
+    threshold: number;
+    hangover_ms: number;
+}
+
+// 對應 src-tauri/src/services/agc.rs
+interface AgcOptions {
+    target_db: number;
+    max_gain_db: number;
+    keep_raw: boolean;
+}
+
+interface RecordingOptions {
+    device: string | null;
+    format: RecordingFormat;
+    system_audio: SystemAudioMode;
+    vad: VadOptions | null;
+    agc: AgcOptions | null;
+    max_duration: number | null;
+}
+
+// 對應 src-tauri/src/services/recording_schedule.rs
+interface ScheduledRecording {
+    id: string;
+    start_time: string;
+    duration: number;
+    project: string;
+    options: RecordingOptions;
+}
+
+interface InputDevice {
+    name: string;
+    is_default: boolean;
+    sample_rate: number;
+    channels: number;
+}
+
+interface RecordingStatus {
+    recording: boolean;
+    paused: boolean;
+    elapsed: number;
+    device: string | null;
+    path: string | null;
+    format: RecordingFormat;
+    system_audio: SystemAudioMode;
+    vad: VadOptions | null;
+    agc: AgcOptions | null;
+    remaining: number | null;
+}
+
+interface LevelEvent {
+    rms: number;
+    peak: number;
+    elapsed: number;
+    paused: boolean;
+    voice: boolean;
+}
+
+interface RecordingResult {
+    path: string;
+    system_path: string | null;
+    markers_path: string | null;
+    raw_path: string | null;
+    duration: number;
+    format: RecordingFormat;
+}
+
+function formatDuration(seconds: number): string {
+    const total = Math.floor(seconds);
+    const h = Math.floor(total / 3600);
+    const m = Math.floor((total % 3600) / 60);
+    const s = total % 60;
+    return [h, m, s].map((n) => n.toString().padStart(2, "0")).join(":");
+}
+
+export function RecordPage() {
+    const { t } = useI18n();
+    const [devices, setDevices] = useState<InputDevice[]>([]);
+    const [device, setDevice] = useState<string>("");
+    const [format, setFormat] = useState<RecordingFormat>("wav");
+    const [systemAudio, setSystemAudio] = useState<SystemAudioMode>("off");
+    const [systemSupported, setSystemSupported] = useState(false);
+    const [vadEnabled, setVadEnabled] = useState(false);
+    const [vadThreshold, setVadThreshold] = useState(0.02);
+    const [agcEnabled, setAgcEnabled] = useState(false);
+    const [keepRaw, setKeepRaw] = useState(false);
+    // 分鐘，0 表示不限制
+    const [maxMinutes, setMaxMinutes] = useState(0);
+    const [scheduleStart, setScheduleStart] = useState("");
+    const [scheduleMinutes, setScheduleMinutes] = useState(180);
+    const [schedules, setSchedules] = useState<ScheduledRecording[]>([]);
+    const [status, setStatus] = useState<RecordingStatus | null>(null);
+    const [level, setLevel] = useState<LevelEvent | null>(null);
+    const [output, setOutput] = useState("");
+    const [busy, setBusy] = useState(false);
+
+    useEffect(() => {
+        refreshDevices();
+        invoke<boolean>("is_system_audio_supported").then(setSystemSupported).catch(console.error);
+        // 切換頁面或重新開啟視窗時恢復錄音狀態
+        invoke<RecordingStatus>("get_recording_status").then(setStatus).catch(console.error);
+        refreshSchedules();
+
+        const unlisten = listen<LevelEvent>("recorder://level", (event) => {
+            setLevel(event.payload);
+        });
+        // 預約錄音開始 / 到達時間自動停止
+        const unlistenStarted = listen<RecordingStatus>("recorder://started", (event) => {
+            setStatus(event.payload);
+            refreshSchedules();
+        });
+        const unlistenStopped = listen<RecordingResult>("recorder://stopped", (event) => {
+            setStatus(null);
+            setLevel(null);
+            showResult(event.payload);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+            unlistenStarted.then((fn) => fn());
+            unlistenStopped.then((fn) => fn());
+        };
+    }, []);
+
+    async function refreshDevices() {
+        try {
+            const list = await invoke<InputDevice[]>("list_input_devices");
+            setDevices(list);
+            const preferred = list.find((d) => d.is_default) ?? list[0];
+            setDevice((current) => current || preferred?.name || "");
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    function refreshSchedules() {
+        invoke<ScheduledRecording[]>("list_scheduled_recordings").then(setSchedules).catch(console.error);
+    }
+
+    function buildOptions(maxDuration: number | null): RecordingOptions {
+        return {
+            device: device || null,
+            format,
+            system_audio: systemAudio,
+            vad: vadEnabled ? { threshold: vadThreshold, hangover_ms: 1500 } : null,
+            agc: agcEnabled ? { target_db: -20, max_gain_db: 24, keep_raw: keepRaw } : null,
+            max_duration: maxDuration,
+        };
+    }
+
+    function showResult(result: RecordingResult) {
+        const files = [result.path, result.system_path, result.raw_path, result.markers_path].filter(Boolean).join("\n");
+        setOutput(`${t.recordingSaved} (${formatDuration(result.duration)}):\n${files}`);
+    }
+
+    async function run(action: () => Promise<void>) {
+        setBusy(true);
+        try {
+            await action();
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        } finally {
+            setBusy(false);
+        }
+    }
+
+    const start = () =>
+        run(async () => {
+            const next = await invoke<RecordingStatus>("start_recording", {
+                options: buildOptions(maxMinutes > 0 ? maxMinutes * 60 : null),
+            });
+            setStatus(next);
+            setOutput("");
+        });
+
+    const togglePause = () =>
+        run(async () => {
+            const next = await invoke<RecordingStatus>(
+                status?.paused ? "resume_recording" : "pause_recording"
+            );
+            setStatus(next);
+        });
+
+    const stop = () =>
+        run(async () => {
+            setOutput(t.recordingSaving);
+            const result = await invoke<RecordingResult>("stop_recording");
+            setStatus(null);
+            setLevel(null);
+            showResult(result);
+        });
+
+    const schedule = () =>
+        run(async () => {
+            const project = await invoke<string | null>("get_current_project_cmd");
+            if (!project) {
+                setOutput(`${t.error}: ${t.recordNoProject}`);
+                return;
+            }
+            await invoke<ScheduledRecording>("schedule_recording", {
+                startTime: scheduleStart,
+                duration: scheduleMinutes * 60,
+                project,
+                options: buildOptions(null),
+            });
+            setScheduleStart("");
+            refreshSchedules();
+        });
+
+    const cancelSchedule = (id: string) =>
+        run(async () => {
+            await invoke("cancel_scheduled_recording", { id });
+            refreshSchedules();
+        });
+
+    const recording = status?.recording ?? false;
+    const elapsed = level?.elapsed ?? status?.elapsed ?? 0;
+
+    return (
+        <div className="page-container">
+            <header className="page-header">
+                <h2 className="page-title">🎙 {t.recordTitle}</h2>
+                <p className="page-description">{t.recordDescription}</p>
+            </header>
+
+            <div className="input-group" style={{ marginBottom: "20px" }}>
+                <label className="input-label">{t.recordDevice}</label>
+                <div style={{ display: "flex", gap: "10px" }}>
+                    <select
+                        className="custom-file-select"
+                        value={device}
+                        onChange={(e) => setDevice(e.target.value)}
+                        disabled={recording}
+                    >
+                        {devices.map((d) => (
+                            <option key={d.name} value={d.name}>
+                                {d.name} ({d.sample_rate} Hz / {d.channels}ch)
+                            </option>
+                        ))}
+                    </select>
+                    <button className="btn btn-secondary" onClick={refreshDevices} disabled={recording}>
+                        ⟳
+                    </button>
+                </div>
+            </div>
+
+            <div className="input-group" style={{ marginBottom: "20px" }}>
+                <label className="input-label">{t.recordFormat}</label>
+                <select
+                    className="custom-file-select"
+                    value={format}
+                    onChange={(e) => setFormat(e.target.value as RecordingFormat)}
+                    disabled={recording}
+                >
+                    <option value="wav">WAV</option>
+                    <option value="mp3">MP3</option>
+                </select>
+            </div>
+
+            {systemSupported && (
+                <div className="input-group" style={{ marginBottom: "20px" }}>
+                    <label className="input-label">{t.recordSystemAudio}</label>
+                    <select
+                        className="custom-file-select"
+                        value={systemAudio}
+                        onChange={(e) => setSystemAudio(e.target.value as SystemAudioMode)}
+                        disabled={recording}
+                    >
+                        <option value="off">{t.recordSystemOff}</option>
+                        <option value="mix">{t.recordSystemMix}</option>
+                        <option value="separate">{t.recordSystemSeparate}</option>
+                    </select>
+                </div>
+            )}
+
+            <div className="input-group" style={{ marginBottom: "20px" }}>
+                <label className="input-label">
+                    <input
+                        type="checkbox"
+                        checked={vadEnabled}
+                        onChange={(e) => setVadEnabled(e.target.checked)}
+                        disabled={recording}
+                    />{" "}
+                    {t.recordVad}
+                </label>
+                {vadEnabled && (
+                    <div style={{ display: "flex", gap: "10px", alignItems: "center" }}>
+                        <span>{t.recordVadThreshold}</span>
+                        <input
+                            type="range"
+                            min={0.005}
+                            max={0.2}
+                            step={0.005}
+                            value={vadThreshold}
+                            onChange={(e) => setVadThreshold(Number(e.target.value))}
+                            disabled={recording}
+                        />
+                        <span>{vadThreshold.toFixed(3)}</span>
+                    </div>
+                )}
+            </div>
+
+            <div className="input-group" style={{ marginBottom: "20px" }}>
+                <label className="input-label">
+                    <input
+                        type="checkbox"
+                        checked={agcEnabled}
+                        onChange={(e) => setAgcEnabled(e.target.checked)}
+                        disabled={recording}
+                    />{" "}
+                    {t.recordAgc}
+                </label>
+                {agcEnabled && (
+                    <label className="input-label">
+                        <input
+                            type="checkbox"
+                            checked={keepRaw}
+                            onChange={(e) => setKeepRaw(e.target.checked)}
+                            disabled={recording}
+                        />{" "}
+                        {t.recordKeepRaw}
+                    </label>
+                )}
+            </div>
+
+            <div className="input-group" style={{ marginBottom: "20px" }}>
+                <label className="input-label">{t.recordMaxDuration}</label>
+                <input
+                    type="number"
+                    className="input"
+                    min={0}
+                    value={maxMinutes}
+                    onChange={(e) => setMaxMinutes(Math.max(0, Number(e.target.value)))}
+                    disabled={recording}
+                />
+            </div>
+
+            <div className="input-group" style={{ marginBottom: "20px" }}>
+                <div style={{ fontSize: "2em", fontVariantNumeric: "tabular-nums" }}>
+                    {formatDuration(elapsed)}
+                    {status?.paused && ` (${t.recordPaused})`}
+                    {status?.remaining != null && ` · ${t.recordRemaining} ${formatDuration(status.remaining)}`}
+                    {status?.vad && !status.paused && level && !level.voice && ` (${t.recordVadSilence})`}
+                </div>
+                <div style={{ height: "8px", background: "var(--bg-secondary)", borderRadius: "4px" }}>
+                    <div
+                        style={{
+                            width: `${Math.min(100, (level?.peak ?? 0) * 100)}%`,
+                            height: "100%",
+                            background: "var(--accent)",
+                            borderRadius: "4px",
+                            transition: "width 0.1s linear",
+                        }}
+                    />
+                </div>
+            </div>
+
+            <div className="btn-group">
+                {!recording ? (
+                    <button className="btn btn-primary" onClick={start} disabled={busy || devices.length === 0}>
+                        ● {t.recordStart}
+                    </button>
+                ) : (
+                    <>
+                        <button className="btn btn-secondary" onClick={togglePause} disabled={busy}>
+                            {status?.paused ? t.recordResume : t.recordPause}
+                        </button>
+                        <button className="btn btn-primary" onClick={stop} disabled={busy}>
+                            ■ {t.recordStop}
+                        </button>
+                    </>
+                )}
+            </div>
+
+            <div className="input-group mt-4">
+                <label className="input-label">{t.recordSchedule}</label>
+                <div style={{ display: "flex", gap: "10px", alignItems: "center" }}>
+                    <input
+                        type="datetime-local"
+                        className="input"
+                        value={scheduleStart}
+                        onChange={(e) => setScheduleStart(e.target.value)}
+                    />
+                    <input
+                        type="number"
+                        className="input"
+                        min={1}
+                        value={scheduleMinutes}
+                        onChange={(e) => setScheduleMinutes(Math.max(1, Number(e.target.value)))}
+                        style={{ width: "100px" }}
+                    />
+                    <span>{t.recordMinutes}</span>
+                    <button className="btn btn-secondary" onClick={schedule} disabled={busy || !scheduleStart}>
+                        {t.recordScheduleAdd}
+                    </button>
+                </div>
+                {schedules.map((s) => (
+                    <div key={s.id} style={{ display: "flex", gap: "10px", alignItems: "center", marginTop: "8px" }}>
+                        <span>
+                            {new Date(s.start_time).toLocaleString()} · {Math.round(s.duration / 60)} {t.recordMinutes} · {s.project}
+                        </span>
+                        <button className="btn btn-secondary" onClick={() => cancelSchedule(s.id)} disabled={busy}>
+                            {t.recordScheduleCancel}
+                        </button>
+                    </div>
+                ))}
+            </div>
+
+            {output && (
+                <div className={`output-box mt-4 fade-in-up ${output.includes(t.error) ? "error" : ""}`}>
+                    {output}
+                </div>
+            )}
+        </div>
+    );
+}
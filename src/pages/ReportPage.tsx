@@ -1,7 +1,33 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 import { open } from "@tauri-apps/plugin-dialog";
 import { useI18n } from "../i18n";
+import { formatError, invokeConfirmed } from "../errors";
+import { useAutosave } from "../autosave";
+
+interface UploadProgressEvent {
+    job_id: string;
+    file: string;
+    uploaded: number;
+    total: number;
+}
+
+type QueueOutcome =
+    | { status: "started"; job_id: string }
+    | { status: "waiting"; id: string };
+
+interface NetworkStatus {
+    online: boolean;
+    pending: number;
+}
+
+// 專案資訊 (提示詞中的 {project_name}、{recording_date}、{department})
+interface ProjectInfo {
+    project_name?: string | null;
+    recording_date?: string | null;
+    department?: string | null;
+}
 
 interface ReportPageProps {
     isActive?: boolean;
@@ -14,6 +40,8 @@ export function ReportPage({ isActive }: ReportPageProps) {
     const [folderPath, setFolderPath] = useState("");
     const [output, setOutput] = useState("");
     const [loading, setLoading] = useState(false);
+    const [upload, setUpload] = useState<UploadProgressEvent | null>(null);
+    const [network, setNetwork] = useState<NetworkStatus | null>(null);
     // const [converting, setConverting] = useState(false);
     // const [reportPath, setReportPath] = useState("");
     const [modelName, setModelName] = useState("gemini-3.1-pro-preview");
@@ -21,6 +49,85 @@ export function ReportPage({ isActive }: ReportPageProps) {
     const [showPromptModal, setShowPromptModal] = useState(false);
     const [defaultPrompt, setDefaultPrompt] = useState("");
     const [modalTitle, setModalTitle] = useState("");
+    const [projectRoot, setProjectRoot] = useState<string | null>(null);
+    const [projectInfo, setProjectInfo] = useState<ProjectInfo>({});
+
+    // 上傳進度 (大檔分段上傳，網路中斷時會自動接續)
+    useEffect(() => {
+        const unlisten = listen<UploadProgressEvent>("report://upload-progress", (event) => {
+            setUpload(event.payload.uploaded >= event.payload.total ? null : event.payload);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    // 切換到此頁時重新讀取目前專案的資訊
+    useEffect(() => {
+        if (!isActive) return;
+        invoke<string | null>("get_current_project_cmd")
+            .then(async (root) => {
+                setProjectRoot(root);
+                setProjectInfo(root ? await invoke<ProjectInfo>("get_project_info", { root }) : {});
+            })
+            .catch(console.error);
+    }, [isActive]);
+
+    async function saveProjectInfo() {
+        if (!projectRoot) return;
+        try {
+            setProjectInfo(await invoke<ProjectInfo>("set_project_info", { root: projectRoot, info: projectInfo }));
+            setOutput(t.projectInfoSaved);
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    // 網路狀態 (離線時報告可排入佇列，恢復連線後自動開始)
+    useEffect(() => {
+        invoke<NetworkStatus>("get_network_status").then(setNetwork).catch(() => {});
+        const unlisten = listen<NetworkStatus>("network://status", (event) => {
+            setNetwork(event.payload);
+        });
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
+    // 尚未送出的報告選項自動保存 (不含 API Key)，異常結束後可恢復
+    const clearAutosave = useAutosave(
+        "report",
+        { folderPath, modelName, customPromptPath },
+        folderPath !== "",
+        (saved) => {
+            setFolderPath(saved.folderPath);
+            setModelName(saved.modelName);
+            setCustomPromptPath(saved.customPromptPath);
+        }
+    );
+
+    async function queueReport() {
+        if (!apiKey) {
+            setOutput(`${t.error}: ${t.errorApiKey}`);
+            return;
+        }
+        if (!folderPath) {
+            setOutput(`${t.error}: ${t.errorSelectFolder}`);
+            return;
+        }
+        try {
+            const outcome = await invoke<QueueOutcome>("queue_report", {
+                apiKey,
+                folderPath,
+                modelName,
+                customPromptPath: customPromptPath || null,
+            });
+            setOutput(outcome.status === "started" ? t.reportQueuedStarted : t.reportQueuedOffline);
+            clearAutosave();
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
 
     // 選擇資料夾
     async function handleSelectFolder() {
@@ -37,7 +144,7 @@ export function ReportPage({ isActive }: ReportPageProps) {
                 localStorage.setItem("latest_report_folder", selected);
             }
         } catch (err) {
-            setOutput(`${t.selectFileError}: ${err}`);
+            setOutput(`${t.selectFileError}: ${formatError(err)}`);
         }
     }
 
@@ -54,7 +161,7 @@ export function ReportPage({ isActive }: ReportPageProps) {
                 setCustomPromptPath(selected);
             }
         } catch (err) {
-            setOutput(`${t.selectFileError}: ${err}`);
+            setOutput(`${t.selectFileError}: ${formatError(err)}`);
         }
     }
 
@@ -88,7 +195,7 @@ export function ReportPage({ isActive }: ReportPageProps) {
             setShowPromptModal(true);
         } catch (err) {
             console.error("Failed to get default prompt:", err);
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
         }
     }
 
@@ -108,13 +215,14 @@ export function ReportPage({ isActive }: ReportPageProps) {
         // setReportPath("");
 
         try {
-            const result = await invoke("generate_report", {
+            const result = await invokeConfirmed("generate_report", {
                 apiKey,
                 folderPath,
                 modelName,
                 customPromptPath: customPromptPath || null,
             });
             setOutput(result as string);
+            clearAutosave();
 
             // 從結果中提取報告路徑
             const match = (result as string).match(/輸出位置: (.+)/);
@@ -122,7 +230,52 @@ export function ReportPage({ isActive }: ReportPageProps) {
                 // setReportPath(match[1]);
             }
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
+        } finally {
+            setLoading(false);
+            setUpload(null);
+        }
+    }
+
+    // 清理 Gemini File API 上失敗流程遺留的音檔 (否則要等 48 小時才自動刪除)
+    async function purgeRemoteFiles() {
+        if (!apiKey) {
+            setOutput(`${t.error}: ${t.errorApiKey}`);
+            return;
+        }
+        setLoading(true);
+        try {
+            const result = await invoke<{ deleted: string[]; failed: [string, string][]; kept: number }>(
+                "purge_remote_files",
+                { apiKey },
+            );
+            const lines = [
+                t.remoteFilesPurged
+                    .replace("{deleted}", String(result.deleted.length))
+                    .replace("{kept}", String(result.kept)),
+                ...result.failed.map(([name, err]) => `${name}: ${err}`),
+            ];
+            setOutput(lines.join("\n"));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        } finally {
+            setLoading(false);
+        }
+    }
+
+    // 匯出 HTML 報告 (段落可點擊播放對應音檔)
+    async function exportHtml() {
+        setLoading(true);
+        try {
+            const project = await invoke<string | null>("get_current_project_cmd");
+            if (!project) {
+                setOutput(`${t.error}: ${t.recordNoProject}`);
+                return;
+            }
+            const path = await invoke<string>("export_report_html", { project });
+            setOutput(t.reportHtmlExported.replace("{path}", path));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
         } finally {
             setLoading(false);
         }
@@ -142,7 +295,7 @@ export function ReportPage({ isActive }: ReportPageProps) {
                 setReportPath(selected);
             }
         } catch (err) {
-            setOutput(`${t.selectFileError}: ${err}`);
+            setOutput(`${t.selectFileError}: ${formatError(err)}`);
         }
     }
     */
@@ -164,7 +317,7 @@ export function ReportPage({ isActive }: ReportPageProps) {
             });
             setOutput(result as string);
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
         } finally {
             setConverting(false);
         }
@@ -291,6 +444,30 @@ export function ReportPage({ isActive }: ReportPageProps) {
                 </div>
             </div>
 
+            {/* 專案資訊 (提示詞模板變數) */}
+            {projectRoot && (
+                <div className="input-group" style={{ marginBottom: "20px" }}>
+                    <label className="input-label">{t.projectInfo}</label>
+                    <div style={{ display: "flex", gap: "10px", alignItems: "center", flexWrap: "wrap" }}>
+                        {(["project_name", "recording_date", "department"] as const).map((field) => (
+                            <input
+                                key={field}
+                                type="text"
+                                className="input"
+                                value={projectInfo[field] ?? ""}
+                                placeholder={`{${field}}`}
+                                onChange={(e) => setProjectInfo({ ...projectInfo, [field]: e.target.value || null })}
+                                style={{ width: "180px" }}
+                            />
+                        ))}
+                        <button className="btn btn-secondary" onClick={saveProjectInfo}>
+                            💾 {t.saveProjectInfo}
+                        </button>
+                    </div>
+                    <small style={{ color: "#888" }}>{t.projectInfoHint}</small>
+                </div>
+            )}
+
             {/* 生成按鈕 */}
             <div className="btn-group" style={{ marginBottom: "30px" }}>
                 <button
@@ -301,6 +478,25 @@ export function ReportPage({ isActive }: ReportPageProps) {
                     {loading && <span className="loading-spinner"></span>}
                     {loading ? t.generating : `🚀 ${t.generateReport}`}
                 </button>
+                <button className="btn" onClick={queueReport} disabled={loading}>
+                    📥 {t.queueReport}
+                </button>
+                <button className="btn" onClick={purgeRemoteFiles} disabled={loading}>
+                    🧹 {t.purgeRemoteFiles}
+                </button>
+                <button className="btn" onClick={exportHtml} disabled={loading}>
+                    🌐 {t.exportReportHtml}
+                </button>
+                {network && !network.online && (
+                    <span style={{ alignSelf: "center", color: "#e0a030" }}>
+                        {t.offlineStatus.replace("{count}", String(network.pending))}
+                    </span>
+                )}
+                {loading && upload && (
+                    <span style={{ alignSelf: "center", color: "#888" }}>
+                        {t.uploadingFile} {upload.file} {Math.floor((upload.uploaded / upload.total) * 100)}%
+                    </span>
+                )}
             </div>
 
             {/* 暫時隱藏手動工具功能
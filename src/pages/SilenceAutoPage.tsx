@@ -1,7 +1,7 @@
 import { useState, useEffect, useRef } from 'react';
 import { invoke } from '@tauri-apps/api/core';
-import { open } from '@tauri-apps/plugin-dialog';
 import { useI18n } from '../i18n';
+import { formatError, isCancelled } from '../errors';
 
 interface Segment {
     start: number;
@@ -10,6 +10,7 @@ interface Segment {
     name: string;
     start_idx?: number;
     end_idx?: number;
+    speaker?: string;
 }
 
 interface PlaybackState {
@@ -23,6 +24,7 @@ interface TranscribeResponse {
     duration: number;
     segments: Segment[];
     full_text: string;
+    speakers?: Record<string, string>;
 }
 
 export function SilenceAutoPage() {
@@ -31,6 +33,7 @@ export function SilenceAutoPage() {
     const [history, setHistory] = useState<string[]>([]);
     const [isConnected, setIsConnected] = useState<boolean | null>(null); // null=unknown, true=connected, false=disconnected
     const [isConnecting, setIsConnecting] = useState(false);
+    const [diarize, setDiarize] = useState(false);
 
     const [logs, setLogs] = useState<string[]>([]);
     const logsContainerRef = useRef<HTMLDivElement>(null);
@@ -106,8 +109,7 @@ export function SilenceAutoPage() {
             const jsonPath = await getJsonPath(fullPath);
             const exists = await invoke<boolean>("check_file_exists", { path: jsonPath });
             if (exists) {
-                const content = await invoke<string>("read_text_file", { path: jsonPath });
-                const data = JSON.parse(content);
+                const data = await invoke<TranscribeResponse>("read_json_file", { path: jsonPath });
                 setResults(data);
                 addToLog(`${t.loaded}: Transcript found.`);
             } else {
@@ -121,6 +123,22 @@ export function SilenceAutoPage() {
     }
 
 
+    async function handleExportSubtitles(format: "srt" | "vtt") {
+        if (!folderPath || !selectedFile) return;
+        try {
+            const fullPath = `${folderPath}/${selectedFile}`.replace(/\\/g, "/");
+            const jsonPath = await getJsonPath(fullPath);
+            const output = await invoke<string>("export_subtitles", {
+                transcriptJson: jsonPath,
+                format,
+                speakerPrefix: results?.segments.some((s) => s.speaker) ?? false,
+            });
+            addToLog(`${t.subtitlesExported}: ${output}`);
+        } catch (err) {
+            addToLog(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
     async function handleSilenceSelected() {
         if (selectedIndices.size === 0 || !results || !folderPath || !selectedFile) return;
 
@@ -149,7 +167,7 @@ export function SilenceAutoPage() {
             // Optionally reload to reflect changes? (Not requested but good practice if file changed)
             // handleLoadTrack(folderPath, selectedFile); 
         } catch (err) {
-            addToLog(`${t.error}: ${err}`);
+            addToLog(`${t.error}: ${formatError(err)}`);
         }
     }
 
@@ -300,13 +318,13 @@ export function SilenceAutoPage() {
                 defaultPath = `${currentProject}${separator}02_split`;
             }
 
-            const selected = await open({
+            // 由後端開啟對話框，選擇的資料夾才能讀寫逐字稿 JSON
+            const selected = await invoke<string | null>("request_path_access", {
                 directory: true,
-                multiple: false,
                 defaultPath,
             });
 
-            if (selected && typeof selected === "string") {
+            if (selected) {
                 setBatchFolder(selected);
                 setFolderPath(selected); // Keep this for player compatibility? Or separate? 
                 // User wants player dropdown to only show selected files.
@@ -318,7 +336,7 @@ export function SilenceAutoPage() {
                 setBatchProgress({});
             }
         } catch (err) {
-            addToLog(`${t.error}: ${err}`);
+            addToLog(`${t.error}: ${formatError(err)}`);
         }
     }
 
@@ -337,6 +355,35 @@ export function SilenceAutoPage() {
         filesToProcess.forEach(f => initialProgress[f] = 'pending');
         setBatchProgress(initialProgress);
 
+        // 整個 02_split 都要轉錄時交給後端一次處理 (同時上傳、失敗重試、已完成的略過)
+        const wholeSplitFolder = /[\\/]02_split[\\/]?$/.test(batchFolder)
+            && filesToProcess.length === batchFiles.length;
+        if (wholeSplitFolder) {
+            filesToProcess.forEach(f => initialProgress[f] = 'processing');
+            setBatchProgress({ ...initialProgress });
+            try {
+                addToLog(t.batchTranscribeStarted);
+                const message = await invoke<string>("transcribe_split_folder", { ip, diarize });
+                addToLog(message);
+                const failed = new Set(
+                    message.split("\n")
+                        .filter(line => line.startsWith("⚠️ "))
+                        .map(line => line.slice(3).split(": ")[0])
+                );
+                filesToProcess.forEach(f => initialProgress[f] = failed.has(f) ? 'error' : 'done');
+                setBatchProgress({ ...initialProgress });
+            } catch (e) {
+                filesToProcess.forEach(f => initialProgress[f] = 'error');
+                setBatchProgress({ ...initialProgress });
+                addToLog(`${t.error}: ${formatError(e)}`);
+            }
+            setIsBatchRunning(false);
+            if (filesToProcess.length > 0 && !isPlaying && !selectedFile) {
+                handleFileChange(filesToProcess[0]);
+            }
+            return;
+        }
+
         for (const filename of filesToProcess) {
             // Check if cancelled? (Optional)
 
@@ -349,19 +396,17 @@ export function SilenceAutoPage() {
 
                 const res: TranscribeResponse = await invoke('transcribe_audio', {
                     ip: ip,
-                    filePath: fullPath
+                    filePath: fullPath,
+                    diarize
                 });
 
                 // Save JSON
                 const jsonPath = await getJsonPath(fullPath);
 
-                // Ensure directory exists
-                const jsonDir = jsonPath.substring(0, jsonPath.lastIndexOf("/"));
-                await invoke("ensure_dir_exists", { path: jsonDir });
-
-                await invoke('save_text_file', {
+                // Atomic write, parent directory is created by the backend
+                await invoke('write_json_file', {
                     path: jsonPath,
-                    content: JSON.stringify(res, null, 2)
+                    value: res
                 });
 
                 setBatchProgress(prev => ({ ...prev, [filename]: 'done' }));
@@ -370,7 +415,7 @@ export function SilenceAutoPage() {
             } catch (e) {
                 console.error(e);
                 setBatchProgress(prev => ({ ...prev, [filename]: 'error' }));
-                addToLog(`Error ${filename}: ${e}`);
+                addToLog(`Error ${filename}: ${formatError(e)}`);
             }
         }
         setIsBatchRunning(false);
@@ -428,7 +473,7 @@ export function SilenceAutoPage() {
             setIsPlaying(false);
             addToLog(`${t.loaded}: ${filename}`);
         } catch (err) {
-            addToLog(`${t.error}: ${err}`);
+            if (!isCancelled(err)) addToLog(`${t.error}: ${formatError(err)}`);
         }
     }
 
@@ -442,7 +487,7 @@ export function SilenceAutoPage() {
                 setIsPlaying(true);
             }
         } catch (err) {
-            addToLog(`${t.error}: ${err}`);
+            addToLog(`${t.error}: ${formatError(err)}`);
         }
     }
 
@@ -576,7 +621,7 @@ export function SilenceAutoPage() {
             setShowHistory(false); // Close dropdown on connect attempt
         } catch (e) {
             setIsConnected(false);
-            addToLog(`${t.error}: ${e}`);
+            addToLog(`${t.error}: ${formatError(e)}`);
         } finally {
             setIsConnecting(false);
         }
@@ -710,6 +755,11 @@ export function SilenceAutoPage() {
                     </button>
                 )}
 
+                <label style={{ display: 'flex', alignItems: 'center', gap: '5px', whiteSpace: 'nowrap' }}>
+                    <input type="checkbox" checked={diarize} onChange={(e) => setDiarize(e.target.checked)} />
+                    {t.diarize}
+                </label>
+
                 {/* Status Light */}
                 <div style={{
                     width: '20px',
@@ -1001,6 +1051,20 @@ export function SilenceAutoPage() {
 
                         {/* Silence Controls */}
                         <div style={{ display: 'flex', gap: '10px' }}>
+                            <button
+                                className="btn btn-secondary"
+                                style={{ padding: '5px 10px', fontSize: '0.9em' }}
+                                onClick={() => handleExportSubtitles("srt")}
+                            >
+                                SRT
+                            </button>
+                            <button
+                                className="btn btn-secondary"
+                                style={{ padding: '5px 10px', fontSize: '0.9em' }}
+                                onClick={() => handleExportSubtitles("vtt")}
+                            >
+                                VTT
+                            </button>
                             <button
                                 className="btn btn-secondary"
                                 style={{ padding: '5px 10px', fontSize: '0.9em' }}
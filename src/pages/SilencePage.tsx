@@ -2,6 +2,8 @@ import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
 import { open } from "@tauri-apps/plugin-dialog";
 import { useI18n } from "../i18n";
+import { formatError, isCancelled } from "../errors";
+import { useAutosave } from "../autosave";
 
 interface PlaybackState {
     position: number;
@@ -63,6 +65,9 @@ function formatTimeString(input: string): string {
     return formattedInt;
 }
 
+/** 波形的區間數 (約等於進度列寬度的像素數) */
+const WAVEFORM_BUCKETS = 800;
+
 export function SilencePage() {
     const { t } = useI18n();
     const [output, setOutput] = useState("");
@@ -79,6 +84,7 @@ export function SilencePage() {
     const [currentTime, setCurrentTime] = useState(0);
     const [isLoaded, setIsLoaded] = useState(false);
     const [isSeeking, setIsSeeking] = useState(false);
+    const [waveform, setWaveform] = useState<[number, number][]>([]);
 
     // Segment List State
     const [segments, setSegments] = useState<Segment[]>([
@@ -167,6 +173,28 @@ export function SilencePage() {
         return () => window.removeEventListener("keydown", handleKeyDown);
     }, [isLoaded, currentTime, duration, isPlaying]);
 
+    // 尚未執行的消音時段自動保存，異常結束後可恢復
+    const clearAutosave = useAutosave(
+        "silence",
+        { folderPath, selectedFile, segments, nextId },
+        segments.some((s) => s.note || s.startTime || s.endTime),
+        async (saved) => {
+            if (!saved.folderPath || !saved.selectedFile) return;
+            setFolderPath(saved.folderPath);
+            try {
+                setFileList(await invoke<string[]>("list_audio_files", { dirPath: saved.folderPath }));
+            } catch (err) {
+                setOutput(`${t.error}: ${formatError(err)}`);
+                return;
+            }
+            setSelectedFile(saved.selectedFile);
+            // 載入音檔會重設段落，載入後再恢復
+            await handleLoadTrack(saved.folderPath, saved.selectedFile);
+            setSegments(saved.segments);
+            setNextId(saved.nextId);
+        }
+    );
+
     async function handleSelectFolder() {
         try {
             let defaultPath = "02_split";
@@ -188,7 +216,7 @@ export function SilencePage() {
                 loadFileList(selected);
             }
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
         }
     }
 
@@ -206,7 +234,7 @@ export function SilencePage() {
                 setOutput(`${t.loaded}: ${path} (No audio files found)`);
             }
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
         } finally {
             setLoading(false);
         }
@@ -217,6 +245,29 @@ export function SilencePage() {
         handleLoadTrack(folderPath, filename);
     }
 
+    async function handleDeleteFile() {
+        if (!folderPath || !selectedFile) return;
+        if (!confirm(t.deleteFileConfirm.replace("{file}", selectedFile))) return;
+        try {
+            if (isPlaying) {
+                await invoke("pause");
+                setIsPlaying(false);
+            }
+            const result = await invoke<{ deleted: string[]; failed: string[] }>("delete_files", {
+                paths: [`${folderPath}/${selectedFile}`.replace(/\\/g, "/")],
+                toTrash: true,
+            });
+            if (result.failed.length > 0) {
+                setOutput(`${t.error}: ${result.failed.join("\n")}`);
+                return;
+            }
+            setOutput(t.fileMovedToTrash.replace("{file}", selectedFile));
+            await loadFileList(folderPath);
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
     async function handleLoadTrack(folder: string, filename: string) {
         if (!folder || !filename) return;
         const fullPath = `${folder}/${filename}`.replace(/\\/g, "/");
@@ -237,10 +288,17 @@ export function SilencePage() {
             setIsPlaying(false);
             setOutput(`${t.loaded}: ${filename}`);
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            if (isCancelled(err)) return;
+            setOutput(`${t.error}: ${formatError(err)}`);
         } finally {
             setLoading(false);
         }
+
+        // 波形不影響播放，讀取失敗時只是不顯示
+        setWaveform([]);
+        invoke<{ peaks: [number, number][] }>("get_waveform", { path: fullPath, buckets: WAVEFORM_BUCKETS })
+            .then((w) => setWaveform(w.peaks))
+            .catch(() => { });
     }
 
     async function handlePlayPause() {
@@ -253,7 +311,7 @@ export function SilencePage() {
                 setIsPlaying(true);
             }
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
         }
     }
 
@@ -358,6 +416,91 @@ export function SilencePage() {
         ));
     }
 
+    // 專案標註：遮蔽類標註與消音段落互通 (切割與報告也使用同一份標註)
+    function parseTime(value: string): number {
+        return value.split(":").reduce((acc, part) => acc * 60 + parseFloat(part || "0"), 0);
+    }
+
+    async function loadAnnotations() {
+        if (!selectedFile || !folderPath) return;
+        try {
+            const fullPath = `${folderPath}/${selectedFile}`.replace(/\\/g, "/");
+            const annotations = await invoke<{ start: number; end: number; category: string; note: string }[]>(
+                "list_annotations",
+                { file: fullPath }
+            );
+            const redact = annotations.filter((a) => a.category === "redact");
+            if (redact.length === 0) {
+                setOutput(t.noRedactAnnotations);
+                return;
+            }
+            setSegments(redact.map((a, i) => ({
+                id: nextId + i,
+                note: a.note,
+                startTime: formatTime(a.start),
+                endTime: formatTime(a.end),
+            })));
+            setNextId(nextId + redact.length);
+            setOutput(t.annotationsLoaded.replace("{count}", String(redact.length)));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    async function saveAnnotations() {
+        if (!selectedFile || !folderPath) return;
+        const validSegments = segments.filter(s => s.startTime && s.endTime);
+        try {
+            const fullPath = `${folderPath}/${selectedFile}`.replace(/\\/g, "/");
+            for (const s of validSegments) {
+                await invoke("add_annotation", {
+                    annotation: {
+                        file: fullPath,
+                        start: parseTime(s.startTime),
+                        end: parseTime(s.endTime),
+                        category: "redact",
+                        note: s.note,
+                    },
+                });
+            }
+            setOutput(t.annotationsSaved.replace("{count}", String(validSegments.length)));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    async function importTimeline() {
+        try {
+            const selected = await open({
+                multiple: false,
+                filters: [{ name: t.timelineFiles, extensions: ["txt", "srt", "vtt"] }],
+            });
+            if (!selected || typeof selected !== "string") return;
+            const imported = await invoke<{
+                silence_segments: { note: string | null; startTime: string; endTime: string }[];
+                merged: number;
+            }>("import_timeline", { path: selected });
+            setSegments(imported.silence_segments.map((s, i) => ({ ...s, id: nextId + i, note: s.note ?? "" })));
+            setNextId(nextId + imported.silence_segments.length);
+            setOutput(t.timelineImportedMerged
+                .replace("{count}", String(imported.silence_segments.length))
+                .replace("{merged}", String(imported.merged)));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    async function exportTimeline(format: "audacity_labels" | "reaper_regions") {
+        if (!selectedFile || !folderPath) return;
+        try {
+            const fullPath = `${folderPath}/${selectedFile}`.replace(/\\/g, "/");
+            const output = await invoke<string>("export_timeline", { audioPath: fullPath, format });
+            setOutput(t.timelineExported.replace("{path}", output));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
     async function runSilence() {
         setLoading(true);
         setOutput(t.detecting);
@@ -394,6 +537,7 @@ export function SilencePage() {
                     }))
                 });
                 setOutput(result as string);
+                clearAutosave();
             } else {
                 // If no valid manual segments, maybe fall back to old behavior? 
                 // But prompt implies manual mode is the new focus.
@@ -410,7 +554,7 @@ export function SilencePage() {
                 }
             }
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
         } finally {
             setLoading(false);
         }
@@ -459,6 +603,11 @@ export function SilencePage() {
                                     ))}
                                 </select>
                             )}
+                            {folderPath && selectedFile && (
+                                <button className="btn btn-secondary" onClick={handleDeleteFile} title={t.moveToTrash}>
+                                    🗑️
+                                </button>
+                            )}
                         </div>
                     </div>
 
@@ -498,6 +647,21 @@ export function SilencePage() {
                                             </div>
                                         )}
 
+                                        {/* Waveform */}
+                                        {waveform.length > 0 && (
+                                            <svg
+                                                className="waveform"
+                                                viewBox={`0 -1 ${waveform.length} 2`}
+                                                preserveAspectRatio="none"
+                                            >
+                                                <path
+                                                    d={waveform
+                                                        .map(([min, max], i) => `M${i} ${-max}V${-min}`)
+                                                        .join("")}
+                                                />
+                                            </svg>
+                                        )}
+
                                         {/* Mark Point 1 (Yellow) */}
                                         {markPoint1 !== null && duration > 0 && (
                                             <div
@@ -610,13 +774,56 @@ export function SilencePage() {
             <div className="segment-table-section" style={{ marginTop: "24px", marginBottom: "24px" }}>
                 <div style={{ display: "flex", justifyContent: "space-between", alignItems: "center", marginBottom: "12px" }}>
                     <h3 style={{ margin: 0 }}>📋 {t.segmentList}</h3>
-                    <button
-                        onClick={addSegment}
-                        className="btn btn-secondary"
-                        style={{ display: "flex", alignItems: "center", gap: "6px", padding: "6px 12px" }}
-                    >
-                        ➕ {t.addSegment}
-                    </button>
+                    <div style={{ display: "flex", gap: "8px" }}>
+                        <button
+                            onClick={addSegment}
+                            className="btn btn-secondary"
+                            style={{ display: "flex", alignItems: "center", gap: "6px", padding: "6px 12px" }}
+                        >
+                            ➕ {t.addSegment}
+                        </button>
+                        <button
+                            onClick={importTimeline}
+                            className="btn btn-secondary"
+                            style={{ display: "flex", alignItems: "center", gap: "6px", padding: "6px 12px" }}
+                        >
+                            📄 {t.importTimeline}
+                        </button>
+                        <button
+                            onClick={loadAnnotations}
+                            className="btn btn-secondary"
+                            disabled={!isLoaded}
+                            style={{ display: "flex", alignItems: "center", gap: "6px", padding: "6px 12px" }}
+                        >
+                            📥 {t.loadAnnotations}
+                        </button>
+                        <button
+                            onClick={saveAnnotations}
+                            className="btn btn-secondary"
+                            disabled={!isLoaded}
+                            style={{ display: "flex", alignItems: "center", gap: "6px", padding: "6px 12px" }}
+                        >
+                            🏷️ {t.saveAsAnnotations}
+                        </button>
+                        <button
+                            onClick={() => exportTimeline("audacity_labels")}
+                            className="btn btn-secondary"
+                            disabled={!isLoaded}
+                            title={t.exportAudacityLabels}
+                            style={{ display: "flex", alignItems: "center", gap: "6px", padding: "6px 12px" }}
+                        >
+                            📤 Audacity
+                        </button>
+                        <button
+                            onClick={() => exportTimeline("reaper_regions")}
+                            className="btn btn-secondary"
+                            disabled={!isLoaded}
+                            title={t.exportReaperRegions}
+                            style={{ display: "flex", alignItems: "center", gap: "6px", padding: "6px 12px" }}
+                        >
+                            📤 Reaper
+                        </button>
+                    </div>
                 </div>
 
                 <div className="table-container" style={{ marginTop: '12px' }}>
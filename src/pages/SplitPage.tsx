@@ -1,12 +1,43 @@
 import { useState, useEffect, useRef } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
+import { listen } from "@tauri-apps/api/event";
+import { open, save } from "@tauri-apps/plugin-dialog";
 import { useI18n } from "../i18n";
+import { formatError, isCancelled } from "../errors";
+import { useAutosave } from "../autosave";
 
 interface PlaybackState {
     position: number;
+    position_ms: number;
+    // 已解碼但尚未播出的長度 (毫秒)
+    buffered_ms: number;
     duration: number;
     is_playing: boolean;
+    state: "idle" | "loaded" | "playing" | "stopped";
+}
+
+// 目前載入音檔的格式資訊
+interface TrackInfo {
+    path: string;
+    exists: boolean;
+    codec: string;
+    sample_rate: number;
+    channels: number;
+    bitrate_kbps: number | null;
+    duration: number;
+    growing: boolean;
+}
+
+// 播放輸出設定 (第二輸出裝置與各自音量)
+interface OutputConfig {
+    monitor_device: string | null;
+    main_volume: number;
+    monitor_volume: number;
+}
+
+interface OutputDevice {
+    name: string;
+    is_default: boolean;
 }
 
 // 段落資料結構
@@ -17,6 +48,14 @@ interface Segment {
     endTime: string;   // HH:MM:SS 格式
 }
 
+// 音檔內含的章節 (m4b、mkv 等)
+interface Chapter {
+    index: number;
+    title: string;
+    start: number;
+    end: number;
+}
+
 // 自動格式化時間輸入：01 -> 01, 0112 -> 01:12, 011223 -> 01:12:23
 function formatTimeString(input: string): string {
     // 移除所有非數字字元
@@ -42,7 +81,12 @@ function formatTimeString(input: string): string {
     }
 }
 
-export function SplitPage() {
+interface SplitPageProps {
+    // 從檔案總管開啟的檔案，載入到播放器
+    openFile?: string | null;
+}
+
+export function SplitPage({ openFile }: SplitPageProps) {
     const { t } = useI18n();
     const [output, setOutput] = useState("");
     const [loading, setLoading] = useState(false);
@@ -54,6 +98,20 @@ export function SplitPage() {
     const [isLoaded, setIsLoaded] = useState(false);
     const [isSeeking, setIsSeeking] = useState(false);
     const [audioFilePath, setAudioFilePath] = useState(""); // 音檔路徑
+    const [chapters, setChapters] = useState<Chapter[]>([]);
+    const [trackInfo, setTrackInfo] = useState<TrackInfo | null>(null);
+    const [outputDevices, setOutputDevices] = useState<OutputDevice[]>([]);
+    const [outputConfig, setOutputConfig] = useState<OutputConfig | null>(null);
+    const [dictating, setDictating] = useState(false);
+    // 按住說話的口述筆記 (轉錄後附加到專案的 notes.md)
+    const [noteRecording, setNoteRecording] = useState(false);
+    const noteStart = useRef<Promise<boolean> | null>(null);
+    // 切割時同時轉錄 (使用最近連線的 STT 伺服器)
+    const [transcribeOnSplit, setTranscribeOnSplit] = useState(false);
+    // 依主題分章使用的 Gemini API Key (不保存)
+    const [chapterApiKey, setChapterApiKey] = useState("");
+    // 比較模式：人聲強化後可切換試聽處理前後
+    const [compareSide, setCompareSide] = useState<"original" | "processed" | null>(null);
 
     // 段落列表狀態
     const [segments, setSegments] = useState<Segment[]>([
@@ -72,6 +130,20 @@ export function SplitPage() {
 
     const positionIntervalRef = useRef<number | null>(null);
 
+    // 尚未切割的段落自動保存，異常結束後可恢復
+    const clearAutosave = useAutosave(
+        "split",
+        { audioFilePath, segments, nextId },
+        segments.some((s) => s.name || s.startTime || s.endTime),
+        async (saved) => {
+            setSegments(saved.segments);
+            setNextId(saved.nextId);
+            if (saved.audioFilePath) {
+                await loadTrack(saved.audioFilePath);
+            }
+        }
+    );
+
     // Workaround for Numpad double input bug (can happen on Windows webview2)
     const lastKeyRef = useRef<{ key: string, time: number }>({ key: '', time: 0 });
     const handleNumpadWorkaround = (e: React.KeyboardEvent<HTMLInputElement>) => {
@@ -89,12 +161,22 @@ export function SplitPage() {
     useEffect(() => {
         async function syncWithBackend() {
             try {
+                // 切換專案後先前載入的檔案可能已不存在
+                const info = await invoke<TrackInfo | null>("get_loaded_track_info");
+                if (info && !info.exists) {
+                    setOutput(t.loadedTrackMissing);
+                    return;
+                }
                 const state = await invoke<PlaybackState>("get_playback_state");
                 if (state.duration > 0) {
                     setDuration(state.duration);
                     setCurrentTime(state.position);
                     setIsPlaying(state.is_playing);
                     setIsLoaded(true);
+                    if (info) {
+                        setTrackInfo(info);
+                        setAudioFilePath((current) => current || info.path);
+                    }
                 }
             } catch (err) {
                 // No audio loaded yet, that's fine
@@ -110,7 +192,7 @@ export function SplitPage() {
             positionIntervalRef.current = window.setInterval(async () => {
                 try {
                     const state = await invoke<PlaybackState>("get_playback_state");
-                    setCurrentTime(state.position);
+                    setCurrentTime(state.position_ms / 1000);
                     setIsPlaying(state.is_playing);
                 } catch (err) {
                     console.error("Failed to get playback state:", err);
@@ -162,6 +244,24 @@ export function SplitPage() {
                     e.preventDefault();
                     handlePlayPause();
                     break;
+
+                // 逐格移動 (每格 10 ms，按住 Shift 一次 10 格)，用於精確設定消音邊界
+                case ",":
+                case ".":
+                case "<":
+                case ">":
+                    e.preventDefault();
+                    try {
+                        const direction = e.key === "," || e.key === "<" ? -1 : 1;
+                        const state = await invoke<PlaybackState>("step_frames", {
+                            frames: direction * (e.shiftKey ? 10 : 1),
+                        });
+                        setCurrentTime(state.position_ms / 1000);
+                        setIsPlaying(state.is_playing);
+                    } catch (err) {
+                        console.error("Step error:", err);
+                    }
+                    break;
             }
         };
 
@@ -184,32 +284,59 @@ export function SplitPage() {
                 filters: [
                     {
                         name: "Audio Files",
-                        extensions: ["mp3", "wav", "flac", "m4a", "aac", "ogg"],
+                        extensions: ["mp3", "wav", "flac", "m4a", "m4b", "aac", "ogg", "mkv", "wma", "amr", "pcm", "raw"],
                     },
                 ],
             });
 
             if (selected && typeof selected === "string") {
-                setLoading(true);
-                setOutput(t.loading);
-
-                const durationStr = await invoke<string>("load_track", { path: selected });
-                const dur = parseFloat(durationStr);
-
-                setDuration(dur);
-                setCurrentTime(0);
-                setIsLoaded(true);
-                setIsPlaying(false);
-                setAudioFilePath(selected); // 儲存音檔路徑
-                setOutput(`${t.loaded}: ${selected.split(/[/\\]/).pop()}`);
+                await loadTrack(selected);
             }
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    async function loadTrack(path: string) {
+        try {
+            setLoading(true);
+            setOutput(t.loading);
+
+            const durationStr = await invoke<string>("load_track", { path });
+            const dur = parseFloat(durationStr);
+
+            setDuration(dur);
+            setCurrentTime(0);
+            setIsLoaded(true);
+            setIsPlaying(false);
+            setAudioFilePath(path); // 儲存音檔路徑
+            setCompareSide(null);
+            setOutput(`${t.loaded}: ${path.split(/[/\\]/).pop()}`);
+
+            setTrackInfo(null);
+            invoke<TrackInfo | null>("get_loaded_track_info")
+                .then(setTrackInfo)
+                .catch((e) => console.warn("Failed to read track info", e));
+
+            // 讀取章節失敗不影響載入
+            setChapters([]);
+            invoke<Chapter[]>("get_audio_chapters", { audioPath: path })
+                .then(setChapters)
+                .catch((e) => console.warn("Failed to read chapters", e));
+        } catch (err) {
+            if (isCancelled(err)) return;
+            setOutput(`${t.error}: ${formatError(err)}`);
         } finally {
             setLoading(false);
         }
     }
 
+    useEffect(() => {
+        if (openFile) {
+            loadTrack(openFile);
+        }
+    }, [openFile]);
+
     // Play/Pause toggle
     async function handlePlayPause() {
         try {
@@ -221,7 +348,7 @@ export function SplitPage() {
                 setIsPlaying(true);
             }
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
         }
     }
 
@@ -231,11 +358,29 @@ export function SplitPage() {
             await invoke("seek", { seconds });
             setCurrentTime(seconds);
         } catch (err) {
-            setOutput(`Seek ${t.error}: ${err}`);
+            setOutput(`Seek ${t.error}: ${formatError(err)}`);
         }
     }
 
     // Format time as HH:MM:SS
+    // 例如 MP3 · 44.1 kHz · 立體聲 · 128 kbps
+    function formatTrackInfo(info: TrackInfo): string {
+        const parts: string[] = [];
+        if (info.codec) parts.push(info.codec.toUpperCase());
+        if (info.sample_rate) parts.push(`${info.sample_rate / 1000} kHz`);
+        if (info.channels === 1) parts.push(t.channelsMono);
+        else if (info.channels === 2) parts.push(t.channelsStereo);
+        else if (info.channels > 2) parts.push(`${info.channels} ch`);
+        if (info.bitrate_kbps) parts.push(`${info.bitrate_kbps} kbps`);
+        return parts.join(" · ");
+    }
+
+    // HH:MM:SS.mmm，用於目前播放位置
+    function formatTimePrecise(seconds: number): string {
+        const ms = Math.floor((seconds % 1) * 1000);
+        return `${formatTime(seconds)}.${ms.toString().padStart(3, "0")}`;
+    }
+
     function formatTime(seconds: number): string {
         const hours = Math.floor(seconds / 3600);
         const mins = Math.floor((seconds % 3600) / 60);
@@ -263,25 +408,126 @@ export function SplitPage() {
         const rect = e.currentTarget.getBoundingClientRect();
         let percent = (e.clientX - rect.left) / rect.width;
         percent = Math.max(0, Math.min(1, percent));
-        const clickedTime = percent * duration;
+        placeMark(percent * duration);
+    };
 
+    const placeMark = (time: number) => {
         if (markPoint1 === null) {
             // First click
-            setMarkPoint1(clickedTime);
+            setMarkPoint1(time);
             setMarkPoint2(null);
             setSegmentNameInput("");
         } else if (markPoint2 === null) {
             // Second click
-            setMarkPoint2(clickedTime);
+            setMarkPoint2(time);
             // Autofocus will be handled by a ref on the input when it renders
         } else {
             // Third click resets and starts over
-            setMarkPoint1(clickedTime);
+            setMarkPoint1(time);
             setMarkPoint2(null);
             setSegmentNameInput("");
         }
     };
 
+    // 全域快捷鍵 (在其他程式中操作播放器) 觸發後同步狀態
+    useEffect(() => {
+        const unlisten = listen<{ action: string; position: number; is_playing: boolean }>(
+            "shortcut://transport",
+            (event) => {
+                const { action, position, is_playing } = event.payload;
+                setCurrentTime(position);
+                setIsPlaying(is_playing);
+                if (action === "marker") {
+                    placeMark(position);
+                }
+            },
+        );
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, [markPoint1, markPoint2]);
+
+    // 輸出裝置 (同時播放到耳機與診間喇叭)
+    useEffect(() => {
+        invoke<OutputDevice[]>("list_output_devices").then(setOutputDevices).catch(console.error);
+        invoke<OutputConfig>("get_output_config").then(setOutputConfig).catch(console.error);
+    }, []);
+
+    async function changeMonitorDevice(device: string) {
+        try {
+            setOutputConfig(await invoke<OutputConfig>("set_monitor_output", { device: device || null }));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    async function changeOutputVolume(target: "main" | "monitor", volume: number) {
+        try {
+            setOutputConfig(await invoke<OutputConfig>("set_output_volume", { target, volume }));
+        } catch (err) {
+            console.error("Failed to set volume:", err);
+        }
+    }
+
+    // 邊聽邊錄：口述註記另存檔案，與播放位置的同步點記在專案描述檔
+    async function toggleDictation() {
+        try {
+            if (dictating) {
+                const record = await invoke<{ output: string }>("stop_overdub");
+                setDictating(false);
+                setOutput(`${t.dictationSaved}: ${record.output}`);
+            } else {
+                await invoke("start_overdub");
+                setDictating(true);
+            }
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    async function startNote() {
+        if (noteStart.current) return;
+        noteStart.current = invoke("start_dictation_note")
+            .then(() => {
+                setNoteRecording(true);
+                return true;
+            })
+            .catch((err) => {
+                setOutput(`${t.error}: ${formatError(err)}`);
+                return false;
+            });
+    }
+
+    // 放開按鍵時停止；開始錄音尚未完成時等待後再停止
+    async function stopNote() {
+        const pending = noteStart.current;
+        if (!pending) return;
+        noteStart.current = null;
+        if (!(await pending)) return;
+        setNoteRecording(false);
+        setOutput(t.noteTranscribing);
+        try {
+            const entry = await invoke<{ text: string; notes_path: string }>("stop_dictation_note");
+            setOutput(`${t.noteSaved}: ${entry.notes_path}\n${entry.text}`);
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    // 仍在寫入中的檔案 (錄音、轉檔進行中)，長度隨檔案增加而更新
+    useEffect(() => {
+        const unlisten = listen<{ path: string; duration: number; growing: boolean }>(
+            "player://duration-updated",
+            (event) => {
+                setDuration(event.payload.duration);
+                setTrackInfo((info) => info && { ...info, growing: event.payload.growing });
+            },
+        );
+        return () => {
+            unlisten.then((fn) => fn());
+        };
+    }, []);
+
     const handleCancelMark = () => {
         setMarkPoint1(null);
         setMarkPoint2(null);
@@ -306,6 +552,31 @@ export function SplitPage() {
         handleCancelMark();
     };
 
+    // 選取範圍直接匯出成檔案
+    async function handleExportMark() {
+        if (markPoint1 === null || markPoint2 === null) return;
+        const start = Math.min(markPoint1, markPoint2);
+        const end = Math.max(markPoint1, markPoint2);
+        const ext = audioFilePath.split(".").pop() || "mp3";
+        const name = segmentNameInput.trim() || `${getFileName(audioFilePath).replace(/\.[^.]+$/, "")}_selection`;
+        try {
+            const dest = await save({
+                defaultPath: `${name}.${ext}`,
+                filters: [{ name: "Audio", extensions: [ext] }],
+            });
+            if (!dest) return;
+            setLoading(true);
+            setOutput(t.exportingSelection);
+            const result = await invoke<string>("export_selection", { start, end, dest });
+            setOutput(`${t.selectionExported}: ${result}`);
+            handleCancelMark();
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        } finally {
+            setLoading(false);
+        }
+    }
+
     // 新增段落
     function addSegment() {
         const newSegment: Segment = {
@@ -318,6 +589,55 @@ export function SplitPage() {
         setNextId(nextId + 1);
     }
 
+    // 從 Audacity 標籤或字幕檔匯入段落 (重疊的段落由後端調整)
+    async function importTimeline() {
+        try {
+            const selected = await open({
+                multiple: false,
+                filters: [{ name: t.timelineFiles, extensions: ["txt", "srt", "vtt"] }],
+            });
+            if (!selected || typeof selected !== "string") return;
+            const imported = await invoke<{
+                segments: { name: string; startTime: string; endTime: string }[];
+                adjusted: number;
+            }>("import_timeline", { path: selected });
+            setSegments(imported.segments.map((s, i) => ({ id: nextId + i, ...s })));
+            setNextId(nextId + imported.segments.length);
+            setOutput(t.timelineImported
+                .replace("{count}", String(imported.segments.length))
+                .replace("{adjusted}", String(imported.adjusted)));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    // 依主題分章：選擇逐字稿 JSON，由 Gemini 提出章節後放入段落清單 (確認後再切割)
+    async function proposeTopicChapters() {
+        if (!chapterApiKey) {
+            setOutput(`${t.error}: ${t.errorApiKey}`);
+            return;
+        }
+        try {
+            const selected = await open({
+                multiple: false,
+                filters: [{ name: "JSON", extensions: ["json"] }],
+            });
+            if (!selected || typeof selected !== "string") return;
+            setLoading(true);
+            setOutput(t.proposingChapters);
+            const proposed = await invoke<{
+                segments: { name: string; startTime: string; endTime: string }[];
+            }>("propose_topic_chapters", { apiKey: chapterApiKey, transcriptJson: selected });
+            setSegments(proposed.segments.map((s, i) => ({ id: nextId + i, ...s })));
+            setNextId(nextId + proposed.segments.length);
+            setOutput(t.chaptersProposed.replace("{count}", String(proposed.segments.length)));
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        } finally {
+            setLoading(false);
+        }
+    }
+
     // 刪除段落
     function deleteSegment(id: number) {
         if (segments.length > 1) {
@@ -370,6 +690,21 @@ export function SplitPage() {
             return;
         }
 
+        // 同時轉錄：取最近一次連線的 STT 伺服器 (記錄在自動消音頁)
+        let transcribeServer: string | null = null;
+        if (transcribeOnSplit) {
+            try {
+                const history: string[] = JSON.parse(localStorage.getItem("server-ip-history") || "[]");
+                transcribeServer = history[0] ?? null;
+            } catch (e) {
+                console.error("Failed to parse server history", e);
+            }
+            if (!transcribeServer) {
+                setOutput(`${t.error}: ${t.errorNoSttServer}`);
+                return;
+            }
+        }
+
         setLoading(true);
         setOutput(t.processing);
 
@@ -382,10 +717,84 @@ export function SplitPage() {
                     startTime: s.startTime,
                     endTime: s.endTime,
                 })),
+                transcribeServer,
+                diarize: false,
             });
             setOutput(result as string);
+            clearAutosave();
         } catch (err) {
-            setOutput(`${t.error}: ${err}`);
+            setOutput(`${t.error}: ${formatError(err)}`);
+        } finally {
+            setLoading(false);
+        }
+    }
+
+    // 降噪目前的音檔，輸出到 01b_cleaned
+    async function runDenoise() {
+        if (!audioFilePath) {
+            setOutput(`${t.error}: ${t.errorLoadAudio}`);
+            return;
+        }
+        setLoading(true);
+        setOutput(t.processing);
+        try {
+            const result = await invoke<string>("denoise_file", { audioPath: audioFilePath });
+            setOutput(result);
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        } finally {
+            setLoading(false);
+        }
+    }
+
+    // 人聲強化後進入比較模式 (先播放原始檔)
+    async function runEnhance() {
+        if (!audioFilePath) {
+            setOutput(`${t.error}: ${t.errorLoadAudio}`);
+            return;
+        }
+        setLoading(true);
+        setOutput(t.processing);
+        try {
+            const enhanced = await invoke<string>("enhance_speech", { path: audioFilePath });
+            const durationStr = await invoke<string>("start_compare", {
+                original: audioFilePath,
+                processed: enhanced,
+            });
+            setDuration(parseFloat(durationStr));
+            setCurrentTime(0);
+            setIsPlaying(false);
+            setCompareSide("original");
+            setOutput(`${t.enhanceDone}: ${enhanced}`);
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        } finally {
+            setLoading(false);
+        }
+    }
+
+    async function switchCompare(side: "original" | "processed") {
+        try {
+            await invoke("switch_compare", { side });
+            setCompareSide(side);
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
+        }
+    }
+
+    // 依音檔內含的章節切割，每個章節一個檔案
+    async function runSplitByChapters() {
+        if (!audioFilePath) {
+            setOutput(`${t.error}: ${t.errorLoadAudio}`);
+            return;
+        }
+        setLoading(true);
+        setOutput(t.processing);
+        try {
+            const result = await invoke<string>("split_by_chapters", { audioPath: audioFilePath });
+            setOutput(result);
+        } catch (err) {
+            setOutput(`${t.error}: ${formatError(err)}`);
         } finally {
             setLoading(false);
         }
@@ -457,6 +866,83 @@ export function SplitPage() {
                             <button className="btn btn-secondary" onClick={handleLoadTrack}>
                                 📂 {t.changeFolder || t.loadAudio}
                             </button>
+                            <button className="btn btn-secondary" style={{ marginLeft: '8px' }} onClick={runDenoise} disabled={loading}>
+                                🔇 {t.denoise}
+                            </button>
+                            <button className="btn btn-secondary" style={{ marginLeft: '8px' }} onClick={runEnhance} disabled={loading}>
+                                🎙️ {t.enhanceSpeech}
+                            </button>
+                            <button
+                                className={`btn ${dictating ? "btn-primary" : "btn-secondary"}`}
+                                style={{ marginLeft: '8px' }}
+                                onClick={toggleDictation}
+                                disabled={!isLoaded}
+                            >
+                                🗣️ {dictating ? t.stopDictation : t.dictateNote}
+                            </button>
+                            <button
+                                className={`btn ${noteRecording ? "btn-primary" : "btn-secondary"}`}
+                                style={{ marginLeft: '8px' }}
+                                onPointerDown={startNote}
+                                onPointerUp={stopNote}
+                                onPointerLeave={stopNote}
+                                title={t.pushToTalkHint}
+                            >
+                                🎤 {noteRecording ? t.noteRecording : t.pushToTalk}
+                            </button>
+                            {compareSide && (
+                                <span style={{ marginLeft: '8px' }}>
+                                    <button
+                                        className={`btn btn-sm ${compareSide === "original" ? "btn-primary" : "btn-secondary"}`}
+                                        onClick={() => switchCompare("original")}
+                                    >
+                                        A · {t.compareOriginal}
+                                    </button>
+                                    <button
+                                        className={`btn btn-sm ${compareSide === "processed" ? "btn-primary" : "btn-secondary"}`}
+                                        style={{ marginLeft: '4px' }}
+                                        onClick={() => switchCompare("processed")}
+                                    >
+                                        B · {t.compareProcessed}
+                                    </button>
+                                </span>
+                            )}
+                            {outputConfig && (
+                                <div className="output-controls">
+                                    <label>
+                                        🔊 <input
+                                            type="range"
+                                            min={0}
+                                            max={2}
+                                            step={0.05}
+                                            value={outputConfig.main_volume}
+                                            onChange={(e) => changeOutputVolume("main", parseFloat(e.target.value))}
+                                        />
+                                    </label>
+                                    <label>
+                                        {t.monitorOutput}
+                                        <select
+                                            value={outputConfig.monitor_device ?? ""}
+                                            onChange={(e) => changeMonitorDevice(e.target.value)}
+                                        >
+                                            <option value="">{t.monitorOff}</option>
+                                            {outputDevices.map((d) => (
+                                                <option key={d.name} value={d.name}>{d.name}</option>
+                                            ))}
+                                        </select>
+                                    </label>
+                                    {outputConfig.monitor_device && (
+                                        <input
+                                            type="range"
+                                            min={0}
+                                            max={2}
+                                            step={0.05}
+                                            value={outputConfig.monitor_volume}
+                                            onChange={(e) => changeOutputVolume("monitor", parseFloat(e.target.value))}
+                                        />
+                                    )}
+                                </div>
+                            )}
                         </div>
 
                         {/* Inner Player Box */}
@@ -465,6 +951,12 @@ export function SplitPage() {
                                 <div className="track-info">
                                     <span className="icon">🎵</span>
                                     <span className="track-name">{getFileName(audioFilePath)}</span>
+                                    {trackInfo && trackInfo.exists && (
+                                        <span className="track-format">
+                                            {formatTrackInfo(trackInfo)}
+                                            {trackInfo.growing && ` · ${t.trackGrowing}`}
+                                        </span>
+                                    )}
                                 </div>
                             </div>
 
@@ -474,7 +966,7 @@ export function SplitPage() {
                                 </button>
 
                                 <div className="seek-container">
-                                    <span className="time-display">{formatTime(currentTime)}</span>
+                                    <span className="time-display">{formatTimePrecise(currentTime)}</span>
                                     <div style={{ position: 'relative', flex: 1, display: 'flex', alignItems: 'center' }}>
                                         {/* Hover Tooltip */}
                                         {hoverTime !== null && (
@@ -548,6 +1040,9 @@ export function SplitPage() {
                                                     <button className="btn-small cancel" onClick={handleCancelMark}>
                                                         ✕
                                                     </button>
+                                                    <button className="btn-small" onClick={handleExportMark} title={t.exportSelection}>
+                                                        ⤓
+                                                    </button>
                                                     <button className="btn-small confirm" onClick={handleConfirmMark}>
                                                         ✓
                                                     </button>
@@ -599,9 +1094,30 @@ export function SplitPage() {
             <div className="segment-section mt-4 fade-in-up">
                 <div className="section-header display-flex justify-between align-center mb-3">
                     <h3>📋 {t.segmentList}</h3>
-                    <button onClick={addSegment} className="btn btn-secondary btn-sm">
-                        ➕ {t.addSegment}
-                    </button>
+                    <div style={{ display: "flex", gap: "8px" }}>
+                        <button onClick={addSegment} className="btn btn-secondary btn-sm">
+                            ➕ {t.addSegment}
+                        </button>
+                        <button onClick={importTimeline} className="btn btn-secondary btn-sm">
+                            📥 {t.importTimeline}
+                        </button>
+                        <input
+                            type="password"
+                            className="input"
+                            value={chapterApiKey}
+                            onChange={(e) => setChapterApiKey(e.target.value)}
+                            placeholder="Gemini API Key"
+                            style={{ width: "160px" }}
+                        />
+                        <button
+                            onClick={proposeTopicChapters}
+                            className="btn btn-secondary btn-sm"
+                            disabled={loading}
+                            title={t.topicChaptersHint}
+                        >
+                            🧭 {t.topicChapters}
+                        </button>
+                    </div>
                 </div>
 
                 <div className="table-container" style={{ marginTop: '12px' }}>
@@ -719,6 +1235,25 @@ export function SplitPage() {
                             {loading ? t.splitting : t.runSplit}
                         </span>
                     </button>
+                    <label style={{ display: 'flex', alignItems: 'center', gap: '5px', whiteSpace: 'nowrap' }}>
+                        <input
+                            type="checkbox"
+                            checked={transcribeOnSplit}
+                            onChange={(e) => setTranscribeOnSplit(e.target.checked)}
+                            disabled={loading}
+                        />
+                        {t.transcribeOnSplit}
+                    </label>
+                    {chapters.length > 0 && (
+                        <button
+                            className="btn btn-secondary btn-large"
+                            onClick={runSplitByChapters}
+                            disabled={loading}
+                            title={t.chaptersFound.replace("{count}", String(chapters.length))}
+                        >
+                            📑 {t.splitByChapters} ({chapters.length})
+                        </button>
+                    )}
                 </div>
             </div>
 
@@ -1,7 +1,7 @@
 import { useState, useEffect } from "react";
 import { invoke } from "@tauri-apps/api/core";
-import { open } from "@tauri-apps/plugin-dialog";
 import { useI18n } from "../i18n";
+import { formatError, isAppError } from "../errors";
 
 interface RecentProject {
     path: string;
@@ -16,6 +16,7 @@ interface WelcomePageProps {
 export function WelcomePage({ onProjectOpened }: WelcomePageProps) {
     const { t, language } = useI18n();
     const [recentProjects, setRecentProjects] = useState<RecentProject[]>([]);
+    const [readOnly, setReadOnly] = useState(false);
 
     useEffect(() => {
         loadRecentProjects();
@@ -53,16 +54,12 @@ export function WelcomePage({ onProjectOpened }: WelcomePageProps) {
 
         localStorage.setItem("recent-projects", JSON.stringify(current));
         setRecentProjects(current.slice(0, 5));
-        
-        // Also save as "last opened project" for auto-load
-        localStorage.setItem("app-last-project", path);
     };
 
     const handleCreateProject = async () => {
         try {
-            const selected = await open({
+            const selected = await invoke<string | null>("request_path_access", {
                 directory: true,
-                multiple: false,
                 title: language === "zh" ? "選擇新建專案位置" : "Select location for new project"
             });
             if (selected && typeof selected === "string") {
@@ -78,13 +75,12 @@ export function WelcomePage({ onProjectOpened }: WelcomePageProps) {
 
     const handleOpenProject = async () => {
         try {
-            const selected = await open({
+            const selected = await invoke<string | null>("request_path_access", {
                 directory: true,
-                multiple: false,
                 title: language === "zh" ? "選擇專案資料夾" : "Select project folder"
             });
             if (selected && typeof selected === "string") {
-                await invoke("open_project_cmd", { path: selected });
+                await invoke("open_project_cmd", { path: selected, readOnly });
                 saveRecentProject(selected);
                 onProjectOpened(selected);
             }
@@ -96,12 +92,25 @@ export function WelcomePage({ onProjectOpened }: WelcomePageProps) {
 
     const handleOpenRecent = async (path: string) => {
         try {
-            await invoke("open_project_cmd", { path });
+            try {
+                await invoke("open_project_cmd", { path, readOnly });
+            } catch (e) {
+                // 重新啟動後，上層資料夾以外的專案需要再次以對話框選擇授權
+                if (!isAppError(e) || e.kind !== "permission_denied") throw e;
+                const selected = await invoke<string | null>("request_path_access", {
+                    directory: true,
+                    defaultPath: path,
+                    title: language === "zh" ? "選擇專案資料夾" : "Select project folder"
+                });
+                if (!selected) return;
+                path = selected;
+                await invoke("open_project_cmd", { path, readOnly });
+            }
             saveRecentProject(path); // Update timestamp
             onProjectOpened(path);
         } catch (e) {
             console.error(e);
-            alert(`${language === "zh" ? "無法開啟專案" : "Failed to open project"}: ${e}`);
+            alert(`${language === "zh" ? "無法開啟專案" : "Failed to open project"}: ${formatError(e)}`);
         }
     };
 
@@ -139,6 +148,11 @@ export function WelcomePage({ onProjectOpened }: WelcomePageProps) {
                     </button>
                 </div>
 
+                <label className="viewer-toggle" title={t.viewerModeHint}>
+                    <input type="checkbox" checked={readOnly} onChange={(e) => setReadOnly(e.target.checked)} />
+                    {" "}{t.openReadOnly}
+                </label>
+
                 {recentProjects.length > 0 && (
                     <div className="recent-section">
                         <h3>{t.recentFiles || "Recent Projects"}</h3>
@@ -189,7 +203,14 @@ export function WelcomePage({ onProjectOpened }: WelcomePageProps) {
                     display: flex;
                     flex-direction: column;
                     gap: 10px;
+                    margin-bottom: 12px;
+                }
+                .viewer-toggle {
+                    display: block;
                     margin-bottom: 30px;
+                    color: var(--text-secondary);
+                    font-size: 0.9rem;
+                    cursor: pointer;
                 }
                 .welcome-btn {
                     display: flex;